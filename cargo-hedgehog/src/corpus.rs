@@ -0,0 +1,405 @@
+//! `cargo hedgehog corpus`: manage the stored inputs properties replay.
+//!
+//! A property that uses `Property::with_corpus(name)` replays every file
+//! under `tests/corpus/<name>` before generating. These commands list,
+//! add and clean up those files, and report which still fail by running
+//! the properties with `HEDGEHOG_CORPUS_CHECK` set.
+
+use crate::error::CliError;
+use hedgehog_core::{corpus_entries, corpus_entry_name, CORPUS_CHECK_ENV_VAR, DEFAULT_CORPUS_DIR};
+use std::path::{Path, PathBuf};
+use std::process::{Command, ExitCode};
+
+const USAGE: &str = "\
+Usage: cargo hedgehog corpus <command> [options]
+
+Commands:
+  list [property]             List the stored inputs of each property
+  add <property> <file>...    Store input files in a property's corpus
+  gc                          Remove corpora of properties that no longer exist
+  check [property]            Run the properties and show which entries still fail
+
+Options:
+  -d, --dir <dir>             Corpus directory (default: tests/corpus)
+  -p, --package <name>        Package whose tests hold the properties
+  -n, --dry-run               With gc, only print what would be removed";
+
+/// Marks the lines properties print for each entry under `check`.
+const CHECK_PREFIX: &str = "hedgehog-corpus: ";
+
+#[derive(Debug, PartialEq)]
+enum Action {
+    List(Option<String>),
+    Add(String, Vec<PathBuf>),
+    Gc,
+    Check(Option<String>),
+}
+
+#[derive(Debug, PartialEq)]
+pub struct Corpus {
+    action: Action,
+    directory: PathBuf,
+    package: Option<String>,
+    dry_run: bool,
+}
+
+impl Corpus {
+    pub fn parse(args: &[String]) -> Result<Self, CliError> {
+        let mut positional = Vec::new();
+        let mut directory = PathBuf::from(DEFAULT_CORPUS_DIR);
+        let mut package = None;
+        let mut dry_run = false;
+        let mut args = args.iter();
+        while let Some(arg) = args.next() {
+            let mut value = || {
+                args.next()
+                    .cloned()
+                    .ok_or_else(|| CliError::Usage(format!("{arg} needs a value\n\n{USAGE}")))
+            };
+            match arg.as_str() {
+                "-d" | "--dir" => directory = PathBuf::from(value()?),
+                "-p" | "--package" => package = Some(value()?),
+                "-n" | "--dry-run" => dry_run = true,
+                flag if flag.starts_with('-') => {
+                    return Err(CliError::Usage(format!(
+                        "unknown option `{flag}`\n\n{USAGE}"
+                    )))
+                }
+                _ => positional.push(arg.clone()),
+            }
+        }
+
+        let mut positional = positional.into_iter();
+        let command = positional.next();
+        let rest: Vec<String> = positional.collect();
+        let action = match (command.as_deref(), rest.as_slice()) {
+            (Some("list"), [] | [_]) => Action::List(rest.first().cloned()),
+            (Some("check"), [] | [_]) => Action::Check(rest.first().cloned()),
+            (Some("gc"), []) => Action::Gc,
+            (Some("add"), [property, files @ ..]) if !files.is_empty() => {
+                Action::Add(property.clone(), files.iter().map(PathBuf::from).collect())
+            }
+            (Some(command @ ("list" | "check" | "gc" | "add")), _) => {
+                return Err(CliError::Usage(format!(
+                    "wrong arguments for `{command}`\n\n{USAGE}"
+                )))
+            }
+            (Some(command), _) => {
+                return Err(CliError::Usage(format!(
+                    "unknown corpus command `{command}`\n\n{USAGE}"
+                )))
+            }
+            (None, _) => {
+                return Err(CliError::Usage(format!(
+                    "no corpus command given\n\n{USAGE}"
+                )))
+            }
+        };
+        Ok(Corpus {
+            action,
+            directory,
+            package,
+            dry_run,
+        })
+    }
+
+    pub fn run(&self) -> Result<ExitCode, CliError> {
+        match &self.action {
+            Action::List(property) => self.list(property.as_deref()),
+            Action::Add(property, files) => self.add(property, files),
+            Action::Gc => self.gc(),
+            Action::Check(property) => self.check(property.as_deref()),
+        }
+    }
+
+    fn list(&self, only: Option<&str>) -> Result<ExitCode, CliError> {
+        let properties = match only {
+            Some(property) => vec![property.to_string()],
+            None => properties(&self.directory)?,
+        };
+        if properties.is_empty() {
+            println!("No corpora in {}", self.directory.display());
+        }
+        for property in properties {
+            let entries = entries(&self.directory.join(&property))?;
+            println!("{property}: {}", count(entries.len()));
+            for entry in entries {
+                let size = std::fs::metadata(&entry).map_err(|source| io(&entry, source))?;
+                println!("  {} ({} bytes)", file_name(&entry), size.len());
+            }
+        }
+        Ok(ExitCode::SUCCESS)
+    }
+
+    fn add(&self, property: &str, files: &[PathBuf]) -> Result<ExitCode, CliError> {
+        let corpus = self.directory.join(property);
+        std::fs::create_dir_all(&corpus).map_err(|source| io(&corpus, source))?;
+        for file in files {
+            let contents = std::fs::read(file).map_err(|source| io(file, source))?;
+            let entry = corpus.join(corpus_entry_name(&contents));
+            if entry.exists() {
+                println!(
+                    "{} is already stored as {}",
+                    file.display(),
+                    entry.display()
+                );
+                continue;
+            }
+            std::fs::write(&entry, &contents).map_err(|source| io(&entry, source))?;
+            println!("Stored {} as {}", file.display(), entry.display());
+        }
+        Ok(ExitCode::SUCCESS)
+    }
+
+    fn gc(&self) -> Result<ExitCode, CliError> {
+        let output = self
+            .test_command()
+            .args(["--", "--list"])
+            .output()
+            .map_err(CliError::Cargo)?;
+        if !output.status.success() {
+            eprint!("{}", String::from_utf8_lossy(&output.stderr));
+            return Err(CliError::CargoFailed("cargo test -- --list".to_string()));
+        }
+        let list = String::from_utf8_lossy(&output.stdout);
+        let tests = test_names(&list);
+
+        let unused = unused(&properties(&self.directory)?, &tests);
+        if unused.is_empty() {
+            println!("Every corpus belongs to a test");
+        }
+        for property in unused {
+            let corpus = self.directory.join(&property);
+            if self.dry_run {
+                println!("Would remove {}", corpus.display());
+            } else {
+                std::fs::remove_dir_all(&corpus).map_err(|source| io(&corpus, source))?;
+                println!("Removed {}", corpus.display());
+            }
+        }
+        Ok(ExitCode::SUCCESS)
+    }
+
+    fn check(&self, property: Option<&str>) -> Result<ExitCode, CliError> {
+        let output = self
+            .check_command(property)
+            .stderr(std::process::Stdio::inherit())
+            .output()
+            .map_err(CliError::Cargo)?;
+
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        let reports: Vec<&str> = stdout
+            .lines()
+            // libtest may have started `test name ... ` on the same line
+            .filter_map(|line| line.split_once(CHECK_PREFIX).map(|(_, report)| report))
+            .collect();
+        if reports.is_empty() {
+            eprintln!("No test reported on its corpus; do the properties use with_corpus?");
+            return Ok(ExitCode::FAILURE);
+        }
+        for report in &reports {
+            println!("{report}");
+        }
+        let failing = reports
+            .iter()
+            .filter(|report| report.contains(": still fails"))
+            .count();
+        println!("{failing} of {} still fail", count(reports.len()));
+        Ok(if failing == 0 {
+            ExitCode::SUCCESS
+        } else {
+            ExitCode::FAILURE
+        })
+    }
+
+    fn test_command(&self) -> Command {
+        let mut command = crate::cargo();
+        command.arg("test");
+        if let Some(package) = &self.package {
+            command.args(["--package", package]);
+        }
+        command
+    }
+
+    /// The `cargo test` run whose properties report on their corpus
+    /// entries.
+    fn check_command(&self, property: Option<&str>) -> Command {
+        let mut command = self.test_command();
+        command.arg("--");
+        if let Some(property) = property {
+            command.arg(property);
+        }
+        command.arg("--nocapture").env(CORPUS_CHECK_ENV_VAR, "1");
+        command
+    }
+}
+
+/// The properties with a corpus, sorted.
+fn properties(directory: &Path) -> Result<Vec<String>, CliError> {
+    let entries = match std::fs::read_dir(directory) {
+        Ok(entries) => entries,
+        Err(error) if error.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(source) => return Err(io(directory, source)),
+    };
+    let mut properties = Vec::new();
+    for entry in entries {
+        let entry = entry.map_err(|source| io(directory, source))?;
+        if entry.path().is_dir() {
+            properties.push(entry.file_name().to_string_lossy().into_owned());
+        }
+    }
+    properties.sort();
+    Ok(properties)
+}
+
+fn entries(corpus: &Path) -> Result<Vec<PathBuf>, CliError> {
+    corpus_entries(corpus).map_err(|source| io(corpus, source))
+}
+
+/// The tests in `cargo test -- --list` output.
+fn test_names(list: &str) -> Vec<&str> {
+    list.lines()
+        .filter_map(|line| line.strip_suffix(": test"))
+        .collect()
+}
+
+/// The corpora named after no test, either by path or by the test's
+/// function name.
+fn unused(properties: &[String], tests: &[&str]) -> Vec<String> {
+    properties
+        .iter()
+        .filter(|property| {
+            !tests.iter().any(|test| {
+                *test == property.as_str() || test.rsplit("::").next() == Some(property.as_str())
+            })
+        })
+        .cloned()
+        .collect()
+}
+
+fn count(entries: usize) -> String {
+    match entries {
+        1 => "1 entry".to_string(),
+        n => format!("{n} entries"),
+    }
+}
+
+fn file_name(path: &Path) -> String {
+    path.file_name()
+        .map_or_else(String::new, |name| name.to_string_lossy().into_owned())
+}
+
+fn io(path: &Path, source: std::io::Error) -> CliError {
+    CliError::Io {
+        path: path.display().to_string(),
+        source,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::ffi::OsStr;
+
+    fn args(args: &[&str]) -> Vec<String> {
+        args.iter().map(|arg| arg.to_string()).collect()
+    }
+
+    #[test]
+    fn test_parses_commands_and_options() {
+        assert_eq!(
+            Corpus::parse(&args(&[
+                "add",
+                "round_trip",
+                "a.txt",
+                "b.txt",
+                "-d",
+                "fixtures"
+            ]))
+            .unwrap(),
+            Corpus {
+                action: Action::Add(
+                    "round_trip".to_string(),
+                    vec![PathBuf::from("a.txt"), PathBuf::from("b.txt")]
+                ),
+                directory: PathBuf::from("fixtures"),
+                package: None,
+                dry_run: false,
+            }
+        );
+        assert_eq!(
+            Corpus::parse(&args(&["gc", "--dry-run"])).unwrap().action,
+            Action::Gc
+        );
+        assert!(Corpus::parse(&args(&["add", "round_trip"])).is_err());
+        assert!(Corpus::parse(&args(&["gc", "extra"])).is_err());
+        assert!(Corpus::parse(&args(&["prune"])).is_err());
+        assert!(Corpus::parse(&args(&[])).is_err());
+    }
+
+    #[test]
+    fn test_adding_stores_each_input_once() {
+        let directory =
+            std::env::temp_dir().join(format!("hedgehog-cli-corpus-{}", std::process::id()));
+        let input = directory.join("crash.txt");
+        std::fs::create_dir_all(&directory).unwrap();
+        std::fs::write(&input, "abcxyzzy\n").unwrap();
+
+        let corpus = Corpus {
+            action: Action::Gc,
+            directory: directory.join("corpus"),
+            package: None,
+            dry_run: false,
+        };
+        corpus.add("round_trip", &[input.clone(), input]).unwrap();
+        assert_eq!(properties(&corpus.directory).unwrap(), ["round_trip"]);
+        let stored = entries(&corpus.directory.join("round_trip")).unwrap();
+        assert_eq!(stored.len(), 1);
+        assert_eq!(file_name(&stored[0]), corpus_entry_name(b"abcxyzzy\n"));
+        std::fs::remove_dir_all(directory).unwrap();
+    }
+
+    #[test]
+    fn test_corpora_without_a_test_are_unused() {
+        let tests = test_names(
+            "parser::tests::round_trip: test\n\
+             encode: test\n\
+             src/lib.rs - decode (line 3): test\n\
+             \n\
+             2 tests, 0 benchmarks\n",
+        );
+        assert_eq!(
+            tests,
+            [
+                "parser::tests::round_trip",
+                "encode",
+                "src/lib.rs - decode (line 3)"
+            ]
+        );
+        assert_eq!(
+            unused(&args(&["decode", "encode", "round_trip"]), &tests),
+            ["decode"]
+        );
+    }
+
+    #[test]
+    fn test_check_runs_the_tests_reporting_on_their_corpus() {
+        let corpus = Corpus::parse(&args(&["check", "round_trip", "-p", "parser"])).unwrap();
+        let command = corpus.check_command(Some("round_trip"));
+
+        let args: Vec<&OsStr> = command.get_args().collect();
+        assert_eq!(
+            args,
+            [
+                "test",
+                "--package",
+                "parser",
+                "--",
+                "round_trip",
+                "--nocapture"
+            ]
+        );
+        let envs: Vec<_> = command.get_envs().collect();
+        assert!(envs.contains(&(OsStr::new(CORPUS_CHECK_ENV_VAR), Some(OsStr::new("1")))));
+    }
+}
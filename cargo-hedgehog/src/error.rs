@@ -0,0 +1,31 @@
+//! Errors from `cargo hedgehog` commands.
+
+use std::path::Path;
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+pub enum CliError {
+    #[error("{0}")]
+    Usage(String),
+
+    #[error("{path}: {source}")]
+    Io {
+        path: String,
+        #[source]
+        source: std::io::Error,
+    },
+
+    #[error("couldn't run cargo: {0}")]
+    Cargo(#[source] std::io::Error),
+
+    #[error("`{0}` failed")]
+    CargoFailed(String),
+}
+
+impl CliError {
+    /// Wrap an I/O error on `path`, for `map_err`.
+    pub fn io(path: &Path) -> impl FnOnce(std::io::Error) -> CliError {
+        let path = path.display().to_string();
+        move |source| CliError::Io { path, source }
+    }
+}
@@ -0,0 +1,68 @@
+//! `cargo hedgehog`: commands for working with Hedgehog properties.
+//!
+//! Properties live in a project's tests, so each command runs them through
+//! `cargo test`, passing what the command needs in environment variables
+//! the Hedgehog runner reads.
+
+mod corpus;
+mod error;
+mod minimize;
+mod report;
+mod shrink_graph;
+mod test;
+mod workspace;
+
+use error::CliError;
+use std::process::ExitCode;
+
+const USAGE: &str = "\
+Usage: cargo hedgehog <command> [options]
+
+Commands:
+  corpus <command>              List, add, clean up and check stored inputs
+  minimize <property> <input>   Shrink a failing input from outside the property
+  report [history]              Show trends and drift over recorded runs
+  shrink-graph <property>       Export the shrink tree explored for a failure
+  test [filter]                 Run tests with checks such as --verify-determinism,
+                                per crate with --workspace";
+
+fn main() -> ExitCode {
+    // Cargo runs `cargo-hedgehog hedgehog <command> ...` for `cargo hedgehog`
+    let mut args: Vec<String> = std::env::args().skip(1).collect();
+    if args.first().map(String::as_str) == Some("hedgehog") {
+        args.remove(0);
+    }
+
+    match run(&args) {
+        Ok(code) => code,
+        Err(err) => {
+            eprintln!("error: {err}");
+            ExitCode::FAILURE
+        }
+    }
+}
+
+fn run(args: &[String]) -> Result<ExitCode, CliError> {
+    match args.split_first() {
+        Some((command, rest)) if command == "corpus" => corpus::Corpus::parse(rest)?.run(),
+        Some((command, rest)) if command == "minimize" => minimize::Minimize::parse(rest)?.run(),
+        Some((command, rest)) if command == "report" => report::Report::parse(rest)?.run(),
+        Some((command, rest)) if command == "shrink-graph" => {
+            shrink_graph::ShrinkGraph::parse(rest)?.run()
+        }
+        Some((command, rest)) if command == "test" => test::Test::parse(rest)?.run(),
+        Some((command, _)) if command == "--help" || command == "-h" => {
+            println!("{USAGE}");
+            Ok(ExitCode::SUCCESS)
+        }
+        Some((command, _)) => Err(CliError::Usage(format!(
+            "unknown command `{command}`\n\n{USAGE}"
+        ))),
+        None => Err(CliError::Usage(format!("no command given\n\n{USAGE}"))),
+    }
+}
+
+/// The `cargo` to run: the one that ran this command, if any.
+fn cargo() -> std::process::Command {
+    std::process::Command::new(std::env::var_os("CARGO").unwrap_or_else(|| "cargo".into()))
+}
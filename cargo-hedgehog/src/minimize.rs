@@ -0,0 +1,178 @@
+//! `cargo hedgehog minimize`: shrink a failing input from outside the
+//! property, such as a production incident or a fuzzer.
+//!
+//! The test running the property must use `Property::run_minimizable`,
+//! which reads the input from the file named by `HEDGEHOG_MINIMIZE` with
+//! `FromStr`, shrinks it through the property and writes the minimal input
+//! to the file named by `HEDGEHOG_MINIMIZE_OUTPUT`.
+
+use crate::error::CliError;
+use hedgehog_core::{MINIMIZE_ENV_VAR, MINIMIZE_OUTPUT_ENV_VAR};
+use std::path::{Path, PathBuf};
+use std::process::{Command, ExitCode};
+
+const USAGE: &str = "\
+Usage: cargo hedgehog minimize <property> <input> [options]
+
+Runs the tests matching <property> with the input in <input> instead of
+generated ones, and prints the minimal failing input.
+
+Options:
+  -o, --output <file>      Also write the minimal input to <file>
+  -p, --package <name>     Package whose tests hold the property";
+
+#[derive(Debug, PartialEq)]
+pub struct Minimize {
+    property: String,
+    input: PathBuf,
+    output: Option<PathBuf>,
+    package: Option<String>,
+}
+
+impl Minimize {
+    pub fn parse(args: &[String]) -> Result<Self, CliError> {
+        let mut positional = Vec::new();
+        let mut output = None;
+        let mut package = None;
+        let mut args = args.iter();
+        while let Some(arg) = args.next() {
+            let mut value = || {
+                args.next()
+                    .cloned()
+                    .ok_or_else(|| CliError::Usage(format!("{arg} needs a value\n\n{USAGE}")))
+            };
+            match arg.as_str() {
+                "-o" | "--output" => output = Some(PathBuf::from(value()?)),
+                "-p" | "--package" => package = Some(value()?),
+                flag if flag.starts_with('-') => {
+                    return Err(CliError::Usage(format!(
+                        "unknown option `{flag}`\n\n{USAGE}"
+                    )))
+                }
+                _ => positional.push(arg.clone()),
+            }
+        }
+
+        match <[String; 2]>::try_from(positional) {
+            Ok([property, input]) => Ok(Minimize {
+                property,
+                input: PathBuf::from(input),
+                output,
+                package,
+            }),
+            Err(_) => Err(CliError::Usage(format!(
+                "minimize takes a property and an input file\n\n{USAGE}"
+            ))),
+        }
+    }
+
+    pub fn run(&self) -> Result<ExitCode, CliError> {
+        let input = absolute(&self.input)?;
+        let output = self.output.clone().unwrap_or_else(|| {
+            std::env::temp_dir().join(format!("hedgehog-minimized-{}", std::process::id()))
+        });
+        let output = absolute(&output)?;
+        // A stale output would look like this run's result
+        let _ = std::fs::remove_file(&output);
+
+        self.command(&input, &output)
+            .status()
+            .map_err(CliError::Cargo)?;
+
+        let minimal = std::fs::read_to_string(&output);
+        if self.output.is_none() {
+            let _ = std::fs::remove_file(&output);
+        }
+        match minimal {
+            Ok(minimal) => {
+                println!("Minimal input: {}", minimal.trim_end());
+                if let Some(path) = &self.output {
+                    println!("Written to {}", path.display());
+                }
+                Ok(ExitCode::SUCCESS)
+            }
+            Err(_) => {
+                eprintln!(
+                    "No minimal input: either no test matching `{}` uses run_minimizable, or {} doesn't fail it",
+                    self.property,
+                    self.input.display()
+                );
+                Ok(ExitCode::FAILURE)
+            }
+        }
+    }
+
+    /// The `cargo test` run that minimizes `input` into `output`, both
+    /// absolute since tests run in their package's directory.
+    fn command(&self, input: &Path, output: &Path) -> Command {
+        let mut command = crate::cargo();
+        command.arg("test");
+        if let Some(package) = &self.package {
+            command.args(["--package", package]);
+        }
+        command
+            .args(["--", &self.property, "--nocapture"])
+            .env(MINIMIZE_ENV_VAR, input)
+            .env(MINIMIZE_OUTPUT_ENV_VAR, output);
+        command
+    }
+}
+
+fn absolute(path: &Path) -> Result<PathBuf, CliError> {
+    std::path::absolute(path).map_err(|source| CliError::Io {
+        path: path.display().to_string(),
+        source,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::ffi::OsStr;
+
+    fn args(args: &[&str]) -> Vec<String> {
+        args.iter().map(|arg| arg.to_string()).collect()
+    }
+
+    #[test]
+    fn test_parses_property_input_and_options() {
+        assert_eq!(
+            Minimize::parse(&args(&["parser::round_trip", "crash.txt", "-o", "min.txt"])).unwrap(),
+            Minimize {
+                property: "parser::round_trip".to_string(),
+                input: PathBuf::from("crash.txt"),
+                output: Some(PathBuf::from("min.txt")),
+                package: None,
+            }
+        );
+        assert!(Minimize::parse(&args(&["only_a_property"])).is_err());
+        assert!(Minimize::parse(&args(&["p", "in", "--output"])).is_err());
+        assert!(Minimize::parse(&args(&["p", "in", "--verbose"])).is_err());
+    }
+
+    #[test]
+    fn test_runs_the_matching_tests_with_the_input() {
+        let minimize =
+            Minimize::parse(&args(&["round_trip", "crash.txt", "-p", "parser"])).unwrap();
+        let command = minimize.command(Path::new("/in"), Path::new("/out"));
+
+        let args: Vec<&OsStr> = command.get_args().collect();
+        assert_eq!(
+            args,
+            [
+                "test",
+                "--package",
+                "parser",
+                "--",
+                "round_trip",
+                "--nocapture"
+            ]
+        );
+        let envs: Vec<_> = command.get_envs().collect();
+        assert!(envs.contains(&(OsStr::new(MINIMIZE_ENV_VAR), Some(OsStr::new("/in")))));
+        assert!(envs.contains(&(
+            OsStr::new(MINIMIZE_OUTPUT_ENV_VAR),
+            Some(OsStr::new("/out"))
+        )));
+    }
+}
@@ -0,0 +1,406 @@
+//! `cargo hedgehog report`: show how properties have changed over the runs
+//! recorded in a history directory or file.
+//!
+//! Tests append to the history while `HEDGEHOG_HISTORY` names a directory,
+//! which `Config::from_env()` picks up, each property to a file of its own.
+//! Keeping the directory between CI builds, as a cached artifact for
+//! example, builds up the history this reports on.
+//!
+//! Properties marked with `expect_failure` or `quarantined` don't fail the
+//! suite, so the report counts them and lists those whose latest run passed,
+//! whose markers may be stale.
+
+use crate::error::CliError;
+use hedgehog_core::history::{drift, Drift};
+use hedgehog_core::{PropertyMarker, RunOutcome, RunRecord};
+use std::collections::BTreeMap;
+use std::fmt::Write;
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+use std::time::Duration;
+
+const USAGE: &str = "\
+Usage: cargo hedgehog report [history] [options]
+
+Shows outcomes, durations and classification percentages over the runs
+recorded in [history], a directory or file (default: hedgehog-history), and
+flags classifications whose share in the latest run has drifted from the
+runs before it. Record runs by running tests with HEDGEHOG_HISTORY set.
+
+Options:
+  -p, --property <text>    Only show properties whose name contains <text>
+  -t, --threshold <pct>    Percentage points of change that count as drift (default: 10)
+      --deny-drift         Exit with failure if any classification drifted";
+
+/// Where the history is read from without a path.
+const DEFAULT_HISTORY: &str = "hedgehog-history";
+
+/// Runs shown in each duration sparkline.
+const SPARKLINE_RUNS: usize = 20;
+
+#[derive(Debug, PartialEq)]
+pub struct Report {
+    history: PathBuf,
+    property: Option<String>,
+    threshold: f64,
+    deny_drift: bool,
+}
+
+impl Report {
+    pub fn parse(args: &[String]) -> Result<Self, CliError> {
+        let mut positional = Vec::new();
+        let mut property = None;
+        let mut threshold = 10.0;
+        let mut deny_drift = false;
+        let mut args = args.iter();
+        while let Some(arg) = args.next() {
+            let mut value = || {
+                args.next()
+                    .cloned()
+                    .ok_or_else(|| CliError::Usage(format!("{arg} needs a value\n\n{USAGE}")))
+            };
+            match arg.as_str() {
+                "-p" | "--property" => property = Some(value()?),
+                "-t" | "--threshold" => {
+                    let text = value()?;
+                    threshold = text.parse().map_err(|_| {
+                        CliError::Usage(format!("`{text}` is not a percentage\n\n{USAGE}"))
+                    })?;
+                }
+                "--deny-drift" => deny_drift = true,
+                flag if flag.starts_with('-') => {
+                    return Err(CliError::Usage(format!(
+                        "unknown option `{flag}`\n\n{USAGE}"
+                    )))
+                }
+                _ => positional.push(arg.clone()),
+            }
+        }
+
+        let history = match positional.as_slice() {
+            [] => PathBuf::from(DEFAULT_HISTORY),
+            [path] => PathBuf::from(path),
+            _ => {
+                return Err(CliError::Usage(format!(
+                    "report takes at most one history file\n\n{USAGE}"
+                )))
+            }
+        };
+        Ok(Report {
+            history,
+            property,
+            threshold,
+            deny_drift,
+        })
+    }
+
+    pub fn run(&self) -> Result<ExitCode, CliError> {
+        let text = read_history(&self.history)?;
+        let mut skipped = 0;
+        let records: Vec<RunRecord> = text
+            .lines()
+            .filter(|line| !line.trim().is_empty())
+            .filter_map(|line| {
+                let record = RunRecord::parse(line);
+                skipped += usize::from(record.is_none());
+                record
+            })
+            .filter(|record| {
+                self.property
+                    .as_ref()
+                    .is_none_or(|filter| record.property.contains(filter.as_str()))
+            })
+            .collect();
+        if skipped > 0 {
+            eprintln!("Skipped {skipped} lines that aren't run records");
+        }
+        if records.is_empty() {
+            println!("No runs recorded in {}", self.history.display());
+            return Ok(ExitCode::SUCCESS);
+        }
+
+        let drifted = drift(&records, self.threshold);
+        print!("{}", render(&records, &drifted));
+        print!("{}", marked(&records));
+        if drifted.is_empty() {
+            println!("No classification drifted by {}% or more.", self.threshold);
+            Ok(ExitCode::SUCCESS)
+        } else {
+            println!(
+                "{} classifications drifted by {}% or more.",
+                drifted.len(),
+                self.threshold
+            );
+            Ok(if self.deny_drift {
+                ExitCode::FAILURE
+            } else {
+                ExitCode::SUCCESS
+            })
+        }
+    }
+}
+
+/// The records in `path`: the file's lines, or every file's in the
+/// directory.
+fn read_history(path: &Path) -> Result<String, CliError> {
+    if !path.is_dir() {
+        return std::fs::read_to_string(path).map_err(CliError::io(path));
+    }
+    let mut files: Vec<PathBuf> = std::fs::read_dir(path)
+        .map_err(CliError::io(path))?
+        .map(|entry| entry.map(|entry| entry.path()))
+        .collect::<Result<_, _>>()
+        .map_err(CliError::io(path))?;
+    files.sort();
+    let mut text = String::new();
+    for file in files.iter().filter(|file| file.is_file()) {
+        text.push_str(&std::fs::read_to_string(file).map_err(CliError::io(file))?);
+        text.push('\n');
+    }
+    Ok(text)
+}
+
+/// The report for each property, in name order.
+fn render(records: &[RunRecord], drifted: &[Drift]) -> String {
+    let mut by_property: BTreeMap<&str, Vec<&RunRecord>> = BTreeMap::new();
+    for record in records {
+        by_property
+            .entry(&record.property)
+            .or_default()
+            .push(record);
+    }
+
+    let mut report = String::new();
+    for (property, runs) in by_property {
+        let latest = runs.last().expect("properties have at least one run");
+        let count = |outcome| runs.iter().filter(|run| run.outcome == outcome).count();
+        let _ = writeln!(
+            report,
+            "{property}: {} runs, {} failed, {} gave up{}",
+            runs.len(),
+            count(RunOutcome::Failed),
+            count(RunOutcome::GaveUp),
+            latest
+                .marker
+                .as_ref()
+                .map_or(String::new(), describe_marker)
+        );
+
+        let durations: Vec<Duration> = runs.iter().map(|run| run.duration).collect();
+        let recent = &durations[durations.len().saturating_sub(SPARKLINE_RUNS)..];
+        let _ = writeln!(
+            report,
+            "  duration  {} latest, {} to {}  {}",
+            millis(latest.duration),
+            millis(*durations.iter().min().expect("at least one run")),
+            millis(*durations.iter().max().expect("at least one run")),
+            sparkline(recent)
+        );
+        let _ = writeln!(
+            report,
+            "  tests     {} latest, {} discarded",
+            latest.tests, latest.discards
+        );
+        if let Some(failure) = runs
+            .iter()
+            .rev()
+            .find(|run| run.outcome == RunOutcome::Failed)
+        {
+            let _ = writeln!(
+                report,
+                "  shrinks   {} in the latest failure",
+                failure.shrinks
+            );
+        }
+
+        for name in latest.classifications.keys() {
+            let flagged = drifted
+                .iter()
+                .find(|d| d.property == property && &d.classification == name);
+            let _ = match flagged {
+                Some(d) => writeln!(
+                    report,
+                    "  {name}: {:.1}%, drifted from {:.1}%",
+                    d.latest, d.baseline
+                ),
+                None => writeln!(report, "  {name}: {:.1}%", latest.percentage(name)),
+            };
+        }
+        for d in drifted.iter().filter(|d| {
+            d.property == property && !latest.classifications.contains_key(&d.classification)
+        }) {
+            let _ = writeln!(
+                report,
+                "  {}: 0.0%, drifted from {:.1}%",
+                d.classification, d.baseline
+            );
+        }
+    }
+    report
+}
+
+/// A count of the properties marked in their latest run, and a line for
+/// each marked property whose latest run passed.
+fn marked(records: &[RunRecord]) -> String {
+    let mut latest: BTreeMap<&str, &RunRecord> = BTreeMap::new();
+    for record in records {
+        latest.insert(&record.property, record);
+    }
+    let marked: Vec<&RunRecord> = latest
+        .into_values()
+        .filter(|record| record.marker.is_some())
+        .collect();
+    if marked.is_empty() {
+        return String::new();
+    }
+
+    let quarantined = marked
+        .iter()
+        .filter(|record| record.marker == Some(PropertyMarker::Quarantined))
+        .count();
+    let mut report = format!(
+        "{quarantined} quarantined, {} expected to fail.\n",
+        marked.len() - quarantined
+    );
+    for record in marked
+        .iter()
+        .filter(|record| record.outcome == RunOutcome::Passed)
+    {
+        let _ = writeln!(
+            report,
+            "  {} passed unexpectedly{}",
+            record.property,
+            record
+                .marker
+                .as_ref()
+                .map_or(String::new(), describe_marker)
+        );
+    }
+    report
+}
+
+fn describe_marker(marker: &PropertyMarker) -> String {
+    match marker {
+        PropertyMarker::ExpectedFailure(reason) => format!(" (expected to fail: {reason})"),
+        PropertyMarker::Quarantined => " (quarantined)".to_string(),
+    }
+}
+
+fn millis(duration: Duration) -> String {
+    format!("{:.1}ms", duration.as_secs_f64() * 1000.0)
+}
+
+/// Durations as bars from lowest to highest.
+fn sparkline(durations: &[Duration]) -> String {
+    const BARS: [char; 8] = ['▁', '▂', '▃', '▄', '▅', '▆', '▇', '█'];
+    let lowest = durations.iter().min().copied().unwrap_or_default();
+    let highest = durations.iter().max().copied().unwrap_or_default();
+    let range = (highest - lowest).as_secs_f64();
+    durations
+        .iter()
+        .map(|duration| {
+            if range == 0.0 {
+                BARS[0]
+            } else {
+                let height = (*duration - lowest).as_secs_f64() / range;
+                BARS[(height * (BARS.len() - 1) as f64).round() as usize]
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(args: &[&str]) -> Vec<String> {
+        args.iter().map(|arg| arg.to_string()).collect()
+    }
+
+    fn record(time: u64, micros: u64, outcome: &str, negative: usize) -> RunRecord {
+        RunRecord::parse(&format!(
+            "time={time}\tproperty=round_trip\toutcome={outcome}\ttests=100\tdiscards=0\tshrinks=4\tmicros={micros}\tclasses=negative:{negative}"
+        ))
+        .unwrap()
+    }
+
+    #[test]
+    fn test_parses_history_and_options() {
+        assert_eq!(
+            Report::parse(&args(&[])).unwrap(),
+            Report {
+                history: PathBuf::from("hedgehog-history"),
+                property: None,
+                threshold: 10.0,
+                deny_drift: false,
+            }
+        );
+        let report = Report::parse(&args(&[
+            "ci.txt",
+            "-t",
+            "5",
+            "-p",
+            "parser",
+            "--deny-drift",
+        ]))
+        .unwrap();
+        assert_eq!(report.history, PathBuf::from("ci.txt"));
+        assert_eq!(report.threshold, 5.0);
+        assert_eq!(report.property.as_deref(), Some("parser"));
+        assert!(report.deny_drift);
+        assert!(Report::parse(&args(&["-t", "lots"])).is_err());
+        assert!(Report::parse(&args(&["a", "b"])).is_err());
+    }
+
+    #[test]
+    fn test_reads_every_file_in_a_history_directory() {
+        let dir = std::env::temp_dir().join(format!("hedgehog-cli-history-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(dir.join("lexer.txt"), "lexer run").unwrap();
+        std::fs::write(dir.join("parser.txt"), "parser run\n").unwrap();
+        let text = read_history(&dir).unwrap();
+        std::fs::remove_dir_all(&dir).unwrap();
+        assert_eq!(text.lines().filter(|line| !line.is_empty()).count(), 2);
+        assert!(text.starts_with("lexer run\n"));
+        assert!(read_history(&dir).is_err());
+    }
+
+    #[test]
+    fn test_renders_trends_and_flags_drift() {
+        let records = vec![
+            record(1, 1000, "pass", 50),
+            record(2, 2000, "fail", 48),
+            record(3, 3000, "pass", 10),
+        ];
+        let drifted = drift(&records, 10.0);
+        let report = render(&records, &drifted);
+        assert!(report.starts_with("round_trip: 3 runs, 1 failed, 0 gave up\n"));
+        assert!(report.contains("  duration  3.0ms latest, 1.0ms to 3.0ms  ▁▅█\n"));
+        assert!(report.contains("  shrinks   4 in the latest failure\n"));
+        assert!(report.contains("  negative: 10.0%, drifted from 49.0%\n"));
+        assert_eq!(marked(&records), "");
+    }
+
+    #[test]
+    fn test_counts_marked_properties_and_flags_passing_ones() {
+        let marked_run = |property: &str, outcome: &str, marker: &str| {
+            RunRecord::parse(&format!(
+                "time=1\tproperty={property}\toutcome={outcome}\ttests=100\tdiscards=0\tshrinks=0\tmicros=5\tclasses=\tmarker={marker}"
+            ))
+            .unwrap()
+        };
+        let records = vec![
+            marked_run("flaky", "fail", "quarantined"),
+            marked_run("parser", "fail", "expected:issue-123"),
+            marked_run("lexer", "fail", "expected:issue-9"),
+            marked_run("lexer", "pass", "expected:issue-9"),
+        ];
+        assert_eq!(
+            marked(&records),
+            "1 quarantined, 2 expected to fail.\n  lexer passed unexpectedly (expected to fail: issue-9)\n"
+        );
+        assert!(
+            render(&records, &[]).contains("flaky: 1 runs, 1 failed, 0 gave up (quarantined)\n")
+        );
+    }
+}
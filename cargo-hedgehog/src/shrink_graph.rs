@@ -0,0 +1,221 @@
+//! `cargo hedgehog shrink-graph`: export the shrink tree explored for a
+//! failing property, to see why shrinking stopped where it did.
+//!
+//! The tests run with `HEDGEHOG_SHRINK_GRAPH` naming a scratch directory,
+//! which `Config::from_env()` picks up, so properties need no changes. Each
+//! failing property writes its graph there, and the graphs are then moved
+//! beside the output.
+
+use crate::error::CliError;
+use hedgehog_core::{PROPERTY_PLACEHOLDER, SHRINK_GRAPH_ENV_VAR};
+use std::path::{Path, PathBuf};
+use std::process::{Command, ExitCode};
+
+const USAGE: &str = "\
+Usage: cargo hedgehog shrink-graph <property> [options]
+
+Runs the tests matching <property> and writes the shrink tree explored for
+the failure, as Graphviz DOT or, for a .json file, as JSON. When several
+properties fail, each graph is written beside <file>, named after it.
+
+Options:
+  -o, --output <file>      Write the graph to <file> (default: shrinks.dot)
+  -p, --package <name>     Package whose tests hold the property";
+
+/// Where the graph goes without `--output`.
+const DEFAULT_OUTPUT: &str = "shrinks.dot";
+
+#[derive(Debug, PartialEq)]
+pub struct ShrinkGraph {
+    property: String,
+    output: PathBuf,
+    package: Option<String>,
+}
+
+impl ShrinkGraph {
+    pub fn parse(args: &[String]) -> Result<Self, CliError> {
+        let mut positional = Vec::new();
+        let mut output = None;
+        let mut package = None;
+        let mut args = args.iter();
+        while let Some(arg) = args.next() {
+            let mut value = || {
+                args.next()
+                    .cloned()
+                    .ok_or_else(|| CliError::Usage(format!("{arg} needs a value\n\n{USAGE}")))
+            };
+            match arg.as_str() {
+                "-o" | "--output" => output = Some(PathBuf::from(value()?)),
+                "-p" | "--package" => package = Some(value()?),
+                flag if flag.starts_with('-') => {
+                    return Err(CliError::Usage(format!(
+                        "unknown option `{flag}`\n\n{USAGE}"
+                    )))
+                }
+                _ => positional.push(arg.clone()),
+            }
+        }
+
+        match <[String; 1]>::try_from(positional) {
+            Ok([property]) => Ok(ShrinkGraph {
+                property,
+                output: output.unwrap_or_else(|| PathBuf::from(DEFAULT_OUTPUT)),
+                package,
+            }),
+            Err(_) => Err(CliError::Usage(format!(
+                "shrink-graph takes one property\n\n{USAGE}"
+            ))),
+        }
+    }
+
+    pub fn run(&self) -> Result<ExitCode, CliError> {
+        let scratch =
+            std::env::temp_dir().join(format!("hedgehog-shrink-graph-{}", std::process::id()));
+        // A stale graph would look like this run's
+        let _ = std::fs::remove_dir_all(&scratch);
+        let _ = std::fs::remove_file(&self.output);
+        std::fs::create_dir_all(&scratch).map_err(CliError::io(&scratch))?;
+
+        let extension = self
+            .output
+            .extension()
+            .map_or("dot".into(), |extension| extension.to_string_lossy());
+        let template = scratch.join(format!("{PROPERTY_PLACEHOLDER}.{extension}"));
+        let status = self.command(&template).status().map_err(CliError::Cargo);
+        let graphs = status.and_then(|_| self.collect(&scratch));
+        let _ = std::fs::remove_dir_all(&scratch);
+        let graphs = graphs?;
+
+        if graphs.is_empty() {
+            eprintln!(
+                "No shrink graph: no test matching `{}` failed with a shrinkable input",
+                self.property
+            );
+            return Ok(ExitCode::FAILURE);
+        }
+        for graph in &graphs {
+            println!("Shrink graph: {}", graph.display());
+        }
+        if extension != "json" {
+            println!(
+                "Render it with: dot -Tsvg {} -o shrinks.svg",
+                graphs[0].display()
+            );
+        }
+        Ok(ExitCode::SUCCESS)
+    }
+
+    /// Move the graphs written to `scratch` beside the output: a lone graph
+    /// becomes the output, several are each named after their property.
+    fn collect(&self, scratch: &Path) -> Result<Vec<PathBuf>, CliError> {
+        let mut written: Vec<PathBuf> = std::fs::read_dir(scratch)
+            .map_err(CliError::io(scratch))?
+            .map(|entry| entry.map(|entry| entry.path()))
+            .collect::<Result<_, _>>()
+            .map_err(CliError::io(scratch))?;
+        written.sort();
+
+        let mut graphs = Vec::new();
+        for graph in &written {
+            let destination = match written.len() {
+                1 => self.output.clone(),
+                _ => self.beside_output(graph),
+            };
+            // Copied rather than renamed, as the scratch directory may be
+            // on another filesystem
+            std::fs::copy(graph, &destination).map_err(CliError::io(&destination))?;
+            graphs.push(destination);
+        }
+        Ok(graphs)
+    }
+
+    /// `graph` renamed to `<output stem>-<property>.<extension>`, beside the
+    /// output.
+    fn beside_output(&self, graph: &Path) -> PathBuf {
+        let stem = self
+            .output
+            .file_stem()
+            .unwrap_or_default()
+            .to_string_lossy();
+        let property = graph.file_name().unwrap_or_default().to_string_lossy();
+        self.output.with_file_name(format!("{stem}-{property}"))
+    }
+
+    /// The `cargo test` run that writes graphs to `template`, absolute since
+    /// tests run in their package's directory.
+    fn command(&self, template: &Path) -> Command {
+        let mut command = crate::cargo();
+        command.arg("test");
+        if let Some(package) = &self.package {
+            command.args(["--package", package]);
+        }
+        command
+            .args(["--", &self.property, "--nocapture"])
+            .env(SHRINK_GRAPH_ENV_VAR, template);
+        command
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::ffi::OsStr;
+
+    fn args(args: &[&str]) -> Vec<String> {
+        args.iter().map(|arg| arg.to_string()).collect()
+    }
+
+    #[test]
+    fn test_parses_property_and_options() {
+        assert_eq!(
+            ShrinkGraph::parse(&args(&["parser::round_trip"])).unwrap(),
+            ShrinkGraph {
+                property: "parser::round_trip".to_string(),
+                output: PathBuf::from("shrinks.dot"),
+                package: None,
+            }
+        );
+        assert_eq!(
+            ShrinkGraph::parse(&args(&["round_trip", "-o", "tree.json"]))
+                .unwrap()
+                .output,
+            PathBuf::from("tree.json")
+        );
+        assert!(ShrinkGraph::parse(&args(&[])).is_err());
+        assert!(ShrinkGraph::parse(&args(&["a", "b"])).is_err());
+        assert!(ShrinkGraph::parse(&args(&["a", "--verbose"])).is_err());
+    }
+
+    #[test]
+    fn test_runs_the_matching_tests_with_the_output() {
+        let graph = ShrinkGraph::parse(&args(&["round_trip", "-p", "parser"])).unwrap();
+        let command = graph.command(Path::new("/scratch/{property}.dot"));
+
+        let args: Vec<&OsStr> = command.get_args().collect();
+        assert_eq!(
+            args,
+            [
+                "test",
+                "--package",
+                "parser",
+                "--",
+                "round_trip",
+                "--nocapture"
+            ]
+        );
+        let envs: Vec<_> = command.get_envs().collect();
+        assert!(envs.contains(&(
+            OsStr::new(SHRINK_GRAPH_ENV_VAR),
+            Some(OsStr::new("/scratch/{property}.dot"))
+        )));
+    }
+
+    #[test]
+    fn test_names_each_graph_after_its_property() {
+        let graph = ShrinkGraph::parse(&args(&["round_trip", "-o", "out/tree.json"])).unwrap();
+        assert_eq!(
+            graph.beside_output(Path::new("/scratch/parser__round_trip.json")),
+            PathBuf::from("out/tree-parser__round_trip.json")
+        );
+    }
+}
@@ -0,0 +1,318 @@
+//! `cargo hedgehog test`: run a project's properties through `cargo test`
+//! in one of Hedgehog's checking modes.
+//!
+//! With `--verify-determinism` the tests run with
+//! `HEDGEHOG_VERIFY_DETERMINISM` set, which `Config::from_env()` picks up:
+//! each case is generated and tested twice, and a property whose input or
+//! verdict changes fails, since its seeds wouldn't replay its failures.
+//!
+//! Properties are `#[test]` functions, which `cargo test` only runs from
+//! the library, binaries and `tests/`. Crates that keep long-running
+//! suites under `examples/` or `benches/` opt in to those with `--examples`
+//! and `--benches`.
+//!
+//! With `--workspace`, each crate in the workspace, or each one named with
+//! `-p`, runs its tests on its own, and a summary says which crates failed.
+//!
+//! With `--backtrace` the tests run with `RUST_BACKTRACE` set, so panics
+//! that properties catch, such as with `Property::no_panic`, are reported
+//! with a backtrace of the frames outside the runtime.
+
+use crate::error::CliError;
+use hedgehog_core::DETERMINISM_ENV_VAR;
+use std::process::{Command, ExitCode};
+
+const USAGE: &str = "\
+Usage: cargo hedgehog test [filter] [options] [-- <test args>]
+
+Runs the tests matching [filter], or all tests, with the checks chosen by
+the options. Arguments after `--` are passed to the test binaries.
+
+Options:
+      --verify-determinism   Run each case twice and fail properties whose
+                             input or verdict differs between the runs
+  -p, --package <name>       Package whose tests to run; may be repeated
+      --workspace            Run each crate in the workspace, or each
+                             package given, separately and summarize
+      --examples             Also run properties defined in examples
+      --benches              Also run properties defined in benches
+      --backtrace            Show backtraces of panics in failures";
+
+#[derive(Debug, PartialEq)]
+pub struct Test {
+    filter: Option<String>,
+    packages: Vec<String>,
+    workspace: bool,
+    verify_determinism: bool,
+    examples: bool,
+    benches: bool,
+    backtrace: bool,
+    test_args: Vec<String>,
+}
+
+impl Test {
+    pub fn parse(args: &[String]) -> Result<Self, CliError> {
+        let mut positional = Vec::new();
+        let mut packages = Vec::new();
+        let mut workspace = false;
+        let mut verify_determinism = false;
+        let mut examples = false;
+        let mut benches = false;
+        let mut backtrace = false;
+        let mut test_args = Vec::new();
+        let mut args = args.iter();
+        while let Some(arg) = args.next() {
+            let mut value = || {
+                args.next()
+                    .cloned()
+                    .ok_or_else(|| CliError::Usage(format!("{arg} needs a value\n\n{USAGE}")))
+            };
+            match arg.as_str() {
+                "--verify-determinism" => verify_determinism = true,
+                "-p" | "--package" => packages.push(value()?),
+                "--workspace" => workspace = true,
+                "--examples" => examples = true,
+                "--benches" => benches = true,
+                "--backtrace" => backtrace = true,
+                "--" => {
+                    test_args = args.by_ref().cloned().collect();
+                    break;
+                }
+                flag if flag.starts_with('-') => {
+                    return Err(CliError::Usage(format!(
+                        "unknown option `{flag}`\n\n{USAGE}"
+                    )))
+                }
+                _ => positional.push(arg.clone()),
+            }
+        }
+
+        if positional.len() > 1 {
+            return Err(CliError::Usage(format!(
+                "test takes at most one filter\n\n{USAGE}"
+            )));
+        }
+        Ok(Test {
+            filter: positional.pop(),
+            packages,
+            workspace,
+            verify_determinism,
+            examples,
+            benches,
+            backtrace,
+            test_args,
+        })
+    }
+
+    pub fn run(&self) -> Result<ExitCode, CliError> {
+        let passed = if self.workspace {
+            self.run_each_crate()?
+        } else {
+            let status = self.command().status().map_err(CliError::Cargo)?;
+            status.success()
+        };
+        if passed {
+            Ok(ExitCode::SUCCESS)
+        } else {
+            if self.verify_determinism {
+                eprintln!(
+                    "Properties failing with `Nondeterministic Generator` or \
+                     `Nondeterministic Test` won't replay from their seeds"
+                );
+            }
+            Ok(ExitCode::FAILURE)
+        }
+    }
+
+    /// Run each selected workspace crate's tests in turn, then print which
+    /// passed, returning whether all of them did.
+    fn run_each_crate(&self) -> Result<bool, CliError> {
+        let members = crate::workspace::members()?;
+        if let Some(unknown) = self.packages.iter().find(|p| !members.contains(p)) {
+            return Err(CliError::Usage(format!(
+                "`{unknown}` is not in the workspace; its crates are {}",
+                members.join(", ")
+            )));
+        }
+        let selected: Vec<&String> = members
+            .iter()
+            .filter(|member| self.packages.is_empty() || self.packages.contains(member))
+            .collect();
+
+        let mut results = Vec::new();
+        for member in selected {
+            println!("━━━ {member} ━━━");
+            let status = self
+                .command_for(std::slice::from_ref(member))
+                .status()
+                .map_err(CliError::Cargo)?;
+            results.push((member.as_str(), status.success()));
+        }
+        print!("{}", summary(&results));
+        Ok(results.iter().all(|(_, passed)| *passed))
+    }
+
+    fn command(&self) -> Command {
+        self.command_for(&self.packages)
+    }
+
+    fn command_for(&self, packages: &[String]) -> Command {
+        let mut command = crate::cargo();
+        command.arg("test");
+        for package in packages {
+            command.args(["--package", package]);
+        }
+        // Naming any target kind drops the default ones, so name those too;
+        // doc tests hold no properties
+        if self.examples || self.benches {
+            command.arg("--tests");
+        }
+        if self.examples {
+            command.arg("--examples");
+        }
+        if self.benches {
+            command.arg("--benches");
+        }
+        command.arg("--");
+        if let Some(filter) = &self.filter {
+            command.arg(filter);
+        }
+        command.args(&self.test_args);
+        if self.verify_determinism {
+            command.env(DETERMINISM_ENV_VAR, "1");
+        }
+        if self.backtrace {
+            command.env("RUST_BACKTRACE", "1");
+        }
+        command
+    }
+}
+
+/// Which crates' tests passed, one per line, and how many passed and
+/// failed.
+fn summary(results: &[(&str, bool)]) -> String {
+    let mut summary = String::from("\nWorkspace summary:\n");
+    for (member, passed) in results {
+        let verdict = if *passed { "passed" } else { "failed" };
+        summary.push_str(&format!("  {member}: {verdict}\n"));
+    }
+    let failed: Vec<&str> = results
+        .iter()
+        .filter(|(_, passed)| !passed)
+        .map(|(member, _)| *member)
+        .collect();
+    let crates = if results.len() == 1 {
+        "crate"
+    } else {
+        "crates"
+    };
+    let passed = results.len() - failed.len();
+    if failed.is_empty() {
+        summary.push_str(&format!("{passed} {crates} passed.\n"));
+    } else {
+        summary.push_str(&format!(
+            "{passed} of {} {crates} passed, {} failed: {}\n",
+            results.len(),
+            failed.len(),
+            failed.join(", ")
+        ));
+    }
+    summary
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::ffi::OsStr;
+
+    fn args(args: &[&str]) -> Vec<String> {
+        args.iter().map(|arg| arg.to_string()).collect()
+    }
+
+    #[test]
+    fn test_parses_filter_options_and_test_args() {
+        assert_eq!(
+            Test::parse(&args(&[])).unwrap(),
+            Test {
+                filter: None,
+                packages: Vec::new(),
+                workspace: false,
+                verify_determinism: false,
+                examples: false,
+                benches: false,
+                backtrace: false,
+                test_args: Vec::new(),
+            }
+        );
+        let test = Test::parse(&args(&[
+            "parser",
+            "--verify-determinism",
+            "-p",
+            "core",
+            "--",
+            "--test-threads",
+            "1",
+        ]))
+        .unwrap();
+        assert_eq!(test.filter.as_deref(), Some("parser"));
+        assert_eq!(test.packages, ["core"]);
+        assert!(test.verify_determinism);
+        assert_eq!(test.test_args, ["--test-threads", "1"]);
+        assert!(Test::parse(&args(&["a", "b"])).is_err());
+        assert!(Test::parse(&args(&["--deterministic"])).is_err());
+    }
+
+    #[test]
+    fn test_runs_cargo_test_with_the_checks() {
+        let test = Test::parse(&args(&[
+            "round_trip",
+            "--verify-determinism",
+            "--backtrace",
+        ]))
+        .unwrap();
+        let command = test.command();
+
+        let args: Vec<&OsStr> = command.get_args().collect();
+        assert_eq!(args, ["test", "--", "round_trip"]);
+        let envs: Vec<_> = command.get_envs().collect();
+        assert!(envs.contains(&(OsStr::new(DETERMINISM_ENV_VAR), Some(OsStr::new("1")))));
+        assert!(envs.contains(&(OsStr::new("RUST_BACKTRACE"), Some(OsStr::new("1")))));
+    }
+
+    #[test]
+    fn test_runs_examples_and_benches_alongside_tests() {
+        let both = Test::parse(&args(&["slow", "--examples", "--benches"])).unwrap();
+        let command = both.command();
+        assert_eq!(
+            command.get_args().collect::<Vec<_>>(),
+            ["test", "--tests", "--examples", "--benches", "--", "slow"]
+        );
+
+        let examples = Test::parse(&args(&["--examples", "-p", "core"])).unwrap();
+        let command = examples.command();
+        assert_eq!(
+            command.get_args().collect::<Vec<_>>(),
+            ["test", "--package", "core", "--tests", "--examples", "--"]
+        );
+    }
+
+    #[test]
+    fn test_runs_each_workspace_crate_and_summarizes() {
+        let test = Test::parse(&args(&["--workspace", "-p", "core", "-p", "cli"])).unwrap();
+        assert!(test.workspace);
+        assert_eq!(test.packages, ["core", "cli"]);
+        let command = test.command_for(&["cli".to_string()]);
+        assert_eq!(
+            command.get_args().collect::<Vec<_>>(),
+            ["test", "--package", "cli", "--"]
+        );
+
+        assert_eq!(
+            summary(&[("core", true), ("cli", false)]),
+            "\nWorkspace summary:\n  core: passed\n  cli: failed\n1 of 2 crates passed, 1 failed: cli\n"
+        );
+        assert!(summary(&[("core", true)]).ends_with("\n1 crate passed.\n"));
+        assert!(summary(&[("core", true), ("cli", true)]).ends_with("\n2 crates passed.\n"));
+        assert!(summary(&[("cli", false)]).ends_with("\n0 of 1 crate passed, 1 failed: cli\n"));
+    }
+}
@@ -0,0 +1,90 @@
+//! Finding the crates in a Cargo workspace.
+//!
+//! `cargo metadata --no-deps` lists the workspace's own packages and nothing
+//! else. Only their names are needed, so rather than depend on a JSON
+//! parser this reads the `name` of each object in the `packages` array.
+
+use crate::error::CliError;
+
+/// The names of the packages in the workspace cargo runs in, in the order
+/// cargo lists them.
+pub fn members() -> Result<Vec<String>, CliError> {
+    let output = crate::cargo()
+        .args(["metadata", "--no-deps", "--format-version", "1"])
+        .output()
+        .map_err(CliError::Cargo)?;
+    if !output.status.success() {
+        return Err(CliError::CargoFailed("cargo metadata".to_string()));
+    }
+    Ok(package_names(&String::from_utf8_lossy(&output.stdout)))
+}
+
+/// The `name` of each package in `cargo metadata` output.
+fn package_names(metadata: &str) -> Vec<String> {
+    let mut names = Vec::new();
+    // The key of each enclosing object member, outermost first
+    let mut path: Vec<Option<String>> = Vec::new();
+    let mut key = None;
+    let mut chars = metadata.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            '{' | '[' => path.push(key.take()),
+            '}' | ']' => {
+                path.pop();
+            }
+            ',' => key = None,
+            '"' => {
+                let text = read_string(&mut chars);
+                let in_package = path.len() == 3 && path[1].as_deref() == Some("packages");
+                match key.take() {
+                    Some(name) if in_package && name == "name" => names.push(text),
+                    Some(_) => {}
+                    None => key = Some(text),
+                }
+                // A string that isn't followed by `:` was a value
+                let rest = chars.as_str().trim_start();
+                if !rest.starts_with(':') {
+                    key = None;
+                }
+            }
+            _ => {}
+        }
+    }
+    names
+}
+
+/// Read a string up to its closing quote, undoing escapes.
+fn read_string(chars: &mut std::str::Chars) -> String {
+    let mut text = String::new();
+    while let Some(c) = chars.next() {
+        match c {
+            '"' => break,
+            '\\' => match chars.next() {
+                Some('n') => text.push('\n'),
+                Some('t') => text.push('\t'),
+                Some(escaped) => text.push(escaped),
+                None => break,
+            },
+            c => text.push(c),
+        }
+    }
+    text
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_reads_package_names_from_metadata() {
+        let metadata = r#"{"packages":[
+            {"name":"core","version":"0.1.0","dependencies":[{"name":"rand","req":"^0.8"}],
+             "targets":[{"kind":["lib"],"name":"core"}]},
+            {"name":"cli \"tool\"","version":"0.1.0","dependencies":[],"targets":[]}
+          ],
+          "workspace_members":["path+file:///w/core#0.1.0"],
+          "resolve":null,"target_directory":"/w/target","version":1}"#;
+        assert_eq!(package_names(metadata), ["core", "cli \"tool\""]);
+        assert!(package_names("{}").is_empty());
+    }
+}
@@ -116,6 +116,7 @@ fn example_shared_state_testing() {
                         module_path: None,
                         assertion_type: Some("Positive Counter".to_string()),
                         shrink_steps: Vec::new(),
+                        details: FailureDetails::default(),
                     }
                 }
             }
@@ -125,6 +126,7 @@ fn example_shared_state_testing() {
             work_distribution: WorkDistribution::RoundRobin,
             timeout: Some(Duration::from_secs(5)),
             detect_non_determinism: true,
+            executor: Executor::Threads,
         },
     );
 
@@ -290,6 +292,7 @@ fn example_concurrent_testing() {
                         module_path: None,
                         assertion_type: Some("Race Condition".to_string()),
                         shrink_steps: Vec::new(),
+                        details: FailureDetails::default(),
                     }
                 }
             }
@@ -368,6 +371,7 @@ fn example_concurrent_testing() {
                         module_path: None,
                         assertion_type: Some("Thread Safety".to_string()),
                         shrink_steps: Vec::new(),
+                        details: FailureDetails::default(),
                     }
                 }
             }
@@ -473,6 +477,7 @@ fn example_concurrent_testing() {
                     module_path: None,
                     assertion_type: Some("Validation".to_string()),
                     shrink_steps: Vec::new(),
+                    details: FailureDetails::default(),
                 }
             }
         })
@@ -567,6 +572,7 @@ fn example_concurrent_testing() {
                     module_path: None,
                     assertion_type: Some("Race Condition".to_string()),
                     shrink_steps: Vec::new(),
+                    details: FailureDetails::default(),
                 }
             }
         }
@@ -651,6 +657,7 @@ fn example_load_generation() {
         ramp_up_duration: Duration::from_millis(50),
         cool_down_duration: Duration::from_millis(30),
         collect_stats: true,
+        arrival: hedgehog_core::ArrivalModel::Closed,
     };
 
     let load_generator = hedgehog_core::LoadGenerator::new(
@@ -682,6 +689,7 @@ fn example_load_generation() {
                         module_path: None,
                         assertion_type: Some("Service Error".to_string()),
                         shrink_steps: Vec::new(),
+                        details: FailureDetails::default(),
                     }
                 }
             }
@@ -733,6 +741,7 @@ fn example_load_generation() {
         ramp_up_duration: Duration::from_millis(20),
         cool_down_duration: Duration::from_millis(10),
         collect_stats: true,
+        arrival: hedgehog_core::ArrivalModel::Closed,
     };
 
     // Simulate a high-performance computation
@@ -768,6 +777,7 @@ fn example_load_generation() {
                         module_path: None,
                         assertion_type: Some("Computation Error".to_string()),
                         shrink_steps: Vec::new(),
+                        details: FailureDetails::default(),
                     }
                 }
             }
@@ -824,6 +834,7 @@ fn example_load_generation() {
         ramp_up_duration: Duration::from_millis(15),
         cool_down_duration: Duration::from_millis(15),
         collect_stats: true,
+        arrival: hedgehog_core::ArrivalModel::Closed,
     };
 
     // Test memory allocation patterns
@@ -850,6 +861,7 @@ fn example_load_generation() {
                     module_path: None,
                     assertion_type: Some("Memory Error".to_string()),
                     shrink_steps: Vec::new(),
+                    details: FailureDetails::default(),
                 }
             }
         },
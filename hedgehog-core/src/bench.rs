@@ -0,0 +1,267 @@
+//! Measuring how fast generators are.
+//!
+//! A generator that gets ten times slower, or starts building shrink trees
+//! with ten times the nodes, slows down every property that uses it without
+//! failing any of them. [`Benchmark`] generates a batch of values and reports
+//! throughput, tree sizes and, with [`CountingAllocator`] installed,
+//! allocations, and the report's assertions turn those into tests.
+
+use crate::data::{Seed, Size};
+use crate::gen::Gen;
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::cell::Cell;
+use std::fmt;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::{Duration, Instant};
+
+/// A global allocator that counts allocations on each thread.
+///
+/// Install it in a test or bench binary to have [`Benchmark`] report
+/// allocations:
+///
+/// ```rust,ignore
+/// #[global_allocator]
+/// static ALLOCATOR: hedgehog_core::bench::CountingAllocator =
+///     hedgehog_core::bench::CountingAllocator;
+/// ```
+///
+/// Counts are kept per thread, so benchmarks running in parallel test
+/// threads don't see each other's allocations.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct CountingAllocator;
+
+static INSTALLED: AtomicBool = AtomicBool::new(false);
+
+thread_local! {
+    static ALLOCATIONS: Cell<u64> = const { Cell::new(0) };
+}
+
+impl CountingAllocator {
+    /// Whether anything has been allocated through the counting allocator.
+    pub fn is_installed() -> bool {
+        INSTALLED.load(Ordering::Relaxed)
+    }
+
+    /// Allocations made on this thread so far.
+    pub fn allocations() -> u64 {
+        ALLOCATIONS.try_with(Cell::get).unwrap_or(0)
+    }
+
+    fn count() {
+        INSTALLED.store(true, Ordering::Relaxed);
+        let _ = ALLOCATIONS.try_with(|count| count.set(count.get() + 1));
+    }
+}
+
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        Self::count();
+        System.alloc(layout)
+    }
+
+    unsafe fn alloc_zeroed(&self, layout: Layout) -> *mut u8 {
+        Self::count();
+        System.alloc_zeroed(layout)
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        Self::count();
+        System.realloc(ptr, layout, new_size)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout)
+    }
+}
+
+/// Settings for measuring a generator.
+///
+/// # Example
+/// ```rust
+/// use hedgehog_core::*;
+/// use hedgehog_core::bench::Benchmark;
+///
+/// let report = Benchmark::new(1_000)
+///     .with_size(Size::new(50))
+///     .run(&Gen::vec_of(Gen::int_range(0, 100)));
+///
+/// report.assert_tree_size_at_most(10_000.0);
+/// println!("{report}");
+/// ```
+#[derive(Debug, Clone, Copy)]
+pub struct Benchmark {
+    samples: usize,
+    size: Size,
+    seed: Seed,
+}
+
+impl Benchmark {
+    /// Measure `samples` values, generated at size 30 from a fixed seed.
+    pub fn new(samples: usize) -> Self {
+        Benchmark {
+            samples,
+            size: Size::new(30),
+            seed: Seed::from_u64(0),
+        }
+    }
+
+    /// Generate every value at `size`.
+    pub fn with_size(mut self, size: Size) -> Self {
+        self.size = size;
+        self
+    }
+
+    /// Start from `seed` instead of the default fixed seed.
+    pub fn with_seed(mut self, seed: Seed) -> Self {
+        self.seed = seed;
+        self
+    }
+
+    /// Generate the samples and report on them.
+    ///
+    /// Only generation is timed and counted; walking each tree to measure it
+    /// happens afterwards.
+    pub fn run<T>(&self, gen: &Gen<T>) -> BenchmarkReport {
+        let mut seed = self.seed;
+        let mut elapsed = Duration::ZERO;
+        let mut allocations = 0;
+        let mut total_nodes = 0;
+        let mut max_nodes = 0;
+
+        for _ in 0..self.samples {
+            let (sample_seed, next) = seed.split();
+            seed = next;
+
+            let before = CountingAllocator::allocations();
+            let started = Instant::now();
+            let tree = gen.generate(self.size, sample_seed);
+            elapsed += started.elapsed();
+            allocations += CountingAllocator::allocations() - before;
+
+            let nodes = tree.count_nodes();
+            total_nodes += nodes;
+            max_nodes = max_nodes.max(nodes);
+        }
+
+        let per_sample = |total: f64| total / self.samples.max(1) as f64;
+        BenchmarkReport {
+            samples: self.samples,
+            elapsed,
+            values_per_sec: self.samples as f64 / elapsed.as_secs_f64().max(f64::MIN_POSITIVE),
+            mean_tree_size: per_sample(total_nodes as f64),
+            max_tree_size: max_nodes,
+            allocations_per_value: CountingAllocator::is_installed()
+                .then(|| per_sample(allocations as f64)),
+        }
+    }
+}
+
+/// What a [`Benchmark`] measured.
+#[derive(Debug, Clone, PartialEq)]
+pub struct BenchmarkReport {
+    /// Values generated
+    pub samples: usize,
+    /// Time spent generating them
+    pub elapsed: Duration,
+    /// Values generated per second
+    pub values_per_sec: f64,
+    /// Mean number of nodes in each value's shrink tree
+    pub mean_tree_size: f64,
+    /// Most nodes in any value's shrink tree
+    pub max_tree_size: usize,
+    /// Mean allocations to generate each value, if [`CountingAllocator`] is
+    /// the global allocator
+    pub allocations_per_value: Option<f64>,
+}
+
+impl BenchmarkReport {
+    /// Panic unless at least `minimum` values were generated per second.
+    pub fn assert_values_per_sec_at_least(&self, minimum: f64) {
+        assert!(
+            self.values_per_sec >= minimum,
+            "generator produced {:.0} values/sec, expected at least {minimum:.0}",
+            self.values_per_sec
+        );
+    }
+
+    /// Panic unless shrink trees had at most `maximum` nodes on average.
+    pub fn assert_tree_size_at_most(&self, maximum: f64) {
+        assert!(
+            self.mean_tree_size <= maximum,
+            "shrink trees had {:.1} nodes on average, expected at most {maximum:.1}",
+            self.mean_tree_size
+        );
+    }
+
+    /// Panic unless each value took at most `maximum` allocations on
+    /// average. Also panics if [`CountingAllocator`] isn't installed, since
+    /// the check would otherwise pass without measuring anything.
+    pub fn assert_allocations_at_most(&self, maximum: f64) {
+        let allocations = self
+            .allocations_per_value
+            .expect("allocation assertions need CountingAllocator as the global allocator");
+        assert!(
+            allocations <= maximum,
+            "generator made {allocations:.1} allocations per value, expected at most {maximum:.1}"
+        );
+    }
+}
+
+impl fmt::Display for BenchmarkReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} values in {:?} ({:.0} values/sec), shrink trees of {:.1} nodes on average and {} at most",
+            self.samples,
+            self.elapsed,
+            self.values_per_sec,
+            self.mean_tree_size,
+            self.max_tree_size
+        )?;
+        if let Some(allocations) = self.allocations_per_value {
+            write!(f, ", {allocations:.1} allocations per value")?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_reports_tree_sizes() {
+        let report = Benchmark::new(50).run(&Gen::constant(7));
+        assert_eq!(report.samples, 50);
+        assert_eq!(report.mean_tree_size, 1.0);
+        assert_eq!(report.max_tree_size, 1);
+        assert!(report.values_per_sec > 0.0);
+        report.assert_tree_size_at_most(1.0);
+
+        let vectors = Benchmark::new(50)
+            .with_size(Size::new(20))
+            .run(&Gen::vec_of(Gen::int_range(0, 100)));
+        assert!(vectors.mean_tree_size > 1.0);
+        assert!(vectors.max_tree_size as f64 >= vectors.mean_tree_size);
+    }
+
+    #[test]
+    #[should_panic(expected = "nodes on average")]
+    fn test_tree_size_assertion_fails() {
+        Benchmark::new(20)
+            .run(&Gen::vec_of(Gen::int_range(0, 100)))
+            .assert_tree_size_at_most(1.0);
+    }
+
+    #[test]
+    fn test_counts_allocations_on_this_thread() {
+        let layout = Layout::new::<u64>();
+        let before = CountingAllocator::allocations();
+        unsafe {
+            let ptr = CountingAllocator.alloc(layout);
+            CountingAllocator.dealloc(ptr, layout);
+        }
+        assert_eq!(CountingAllocator::allocations(), before + 1);
+        assert!(CountingAllocator::is_installed());
+    }
+}
@@ -0,0 +1,215 @@
+//! Recording and replaying the effects of state machine commands.
+//!
+//! A state machine test against a real system, such as a database or a web
+//! service, needs that system for every run, and every shrink of a failure
+//! runs the commands again. A [`Cassette`] records the system's response to
+//! each command while recording, then answers from the recording when
+//! replaying. A failure found against the real system can then be rerun
+//! and shrunk without it, and the same responses come back every time.
+//!
+//! ```rust
+//! use hedgehog_core::*;
+//!
+//! #[derive(Debug, Clone)]
+//! struct Model;
+//!
+//! let cassette = Cassette::new();
+//! let command = Command::<u32, String, Model, String>::new(
+//!     "lookup".to_string(),
+//!     |_| Some(Gen::u32_range(0, 9)),
+//!     |id| format!("user-{id}"), // A request to the real service
+//! )
+//! .recorded(&cassette);
+//!
+//! // Later, with the same responses and no service
+//! let replaying = cassette.replay();
+//! ```
+
+use std::any::Any;
+use std::collections::HashMap;
+use std::fmt::{self, Debug};
+use std::sync::{Arc, Mutex};
+
+/// Whether a [`Cassette`] calls the real system or answers from its
+/// recording.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CassetteMode {
+    /// Call the real system and record each response
+    Record,
+    /// Answer from the recording without calling the real system
+    Replay,
+}
+
+/// A command's name and its input, written with `Debug`.
+type Key = (String, String);
+
+type Response = Arc<dyn Any + Send + Sync>;
+
+#[derive(Default)]
+struct Tape {
+    responses: HashMap<Key, Vec<Response>>,
+    /// How many responses to each key have been replayed since the last
+    /// rewind
+    positions: HashMap<Key, usize>,
+}
+
+/// Responses recorded from a real system, keyed by command and input.
+///
+/// Clones share the recording. Responses to the same command and input are
+/// replayed in the order they were recorded; once those run out, the last
+/// one repeats. Call [`rewind`](Cassette::rewind) at the start of each run
+/// so each run replays from the first response.
+#[derive(Clone)]
+pub struct Cassette {
+    mode: CassetteMode,
+    tape: Arc<Mutex<Tape>>,
+}
+
+impl Cassette {
+    /// An empty cassette that records.
+    pub fn new() -> Self {
+        Cassette {
+            mode: CassetteMode::Record,
+            tape: Arc::new(Mutex::new(Tape::default())),
+        }
+    }
+
+    /// A cassette sharing this recording that replays it from the start.
+    pub fn replay(&self) -> Cassette {
+        let replaying = Cassette {
+            mode: CassetteMode::Replay,
+            tape: self.tape.clone(),
+        };
+        replaying.rewind();
+        replaying
+    }
+
+    /// Whether this cassette records or replays.
+    pub fn mode(&self) -> CassetteMode {
+        self.mode
+    }
+
+    /// Replay from the first recorded response again.
+    pub fn rewind(&self) {
+        self.tape.lock().unwrap().positions.clear();
+    }
+
+    /// The number of responses recorded.
+    pub fn len(&self) -> usize {
+        self.tape
+            .lock()
+            .unwrap()
+            .responses
+            .values()
+            .map(Vec::len)
+            .sum()
+    }
+
+    /// Whether nothing has been recorded.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Wrap a command's effect so it's recorded or replayed by this
+    /// cassette.
+    ///
+    /// Replaying an input with no recorded response panics, since there is
+    /// nothing to answer with.
+    pub fn wrap<Input, M, F>(&self, name: &str, execute: F) -> impl Fn(Input) -> M + Send + Sync
+    where
+        Input: Debug,
+        M: Clone + Send + Sync + 'static,
+        F: Fn(Input) -> M + Send + Sync,
+    {
+        let cassette = self.clone();
+        let name = name.to_string();
+        move |input| {
+            let key = (name.clone(), format!("{input:?}"));
+            match cassette.mode {
+                CassetteMode::Record => {
+                    let response = execute(input);
+                    let mut tape = cassette.tape.lock().unwrap();
+                    tape.responses
+                        .entry(key)
+                        .or_default()
+                        .push(Arc::new(response.clone()));
+                    response
+                }
+                CassetteMode::Replay => cassette.next_response(&key),
+            }
+        }
+    }
+
+    fn next_response<M: Clone + 'static>(&self, key: &Key) -> M {
+        let mut tape = self.tape.lock().unwrap();
+        let Tape {
+            responses,
+            positions,
+        } = &mut *tape;
+        let recorded = responses.get(key).map(Vec::as_slice).unwrap_or_default();
+        let position = positions.entry(key.clone()).or_default();
+        let Some(response) = recorded.get(*position).or(recorded.last()) else {
+            panic!("no response recorded for {}({})", key.0, key.1);
+        };
+        *position += 1;
+        response
+            .downcast_ref::<M>()
+            .unwrap_or_else(|| {
+                panic!(
+                    "response recorded for {}({}) has another type",
+                    key.0, key.1
+                )
+            })
+            .clone()
+    }
+}
+
+impl Default for Cassette {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Debug for Cassette {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Cassette")
+            .field("mode", &self.mode)
+            .field("responses", &self.len())
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    #[test]
+    fn test_replays_recorded_responses_in_order() {
+        let calls = Arc::new(AtomicUsize::new(0));
+        let counter = calls.clone();
+        let cassette = Cassette::new();
+        let next = cassette.wrap("next", move |step: u32| {
+            step * 100 + counter.fetch_add(1, Ordering::SeqCst) as u32
+        });
+
+        assert_eq!([next(1), next(1), next(2)], [100, 101, 202]);
+        assert_eq!(cassette.len(), 3);
+
+        let replaying = cassette.replay();
+        let next = replaying.wrap("next", |_: u32| -> u32 { unreachable!() });
+        assert_eq!([next(2), next(1), next(1), next(1)], [202, 100, 101, 101]);
+
+        replaying.rewind();
+        assert_eq!(next(1), 100);
+        assert_eq!(calls.load(Ordering::SeqCst), 3);
+    }
+
+    #[test]
+    #[should_panic(expected = "no response recorded for next(7)")]
+    fn test_replaying_unrecorded_input_panics() {
+        let cassette = Cassette::new().replay();
+        let next = cassette.wrap("next", |step: u32| step);
+        next(7);
+    }
+}
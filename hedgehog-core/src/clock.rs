@@ -0,0 +1,276 @@
+//! Virtual time for properties about timeouts, backoff and expiry.
+//!
+//! Code that reads the system clock can't be tested deterministically: the
+//! same input passes or fails depending on how fast the machine is. Code
+//! written against [`Clock`] can be given a [`TestClock`] instead, which
+//! only moves when told to. With [`for_all_with_clock`] the steps the clock
+//! takes are generated alongside the input, so a failure shrinks to the
+//! fewest and shortest steps that still break the property.
+
+use crate::data::Range;
+use crate::gen::Gen;
+use crate::property::Property;
+use std::collections::VecDeque;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+/// A source of the current time, measured from when the clock started.
+pub trait Clock {
+    /// Time elapsed since the clock started.
+    fn now(&self) -> Duration;
+}
+
+/// The real clock, for use outside of tests.
+#[derive(Debug, Clone, Copy)]
+pub struct SystemClock {
+    start: Instant,
+}
+
+impl SystemClock {
+    /// A clock starting now.
+    pub fn new() -> Self {
+        SystemClock {
+            start: Instant::now(),
+        }
+    }
+}
+
+impl Default for SystemClock {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Clock for SystemClock {
+    fn now(&self) -> Duration {
+        self.start.elapsed()
+    }
+}
+
+/// The steps a [`TestClock`] takes each time it ticks.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct ClockSchedule {
+    steps: Vec<Duration>,
+}
+
+impl ClockSchedule {
+    /// A schedule taking the given steps in order.
+    pub fn new(steps: Vec<Duration>) -> Self {
+        ClockSchedule { steps }
+    }
+
+    /// The steps, in the order they are taken.
+    pub fn steps(&self) -> &[Duration] {
+        &self.steps
+    }
+
+    /// The time that has passed once every step was taken.
+    pub fn total(&self) -> Duration {
+        self.steps.iter().sum()
+    }
+}
+
+#[derive(Debug)]
+struct ClockState {
+    now: Duration,
+    pending: VecDeque<Duration>,
+}
+
+/// A clock that only moves when advanced or ticked.
+///
+/// Clones share the same time, so a clone can be handed to the code under
+/// test while the test keeps one to advance.
+///
+/// # Example
+/// ```rust
+/// use hedgehog_core::*;
+/// use std::time::Duration;
+///
+/// let clock = TestClock::new();
+/// clock.advance(Duration::from_secs(5));
+/// assert_eq!(clock.now(), Duration::from_secs(5));
+/// ```
+#[derive(Debug, Clone)]
+pub struct TestClock {
+    state: Arc<Mutex<ClockState>>,
+}
+
+impl TestClock {
+    /// A clock at zero with nothing scheduled.
+    pub fn new() -> Self {
+        Self::with_schedule(&ClockSchedule::default())
+    }
+
+    /// A clock at zero that takes the schedule's steps as it ticks.
+    pub fn with_schedule(schedule: &ClockSchedule) -> Self {
+        TestClock {
+            state: Arc::new(Mutex::new(ClockState {
+                now: Duration::ZERO,
+                pending: schedule.steps.iter().copied().collect(),
+            })),
+        }
+    }
+
+    /// Move the clock forward by `duration`.
+    pub fn advance(&self, duration: Duration) {
+        self.state().now += duration;
+    }
+
+    /// Take the next scheduled step, returning how far the clock moved, or
+    /// `None` without moving once the schedule is used up.
+    pub fn tick(&self) -> Option<Duration> {
+        let mut state = self.state();
+        let step = state.pending.pop_front()?;
+        state.now += step;
+        Some(step)
+    }
+
+    /// Take every remaining scheduled step.
+    pub fn run_to_end(&self) {
+        while self.tick().is_some() {}
+    }
+
+    /// Number of scheduled steps not yet taken.
+    pub fn remaining(&self) -> usize {
+        self.state().pending.len()
+    }
+
+    fn state(&self) -> std::sync::MutexGuard<'_, ClockState> {
+        self.state
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+}
+
+impl Default for TestClock {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Clock for TestClock {
+    fn now(&self) -> Duration {
+        self.state().now
+    }
+}
+
+impl Gen<ClockSchedule> {
+    /// Generate schedules with a number of steps drawn from `steps`, each
+    /// lasting from zero up to `max_step`.
+    ///
+    /// Short steps are more likely than long ones. Schedules shrink by
+    /// dropping steps and by shortening them towards zero.
+    pub fn clock_schedule(steps: Range<usize>, max_step: Duration) -> Self {
+        let max_nanos = u64::try_from(max_step.as_nanos()).unwrap_or(u64::MAX);
+        let step = Gen::<u64>::from_range(Range::linear(0, max_nanos)).map(Duration::from_nanos);
+        Gen::vec_with_range(steps, step).map(ClockSchedule::new)
+    }
+}
+
+/// Create a property checking `condition` against each input and a fresh
+/// [`TestClock`] driven by a generated schedule.
+///
+/// The condition ticks the clock wherever the code under test would wait.
+///
+/// # Example
+/// ```rust
+/// use hedgehog_core::*;
+/// use std::time::Duration;
+///
+/// fn expired(created: Duration, ttl: Duration, clock: &impl Clock) -> bool {
+///     clock.now() - created >= ttl
+/// }
+///
+/// // Entries never expire before their time to live has passed
+/// let prop = for_all_with_clock(
+///     Gen::<u64>::from_range(Range::new(1, 60)),
+///     Gen::<ClockSchedule>::clock_schedule(Range::new(0, 10), Duration::from_secs(20)),
+///     |&ttl_secs, clock| {
+///         let ttl = Duration::from_secs(ttl_secs);
+///         let created = clock.now();
+///         while clock.tick().is_some() {
+///             if expired(created, ttl, clock) != (clock.now() - created >= ttl) {
+///                 return false;
+///             }
+///         }
+///         true
+///     },
+/// );
+/// ```
+#[track_caller]
+pub fn for_all_with_clock<T, F>(
+    generator: Gen<T>,
+    schedule: Gen<ClockSchedule>,
+    condition: F,
+) -> Property<(T, ClockSchedule)>
+where
+    T: 'static + std::fmt::Debug + Clone,
+    F: Fn(&T, &TestClock) -> bool + 'static,
+{
+    Property::for_all(
+        Gen::<(T, ClockSchedule)>::tuple_of(generator, schedule),
+        move |(input, schedule)| condition(input, &TestClock::with_schedule(schedule)),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data::{Config, Seed, Size};
+    use crate::error::TestResult;
+
+    #[test]
+    fn test_clock_follows_its_schedule() {
+        let schedule = ClockSchedule::new(vec![Duration::from_millis(5), Duration::from_millis(7)]);
+        let clock = TestClock::with_schedule(&schedule);
+        let shared = clock.clone();
+
+        assert_eq!(clock.tick(), Some(Duration::from_millis(5)));
+        shared.advance(Duration::from_millis(1));
+        assert_eq!(clock.now(), Duration::from_millis(6));
+        clock.run_to_end();
+        assert_eq!(shared.now(), Duration::from_millis(13));
+        assert_eq!((clock.tick(), clock.remaining()), (None, 0));
+    }
+
+    #[test]
+    fn test_schedules_shrink_to_fewer_shorter_steps() {
+        let gen = Gen::<ClockSchedule>::clock_schedule(Range::new(0, 8), Duration::from_secs(1));
+        for seed in 0..20 {
+            let tree = gen.generate(Size::new(10), Seed::from_u64(seed));
+            assert!(tree.value.steps().len() <= 8);
+            assert!(tree
+                .value
+                .steps()
+                .iter()
+                .all(|&step| step <= Duration::from_secs(1)));
+            for shrink in tree.shrinks() {
+                assert!(shrink.steps().len() <= tree.value.steps().len());
+                assert!(shrink.total() <= tree.value.total());
+            }
+        }
+    }
+
+    #[test]
+    fn test_for_all_with_clock_finds_late_timeouts() {
+        // Up to three attempts, each waiting for the next step, don't always
+        // fit in a second
+        let prop = for_all_with_clock(
+            Gen::<u64>::from_range(Range::new(1, 3)),
+            Gen::<ClockSchedule>::clock_schedule(Range::new(3, 6), Duration::from_secs(1)),
+            |&attempts, clock| {
+                for _ in 0..attempts {
+                    clock.tick();
+                }
+                clock.now() < Duration::from_secs(1)
+            },
+        );
+
+        match prop.run(&Config::default()) {
+            TestResult::Fail { counterexample, .. } => {
+                assert!(counterexample.contains("ClockSchedule"));
+            }
+            other => panic!("Expected failure, got: {other:?}"),
+        }
+    }
+}
@@ -0,0 +1,268 @@
+//! Differential testing: checking two implementations agree.
+//!
+//! The most common real-world property is "the new code does what the old
+//! code did": an optimised routine against a naive one, a rewrite against
+//! the original, or a parser against a reference implementation.
+//! [`for_all_compare`] runs both on every input and fails with a diff of
+//! their outputs when they disagree. [`for_all_against_model`] does the
+//! same against a slow reference model, running the model once per
+//! distinct input however often shrinking revisits it.
+
+use crate::error::TestResult;
+use crate::gen::Gen;
+use crate::memo::Memo;
+use crate::property::Property;
+use std::fmt::Debug;
+use std::hash::Hash;
+
+/// Outputs whose compact form is at most this long are diffed on one line
+/// each.
+const COMPACT_WIDTH: usize = 80;
+
+/// Unchanged lines kept either side of a change in multi-line diffs.
+const CONTEXT: usize = 2;
+
+/// Create a property that passes when `impl_a` and `impl_b` return equal
+/// outputs for each input.
+///
+/// When they disagree the failure shows a diff of the two outputs, and the
+/// input shrinks like any other. A panic on one side is a disagreement;
+/// both sides panicking counts as agreeing, as when both reject an input.
+///
+/// # Example
+/// ```rust
+/// use hedgehog_core::*;
+///
+/// fn insertion_sort(xs: &Vec<i32>) -> Vec<i32> {
+///     let mut sorted: Vec<i32> = Vec::new();
+///     for &x in xs {
+///         let at = sorted.iter().position(|&y| y > x).unwrap_or(sorted.len());
+///         sorted.insert(at, x);
+///     }
+///     sorted
+/// }
+///
+/// let prop = for_all_compare(
+///     Gen::<Vec<i32>>::vec_of(Gen::int_range(-50, 50)),
+///     insertion_sort,
+///     |xs| {
+///         let mut sorted = xs.clone();
+///         sorted.sort();
+///         sorted
+///     },
+/// );
+/// assert!(matches!(prop.run(&Config::default()), TestResult::Pass { .. }));
+/// ```
+#[track_caller]
+pub fn for_all_compare<T, O, A, B>(generator: Gen<T>, impl_a: A, impl_b: B) -> Property<T>
+where
+    T: 'static + Debug + Clone,
+    O: PartialEq + Debug,
+    A: Fn(&T) -> O + 'static,
+    B: Fn(&T) -> O + 'static,
+{
+    Property::new(generator, move |input| {
+        let a = crate::panic::catch(|| impl_a(input));
+        let b = crate::panic::catch(|| impl_b(input));
+        compare(
+            input,
+            ("impl_a", &a),
+            ("impl_b", &b),
+            "Implementations Disagree",
+        )
+    })
+}
+
+/// Create a property that passes when `implementation` returns what the
+/// reference `model` does for each input.
+///
+/// Models are usually simple and slow, so each distinct input runs the
+/// model once and later checks of the same input, while shrinking or in
+/// later runs of the property, reuse its output. Failures show a diff
+/// between the model's output and the implementation's, as
+/// [`for_all_compare`] does.
+#[track_caller]
+pub fn for_all_against_model<T, O, M, I>(
+    generator: Gen<T>,
+    model: M,
+    implementation: I,
+) -> Property<T>
+where
+    T: 'static + Debug + Clone + Hash + Eq,
+    O: PartialEq + Debug + 'static,
+    M: Fn(&T) -> O + 'static,
+    I: Fn(&T) -> O + 'static,
+{
+    let expected = Memo::new(move |input: &T| crate::panic::catch(|| model(input)));
+    Property::new(generator, move |input| {
+        let actual = crate::panic::catch(|| implementation(input));
+        compare(
+            input,
+            ("model", &expected.get(input)),
+            ("implementation", &actual),
+            "Implementation Disagrees With Model",
+        )
+    })
+}
+
+/// Pass if both outputs agree, otherwise fail with `headline` and a diff.
+fn compare<T: Debug, O: PartialEq + Debug>(
+    input: &T,
+    (a_label, a): (&str, &Result<O, String>),
+    (b_label, b): (&str, &Result<O, String>),
+    headline: &str,
+) -> TestResult {
+    let detail = match (a, b) {
+        (Ok(a), Ok(b)) if a == b => None,
+        (Err(_), Err(_)) => None,
+        (Ok(a), Ok(b)) => Some(diff((a_label, a), (b_label, b))),
+        (Err(message), Ok(b)) => Some(format!("{a_label} panicked: {message}\n{b_label}: {b:?}")),
+        (Ok(a), Err(message)) => Some(format!("{a_label}: {a:?}\n{b_label} panicked: {message}")),
+    };
+    match detail {
+        None => TestResult::Pass {
+            tests_run: 1,
+            property_name: None,
+            module_path: None,
+        },
+        Some(detail) => disagreement(input, headline, &detail),
+    }
+}
+
+/// A failure of `input` with `headline` as its assertion type and `detail`
+/// on the lines under it.
+pub(crate) fn disagreement<T: Debug>(input: &T, headline: &str, detail: &str) -> TestResult {
+    TestResult::failure(format!("{input:?}"), format!("{headline}\n{detail}"))
+}
+
+/// A diff of two outputs: one line each when short, otherwise a line diff
+/// of their pretty-printed forms with distant unchanged lines elided.
+pub(crate) fn diff<O: Debug>((a_label, a): (&str, &O), (b_label, b): (&str, &O)) -> String {
+    let (compact_a, compact_b) = (format!("{a:?}"), format!("{b:?}"));
+    if compact_a.chars().count() <= COMPACT_WIDTH && compact_b.chars().count() <= COMPACT_WIDTH {
+        return format!("- {a_label}: {compact_a}\n+ {b_label}: {compact_b}");
+    }
+
+    let (pretty_a, pretty_b) = (format!("{a:#?}"), format!("{b:#?}"));
+    let lines = line_diff(
+        &pretty_a.lines().collect::<Vec<_>>(),
+        &pretty_b.lines().collect::<Vec<_>>(),
+    );
+    let near_change = |i: usize| {
+        let start = i.saturating_sub(CONTEXT);
+        let end = (i + CONTEXT + 1).min(lines.len());
+        lines[start..end].iter().any(|(tag, _)| *tag != ' ')
+    };
+
+    let mut out = vec![format!("--- {a_label}"), format!("+++ {b_label}")];
+    let mut elided = false;
+    for (i, (tag, line)) in lines.iter().enumerate() {
+        if near_change(i) {
+            out.push(format!("{tag} {line}"));
+            elided = false;
+        } else if !elided {
+            out.push("  ...".to_string());
+            elided = true;
+        }
+    }
+    out.join("\n")
+}
+
+/// The lines of `a` and `b` tagged `' '` when shared, `'-'` when only in
+/// `a` and `'+'` when only in `b`, following a longest common subsequence.
+fn line_diff<'a>(a: &[&'a str], b: &[&'a str]) -> Vec<(char, &'a str)> {
+    // common[i][j]: length of the longest common subsequence of a[i..], b[j..]
+    let mut common = vec![vec![0usize; b.len() + 1]; a.len() + 1];
+    for i in (0..a.len()).rev() {
+        for j in (0..b.len()).rev() {
+            common[i][j] = if a[i] == b[j] {
+                common[i + 1][j + 1] + 1
+            } else {
+                common[i + 1][j].max(common[i][j + 1])
+            };
+        }
+    }
+
+    let (mut i, mut j) = (0, 0);
+    let mut lines = Vec::new();
+    while i < a.len() || j < b.len() {
+        if i < a.len() && j < b.len() && a[i] == b[j] {
+            lines.push((' ', a[i]));
+            i += 1;
+            j += 1;
+        } else if j == b.len() || (i < a.len() && common[i + 1][j] >= common[i][j + 1]) {
+            lines.push(('-', a[i]));
+            i += 1;
+        } else {
+            lines.push(('+', b[j]));
+            j += 1;
+        }
+    }
+    lines
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data::Config;
+    use std::cell::Cell;
+    use std::rc::Rc;
+
+    #[test]
+    fn test_disagreements_fail_with_a_diff() {
+        // Off by one from ten upwards
+        let prop = for_all_compare(
+            Gen::int_range(0, 100),
+            |&n: &i32| n,
+            |&n: &i32| if n >= 10 { n - 1 } else { n },
+        );
+        match prop.run(&Config::default()) {
+            TestResult::Fail {
+                counterexample,
+                assertion_type,
+                ..
+            } => {
+                let n: i32 = counterexample.parse().unwrap();
+                assert!(n >= 10);
+                assert_eq!(
+                    assertion_type.unwrap(),
+                    format!(
+                        "Implementations Disagree\n- impl_a: {n}\n+ impl_b: {}",
+                        n - 1
+                    )
+                );
+            }
+            other => panic!("Expected failure, got: {other:?}"),
+        }
+    }
+
+    #[test]
+    fn test_model_runs_once_per_input() {
+        let model_calls = Rc::new(Cell::new(0));
+        let calls = Rc::clone(&model_calls);
+        let prop = for_all_against_model(
+            Gen::int_range(0, 3),
+            move |&n: &i32| {
+                calls.set(calls.get() + 1);
+                n * 2
+            },
+            |&n: &i32| n + n,
+        );
+        assert!(matches!(
+            prop.run(&Config::default().with_tests(100)),
+            TestResult::Pass { .. }
+        ));
+        assert!(model_calls.get() <= 4);
+    }
+
+    #[test]
+    fn test_long_outputs_diff_by_line() {
+        let a: Vec<usize> = (0..30).collect();
+        let mut b = a.clone();
+        b[15] = 99;
+        assert_eq!(
+            diff(("model", &a), ("implementation", &b)),
+            "--- model\n+++ implementation\n  ...\n      13,\n      14,\n-     15,\n+     99,\n      16,\n      17,\n  ..."
+        );
+    }
+}
@@ -1,6 +1,8 @@
 //! Core data types for Hedgehog property-based testing.
 
 use std::fmt;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
 
 /// Size parameter for controlling test data generation.
 ///
@@ -74,6 +76,35 @@ impl Seed {
         (Seed(new_state, gamma), Seed(output, new_gamma))
     }
 
+    /// Split a seed into `k` independent seeds.
+    ///
+    /// This is the chain of splits that generators use for the elements of
+    /// a collection: each split's first half is the next seed and the second
+    /// half is split again. Use it when composing sub-generators by hand,
+    /// rather than reusing one seed or deriving seeds by arithmetic, which
+    /// gives correlated streams. See [`crate::independence`] for checking
+    /// streams built another way.
+    ///
+    /// # Example
+    /// ```rust
+    /// use hedgehog_core::*;
+    ///
+    /// let seeds = Seed::from_u64(42).split_n(3);
+    /// let names = Gen::<String>::ascii_alpha().generate(Size::new(10), seeds[0]);
+    /// let ages = Gen::int_range(0, 120).generate(Size::new(10), seeds[1]);
+    /// let scores = Gen::int_range(0, 100).generate(Size::new(10), seeds[2]);
+    /// ```
+    pub fn split_n(self, k: usize) -> Vec<Self> {
+        let mut seeds = Vec::with_capacity(k);
+        let mut current = self;
+        for _ in 0..k {
+            let (seed, next) = current.split();
+            seeds.push(seed);
+            current = next;
+        }
+        seeds
+    }
+
     /// Generate the next random value and advance the seed.
     /// Uses SplitMix64 algorithm for high-quality randomness.
     pub fn next_u64(self) -> (u64, Self) {
@@ -109,6 +140,31 @@ impl fmt::Display for Seed {
     }
 }
 
+/// Reads a seed back from its printed form, `Seed(1, 2)`.
+impl std::str::FromStr for Seed {
+    type Err = crate::HedgehogError;
+
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        let invalid = |reason: &str| crate::HedgehogError::SeedDecodeError {
+            input: input.to_string(),
+            reason: reason.to_string(),
+        };
+        let fields = input
+            .trim()
+            .strip_prefix("Seed(")
+            .and_then(|rest| rest.strip_suffix(')'))
+            .ok_or_else(|| invalid("expected Seed(..., ...)"))?;
+        let mut parts = fields.split(',').map(|part| part.trim().parse::<u64>());
+        match (parts.next(), parts.next(), parts.next()) {
+            (Some(Ok(state)), Some(Ok(gamma)), None) => Ok(Seed(state, gamma)),
+            (Some(Err(error)), ..) | (_, Some(Err(error)), None) => {
+                Err(invalid(&error.to_string()))
+            }
+            _ => Err(invalid("expected two fields")),
+        }
+    }
+}
+
 /// Configuration for property testing.
 #[derive(Debug, Clone)]
 pub struct Config {
@@ -123,20 +179,169 @@ pub struct Config {
 
     /// Maximum number of discards before giving up.
     pub discard_limit: usize,
+
+    /// Number of times a failing case is re-run before it is reported.
+    pub retries: usize,
+
+    /// How the outcomes of a retried case decide whether it failed.
+    pub retry_policy: RetryPolicy,
+
+    /// Whether a passing run always reports statistics, including how long
+    /// was spent generating inputs and running the test function.
+    pub report_timings: bool,
+
+    /// Stop starting new test cases once a run has taken this long.
+    pub timeout: Option<Duration>,
+
+    /// Time allowed for shrinking a failure. With a deadline, structurally
+    /// smaller candidates are tried first.
+    pub shrink_deadline: Option<Duration>,
+
+    /// How many of each value's shrink candidates are tried while
+    /// shrinking, most aggressive first. `None` tries them all.
+    pub shrink_candidate_limit: Option<usize>,
+
+    /// Whether each call of the test function runs in a forked child
+    /// process, so crashes are reported as failures of the input.
+    pub process_isolation: bool,
+
+    /// Whether a failure is followed by re-running the property at fixed
+    /// sizes to find the smallest size at which it fails.
+    pub size_diagnostics: bool,
+
+    /// Whether large counterexamples are printed in full rather than with
+    /// long containers and lines cut short.
+    pub full_counterexamples: bool,
+
+    /// File the explored shrink tree of a failure is written to; see
+    /// [`crate::shrink_graph`]. A `{property}` in the path is replaced with
+    /// the property's name.
+    pub shrink_graph: Option<PathBuf>,
+
+    /// File a summary of each finished run is appended to; see
+    /// [`crate::history`]. A `{property}` in the path is replaced with the
+    /// property's name.
+    pub history: Option<PathBuf>,
+
+    /// Whether each case is generated and tested twice, failing if the two
+    /// differ; see [`crate::determinism`].
+    pub verify_determinism: bool,
+
+    /// Most bytes a generated value may take, for properties that estimate
+    /// them; see [`crate::size_of`].
+    pub max_value_bytes: Option<usize>,
+
+    /// Whether panics caught from the test function keep a backtrace, for
+    /// properties that catch them such as
+    /// [`Property::no_panic`](crate::Property::no_panic).
+    pub panic_backtraces: PanicBacktraces,
+}
+
+/// Whether panics caught from test functions keep a backtrace, shown under
+/// the failure they're reported as.
+///
+/// Capturing a backtrace is slow, and shrinking can panic hundreds of times,
+/// so by default this follows `RUST_BACKTRACE` as uncaught panics do.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum PanicBacktraces {
+    /// Capture when `RUST_BACKTRACE` or `RUST_LIB_BACKTRACE` asks for
+    /// backtraces.
+    #[default]
+    FromEnv,
+    /// Always capture.
+    Always,
+    /// Report only the panic message and location.
+    Never,
+}
+
+/// Decides whether a case that failed and was re-run counts as a failure.
+///
+/// Retries are meant for properties that exercise inherently flaky systems,
+/// such as real network services, where a rare transient failure is not the
+/// property's fault.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum RetryPolicy {
+    /// The case passes if any attempt passed.
+    #[default]
+    AnyPass,
+    /// The case passes if more attempts passed than failed.
+    Majority,
+    /// The case passes if at least this many attempts passed.
+    AtLeast(usize),
+}
+
+impl RetryPolicy {
+    /// Whether a case with the given attempt outcomes counts as passing.
+    pub fn accepts(&self, passes: usize, failures: usize) -> bool {
+        match self {
+            RetryPolicy::AnyPass => passes > 0,
+            RetryPolicy::Majority => passes > failures,
+            RetryPolicy::AtLeast(required) => passes >= *required,
+        }
+    }
 }
 
 impl Default for Config {
+    /// The built-in defaults scaled by `HEDGEHOG_TEST_MULTIPLIER` and
+    /// `HEDGEHOG_MAX_SHRINKS`, so CI can run every property more thoroughly.
+    /// Use [`Config::from_env`] for the active profile and the other
+    /// environment settings too.
+    ///
+    /// # Panics
+    ///
+    /// If either variable is malformed, rather than quietly run fewer tests.
     fn default() -> Self {
+        crate::profile::EnvOverrides::from_env()
+            .unwrap_or_else(|error| panic!("{error}"))
+            .apply(Config::builtin())
+    }
+}
+
+/// Written in output paths where the property's name goes.
+pub const PROPERTY_PLACEHOLDER: &str = "{property}";
+
+/// `path` with each [`PROPERTY_PLACEHOLDER`] replaced by `property`, with
+/// characters that don't belong in a file name replaced by `_`.
+pub(crate) fn property_path(path: &Path, property: &str) -> PathBuf {
+    let text = path.to_string_lossy();
+    if !text.contains(PROPERTY_PLACEHOLDER) {
+        return path.to_path_buf();
+    }
+    let name: String = property
+        .chars()
+        .map(|c| match c {
+            'a'..='z' | 'A'..='Z' | '0'..='9' | '-' | '_' | '.' => c,
+            _ => '_',
+        })
+        .collect();
+    PathBuf::from(text.replace(PROPERTY_PLACEHOLDER, &name))
+}
+
+impl Config {
+    /// The built-in defaults, ignoring any active profile.
+    pub fn builtin() -> Self {
         Config {
             test_limit: 100,
             shrink_limit: 1000,
             size_limit: 100,
             discard_limit: 100,
+            retries: 0,
+            retry_policy: RetryPolicy::AnyPass,
+            report_timings: false,
+            timeout: None,
+            shrink_deadline: None,
+            shrink_candidate_limit: None,
+            process_isolation: false,
+            size_diagnostics: false,
+            full_counterexamples: false,
+            shrink_graph: None,
+            history: None,
+            verify_determinism: false,
+            max_value_bytes: None,
+            panic_backtraces: PanicBacktraces::FromEnv,
         }
     }
-}
 
-impl Config {
     /// Create a new config with the given number of tests.
     pub fn with_tests(mut self, tests: usize) -> Self {
         self.test_limit = tests;
@@ -154,6 +359,116 @@ impl Config {
         self.size_limit = size;
         self
     }
+
+    /// Create a new config that re-runs failing cases up to `retries` times,
+    /// using `policy` to decide whether they failed.
+    pub fn with_retries(mut self, retries: usize, policy: RetryPolicy) -> Self {
+        self.retries = retries;
+        self.retry_policy = policy;
+        self
+    }
+
+    /// Create a new config that reports the time spent in each phase of a
+    /// passing run.
+    pub fn with_timings(mut self) -> Self {
+        self.report_timings = true;
+        self
+    }
+
+    /// Create a new config that stops starting new test cases after
+    /// `timeout`, passing with the cases run so far.
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
+
+    /// Create a new config that spends at most `deadline` shrinking a
+    /// failure, trying each value's smallest shrinks first.
+    pub fn with_shrink_deadline(mut self, deadline: Duration) -> Self {
+        self.shrink_deadline = Some(deadline);
+        self
+    }
+
+    /// Create a new config that tries at most `candidates` shrinks of each
+    /// value visited while shrinking, taking the most aggressive first.
+    ///
+    /// Generators list their shrinks from most to least aggressive (the empty
+    /// list before single removals, zero before one less), so a small cap
+    /// still finds big reductions while skipping the long tail of small ones.
+    pub fn with_shrink_candidates(mut self, candidates: usize) -> Self {
+        self.shrink_candidate_limit = Some(candidates);
+        self
+    }
+
+    /// Create a new config that runs every test case and shrink candidate
+    /// in a forked child process, reporting segfaults, aborts and stack
+    /// overflows as failures of the input instead of killing the test
+    /// binary.
+    ///
+    /// Forking is only available on Unix; elsewhere cases run in-process.
+    #[cfg(feature = "isolation")]
+    pub fn with_process_isolation(mut self) -> Self {
+        self.process_isolation = true;
+        self
+    }
+
+    /// Create a new config that, when a property fails, re-runs it at fixed
+    /// sizes from 0 upwards and prints the smallest size at which it fails.
+    /// See [`crate::Property::diagnose_sizes`].
+    pub fn with_size_diagnostics(mut self) -> Self {
+        self.size_diagnostics = true;
+        self
+    }
+
+    /// Create a new config that prints large counterexamples in full.
+    ///
+    /// By default a counterexample too long for one line is printed with
+    /// one field or element per line, showing the first few elements of
+    /// each collection and the start of each long line.
+    pub fn with_full_counterexamples(mut self) -> Self {
+        self.full_counterexamples = true;
+        self
+    }
+
+    /// Create a new config that writes the shrink tree explored for a
+    /// failure to `path`, as JSON if it ends in `.json` and Graphviz DOT
+    /// otherwise. A `{property}` in the path gives each property a file of
+    /// its own. See [`crate::shrink_graph`].
+    pub fn with_shrink_graph(mut self, path: impl Into<PathBuf>) -> Self {
+        self.shrink_graph = Some(path.into());
+        self
+    }
+
+    /// Create a new config that appends a summary of each finished run to
+    /// `path`, for `cargo hedgehog report`. A `{property}` in the path gives
+    /// each property a file of its own. See [`crate::history`].
+    pub fn with_history(mut self, path: impl Into<PathBuf>) -> Self {
+        self.history = Some(path.into());
+        self
+    }
+
+    /// Generate and test each case twice, failing if the inputs or verdicts
+    /// differ. See [`crate::determinism`].
+    pub fn with_determinism_check(mut self) -> Self {
+        self.verify_determinism = true;
+        self
+    }
+
+    /// Stop the run when a generated value is estimated to take more than
+    /// `bytes`. Only properties built with
+    /// [`Property::with_byte_estimate`](crate::Property::with_byte_estimate)
+    /// are measured; see [`crate::size_of`].
+    pub fn with_max_value_bytes(mut self, bytes: usize) -> Self {
+        self.max_value_bytes = Some(bytes);
+        self
+    }
+
+    /// Create a new config that captures backtraces of caught panics as
+    /// `backtraces` says.
+    pub fn with_panic_backtraces(mut self, backtraces: PanicBacktraces) -> Self {
+        self.panic_backtraces = backtraces;
+        self
+    }
 }
 
 /// SplitMix64 mixing function for high-quality output.
@@ -182,6 +497,10 @@ pub struct Range<T> {
     pub origin: Option<T>,
     /// Distribution shape for generating values within the range.
     pub distribution: Distribution,
+    /// Share of values replaced by the range's edge cases: for integers the
+    /// bounds and their neighbours, zero and powers of two, for lengths the
+    /// bounds.
+    pub edge_bias: f64,
 }
 
 /// Distribution shapes for value generation within ranges.
@@ -208,6 +527,7 @@ where
             max,
             origin: None,
             distribution: Distribution::Uniform,
+            edge_bias: 0.0,
         }
     }
 
@@ -218,6 +538,7 @@ where
             max,
             origin: None,
             distribution: Distribution::Linear,
+            edge_bias: 0.0,
         }
     }
 
@@ -228,6 +549,7 @@ where
             max,
             origin: None,
             distribution: Distribution::Exponential,
+            edge_bias: 0.0,
         }
     }
 
@@ -238,6 +560,7 @@ where
             max: value,
             origin: Some(value),
             distribution: Distribution::Constant,
+            edge_bias: 0.0,
         }
     }
 
@@ -247,6 +570,25 @@ where
         self
     }
 
+    /// Replace a share of the generated values with the range's edge cases.
+    ///
+    /// Uniform ranges rarely produce the bounds, their neighbours, zero or
+    /// powers of two; with probability `probability` one of those is
+    /// generated instead, shrinking towards simpler edge cases. Length
+    /// ranges give their bounds, so collections are as short or as long as
+    /// allowed.
+    ///
+    /// # Example
+    /// ```rust
+    /// use hedgehog_core::*;
+    ///
+    /// let gen = Gen::<i32>::from_range(Range::new(-1000, 1000).with_edge_bias(0.2));
+    /// ```
+    pub fn with_edge_bias(mut self, probability: f64) -> Self {
+        self.edge_bias = probability.clamp(0.0, 1.0);
+        self
+    }
+
     /// Check if a value is within this range.
     pub fn contains(&self, value: &T) -> bool {
         value >= &self.min && value <= &self.max
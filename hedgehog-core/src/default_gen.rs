@@ -0,0 +1,201 @@
+//! Default generators for types.
+//!
+//! Most properties over standard types use the same generators every time:
+//! any `i32`, a printable `String`, a `Vec` of whatever the element type
+//! defaults to. [`DefaultGen`] records that choice once per type, so
+//! [`for_all_default`](crate::property::for_all_default) and the `Generate`
+//! derive can pick generators from types alone. Passing a generator
+//! explicitly, to [`for_all`](crate::property::for_all) or with a
+//! `#[generate(...)]` field attribute, always overrides the default.
+
+use crate::data::Range;
+use crate::gen::Gen;
+
+/// Types with a generator to use when none is given.
+///
+/// # Example
+/// ```rust
+/// use hedgehog_core::*;
+///
+/// #[derive(Debug, Clone)]
+/// struct Port(u16);
+///
+/// impl DefaultGen for Port {
+///     fn default_gen() -> Gen<Self> {
+///         Gen::u16_range(1024, u16::MAX).map(Port)
+///     }
+/// }
+///
+/// let gen = <Vec<Port>>::default_gen();
+/// ```
+pub trait DefaultGen: Sized {
+    /// The generator for this type.
+    fn default_gen() -> Gen<Self>;
+}
+
+/// Share of integers replaced by boundary values such as zero, the type's
+/// bounds and powers of two.
+const EDGE_PROBABILITY: f64 = 0.2;
+
+macro_rules! impl_default_gen_int {
+    ($($type:ty),*) => {
+        $(
+            impl DefaultGen for $type {
+                /// Any value of the type, with boundary values mixed in.
+                fn default_gen() -> Gen<Self> {
+                    Gen::<$type>::from_range(
+                        Range::new(<$type>::MIN, <$type>::MAX).with_edge_bias(EDGE_PROBABILITY),
+                    )
+                }
+            }
+        )*
+    };
+}
+
+impl_default_gen_int!(i8, i16, i32, i64, isize, u8, u16, u32, u64, usize);
+
+impl DefaultGen for f64 {
+    /// Values in `-1000.0..=1000.0`.
+    fn default_gen() -> Gen<Self> {
+        Gen::f64_range(-1000.0, 1000.0)
+    }
+}
+
+impl DefaultGen for f32 {
+    /// Values in `-1000.0..=1000.0`.
+    fn default_gen() -> Gen<Self> {
+        f64::default_gen().map(|x| x as f32)
+    }
+}
+
+impl DefaultGen for bool {
+    fn default_gen() -> Gen<Self> {
+        Gen::bool()
+    }
+}
+
+impl DefaultGen for () {
+    fn default_gen() -> Gen<Self> {
+        Gen::unit_value()
+    }
+}
+
+impl DefaultGen for char {
+    /// Printable ASCII characters.
+    fn default_gen() -> Gen<Self> {
+        Gen::<char>::ascii_printable()
+    }
+}
+
+impl DefaultGen for String {
+    /// Printable ASCII strings.
+    fn default_gen() -> Gen<Self> {
+        Gen::<String>::ascii_printable()
+    }
+}
+
+macro_rules! impl_default_gen_string_like {
+    ($($type:ty),*) => {
+        $(
+            impl DefaultGen for $type {
+                /// Printable ASCII strings.
+                fn default_gen() -> Gen<Self> {
+                    Gen::<$type>::ascii_printable()
+                }
+            }
+        )*
+    };
+}
+
+impl_default_gen_string_like!(
+    Box<str>,
+    std::rc::Rc<str>,
+    std::sync::Arc<str>,
+    std::borrow::Cow<'static, str>
+);
+
+impl<T: DefaultGen + Clone + 'static> DefaultGen for Vec<T> {
+    fn default_gen() -> Gen<Self> {
+        Gen::vec_of(T::default_gen())
+    }
+}
+
+impl<T: DefaultGen + Clone + 'static> DefaultGen for Option<T> {
+    fn default_gen() -> Gen<Self> {
+        Gen::option_of(T::default_gen())
+    }
+}
+
+impl<T, E> DefaultGen for Result<T, E>
+where
+    T: DefaultGen + Clone + 'static,
+    E: DefaultGen + Clone + 'static,
+{
+    fn default_gen() -> Gen<Self> {
+        Gen::result_of(T::default_gen(), E::default_gen())
+    }
+}
+
+impl<T: DefaultGen + 'static> DefaultGen for Box<T> {
+    fn default_gen() -> Gen<Self> {
+        T::default_gen().map(Box::new)
+    }
+}
+
+macro_rules! impl_default_gen_tuple {
+    ($($name:ident),*) => {
+        impl<$($name),*> DefaultGen for ($($name,)*)
+        where
+            $($name: DefaultGen + Clone + 'static,)*
+        {
+            fn default_gen() -> Gen<Self> {
+                Gen::<Self>::tuple_of($($name::default_gen()),*)
+            }
+        }
+    };
+}
+
+impl_default_gen_tuple!(A, B);
+impl_default_gen_tuple!(A, B, C);
+impl_default_gen_tuple!(A, B, C, D);
+impl_default_gen_tuple!(A, B, C, D, E);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data::{Seed, Size};
+
+    fn sample<T: DefaultGen>(count: u64) -> Vec<T> {
+        (0..count)
+            .map(|n| {
+                T::default_gen()
+                    .generate(Size::new(30), Seed::from_u64(n))
+                    .value
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_integers_cover_the_whole_type() {
+        let values = sample::<i8>(500);
+        assert!(values.contains(&i8::MIN));
+        assert!(values.contains(&i8::MAX));
+        assert!(values.contains(&0));
+        assert!(values.iter().any(|&x| x < -64));
+        assert!(values.iter().any(|&x| x > 64));
+    }
+
+    #[test]
+    fn test_containers_use_element_defaults() {
+        let values = sample::<Vec<(bool, Option<char>)>>(100);
+        assert!(values.iter().any(|xs| !xs.is_empty()));
+        assert!(values
+            .iter()
+            .flatten()
+            .filter_map(|(_, c)| *c)
+            .all(|c| c.is_ascii() && !c.is_ascii_control()));
+
+        let strings = sample::<String>(100);
+        assert!(strings.iter().all(|s| s.chars().all(|c| !c.is_control())));
+    }
+}
@@ -0,0 +1,93 @@
+//! Checking that properties are deterministic.
+//!
+//! A seed only reproduces a failure if the same seed always generates the
+//! same input and the test always reaches the same verdict on it. Iterating
+//! a `HashMap`, reading the clock or keeping global state breaks that
+//! quietly: the failure is reported, but replaying its seed passes.
+//!
+//! A config built with [`Config::with_determinism_check`](crate::Config::with_determinism_check),
+//! or [`Config::from_env`](crate::Config::from_env) while
+//! `HEDGEHOG_VERIFY_DETERMINISM` is set, runs
+//! each case twice: it generates the input again from the same size and
+//! seed, and calls the test on it a second time. If the two inputs or the
+//! two verdicts differ, the property fails with both of them.
+//! `cargo hedgehog test --verify-determinism` runs a project's tests this
+//! way.
+
+use crate::error::TestResult;
+
+/// Environment variable that makes every property check its determinism.
+pub const DETERMINISM_ENV_VAR: &str = "HEDGEHOG_VERIFY_DETERMINISM";
+
+/// The part of a test's result that must be the same on every call: whether
+/// it passed, failed or was discarded, and the failed assertion.
+pub(crate) fn verdict(result: &TestResult) -> String {
+    match result {
+        TestResult::Pass { .. } | TestResult::PassWithStatistics { .. } => "pass".to_string(),
+        TestResult::Fail {
+            assertion_type: Some(assertion),
+            ..
+        } => format!("fail: {assertion}"),
+        TestResult::Fail { .. } => "fail".to_string(),
+        TestResult::Discard { .. } => "discard".to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data::Config;
+    use crate::gen::Gen;
+    use crate::property::for_all;
+    use std::cell::Cell;
+
+    fn assertion(result: TestResult) -> String {
+        match result {
+            TestResult::Fail {
+                assertion_type: Some(assertion),
+                ..
+            } => assertion,
+            other => panic!("Expected failure, got: {other:?}"),
+        }
+    }
+
+    #[test]
+    fn test_deterministic_properties_pass() {
+        let config = Config::default().with_determinism_check();
+        let prop = for_all(Gen::<Vec<i32>>::vec_of(Gen::int_range(0, 9)), |xs| {
+            xs.len() < 1000
+        });
+        assert!(matches!(prop.run(&config), TestResult::Pass { .. }));
+    }
+
+    #[test]
+    fn test_generators_with_state_are_caught() {
+        let calls = Cell::new(0);
+        let gen = Gen::new(move |_, _| {
+            calls.set(calls.get() + 1);
+            crate::tree::Tree::singleton(calls.get())
+        });
+        let prop = for_all(gen, |_: &i32| true);
+
+        assert!(matches!(
+            prop.run(&Config::default()),
+            TestResult::Pass { .. }
+        ));
+        let assertion = assertion(prop.run(&Config::default().with_determinism_check()));
+        assert!(assertion.starts_with("Nondeterministic Generator\n- first generation: "));
+    }
+
+    #[test]
+    fn test_tests_with_state_are_caught() {
+        let calls = Cell::new(0);
+        let prop = for_all(Gen::int_range(0, 9), move |_: &i32| {
+            calls.set(calls.get() + 1);
+            calls.get() % 2 == 1
+        });
+        let assertion = assertion(prop.run(&Config::default().with_determinism_check()));
+        assert_eq!(
+            assertion,
+            "Nondeterministic Test\n- first call: pass\n+ second call: fail: Boolean Condition"
+        );
+    }
+}
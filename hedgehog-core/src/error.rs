@@ -1,6 +1,7 @@
 //! Error types for Hedgehog property-based testing.
 
 use std::fmt;
+use std::time::Duration;
 use thiserror::Error;
 
 /// Main error type for Hedgehog property testing.
@@ -29,6 +30,74 @@ pub enum HedgehogError {
     /// Invalid generator construction.
     #[error("Invalid generator: {message}")]
     InvalidGenerator { message: String },
+
+    /// Generating a single value took longer than the whole run may.
+    #[error(
+        "Generating one value at size {size} took {elapsed:?}, over the run's timeout of {limit:?}"
+    )]
+    GenerationTimeout {
+        size: usize,
+        elapsed: Duration,
+        limit: Duration,
+    },
+
+    /// Shrinking stopped at its step budget, so the counterexample may not be
+    /// minimal.
+    #[error("Shrinking stopped after {limit} steps at {counterexample}")]
+    ShrinkBudgetExceeded {
+        limit: usize,
+        counterexample: String,
+    },
+
+    /// A filter rejected every value it was offered.
+    #[error("Filter rejected {limit} generated values in a row")]
+    FilterTooRestrictive { limit: usize },
+
+    /// A seed couldn't be read back from its printed form.
+    #[error("Couldn't decode seed {input:?}: {reason}")]
+    SeedDecodeError { input: String, reason: String },
+}
+
+impl HedgehogError {
+    /// A suggested fix for the error, if there's a usual one.
+    ///
+    /// # Example
+    /// ```rust
+    /// use hedgehog_core::*;
+    ///
+    /// let error = "Seed(1)".parse::<Seed>().unwrap_err();
+    /// assert!(error.hint().unwrap().contains("Seed(1, 2)"));
+    /// ```
+    pub fn hint(&self) -> Option<&'static str> {
+        match self {
+            HedgehogError::PropertyFailed { .. } | HedgehogError::GeneratorFailed { .. } => None,
+            HedgehogError::TooManyDiscards { .. } => Some(
+                "generate valid inputs directly instead of discarding invalid ones, \
+                 or raise Config::discard_limit",
+            ),
+            HedgehogError::InvalidConfig { .. } => Some(
+                "check the config, or the hedgehog.toml and environment variables it came from",
+            ),
+            HedgehogError::InvalidGenerator { .. } => {
+                Some("check the arguments the generator was built with")
+            }
+            HedgehogError::GenerationTimeout { .. } => Some(
+                "bound recursive or nested generators, such as with Gen::recursive_weighted, \
+                 or lower the size limit with Config::with_size_limit",
+            ),
+            HedgehogError::ShrinkBudgetExceeded { .. } => Some(
+                "raise the budget with Config::with_shrinks, or cap the candidates tried per \
+                 step with Config::with_shrink_candidates",
+            ),
+            HedgehogError::FilterTooRestrictive { .. } => Some(
+                "generate the accepted values directly, such as from a narrower range or with \
+                 Gen::ordered_pair, instead of filtering for them",
+            ),
+            HedgehogError::SeedDecodeError { .. } => {
+                Some("seeds are written as two unsigned integers, as in Seed(1, 2)")
+            }
+        }
+    }
 }
 
 /// Result type for Hedgehog operations.
@@ -45,6 +114,43 @@ pub struct ShrinkStep {
     pub variable_name: Option<String>,
 }
 
+/// A panic caught from a test function.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PanicReport {
+    /// The message given to `panic!`.
+    pub message: String,
+    /// Where the panic was raised, as `file:line:column`.
+    pub location: Option<String>,
+    /// The backtrace at the panic, if one was captured; see
+    /// [`crate::PanicBacktraces`].
+    pub backtrace: Option<String>,
+}
+
+/// What the runner learned about a failure besides its counterexample.
+///
+/// Test functions leave these empty, with `FailureDetails::default()`, and
+/// the runner fills in what it knows.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct FailureDetails {
+    /// Where the property was created, as `file:line:column`.
+    pub location: Option<String>,
+    /// The size the failing input was generated at, if it was generated.
+    pub size: Option<usize>,
+    /// The description of the generator that produced the input, if it
+    /// has one; see [`crate::Gen::named`].
+    pub generator: Option<String>,
+    /// The branch a choice generator such as [`crate::Gen::frequency`]
+    /// took to produce the input, if one did; see [`crate::Tree::origin`].
+    pub origin: Option<String>,
+    /// The panic the test function raised on the input, for properties
+    /// that catch panics such as [`crate::Property::no_panic`].
+    pub panic: Option<PanicReport>,
+    /// The shrink budget, if shrinking stopped at it with candidates left
+    /// untried, so the counterexample may not be minimal; see
+    /// [`TestResult::shrink_error`].
+    pub shrink_budget: Option<usize>,
+}
+
 /// Outcome of a property test.
 #[derive(Debug, Clone, PartialEq)]
 pub enum TestResult {
@@ -73,6 +179,9 @@ pub enum TestResult {
         assertion_type: Option<String>,
         /// The shrinking progression showing how we reached the minimal counterexample.
         shrink_steps: Vec<ShrinkStep>,
+        /// What else is known about the failure, such as where the property
+        /// was created.
+        details: FailureDetails,
     },
 
     /// Too many test cases were discarded.
@@ -83,6 +192,58 @@ pub enum TestResult {
     },
 }
 
+impl TestResult {
+    /// A failure of the test function on `counterexample`, for test
+    /// functions to return. The runner fills in the counts, names and
+    /// details.
+    ///
+    /// # Example
+    /// ```rust
+    /// use hedgehog_core::*;
+    ///
+    /// let prop = Property::new(Gen::int_range(0, 100), |&n| {
+    ///     if n < 50 {
+    ///         TestResult::Pass { tests_run: 1, property_name: None, module_path: None }
+    ///     } else {
+    ///         TestResult::failure(format!("{n:?}"), "Too Large")
+    ///     }
+    /// });
+    /// assert!(matches!(prop.run(&Config::default()), TestResult::Fail { .. }));
+    /// ```
+    pub fn failure(counterexample: impl Into<String>, assertion_type: impl Into<String>) -> Self {
+        TestResult::Fail {
+            counterexample: counterexample.into(),
+            tests_run: 0,
+            shrinks_performed: 0,
+            property_name: None,
+            module_path: None,
+            assertion_type: Some(assertion_type.into()),
+            shrink_steps: Vec::new(),
+            details: FailureDetails::default(),
+        }
+    }
+
+    /// The [`HedgehogError::ShrinkBudgetExceeded`] error of a failure whose
+    /// shrinking stopped at its budget, if it did.
+    pub fn shrink_error(&self) -> Option<HedgehogError> {
+        match self {
+            TestResult::Fail {
+                counterexample,
+                details:
+                    FailureDetails {
+                        shrink_budget: Some(limit),
+                        ..
+                    },
+                ..
+            } => Some(HedgehogError::ShrinkBudgetExceeded {
+                limit: *limit,
+                counterexample: counterexample.clone(),
+            }),
+            _ => None,
+        }
+    }
+}
+
 impl fmt::Display for TestResult {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
@@ -111,7 +272,33 @@ impl fmt::Display for TestResult {
                 }
 
                 let prop_name = property_name.as_deref().unwrap_or("property");
-                writeln!(f, "  ✓ {prop_name} passed {tests_run} tests.")?;
+                if statistics.discards > 0 {
+                    writeln!(
+                        f,
+                        "  ✓ {prop_name} passed {tests_run} tests; {} discarded.",
+                        statistics.discards
+                    )?;
+                } else {
+                    writeln!(f, "  ✓ {prop_name} passed {tests_run} tests.")?;
+                }
+
+                // Show how far sizes ramped up
+                if statistics.max_size > 0 {
+                    writeln!(f, "  Sizes ramped from 0 to {}.", statistics.max_size)?;
+                }
+                if let Some(generator) = &statistics.generator {
+                    writeln!(f, "  Input generated by: {generator}")?;
+                }
+
+                // Warn when the generator kept producing the same values
+                if statistics.mostly_duplicates() {
+                    let percentage =
+                        (statistics.duplicates as f64 / statistics.total_tests as f64) * 100.0;
+                    writeln!(
+                        f,
+                        "  ⚠ {percentage:.0}% of cases were duplicates; fewer tests or a wider range would cover the same ground."
+                    )?;
+                }
 
                 // Show classification distribution
                 if !statistics.classifications.is_empty() {
@@ -168,6 +355,37 @@ impl fmt::Display for TestResult {
                     }
                 }
 
+                // Show cases that only passed after being retried
+                if !statistics.retried_cases.is_empty() {
+                    writeln!(f)?;
+                    writeln!(f, "  Retried cases:")?;
+                    for case in &statistics.retried_cases {
+                        writeln!(
+                            f,
+                            "    {}: passed {} of {} attempts",
+                            case.input,
+                            case.passes,
+                            case.passes + case.failures
+                        )?;
+                    }
+                }
+
+                // Show where the time went
+                let timings = &statistics.timings;
+                if !timings.total().is_zero() {
+                    writeln!(f)?;
+                    writeln!(f, "  Time spent:")?;
+                    for (phase, duration) in [
+                        ("generating", timings.generation),
+                        ("testing", timings.execution),
+                        ("shrinking", timings.shrinking),
+                    ] {
+                        let percentage =
+                            duration.as_secs_f64() / timings.total().as_secs_f64() * 100.0;
+                        writeln!(f, "    {percentage:>3.0}% {phase} ({duration:.2?})")?;
+                    }
+                }
+
                 Ok(())
             }
             TestResult::Fail {
@@ -178,6 +396,15 @@ impl fmt::Display for TestResult {
                 module_path,
                 assertion_type,
                 shrink_steps,
+                details:
+                    FailureDetails {
+                        location,
+                        size,
+                        generator,
+                        origin,
+                        panic,
+                        shrink_budget: _,
+                    },
             } => {
                 // Show module header if available
                 if let Some(module) = module_path {
@@ -189,40 +416,87 @@ impl fmt::Display for TestResult {
                     f,
                     "  ✗ {prop_name} failed after {tests_run} tests and {shrinks_performed} shrinks."
                 )?;
+                if let Some(location) = location {
+                    writeln!(f, "    at {location}")?;
+                }
+                if let Some(size) = size {
+                    writeln!(f, "    generated at size {size}")?;
+                }
+                if let Some(generator) = generator {
+                    writeln!(f, "    input generated by: {generator}")?;
+                }
+                if let Some(origin) = origin {
+                    writeln!(f, "    chosen branch: {origin}")?;
+                }
+                if let Some(location) = panic.as_ref().and_then(|panic| panic.location.as_ref()) {
+                    writeln!(f, "    panicked at {location}")?;
+                }
+                if let Some(error) = self.shrink_error() {
+                    writeln!(f, "    ⚠ {error}")?;
+                    if let Some(hint) = error.hint() {
+                        writeln!(f, "    hint: {hint}")?;
+                    }
+                }
 
                 if !shrink_steps.is_empty() {
                     writeln!(f)?;
                     writeln!(f, "    Shrinking progression:")?;
                     for step in shrink_steps {
+                        let shown = indent_lines(&step.counterexample, "      │   ");
                         if step.step == 0 {
                             if let Some(ref var_name) = step.variable_name {
-                                writeln!(
-                                    f,
-                                    "      │ forAll 0 = {} -- {}",
-                                    step.counterexample, var_name
-                                )?;
+                                writeln!(f, "      │ forAll 0 = {} -- {}", shown, var_name)?;
                             } else {
-                                writeln!(f, "      │ Original: {}", step.counterexample)?;
+                                writeln!(f, "      │ Original: {shown}")?;
                             }
                         } else if let Some(ref var_name) = step.variable_name {
                             writeln!(
                                 f,
                                 "      │ forAll {} = {} -- {}",
-                                step.step, step.counterexample, var_name
+                                step.step, shown, var_name
                             )?;
                         } else {
-                            writeln!(f, "      │ Step {}: {}", step.step, step.counterexample)?;
+                            writeln!(f, "      │ Step {}: {shown}", step.step)?;
                         }
                     }
                     writeln!(f)?;
                 }
 
-                // Show assertion type if available
+                // Show assertion type if available, with any detail lines under it
                 if let Some(assertion) = assertion_type {
-                    writeln!(f, "    === {assertion} ===")?;
+                    let mut lines = assertion.lines();
+                    writeln!(f, "    === {} ===", lines.next().unwrap_or_default())?;
+                    for line in lines {
+                        writeln!(f, "    {line}")?;
+                    }
                 }
 
-                write!(f, "    Minimal counterexample: {counterexample}")
+                if counterexample.contains('\n') {
+                    write!(
+                        f,
+                        "    Minimal counterexample:\n      {}",
+                        indent_lines(counterexample, "      ")
+                    )?;
+                } else {
+                    write!(f, "    Minimal counterexample: {counterexample}")?;
+                }
+
+                // Show the backtrace with the runtime's own frames folded away
+                if let Some(backtrace) = panic.as_ref().and_then(|panic| panic.backtrace.as_ref()) {
+                    let (frames, hidden) = own_frames(backtrace);
+                    write!(
+                        f,
+                        "\n\n    ▸ Backtrace ({} frames, {hidden} runtime frames hidden)",
+                        frames.len()
+                    )?;
+                    for frame in frames {
+                        for (i, line) in frame.lines().enumerate() {
+                            let indent = if i == 0 { "      " } else { "            " };
+                            write!(f, "\n{indent}{}", line.trim())?;
+                        }
+                    }
+                }
+                Ok(())
             }
             TestResult::Discard {
                 limit,
@@ -241,6 +515,53 @@ impl fmt::Display for TestResult {
     }
 }
 
+/// The frames of a captured backtrace that aren't the standard library's,
+/// the test harness's or Hedgehog's own, with how many were left out.
+///
+/// Frames without a source location, such as those in the C runtime, are
+/// left out too.
+fn own_frames(backtrace: &str) -> (Vec<String>, usize) {
+    const RUNTIME: [&str; 8] = [
+        "std::",
+        "core::",
+        "alloc::",
+        "test::",
+        "hedgehog_core::",
+        "hedgehog::",
+        "rust_begin_unwind",
+        "__rust",
+    ];
+    let mut frames: Vec<String> = Vec::new();
+    for line in backtrace.lines() {
+        match frames.last_mut() {
+            Some(frame) if line.trim_start().starts_with("at ") => {
+                frame.push('\n');
+                frame.push_str(line);
+            }
+            _ => frames.push(line.to_string()),
+        }
+    }
+    let total = frames.len();
+    let own: Vec<String> = frames
+        .into_iter()
+        .filter(|frame| {
+            let symbol = frame
+                .lines()
+                .next()
+                .and_then(|line| line.split_once(": "))
+                .map_or("", |(_, symbol)| symbol.trim_start_matches('<'));
+            frame.contains("\n") && !RUNTIME.iter().any(|runtime| symbol.starts_with(runtime))
+        })
+        .collect();
+    let hidden = total - own.len();
+    (own, hidden)
+}
+
+/// Continue a multi-line counterexample under `prefix`.
+fn indent_lines(text: &str, prefix: &str) -> String {
+    text.replace('\n', &format!("\n{prefix}"))
+}
+
 impl From<HedgehogError> for TestResult {
     fn from(error: HedgehogError) -> Self {
         match error {
@@ -256,6 +577,7 @@ impl From<HedgehogError> for TestResult {
                 module_path: None,
                 assertion_type: None,
                 shrink_steps: Vec::new(),
+                details: FailureDetails::default(),
             },
             HedgehogError::TooManyDiscards { limit } => TestResult::Discard {
                 limit,
@@ -270,6 +592,7 @@ impl From<HedgehogError> for TestResult {
                 module_path: None,
                 assertion_type: None,
                 shrink_steps: Vec::new(),
+                details: FailureDetails::default(),
             },
         }
     }
@@ -0,0 +1,529 @@
+//! A deterministic executor for async properties.
+//!
+//! Async code that sleeps, retries with backoff or debounces events depends
+//! on real time and on the order its tasks happen to run in, so the same
+//! input can pass on one run and fail on the next. [`VirtualRuntime`] runs
+//! futures on one thread against a [`TestClock`]: when every task is
+//! waiting, the clock jumps straight to the next timer, so a backoff of
+//! minutes takes no time at all. Which ready task runs next follows a
+//! [`TaskSchedule`], and [`for_all_async`] generates those schedules
+//! alongside the input, so a failure shrinks to a short schedule that
+//! still breaks the property.
+
+use crate::clock::{Clock, TestClock};
+use crate::data::Range;
+use crate::gen::Gen;
+use crate::property::Property;
+use std::cell::{Cell, RefCell};
+use std::collections::VecDeque;
+use std::future::Future;
+use std::pin::{pin, Pin};
+use std::rc::Rc;
+use std::sync::{Arc, Mutex};
+use std::task::{Context, Poll, Wake, Waker};
+use std::time::Duration;
+
+/// The choices a [`VirtualRuntime`] makes when several tasks are ready.
+///
+/// Each choice picks a ready task by its position among them, wrapping
+/// around. Once the choices run out, ready tasks run in the order they
+/// were woken.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct TaskSchedule {
+    choices: Vec<usize>,
+}
+
+impl TaskSchedule {
+    /// A schedule making the given choices in order.
+    pub fn new(choices: Vec<usize>) -> Self {
+        TaskSchedule { choices }
+    }
+
+    /// The choices, in order.
+    pub fn choices(&self) -> &[usize] {
+        &self.choices
+    }
+}
+
+impl Gen<TaskSchedule> {
+    /// Generate schedules with a number of choices drawn from `choices`.
+    ///
+    /// Schedules shrink by dropping choices and by moving them towards the
+    /// first ready task.
+    pub fn task_schedule(choices: Range<usize>) -> Self {
+        let choice = Gen::<usize>::from_range(Range::linear(0, 15));
+        Gen::vec_with_range(choices, choice).map(TaskSchedule::new)
+    }
+}
+
+/// Tasks woken since they were last polled, in the order they were woken.
+/// The future passed to [`VirtualRuntime::block_on`] is task zero.
+type Woken = Arc<Mutex<Vec<usize>>>;
+
+struct TaskWaker {
+    id: usize,
+    woken: Woken,
+}
+
+impl Wake for TaskWaker {
+    fn wake(self: Arc<Self>) {
+        self.wake_by_ref();
+    }
+
+    fn wake_by_ref(self: &Arc<Self>) {
+        let mut woken = self.woken.lock().unwrap();
+        if !woken.contains(&self.id) {
+            woken.push(self.id);
+        }
+    }
+}
+
+type Task = Pin<Box<dyn Future<Output = ()>>>;
+
+struct Timer {
+    deadline: Duration,
+    waker: Waker,
+}
+
+struct Inner {
+    clock: TestClock,
+    schedule: RefCell<VecDeque<usize>>,
+    woken: Woken,
+    timers: RefCell<Vec<Timer>>,
+    /// Spawned tasks by id less one; `None` once finished or while polled
+    tasks: RefCell<Vec<Option<Task>>>,
+    running: Cell<bool>,
+}
+
+/// A single-threaded executor with virtual time.
+///
+/// Clones share the executor, so tasks can hold one to spawn tasks and
+/// sleep.
+///
+/// # Example
+/// ```rust
+/// use hedgehog_core::*;
+/// use std::time::Duration;
+///
+/// let runtime = VirtualRuntime::new();
+/// let sleeper = runtime.clone();
+/// let waited = runtime.block_on(async move {
+///     // An hour passes instantly
+///     sleeper.sleep(Duration::from_secs(3600)).await;
+///     sleeper.now()
+/// });
+/// assert_eq!(waited, Duration::from_secs(3600));
+/// ```
+#[derive(Clone)]
+pub struct VirtualRuntime {
+    inner: Rc<Inner>,
+}
+
+impl VirtualRuntime {
+    /// A runtime at time zero that runs ready tasks in the order they were
+    /// woken.
+    pub fn new() -> Self {
+        Self::with_schedule(&TaskSchedule::default())
+    }
+
+    /// A runtime at time zero that picks ready tasks following `schedule`.
+    pub fn with_schedule(schedule: &TaskSchedule) -> Self {
+        VirtualRuntime {
+            inner: Rc::new(Inner {
+                clock: TestClock::new(),
+                schedule: RefCell::new(schedule.choices.iter().copied().collect()),
+                woken: Arc::default(),
+                timers: RefCell::default(),
+                tasks: RefCell::default(),
+                running: Cell::new(false),
+            }),
+        }
+    }
+
+    /// The runtime's clock, for code written against [`Clock`].
+    ///
+    /// Advancing it by hand wakes any sleeps that are due.
+    pub fn clock(&self) -> TestClock {
+        self.inner.clock.clone()
+    }
+
+    /// Virtual time elapsed since the runtime started.
+    pub fn now(&self) -> Duration {
+        self.inner.clock.now()
+    }
+
+    /// A future that completes once `duration` of virtual time has passed.
+    pub fn sleep(&self, duration: Duration) -> Sleep {
+        Sleep {
+            runtime: self.clone(),
+            deadline: self.now() + duration,
+        }
+    }
+
+    /// A future that lets other ready tasks run before it completes.
+    pub fn yield_now(&self) -> YieldNow {
+        YieldNow { yielded: false }
+    }
+
+    /// Run `future` as a task of its own, returning a handle that
+    /// completes with its output.
+    pub fn spawn<T: 'static>(&self, future: impl Future<Output = T> + 'static) -> JoinHandle<T> {
+        let state = Rc::new(RefCell::new(JoinState {
+            output: None,
+            waiter: None,
+        }));
+        let finished = state.clone();
+        let task = Box::pin(async move {
+            let output = future.await;
+            let mut state = finished.borrow_mut();
+            state.output = Some(output);
+            if let Some(waiter) = state.waiter.take() {
+                waiter.wake();
+            }
+        });
+
+        let mut tasks = self.inner.tasks.borrow_mut();
+        tasks.push(Some(task));
+        self.wake(tasks.len());
+        JoinHandle { state }
+    }
+
+    /// Run `future` to completion, along with any tasks it spawns, and
+    /// return its output.
+    ///
+    /// # Panics
+    /// Panics if every task is waiting and no sleep is pending, since
+    /// nothing could ever wake them, or if called from within a task.
+    pub fn block_on<T>(&self, future: impl Future<Output = T>) -> T {
+        assert!(
+            !self.inner.running.replace(true),
+            "block_on can't be called from within a task"
+        );
+        let mut main = pin!(future);
+        self.wake(0);
+
+        loop {
+            self.fire_due_timers();
+            let Some(id) = self.next_ready() else {
+                if !self.advance_to_next_timer() {
+                    self.inner.running.set(false);
+                    panic!("every task is waiting and no sleep is pending");
+                }
+                continue;
+            };
+
+            let waker = Waker::from(Arc::new(TaskWaker {
+                id,
+                woken: self.inner.woken.clone(),
+            }));
+            let mut context = Context::from_waker(&waker);
+
+            if id == 0 {
+                if let Poll::Ready(output) = main.as_mut().poll(&mut context) {
+                    self.inner.running.set(false);
+                    return output;
+                }
+                continue;
+            }
+
+            let task = self.inner.tasks.borrow_mut()[id - 1].take();
+            if let Some(mut task) = task {
+                if task.as_mut().poll(&mut context).is_pending() {
+                    self.inner.tasks.borrow_mut()[id - 1] = Some(task);
+                }
+            }
+        }
+    }
+
+    fn wake(&self, id: usize) {
+        Waker::from(Arc::new(TaskWaker {
+            id,
+            woken: self.inner.woken.clone(),
+        }))
+        .wake();
+    }
+
+    /// Take the next task to poll, following the schedule.
+    fn next_ready(&self) -> Option<usize> {
+        let mut woken = self.inner.woken.lock().unwrap();
+        if woken.is_empty() {
+            return None;
+        }
+        let choice = self.inner.schedule.borrow_mut().pop_front().unwrap_or(0);
+        let index = choice % woken.len();
+        Some(woken.remove(index))
+    }
+
+    fn fire_due_timers(&self) {
+        let now = self.now();
+        self.inner.timers.borrow_mut().retain(|timer| {
+            let due = timer.deadline <= now;
+            if due {
+                timer.waker.wake_by_ref();
+            }
+            !due
+        });
+    }
+
+    /// Move the clock to the earliest pending sleep, if there is one.
+    fn advance_to_next_timer(&self) -> bool {
+        let next = self
+            .inner
+            .timers
+            .borrow()
+            .iter()
+            .map(|timer| timer.deadline)
+            .min();
+        match next {
+            Some(deadline) => {
+                let now = self.now();
+                self.inner.clock.advance(deadline.saturating_sub(now));
+                true
+            }
+            None => false,
+        }
+    }
+}
+
+impl Default for VirtualRuntime {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// A future that completes at a point in virtual time.
+#[must_use = "futures do nothing unless awaited"]
+pub struct Sleep {
+    runtime: VirtualRuntime,
+    deadline: Duration,
+}
+
+impl Future for Sleep {
+    type Output = ();
+
+    fn poll(self: Pin<&mut Self>, context: &mut Context<'_>) -> Poll<()> {
+        if self.runtime.now() >= self.deadline {
+            return Poll::Ready(());
+        }
+        self.runtime.inner.timers.borrow_mut().push(Timer {
+            deadline: self.deadline,
+            waker: context.waker().clone(),
+        });
+        Poll::Pending
+    }
+}
+
+/// A future that lets other ready tasks run once.
+#[must_use = "futures do nothing unless awaited"]
+pub struct YieldNow {
+    yielded: bool,
+}
+
+impl Future for YieldNow {
+    type Output = ();
+
+    fn poll(mut self: Pin<&mut Self>, context: &mut Context<'_>) -> Poll<()> {
+        if self.yielded {
+            return Poll::Ready(());
+        }
+        self.yielded = true;
+        context.waker().wake_by_ref();
+        Poll::Pending
+    }
+}
+
+struct JoinState<T> {
+    output: Option<T>,
+    waiter: Option<Waker>,
+}
+
+/// A future completing with a spawned task's output.
+pub struct JoinHandle<T> {
+    state: Rc<RefCell<JoinState<T>>>,
+}
+
+impl<T> Future for JoinHandle<T> {
+    type Output = T;
+
+    fn poll(self: Pin<&mut Self>, context: &mut Context<'_>) -> Poll<T> {
+        let mut state = self.state.borrow_mut();
+        match state.output.take() {
+            Some(output) => Poll::Ready(output),
+            None => {
+                state.waiter = Some(context.waker().clone());
+                Poll::Pending
+            }
+        }
+    }
+}
+
+/// Create a property checking an async `condition` against each input, run
+/// on a fresh [`VirtualRuntime`] following a generated schedule.
+///
+/// # Example
+/// ```rust
+/// use hedgehog_core::*;
+/// use std::time::Duration;
+///
+/// // Retrying with doubling backoff waits 2^n - 1 seconds in total
+/// let prop = for_all_async(
+///     Gen::u32_range(0, 10),
+///     Gen::<TaskSchedule>::task_schedule(Range::new(0, 5)),
+///     |retries, runtime| async move {
+///         for attempt in 0..retries {
+///             runtime.sleep(Duration::from_secs(1 << attempt)).await;
+///         }
+///         runtime.now() == Duration::from_secs((1 << retries) - 1)
+///     },
+/// );
+/// ```
+#[track_caller]
+pub fn for_all_async<T, F, Fut>(
+    generator: Gen<T>,
+    schedule: Gen<TaskSchedule>,
+    condition: F,
+) -> Property<(T, TaskSchedule)>
+where
+    T: 'static + std::fmt::Debug + Clone,
+    F: Fn(T, VirtualRuntime) -> Fut + 'static,
+    Fut: Future<Output = bool>,
+{
+    Property::for_all(
+        Gen::<(T, TaskSchedule)>::tuple_of(generator, schedule),
+        move |(input, schedule)| {
+            let runtime = VirtualRuntime::with_schedule(schedule);
+            runtime.block_on(condition(input.clone(), runtime.clone()))
+        },
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data::Config;
+    use crate::error::TestResult;
+
+    #[test]
+    fn test_sleeps_finish_in_deadline_order() {
+        let runtime = VirtualRuntime::new();
+        let order = Rc::new(RefCell::new(Vec::new()));
+
+        let handles: Vec<_> = [30, 10, 20]
+            .into_iter()
+            .map(|secs| {
+                let (sleeper, order) = (runtime.clone(), order.clone());
+                runtime.spawn(async move {
+                    sleeper.sleep(Duration::from_secs(secs)).await;
+                    order.borrow_mut().push(secs);
+                    secs * 2
+                })
+            })
+            .collect();
+
+        let outputs = runtime.block_on(async move {
+            let mut outputs = Vec::new();
+            for handle in handles {
+                outputs.push(handle.await);
+            }
+            outputs
+        });
+
+        assert_eq!(outputs, [60, 20, 40]);
+        assert_eq!(*order.borrow(), [10, 20, 30]);
+        assert_eq!(runtime.now(), Duration::from_secs(30));
+    }
+
+    #[test]
+    fn test_debounce() {
+        // Only the last of a burst of events fires once it has been quiet
+        // for 100ms
+        let runtime = VirtualRuntime::new();
+        let latest = Rc::new(Cell::new(0));
+        let fired = Rc::new(RefCell::new(Vec::new()));
+
+        for (event, at) in [(1, 0), (2, 50), (3, 120), (4, 300)] {
+            let (sleeper, latest, fired) = (runtime.clone(), latest.clone(), fired.clone());
+            runtime.spawn(async move {
+                sleeper.sleep(Duration::from_millis(at)).await;
+                latest.set(event);
+                sleeper.sleep(Duration::from_millis(100)).await;
+                if latest.get() == event {
+                    fired.borrow_mut().push(event);
+                }
+            });
+        }
+        let clock = runtime.clone();
+        runtime.block_on(async move { clock.sleep(Duration::from_secs(1)).await });
+
+        assert_eq!(*fired.borrow(), [3, 4]);
+    }
+
+    #[test]
+    #[should_panic(expected = "every task is waiting and no sleep is pending")]
+    fn test_deadlock_panics() {
+        let runtime = VirtualRuntime::new();
+        let never = runtime.spawn(std::future::pending::<()>());
+        runtime.block_on(never);
+    }
+
+    #[test]
+    fn test_schedules_find_lost_updates() {
+        // Two tasks each read a counter, yield, then write it back plus one
+        let prop = for_all_async(
+            Gen::constant(()),
+            Gen::<TaskSchedule>::task_schedule(Range::new(0, 8)),
+            |(), runtime| async move {
+                let counter = Rc::new(Cell::new(0));
+                let tasks: Vec<_> = (0..2)
+                    .map(|_| {
+                        let (runtime, counter) = (runtime.clone(), counter.clone());
+                        runtime.clone().spawn(async move {
+                            let read = counter.get();
+                            runtime.yield_now().await;
+                            counter.set(read + 1);
+                        })
+                    })
+                    .collect();
+                for task in tasks {
+                    task.await;
+                }
+                counter.get() == 2
+            },
+        );
+
+        match prop.run(&Config::default()) {
+            TestResult::Fail { counterexample, .. } => {
+                assert!(counterexample.contains("TaskSchedule"), "{counterexample}");
+            }
+            other => panic!("Expected a lost update, got: {other:?}"),
+        }
+    }
+
+    #[test]
+    fn test_schedule_is_deterministic() {
+        let run = |schedule: &TaskSchedule| {
+            let runtime = VirtualRuntime::with_schedule(schedule);
+            let order = Rc::new(RefCell::new(Vec::new()));
+            for task in 0..4 {
+                let (runtime_, order) = (runtime.clone(), order.clone());
+                runtime.spawn(async move {
+                    order.borrow_mut().push(task);
+                    runtime_.yield_now().await;
+                    order.borrow_mut().push(task + 10);
+                });
+            }
+            let yielder = runtime.clone();
+            runtime.block_on(async move {
+                for _ in 0..10 {
+                    yielder.yield_now().await;
+                }
+            });
+            let order = order.borrow().clone();
+            order
+        };
+
+        let schedule = TaskSchedule::new(vec![3, 1, 4, 1, 5, 9, 2, 6]);
+        assert_eq!(run(&schedule), run(&schedule));
+        assert_ne!(run(&schedule), run(&TaskSchedule::default()));
+    }
+}
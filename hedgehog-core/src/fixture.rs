@@ -0,0 +1,198 @@
+//! Resources set up for each test case and torn down after it.
+//!
+//! Properties over databases, servers or temporary directories need a
+//! resource to test against, and need it cleaned up however the test ends.
+//! A failing case is rerun many times while shrinking, and a test that
+//! panics skips any cleanup written after it, so each attempt can leak a
+//! container or a directory. A [`Fixture`] pairs the setup with its
+//! teardown, and [`for_all_with_fixture`] runs both around every case and
+//! every shrink attempt, tearing down even when the test panics.
+
+use crate::error::TestResult;
+use crate::gen::Gen;
+use crate::property::Property;
+use std::fmt::Debug;
+
+/// How to set up a resource for a test case, and how to tear it down.
+///
+/// # Example
+/// ```rust
+/// use hedgehog_core::*;
+/// use std::path::PathBuf;
+///
+/// let scratch = Fixture::new(
+///     || {
+///         let dir = std::env::temp_dir().join(format!("scratch-{}", std::process::id()));
+///         std::fs::create_dir_all(&dir).unwrap();
+///         dir
+///     },
+///     |dir: PathBuf| std::fs::remove_dir_all(dir).unwrap(),
+/// );
+///
+/// let prop = for_all_with_fixture(Gen::<String>::ascii_alpha(), scratch, |name, dir| {
+///     let path = dir.join(format!("file-{name}"));
+///     std::fs::write(&path, name).unwrap();
+///     std::fs::read_to_string(&path).unwrap() == *name
+/// });
+/// ```
+pub struct Fixture<A> {
+    setup: Box<dyn Fn() -> A>,
+    teardown: Box<dyn Fn(A)>,
+}
+
+impl<A> Fixture<A> {
+    /// A fixture built by `setup` and cleaned up by `teardown`.
+    pub fn new<S, D>(setup: S, teardown: D) -> Self
+    where
+        S: Fn() -> A + 'static,
+        D: Fn(A) + 'static,
+    {
+        Fixture {
+            setup: Box::new(setup),
+            teardown: Box::new(teardown),
+        }
+    }
+
+    /// A fixture cleaned up by dropping it, for resources whose `Drop`
+    /// does the teardown.
+    pub fn from_setup<S>(setup: S) -> Self
+    where
+        A: 'static,
+        S: Fn() -> A + 'static,
+    {
+        Self::new(setup, drop)
+    }
+
+    /// Set up the resource, run `test` with it and tear it down, even if
+    /// `test` panics.
+    ///
+    /// Returns the test's result, or a description of the first panic in
+    /// setup, the test or teardown.
+    pub fn run<R>(&self, test: impl FnOnce(&A) -> R) -> Result<R, String> {
+        let resource = crate::panic::catch(|| (self.setup)())
+            .map_err(|message| format!("Fixture setup panicked: {message}"))?;
+        let result =
+            crate::panic::catch(|| test(&resource)).map_err(|message| format!("Panic: {message}"));
+        let torn_down = crate::panic::catch(|| (self.teardown)(resource))
+            .map_err(|message| format!("Fixture teardown panicked: {message}"));
+        let result = result?;
+        torn_down?;
+        Ok(result)
+    }
+}
+
+/// Create a property checking `condition` against each input and a
+/// resource from `fixture`.
+///
+/// Every case and every shrink attempt gets a freshly set up resource,
+/// torn down as soon as the condition returns or panics. Panics in setup,
+/// the condition or teardown fail the case, and the failure shrinks like
+/// any other.
+#[track_caller]
+pub fn for_all_with_fixture<T, A, F>(
+    generator: Gen<T>,
+    fixture: Fixture<A>,
+    condition: F,
+) -> Property<T>
+where
+    T: 'static + Debug + Clone,
+    A: 'static,
+    F: Fn(&T, &A) -> bool + 'static,
+{
+    Property::new(generator, move |input| {
+        let assertion_type = match fixture.run(|resource| condition(input, resource)) {
+            Ok(true) => {
+                return TestResult::Pass {
+                    tests_run: 1,
+                    property_name: None,
+                    module_path: None,
+                }
+            }
+            Ok(false) => "Boolean Condition".to_string(),
+            Err(message) => message,
+        };
+        TestResult::failure(format!("{input:?}"), assertion_type)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data::Config;
+    use std::cell::Cell;
+    use std::rc::Rc;
+
+    /// A fixture counting the resources alive and the setups made.
+    fn counted() -> (Fixture<u32>, Rc<Cell<i32>>, Rc<Cell<u32>>) {
+        let (alive, setups) = (Rc::new(Cell::new(0)), Rc::new(Cell::new(0)));
+        let (opened, closed, made) = (alive.clone(), alive.clone(), setups.clone());
+        let fixture = Fixture::new(
+            move || {
+                opened.set(opened.get() + 1);
+                made.set(made.get() + 1);
+                made.get()
+            },
+            move |_| closed.set(closed.get() - 1),
+        );
+        (fixture, alive, setups)
+    }
+
+    #[test]
+    fn test_tears_down_after_every_case_and_shrink() {
+        let (fixture, alive, setups) = counted();
+        let prop = for_all_with_fixture(Gen::int_range(0, 100), fixture, |&n, _| {
+            assert!(n < 50, "too big");
+            true
+        });
+
+        match prop.run(&Config::default()) {
+            TestResult::Fail {
+                counterexample,
+                assertion_type,
+                shrinks_performed,
+                ..
+            } => {
+                assert!(counterexample.parse::<i32>().unwrap() >= 50);
+                assert_eq!(assertion_type.as_deref(), Some("Panic: too big"));
+                assert!(shrinks_performed > 0);
+            }
+            other => panic!("Expected failure, got: {other:?}"),
+        }
+        assert!(setups.get() > 1);
+        assert_eq!(alive.get(), 0);
+    }
+
+    #[test]
+    fn test_fresh_resource_for_each_case() {
+        let (fixture, alive, _) = counted();
+        let seen = Rc::new(Cell::new(0));
+        let last = seen.clone();
+        let prop = for_all_with_fixture(Gen::bool(), fixture, move |_, &resource| {
+            let fresh = resource > last.get();
+            last.set(resource);
+            fresh
+        });
+
+        assert!(matches!(
+            prop.run(&Config::default()),
+            TestResult::Pass { .. }
+        ));
+        assert_eq!(seen.get(), 100);
+        assert_eq!(alive.get(), 0);
+    }
+
+    #[test]
+    fn test_teardown_and_setup_panics_fail() {
+        let failing_teardown = Fixture::new(|| (), |()| panic!("disk full"));
+        assert_eq!(
+            failing_teardown.run(|()| 1),
+            Err("Fixture teardown panicked: disk full".to_string())
+        );
+
+        let failing_setup = Fixture::<()>::from_setup(|| panic!("no docker"));
+        assert_eq!(
+            failing_setup.run(|()| 1),
+            Err("Fixture setup panicked: no docker".to_string())
+        );
+    }
+}
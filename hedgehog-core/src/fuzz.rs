@@ -0,0 +1,141 @@
+//! Running properties as fuzz targets.
+//!
+//! A property already says what inputs look like and what must hold of
+//! them, which is everything a fuzz target needs. [`hedgehog_fuzz_target!`]
+//! turns a function returning a property into a libFuzzer entry point, so
+//! the same property runs under `cargo test` and `cargo fuzz`.
+//!
+//! Each fuzzer input picks one test case: the first byte chooses the size
+//! and the rest are hashed into the seed. The fuzzer explores seeds rather
+//! than values, so a small change to the bytes gives an unrelated case, but
+//! crashes it finds replay exactly and still shrink before being reported.
+
+use crate::data::{Config, Seed, Size};
+use crate::error::TestResult;
+use crate::property::Property;
+
+/// The size and seed of the test case picked by fuzzer input `data`.
+///
+/// Sizes run from 0 to `size_limit`. The same bytes always pick the same
+/// case.
+///
+/// # Example
+/// ```rust
+/// use hedgehog_core::*;
+///
+/// let (size, seed) = hedgehog_core::fuzz::case_from_bytes(b"\x20hello", 100);
+/// assert_eq!(size.get(), 32);
+/// assert_eq!(hedgehog_core::fuzz::case_from_bytes(b"\x20hello", 100).1, seed);
+/// ```
+pub fn case_from_bytes(data: &[u8], size_limit: usize) -> (Size, Seed) {
+    let size = data
+        .first()
+        .map_or(0, |&byte| byte as usize % (size_limit + 1));
+    // FNV-1a, spread over the seed by from_u64
+    let hash = data
+        .iter()
+        .skip(1)
+        .fold(0xcbf2_9ce4_8422_2325, |hash, &byte| {
+            (hash ^ byte as u64).wrapping_mul(0x0000_0100_0000_01b3)
+        });
+    (Size::new(size), Seed::from_u64(hash))
+}
+
+/// Define a libFuzzer entry point from a function returning a property.
+///
+/// Each fuzzer input runs one test case of the property, as picked by
+/// [`case_from_bytes`](crate::fuzz::case_from_bytes). A failure panics with
+/// the shrunk counterexample, which the fuzzer reports as a crash and saves
+/// the input for. Discarded cases are ignored. An optional second argument
+/// gives the config; it defaults to `Config::default()`.
+///
+/// The fuzz crate needs `libfuzzer-sys` as a dependency, as any `cargo
+/// fuzz` target does.
+///
+/// # Example
+/// ```rust,ignore
+/// // fuzz/fuzz_targets/parse.rs
+/// #![no_main]
+/// use hedgehog::*;
+///
+/// hedgehog_fuzz_target!(my_crate::properties::parse_round_trip);
+/// ```
+///
+/// where the same function backs a regular test:
+///
+/// ```rust,ignore
+/// pub fn parse_round_trip() -> Property<Expr> {
+///     for_all(expr(), |e: &Expr| parse(&e.to_string()) == Ok(e.clone()))
+/// }
+///
+/// #[test]
+/// fn parse_round_trips() {
+///     assert!(matches!(parse_round_trip().run(&Config::default()), TestResult::Pass { .. }));
+/// }
+/// ```
+#[macro_export]
+macro_rules! hedgehog_fuzz_target {
+    ($property:path) => {
+        $crate::hedgehog_fuzz_target!($property, $crate::Config::default());
+    };
+    ($property:path, $config:expr) => {
+        ::libfuzzer_sys::fuzz_target!(|data: &[u8]| {
+            $crate::fuzz::check_fuzz_input(&$property(), data, &$config);
+        });
+    };
+}
+
+/// Run one fuzzer input, panicking if the property fails.
+#[doc(hidden)]
+pub fn check_fuzz_input<T>(property: &Property<T>, data: &[u8], config: &Config)
+where
+    T: 'static + std::fmt::Debug + Clone,
+{
+    if let failure @ TestResult::Fail { .. } = property.run_fuzz_input(data, config) {
+        panic!("{failure}");
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::error::FailureDetails;
+    use crate::gen::Gen;
+    use crate::property::for_all;
+
+    #[test]
+    fn test_bytes_pick_the_case() {
+        let (size, seed) = case_from_bytes(&[250, 1, 2, 3], 100);
+        assert_eq!(size.get(), 250 % 101);
+        assert_eq!(case_from_bytes(&[250, 1, 2, 3], 100).1, seed);
+        assert_ne!(case_from_bytes(&[250, 1, 2, 4], 100).1, seed);
+        assert_eq!(case_from_bytes(&[], 100).0.get(), 0);
+    }
+
+    #[test]
+    fn test_fuzz_inputs_fail_with_shrunk_counterexamples() {
+        let prop = for_all(Gen::int_range(0, 1000), |&n: &i32| n < 10);
+        let failing = (0..=255u8)
+            .map(|byte| prop.run_fuzz_input(&[100, byte], &Config::default()))
+            .find(|result| matches!(result, TestResult::Fail { .. }))
+            .expect("some input should fail");
+        let TestResult::Fail {
+            counterexample,
+            details: FailureDetails { size, .. },
+            ..
+        } = failing
+        else {
+            unreachable!()
+        };
+        assert!(counterexample.parse::<i32>().unwrap() >= 10);
+        assert_eq!(size, Some(100));
+
+        let result = std::panic::catch_unwind(|| {
+            let prop = for_all(Gen::int_range(0, 1000), |&n: &i32| n < 10);
+            for byte in 0..=255u8 {
+                check_fuzz_input(&prop, &[100, byte], &Config::default());
+            }
+        });
+        assert!(result.is_err());
+    }
+}
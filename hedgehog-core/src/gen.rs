@@ -1,6 +1,60 @@
 //! Generator combinators for property-based testing.
 
 use crate::{data::*, tree::*};
+use std::cell::Cell;
+
+mod address;
+mod calibration;
+#[cfg(feature = "clap")]
+mod clap;
+mod config;
+pub mod context;
+#[cfg(any(feature = "chrono", feature = "time"))]
+mod datetime;
+#[cfg(feature = "rust_decimal")]
+mod decimal;
+mod filesystem;
+pub mod fun;
+pub mod grammar;
+mod http;
+mod interval;
+#[cfg(feature = "strum")]
+mod iterable_enum;
+mod markov;
+mod matrix;
+mod near;
+#[cfg(feature = "protobuf")]
+mod protobuf;
+mod replay;
+pub mod sql;
+mod string_like;
+mod time_series;
+
+pub use address::AddressStrictness;
+pub use calibration::{CalibrationPoint, SizeCalibration};
+pub use config::{ConfigKind, ConfigOptions, ConfigValue};
+pub use context::GenContext;
+pub use filesystem::{DirTree, DirTreeOptions, FsEntry, TempDir};
+pub use fun::Fun;
+pub use grammar::{Grammar, Production};
+pub use http::{HttpRequest, HttpResponse};
+#[cfg(feature = "protobuf")]
+pub use protobuf::ProtobufMessage;
+pub use time_series::TimeSeries;
+
+thread_local! {
+    /// Values discarded by generators on this thread since the runner last
+    /// took the count.
+    static GENERATION_DISCARDS: Cell<usize> = const { Cell::new(0) };
+}
+
+/// Take the number of values discarded by generators on this thread.
+pub(crate) fn take_generation_discards() -> usize {
+    GENERATION_DISCARDS.with(|discards| discards.replace(0))
+}
+
+/// Size used by the `sample` family of methods.
+const SAMPLE_SIZE: Size = Size(30);
 
 // Helper function to safely subtract two values, returning None if overflow would occur
 fn try_safe_subtract<T>(a: T, b: T) -> Option<T>
@@ -122,6 +176,23 @@ fn list_shrinks<T: Clone>(xs: &[T]) -> Vec<Vec<T>> {
     result
 }
 
+/// String shrinks without repeats, shortest first.
+///
+/// The strategies a string generator combines overlap: removing every
+/// character and taking an empty prefix both give `""`, and removing the
+/// first or last of two equal characters gives the same string. A stable
+/// sort by length puts the biggest reductions first without reordering
+/// candidates of the same length.
+fn string_shrinks(candidates: Vec<String>) -> Vec<Tree<String>> {
+    let mut seen = std::collections::HashSet::new();
+    let mut distinct: Vec<String> = candidates
+        .into_iter()
+        .filter(|candidate| seen.insert(candidate.clone()))
+        .collect();
+    distinct.sort_by_key(|candidate| candidate.chars().count());
+    distinct.into_iter().map(Tree::singleton).collect()
+}
+
 /// A weighted choice for frequency-based generation.
 pub struct WeightedChoice<T> {
     /// The weight of this choice (higher weights are more likely).
@@ -196,6 +267,14 @@ fn simplify_char(ch: char) -> char {
 /// type-directed approaches like QuickCheck.
 pub struct Gen<T> {
     generator: Box<dyn Fn(Size, Seed) -> Tree<T>>,
+    metadata: Metadata,
+}
+
+/// What a generator is called in reports.
+#[derive(Clone, Default)]
+struct Metadata {
+    name: Option<String>,
+    description: Option<String>,
 }
 
 impl<T> Gen<T> {
@@ -206,12 +285,93 @@ impl<T> Gen<T> {
     {
         Gen {
             generator: Box::new(f),
+            metadata: Metadata::default(),
         }
     }
 
+    /// Name this generator in reports.
+    ///
+    /// A failing property's output says which generator produced its input,
+    /// and generators built from this one refer to it by name:
+    ///
+    /// ```rust
+    /// use hedgehog_core::*;
+    ///
+    /// let ages = Gen::int_range(0, 120).named("age");
+    /// assert_eq!(ages.description().unwrap(), "age → int_range(0, 120)");
+    ///
+    /// let households = Gen::vec_of(ages).named("household");
+    /// assert_eq!(households.description().unwrap(), "household → vec_of(age)");
+    /// ```
+    pub fn named(mut self, name: &str) -> Self {
+        self.metadata.name = Some(name.to_string());
+        self
+    }
+
+    /// Describe how this generator builds its values, for reports.
+    ///
+    /// Range generators, `vec_of` and `option_of` describe themselves, and
+    /// `map` and `filter` keep the description of the generator they wrap.
+    pub fn describe(mut self, description: &str) -> Self {
+        self.metadata.description = Some(description.to_string());
+        self
+    }
+
+    /// This generator's name and description, as `name → description`,
+    /// or whichever of the two it has.
+    pub fn description(&self) -> Option<String> {
+        match (&self.metadata.name, &self.metadata.description) {
+            (Some(name), Some(description)) => Some(format!("{name} → {description}")),
+            (Some(name), None) => Some(name.clone()),
+            (None, Some(description)) => Some(description.clone()),
+            (None, None) => None,
+        }
+    }
+
+    /// How a generator built from this one refers to it: by name if it has
+    /// one, otherwise by description.
+    fn label(&self) -> &str {
+        let metadata = &self.metadata;
+        metadata
+            .name
+            .as_deref()
+            .or(metadata.description.as_deref())
+            .unwrap_or("_")
+    }
+
+    fn with_metadata(mut self, metadata: Metadata) -> Self {
+        self.metadata = metadata;
+        self
+    }
+
     /// Generate a value using the given size and seed.
+    ///
+    /// Panics with the error if the generator can't produce a value; see
+    /// [`try_generate`](Self::try_generate).
     pub fn generate(&self, size: Size, seed: Seed) -> Tree<T> {
-        (self.generator)(size, seed)
+        if crate::panic::generating() {
+            (self.generator)(size, seed)
+        } else {
+            self.try_generate(size, seed)
+                .unwrap_or_else(|error| panic!("{error}"))
+        }
+    }
+
+    /// Generate a value, returning the error if the generator can't produce
+    /// one, such as a [`filter`](Self::filter) that rejects everything.
+    ///
+    /// [`generate`](Self::generate) panics with the same error instead.
+    ///
+    /// # Example
+    /// ```rust
+    /// use hedgehog_core::*;
+    ///
+    /// let never = Gen::int_range(0, 10).filter(|&n| n > 10);
+    /// let error = never.try_generate(Size::new(10), Seed::from_u64(1)).unwrap_err();
+    /// assert!(matches!(error, HedgehogError::FilterTooRestrictive { .. }));
+    /// ```
+    pub fn try_generate(&self, size: Size, seed: Seed) -> crate::Result<Tree<T>> {
+        crate::panic::catch_error(|| (self.generator)(size, seed))
     }
 
     /// Create a generator that always produces the same value.
@@ -224,12 +384,33 @@ impl<T> Gen<T> {
 
     /// Generate a sample value with default parameters.
     /// This is a convenience method for state machine testing.
-    pub fn sample(&self) -> T
-    where
-        T: Clone,
-    {
-        let tree = self.generate(Size(30), Seed(42, 1337));
-        tree.value
+    ///
+    /// Each call uses a fresh random seed; use [`Gen::sample_with`] for a
+    /// reproducible value.
+    pub fn sample(&self) -> T {
+        self.sample_random()
+    }
+
+    /// Generate a sample value at the given size and seed.
+    pub fn sample_with(&self, size: Size, seed: Seed) -> T {
+        self.generate(size, seed).value
+    }
+
+    /// Generate a sample value at the default size with a fresh random seed.
+    pub fn sample_random(&self) -> T {
+        self.sample_with(SAMPLE_SIZE, Seed::random())
+    }
+
+    /// Generate `n` independent sample values at the default size.
+    ///
+    /// The values come from one random seed, split for each sample.
+    pub fn samples(&self, n: usize) -> impl Iterator<Item = T> + '_ {
+        let mut seed = Seed::random();
+        (0..n).map(move |_| {
+            let (sample_seed, next_seed) = seed.split();
+            seed = next_seed;
+            self.sample_with(SAMPLE_SIZE, sample_seed)
+        })
     }
 }
 
@@ -243,13 +424,19 @@ where
         F: Fn(T) -> U + 'static + Clone,
         U: 'static,
     {
+        let metadata = self.metadata.clone();
         Gen::new(move |size, seed| {
             let tree = self.generate(size, seed);
             tree.map(f.clone())
         })
+        .with_metadata(metadata)
     }
 
     /// Bind/flatmap for dependent generation.
+    ///
+    /// Shrinks the first value before the dependent one. Every shrink of the
+    /// first value generates the dependent value again from the same seed, so
+    /// the two stay consistent while both shrink.
     pub fn bind<U, F>(self, f: F) -> Gen<U>
     where
         F: Fn(T) -> Gen<U> + 'static,
@@ -259,7 +446,7 @@ where
         Gen::new(move |size, seed| {
             let (seed1, seed2) = seed.split();
             let tree = self.generate(size, seed1);
-            tree.bind(|value| f(value.clone()).generate(size, seed2))
+            bind_tree(tree, &|value: &T| f(value.clone()).generate(size, seed2))
         })
     }
 
@@ -269,6 +456,7 @@ where
         F: Fn(&T) -> bool + 'static,
         T: Clone,
     {
+        let metadata = self.metadata.clone();
         Gen::new(move |size, mut seed| {
             const MAX_DISCARDS: usize = 100;
 
@@ -281,12 +469,40 @@ where
                 seed = seed.split().1;
             }
 
-            // If we couldn't generate a valid value after MAX_DISCARDS attempts,
-            // this is likely a too-restrictive filter or a generator issue.
-            // For now, panic to make the issue visible rather than silently returning invalid data.
-            panic!(
-                "Filter: exceeded maximum discards ({MAX_DISCARDS}) - predicate may be too restrictive"
-            );
+            // Returning an unfiltered value would hide the problem
+            crate::panic::raise(crate::HedgehogError::FilterTooRestrictive {
+                limit: MAX_DISCARDS,
+            })
+        })
+        .with_metadata(metadata)
+    }
+
+    /// Map a fallible function over the generated values.
+    ///
+    /// A value the function rejects is discarded and generation is retried
+    /// with a new seed, so fallible conversions such as parsing or checked
+    /// arithmetic can be used without panicking. Discards are counted in the
+    /// property's statistics, and shrinks the function rejects are skipped.
+    pub fn try_map<U, E, F>(self, f: F) -> Gen<U>
+    where
+        F: Fn(T) -> Result<U, E> + 'static + Clone,
+        U: 'static,
+    {
+        Gen::new(move |size, mut seed| {
+            const MAX_DISCARDS: usize = 100;
+
+            for _ in 0..MAX_DISCARDS {
+                let tree = self.generate(size, seed);
+                if let Some(mapped_tree) = tree.filter_map(|value| f(value).ok()) {
+                    return mapped_tree;
+                }
+                GENERATION_DISCARDS.with(|discards| discards.set(discards.get() + 1));
+                seed = seed.split().1;
+            }
+
+            crate::panic::raise(crate::HedgehogError::FilterTooRestrictive {
+                limit: MAX_DISCARDS,
+            })
         })
     }
 
@@ -297,6 +513,16 @@ where
     ///
     /// Returns an error if the choices list is empty or all weights are zero.
     pub fn frequency(choices: Vec<WeightedChoice<T>>) -> crate::Result<Gen<T>>
+    where
+        T: Clone,
+    {
+        Gen::choose_weighted("frequency", choices)
+    }
+
+    /// [`frequency`](Self::frequency), recording the chosen branch in each
+    /// tree's [`origin`](Tree::origin) as `name[index]`, followed by the
+    /// branch's name or description if it has one.
+    fn choose_weighted(name: &str, choices: Vec<WeightedChoice<T>>) -> crate::Result<Gen<T>>
     where
         T: Clone,
     {
@@ -315,22 +541,34 @@ where
             });
         }
 
+        let origins: Vec<std::sync::Arc<str>> = choices
+            .iter()
+            .enumerate()
+            .map(|(index, choice)| match choice.generator.label() {
+                "_" => format!("{name}[{index}]").into(),
+                label => format!("{name}[{index}]: {label}").into(),
+            })
+            .collect();
+
         Ok(Gen::new(move |size, seed| {
             let (choice_value, new_seed) = seed.next_bounded(total_weight);
 
             // Find the chosen generator based on cumulative weights
             let mut cumulative_weight = 0;
-            let mut chosen_generator = &choices[0].generator;
+            let mut chosen = 0;
 
-            for choice in &choices {
+            for (index, choice) in choices.iter().enumerate() {
                 cumulative_weight += choice.weight;
                 if choice_value < cumulative_weight {
-                    chosen_generator = &choice.generator;
+                    chosen = index;
                     break;
                 }
             }
 
-            chosen_generator.generate(size, new_seed)
+            choices[chosen]
+                .generator
+                .generate(size, new_seed)
+                .with_origin(origins[chosen].clone())
         }))
     }
 
@@ -346,7 +584,187 @@ where
             .into_iter()
             .map(|gen| WeightedChoice::new(1, gen))
             .collect();
-        Gen::frequency(choices)
+        Gen::choose_weighted("one_of", choices)
+    }
+
+    /// Generate recursive values, choosing by weight between `leaves`,
+    /// which don't recurse, and `branches`, which do.
+    ///
+    /// Branch weights are scaled by the size: at size 100 and above every
+    /// weight is as given, below that branches become proportionally less
+    /// likely, and at size 0 only leaves are chosen. A branch runs at the
+    /// golden-ratio fraction of the current size, so each level of nesting
+    /// is smaller than the last and generation always reaches the leaves.
+    /// Values from a branch shrink first to a leaf, then as the branch
+    /// shrinks them.
+    ///
+    /// Branches refer back to the generator being defined through
+    /// [`defer`](Self::defer) or [`lazy`](Self::lazy). Returns an error if
+    /// there are no leaves or their weights are all zero.
+    ///
+    /// # Example
+    /// ```rust
+    /// use hedgehog_core::*;
+    ///
+    /// #[derive(Debug, Clone)]
+    /// enum Expr {
+    ///     Num(i32),
+    ///     Add(Box<Expr>, Box<Expr>),
+    /// }
+    ///
+    /// fn expr() -> Gen<Expr> {
+    ///     let add = Gen::<(Expr, Expr)>::tuple_of(Gen::defer(expr), Gen::defer(expr))
+    ///         .map(|(a, b)| Expr::Add(Box::new(a), Box::new(b)));
+    ///     Gen::recursive_weighted(
+    ///         vec![WeightedChoice::new(1, Gen::int_range(0, 9).map(Expr::Num))],
+    ///         vec![WeightedChoice::new(2, add)],
+    ///     )
+    ///     .unwrap()
+    /// }
+    ///
+    /// let leaf = expr().sample_with(Size::new(0), Seed::from_u64(1));
+    /// assert!(matches!(leaf, Expr::Num(_)));
+    /// ```
+    pub fn recursive_weighted(
+        leaves: Vec<WeightedChoice<T>>,
+        branches: Vec<WeightedChoice<T>>,
+    ) -> crate::Result<Gen<T>>
+    where
+        T: Clone + 'static,
+    {
+        /// The size at which branches get their full weight.
+        const FULL_SIZE: u64 = 100;
+
+        let leaf_weight: u64 = leaves.iter().map(|leaf| leaf.weight).sum();
+        if leaf_weight == 0 {
+            return Err(crate::HedgehogError::InvalidGenerator {
+                message: "recursive_weighted needs a leaf with a non-zero weight".to_string(),
+            });
+        }
+
+        fn pick<T>(choices: &[WeightedChoice<T>], mut roll: u64) -> Option<&Gen<T>> {
+            choices
+                .iter()
+                .find(|choice| {
+                    let found = roll < choice.weight;
+                    roll = roll.saturating_sub(choice.weight);
+                    found
+                })
+                .map(|choice| &choice.generator)
+        }
+
+        Ok(Gen::new(move |size, seed| {
+            let (choice_seed, value_seed) = seed.split();
+            let scale = (size.get() as u64).min(FULL_SIZE);
+            let branch_weight: u64 = branches.iter().map(|branch| branch.weight * scale).sum();
+            let (roll, _) = choice_seed.next_bounded(leaf_weight * FULL_SIZE + branch_weight);
+
+            let leaf = || {
+                let (leaf_roll, _) = value_seed.split().0.next_bounded(leaf_weight);
+                pick(&leaves, leaf_roll)
+                    .expect("a roll below the total weight picks a leaf")
+                    .generate(Size::new(0), value_seed)
+            };
+
+            if roll < leaf_weight * FULL_SIZE {
+                return pick(&leaves, roll / FULL_SIZE)
+                    .expect("a roll below the total weight picks a leaf")
+                    .generate(size, value_seed);
+            }
+            let branch = pick(&branches, (roll - leaf_weight * FULL_SIZE) / scale)
+                .expect("a roll below the total weight picks a branch");
+            let mut tree = branch.generate(size.golden(), value_seed);
+            tree.children.insert(0, leaf());
+            tree
+        }))
+    }
+
+    /// Build a generator the first time it is used, rather than now.
+    ///
+    /// Generators that refer to each other, like statements containing
+    /// expressions containing statements, can't all be built up front: each
+    /// would build the other forever. Wrapping the reference in `lazy`
+    /// breaks the cycle. The generator is built once and then reused.
+    ///
+    /// # Example
+    /// ```rust
+    /// use hedgehog_core::*;
+    ///
+    /// #[derive(Debug, Clone)]
+    /// enum Expr {
+    ///     Num(i32),
+    ///     Block(Vec<Stmt>),
+    /// }
+    ///
+    /// #[derive(Debug, Clone)]
+    /// enum Stmt {
+    ///     Eval(Expr),
+    ///     Return,
+    /// }
+    ///
+    /// fn expr() -> Gen<Expr> {
+    ///     Gen::frequency(vec![
+    ///         WeightedChoice::new(3, Gen::int_range(0, 9).map(Expr::Num)),
+    ///         WeightedChoice::new(1, Gen::lazy(|| Gen::<Vec<Stmt>>::vec_of(stmt())).map(Expr::Block)),
+    ///     ])
+    ///     .unwrap()
+    /// }
+    ///
+    /// fn stmt() -> Gen<Stmt> {
+    ///     Gen::one_of(vec![Gen::lazy(|| expr().map(Stmt::Eval)), Gen::constant(Stmt::Return)])
+    ///         .unwrap()
+    /// }
+    ///
+    /// let program = Gen::<Vec<Stmt>>::vec_of(stmt()).sample_with(Size::new(5), Seed::from_u64(1));
+    /// ```
+    pub fn lazy<F>(build: F) -> Gen<T>
+    where
+        F: FnOnce() -> Gen<T> + 'static,
+        T: 'static,
+    {
+        let build = Cell::new(Some(build));
+        let gen = std::cell::OnceCell::new();
+        Gen::new(move |size, seed| {
+            gen.get_or_init(|| build.take().expect("lazy generator is built once")())
+                .generate(size, seed)
+        })
+    }
+
+    /// Build a fresh generator every time a value is generated.
+    ///
+    /// Like [`lazy`](Self::lazy), but without keeping the generator, for
+    /// constructors that are cheap or that should see changing state.
+    pub fn defer<F>(build: F) -> Gen<T>
+    where
+        F: Fn() -> Gen<T> + 'static,
+        T: 'static,
+    {
+        Gen::new(move |size, seed| build().generate(size, seed))
+    }
+
+    /// Generate values using one of the given generator constructors with
+    /// equal probability.
+    ///
+    /// Only the chosen constructor is called, so this suits generators that
+    /// are expensive to build or that refer back to themselves. Shrinking
+    /// prefers the generated value first, then moves towards earlier choices.
+    /// Returns an error if the constructors list is empty.
+    pub fn choice_lazy(constructors: Vec<Box<dyn Fn() -> Gen<T>>>) -> crate::Result<Gen<T>> {
+        if constructors.is_empty() {
+            return Err(crate::HedgehogError::InvalidGenerator {
+                message: "choice_lazy constructors list cannot be empty".to_string(),
+            });
+        }
+
+        Ok(Gen::new(move |size, seed| {
+            let (choice_seed, value_seed) = seed.split();
+            let (index, _new_seed) = choice_seed.next_bounded(constructors.len() as u64);
+            let index = index as usize;
+
+            let shrinks = towards(0, index).into_iter().map(Tree::singleton).collect();
+            Tree::with_children(index, shrinks)
+                .bind(|chosen| constructors[chosen]().generate(size, value_seed))
+        }))
     }
 
     /// Generate values from a dictionary (list of predefined elements).
@@ -354,11 +772,15 @@ where
     /// This is useful for injecting domain-specific realistic values into tests.
     /// Returns an error if the elements list is empty.
     ///
+    /// Values shrink towards the earlier elements, so list the simplest or
+    /// most canonical ones first; [`from_elements_by_key`](Self::from_elements_by_key)
+    /// orders them for you.
+    ///
     /// # Example
     /// ```rust
     /// use hedgehog_core::*;
     ///
-    /// // Generate HTTP status codes from common values
+    /// // Generate HTTP status codes from common values, shrinking towards 200
     /// let status_codes = vec![200, 404, 500, 302, 401];
     /// let gen = Gen::from_elements(status_codes).unwrap();
     /// ```
@@ -374,20 +796,40 @@ where
 
         Ok(Gen::new(move |_size, seed| {
             let (index, _new_seed) = seed.next_bounded(elements.len() as u64);
-            let chosen = elements[index as usize].clone();
-
-            // Create shrinking candidates by trying other elements
-            let mut shrinks = Vec::new();
-            for (i, element) in elements.iter().enumerate() {
-                if i != index as usize {
-                    shrinks.push(element.clone());
-                }
-            }
-
-            Tree::with_children(chosen, shrinks.into_iter().map(Tree::singleton).collect())
+            let shrinks = towards(0, index as usize)
+                .into_iter()
+                .map(|shrink| Tree::singleton(elements[shrink].clone()))
+                .collect();
+            Tree::with_children(elements[index as usize].clone(), shrinks)
         }))
     }
 
+    /// Generate values from a list of elements, shrinking towards the one
+    /// with the smallest key.
+    ///
+    /// The elements are put in order of their keys, equal keys keeping
+    /// their order in the list, and then used as with
+    /// [`from_elements`](Self::from_elements). Returns an error if the list
+    /// is empty.
+    ///
+    /// # Example
+    /// ```rust
+    /// use hedgehog_core::*;
+    ///
+    /// // Shrink towards the shortest path
+    /// let paths = vec!["/api/v1/users/:id", "/", "/health"];
+    /// let gen = Gen::from_elements_by_key(paths, |path| path.len()).unwrap();
+    /// ```
+    pub fn from_elements_by_key<K, F>(mut elements: Vec<T>, key: F) -> crate::Result<Gen<T>>
+    where
+        T: Clone + 'static,
+        K: Ord,
+        F: FnMut(&T) -> K,
+    {
+        elements.sort_by_key(key);
+        Gen::from_elements(elements)
+    }
+
     /// Mix dictionary values with random generation based on probability weights.
     ///
     /// This allows combining realistic domain-specific values with random generation
@@ -432,14 +874,72 @@ where
             });
         }
 
-        let elements_gen = Gen::from_elements(elements)?;
+        let elements_gen = Gen::from_elements(elements)?.describe("dictionary");
 
         let choices = vec![
             WeightedChoice::new(elements_weight, elements_gen),
             WeightedChoice::new(random_weight, random_gen),
         ];
 
-        Gen::frequency(choices)
+        Gen::choose_weighted("from_dictionary", choices)
+    }
+
+    /// Replace a share of the generated values with values from a
+    /// dictionary of realistic or known-awkward inputs.
+    ///
+    /// With probability `probability` one of `values` is generated instead,
+    /// shrinking towards the earlier entries. This is
+    /// [`from_dictionary`](Self::from_dictionary) as a method, for adding a
+    /// dictionary to an existing generator. With no values the generator is
+    /// unchanged.
+    ///
+    /// # Example
+    /// ```rust
+    /// use hedgehog_core::*;
+    ///
+    /// let names = Gen::<String>::ascii_alpha().with_dictionary(
+    ///     vec!["".to_string(), "null".to_string(), "O'Brien".to_string()],
+    ///     0.2,
+    /// );
+    /// ```
+    pub fn with_dictionary(self, values: Vec<T>, probability: f64) -> Gen<T>
+    where
+        T: Clone,
+    {
+        let probability = probability.clamp(0.0, 1.0);
+
+        Gen::new(move |size, seed| {
+            let (roll_seed, value_seed) = seed.split();
+            if values.is_empty() || unit_interval(roll_seed) >= probability {
+                return self.generate(size, value_seed);
+            }
+
+            let (index, _new_seed) = value_seed.next_bounded(values.len() as u64);
+            let index = index as usize;
+            let shrinks = values[..index]
+                .iter()
+                .cloned()
+                .map(Tree::singleton)
+                .collect();
+            Tree::with_children(values[index].clone(), shrinks)
+        })
+    }
+}
+
+impl<T> Gen<Gen<T>>
+where
+    T: 'static,
+{
+    /// Flatten a generator of generators by generating from the generator
+    /// that was produced.
+    ///
+    /// Shrinks the generated value first, then the choice of generator.
+    pub fn flatten(self) -> Gen<T> {
+        Gen::new(move |size, seed| {
+            let (outer_seed, inner_seed) = seed.split();
+            self.generate(size, outer_seed)
+                .bind(|gen| gen.generate(size, inner_seed))
+        })
     }
 }
 
@@ -454,6 +954,54 @@ impl Gen<bool> {
     }
 }
 
+/// Trivial generators, for properties that need a generator but no input.
+impl Gen<()> {
+    /// Generate `()`.
+    ///
+    /// Useful for concurrent and stateful properties where the interesting
+    /// behaviour comes from scheduling rather than input.
+    ///
+    /// # Example
+    /// ```rust
+    /// use hedgehog_core::*;
+    ///
+    /// let prop = for_all(Gen::unit_value(), |_| true);
+    /// ```
+    pub fn unit_value() -> Self {
+        Gen::constant(())
+    }
+}
+
+impl<T: 'static> Gen<std::marker::PhantomData<T>> {
+    /// Generate `PhantomData`, for types that carry a marker field.
+    pub fn phantom() -> Self {
+        Gen::new(|_size, _seed| Tree::singleton(std::marker::PhantomData))
+    }
+}
+
+impl Gen<std::cmp::Ordering> {
+    /// Generate `Less`, `Equal` or `Greater`, shrinking towards `Equal`.
+    pub fn ordering() -> Self {
+        use std::cmp::Ordering;
+        Gen::new(|_size, seed| {
+            let (index, _) = seed.next_bounded(3);
+            match index {
+                0 => Tree::singleton(Ordering::Equal),
+                1 => Tree::with_children(Ordering::Less, vec![Tree::singleton(Ordering::Equal)]),
+                _ => Tree::with_children(Ordering::Greater, vec![Tree::singleton(Ordering::Equal)]),
+            }
+        })
+    }
+}
+
+impl<T: 'static> Gen<Result<T, std::convert::Infallible>> {
+    /// Generate `Ok` values from `ok_gen`, for APIs that return a `Result`
+    /// whose error can never happen.
+    pub fn infallible(ok_gen: Gen<T>) -> Self {
+        ok_gen.map(Ok)
+    }
+}
+
 /// Macro to implement enhanced numeric generators with origin-based shrinking for types that support From<u8>.
 macro_rules! impl_numeric_gen_with_towards {
     ($type:ty, $method:ident, $max_val:expr) => {
@@ -486,6 +1034,7 @@ macro_rules! impl_numeric_gen_with_towards {
 
                     Tree::with_children(result, shrinks)
                 })
+                .describe(&format!("{}({min}, {max})", stringify!($method)))
             }
 
             /// Generate a positive number.
@@ -538,6 +1087,7 @@ macro_rules! impl_numeric_gen_simple_shrink {
 
                     Tree::with_children(result, shrinks)
                 })
+                .describe(&format!("{}({min}, {max})", stringify!($method)))
             }
 
             /// Generate a positive number.
@@ -584,6 +1134,7 @@ macro_rules! impl_unsigned_gen {
 
                     Tree::with_children(result, shrinks)
                 })
+                .describe(&format!("{}({min}, {max})", stringify!($method)))
             }
 
             /// Generate a positive number.
@@ -605,6 +1156,136 @@ impl_unsigned_gen!(u32, u32_range, u32::MAX);
 impl_unsigned_gen!(u64, u64_range, u64::MAX);
 impl_unsigned_gen!(usize, usize_range, usize::MAX);
 
+/// Types with boundary values worth testing within a range.
+pub trait EdgeCases: Sized {
+    /// Boundary values within `range`: the bounds and their neighbours,
+    /// zero, and powers of two. Ordered from the simplest value, the range's
+    /// origin or the nearest to it, to the least simple.
+    fn edge_cases(range: &Range<Self>) -> Vec<Self>;
+
+    /// Shrinks of `value` towards the origin of `range`, halving the
+    /// distance as the range's generators do.
+    fn shrinks_within(range: &Range<Self>, value: Self) -> Vec<Self>;
+}
+
+macro_rules! impl_edge_cases {
+    ($($type:ty),*) => {
+        $(
+            impl EdgeCases for $type {
+                fn edge_cases(range: &Range<Self>) -> Vec<Self> {
+                    let (low, high) = (range.min as i128, range.max as i128);
+                    if low > high {
+                        return Vec::new();
+                    }
+
+                    let mut candidates = vec![low, high, low + 1, high - 1, 0];
+                    for bit in 0..<$type>::BITS {
+                        candidates.push(1i128 << bit);
+                        candidates.push(-(1i128 << bit));
+                    }
+
+                    let origin = range.origin.map_or(0, |origin| origin as i128).clamp(low, high);
+                    candidates.retain(|candidate| (low..=high).contains(candidate));
+                    candidates.sort_by_key(|&candidate| ((candidate - origin).abs(), candidate));
+                    candidates.dedup();
+                    candidates.into_iter().map(|candidate| candidate as $type).collect()
+                }
+
+                fn shrinks_within(range: &Range<Self>, value: Self) -> Vec<Self> {
+                    let (low, high) = (range.min as i128, range.max as i128);
+                    let origin = range.origin.map_or(0, |origin| origin as i128).clamp(low, high);
+                    towards(origin, value as i128)
+                        .into_iter()
+                        .filter(|shrink| (low..=high).contains(shrink))
+                        .map(|shrink| shrink as $type)
+                        .collect()
+                }
+            }
+        )*
+    };
+}
+
+impl_edge_cases!(i8, i16, i32, i64, isize, u8, u16, u32, u64, usize);
+
+impl<T> Gen<T>
+where
+    T: EdgeCases + Copy + 'static,
+{
+    /// Replace a share of the generated values with edge cases of `range`,
+    /// as its [`edge_bias`](crate::data::Range::edge_bias) asks. An edge
+    /// case shrinks towards the range's origin like any other value.
+    fn with_range_edges(self, range: &Range<T>) -> Gen<T> {
+        let range = *range;
+        let edges = T::edge_cases(&range);
+        if range.edge_bias <= 0.0 || edges.is_empty() {
+            return self;
+        }
+
+        Gen::new(move |size, seed| {
+            let (roll_seed, value_seed) = seed.split();
+            if unit_interval(roll_seed) >= range.edge_bias {
+                return self.generate(size, value_seed);
+            }
+
+            let (index, _new_seed) = value_seed.next_bounded(edges.len() as u64);
+            let edge = edges[index as usize];
+            let shrinks = T::shrinks_within(&range, edge)
+                .into_iter()
+                .map(Tree::singleton)
+                .collect();
+            Tree::with_children(edge, shrinks)
+        })
+    }
+}
+
+/// Draw a value in `[0, 1)` from a seed.
+fn unit_interval(seed: Seed) -> f64 {
+    let (bits, _new_seed) = seed.next_u64();
+    (bits >> 11) as f64 / (1u64 << 53) as f64
+}
+
+/// Draw a length from a range using its distribution, or one of its bounds
+/// as often as its edge bias asks.
+fn sample_length(range: &Range<usize>, seed: Seed) -> usize {
+    let seed = if range.edge_bias > 0.0 {
+        let (roll_seed, seed) = seed.split();
+        let roll = unit_interval(roll_seed);
+        if roll < range.edge_bias / 2.0 {
+            return range.min;
+        } else if roll < range.edge_bias {
+            return range.max;
+        }
+        seed
+    } else {
+        seed
+    };
+    let range_size = (range.max - range.min + 1) as u64;
+    let (offset, _) = range.distribution.sample_u64(seed, range_size);
+    range.min + offset as usize
+}
+
+/// Draws a series of choices from a seed, splitting it as it goes.
+///
+/// Used by generators that walk a schema and make many small decisions.
+struct Draw(Seed);
+
+impl Draw {
+    /// A number below `n`, or zero if `n` is zero.
+    fn below(&mut self, n: usize) -> usize {
+        if n == 0 {
+            return 0;
+        }
+        let (now, later) = self.0.split();
+        self.0 = later;
+        now.next_bounded(n as u64).0 as usize
+    }
+
+    /// A fair coin flip.
+    fn chance(&mut self) -> bool {
+        self.below(2) == 0
+    }
+}
+
 // Enhanced range-based generators with distribution support
 impl Gen<i32> {
     /// Generate integers using a Range specification with distribution control.
@@ -653,6 +1334,7 @@ impl Gen<i32> {
 
             Tree::with_children(result, shrinks)
         })
+        .with_range_edges(&range)
     }
 }
 
@@ -689,6 +1371,7 @@ impl Gen<i64> {
 
             Tree::with_children(result, shrinks)
         })
+        .with_range_edges(&range)
     }
 }
 
@@ -713,6 +1396,7 @@ impl Gen<u32> {
 
             Tree::with_children(result, shrinks)
         })
+        .with_range_edges(&range)
     }
 }
 
@@ -801,6 +1485,7 @@ impl Gen<i8> {
 
             Tree::with_children(result, shrinks)
         })
+        .with_range_edges(&range)
     }
 }
 
@@ -840,6 +1525,7 @@ impl Gen<i16> {
 
             Tree::with_children(result, shrinks)
         })
+        .with_range_edges(&range)
     }
 }
 
@@ -883,6 +1569,7 @@ impl Gen<isize> {
 
             Tree::with_children(result, shrinks)
         })
+        .with_range_edges(&range)
     }
 }
 
@@ -908,6 +1595,7 @@ impl Gen<u8> {
 
             Tree::with_children(result, shrinks)
         })
+        .with_range_edges(&range)
     }
 }
 
@@ -935,6 +1623,7 @@ impl Gen<u64> {
 
             Tree::with_children(result, shrinks)
         })
+        .with_range_edges(&range)
     }
 }
 
@@ -962,6 +1651,7 @@ impl Gen<usize> {
 
             Tree::with_children(result, shrinks)
         })
+        .with_range_edges(&range)
     }
 }
 
@@ -1040,6 +1730,7 @@ impl Gen<u16> {
 
             Tree::with_children(result, shrinks)
         })
+        .with_range_edges(&range)
     }
 }
 
@@ -1080,6 +1771,168 @@ impl Gen<char> {
             Tree::singleton(ch)
         })
     }
+
+    /// Generate characters in the codepoint range `lo..=hi`.
+    ///
+    /// Surrogate codepoints are skipped, so ranges that span them still only
+    /// produce valid characters. Shrinks towards `lo`.
+    ///
+    /// # Example
+    /// ```rust
+    /// use hedgehog_core::*;
+    ///
+    /// let box_drawing = Gen::char_range('\u{2500}', '\u{257F}');
+    /// ```
+    pub fn char_range(lo: char, hi: char) -> Self {
+        Self::char_ranges(vec![(lo, hi)])
+    }
+
+    /// Generate characters from several codepoint ranges, each `(lo, hi)`
+    /// inclusive.
+    ///
+    /// Every character is equally likely, so larger ranges are chosen more
+    /// often. Shrinks towards the low end of the first range. Reversed ranges
+    /// are treated as if their ends were swapped, and with no ranges at all
+    /// `'\0'` is always generated.
+    pub fn char_ranges(ranges: Vec<(char, char)>) -> Self {
+        let ranges: Vec<(u32, u32)> = ranges
+            .into_iter()
+            .map(|(lo, hi)| {
+                let (lo, hi) = (scalar_index(lo.min(hi)), scalar_index(lo.max(hi)));
+                (lo, hi - lo + 1)
+            })
+            .collect();
+        let total: u64 = ranges.iter().map(|&(_, count)| count as u64).sum();
+
+        Gen::new(move |_size, seed| {
+            if total == 0 {
+                return Tree::singleton('\0');
+            }
+
+            let (position, _new_seed) = seed.next_bounded(total);
+            let position = position as u32;
+
+            let to_char = |position: u32| {
+                let mut remaining = position;
+                for &(start, count) in &ranges {
+                    if remaining < count {
+                        return from_scalar_index(start + remaining);
+                    }
+                    remaining -= count;
+                }
+                unreachable!("position is within the total range size")
+            };
+
+            let shrinks = towards(0, position)
+                .into_iter()
+                .map(|shrink| Tree::singleton(to_char(shrink)))
+                .collect();
+            Tree::with_children(to_char(position), shrinks)
+        })
+    }
+
+    /// Generate hexadecimal digits, in either case. Shrinks towards `'0'`.
+    pub fn hex_digit() -> Self {
+        element_towards_first(HEX_DIGITS)
+    }
+
+    /// Generate characters from the standard base64 alphabet (`A-Z`, `a-z`,
+    /// `0-9`, `+`, `/`), not including the `=` padding character.
+    pub fn base64_char() -> Self {
+        element_towards_first(BASE64_ALPHABET)
+    }
+
+    /// Generate characters from the URL-safe base64 alphabet, which uses `-`
+    /// and `_` in place of `+` and `/`.
+    pub fn base64_url_char() -> Self {
+        element_towards_first(BASE64_URL_ALPHABET)
+    }
+
+    /// Generate whitespace characters: the ASCII ones, including `'\r'` and
+    /// vertical tab, and the Unicode spaces and line separators such as
+    /// U+00A0 and U+2028. Shrinks towards `' '`.
+    pub fn whitespace() -> Self {
+        element_towards_first(WHITESPACE_CHARS)
+    }
+
+    /// Generate control characters: C0 controls, DEL, and C1 controls.
+    /// Shrinks towards `'\0'`.
+    pub fn control() -> Self {
+        Self::char_ranges(vec![('\0', '\u{1F}'), ('\u{7F}', '\u{9F}')])
+    }
+}
+
+/// Hexadecimal digits, lowercase before uppercase.
+const HEX_DIGITS: &[char] = &[
+    '0', '1', '2', '3', '4', '5', '6', '7', '8', '9', 'a', 'b', 'c', 'd', 'e', 'f', 'A', 'B', 'C',
+    'D', 'E', 'F',
+];
+
+/// The standard base64 alphabet, in encoding order.
+const BASE64_ALPHABET: &[char] = &[
+    'A', 'B', 'C', 'D', 'E', 'F', 'G', 'H', 'I', 'J', 'K', 'L', 'M', 'N', 'O', 'P', 'Q', 'R', 'S',
+    'T', 'U', 'V', 'W', 'X', 'Y', 'Z', 'a', 'b', 'c', 'd', 'e', 'f', 'g', 'h', 'i', 'j', 'k', 'l',
+    'm', 'n', 'o', 'p', 'q', 'r', 's', 't', 'u', 'v', 'w', 'x', 'y', 'z', '0', '1', '2', '3', '4',
+    '5', '6', '7', '8', '9', '+', '/',
+];
+
+/// The URL-safe base64 alphabet, in encoding order.
+const BASE64_URL_ALPHABET: &[char] = &[
+    'A', 'B', 'C', 'D', 'E', 'F', 'G', 'H', 'I', 'J', 'K', 'L', 'M', 'N', 'O', 'P', 'Q', 'R', 'S',
+    'T', 'U', 'V', 'W', 'X', 'Y', 'Z', 'a', 'b', 'c', 'd', 'e', 'f', 'g', 'h', 'i', 'j', 'k', 'l',
+    'm', 'n', 'o', 'p', 'q', 'r', 's', 't', 'u', 'v', 'w', 'x', 'y', 'z', '0', '1', '2', '3', '4',
+    '5', '6', '7', '8', '9', '-', '_',
+];
+
+/// Whitespace characters, ASCII first so that shrinking reaches them.
+const WHITESPACE_CHARS: &[char] = &[
+    ' ', '\t', '\n', '\r', '\u{0B}', '\u{0C}', '\u{85}', '\u{A0}', '\u{1680}', '\u{2000}',
+    '\u{2001}', '\u{2002}', '\u{2003}', '\u{2004}', '\u{2005}', '\u{2006}', '\u{2007}', '\u{2008}',
+    '\u{2009}', '\u{200A}', '\u{2028}', '\u{2029}', '\u{202F}', '\u{205F}', '\u{3000}',
+];
+
+/// Whitespace as it appears in text, with `"\r\n"` kept together.
+const WHITESPACE_TOKENS: &[&str] = &[
+    " ", "\t", "\n", "\r\n", "\r", "\u{0B}", "\u{0C}", "\u{85}", "\u{A0}", "\u{1680}", "\u{2000}",
+    "\u{2001}", "\u{2002}", "\u{2003}", "\u{2004}", "\u{2005}", "\u{2006}", "\u{2007}", "\u{2008}",
+    "\u{2009}", "\u{200A}", "\u{2028}", "\u{2029}", "\u{202F}", "\u{205F}", "\u{3000}",
+];
+
+/// Pick one of a fixed set of items, shrinking towards the earlier ones.
+fn element_towards_first<T: Copy + 'static>(items: &'static [T]) -> Gen<T> {
+    Gen::new(move |_size, seed| {
+        let (index, _new_seed) = seed.next_bounded(items.len() as u64);
+        let shrinks = towards(0, index as usize)
+            .into_iter()
+            .map(|shrink| Tree::singleton(items[shrink]))
+            .collect();
+        Tree::with_children(items[index as usize], shrinks)
+    })
+}
+
+/// Start of the surrogate codepoints, which are not valid characters.
+const SURROGATE_START: u32 = 0xD800;
+/// Number of surrogate codepoints.
+const SURROGATE_COUNT: u32 = 0x800;
+
+/// Position of a character among all valid characters.
+fn scalar_index(ch: char) -> u32 {
+    let code = ch as u32;
+    if code >= SURROGATE_START {
+        code - SURROGATE_COUNT
+    } else {
+        code
+    }
+}
+
+/// The character at a position among all valid characters.
+fn from_scalar_index(index: u32) -> char {
+    let code = if index >= SURROGATE_START {
+        index + SURROGATE_COUNT
+    } else {
+        index
+    };
+    char::from_u32(code).expect("scalar index maps to a valid character")
 }
 
 impl Gen<String> {
@@ -1109,13 +1962,13 @@ impl Gen<String> {
 
             // Always try empty string first (ultimate shrink)
             if !chars.is_empty() {
-                shrinks.push(Tree::singleton(String::new()));
+                shrinks.push(String::new());
             }
 
             // Use sophisticated character removal shrinking
             for shrunk_chars in list_shrinks(&chars) {
                 let shrunk_string: String = shrunk_chars.iter().collect();
-                shrinks.push(Tree::singleton(shrunk_string));
+                shrinks.push(shrunk_string);
             }
 
             // Character simplification shrinking
@@ -1134,7 +1987,7 @@ impl Gen<String> {
 
                 if did_simplify {
                     let simplified_string: String = simplified_chars.iter().collect();
-                    shrinks.push(Tree::singleton(simplified_string));
+                    shrinks.push(simplified_string);
                 }
             }
 
@@ -1143,14 +1996,14 @@ impl Gen<String> {
                 // Try first half
                 let half = chars.len() / 2;
                 let first_half: String = chars[..half].iter().collect();
-                shrinks.push(Tree::singleton(first_half));
+                shrinks.push(first_half);
 
                 // Try second half
                 let second_half: String = chars[half..].iter().collect();
-                shrinks.push(Tree::singleton(second_half));
+                shrinks.push(second_half);
             }
 
-            Tree::with_children(string_value, shrinks)
+            Tree::with_children(string_value, string_shrinks(shrinks))
         })
     }
 
@@ -1169,15 +2022,42 @@ impl Gen<String> {
         Self::string_of(Gen::<char>::ascii_printable())
     }
 
+    /// Generate strings of hexadecimal digits, in either case.
+    pub fn hex() -> Self {
+        Self::string_of(Gen::<char>::hex_digit())
+    }
+
+    /// Generate strings over the standard base64 alphabet.
+    ///
+    /// These are unpadded and of any length, so they are not always valid
+    /// base64; this is what a decoder should be tested against.
+    pub fn base64() -> Self {
+        Self::string_of(Gen::<char>::base64_char())
+    }
+
+    /// Generate strings over the URL-safe base64 alphabet.
+    pub fn base64_url() -> Self {
+        Self::string_of(Gen::<char>::base64_url_char())
+    }
+
+    /// Generate runs of mixed whitespace, including `"\r\n"` line endings
+    /// and Unicode spaces. Shrinks towards fewer, plainer spaces.
+    pub fn whitespace() -> Self {
+        Gen::<Vec<&'static str>>::vec_of(element_towards_first(WHITESPACE_TOKENS))
+            .map(|tokens| tokens.concat())
+    }
+
+    /// Generate strings of control characters.
+    pub fn control() -> Self {
+        Self::string_of(Gen::<char>::control())
+    }
+
     /// Generate strings with controlled length using a Range specification.
     pub fn with_range(length_range: crate::data::Range<usize>, char_gen: Gen<char>) -> Self {
         Gen::new(move |size, seed| {
             let (len_seed, chars_seed) = seed.split();
 
-            // Use the range distribution to determine length
-            let range_size = (length_range.max - length_range.min + 1) as u64;
-            let (offset, _) = length_range.distribution.sample_u64(len_seed, range_size);
-            let length = length_range.min + offset as usize;
+            let length = sample_length(&length_range, len_seed);
 
             let mut current_seed = chars_seed;
             let mut chars = Vec::new();
@@ -1204,28 +2084,28 @@ impl Gen<String> {
                 && origin_length <= length_range.max
             {
                 if origin_length == 0 {
-                    shrinks.push(Tree::singleton(String::new()));
+                    shrinks.push(String::new());
                 } else if origin_length < length {
                     let origin_string: String = chars[..origin_length].iter().collect();
-                    shrinks.push(Tree::singleton(origin_string));
+                    shrinks.push(origin_string);
                 }
             }
 
             // Always try empty string as ultimate shrink
             if !chars.is_empty() && length_range.min == 0 {
-                shrinks.push(Tree::singleton(String::new()));
+                shrinks.push(String::new());
             }
 
             // Character removal shrinking
             if !chars.is_empty() {
                 // Remove last character
                 let shorter: String = chars[..chars.len() - 1].iter().collect();
-                shrinks.push(Tree::singleton(shorter));
+                shrinks.push(shorter);
 
                 // Remove first character
                 if chars.len() > 1 {
                     let shorter: String = chars[1..].iter().collect();
-                    shrinks.push(Tree::singleton(shorter));
+                    shrinks.push(shorter);
                 }
             }
 
@@ -1245,11 +2125,11 @@ impl Gen<String> {
 
                 if did_simplify {
                     let simplified_string: String = simplified_chars.iter().collect();
-                    shrinks.push(Tree::singleton(simplified_string));
+                    shrinks.push(simplified_string);
                 }
             }
 
-            Tree::with_children(string_value, shrinks)
+            Tree::with_children(string_value, string_shrinks(shrinks))
         })
     }
 
@@ -1384,6 +2264,8 @@ impl Gen<String> {
     /// # Parameters
     /// - `include_keywords`: Whether to include SQL keywords (can cause syntax errors)
     ///
+    /// For whole statements, see [`Gen::sql_statement`].
+    ///
     /// # Example
     /// ```rust
     /// use hedgehog_core::*;
@@ -1392,17 +2274,9 @@ impl Gen<String> {
     /// let risky_gen = Gen::<String>::sql_identifier(true);       // May include keywords
     /// ```
     pub fn sql_identifier(include_keywords: bool) -> Self {
-        let keywords = vec![
-            "SELECT", "INSERT", "UPDATE", "DELETE", "FROM", "WHERE", "JOIN", "INNER", "LEFT",
-            "RIGHT", "ON", "AS", "AND", "OR", "NOT", "NULL", "TRUE", "FALSE", "ORDER", "BY",
-            "GROUP", "HAVING", "LIMIT", "OFFSET", "UNION", "DISTINCT", "COUNT", "SUM", "AVG",
-            "MAX", "MIN", "CREATE", "TABLE", "INDEX", "PRIMARY", "KEY", "FOREIGN", "UNIQUE",
-            "CHECK", "DEFAULT",
-        ];
-
         if include_keywords {
             Gen::from_dictionary(
-                keywords.into_iter().map(|s| s.to_string()).collect(),
+                sql::SQL_KEYWORDS.iter().map(|s| s.to_string()).collect(),
                 Gen::<String>::alpha_with_range(crate::data::Range::linear(3, 20)),
                 30, // 30% keywords
                 70, // 70% random identifiers
@@ -1444,44 +2318,94 @@ where
 {
     /// Generate vectors using the given element generator.
     pub fn vec_of(element_gen: Gen<T>) -> Self {
+        let description = format!("vec_of({})", element_gen.label());
         Gen::new(move |size, seed| {
             let (len_seed, elements_seed) = seed.split();
             let (length, _) = len_seed.next_bounded(size.get() as u64 + 1);
+            vec_tree(&element_gen, length as usize, size, elements_seed)
+        })
+        .describe(&description)
+    }
 
-            let mut current_seed = elements_seed;
-            let mut elements = Vec::new();
-            let mut element_trees = Vec::new();
+    /// Generate vectors whose length is drawn from a range.
+    ///
+    /// The range's distribution decides how lengths spread between `min` and
+    /// `max`, so `Range::exponential(10, 1000)` usually gives short vectors
+    /// while still reaching long ones. Shrinking tries the origin length (or
+    /// `min`) first, then removes elements, and never goes below `min`.
+    pub fn vec_with_range(length_range: crate::data::Range<usize>, element_gen: Gen<T>) -> Self {
+        Gen::new(move |size, seed| {
+            let (len_seed, elements_seed) = seed.split();
 
-            for _ in 0..length {
-                let (element_seed, next_seed) = current_seed.split();
-                current_seed = next_seed;
+            let length = sample_length(&length_range, len_seed);
 
-                let element_tree = element_gen.generate(size, element_seed);
-                elements.push(element_tree.value.clone());
-                element_trees.push(element_tree);
-            }
+            let tree = vec_tree(&element_gen, length, size, elements_seed);
+            let elements = tree.value;
 
             let mut shrinks = Vec::new();
 
-            // Use sophisticated list shrinking algorithm
-            for shrunk_list in list_shrinks(&elements) {
-                shrinks.push(Tree::singleton(shrunk_list));
+            // Try the origin length first, keeping the leading elements
+            let origin_length = length_range.origin.unwrap_or(length_range.min);
+            if origin_length < length && origin_length >= length_range.min {
+                shrinks.push(Tree::singleton(elements[..origin_length].to_vec()));
             }
 
-            // Element-wise shrinking: shrink individual elements while keeping the structure
-            for (i, element_tree) in element_trees.iter().enumerate() {
-                for shrunk_element in element_tree.shrinks() {
-                    let mut shrunk_vec = elements.clone();
-                    shrunk_vec[i] = shrunk_element.clone();
-                    shrinks.push(Tree::singleton(shrunk_vec));
-                }
-            }
+            // Then the usual removals and element shrinks, within the range
+            shrinks.extend(
+                tree.children
+                    .into_iter()
+                    .filter(|shrink| shrink.value.len() >= length_range.min),
+            );
 
             Tree::with_children(elements, shrinks)
         })
     }
 }
 
+/// Generate a vector of the given length along with its shrinks.
+fn vec_tree<T>(element_gen: &Gen<T>, length: usize, size: Size, seed: Seed) -> Tree<Vec<T>>
+where
+    T: Clone,
+{
+    let mut current_seed = seed;
+
+    let mut elements = Vec::new();
+    let mut element_trees = Vec::new();
+
+    for _ in 0..length {
+        let (element_seed, next_seed) = current_seed.split();
+        current_seed = next_seed;
+
+        let element_tree = element_gen.generate(size, element_seed);
+        elements.push(element_tree.value.clone());
+        element_trees.push(element_tree);
+    }
+
+    let mut shrinks = Vec::new();
+
+    // Use sophisticated list shrinking algorithm, shortest lists first
+    for shrunk_list in list_shrinks(&elements) {
+        shrinks.push(Tree::singleton(shrunk_list));
+    }
+
+    // Element-wise shrinking: shrink individual elements while keeping the
+    // structure. Each element's most aggressive shrink comes before any
+    // element's second, so a capped search reaches every element.
+    let element_shrinks: Vec<Vec<&T>> = element_trees.iter().map(Tree::shrinks).collect();
+    let rounds = element_shrinks.iter().map(Vec::len).max().unwrap_or(0);
+    for round in 0..rounds {
+        for (i, shrunk_elements) in element_shrinks.iter().enumerate() {
+            if let Some(&shrunk_element) = shrunk_elements.get(round) {
+                let mut shrunk_vec = elements.clone();
+                shrunk_vec[i] = shrunk_element.clone();
+                shrinks.push(Tree::singleton(shrunk_vec));
+            }
+        }
+    }
+
+    Tree::with_children(elements, shrinks)
+}
+
 impl Gen<Vec<i32>> {
     /// Generate vectors of integers.
     pub fn vec_int() -> Self {
@@ -1496,26 +2420,111 @@ impl Gen<Vec<bool>> {
     }
 }
 
+impl Gen<Vec<f64>> {
+    /// Generate vectors of values from `element_gen` in ascending order.
+    ///
+    /// Shrinks are sorted too, so shrinking never breaks the order.
+    pub fn sorted_f64(element_gen: Gen<f64>) -> Self {
+        Self::vec_of(element_gen).map(|mut values: Vec<f64>| {
+            values.sort_by(f64::total_cmp);
+            values
+        })
+    }
+
+    /// Generate probability distributions: non-empty vectors of
+    /// non-negative weights that sum to one, to within rounding.
+    ///
+    /// Longer vectors come with larger sizes. Shrinking removes weights and
+    /// moves them towards zero or one, towards distributions such as
+    /// `[1.0]` or an even split, and every shrink still sums to one.
+    pub fn probability_vector() -> Self {
+        Self::vec_of(Gen::f64_range(0.0, 1.0)).map(|weights: Vec<f64>| {
+            let total: f64 = weights.iter().sum();
+            if weights.is_empty() {
+                vec![1.0]
+            } else if total == 0.0 {
+                vec![1.0 / weights.len() as f64; weights.len()]
+            } else {
+                weights.iter().map(|weight| weight / total).collect()
+            }
+        })
+    }
+
+    /// Generate vectors of `dimensions` values with a Euclidean norm of
+    /// one, to within rounding.
+    ///
+    /// Shrinking moves components towards zero and one, towards the axis
+    /// directions, and keeps the length and the norm.
+    ///
+    /// # Panics
+    /// Panics if `dimensions` is zero.
+    pub fn unit_vector(dimensions: usize) -> Self {
+        assert!(dimensions > 0, "a unit vector needs at least one dimension");
+        Self::vec_with_range(
+            crate::data::Range::constant(dimensions),
+            Gen::f64_range(-1.0, 1.0),
+        )
+        .map(|components: Vec<f64>| {
+            let norm = components.iter().map(|x| x * x).sum::<f64>().sqrt();
+            if norm == 0.0 {
+                let mut axis = vec![0.0; components.len()];
+                axis[0] = 1.0;
+                axis
+            } else {
+                components.iter().map(|x| x / norm).collect()
+            }
+        })
+    }
+
+    /// Generate vectors of strictly positive values no larger than `max`,
+    /// which must be positive.
+    ///
+    /// Values shrink towards one and one half, never to zero.
+    pub fn positive_f64(max: f64) -> Self {
+        Self::vec_of(Gen::f64_range(f64::MIN_POSITIVE, max))
+    }
+}
+
 impl<T> Gen<Option<T>>
 where
     T: 'static + Clone,
 {
     /// Generate optional values using the given generator.
+    /// Generates `None` 25% of the time.
     pub fn option_of(inner_gen: Gen<T>) -> Self {
+        let description = format!("option_of({})", inner_gen.label());
+        Self::option_of_weighted(inner_gen, 1, 3).describe(&description)
+    }
+
+    /// Generate optional values with `None` and `Some` in the ratio
+    /// `none_weight : some_weight`.
+    ///
+    /// `Some` values shrink to `None` (unless `none_weight` is zero) and then
+    /// through the inner value's shrinks.
+    ///
+    /// # Panics
+    /// Panics if both weights are zero.
+    pub fn option_of_weighted(inner_gen: Gen<T>, none_weight: u64, some_weight: u64) -> Self {
+        let total_weight = none_weight
+            .checked_add(some_weight)
+            .filter(|&total| total > 0)
+            .expect("option_of_weighted needs a non-zero total weight");
+
         Gen::new(move |size, seed| {
             let (choice_seed, value_seed) = seed.split();
-            let (choice, _) = choice_seed.next_bounded(4);
+            let (choice, _) = choice_seed.next_bounded(total_weight);
 
-            if choice == 0 {
-                // Generate None (25% chance)
+            if choice < none_weight {
                 Tree::singleton(None)
             } else {
-                // Generate Some(value) (75% chance)
                 let value_tree = inner_gen.generate(size, value_seed);
                 let some_value = Some(value_tree.value.clone());
 
                 // Shrink to None and shrink the inner value
-                let mut shrinks = vec![Tree::singleton(None)];
+                let mut shrinks = Vec::new();
+                if none_weight > 0 {
+                    shrinks.push(Tree::singleton(None));
+                }
 
                 // Add shrinks of the inner value wrapped in Some
                 for shrink in value_tree.shrinks() {
@@ -1561,6 +2570,57 @@ where
             Tree::with_children(tuple_value, shrinks)
         })
     }
+
+    /// Generate a value together with a second value derived from it.
+    ///
+    /// The second value's generator is built from the first value, e.g. an
+    /// index generator built from a generated `Vec`'s length. Shrinking
+    /// shrinks the first value before the second, and builds the second value
+    /// again from every shrunk first value, so the dependency always holds.
+    pub fn dependent<F>(base_gen: Gen<T>, f: F) -> Self
+    where
+        F: Fn(&T) -> Gen<U> + 'static,
+    {
+        Gen::new(move |size, seed| {
+            let (base_seed, dependent_seed) = seed.split();
+            let base_tree = base_gen.generate(size, base_seed);
+            dependent_tree(base_tree, &f, size, dependent_seed)
+        })
+    }
+}
+
+/// Substitute a tree for every value in another, as upstream Hedgehog's
+/// monadic bind does: the shrinks of the outer value come first, each bound
+/// again, followed by the shrinks of the substituted tree.
+fn bind_tree<T, U, F>(tree: Tree<T>, f: &F) -> Tree<U>
+where
+    F: Fn(&T) -> Tree<U>,
+{
+    let mut bound = f(&tree.value).or_origin(&tree);
+
+    let mut shrinks: Vec<Tree<U>> = tree
+        .children
+        .into_iter()
+        .map(|child| bind_tree(child, f))
+        .collect();
+    shrinks.append(&mut bound.children);
+    bound.children = shrinks;
+    bound
+}
+
+/// Pair every value in a tree with a value generated from it.
+fn dependent_tree<T, U, F>(base_tree: Tree<T>, f: &F, size: Size, seed: Seed) -> Tree<(T, U)>
+where
+    T: Clone,
+    U: Clone,
+    F: Fn(&T) -> Gen<U>,
+{
+    bind_tree(base_tree, &|base: &T| {
+        let base = base.clone();
+        f(&base)
+            .generate(size, seed)
+            .map(move |dependent| (base.clone(), dependent))
+    })
 }
 
 // 3-element tuple implementation
@@ -1811,23 +2871,53 @@ where
     /// Generate Result values using the given success and error generators.
     /// By default, generates Ok values 75% of the time and Err values 25% of the time.
     pub fn result_of(ok_gen: Gen<T>, err_gen: Gen<E>) -> Self {
+        Self::result_of_weights(ok_gen, err_gen, 3, 1)
+    }
+
+    /// Generate Result values with custom success/error ratio.
+    /// Generates Ok and Err in the ratio `ok_weight : 1`.
+    pub fn result_of_weighted(ok_gen: Gen<T>, err_gen: Gen<E>, ok_weight: u64) -> Self {
+        Self::result_of_weights(ok_gen, err_gen, ok_weight, 1)
+    }
+
+    /// Generate Result values with Ok and Err in the ratio
+    /// `ok_weight : err_weight`.
+    ///
+    /// `Err` values shrink to a small `Ok` (unless `ok_weight` is zero) and
+    /// then through the error's shrinks; `Ok` values shrink through the inner
+    /// value's shrinks.
+    ///
+    /// # Panics
+    /// Panics if both weights are zero.
+    pub fn result_of_weights(
+        ok_gen: Gen<T>,
+        err_gen: Gen<E>,
+        ok_weight: u64,
+        err_weight: u64,
+    ) -> Self {
+        let total_weight = ok_weight
+            .checked_add(err_weight)
+            .filter(|&total| total > 0)
+            .expect("result_of_weights needs a non-zero total weight");
+
         Gen::new(move |size, seed| {
             let (choice_seed, value_seed) = seed.split();
-            let (choice, _) = choice_seed.next_bounded(4);
+            let (choice, _) = choice_seed.next_bounded(total_weight);
 
-            if choice == 0 {
-                // Generate Err (25% chance)
+            if choice < err_weight {
+                // Generate Err
                 let err_tree = err_gen.generate(size, value_seed);
                 let err_value = Err(err_tree.value.clone());
 
-                // Shrinking strategy: try shrinking the error, but prioritize Ok values
                 let mut shrinks = Vec::new();
 
-                // Try to shrink to a simple Ok value if possible
-                // We use a minimal seed to generate a simple success case
-                let (ok_seed, _) = value_seed.split();
-                let ok_tree = ok_gen.generate(Size::new(0), ok_seed);
-                shrinks.push(Tree::singleton(Ok(ok_tree.value.clone())));
+                // Try to shrink to a simple Ok value if the generator makes them,
+                // using a minimal size to generate a simple success case
+                if ok_weight > 0 {
+                    let (ok_seed, _) = value_seed.split();
+                    let ok_tree = ok_gen.generate(Size::new(0), ok_seed);
+                    shrinks.push(Tree::singleton(Ok(ok_tree.value.clone())));
+                }
 
                 // Add shrinks of the error value wrapped in Err
                 for shrink in err_tree.shrinks() {
@@ -1836,14 +2926,11 @@ where
 
                 Tree::with_children(err_value, shrinks)
             } else {
-                // Generate Ok (75% chance)
+                // Generate Ok, shrinking the inner value but keeping it Ok
                 let ok_tree = ok_gen.generate(size, value_seed);
                 let ok_value = Ok(ok_tree.value.clone());
 
-                // Shrinking strategy: shrink the inner value, but keep it as Ok
                 let mut shrinks = Vec::new();
-
-                // Add shrinks of the inner value wrapped in Ok
                 for shrink in ok_tree.shrinks() {
                     shrinks.push(Tree::singleton(Ok(shrink.clone())));
                 }
@@ -1853,45 +2940,16 @@ where
         })
     }
 
-    /// Generate Result values with custom success/error ratio.
-    /// `ok_weight` should be between 1-10, higher values favor Ok results.
-    pub fn result_of_weighted(ok_gen: Gen<T>, err_gen: Gen<E>, ok_weight: u64) -> Self {
-        let total_weight = ok_weight + 1; // Error always has weight 1
-        Gen::new(move |size, seed| {
-            let (choice_seed, value_seed) = seed.split();
-            let (choice, _) = choice_seed.next_bounded(total_weight);
-
-            if choice < ok_weight {
-                // Generate Ok
-                let ok_tree = ok_gen.generate(size, value_seed);
-                let ok_value = Ok(ok_tree.value.clone());
-
-                let mut shrinks = Vec::new();
-                for shrink in ok_tree.shrinks() {
-                    shrinks.push(Tree::singleton(Ok(shrink.clone())));
-                }
-
-                Tree::with_children(ok_value, shrinks)
-            } else {
-                // Generate Err
-                let err_tree = err_gen.generate(size, value_seed);
-                let err_value = Err(err_tree.value.clone());
-
-                let mut shrinks = Vec::new();
-
-                // Try to shrink to a simple Ok value
-                let (ok_seed, _) = value_seed.split();
-                let ok_tree = ok_gen.generate(Size::new(0), ok_seed);
-                shrinks.push(Tree::singleton(Ok(ok_tree.value.clone())));
-
-                // Add shrinks of the error value
-                for shrink in err_tree.shrinks() {
-                    shrinks.push(Tree::singleton(Err(shrink.clone())));
-                }
+    /// Generate mostly errors: Err three times as often as Ok. For exercising
+    /// error handling paths.
+    pub fn result_of_error_biased(ok_gen: Gen<T>, err_gen: Gen<E>) -> Self {
+        Self::result_of_weights(ok_gen, err_gen, 1, 3)
+    }
 
-                Tree::with_children(err_value, shrinks)
-            }
-        })
+    /// Generate errors 1% of the time, closer to the error rate of a
+    /// healthy system.
+    pub fn result_of_rare_errors(ok_gen: Gen<T>, err_gen: Gen<E>) -> Self {
+        Self::result_of_weights(ok_gen, err_gen, 99, 1)
     }
 }
 
@@ -2262,6 +3320,65 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_int_and_vec_shrinks_are_most_aggressive_first() {
+        for seed in 0..20 {
+            let tree = Gen::int_range(-1000, 1000).generate(Size::new(50), Seed::from_u64(seed));
+            let distances: Vec<_> = tree.shrinks().iter().map(|n| n.abs()).collect();
+            assert!(
+                distances.windows(2).all(|pair| pair[0] <= pair[1]),
+                "{distances:?}"
+            );
+
+            let gen = Gen::<Vec<i32>>::vec_of(Gen::int_range(0, 100));
+            let tree = gen.generate(Size::new(20), Seed::from_u64(seed));
+            let shrinks = tree.shrinks();
+            let lengths: Vec<_> = shrinks.iter().map(|shrink| shrink.len()).collect();
+            assert!(
+                lengths.windows(2).all(|pair| pair[0] <= pair[1]),
+                "{lengths:?}"
+            );
+
+            // Every element moves to its origin before any takes a smaller step
+            let zeroed: Vec<usize> = shrinks
+                .iter()
+                .skip_while(|shrink| shrink.len() < tree.value.len())
+                .take(tree.value.iter().filter(|&&n| n != 0).count())
+                .map(|shrink| {
+                    let changed: Vec<_> = (0..shrink.len())
+                        .filter(|&i| shrink[i] != tree.value[i])
+                        .collect();
+                    assert_eq!(changed.len(), 1);
+                    assert_eq!(shrink[changed[0]], 0);
+                    changed[0]
+                })
+                .collect();
+            assert!(
+                zeroed.windows(2).all(|pair| pair[0] < pair[1]),
+                "{zeroed:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_string_shrinks_are_distinct_and_shortest_first() {
+        let gens = [
+            Gen::<String>::string_of(Gen::constant('a')),
+            Gen::<String>::with_range(Range::linear(0, 20), Gen::constant('a')),
+        ];
+        for gen in gens {
+            for seed in 0..20 {
+                let tree = gen.generate(Size::new(20), Seed::from_u64(seed));
+                let shrinks = tree.shrinks();
+                let distinct: std::collections::HashSet<_> = shrinks.iter().collect();
+                assert_eq!(distinct.len(), shrinks.len(), "{shrinks:?}");
+                assert!(shrinks
+                    .windows(2)
+                    .all(|pair| pair[0].len() <= pair[1].len()));
+            }
+        }
+    }
+
     #[test]
     fn test_vector_element_wise_shrinking() {
         let gen = Gen::<Vec<i32>>::vec_of(Gen::int_range(10, 20));
@@ -2595,6 +3712,501 @@ mod tests {
         assert!(tree.value == "hello" || tree.value == "world" || tree.value == "test");
     }
 
+    #[test]
+    fn test_try_map_discards_failed_conversions() {
+        take_generation_discards();
+        let gen = Gen::int_range(0, 100).try_map(|n| u8::try_from(n * 3));
+
+        for seed in 0..50 {
+            let tree = gen.generate(Size::new(100), Seed::from_u64(seed));
+            assert_eq!(tree.value % 3, 0);
+            assert!(tree.shrinks().into_iter().all(|v| v % 3 == 0));
+        }
+        assert!(take_generation_discards() > 0);
+        assert_eq!(take_generation_discards(), 0);
+    }
+
+    #[test]
+    fn test_dependent_keeps_index_valid_under_shrinking() {
+        let gen = Gen::dependent(
+            Gen::vec_of(Gen::int_range(0, 100)).filter(|xs| !xs.is_empty()),
+            |xs: &Vec<i32>| Gen::usize_range(0, xs.len() - 1),
+        );
+
+        for seed in 0..20 {
+            let tree = gen.generate(Size::new(20), Seed::from_u64(seed));
+            let (xs, index) = &tree.value;
+            assert!(index < &xs.len());
+            for (xs, index) in tree.shrinks() {
+                assert!(*index < xs.len(), "index {index} out of bounds for {xs:?}");
+            }
+        }
+    }
+
+    #[test]
+    fn test_bind_shrinks_first_value_first_and_coherently() {
+        let gen = Gen::int_range(5, 50).bind(|x| Gen::int_range(0, x).map(move |y| (x, y)));
+
+        for seed in 0..20 {
+            let tree = gen.generate(Size::new(30), Seed::from_u64(seed));
+            let (x, _) = tree.value;
+
+            // Every shrink, however deep, keeps the dependent value in range
+            for (_, shrink) in tree.bfs() {
+                let (x, y) = shrink.value;
+                assert!((0..=x).contains(&y), "{y} out of range for {x}");
+            }
+
+            // Shrinks of the first value come before shrinks of the second
+            let firsts: Vec<i32> = tree.children.iter().map(|child| child.value.0).collect();
+            let switch = firsts.iter().position(|&first| first == x);
+            if let Some(switch) = switch {
+                assert!(firsts[switch..].iter().all(|&first| first == x));
+            }
+            if x != 5 {
+                assert_ne!(firsts.first(), Some(&x));
+            }
+        }
+    }
+
+    #[test]
+    fn test_weighted_option_and_result() {
+        let count = |gen: &Gen<Option<i32>>| {
+            (0..1000)
+                .filter(|&seed| {
+                    gen.generate(Size::new(10), Seed::from_u64(seed))
+                        .value
+                        .is_none()
+                })
+                .count()
+        };
+        let mostly_none = Gen::<Option<i32>>::option_of_weighted(Gen::int_range(1, 9), 9, 1);
+        assert!(count(&mostly_none) > 800);
+
+        let never_none = Gen::<Option<i32>>::option_of_weighted(Gen::int_range(1, 9), 0, 1);
+        assert_eq!(count(&never_none), 0);
+        let tree = never_none.generate(Size::new(10), Seed::from_u64(0));
+        assert!(tree.shrinks().iter().all(|shrink| shrink.is_some()));
+
+        let errors = |gen: Gen<Result<i32, i32>>| {
+            (0..1000)
+                .filter(|&seed| {
+                    gen.generate(Size::new(10), Seed::from_u64(seed))
+                        .value
+                        .is_err()
+                })
+                .count()
+        };
+        let ok = || Gen::int_range(0, 9);
+        let err = || Gen::int_range(-9, -1);
+        assert!(errors(Gen::result_of_error_biased(ok(), err())) > 650);
+        assert!(errors(Gen::result_of_rare_errors(ok(), err())) < 50);
+        assert_eq!(errors(Gen::result_of_weights(ok(), err(), 1, 0)), 0);
+
+        let only_errors = Gen::<Result<i32, i32>>::result_of_weights(ok(), err(), 0, 1);
+        let tree = only_errors.generate(Size::new(10), Seed::from_u64(3));
+        assert!(tree.shrinks().iter().all(|shrink| shrink.is_err()));
+    }
+
+    #[test]
+    #[should_panic(expected = "non-zero total weight")]
+    fn test_weighted_option_rejects_zero_weights() {
+        Gen::<Option<i32>>::option_of_weighted(Gen::int_range(1, 9), 0, 0);
+    }
+
+    #[test]
+    fn test_edge_cases_for_ranges() {
+        assert_eq!(
+            i32::edge_cases(&Range::new(-10, 10)),
+            vec![0, -1, 1, -2, 2, -4, 4, -8, 8, -9, 9, -10, 10]
+        );
+        assert_eq!(u8::edge_cases(&Range::new(5, 9)), vec![5, 6, 8, 9]);
+        assert_eq!(
+            u8::edge_cases(&Range::new(5, 9).with_origin(8)),
+            vec![8, 9, 6, 5]
+        );
+        assert_eq!(i8::edge_cases(&Range::new(-128, 127)).first(), Some(&0));
+        assert!(i8::edge_cases(&Range::new(-128, 127)).contains(&-128));
+        assert!(u64::edge_cases(&Range::new(0, u64::MAX)).contains(&(1 << 63)));
+        assert!(u8::edge_cases(&Range::new(9, 5)).is_empty());
+    }
+
+    #[test]
+    fn test_edge_bias_injects_boundaries() {
+        let range = Range::new(-1000, 1000).with_edge_bias(1.0);
+        let gen = Gen::<i32>::from_range(range);
+        let edges = i32::edge_cases(&range);
+
+        let mut seen = std::collections::HashSet::new();
+        for seed in 0..500 {
+            let tree = gen.generate(Size::new(50), Seed::from_u64(seed));
+            assert!(edges.contains(&tree.value));
+            assert_eq!(
+                tree.shrinks().into_iter().copied().collect::<Vec<_>>(),
+                i32::shrinks_within(&range, tree.value)
+            );
+            seen.insert(tree.value);
+        }
+        assert!(seen.contains(&-1000) && seen.contains(&1000) && seen.contains(&999));
+
+        // Edges shrink like other values, not only to simpler edges
+        let shrinks = i32::shrinks_within(&range, 512);
+        assert_eq!(shrinks.first(), Some(&0));
+        assert!(shrinks.contains(&384) && shrinks.contains(&511));
+        let shifted = Range::new(100, 1000).with_origin(200);
+        assert_eq!(u32::shrinks_within(&shifted, 512).first(), Some(&200));
+
+        let unbiased = Gen::<u8>::from_range(Range::new(1, 5));
+        let biased = Gen::<u8>::from_range(Range::new(1, 5).with_edge_bias(0.0));
+        for seed in 0..20 {
+            let value = unbiased.generate(Size::new(50), Seed::from_u64(seed)).value;
+            assert!((1..=5).contains(&value));
+            let same = biased.generate(Size::new(50), Seed::from_u64(seed)).value;
+            assert_eq!(value, same);
+        }
+    }
+
+    #[test]
+    fn test_length_edge_bias_generates_the_bounds() {
+        let gen = Gen::vec_with_range(Range::new(2, 12).with_edge_bias(1.0), Gen::int_range(0, 9));
+
+        let lengths: std::collections::HashSet<_> = (0..50)
+            .map(|seed| {
+                gen.generate(Size::new(12), Seed::from_u64(seed))
+                    .value
+                    .len()
+            })
+            .collect();
+        assert_eq!(lengths, [2, 12].into_iter().collect());
+    }
+
+    #[test]
+    fn test_vec_with_range_respects_bounds_and_origin() {
+        let range = crate::data::Range::exponential(10, 1000).with_origin(20);
+        let gen = Gen::vec_with_range(range, Gen::int_range(0, 9));
+
+        for seed in 0..50 {
+            let tree = gen.generate(Size::new(10), Seed::from_u64(seed));
+            assert!((10..=1000).contains(&tree.value.len()));
+            for shrink in tree.shrinks() {
+                assert!(shrink.len() >= 10);
+            }
+            if tree.value.len() > 20 {
+                assert_eq!(*tree.shrinks()[0], tree.value[..20].to_vec());
+            }
+        }
+    }
+
+    #[test]
+    fn test_char_range_stays_in_range() {
+        let gen = Gen::char_range('\u{4E00}', '\u{9FFF}');
+        for seed in 0..50 {
+            let tree = gen.generate(Size::new(10), Seed::from_u64(seed));
+            assert!(('\u{4E00}'..='\u{9FFF}').contains(&tree.value));
+            for shrink in tree.shrinks() {
+                assert!(('\u{4E00}'..=tree.value).contains(shrink));
+            }
+            if tree.value != '\u{4E00}' {
+                assert_eq!(*tree.shrinks()[0], '\u{4E00}');
+            }
+        }
+    }
+
+    #[test]
+    fn test_char_range_skips_surrogates() {
+        // Only the two characters on either side of the surrogate block
+        let gen = Gen::char_range('\u{D7FF}', '\u{E000}');
+        let seen: std::collections::HashSet<_> = (0..50)
+            .map(|seed| gen.generate(Size::new(10), Seed::from_u64(seed)).value)
+            .collect();
+        assert_eq!(seen, ['\u{D7FF}', '\u{E000}'].into_iter().collect());
+
+        assert_eq!(from_scalar_index(scalar_index(char::MAX)), char::MAX);
+        assert_eq!(from_scalar_index(scalar_index('\u{E000}')), '\u{E000}');
+    }
+
+    #[test]
+    fn test_char_ranges_shrink_towards_first_range() {
+        let gen = Gen::char_ranges(vec![('a', 'c'), ('\u{2500}', '\u{257F}')]);
+        for seed in 0..50 {
+            let tree = gen.generate(Size::new(10), Seed::from_u64(seed));
+            let ch = tree.value;
+            assert!(('a'..='c').contains(&ch) || ('\u{2500}'..='\u{257F}').contains(&ch));
+            if ch != 'a' {
+                assert_eq!(*tree.shrinks()[0], 'a');
+            }
+        }
+    }
+
+    #[test]
+    fn test_with_dictionary_mixes_in_values() {
+        let dictionary = vec![-1, 1_000_000, 7];
+        let gen = Gen::int_range(10, 20).with_dictionary(dictionary.clone(), 0.5);
+
+        let mut from_dictionary = 0;
+        for seed in 0..200 {
+            let tree = gen.generate(Size::new(10), Seed::from_u64(seed));
+            if let Some(index) = dictionary.iter().position(|&d| d == tree.value) {
+                from_dictionary += 1;
+                let expected: Vec<_> = dictionary[..index].iter().collect();
+                assert_eq!(tree.shrinks(), expected);
+            } else {
+                assert!((10..=20).contains(&tree.value));
+            }
+        }
+        assert!((50..150).contains(&from_dictionary), "{from_dictionary}");
+
+        let never = Gen::int_range(10, 20).with_dictionary(dictionary, 0.0);
+        let unchanged = Gen::int_range(10, 20).with_dictionary(Vec::new(), 1.0);
+        for seed in 0..50 {
+            assert!((10..=20).contains(&never.generate(Size::new(10), Seed::from_u64(seed)).value));
+            assert!((10..=20).contains(
+                &unchanged
+                    .generate(Size::new(10), Seed::from_u64(seed))
+                    .value
+            ));
+        }
+    }
+
+    #[test]
+    fn test_lazy_builds_once_and_defer_every_time() {
+        use std::rc::Rc;
+
+        let lazy_builds = Rc::new(Cell::new(0));
+        let counter = Rc::clone(&lazy_builds);
+        let lazy = Gen::lazy(move || {
+            counter.set(counter.get() + 1);
+            Gen::int_range(0, 9)
+        });
+        assert_eq!(lazy_builds.get(), 0);
+
+        let deferred_builds = Rc::new(Cell::new(0));
+        let counter = Rc::clone(&deferred_builds);
+        let deferred = Gen::defer(move || {
+            counter.set(counter.get() + 1);
+            Gen::int_range(0, 9)
+        });
+
+        for seed in 0..5 {
+            let tree = lazy.generate(Size::new(10), Seed::from_u64(seed));
+            assert!((0..=9).contains(&tree.value));
+            deferred.generate(Size::new(10), Seed::from_u64(seed));
+        }
+        assert_eq!(lazy_builds.get(), 1);
+        assert_eq!(deferred_builds.get(), 5);
+    }
+
+    #[test]
+    fn test_lazy_allows_mutually_recursive_generators() {
+        #[derive(Debug, Clone)]
+        enum Rose {
+            Leaf,
+            Node(Vec<Forest>),
+        }
+
+        #[derive(Debug, Clone)]
+        struct Forest(Vec<Rose>);
+
+        fn rose() -> Gen<Rose> {
+            Gen::frequency(vec![
+                WeightedChoice::new(3, Gen::constant(Rose::Leaf)),
+                WeightedChoice::new(
+                    1,
+                    Gen::lazy(|| Gen::<Vec<Forest>>::vec_of(forest())).map(Rose::Node),
+                ),
+            ])
+            .unwrap()
+        }
+
+        fn forest() -> Gen<Forest> {
+            Gen::lazy(|| Gen::<Vec<Rose>>::vec_of(rose()).map(Forest))
+        }
+
+        fn depth(rose: &Rose) -> usize {
+            match rose {
+                Rose::Leaf => 0,
+                Rose::Node(forests) => {
+                    1 + forests
+                        .iter()
+                        .flat_map(|forest| forest.0.iter().map(depth))
+                        .max()
+                        .unwrap_or(0)
+                }
+            }
+        }
+
+        let gen = rose();
+        let deepest = (0..50)
+            .map(|seed| depth(&gen.generate(Size::new(3), Seed::from_u64(seed)).value))
+            .max()
+            .unwrap();
+        assert!(deepest > 0);
+    }
+
+    #[test]
+    fn test_recursive_weighted_terminates_and_shrinks_to_leaves() {
+        #[derive(Debug, Clone, PartialEq)]
+        enum Tree2 {
+            Leaf(i32),
+            Node(Box<Tree2>, Box<Tree2>),
+        }
+
+        fn tree() -> Gen<Tree2> {
+            // Recursion is twice as likely as a leaf at full size
+            let node = Gen::<(Tree2, Tree2)>::tuple_of(Gen::defer(tree), Gen::defer(tree))
+                .map(|(left, right)| Tree2::Node(Box::new(left), Box::new(right)));
+            Gen::recursive_weighted(
+                vec![WeightedChoice::new(
+                    1,
+                    Gen::int_range(0, 9).map(Tree2::Leaf),
+                )],
+                vec![WeightedChoice::new(2, node)],
+            )
+            .unwrap()
+        }
+
+        fn depth(tree: &Tree2) -> usize {
+            match tree {
+                Tree2::Leaf(_) => 0,
+                Tree2::Node(left, right) => 1 + depth(left).max(depth(right)),
+            }
+        }
+
+        let gen = tree();
+        let mut deepest = 0;
+        for seed in 0..100 {
+            let seed = Seed::from_u64(seed);
+            assert!(matches!(
+                gen.generate(Size::new(0), seed).value,
+                Tree2::Leaf(_)
+            ));
+
+            let generated = gen.generate(Size::new(100), seed);
+            deepest = deepest.max(depth(&generated.value));
+            if let Tree2::Node(..) = generated.value {
+                assert!(matches!(generated.children[0].value, Tree2::Leaf(_)));
+            }
+        }
+        // Sizes 100, 61, 37, 22, 13, 8, 4, 2, 1 can recurse; 0 can't
+        assert!(deepest > 1 && deepest <= 9);
+
+        assert!(Gen::<i32>::recursive_weighted(vec![], vec![]).is_err());
+        assert!(Gen::recursive_weighted(
+            vec![WeightedChoice::new(0, Gen::constant(1))],
+            vec![WeightedChoice::new(1, Gen::constant(2))],
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn test_character_class_generators() {
+        for seed in 0..50 {
+            let seed = Seed::from_u64(seed);
+            let size = Size::new(20);
+
+            assert!(Gen::<String>::hex()
+                .generate(size, seed)
+                .value
+                .chars()
+                .all(|ch| ch.is_ascii_hexdigit()));
+            assert!(Gen::<String>::base64()
+                .generate(size, seed)
+                .value
+                .chars()
+                .all(|ch| ch.is_ascii_alphanumeric() || ch == '+' || ch == '/'));
+            assert!(Gen::<String>::base64_url()
+                .generate(size, seed)
+                .value
+                .chars()
+                .all(|ch| ch.is_ascii_alphanumeric() || ch == '-' || ch == '_'));
+            assert!(Gen::<String>::control()
+                .generate(size, seed)
+                .value
+                .chars()
+                .all(char::is_control));
+
+            let whitespace = Gen::<String>::whitespace().generate(size, seed);
+            assert!(whitespace.value.chars().all(char::is_whitespace));
+            for shrink in whitespace.shrinks() {
+                assert!(shrink.chars().all(char::is_whitespace));
+            }
+
+            let ch = Gen::<char>::whitespace().generate(size, seed);
+            if ch.value != ' ' {
+                assert_eq!(*ch.shrinks()[0], ' ');
+            }
+        }
+
+        assert!(WHITESPACE_CHARS.iter().all(|ch| ch.is_whitespace()));
+        let seen_crlf = (0..100).any(|seed| {
+            Gen::<String>::whitespace()
+                .generate(Size::new(20), Seed::from_u64(seed))
+                .value
+                .contains("\r\n")
+        });
+        assert!(seen_crlf);
+    }
+
+    #[test]
+    fn test_flatten_generates_from_chosen_generator() {
+        let gen = Gen::bool()
+            .map(|small| {
+                if small {
+                    Gen::int_range(0, 9)
+                } else {
+                    Gen::int_range(100, 109)
+                }
+            })
+            .flatten();
+
+        for seed in 0..20 {
+            let tree = gen.generate(Size::new(10), Seed::from_u64(seed));
+            assert!((0..=9).contains(&tree.value) || (100..=109).contains(&tree.value));
+        }
+
+        // Shrinks the value within the chosen generator before the choice
+        let tree = Gen::constant(0)
+            .map(|_| Gen::int_range(100, 109))
+            .flatten()
+            .generate(Size::new(10), Seed::from_u64(3));
+        assert!(tree.shrinks().into_iter().all(|v| (100..=109).contains(v)));
+    }
+
+    #[test]
+    fn test_choice_lazy_builds_only_chosen_generator() {
+        use std::cell::Cell;
+        use std::rc::Rc;
+
+        let built = Rc::new(Cell::new(0));
+        let constructor = |value: i32| -> Box<dyn Fn() -> Gen<i32>> {
+            let built = Rc::clone(&built);
+            Box::new(move || {
+                built.set(built.get() + 1);
+                Gen::constant(value)
+            })
+        };
+        let gen = Gen::choice_lazy(vec![constructor(0), constructor(1), constructor(2)])
+            .expect("valid choice_lazy generator");
+        assert_eq!(built.get(), 0);
+
+        for seed in 0..20 {
+            built.set(0);
+            let tree = gen.generate(Size::new(10), Seed::from_u64(seed));
+
+            // One constructor for the choice, one per earlier choice to shrink to
+            assert_eq!(built.get(), 1 + tree.shrinks().len());
+            assert!(tree.shrinks().into_iter().all(|&v| v < tree.value));
+            if tree.value > 0 {
+                assert_eq!(*tree.shrinks()[0], 0);
+            }
+        }
+
+        assert!(matches!(
+            Gen::<i32>::choice_lazy(vec![]),
+            Err(crate::HedgehogError::InvalidGenerator { .. })
+        ));
+    }
+
     #[test]
     fn test_frequency_errors() {
         // Test empty choices list
@@ -2657,6 +4269,31 @@ mod tests {
         assert_eq!(const_val, 0);
     }
 
+    #[test]
+    fn test_sample_with_is_reproducible() {
+        let gen = Gen::int_range(0, 1_000_000);
+        let seed = Seed::from_u64(7);
+        assert_eq!(
+            gen.sample_with(Size::new(10), seed),
+            gen.sample_with(Size::new(10), seed)
+        );
+
+        let sizes = Gen::new(|size, _seed| Tree::singleton(size.get()));
+        assert_eq!(sizes.sample_with(Size::new(3), seed), 3);
+        assert_eq!(sizes.sample(), 30);
+    }
+
+    #[test]
+    fn test_samples_vary() {
+        let gen = Gen::int_range(0, 1_000_000);
+        let values: Vec<i32> = gen.samples(20).collect();
+        assert_eq!(values.len(), 20);
+        assert!(values.iter().any(|&value| value != values[0]));
+
+        let first: Vec<i32> = (0..20).map(|_| gen.sample_random()).collect();
+        assert!(first.iter().any(|&value| value != first[0]));
+    }
+
     #[test]
     fn test_function_generator() {
         // Test function_of generator
@@ -2936,16 +4573,27 @@ mod tests {
     fn test_from_elements_shrinking() {
         let elements = vec![1, 2, 3, 4, 5];
         let gen = Gen::from_elements(elements).unwrap();
-        let tree = gen.generate(crate::data::Size::new(10), crate::data::Seed::random());
+        for n in 0..50 {
+            let tree = gen.generate(crate::data::Size::new(10), crate::data::Seed::from_u64(n));
 
-        // Should have shrinks to other elements
-        let shrinks = tree.shrinks();
-        assert!(!shrinks.is_empty());
-
-        // All shrinks should be from the original elements
-        for &shrink in shrinks {
-            assert!([1, 2, 3, 4, 5].contains(&shrink));
+            // Shrinks go to earlier elements only, the first one first
+            let shrinks = tree.shrinks();
+            assert!(shrinks.iter().all(|&&shrink| shrink < tree.value));
+            match tree.value {
+                1 => assert!(shrinks.is_empty()),
+                _ => assert_eq!(shrinks.first(), Some(&&1)),
+            }
         }
+
+        let by_length =
+            Gen::from_elements_by_key(vec!["ccc", "a", "bb", "d"], |s| s.len()).unwrap();
+        let shrunk = crate::shrinking::minimal_counterexample(
+            &by_length,
+            |s: &&str| s.len() < 2,
+            &crate::data::Config::default(),
+        )
+        .expect("some element should be long");
+        assert_eq!(*shrunk.minimal(), "bb");
     }
 
     #[test]
@@ -3087,6 +4735,34 @@ mod tests {
         assert!(domains.len() > 3);
     }
 
+    #[test]
+    fn test_float_vectors_keep_their_invariants_while_shrinking() {
+        fn check(gen: &Gen<Vec<f64>>, holds: impl Fn(&[f64]) -> bool) {
+            for seed in 0..50 {
+                let tree = gen.generate(Size::new(20), Seed::from_u64(seed));
+                for values in std::iter::once(&tree.value).chain(tree.shrinks()) {
+                    assert!(values.iter().all(|x| x.is_finite()), "{values:?}");
+                    assert!(holds(values), "{values:?}");
+                }
+            }
+        }
+        let close_to_one = |x: f64| (x - 1.0).abs() < 1e-9;
+
+        check(
+            &Gen::<Vec<f64>>::sorted_f64(Gen::f64_range(-5.0, 5.0)),
+            |xs| xs.windows(2).all(|pair| pair[0] <= pair[1]),
+        );
+        check(&Gen::<Vec<f64>>::probability_vector(), |xs| {
+            !xs.is_empty() && xs.iter().all(|&p| p >= 0.0) && close_to_one(xs.iter().sum())
+        });
+        check(&Gen::<Vec<f64>>::unit_vector(3), |xs| {
+            xs.len() == 3 && close_to_one(xs.iter().map(|x| x * x).sum::<f64>().sqrt())
+        });
+        check(&Gen::<Vec<f64>>::positive_f64(100.0), |xs| {
+            xs.iter().all(|&x| x > 0.0 && x <= 100.0)
+        });
+    }
+
     #[test]
     fn test_email_address_generator() {
         let gen = Gen::<String>::email_address();
@@ -3115,6 +4791,34 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_trivial_generators() {
+        use std::cmp::Ordering;
+        let seed = crate::data::Seed::from_u64(3);
+        let size = crate::data::Size::new(10);
+
+        assert_eq!(Gen::unit_value().generate(size, seed).value, ());
+        let _: std::marker::PhantomData<String> = Gen::phantom().generate(size, seed).value;
+        assert_eq!(
+            Gen::<Result<u8, std::convert::Infallible>>::infallible(Gen::constant(7))
+                .generate(size, seed)
+                .value,
+            Ok(7)
+        );
+
+        let mut seen = Vec::new();
+        for i in 0..30 {
+            let tree = Gen::<Ordering>::ordering().generate(size, crate::data::Seed::from_u64(i));
+            if tree.value != Ordering::Equal {
+                assert_eq!(tree.shrinks(), vec![&Ordering::Equal]);
+            }
+            if !seen.contains(&tree.value) {
+                seen.push(tree.value);
+            }
+        }
+        assert_eq!(seen.len(), 3);
+    }
+
     #[test]
     fn test_sql_identifier_safe() {
         let gen = Gen::<String>::sql_identifier(false);
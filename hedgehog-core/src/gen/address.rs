@@ -0,0 +1,473 @@
+//! Internationalized and pathological domain names and email addresses.
+//!
+//! Validators written against `user@example.com` tend to reject addresses
+//! that are perfectly legal: Unicode domains and their `xn--` punycode
+//! forms, `+tag` sub-addresses, quoted local parts containing spaces or an
+//! `@`, and names right at the length limits. How far into that territory
+//! to go is chosen with [`AddressStrictness`].
+
+use super::{Draw, Gen};
+use crate::tree::Tree;
+
+/// How unusual the domains and addresses from
+/// [`Gen::web_domain_with`] and [`Gen::email_address_with`] get.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AddressStrictness {
+    /// Lowercase ASCII names under common TLDs, as from
+    /// [`Gen::web_domain`] and [`Gen::email_address`]
+    Common,
+    /// Also Unicode labels, in both their Unicode and `xn--` punycode
+    /// forms, subdomains, dotted local parts and `+tag` sub-addresses
+    International,
+    /// Also quoted local parts, every special character a local part may
+    /// hold, single-character and all-digit labels, fully qualified names
+    /// with a trailing dot, and names at the maximum lengths: 63 bytes per
+    /// label, 253 per domain, 64 per local part and 254 per address
+    Pathological,
+}
+
+/// The longest a DNS label may be, in bytes.
+const MAX_LABEL: usize = 63;
+/// The longest a domain name may be, in bytes, without a trailing dot.
+const MAX_DOMAIN: usize = 253;
+/// The longest the local part of an address may be, in bytes.
+const MAX_LOCAL: usize = 64;
+/// The longest an address may be, in bytes.
+const MAX_ADDRESS: usize = 254;
+
+/// Words in a range of scripts, for labels and local parts.
+const UNICODE_WORDS: &[&str] = &[
+    "bücher",
+    "münchen",
+    "café",
+    "ñandú",
+    "пример",
+    "почта",
+    "δοκιμή",
+    "例え",
+    "テスト",
+    "中国",
+    "مثال",
+    "उदाहरण",
+    "실례",
+];
+
+const ASCII_TLDS: &[&str] = &["com", "org", "net", "io", "de", "jp"];
+const UNICODE_TLDS: &[&str] = &["рф", "中国", "テスト", "ευ", "भारत"];
+
+/// Characters other than letters and digits allowed unquoted in a local part.
+const LOCAL_SPECIALS: &str = "!#$%&'*+-/=?^_`{|}~";
+
+/// Local parts that are only legal quoted.
+const QUOTED_LOCALS: &[&str] = &[
+    "\"john doe\"",
+    "\"a@b\"",
+    "\"\"",
+    "\"quote\\\"d\"",
+    "\"..\"",
+    "\"back\\\\slash\"",
+];
+
+impl Gen<String> {
+    /// Generate domain names, as unusual as `strictness` allows.
+    ///
+    /// Shrinks towards `a.com`: first the whole name, then dropping
+    /// subdomains, simplifying each label and the TLD, and dropping a
+    /// trailing dot.
+    ///
+    /// # Example
+    /// ```rust
+    /// use hedgehog_core::*;
+    ///
+    /// // Includes names like "bücher.de" and "xn--bcher-kva.de"
+    /// let gen = Gen::<String>::web_domain_with(AddressStrictness::International);
+    /// ```
+    pub fn web_domain_with(strictness: AddressStrictness) -> Self {
+        if strictness == AddressStrictness::Common {
+            return Gen::<String>::web_domain();
+        }
+        Gen::new(move |_size, seed| {
+            let domain = Domain::draw(&mut Draw(seed), strictness, MAX_DOMAIN);
+            let shrinks = domain
+                .shrinks()
+                .iter()
+                .map(|shrink| Tree::singleton(shrink.to_string()))
+                .collect();
+            Tree::with_children(domain.to_string(), shrinks)
+        })
+    }
+
+    /// Generate email addresses, as unusual as `strictness` allows.
+    ///
+    /// The domain is drawn as by [`web_domain_with`](Self::web_domain_with).
+    /// Shrinks towards `a@a.com`: first the whole address, then the local
+    /// part to `a`, then the domain as domains shrink.
+    ///
+    /// # Example
+    /// ```rust
+    /// use hedgehog_core::*;
+    ///
+    /// // Includes addresses like "\"john doe\"@example.com" and "user+tag@例え.jp"
+    /// let gen = Gen::<String>::email_address_with(AddressStrictness::Pathological);
+    /// ```
+    pub fn email_address_with(strictness: AddressStrictness) -> Self {
+        if strictness == AddressStrictness::Common {
+            return Gen::<String>::email_address();
+        }
+        Gen::new(move |_size, seed| {
+            let mut draw = Draw(seed);
+            let local = local_part(&mut draw, strictness);
+            let budget = (MAX_ADDRESS - local.len() - 1).min(MAX_DOMAIN);
+            let domain = Domain {
+                // Addresses can't end in a dot
+                trailing_dot: false,
+                ..Domain::draw(&mut draw, strictness, budget)
+            };
+
+            let address = |local: &str, domain: &Domain| format!("{local}@{domain}");
+            let mut shrinks = vec![address("a", &Domain::simplest())];
+            if local != "a" {
+                shrinks.push(address("a", &domain));
+            }
+            for shrink in domain.shrinks() {
+                shrinks.push(address(&local, &shrink));
+            }
+            shrinks.dedup();
+            shrinks.retain(|shrink| *shrink != address(&local, &domain));
+
+            Tree::with_children(
+                address(&local, &domain),
+                shrinks.into_iter().map(Tree::singleton).collect(),
+            )
+        })
+    }
+}
+
+/// A domain name as labels, the last of them the TLD.
+#[derive(Debug, Clone, PartialEq)]
+struct Domain {
+    labels: Vec<String>,
+    trailing_dot: bool,
+}
+
+impl Domain {
+    fn simplest() -> Domain {
+        Domain {
+            labels: vec!["a".to_string(), "com".to_string()],
+            trailing_dot: false,
+        }
+    }
+
+    /// A domain of at most `max_len` bytes as written.
+    fn draw(draw: &mut Draw, strictness: AddressStrictness, max_len: usize) -> Domain {
+        let pathological = strictness == AddressStrictness::Pathological;
+        let tld = match draw.below(3) {
+            0 => encode_label(pick(draw, UNICODE_TLDS), draw),
+            _ => pick(draw, ASCII_TLDS).to_string(),
+        };
+
+        let mut labels = if pathological && draw.below(4) == 0 {
+            longest_labels(max_len - tld.len() - 1)
+        } else {
+            (0..=draw.below(3))
+                .map(|_| label(draw, pathological))
+                .collect()
+        };
+        labels.push(tld);
+
+        let mut domain = Domain {
+            labels,
+            trailing_dot: pathological && draw.below(8) == 0,
+        };
+        while domain.len() > max_len && domain.labels.len() > 2 {
+            domain.labels.remove(0);
+        }
+        domain
+    }
+
+    /// The length of the name in bytes, in the form it's written.
+    fn len(&self) -> usize {
+        self.to_string().len() - usize::from(self.trailing_dot)
+    }
+
+    /// Simpler domains, the simplest first.
+    fn shrinks(&self) -> Vec<Domain> {
+        let mut shrinks = vec![Domain::simplest()];
+        let (subdomains, tld) = self.labels.split_at(self.labels.len() - 1);
+        for i in 0..subdomains.len().saturating_sub(1) {
+            let mut labels = self.labels.clone();
+            labels.remove(i);
+            shrinks.push(Domain {
+                labels,
+                ..self.clone()
+            });
+        }
+        for i in 0..subdomains.len() {
+            let mut labels = self.labels.clone();
+            labels[i] = "a".to_string();
+            shrinks.push(Domain {
+                labels,
+                ..self.clone()
+            });
+        }
+        if tld[0] != "com" {
+            let mut labels = self.labels.clone();
+            *labels.last_mut().expect("a domain has a TLD") = "com".to_string();
+            shrinks.push(Domain {
+                labels,
+                ..self.clone()
+            });
+        }
+        if self.trailing_dot {
+            shrinks.push(Domain {
+                trailing_dot: false,
+                ..self.clone()
+            });
+        }
+        shrinks.retain(|shrink| shrink != self);
+        shrinks.dedup();
+        shrinks
+    }
+}
+
+impl std::fmt::Display for Domain {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.labels.join("."))?;
+        if self.trailing_dot {
+            write!(f, ".")?;
+        }
+        Ok(())
+    }
+}
+
+fn pick<'a>(draw: &mut Draw, choices: &[&'a str]) -> &'a str {
+    choices[draw.below(choices.len())]
+}
+
+/// A label other than the TLD.
+fn label(draw: &mut Draw, pathological: bool) -> String {
+    let kinds = if pathological { 5 } else { 3 };
+    match draw.below(kinds) {
+        0 => ascii_label(draw),
+        1 => encode_label(pick(draw, UNICODE_WORDS), draw),
+        2 => {
+            let word: String = (0..=draw.below(6)).map(|_| unicode_letter(draw)).collect();
+            encode_label(&word, draw)
+        }
+        3 => ["x", "0", "123", "a-b", "a--b", "xn--bcher-kva"][draw.below(6)].to_string(),
+        _ => {
+            let length = MAX_LABEL - draw.below(2);
+            (0..length)
+                .map(|i| (b'a' + (i % 26) as u8) as char)
+                .collect()
+        }
+    }
+}
+
+/// A lowercase ASCII label, sometimes with a hyphen inside.
+fn ascii_label(draw: &mut Draw) -> String {
+    let length = 1 + draw.below(12);
+    let mut label: String = (0..length)
+        .map(|_| (b'a' + draw.below(26) as u8) as char)
+        .collect();
+    if length > 2 && draw.below(4) == 0 {
+        label.replace_range(length / 2..length / 2 + 1, "-");
+    }
+    label
+}
+
+/// A lowercase letter from one of several scripts.
+fn unicode_letter(draw: &mut Draw) -> char {
+    let (lo, hi) = [
+        ('à', 'ÿ'),
+        ('а', 'я'),
+        ('α', 'ω'),
+        ('ぁ', 'ゖ'),
+        ('一', '龥'),
+    ][draw.below(5)];
+    let offset = draw.below((hi as usize) - (lo as usize) + 1) as u32;
+    char::from_u32(lo as u32 + offset)
+        .filter(|c| c.is_alphabetic() && !c.is_uppercase())
+        .unwrap_or(lo)
+}
+
+/// A Unicode label as written, or half the time in its `xn--` form.
+fn encode_label(label: &str, draw: &mut Draw) -> String {
+    if label.is_ascii() || draw.chance() {
+        label.to_string()
+    } else {
+        format!("xn--{}", punycode(label))
+    }
+}
+
+/// ASCII labels as long as allowed, filling exactly `len` bytes.
+fn longest_labels(mut len: usize) -> Vec<String> {
+    let mut labels = Vec::new();
+    while len > 0 {
+        // Labels can't be empty, so never leave just the dot after this one
+        let label_len = if len == MAX_LABEL + 1 {
+            MAX_LABEL - 1
+        } else {
+            len.min(MAX_LABEL)
+        };
+        labels.push("a".repeat(label_len));
+        len = len.saturating_sub(label_len + 1);
+    }
+    labels
+}
+
+/// A local part, with its length limit respected.
+fn local_part(draw: &mut Draw, strictness: AddressStrictness) -> String {
+    let pathological = strictness == AddressStrictness::Pathological;
+    let kinds = if pathological { 7 } else { 4 };
+    match draw.below(kinds) {
+        0 => ascii_label(draw).replace('-', "."),
+        1 => {
+            let tag = if draw.chance() {
+                ascii_label(draw)
+            } else {
+                String::new()
+            };
+            format!("{}+{tag}", ascii_label(draw))
+        }
+        2 => pick(draw, UNICODE_WORDS).to_string(),
+        3 => format!("{}.{}", ascii_label(draw), ascii_label(draw)),
+        4 => pick(draw, QUOTED_LOCALS).to_string(),
+        5 => (0..=draw.below(5))
+            .map(|_| {
+                let specials: Vec<char> = LOCAL_SPECIALS.chars().collect();
+                specials[draw.below(specials.len())]
+            })
+            .collect(),
+        _ => "a".repeat(MAX_LOCAL),
+    }
+}
+
+/// Encode a label with punycode (RFC 3492), without the `xn--` prefix.
+fn punycode(input: &str) -> String {
+    const BASE: u32 = 36;
+    const T_MIN: u32 = 1;
+    const T_MAX: u32 = 26;
+
+    let code_points: Vec<u32> = input.chars().map(u32::from).collect();
+    let mut output: String = input.chars().filter(char::is_ascii).collect();
+    let basic = output.len() as u32;
+    if basic > 0 {
+        output.push('-');
+    }
+
+    let digit = |d: u32| {
+        char::from(if d < 26 {
+            b'a' + d as u8
+        } else {
+            b'0' + (d - 26) as u8
+        })
+    };
+    let (mut n, mut delta, mut bias, mut handled) = (0x80, 0, 72, basic);
+    while (handled as usize) < code_points.len() {
+        let next = *code_points
+            .iter()
+            .filter(|&&c| c >= n)
+            .min()
+            .expect("an unhandled code point remains");
+        delta += (next - n) * (handled + 1);
+        n = next;
+        for &c in &code_points {
+            if c < n {
+                delta += 1;
+            }
+            if c == n {
+                let mut q = delta;
+                let mut k = BASE;
+                loop {
+                    let t = k.saturating_sub(bias).clamp(T_MIN, T_MAX);
+                    if q < t {
+                        break;
+                    }
+                    output.push(digit(t + (q - t) % (BASE - t)));
+                    q = (q - t) / (BASE - t);
+                    k += BASE;
+                }
+                output.push(digit(q));
+                bias = adapt(delta, handled + 1, handled == basic);
+                delta = 0;
+                handled += 1;
+            }
+        }
+        delta += 1;
+        n += 1;
+    }
+    output
+}
+
+/// The punycode bias adaptation function.
+fn adapt(delta: u32, points: u32, first: bool) -> u32 {
+    const BASE: u32 = 36;
+    let mut delta = if first { delta / 700 } else { delta / 2 };
+    delta += delta / points;
+    let mut k = 0;
+    while delta > (BASE - 1) * 26 / 2 {
+        delta /= BASE - 1;
+        k += BASE;
+    }
+    k + BASE * delta / (delta + 38)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data::{Seed, Size};
+
+    #[test]
+    fn test_punycode_matches_rfc_3492() {
+        assert_eq!(punycode("bücher"), "bcher-kva");
+        assert_eq!(punycode("münchen"), "mnchen-3ya");
+        assert_eq!(punycode("例え"), "r8jz45g");
+        assert_eq!(punycode("テスト"), "zckzah");
+        assert_eq!(punycode("рф"), "p1ai");
+    }
+
+    #[test]
+    fn test_domains_stay_within_dns_limits() {
+        let gen = Gen::<String>::web_domain_with(AddressStrictness::Pathological);
+        let mut seen = (false, false, false);
+        for seed in 0..500 {
+            let tree = gen.generate(Size::new(50), Seed::from_u64(seed));
+            let domain = tree.value.strip_suffix('.').unwrap_or(&tree.value);
+            assert!(domain.len() <= MAX_DOMAIN, "{domain}");
+            for label in domain.split('.') {
+                assert!(!label.is_empty() && label.len() <= MAX_LABEL, "{domain}");
+            }
+            seen.0 |= domain.contains("xn--");
+            seen.1 |= !domain.is_ascii();
+            seen.2 |= domain.len() == MAX_DOMAIN;
+            if tree.value != "a.com" {
+                assert_eq!(*tree.shrinks()[0], "a.com");
+            }
+        }
+        assert_eq!(seen, (true, true, true));
+    }
+
+    #[test]
+    fn test_addresses_cover_the_unusual_cases() {
+        let gen = Gen::<String>::email_address_with(AddressStrictness::Pathological);
+        let mut seen = (false, false, false);
+        for seed in 0..500 {
+            let tree = gen.generate(Size::new(50), Seed::from_u64(seed));
+            let address = &tree.value;
+            assert!(address.len() <= MAX_ADDRESS, "{address}");
+            let (local, _) = address.rsplit_once('@').unwrap();
+            assert!(local.len() <= MAX_LOCAL, "{address}");
+            seen.0 |= local.starts_with('"');
+            seen.1 |= local.contains('+');
+            seen.2 |= local.len() == MAX_LOCAL;
+            if address != "a@a.com" {
+                assert_eq!(*tree.shrinks()[0], "a@a.com");
+            }
+        }
+        assert_eq!(seen, (true, true, true));
+
+        let common = Gen::<String>::email_address_with(AddressStrictness::Common);
+        assert!(common.sample().is_ascii());
+    }
+}
@@ -0,0 +1,175 @@
+//! Checking that a generator's output grows with the size.
+//!
+//! The size ramps from small to large over a run so that early cases are
+//! small and later ones explore. A composed generator that builds its
+//! collections from a fixed range, or that calls an inner generator at a
+//! constant size, ignores the ramp: every case is as large as the first, and
+//! nothing in the run says so. [`Gen::calibrate_sizes`] samples a generator
+//! along the ramp and reports how a measure of its values, such as a
+//! collection's length, grows.
+
+use super::Gen;
+use crate::data::{Config, Seed, Size};
+use std::fmt;
+
+/// Sizes sampled along the ramp, the smallest and largest included.
+const CALIBRATION_POINTS: usize = 11;
+
+/// How much larger values at the top of the ramp must be, on average, than
+/// at the bottom for a generator not to count as flat.
+const FLAT_GROWTH: f64 = 1.5;
+
+/// How a generator's values grow with the size, from
+/// [`Gen::calibrate_sizes`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct SizeCalibration {
+    /// Each size sampled, with the mean and largest measure of the values
+    /// generated at it
+    pub points: Vec<CalibrationPoint>,
+}
+
+/// The values generated at one size; see [`SizeCalibration`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct CalibrationPoint {
+    /// The size the values were generated at
+    pub size: usize,
+    /// The mean measure of the values
+    pub mean: f64,
+    /// The largest measure of the values
+    pub max: usize,
+}
+
+impl SizeCalibration {
+    /// Whether values barely grow along the ramp: the mean over the top
+    /// quarter of sizes is under one and a half times the mean over the
+    /// bottom quarter.
+    ///
+    /// A flat generator usually doesn't pass the size on to what it's built
+    /// from. Generators that are meant to be bounded, such as a fixed-length
+    /// array, are flat too.
+    pub fn is_flat(&self) -> bool {
+        let quarter = self.points.len().div_ceil(4).max(1);
+        let mean = |points: &[CalibrationPoint]| {
+            points.iter().map(|point| point.mean).sum::<f64>() / points.len().max(1) as f64
+        };
+        let bottom = mean(&self.points[..quarter.min(self.points.len())]);
+        let top = mean(&self.points[self.points.len().saturating_sub(quarter)..]);
+        top < bottom * FLAT_GROWTH || top == 0.0
+    }
+}
+
+impl fmt::Display for SizeCalibration {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "   size       mean        max")?;
+        for point in &self.points {
+            writeln!(
+                f,
+                "{:>7} {:>10.1} {:>10}",
+                point.size, point.mean, point.max
+            )?;
+        }
+        if self.is_flat() {
+            write!(
+                f,
+                "warning: values barely grow with the size; is the size passed to the \
+                 generators this one is built from?"
+            )?;
+        }
+        Ok(())
+    }
+}
+
+impl<T> Gen<T> {
+    /// Sample the generator along the size ramp, from size 0 to the config's
+    /// size limit, measuring each value with `measure`.
+    ///
+    /// `samples_per_size` values are generated at each of a handful of
+    /// evenly spaced sizes. The measure is usually a length, such as a
+    /// collection's `len()` or a string's `chars().count()`. Printing the
+    /// result shows a table of sizes and measures, with a warning if the
+    /// generator [is flat](SizeCalibration::is_flat).
+    ///
+    /// # Example
+    /// ```rust
+    /// use hedgehog_core::*;
+    ///
+    /// let calibration =
+    ///     Gen::vec_of(Gen::int_range(0, 9)).calibrate_sizes(&Config::default(), 50, Vec::len);
+    /// assert!(!calibration.is_flat());
+    ///
+    /// // The length comes from a fixed range, whatever the size
+    /// let fixed = Gen::int_range(0, 10).map(|n| vec![0; n as usize]);
+    /// let calibration = fixed.calibrate_sizes(&Config::default(), 50, Vec::len);
+    /// assert!(calibration.is_flat());
+    /// ```
+    pub fn calibrate_sizes<M>(
+        &self,
+        config: &Config,
+        samples_per_size: usize,
+        measure: M,
+    ) -> SizeCalibration
+    where
+        M: Fn(&T) -> usize,
+    {
+        let samples_per_size = samples_per_size.max(1);
+        let mut seed = Seed::random();
+        let mut sizes: Vec<usize> = (0..CALIBRATION_POINTS)
+            .map(|point| config.size_limit * point / (CALIBRATION_POINTS - 1))
+            .collect();
+        sizes.dedup();
+
+        let points = sizes
+            .into_iter()
+            .map(|size| {
+                let mut total = 0;
+                let mut max = 0;
+                for _ in 0..samples_per_size {
+                    let (sample_seed, next_seed) = seed.split();
+                    seed = next_seed;
+                    let value = self.sample_with(Size::new(size), sample_seed);
+                    let measured = measure(&value);
+                    total += measured;
+                    max = max.max(measured);
+                }
+                CalibrationPoint {
+                    size,
+                    mean: total as f64 / samples_per_size as f64,
+                    max,
+                }
+            })
+            .collect();
+        super::take_generation_discards();
+
+        SizeCalibration { points }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_calibration_follows_the_size_ramp() {
+        let config = Config::default().with_size_limit(50);
+        let calibration =
+            Gen::<String>::ascii_alpha().calibrate_sizes(&config, 20, |s| s.chars().count());
+        let sizes: Vec<usize> = calibration.points.iter().map(|point| point.size).collect();
+        assert_eq!(sizes, vec![0, 5, 10, 15, 20, 25, 30, 35, 40, 45, 50]);
+        assert_eq!(calibration.points[0].max, 0);
+        assert!(calibration
+            .points
+            .iter()
+            .all(|point| point.max <= point.size));
+        assert!(!calibration.is_flat());
+        assert!(!calibration.to_string().contains("warning"));
+
+        // An inner generator run at a constant size
+        let inner = Gen::vec_of(Gen::int_range(0, 9));
+        let pinned = Gen::new(move |_size, seed| inner.generate(Size::new(10), seed));
+        let calibration = pinned.calibrate_sizes(&config, 20, Vec::len);
+        assert!(calibration.is_flat());
+        assert!(calibration
+            .to_string()
+            .contains("warning: values barely grow with the size"));
+    }
+}
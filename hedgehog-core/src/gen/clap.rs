@@ -0,0 +1,561 @@
+//! Argument vectors for `clap` command-line definitions.
+//!
+//! The generators read the arguments, options and subcommands of a
+//! `clap::Command` and build invocations from them. Valid invocations shrink
+//! by dropping optional arguments and subcommands and by simplifying values,
+//! so every shrink still parses. Near-valid invocations add a single
+//! mistake, such as an unknown flag or a missing value, to exercise the
+//! error paths.
+//!
+//! Values are chosen from an argument's possible values, or else from the
+//! type its value parser produces: integers, floats, booleans and text.
+//! Range restrictions, custom parsers, `requires` relations and required
+//! groups are not visible through clap's API, so arguments using them may
+//! still produce invocations clap rejects.
+
+use super::{list_shrinks, unit_interval, Draw, Gen};
+use crate::tree::Tree;
+use clap::{Arg, ArgAction, Command};
+use std::any::TypeId;
+
+impl Gen<Vec<String>> {
+    /// Generate argument vectors that parse with `command`, starting with
+    /// the command's name as `argv[0]`.
+    ///
+    /// # Example
+    /// ```rust
+    /// use clap::{Arg, ArgAction, Command};
+    /// use hedgehog_core::*;
+    ///
+    /// fn cli() -> Command {
+    ///     Command::new("grep")
+    ///         .arg(Arg::new("count").short('c').long("count").action(ArgAction::SetTrue))
+    ///         .arg(Arg::new("pattern").required(true))
+    /// }
+    ///
+    /// let prop = for_all(Gen::<Vec<String>>::clap_args(cli()), |args| {
+    ///     cli().try_get_matches_from(args).is_ok()
+    /// });
+    /// ```
+    pub fn clap_args(command: Command) -> Self {
+        let spec = CommandSpec::new(command);
+        Gen::new(move |_size, seed| {
+            let mut units = vec![Unit::fixed(vec![spec.name.clone()])];
+            spec.invocation(&mut Draw(seed), true, &mut units);
+            units_tree(units)
+        })
+    }
+
+    /// Generate argument vectors for `command`, where a share of them carry
+    /// one mistake: a dropped, duplicated or swapped token, an unknown flag,
+    /// a stray `--` or an empty value, or a request for `--help`.
+    ///
+    /// With probability `probability` the invocation is mutated. Mutated
+    /// invocations shrink by removing tokens, whether or not they parse.
+    pub fn clap_args_near_valid(command: Command, probability: f64) -> Self {
+        let probability = probability.clamp(0.0, 1.0);
+        let valid = Self::clap_args(command);
+
+        Gen::new(move |size, seed| {
+            let (valid_seed, mutation_seed) = seed.split();
+            let (roll_seed, mutation_seed) = mutation_seed.split();
+            let tree = valid.generate(size, valid_seed);
+            if unit_interval(roll_seed) >= probability {
+                return tree;
+            }
+
+            let args = mutate(tree.value, &mut Draw(mutation_seed));
+            let shrinks = list_shrinks(&args[1..])
+                .into_iter()
+                .map(|rest| {
+                    let mut shrunk = vec![args[0].clone()];
+                    shrunk.extend(rest);
+                    Tree::singleton(shrunk)
+                })
+                .collect();
+            Tree::with_children(args, shrinks)
+        })
+    }
+}
+
+/// Apply one mistake to a valid invocation, leaving `argv[0]` alone.
+fn mutate(mut args: Vec<String>, draw: &mut Draw) -> Vec<String> {
+    let tokens = args.len() - 1;
+    let at = 1 + draw.below(tokens + 1);
+    match draw.below(7) {
+        0 if tokens > 0 => {
+            args.remove(at.min(tokens));
+        }
+        1 if tokens > 0 => {
+            let token = args[at.min(tokens)].clone();
+            args.insert(at, token);
+        }
+        2 if tokens > 1 => {
+            let first = 1 + draw.below(tokens - 1);
+            args.swap(first, first + 1);
+        }
+        3 => args.insert(at, "--hedgehog-unknown".to_string()),
+        4 => args.insert(at, "--".to_string()),
+        5 => args.insert(at, String::new()),
+        _ => args.insert(at, "--help".to_string()),
+    }
+    args
+}
+
+/// How an invocation's unit may be dropped when shrinking.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum UnitKind {
+    /// Required; never dropped.
+    Fixed,
+    /// Optional; dropped on its own.
+    Optional,
+    /// A subcommand; dropped along with everything after it.
+    Tail,
+}
+
+/// A run of tokens that shrinking keeps or drops as a whole.
+#[derive(Debug, Clone)]
+struct Unit {
+    tokens: Vec<String>,
+    simpler: Vec<String>,
+    kind: UnitKind,
+}
+
+impl Unit {
+    fn fixed(tokens: Vec<String>) -> Self {
+        Unit {
+            simpler: tokens.clone(),
+            tokens,
+            kind: UnitKind::Fixed,
+        }
+    }
+}
+
+fn flatten(units: &[Unit]) -> Vec<String> {
+    units.iter().flat_map(|unit| unit.tokens.clone()).collect()
+}
+
+/// The invocation with shrinks that drop optional units, then simplify
+/// their values.
+fn units_tree(units: Vec<Unit>) -> Tree<Vec<String>> {
+    let mut shrinks = Vec::new();
+    for (i, unit) in units.iter().enumerate() {
+        match unit.kind {
+            UnitKind::Fixed => {}
+            UnitKind::Optional => {
+                let mut fewer = units.clone();
+                fewer.remove(i);
+                shrinks.push(Tree::singleton(flatten(&fewer)));
+            }
+            UnitKind::Tail => shrinks.push(Tree::singleton(flatten(&units[..i]))),
+        }
+    }
+    for (i, unit) in units.iter().enumerate() {
+        if unit.simpler != unit.tokens {
+            let mut simpler = units.clone();
+            simpler[i].tokens = unit.simpler.clone();
+            shrinks.push(Tree::singleton(flatten(&simpler)));
+        }
+    }
+    Tree::with_children(flatten(&units), shrinks)
+}
+
+/// What a command accepts, read once from its definition.
+#[derive(Debug, Clone)]
+struct CommandSpec {
+    name: String,
+    positionals: Vec<ArgSpec>,
+    options: Vec<ArgSpec>,
+    subcommands: Vec<CommandSpec>,
+    subcommand_required: bool,
+    args_conflict_with_subcommands: bool,
+    reserved: Vec<String>,
+}
+
+impl CommandSpec {
+    fn new(mut command: Command) -> Self {
+        command.build();
+        Self::from_built(&command)
+    }
+
+    fn from_built(command: &Command) -> Self {
+        let mut positionals = Vec::new();
+        let mut options = Vec::new();
+        for arg in command.get_arguments() {
+            if let Some(spec) = ArgSpec::new(command, arg) {
+                if arg.is_positional() {
+                    positionals.push((arg.get_index().unwrap_or(usize::MAX), spec));
+                } else {
+                    options.push(spec);
+                }
+            }
+        }
+        positionals.sort_by_key(|(index, _)| *index);
+        // Options taking a variable number of values go last, where they
+        // can't swallow the values of the options after them
+        options.sort_by_key(|option| option.values.0 != option.values.1);
+
+        let subcommands: Vec<_> = command
+            .get_subcommands()
+            .filter(|subcommand| subcommand.get_name() != "help")
+            .map(Self::from_built)
+            .collect();
+        let reserved = command
+            .get_subcommands()
+            .flat_map(|subcommand| {
+                std::iter::once(subcommand.get_name()).chain(subcommand.get_all_aliases())
+            })
+            .map(str::to_string)
+            .collect();
+
+        CommandSpec {
+            name: command.get_name().to_string(),
+            positionals: positionals.into_iter().map(|(_, spec)| spec).collect(),
+            options,
+            subcommand_required: command.is_subcommand_required_set() && !subcommands.is_empty(),
+            subcommands,
+            args_conflict_with_subcommands: command.is_args_conflicts_with_subcommands_set(),
+            reserved,
+        }
+    }
+
+    /// Append an invocation of this command's arguments to `units`.
+    fn invocation(&self, draw: &mut Draw, top: bool, units: &mut Vec<Unit>) {
+        let subcommand = if self.subcommands.is_empty() {
+            None
+        } else if self.subcommand_required || draw.chance() {
+            Some(&self.subcommands[draw.below(self.subcommands.len())])
+        } else {
+            None
+        };
+        let optional_args = subcommand.is_none() || !self.args_conflict_with_subcommands;
+
+        // Positionals fill from the left, so only the last one present can
+        // be dropped, and only if it is optional
+        let mut positionals = Vec::new();
+        for positional in &self.positionals {
+            let present = positional.required || (optional_args && draw.chance());
+            if !present {
+                break;
+            }
+            positionals.push(positional.unit(draw, &self.reserved));
+        }
+        let present = positionals.len();
+        if present > 0 && !self.positionals[present - 1].required {
+            positionals[present - 1].kind = UnitKind::Optional;
+        }
+        units.extend(positionals);
+
+        let mut chosen: Vec<&ArgSpec> = Vec::new();
+        for option in &self.options {
+            if option.global && !top {
+                continue;
+            }
+            let conflicts = chosen.iter().any(|other| {
+                other.conflicts.contains(&option.id) || option.conflicts.contains(&other.id)
+            });
+            let variadic = option.values.0 != option.values.1;
+            let wanted = option.required
+                || (optional_args
+                    && !conflicts
+                    && !(variadic && subcommand.is_some())
+                    && draw.chance());
+            if !wanted {
+                continue;
+            }
+            chosen.push(option);
+
+            let occurrences = match option.action {
+                Action::Count | Action::Append => 1 + draw.below(3),
+                Action::Flag | Action::Set => 1,
+            };
+            for occurrence in 0..occurrences {
+                let mut unit = option.unit(draw, &self.reserved);
+                if !option.required || occurrence > 0 {
+                    unit.kind = UnitKind::Optional;
+                }
+                units.push(unit);
+            }
+        }
+
+        if let Some(subcommand) = subcommand {
+            let mut unit = Unit::fixed(vec![subcommand.name.clone()]);
+            if !self.subcommand_required {
+                unit.kind = UnitKind::Tail;
+            }
+            units.push(unit);
+            subcommand.invocation(draw, false, units);
+        }
+    }
+}
+
+/// What an argument does when it appears.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Action {
+    /// A flag that may appear once.
+    Flag,
+    /// A flag that may be repeated.
+    Count,
+    /// Takes values once.
+    Set,
+    /// Takes values, and may be repeated.
+    Append,
+}
+
+/// The values an argument accepts.
+#[derive(Debug, Clone, PartialEq)]
+enum ValueKind {
+    Possible(Vec<String>),
+    Integer,
+    Float,
+    Bool,
+    Text,
+}
+
+impl ValueKind {
+    fn of(arg: &Arg) -> Self {
+        let possible: Vec<String> = arg
+            .get_possible_values()
+            .iter()
+            .filter(|value| !value.is_hide_set())
+            .map(|value| value.get_name().to_string())
+            .collect();
+        if !possible.is_empty() {
+            return ValueKind::Possible(possible);
+        }
+
+        let type_id = arg.get_value_parser().type_id();
+        let is = |id: TypeId| type_id == id;
+        if [
+            TypeId::of::<i8>(),
+            TypeId::of::<i16>(),
+            TypeId::of::<i32>(),
+            TypeId::of::<i64>(),
+            TypeId::of::<isize>(),
+            TypeId::of::<u8>(),
+            TypeId::of::<u16>(),
+            TypeId::of::<u32>(),
+            TypeId::of::<u64>(),
+            TypeId::of::<usize>(),
+        ]
+        .into_iter()
+        .any(is)
+        {
+            ValueKind::Integer
+        } else if is(TypeId::of::<f32>()) || is(TypeId::of::<f64>()) {
+            ValueKind::Float
+        } else if is(TypeId::of::<bool>()) {
+            ValueKind::Bool
+        } else {
+            ValueKind::Text
+        }
+    }
+
+    /// A value, and the simplest value of the same kind.
+    fn value(&self, draw: &mut Draw, reserved: &[String]) -> (String, String) {
+        match self {
+            ValueKind::Possible(values) => {
+                (values[draw.below(values.len())].clone(), values[0].clone())
+            }
+            // Negative numbers would read as flags
+            ValueKind::Integer => (draw.below(128).to_string(), "0".to_string()),
+            ValueKind::Float => (
+                format!("{}.{}", draw.below(100), draw.below(100)),
+                "0".to_string(),
+            ),
+            ValueKind::Bool => {
+                let value = if draw.chance() { "true" } else { "false" };
+                (value.to_string(), "false".to_string())
+            }
+            ValueKind::Text => {
+                const LETTERS: &[u8] = b"abcdefghijklmnopqrstuvwxyz";
+                const CHARACTERS: &[u8] = b"abcdefghijklmnopqrstuvwxyz0123456789_.";
+                let mut text = String::new();
+                text.push(LETTERS[draw.below(LETTERS.len())] as char);
+                for _ in 0..draw.below(8) {
+                    text.push(CHARACTERS[draw.below(CHARACTERS.len())] as char);
+                }
+                // A value spelled like a subcommand would be read as one
+                while reserved.contains(&text) {
+                    text.push('x');
+                }
+                (text, "a".to_string())
+            }
+        }
+    }
+}
+
+/// What an argument accepts, read from its definition.
+#[derive(Debug, Clone)]
+struct ArgSpec {
+    id: String,
+    long: Option<String>,
+    short: Option<char>,
+    action: Action,
+    /// The fewest and most values taken per occurrence.
+    values: (usize, usize),
+    kind: ValueKind,
+    required: bool,
+    require_equals: bool,
+    last: bool,
+    global: bool,
+    conflicts: Vec<String>,
+}
+
+impl ArgSpec {
+    /// Read an argument, or `None` for help and version flags.
+    fn new(command: &Command, arg: &Arg) -> Option<Self> {
+        let action = match arg.get_action() {
+            ArgAction::SetTrue | ArgAction::SetFalse => Action::Flag,
+            ArgAction::Count => Action::Count,
+            ArgAction::Set => Action::Set,
+            ArgAction::Append => Action::Append,
+            _ => return None,
+        };
+        let values = match action {
+            Action::Flag | Action::Count => (0, 0),
+            Action::Set | Action::Append => {
+                let range = arg.get_num_args().unwrap_or_default();
+                let min = range.min_values();
+                (min, range.max_values().min(min.max(1) + 2))
+            }
+        };
+
+        Some(ArgSpec {
+            id: arg.get_id().to_string(),
+            long: arg.get_long().map(str::to_string),
+            short: arg.get_short(),
+            action,
+            values,
+            kind: ValueKind::of(arg),
+            required: arg.is_required_set(),
+            require_equals: arg.is_require_equals_set(),
+            last: arg.is_last_set(),
+            global: arg.is_global_set(),
+            conflicts: command
+                .get_arg_conflicts_with(arg)
+                .into_iter()
+                .map(|other| other.get_id().to_string())
+                .collect(),
+        })
+    }
+
+    /// One occurrence of the argument with its values.
+    fn unit(&self, draw: &mut Draw, reserved: &[String]) -> Unit {
+        let count = self.values.0 + draw.below(self.values.1 - self.values.0 + 1);
+        let (values, simplest): (Vec<_>, Vec<_>) =
+            (0..count).map(|_| self.kind.value(draw, reserved)).unzip();
+
+        let flag = match (&self.long, self.short) {
+            (Some(_), Some(short)) if draw.chance() => Some(format!("-{short}")),
+            (Some(long), _) => Some(format!("--{long}")),
+            (None, Some(short)) => Some(format!("-{short}")),
+            (None, None) => None,
+        };
+        let equals = draw.chance();
+        let render = |values: Vec<String>| -> Vec<String> {
+            match &flag {
+                None if self.last => std::iter::once("--".to_string()).chain(values).collect(),
+                None => values,
+                Some(flag) if values.len() == 1 && (self.require_equals || equals) => {
+                    vec![format!("{flag}={}", values[0])]
+                }
+                Some(flag) => std::iter::once(flag.clone()).chain(values).collect(),
+            }
+        };
+        let tokens = render(values);
+        Unit {
+            simpler: render(simplest),
+            tokens,
+            kind: UnitKind::Fixed,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data::{Seed, Size};
+
+    fn cli() -> Command {
+        Command::new("tool")
+            .arg(
+                Arg::new("verbose")
+                    .short('v')
+                    .long("verbose")
+                    .action(ArgAction::Count)
+                    .global(true),
+            )
+            .arg(
+                Arg::new("format")
+                    .long("format")
+                    .value_parser(["json", "yaml", "toml"]),
+            )
+            .arg(
+                Arg::new("quiet")
+                    .short('q')
+                    .action(ArgAction::SetTrue)
+                    .conflicts_with("verbose"),
+            )
+            .subcommand(
+                Command::new("copy")
+                    .arg(Arg::new("from").required(true))
+                    .arg(Arg::new("to"))
+                    .arg(
+                        Arg::new("jobs")
+                            .short('j')
+                            .value_parser(clap::value_parser!(u16)),
+                    )
+                    .arg(
+                        Arg::new("exclude")
+                            .long("exclude")
+                            .num_args(1..)
+                            .action(ArgAction::Append),
+                    ),
+            )
+            .subcommand(
+                Command::new("check").arg(
+                    Arg::new("strict")
+                        .long("strict")
+                        .value_parser(clap::value_parser!(bool))
+                        .required(true),
+                ),
+            )
+    }
+
+    #[test]
+    fn test_clap_args_and_their_shrinks_parse() {
+        let gen = Gen::<Vec<String>>::clap_args(cli());
+        let mut subcommands = std::collections::HashSet::new();
+
+        for seed in 0..200 {
+            let tree = gen.generate(Size::new(10), Seed::from_u64(seed));
+            assert_eq!(tree.value[0], "tool");
+            let matches = cli()
+                .try_get_matches_from(&tree.value)
+                .unwrap_or_else(|error| panic!("{:?} did not parse: {error}", tree.value));
+            subcommands.insert(matches.subcommand_name().map(str::to_string));
+
+            for shrink in tree.shrinks() {
+                assert!(
+                    cli().try_get_matches_from(shrink).is_ok(),
+                    "shrink {shrink:?} of {:?} did not parse",
+                    tree.value
+                );
+            }
+        }
+        assert_eq!(subcommands.len(), 3);
+    }
+
+    #[test]
+    fn test_near_valid_args_sometimes_fail_to_parse() {
+        let gen = Gen::<Vec<String>>::clap_args_near_valid(cli(), 1.0);
+        let failures = (0..100)
+            .filter(|&seed| {
+                let args = gen.generate(Size::new(10), Seed::from_u64(seed)).value;
+                cli().try_get_matches_from(args).is_err()
+            })
+            .count();
+        assert!(failures > 20, "only {failures} of 100 failed to parse");
+    }
+}
@@ -0,0 +1,621 @@
+//! Configuration documents for testing configuration loaders.
+//!
+//! A [`ConfigValue`] is the common shape of JSON, YAML and TOML documents:
+//! nested maps and lists of scalars. Generated documents can be required to
+//! contain keys of a given kind, so a loader gets past its own validation
+//! and the rest of the document exercises everything around it. Documents
+//! shrink by dropping optional keys and list items and by simplifying
+//! scalars, and required keys are never dropped.
+//!
+//! # Example
+//! ```rust
+//! use hedgehog_core::*;
+//!
+//! let options = ConfigOptions::new()
+//!     .with_max_depth(2)
+//!     .require("server.port", ConfigKind::Int)
+//!     .require("name", ConfigKind::String);
+//!
+//! let prop = for_all(Gen::<ConfigValue>::config(options), |config| {
+//!     config.get("server.port").is_some() && !config.to_toml().is_empty()
+//! });
+//! ```
+
+use super::{Draw, Gen};
+use crate::tree::Tree;
+use std::fmt::Write;
+
+/// A value in a configuration document.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ConfigValue {
+    Bool(bool),
+    Int(i64),
+    /// Always finite, since TOML and JSON differ on the rest
+    Float(f64),
+    String(String),
+    List(Vec<ConfigValue>),
+    /// Keys in document order
+    Map(Vec<(String, ConfigValue)>),
+}
+
+/// The kind of a [`ConfigValue`], used to constrain required keys.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConfigKind {
+    Bool,
+    Int,
+    Float,
+    String,
+    List,
+    Map,
+    /// Any kind of value
+    Any,
+}
+
+/// Options for [`Gen::config`].
+#[derive(Debug, Clone)]
+pub struct ConfigOptions {
+    max_depth: usize,
+    max_width: usize,
+    weights: [u64; 3],
+    required: Vec<(Vec<String>, ConfigKind)>,
+}
+
+impl ConfigOptions {
+    /// Documents nested up to three levels deep, with up to five keys or
+    /// items per map or list and mostly scalar values.
+    pub fn new() -> Self {
+        ConfigOptions {
+            max_depth: 3,
+            max_width: 5,
+            weights: [6, 2, 2],
+            required: Vec::new(),
+        }
+    }
+
+    /// Nest maps and lists at most `depth` levels below the top level.
+    pub fn with_max_depth(mut self, depth: usize) -> Self {
+        self.max_depth = depth;
+        self
+    }
+
+    /// Put at most `width` optional keys in a map or items in a list.
+    pub fn with_max_width(mut self, width: usize) -> Self {
+        self.max_width = width;
+        self
+    }
+
+    /// Relative weights of scalars, lists and maps for values that are not
+    /// at the maximum depth.
+    pub fn with_weights(mut self, scalar: u64, list: u64, map: u64) -> Self {
+        self.weights = [scalar, list, map];
+        self
+    }
+
+    /// Require a key of the given kind, with a dotted path for nested keys.
+    /// The maps along the path are required too.
+    pub fn require(mut self, path: &str, kind: ConfigKind) -> Self {
+        self.required
+            .push((path.split('.').map(str::to_string).collect(), kind));
+        self
+    }
+}
+
+impl Default for ConfigOptions {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ConfigValue {
+    /// The value at a dotted path through nested maps.
+    pub fn get(&self, path: &str) -> Option<&ConfigValue> {
+        path.split('.').try_fold(self, |value, key| match value {
+            ConfigValue::Map(entries) => entries.iter().find(|(k, _)| k == key).map(|(_, v)| v),
+            _ => None,
+        })
+    }
+
+    /// The kind of this value.
+    pub fn kind(&self) -> ConfigKind {
+        match self {
+            ConfigValue::Bool(_) => ConfigKind::Bool,
+            ConfigValue::Int(_) => ConfigKind::Int,
+            ConfigValue::Float(_) => ConfigKind::Float,
+            ConfigValue::String(_) => ConfigKind::String,
+            ConfigValue::List(_) => ConfigKind::List,
+            ConfigValue::Map(_) => ConfigKind::Map,
+        }
+    }
+
+    /// The value as compact JSON.
+    pub fn to_json(&self) -> String {
+        match self {
+            ConfigValue::List(items) => {
+                let items: Vec<String> = items.iter().map(ConfigValue::to_json).collect();
+                format!("[{}]", items.join(","))
+            }
+            ConfigValue::Map(entries) => {
+                let entries: Vec<String> = entries
+                    .iter()
+                    .map(|(key, value)| format!("{}:{}", quote(key), value.to_json()))
+                    .collect();
+                format!("{{{}}}", entries.join(","))
+            }
+            scalar => scalar.scalar(),
+        }
+    }
+
+    /// The value as a block-style YAML document.
+    pub fn to_yaml(&self) -> String {
+        let mut out = String::new();
+        if self.is_block() {
+            self.write_yaml(0, &mut out);
+        } else {
+            out.push_str(&self.to_json());
+            out.push('\n');
+        }
+        out
+    }
+
+    /// The value as a TOML document. The top level of a TOML document is a
+    /// table, so any other value is written as the `value` key.
+    pub fn to_toml(&self) -> String {
+        let mut out = String::new();
+        match self {
+            ConfigValue::Map(entries) => write_table(&[], entries, &mut out),
+            other => write_table(&[], &[("value".to_string(), other.clone())], &mut out),
+        }
+        out
+    }
+
+    /// Scalars are written the same way in all three formats.
+    fn scalar(&self) -> String {
+        match self {
+            ConfigValue::Bool(b) => b.to_string(),
+            ConfigValue::Int(n) => n.to_string(),
+            ConfigValue::Float(x) => format!("{x:?}"),
+            ConfigValue::String(s) => quote(s),
+            _ => unreachable!("not a scalar"),
+        }
+    }
+
+    /// Non-empty containers are written as indented blocks in YAML.
+    fn is_block(&self) -> bool {
+        match self {
+            ConfigValue::List(items) => !items.is_empty(),
+            ConfigValue::Map(entries) => !entries.is_empty(),
+            _ => false,
+        }
+    }
+
+    fn write_yaml(&self, indent: usize, out: &mut String) {
+        let pad = " ".repeat(indent);
+        let mut write_item = |prefix: String, value: &ConfigValue| {
+            if value.is_block() {
+                let _ = writeln!(out, "{pad}{prefix}");
+                value.write_yaml(indent + 2, out);
+            } else {
+                let _ = writeln!(out, "{pad}{prefix} {}", value.to_json());
+            }
+        };
+        match self {
+            ConfigValue::List(items) => {
+                for item in items {
+                    write_item("-".to_string(), item);
+                }
+            }
+            ConfigValue::Map(entries) => {
+                for (key, value) in entries {
+                    write_item(format!("{}:", yaml_key(key)), value);
+                }
+            }
+            _ => unreachable!("not a block"),
+        }
+    }
+
+    /// The value inline, for TOML arrays and inline tables.
+    fn toml_inline(&self) -> String {
+        match self {
+            ConfigValue::List(items) => {
+                let items: Vec<String> = items.iter().map(ConfigValue::toml_inline).collect();
+                format!("[{}]", items.join(", "))
+            }
+            ConfigValue::Map(entries) if entries.is_empty() => "{}".to_string(),
+            ConfigValue::Map(entries) => {
+                let entries: Vec<String> = entries
+                    .iter()
+                    .map(|(key, value)| format!("{} = {}", toml_key(key), value.toml_inline()))
+                    .collect();
+                format!("{{ {} }}", entries.join(", "))
+            }
+            scalar => scalar.scalar(),
+        }
+    }
+}
+
+/// Write a table's own keys, then each nested map as its own table.
+fn write_table(path: &[String], entries: &[(String, ConfigValue)], out: &mut String) {
+    for (key, value) in entries {
+        if !matches!(value, ConfigValue::Map(_)) {
+            let _ = writeln!(out, "{} = {}", toml_key(key), value.toml_inline());
+        }
+    }
+    for (key, value) in entries {
+        if let ConfigValue::Map(nested) = value {
+            let path = [path, std::slice::from_ref(key)].concat();
+            let header: Vec<String> = path.iter().map(|key| toml_key(key)).collect();
+            let _ = writeln!(out, "\n[{}]", header.join("."));
+            write_table(&path, nested, out);
+        }
+    }
+}
+
+/// A double-quoted string, with escapes that JSON, YAML and TOML all read
+/// the same way.
+fn quote(s: &str) -> String {
+    let mut quoted = String::with_capacity(s.len() + 2);
+    quoted.push('"');
+    for c in s.chars() {
+        match c {
+            '"' => quoted.push_str("\\\""),
+            '\\' => quoted.push_str("\\\\"),
+            '\n' => quoted.push_str("\\n"),
+            '\t' => quoted.push_str("\\t"),
+            '\r' => quoted.push_str("\\r"),
+            c if c.is_control() => {
+                let _ = write!(quoted, "\\u{:04X}", c as u32);
+            }
+            c => quoted.push(c),
+        }
+    }
+    quoted.push('"');
+    quoted
+}
+
+fn is_bare(key: &str) -> bool {
+    !key.is_empty()
+        && key
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-')
+}
+
+fn toml_key(key: &str) -> String {
+    if is_bare(key) {
+        key.to_string()
+    } else {
+        quote(key)
+    }
+}
+
+/// Keys YAML would read as something other than a string are quoted.
+fn yaml_key(key: &str) -> String {
+    const RESERVED: &[&str] = &["y", "n", "yes", "no", "on", "off", "true", "false", "null"];
+    let starts_like_number = key.starts_with(|c: char| c.is_ascii_digit() || c == '-');
+    if is_bare(key) && !starts_like_number && !RESERVED.contains(&key.to_lowercase().as_str()) {
+        key.to_string()
+    } else {
+        quote(key)
+    }
+}
+
+impl Gen<ConfigValue> {
+    /// Generate configuration documents: a map at the top level holding the
+    /// required keys followed by optional ones, nested as `options` allows.
+    ///
+    /// Documents grow with size. Shrinking removes optional keys and list
+    /// items, then simplifies what is left; required keys stay, with a value
+    /// of their kind.
+    ///
+    /// # Example
+    /// ```rust
+    /// use hedgehog_core::*;
+    ///
+    /// let gen = Gen::<ConfigValue>::config(ConfigOptions::new().require("debug", ConfigKind::Bool));
+    /// let config = gen.generate(Size::new(20), Seed::from_u64(7)).value;
+    /// assert_eq!(config.get("debug").map(ConfigValue::kind), Some(ConfigKind::Bool));
+    /// ```
+    pub fn config(options: ConfigOptions) -> Self {
+        Gen::new(move |size, seed| {
+            let generator = Generator {
+                options: &options,
+                size: size.get(),
+            };
+            let required: Vec<_> = options
+                .required
+                .iter()
+                .map(|(path, kind)| (path.as_slice(), *kind))
+                .collect();
+            let config = generator.map(0, &required, &mut Draw(seed));
+            let shrinks = shrinks(&config, &required)
+                .into_iter()
+                .map(Tree::singleton)
+                .collect();
+            Tree::with_children(config, shrinks)
+        })
+    }
+}
+
+type Required<'a> = [(&'a [String], ConfigKind)];
+
+/// The requirements below `key`, relative to it.
+fn below<'a>(required: &Required<'a>, key: &str) -> Vec<(&'a [String], ConfigKind)> {
+    required
+        .iter()
+        .filter(|(path, _)| path.len() > 1 && path[0] == key)
+        .map(|(path, kind)| (&path[1..], *kind))
+        .collect()
+}
+
+/// Whether `key` must stay in its map.
+fn is_required(required: &Required<'_>, key: &str) -> bool {
+    required.iter().any(|(path, _)| path[0] == key)
+}
+
+struct Generator<'a> {
+    options: &'a ConfigOptions,
+    size: usize,
+}
+
+impl Generator<'_> {
+    fn width(&self, draw: &mut Draw) -> usize {
+        draw.below(self.size.min(self.options.max_width) + 1)
+    }
+
+    fn map(&self, depth: usize, required: &Required<'_>, draw: &mut Draw) -> ConfigValue {
+        let mut entries: Vec<(String, ConfigValue)> = Vec::new();
+        for (path, kind) in required {
+            if entries.iter().any(|(key, _)| *key == path[0]) {
+                continue;
+            }
+            let nested = below(required, &path[0]);
+            // Maps along a longer path win over a kind required of the key
+            let value = if nested.is_empty() {
+                self.of_kind(*kind, depth + 1, draw)
+            } else {
+                self.map(depth + 1, &nested, draw)
+            };
+            entries.push((path[0].clone(), value));
+        }
+        for _ in 0..self.width(draw) {
+            let key = key(draw);
+            if !entries.iter().any(|(k, _)| *k == key) {
+                let value = self.value(depth + 1, draw);
+                entries.push((key, value));
+            }
+        }
+        ConfigValue::Map(entries)
+    }
+
+    fn value(&self, depth: usize, draw: &mut Draw) -> ConfigValue {
+        let [scalar, list, map] = self.options.weights;
+        let total = scalar + list + map;
+        if depth > self.options.max_depth || total == 0 {
+            return self.scalar(draw);
+        }
+        let pick = draw.below(usize::try_from(total).unwrap_or(usize::MAX)) as u64;
+        if pick < scalar {
+            self.scalar(draw)
+        } else if pick < scalar + list {
+            self.of_kind(ConfigKind::List, depth, draw)
+        } else {
+            self.map(depth, &[], draw)
+        }
+    }
+
+    fn of_kind(&self, kind: ConfigKind, depth: usize, draw: &mut Draw) -> ConfigValue {
+        match kind {
+            ConfigKind::Bool => ConfigValue::Bool(draw.chance()),
+            ConfigKind::Int => ConfigValue::Int(match draw.below(8) {
+                0 => i64::MAX,
+                1 => i64::MIN,
+                2 => 0,
+                _ => draw.below(self.size * 100 + 1) as i64 - (self.size * 50) as i64,
+            }),
+            ConfigKind::Float => ConfigValue::Float(
+                (draw.below(self.size * 40 + 1) as f64 - (self.size * 20) as f64) / 4.0,
+            ),
+            ConfigKind::String => {
+                let len = draw.below(self.size.min(20) + 1);
+                ConfigValue::String(
+                    (0..len)
+                        .map(|_| match draw.below(20) {
+                            0 => ['"', '\\', '\n', '\t', '#', ':', 'é'][draw.below(7)],
+                            _ => (b' ' + draw.below(95) as u8) as char,
+                        })
+                        .collect(),
+                )
+            }
+            ConfigKind::List => {
+                let items = (0..self.width(draw))
+                    .map(|_| self.value(depth + 1, draw))
+                    .collect();
+                ConfigValue::List(items)
+            }
+            ConfigKind::Map => self.map(depth, &[], draw),
+            ConfigKind::Any => self.value(depth, draw),
+        }
+    }
+
+    fn scalar(&self, draw: &mut Draw) -> ConfigValue {
+        let kinds = [
+            ConfigKind::Bool,
+            ConfigKind::Int,
+            ConfigKind::Float,
+            ConfigKind::String,
+        ];
+        self.of_kind(kinds[draw.below(kinds.len())], 0, draw)
+    }
+}
+
+/// A lowercase key, sometimes with underscores or dashes.
+fn key(draw: &mut Draw) -> String {
+    let len = 1 + draw.below(10);
+    (0..len)
+        .map(|i| match draw.below(10) {
+            0 if i > 0 => '_',
+            1 if i > 0 => '-',
+            _ => (b'a' + draw.below(26) as u8) as char,
+        })
+        .collect()
+}
+
+/// Values one step simpler that still meet the requirements.
+fn shrinks(value: &ConfigValue, required: &Required<'_>) -> Vec<ConfigValue> {
+    match value {
+        ConfigValue::Bool(true) => vec![ConfigValue::Bool(false)],
+        ConfigValue::Bool(false) | ConfigValue::Int(0) => Vec::new(),
+        ConfigValue::Int(n) if n / 2 == 0 => vec![ConfigValue::Int(0)],
+        ConfigValue::Int(n) => vec![ConfigValue::Int(0), ConfigValue::Int(n / 2)],
+        ConfigValue::Float(x) if *x == 0.0 => Vec::new(),
+        ConfigValue::Float(x) => {
+            let mut shrinks = vec![ConfigValue::Float(0.0)];
+            if x.trunc() != *x {
+                shrinks.push(ConfigValue::Float(x.trunc()));
+            }
+            shrinks
+        }
+        ConfigValue::String(s) if s.is_empty() => Vec::new(),
+        ConfigValue::String(s) => {
+            let mut shrinks = vec![ConfigValue::String(String::new())];
+            let half: String = s.chars().take(s.chars().count() / 2).collect();
+            if !half.is_empty() {
+                shrinks.push(ConfigValue::String(half));
+            }
+            shrinks
+        }
+        ConfigValue::List(items) => {
+            let mut shrinks = Vec::new();
+            for i in 0..items.len() {
+                let mut fewer = items.clone();
+                fewer.remove(i);
+                shrinks.push(ConfigValue::List(fewer));
+            }
+            for (i, item) in items.iter().enumerate() {
+                for simpler in self::shrinks(item, &[]) {
+                    let mut items = items.clone();
+                    items[i] = simpler;
+                    shrinks.push(ConfigValue::List(items));
+                }
+            }
+            shrinks
+        }
+        ConfigValue::Map(entries) => {
+            let mut shrinks = Vec::new();
+            for (i, (key, _)) in entries.iter().enumerate() {
+                if !is_required(required, key) {
+                    let mut fewer = entries.clone();
+                    fewer.remove(i);
+                    shrinks.push(ConfigValue::Map(fewer));
+                }
+            }
+            for (i, (key, value)) in entries.iter().enumerate() {
+                for simpler in self::shrinks(value, &below(required, key)) {
+                    let mut entries = entries.clone();
+                    entries[i].1 = simpler;
+                    shrinks.push(ConfigValue::Map(entries));
+                }
+            }
+            shrinks
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data::{Seed, Size};
+
+    fn depth(value: &ConfigValue) -> usize {
+        match value {
+            ConfigValue::List(items) => 1 + items.iter().map(depth).max().unwrap_or(0),
+            ConfigValue::Map(entries) => {
+                1 + entries.iter().map(|(_, v)| depth(v)).max().unwrap_or(0)
+            }
+            _ => 0,
+        }
+    }
+
+    #[test]
+    fn test_required_keys_survive_shrinking() {
+        let gen = Gen::<ConfigValue>::config(
+            ConfigOptions::new()
+                .require("server.port", ConfigKind::Int)
+                .require("server.host", ConfigKind::String)
+                .require("features", ConfigKind::List),
+        );
+        for seed in 0..50 {
+            let tree = gen.generate(Size::new(30), Seed::from_u64(seed));
+            for config in std::iter::once(&tree.value).chain(tree.shrinks()) {
+                assert_eq!(
+                    config.get("server.port").map(ConfigValue::kind),
+                    Some(ConfigKind::Int)
+                );
+                assert_eq!(
+                    config.get("server.host").map(ConfigValue::kind),
+                    Some(ConfigKind::String)
+                );
+                assert_eq!(
+                    config.get("features").map(ConfigValue::kind),
+                    Some(ConfigKind::List)
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_depth_and_width_are_bounded() {
+        let gen = Gen::<ConfigValue>::config(
+            ConfigOptions::new()
+                .with_max_depth(1)
+                .with_max_width(2)
+                .with_weights(0, 1, 1),
+        );
+        let mut nested = false;
+        for seed in 0..50 {
+            let config = gen.generate(Size::new(30), Seed::from_u64(seed)).value;
+            // The top-level map, then one more level of containers
+            assert!(depth(&config) <= 2, "{config:?}");
+            let ConfigValue::Map(entries) = &config else {
+                panic!("top level is not a map");
+            };
+            assert!(entries.len() <= 2);
+            nested |= depth(&config) == 2;
+        }
+        assert!(nested);
+    }
+
+    #[test]
+    fn test_serialisation() {
+        let config = ConfigValue::Map(vec![
+            (
+                "name".to_string(),
+                ConfigValue::String("a \"b\"\n".to_string()),
+            ),
+            ("on".to_string(), ConfigValue::Bool(true)),
+            (
+                "ports".to_string(),
+                ConfigValue::List(vec![ConfigValue::Int(80), ConfigValue::Float(0.5)]),
+            ),
+            (
+                "server".to_string(),
+                ConfigValue::Map(vec![
+                    ("tls".to_string(), ConfigValue::Map(Vec::new())),
+                    ("retries".to_string(), ConfigValue::Int(-3)),
+                ]),
+            ),
+        ]);
+
+        assert_eq!(
+            config.to_json(),
+            r#"{"name":"a \"b\"\n","on":true,"ports":[80,0.5],"server":{"tls":{},"retries":-3}}"#
+        );
+        assert_eq!(
+            config.to_yaml(),
+            "name: \"a \\\"b\\\"\\n\"\n\"on\": true\nports:\n  - 80\n  - 0.5\nserver:\n  tls: {}\n  retries: -3\n"
+        );
+        assert_eq!(
+            config.to_toml(),
+            "name = \"a \\\"b\\\"\\n\"\non = true\nports = [80, 0.5]\n\n[server]\nretries = -3\n\n[server.tls]\n"
+        );
+    }
+}
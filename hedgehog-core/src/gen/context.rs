@@ -0,0 +1,201 @@
+//! Generation contexts for values that refer to each other.
+//!
+//! A [`GenContext`] holds named pools of values generated earlier in the same
+//! test case, so later generators can refer back to them: orders that refer to
+//! existing users, edges between existing nodes, and so on. Each test case gets
+//! a fresh context from [`Gen::with_context`].
+
+use super::Gen;
+use crate::tree::Tree;
+use std::any::Any;
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::rc::Rc;
+
+type Pools = HashMap<String, Vec<Box<dyn Any>>>;
+
+/// Named pools of values generated within a single test case.
+///
+/// Cloning a `GenContext` shares its pools.
+#[derive(Clone, Default)]
+pub struct GenContext {
+    pools: Rc<RefCell<Pools>>,
+}
+
+impl GenContext {
+    /// Create an empty context.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Wrap a generator so every value it generates is added to `pool`.
+    ///
+    /// Recorded values are not shrunk, so values picked from the pool later
+    /// always refer to a value that was generated.
+    pub fn record<T>(&self, pool: &str, gen: Gen<T>) -> Gen<T>
+    where
+        T: Clone + 'static,
+    {
+        let context = self.clone();
+        let pool = pool.to_string();
+        Gen::new(move |size, seed| {
+            let value = gen.generate(size, seed).value;
+            context
+                .pools
+                .borrow_mut()
+                .entry(pool.clone())
+                .or_default()
+                .push(Box::new(value.clone()));
+            Tree::singleton(value)
+        })
+    }
+
+    /// Pick a value recorded in `pool` earlier in the test case.
+    ///
+    /// Generates `None` while the pool has no values of type `T`. Shrinks
+    /// towards values recorded earlier.
+    pub fn pick<T>(&self, pool: &str) -> Gen<Option<T>>
+    where
+        T: Clone + 'static,
+    {
+        let context = self.clone();
+        let pool = pool.to_string();
+        Gen::new(move |_size, seed| {
+            let values = context.values::<T>(&pool);
+            if values.is_empty() {
+                return Tree::singleton(None);
+            }
+
+            let (index, _new_seed) = seed.next_bounded(values.len() as u64);
+            let index = index as usize;
+            let shrinks = (0..index)
+                .map(|earlier| Tree::singleton(Some(values[earlier].clone())))
+                .collect();
+            Tree::with_children(Some(values[index].clone()), shrinks)
+        })
+    }
+
+    /// Values of type `T` recorded in `pool` so far, in the order they were
+    /// generated.
+    pub fn values<T>(&self, pool: &str) -> Vec<T>
+    where
+        T: Clone + 'static,
+    {
+        self.pools
+            .borrow()
+            .get(pool)
+            .map(|values| {
+                values
+                    .iter()
+                    .filter_map(|value| value.downcast_ref::<T>().cloned())
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+}
+
+impl std::fmt::Debug for GenContext {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let pools = self.pools.borrow();
+        let mut sizes: Vec<_> = pools
+            .iter()
+            .map(|(name, values)| (name.clone(), values.len()))
+            .collect();
+        sizes.sort();
+        f.debug_struct("GenContext").field("pools", &sizes).finish()
+    }
+}
+
+impl<T> Gen<T>
+where
+    T: 'static,
+{
+    /// Build a generator from a context that is fresh for every test case.
+    ///
+    /// # Example
+    /// ```rust
+    /// use hedgehog_core::*;
+    ///
+    /// let gen = Gen::with_context(|ctx| {
+    ///     let users = Gen::vec_of(ctx.record("users", Gen::int_range(1, 1000)));
+    ///     let order_user = ctx.pick::<i32>("users");
+    ///     Gen::<(Vec<i32>, Option<i32>)>::tuple_of(users, order_user)
+    /// });
+    /// ```
+    pub fn with_context<F>(f: F) -> Gen<T>
+    where
+        F: Fn(&GenContext) -> Gen<T> + 'static,
+    {
+        Gen::new(move |size, seed| {
+            let context = GenContext::new();
+            f(&context).generate(size, seed)
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data::{Seed, Size};
+
+    #[test]
+    fn test_picks_refer_to_recorded_values() {
+        let gen = Gen::with_context(|ctx| {
+            let users = Gen::vec_of(ctx.record("users", Gen::int_range(1, 1000)));
+            let orders = Gen::vec_of(ctx.pick::<i32>("users"));
+            Gen::<(Vec<i32>, Vec<Option<i32>>)>::tuple_of(users, orders)
+        });
+
+        for seed in 0..20 {
+            let tree = gen.generate(Size::new(20), Seed::from_u64(seed));
+            let (users, orders) = &tree.value;
+            for order in orders {
+                match order {
+                    Some(user) => assert!(users.contains(user)),
+                    None => assert!(users.is_empty()),
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_context_is_fresh_for_each_case() {
+        let gen = Gen::with_context(|ctx| {
+            let ctx = ctx.clone();
+            ctx.record("ids", Gen::int_range(1, 10))
+                .map(move |_| ctx.values::<i32>("ids").len())
+        });
+
+        for seed in 0..5 {
+            assert_eq!(gen.generate(Size::new(10), Seed::from_u64(seed)).value, 1);
+        }
+    }
+
+    #[test]
+    fn test_pick_shrinks_towards_earlier_values() {
+        let ctx = GenContext::new();
+        for id in [10, 20, 30, 40] {
+            ctx.record("ids", Gen::constant(id))
+                .generate(Size::new(1), Seed::from_u64(0));
+        }
+
+        assert_eq!(ctx.pick::<i32>("missing").sample(), None);
+        assert!(ctx.pick::<String>("ids").sample().is_none());
+
+        for seed in 0..10 {
+            let tree = ctx
+                .pick::<i32>("ids")
+                .generate(Size::new(1), Seed::from_u64(seed));
+            let chosen = tree.value.unwrap();
+            let expected: Vec<_> = [10, 20, 30, 40]
+                .into_iter()
+                .take_while(|&id| id != chosen)
+                .map(Some)
+                .collect();
+            assert_eq!(
+                tree.shrinks().into_iter().cloned().collect::<Vec<_>>(),
+                expected
+            );
+        }
+    }
+}
@@ -0,0 +1,335 @@
+//! Date and time generators for `chrono` and `time`.
+//!
+//! Uniformly random instants almost never land on the dates that break date
+//! handling, so half of the generated values are edge cases: DST transitions
+//! (EU and US Eastern rules), leap days, the unix epoch, the 32-bit rollover
+//! in 2038, and the ends of the year 1 to 9999 range. Everything shrinks
+//! towards the unix epoch, and offsets shrink towards UTC.
+
+use super::{towards, Gen};
+use crate::data::{Seed, Size};
+use crate::tree::Tree;
+
+const SECS_PER_HOUR: i64 = 3_600;
+const SECS_PER_DAY: i64 = 86_400;
+const NANOS_PER_SEC: u32 = 1_000_000_000;
+/// 0001-01-01T00:00:00Z
+const MIN_TIMESTAMP: i64 = -62_135_596_800;
+/// 9999-12-31T23:59:59Z
+const MAX_TIMESTAMP: i64 = 253_402_300_799;
+
+/// Instants that commonly break date handling, in seconds since the epoch.
+const NOTABLE_TIMESTAMPS: &[i64] = &[
+    0,
+    -1,
+    1,
+    MIN_TIMESTAMP,
+    MAX_TIMESTAMP,
+    // 1900-01-01, the NTP and spreadsheet epoch
+    -2_208_988_800,
+    // 2000-01-01, and the last second of 1999
+    946_684_800,
+    946_684_799,
+    // The last second representable in a signed 32-bit timestamp, and the next
+    2_147_483_647,
+    2_147_483_648,
+    // The last second representable in an unsigned 32-bit timestamp
+    4_294_967_295,
+];
+
+/// UTC offsets worth covering: UTC, whole hours, half and quarter hours, and
+/// the extremes.
+const NOTABLE_OFFSETS: &[i32] = &[
+    0, 3_600, -3_600, 19_800, 20_700, -12_600, 34_200, 50_400, -43_200,
+];
+
+/// Offsets are generated in quarter hours, like every offset in use today.
+const OFFSET_STEP: i32 = 900;
+
+/// A point in time as seconds and nanoseconds since the unix epoch.
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct Instant {
+    secs: i64,
+    nanos: u32,
+}
+
+impl Instant {
+    fn at(secs: i64) -> Self {
+        Instant { secs, nanos: 0 }
+    }
+}
+
+/// Days since the epoch of a proleptic Gregorian date.
+fn days_from_civil(year: i64, month: u32, day: u32) -> i64 {
+    let year = if month <= 2 { year - 1 } else { year };
+    let era = year.div_euclid(400);
+    let year_of_era = year - era * 400;
+    let month = month as i64;
+    let day_of_year =
+        (153 * (if month > 2 { month - 3 } else { month + 9 }) + 2) / 5 + day as i64 - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+    era * 146_097 + day_of_era - 719_468
+}
+
+/// Day of the week, with Sunday as 0.
+fn weekday(days: i64) -> i64 {
+    // The epoch was a Thursday
+    (days + 4).rem_euclid(7)
+}
+
+/// Seconds since the epoch of the `nth` Sunday of a month, at `hour` UTC.
+fn nth_sunday(year: i64, month: u32, nth: u32, hour: i64) -> i64 {
+    let first = days_from_civil(year, month, 1);
+    let first_sunday = first + (7 - weekday(first)) % 7;
+    (first_sunday + 7 * (nth as i64 - 1)) * SECS_PER_DAY + hour * SECS_PER_HOUR
+}
+
+/// Seconds since the epoch of the last Sunday of a 31-day month, at `hour`
+/// UTC.
+fn last_sunday(year: i64, month: u32, hour: i64) -> i64 {
+    let last = days_from_civil(year, month, 31);
+    (last - weekday(last)) * SECS_PER_DAY + hour * SECS_PER_HOUR
+}
+
+/// Pick an edge-case instant.
+fn edge_instant(seed: Seed) -> Instant {
+    let (kind, seed) = seed.next_bounded(3);
+    let (pick, seed) = seed.next_u64();
+    let (nudge, _) = seed.next_bounded(5);
+    // Either side of the boundary as well as on it
+    let nudge = [-SECS_PER_HOUR, -1, 0, 1, SECS_PER_HOUR][nudge as usize];
+
+    match kind {
+        0 => Instant::at(NOTABLE_TIMESTAMPS[(pick % NOTABLE_TIMESTAMPS.len() as u64) as usize]),
+        1 => {
+            // Midnight at the start of the 29th of February, 1904 to 2096
+            let year = 1904 + 4 * (pick % 49) as i64;
+            Instant::at(days_from_civil(year, 2, 29) * SECS_PER_DAY + nudge)
+        }
+        _ => {
+            let year = 1970 + (pick % 131) as i64;
+            let transition = match (pick >> 32) % 4 {
+                // EU: 01:00 UTC on the last Sundays of March and October
+                0 => last_sunday(year, 3, 1),
+                1 => last_sunday(year, 10, 1),
+                // US Eastern: 2am local on the second Sunday of March and
+                // the first Sunday of November
+                2 => nth_sunday(year, 3, 2, 7),
+                _ => nth_sunday(year, 11, 1, 6),
+            };
+            Instant::at(transition + nudge)
+        }
+    }
+}
+
+/// Generate instants between the years 1 and 9999, half of them edge cases.
+fn instant_tree(seed: Seed) -> Tree<Instant> {
+    let (edge, seed) = seed.next_bounded(2);
+    let (value_seed, nanos_seed) = seed.split();
+
+    let instant = if edge == 0 {
+        edge_instant(value_seed)
+    } else {
+        let span = (MAX_TIMESTAMP - MIN_TIMESTAMP) as u64 + 1;
+        let (offset, _) = value_seed.next_bounded(span);
+        let (nanos, _) = nanos_seed.next_bounded(NANOS_PER_SEC as u64);
+        Instant {
+            secs: MIN_TIMESTAMP + offset as i64,
+            nanos: nanos as u32,
+        }
+    };
+
+    let mut shrinks = Vec::new();
+    if instant.nanos != 0 {
+        shrinks.push(Tree::singleton(Instant::at(instant.secs)));
+    }
+    shrinks.extend(
+        towards(0, instant.secs)
+            .into_iter()
+            .map(|secs| Tree::singleton(Instant::at(secs))),
+    );
+    Tree::with_children(instant, shrinks)
+}
+
+/// Generate UTC offsets in seconds east of UTC, from -12:00 to +14:00.
+fn offset_tree(seed: Seed) -> Tree<i32> {
+    let (notable, seed) = seed.next_bounded(2);
+    let steps = if notable == 0 {
+        let (index, _) = seed.next_bounded(NOTABLE_OFFSETS.len() as u64);
+        NOTABLE_OFFSETS[index as usize] / OFFSET_STEP
+    } else {
+        // -48 to +56 quarter hours
+        let (steps, _) = seed.next_bounded(105);
+        steps as i32 - 48
+    };
+
+    let shrinks = towards(0, steps)
+        .into_iter()
+        .map(|steps| Tree::singleton(steps * OFFSET_STEP))
+        .collect();
+    Tree::with_children(steps * OFFSET_STEP, shrinks)
+}
+
+fn instant_gen() -> Gen<Instant> {
+    Gen::new(|_size: Size, seed| instant_tree(seed))
+}
+
+fn offset_gen() -> Gen<i32> {
+    Gen::new(|_size: Size, seed| offset_tree(seed))
+}
+
+#[cfg(feature = "chrono")]
+impl Gen<chrono::DateTime<chrono::Utc>> {
+    /// Generate UTC datetimes from the year 1 to 9999, biased towards DST
+    /// transitions, leap days and epoch boundaries, shrinking towards the
+    /// unix epoch.
+    ///
+    /// # Example
+    /// ```rust
+    /// use hedgehog_core::*;
+    ///
+    /// let gen = Gen::<chrono::DateTime<chrono::Utc>>::datetime_utc();
+    /// ```
+    pub fn datetime_utc() -> Self {
+        instant_gen().map(|instant| {
+            chrono::DateTime::from_timestamp(instant.secs, instant.nanos)
+                .expect("instant is within chrono's range")
+        })
+    }
+}
+
+#[cfg(feature = "chrono")]
+impl Gen<chrono::DateTime<chrono::FixedOffset>> {
+    /// Generate datetimes like [`datetime_utc`](Gen::datetime_utc), in
+    /// offsets from -12:00 to +14:00 including the half and quarter hour
+    /// ones. Shrinks the instant first, then the offset towards UTC.
+    pub fn datetime_with_offset() -> Self {
+        Gen::<(Instant, i32)>::tuple_of(instant_gen(), offset_gen()).map(|(instant, offset)| {
+            let offset = chrono::FixedOffset::east_opt(offset).expect("offset is less than a day");
+            chrono::DateTime::from_timestamp(instant.secs, instant.nanos)
+                .expect("instant is within chrono's range")
+                .with_timezone(&offset)
+        })
+    }
+}
+
+#[cfg(feature = "time")]
+impl Gen<time::OffsetDateTime> {
+    /// Generate UTC datetimes from the year 1 to 9999, biased towards DST
+    /// transitions, leap days and epoch boundaries, shrinking towards the
+    /// unix epoch.
+    ///
+    /// # Example
+    /// ```rust
+    /// use hedgehog_core::*;
+    ///
+    /// let gen = Gen::<time::OffsetDateTime>::datetime_utc();
+    /// ```
+    pub fn datetime_utc() -> Self {
+        instant_gen().map(to_offset_datetime)
+    }
+
+    /// Generate datetimes like [`datetime_utc`](Gen::datetime_utc), in
+    /// offsets from -12:00 to +14:00 including the half and quarter hour
+    /// ones. Shrinks the instant first, then the offset towards UTC.
+    pub fn datetime_with_offset() -> Self {
+        Gen::<(Instant, i32)>::tuple_of(instant_gen(), offset_gen()).map(|(instant, offset)| {
+            let offset =
+                time::UtcOffset::from_whole_seconds(offset).expect("offset is less than a day");
+            to_offset_datetime(instant).to_offset(offset)
+        })
+    }
+}
+
+#[cfg(feature = "time")]
+fn to_offset_datetime(instant: Instant) -> time::OffsetDateTime {
+    time::OffsetDateTime::from_unix_timestamp(instant.secs)
+        .expect("instant is within time's range")
+        .replace_nanosecond(instant.nanos)
+        .expect("nanoseconds are less than a second")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_calendar_arithmetic() {
+        assert_eq!(days_from_civil(1970, 1, 1), 0);
+        assert_eq!(days_from_civil(2000, 3, 1), 11_017);
+        assert_eq!(days_from_civil(1, 1, 1) * SECS_PER_DAY, MIN_TIMESTAMP);
+        assert_eq!(
+            days_from_civil(9999, 12, 31) * SECS_PER_DAY + SECS_PER_DAY - 1,
+            MAX_TIMESTAMP
+        );
+
+        // 2024-03-31T01:00Z and 2024-10-27T01:00Z in the EU
+        assert_eq!(last_sunday(2024, 3, 1), 1_711_846_800);
+        assert_eq!(last_sunday(2024, 10, 1), 1_729_990_800);
+        // 2024-03-10T07:00Z and 2024-11-03T06:00Z in New York
+        assert_eq!(nth_sunday(2024, 3, 2, 7), 1_710_054_000);
+        assert_eq!(nth_sunday(2024, 11, 1, 6), 1_730_613_600);
+    }
+
+    #[test]
+    fn test_instants_stay_in_range_and_shrink_to_epoch() {
+        let mut edges = 0;
+        for seed in 0..200 {
+            let tree = instant_tree(Seed::from_u64(seed));
+            let instant = tree.value;
+            assert!((MIN_TIMESTAMP..=MAX_TIMESTAMP).contains(&instant.secs));
+            assert!(instant.nanos < NANOS_PER_SEC);
+            if instant.nanos == 0 {
+                edges += 1;
+            }
+            if instant != Instant::at(0) {
+                assert!(tree.shrinks().contains(&&Instant::at(0)));
+            }
+        }
+        assert!(edges > 50, "only {edges} edge cases in 200");
+    }
+
+    #[test]
+    fn test_offsets_are_quarter_hours_within_bounds() {
+        for seed in 0..100 {
+            let tree = offset_tree(Seed::from_u64(seed));
+            assert!((-43_200..=50_400).contains(&tree.value));
+            assert_eq!(tree.value % OFFSET_STEP, 0);
+            if tree.value != 0 {
+                assert_eq!(*tree.shrinks()[0], 0);
+            }
+        }
+    }
+
+    #[cfg(feature = "chrono")]
+    #[test]
+    fn test_chrono_datetimes() {
+        use chrono::{Datelike, Offset};
+
+        let gen = Gen::<chrono::DateTime<chrono::FixedOffset>>::datetime_with_offset();
+        for seed in 0..50 {
+            let tree = gen.generate(Size::new(10), Seed::from_u64(seed));
+            assert!((1..=9999).contains(&tree.value.year()));
+            if tree.value.timestamp() != 0 {
+                assert!(tree.shrinks().iter().any(|shrink| {
+                    shrink.timestamp() == 0 && shrink.offset() == tree.value.offset()
+                }));
+            }
+        }
+
+        let utc = Gen::<chrono::DateTime<chrono::Utc>>::datetime_utc()
+            .generate(Size::new(10), Seed::from_u64(0));
+        assert_eq!(utc.value.offset().fix().local_minus_utc(), 0);
+    }
+
+    #[cfg(feature = "time")]
+    #[test]
+    fn test_time_datetimes() {
+        let gen = Gen::<time::OffsetDateTime>::datetime_with_offset();
+        for seed in 0..50 {
+            let tree = gen.generate(Size::new(10), Seed::from_u64(seed));
+            let offset = tree.value.offset().whole_seconds();
+            assert!((-43_200..=50_400).contains(&offset));
+        }
+    }
+}
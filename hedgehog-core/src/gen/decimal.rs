@@ -0,0 +1,208 @@
+//! Fixed-point decimal generators for `rust_decimal`.
+//!
+//! Money can't be tested with `f64`, and uniformly random decimals rarely hit
+//! the values that break rounding and formatting code. Half of the generated
+//! values are edge cases: zero, negative zero, the ends of the range, and
+//! values exactly half way between two roundings.
+
+use super::{towards, Gen};
+use crate::data::Seed;
+use crate::tree::Tree;
+use rust_decimal::{Decimal, RoundingStrategy};
+
+/// The largest mantissa a `Decimal` can hold, `2^96 - 1`.
+const MAX_MANTISSA: i128 = (1 << 96) - 1;
+
+impl Gen<Decimal> {
+    /// Generate decimals with exactly `scale` digits after the point, over
+    /// the whole range a `Decimal` of that scale can hold.
+    ///
+    /// Returns an error if `scale` is above [`Decimal::MAX_SCALE`].
+    ///
+    /// # Example
+    /// ```rust
+    /// use hedgehog_core::*;
+    /// use rust_decimal::Decimal;
+    ///
+    /// // Amounts in cents
+    /// let gen = Gen::<Decimal>::decimal(2).unwrap();
+    /// ```
+    pub fn decimal(scale: u32) -> crate::Result<Self> {
+        check_scale(scale)?;
+        Ok(mantissa_gen(-MAX_MANTISSA, MAX_MANTISSA, scale))
+    }
+
+    /// Generate decimals with exactly `scale` digits after the point, from
+    /// `min` to `max` inclusive, shrinking towards the value closest to zero.
+    ///
+    /// Bounds with more digits than `scale` are rounded inwards. Returns an
+    /// error if `scale` is above [`Decimal::MAX_SCALE`] or if no value of
+    /// that scale lies in the range.
+    ///
+    /// # Example
+    /// ```rust
+    /// use hedgehog_core::*;
+    /// use rust_decimal::Decimal;
+    ///
+    /// // Prices from 0.01 to 10,000.00
+    /// let gen = Gen::<Decimal>::decimal_range(Decimal::new(1, 2), Decimal::new(10_000, 0), 2)
+    ///     .unwrap();
+    /// ```
+    pub fn decimal_range(min: Decimal, max: Decimal, scale: u32) -> crate::Result<Self> {
+        check_scale(scale)?;
+        let lo = mantissa_at(min, scale, RoundingStrategy::ToPositiveInfinity);
+        let hi = mantissa_at(max, scale, RoundingStrategy::ToNegativeInfinity);
+        if lo > hi {
+            return Err(crate::HedgehogError::InvalidGenerator {
+                message: format!("no decimal with scale {scale} lies between {min} and {max}"),
+            });
+        }
+        Ok(mantissa_gen(lo, hi, scale))
+    }
+}
+
+fn check_scale(scale: u32) -> crate::Result<()> {
+    if scale > Decimal::MAX_SCALE {
+        return Err(crate::HedgehogError::InvalidGenerator {
+            message: format!(
+                "decimal scale {scale} is above the maximum of {}",
+                Decimal::MAX_SCALE
+            ),
+        });
+    }
+    Ok(())
+}
+
+/// The mantissa of `value` at `scale`, rounding with `strategy` when that
+/// drops digits.
+fn mantissa_at(value: Decimal, scale: u32, strategy: RoundingStrategy) -> i128 {
+    let rounded = value.round_dp_with_strategy(scale, strategy);
+    let mut mantissa = rounded.mantissa();
+    for _ in rounded.scale()..scale {
+        mantissa = mantissa.saturating_mul(10);
+    }
+    mantissa.clamp(-MAX_MANTISSA, MAX_MANTISSA)
+}
+
+/// Generate decimals whose mantissas lie in `lo..=hi`.
+fn mantissa_gen(lo: i128, hi: i128, scale: u32) -> Gen<Decimal> {
+    let origin = 0.clamp(lo, hi);
+
+    Gen::new(move |_size, seed| {
+        let (edge, seed) = seed.next_bounded(2);
+        let (value_seed, edge_seed) = seed.split();
+        let mantissa = next_in_range(value_seed, lo, hi);
+
+        if edge == 0 {
+            let (kind, edge_seed) = edge_seed.next_bounded(4);
+            match kind {
+                0 if origin == 0 => {
+                    let mut negative_zero = Decimal::new(0, scale);
+                    negative_zero.set_sign_negative(true);
+                    let zero = Decimal::new(0, scale);
+                    return Tree::with_children(negative_zero, vec![Tree::singleton(zero)]);
+                }
+                0 | 1 => return mantissa_tree(origin, origin, scale),
+                2 => {
+                    let (high, _) = edge_seed.next_bool();
+                    return mantissa_tree(if high { hi } else { lo }, origin, scale);
+                }
+                _ => {
+                    let halfway = halfway_near(mantissa, edge_seed, scale);
+                    if (lo..=hi).contains(&halfway) {
+                        return mantissa_tree(halfway, origin, scale);
+                    }
+                }
+            }
+        }
+
+        mantissa_tree(mantissa, origin, scale)
+    })
+}
+
+/// A mantissa close to `mantissa` that is exactly half way between two
+/// roundings to fewer decimal places, such as `2.5` or `0.125`.
+fn halfway_near(mantissa: i128, seed: Seed, scale: u32) -> i128 {
+    let (dropped, _) = seed.next_bounded(scale.max(1) as u64);
+    let unit = 10i128.pow(dropped as u32 + 1);
+    let half = unit / 2;
+    let base = mantissa - mantissa % unit;
+    if mantissa < 0 {
+        base - half
+    } else {
+        base + half
+    }
+}
+
+fn mantissa_tree(mantissa: i128, origin: i128, scale: u32) -> Tree<Decimal> {
+    let shrinks = towards(origin, mantissa)
+        .into_iter()
+        .map(|shrink| Tree::singleton(Decimal::from_i128_with_scale(shrink, scale)))
+        .collect();
+    Tree::with_children(Decimal::from_i128_with_scale(mantissa, scale), shrinks)
+}
+
+/// A uniformly random integer in `lo..=hi`, for spans wider than `u64`.
+fn next_in_range(seed: Seed, lo: i128, hi: i128) -> i128 {
+    let span = (hi - lo) as u128 + 1;
+    let (high, seed) = seed.next_u64();
+    let (low, _) = seed.next_u64();
+    let random = ((high as u128) << 64) | low as u128;
+    lo + (random % span) as i128
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data::Size;
+
+    #[test]
+    fn test_decimals_keep_their_scale_and_shrink_to_zero() {
+        let gen = Gen::<Decimal>::decimal(2).unwrap();
+        let mut negative_zero = false;
+        let mut halfway = false;
+        for seed in 0..200 {
+            let tree = gen.generate(Size::new(10), Seed::from_u64(seed));
+            assert_eq!(tree.value.scale(), 2);
+            if tree.value.is_zero() && tree.value.is_sign_negative() {
+                negative_zero = true;
+            }
+            if tree.value.mantissa().abs() % 10 == 5 {
+                halfway = true;
+            }
+            if !tree.value.is_zero() {
+                assert_eq!(*tree.shrinks()[0], Decimal::new(0, 2));
+            }
+        }
+        assert!(negative_zero);
+        assert!(halfway);
+    }
+
+    #[test]
+    fn test_decimal_range_stays_in_bounds() {
+        let min = Decimal::new(1005, 3);
+        let max = Decimal::new(2, 0);
+        let gen = Gen::<Decimal>::decimal_range(min, max, 2).unwrap();
+        let mut seen_bounds = (false, false);
+        for seed in 0..200 {
+            let tree = gen.generate(Size::new(10), Seed::from_u64(seed));
+            let value = tree.value;
+            assert!(value >= Decimal::new(101, 2) && value <= max, "{value}");
+            assert_eq!(value.scale(), 2);
+            seen_bounds.0 |= value == Decimal::new(101, 2);
+            seen_bounds.1 |= value == max;
+            if value != Decimal::new(101, 2) {
+                assert_eq!(*tree.shrinks()[0], Decimal::new(101, 2));
+            }
+        }
+        assert_eq!(seen_bounds, (true, true));
+    }
+
+    #[test]
+    fn test_decimal_rejects_bad_parameters() {
+        assert!(Gen::<Decimal>::decimal(29).is_err());
+        assert!(
+            Gen::<Decimal>::decimal_range(Decimal::new(11, 3), Decimal::new(19, 3), 2).is_err()
+        );
+    }
+}
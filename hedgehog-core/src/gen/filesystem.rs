@@ -0,0 +1,467 @@
+//! Directory trees for testing tools that walk, copy or back up files.
+//!
+//! A [`DirTree`] describes files, directories and symlinks as plain data, so
+//! it can be generated, printed and shrunk like any other value, then
+//! written into a [`TempDir`] that removes itself when dropped. Generated
+//! trees vary in depth, width and file size, include awkward names such as
+//! dotfiles and names with spaces, and can link back up the tree or to
+//! entries that don't exist. [`DirTree::read_from`] reads a directory back,
+//! so a copy can be compared against the tree it came from.
+//!
+//! # Example
+//! ```rust
+//! use hedgehog_core::*;
+//!
+//! let prop = for_all(Gen::<DirTree>::dir_tree(DirTreeOptions::new()), |tree| {
+//!     let dir = tree.materialize().unwrap();
+//!     DirTree::read_from(dir.path()).unwrap() == *tree
+//! });
+//! ```
+
+use super::{Draw, Gen};
+use crate::tree::Tree;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+/// The contents of a directory, sorted by name.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct DirTree {
+    pub entries: Vec<(String, FsEntry)>,
+}
+
+/// A file, directory or symlink in a [`DirTree`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum FsEntry {
+    File(Vec<u8>),
+    Dir(DirTree),
+    /// A link to a path relative to the directory holding it, which may not
+    /// exist
+    Symlink(PathBuf),
+}
+
+/// Options for [`Gen::dir_tree`].
+#[derive(Debug, Clone)]
+pub struct DirTreeOptions {
+    max_depth: usize,
+    max_width: usize,
+    max_file_size: usize,
+    symlinks: bool,
+    awkward_names: bool,
+}
+
+impl DirTreeOptions {
+    /// Trees up to three directories deep, with up to five entries in each
+    /// directory, files of up to 4 KiB, symlinks and awkward names.
+    pub fn new() -> Self {
+        DirTreeOptions {
+            max_depth: 3,
+            max_width: 5,
+            max_file_size: 4096,
+            symlinks: true,
+            awkward_names: true,
+        }
+    }
+
+    /// Nest directories at most `depth` levels below the root.
+    pub fn with_max_depth(mut self, depth: usize) -> Self {
+        self.max_depth = depth;
+        self
+    }
+
+    /// Put at most `width` entries in each directory.
+    pub fn with_max_width(mut self, width: usize) -> Self {
+        self.max_width = width;
+        self
+    }
+
+    /// Make files at most `bytes` long.
+    pub fn with_max_file_size(mut self, bytes: usize) -> Self {
+        self.max_file_size = bytes;
+        self
+    }
+
+    /// Whether to include symlinks. Writing them is only supported on Unix.
+    pub fn with_symlinks(mut self, symlinks: bool) -> Self {
+        self.symlinks = symlinks;
+        self
+    }
+
+    /// Whether to include names with spaces, leading dots and dashes,
+    /// non-ASCII characters and the like.
+    pub fn with_awkward_names(mut self, awkward: bool) -> Self {
+        self.awkward_names = awkward;
+        self
+    }
+}
+
+impl Default for DirTreeOptions {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl DirTree {
+    /// Write the tree into `dir`, which must already exist.
+    pub fn write_to(&self, dir: &Path) -> io::Result<()> {
+        for (name, entry) in &self.entries {
+            let path = dir.join(name);
+            match entry {
+                FsEntry::File(contents) => std::fs::write(&path, contents)?,
+                FsEntry::Dir(tree) => {
+                    std::fs::create_dir(&path)?;
+                    tree.write_to(&path)?;
+                }
+                FsEntry::Symlink(target) => symlink(target, &path)?,
+            }
+        }
+        Ok(())
+    }
+
+    /// Write the tree into a new temporary directory.
+    pub fn materialize(&self) -> io::Result<TempDir> {
+        let dir = TempDir::new()?;
+        self.write_to(dir.path())?;
+        Ok(dir)
+    }
+
+    /// Read the tree under `dir`, without following symlinks.
+    pub fn read_from(dir: &Path) -> io::Result<DirTree> {
+        let mut entries = Vec::new();
+        for entry in std::fs::read_dir(dir)? {
+            let entry = entry?;
+            let name = entry.file_name().into_string().map_err(|name| {
+                io::Error::new(io::ErrorKind::InvalidData, format!("{name:?} is not UTF-8"))
+            })?;
+            let kind = entry.file_type()?;
+            let entry = if kind.is_symlink() {
+                FsEntry::Symlink(std::fs::read_link(entry.path())?)
+            } else if kind.is_dir() {
+                FsEntry::Dir(DirTree::read_from(&entry.path())?)
+            } else {
+                FsEntry::File(std::fs::read(entry.path())?)
+            };
+            entries.push((name, entry));
+        }
+        entries.sort_by(|(a, _), (b, _)| a.cmp(b));
+        Ok(DirTree { entries })
+    }
+
+    /// The path of every entry relative to the root, parents before their
+    /// contents.
+    pub fn paths(&self) -> Vec<PathBuf> {
+        let mut paths = Vec::new();
+        self.collect_paths(Path::new(""), &mut paths);
+        paths
+    }
+
+    /// The total number of bytes in files.
+    pub fn total_size(&self) -> usize {
+        self.entries
+            .iter()
+            .map(|(_, entry)| match entry {
+                FsEntry::File(contents) => contents.len(),
+                FsEntry::Dir(tree) => tree.total_size(),
+                FsEntry::Symlink(_) => 0,
+            })
+            .sum()
+    }
+
+    /// How many directories deep the tree goes, zero for a tree with no
+    /// directories.
+    pub fn depth(&self) -> usize {
+        self.entries
+            .iter()
+            .map(|(_, entry)| match entry {
+                FsEntry::Dir(tree) => 1 + tree.depth(),
+                _ => 0,
+            })
+            .max()
+            .unwrap_or(0)
+    }
+
+    fn collect_paths(&self, parent: &Path, paths: &mut Vec<PathBuf>) {
+        for (name, entry) in &self.entries {
+            let path = parent.join(name);
+            paths.push(path.clone());
+            if let FsEntry::Dir(tree) = entry {
+                tree.collect_paths(&path, paths);
+            }
+        }
+    }
+}
+
+#[cfg(unix)]
+fn symlink(target: &Path, link: &Path) -> io::Result<()> {
+    std::os::unix::fs::symlink(target, link)
+}
+
+#[cfg(not(unix))]
+fn symlink(_target: &Path, link: &Path) -> io::Result<()> {
+    Err(io::Error::new(
+        io::ErrorKind::Unsupported,
+        format!("can't create symlink {}", link.display()),
+    ))
+}
+
+/// A directory under the system's temporary directory, removed with
+/// everything in it when dropped.
+#[derive(Debug)]
+pub struct TempDir {
+    path: PathBuf,
+}
+
+impl TempDir {
+    /// Create an empty directory with a name no other `TempDir` uses.
+    pub fn new() -> io::Result<TempDir> {
+        static CREATED: AtomicUsize = AtomicUsize::new(0);
+        let nanos = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map_or(0, |elapsed| elapsed.subsec_nanos());
+        let path = std::env::temp_dir().join(format!(
+            "hedgehog-{}-{}-{nanos}",
+            std::process::id(),
+            CREATED.fetch_add(1, Ordering::Relaxed)
+        ));
+        std::fs::create_dir(&path)?;
+        Ok(TempDir { path })
+    }
+
+    /// Where the directory is.
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+}
+
+impl Drop for TempDir {
+    fn drop(&mut self) {
+        // Symlinks are removed, not followed
+        let _ = std::fs::remove_dir_all(&self.path);
+    }
+}
+
+impl Gen<DirTree> {
+    /// Generate directory trees shaped by `options`.
+    ///
+    /// Trees and files grow with size. Symlinks point at other entries in
+    /// the tree, at directories above them and sometimes at nothing.
+    /// Shrinking removes entries and empties files, so a failure comes down
+    /// to the few entries that cause it.
+    ///
+    /// # Example
+    /// ```rust
+    /// use hedgehog_core::*;
+    ///
+    /// let gen = Gen::<DirTree>::dir_tree(DirTreeOptions::new().with_max_depth(1));
+    /// let tree = gen.generate(Size::new(20), Seed::from_u64(7)).value;
+    /// assert!(tree.depth() <= 1);
+    /// ```
+    pub fn dir_tree(options: DirTreeOptions) -> Self {
+        Gen::new(move |size, seed| {
+            let generator = Generator {
+                options: &options,
+                size: size.get(),
+            };
+            let mut draw = Draw(seed);
+            let mut tree = generator.dir(0, &mut draw);
+            let targets = tree.paths();
+            link(&mut tree, 0, &targets, &mut draw);
+            let shrinks = shrinks(&tree).into_iter().map(Tree::singleton).collect();
+            Tree::with_children(tree, shrinks)
+        })
+    }
+}
+
+struct Generator<'a> {
+    options: &'a DirTreeOptions,
+    size: usize,
+}
+
+impl Generator<'_> {
+    fn dir(&self, depth: usize, draw: &mut Draw) -> DirTree {
+        let width = draw.below(self.size.min(self.options.max_width) + 1);
+        let mut entries: Vec<(String, FsEntry)> = Vec::new();
+        for _ in 0..width {
+            let name = self.name(draw);
+            // Case-insensitive file systems would merge names differing
+            // only in case
+            if entries
+                .iter()
+                .any(|(taken, _)| taken.to_lowercase() == name.to_lowercase())
+            {
+                continue;
+            }
+            let entry = match draw.below(10) {
+                0..=2 if depth < self.options.max_depth => FsEntry::Dir(self.dir(depth + 1, draw)),
+                3 if self.options.symlinks => FsEntry::Symlink(PathBuf::new()),
+                _ => FsEntry::File(self.contents(draw)),
+            };
+            entries.push((name, entry));
+        }
+        entries.sort_by(|(a, _), (b, _)| a.cmp(b));
+        DirTree { entries }
+    }
+
+    fn name(&self, draw: &mut Draw) -> String {
+        const AWKWARD: &[&str] = &[
+            ".hidden",
+            "with space",
+            "-starts-with-dash",
+            "ünïcödé",
+            "UPPER.TXT",
+            "many.dots.in.name",
+            "#hash",
+            "trailing.",
+            "日本語",
+        ];
+        if self.options.awkward_names && draw.below(8) == 0 {
+            return AWKWARD[draw.below(AWKWARD.len())].to_string();
+        }
+        let len = 1 + draw.below(8);
+        let stem: String = (0..len)
+            .map(|_| (b'a' + draw.below(26) as u8) as char)
+            .collect();
+        let extension = ["", ".txt", ".rs", ".log", ".tar.gz"][draw.below(5)];
+        stem + extension
+    }
+
+    fn contents(&self, draw: &mut Draw) -> Vec<u8> {
+        let len = match draw.below(6) {
+            0 => 0,
+            _ => draw.below((self.size * 64).min(self.options.max_file_size) + 1),
+        };
+        (0..len).map(|_| draw.below(256) as u8).collect()
+    }
+}
+
+/// Point each placeholder symlink at an entry of the whole tree, at a
+/// directory above it or at nothing.
+fn link(tree: &mut DirTree, depth: usize, targets: &[PathBuf], draw: &mut Draw) {
+    for (_, entry) in &mut tree.entries {
+        match entry {
+            FsEntry::Dir(subtree) => link(subtree, depth + 1, targets, draw),
+            FsEntry::Symlink(target) => {
+                let up: PathBuf = std::iter::repeat_n("..", depth).collect();
+                *target = match draw.below(6) {
+                    0 => PathBuf::from("missing"),
+                    1 if depth > 0 => PathBuf::from(".."),
+                    _ => up.join(&targets[draw.below(targets.len())]),
+                };
+            }
+            FsEntry::File(_) => {}
+        }
+    }
+}
+
+/// Trees one step simpler: with an entry removed, a file emptied or
+/// halved, or a directory simplified.
+fn shrinks(tree: &DirTree) -> Vec<DirTree> {
+    let mut shrinks = Vec::new();
+    for i in 0..tree.entries.len() {
+        let mut fewer = tree.clone();
+        fewer.entries.remove(i);
+        shrinks.push(fewer);
+    }
+    for (i, (_, entry)) in tree.entries.iter().enumerate() {
+        let simpler: Vec<FsEntry> = match entry {
+            FsEntry::File(contents) if contents.is_empty() => Vec::new(),
+            FsEntry::File(contents) if contents.len() == 1 => vec![FsEntry::File(Vec::new())],
+            FsEntry::File(contents) => vec![
+                FsEntry::File(Vec::new()),
+                FsEntry::File(contents[..contents.len() / 2].to_vec()),
+            ],
+            FsEntry::Dir(subtree) => self::shrinks(subtree)
+                .into_iter()
+                .map(FsEntry::Dir)
+                .collect(),
+            FsEntry::Symlink(_) => Vec::new(),
+        };
+        for entry in simpler {
+            let mut tree = tree.clone();
+            tree.entries[i].1 = entry;
+            shrinks.push(tree);
+        }
+    }
+    shrinks
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data::{Seed, Size};
+
+    fn symlinks(tree: &DirTree) -> usize {
+        tree.entries
+            .iter()
+            .map(|(_, entry)| match entry {
+                FsEntry::Dir(subtree) => symlinks(subtree),
+                FsEntry::Symlink(_) => 1,
+                FsEntry::File(_) => 0,
+            })
+            .sum()
+    }
+
+    #[test]
+    fn test_trees_respect_options() {
+        let gen = Gen::<DirTree>::dir_tree(
+            DirTreeOptions::new()
+                .with_max_depth(1)
+                .with_max_width(3)
+                .with_max_file_size(10)
+                .with_symlinks(false),
+        );
+        let mut nested = false;
+        for seed in 0..100 {
+            let tree = gen.generate(Size::new(50), Seed::from_u64(seed)).value;
+            assert!(tree.depth() <= 1, "{tree:?}");
+            assert!(tree.entries.len() <= 3);
+            assert!(tree.total_size() <= 10 * tree.paths().len());
+            assert_eq!(symlinks(&tree), 0);
+            nested |= tree.depth() == 1;
+        }
+        assert!(nested);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_materialized_trees_read_back_and_clean_up() {
+        let gen = Gen::<DirTree>::dir_tree(DirTreeOptions::new());
+        let mut linked = false;
+        for seed in 0..30 {
+            let tree = gen.generate(Size::new(30), Seed::from_u64(seed)).value;
+            linked |= symlinks(&tree) > 0;
+            let dir = tree.materialize().unwrap();
+            let path = dir.path().to_path_buf();
+            assert_eq!(DirTree::read_from(&path).unwrap(), tree);
+            drop(dir);
+            assert!(!path.exists());
+        }
+        assert!(linked);
+    }
+
+    #[test]
+    fn test_shrinks_remove_entries_and_empty_files() {
+        let tree = DirTree {
+            entries: vec![
+                ("a".to_string(), FsEntry::File(vec![1, 2])),
+                (
+                    "b".to_string(),
+                    FsEntry::Dir(DirTree {
+                        entries: vec![("c".to_string(), FsEntry::File(Vec::new()))],
+                    }),
+                ),
+            ],
+        };
+        let shrinks = shrinks(&tree);
+        assert_eq!(shrinks.len(), 5);
+        assert_eq!(
+            shrinks[0].paths(),
+            [PathBuf::from("b"), PathBuf::from("b/c")]
+        );
+        assert_eq!(shrinks[1].paths(), [PathBuf::from("a")]);
+        assert_eq!(shrinks[2].entries[0].1, FsEntry::File(Vec::new()));
+        assert_eq!(shrinks[3].entries[0].1, FsEntry::File(vec![1]));
+        assert_eq!(shrinks[4].depth(), 1);
+        assert_eq!(shrinks[4].paths().len(), 2);
+    }
+}
@@ -0,0 +1,221 @@
+//! Generated functions that can be shown when a property fails.
+//!
+//! A boxed closure can't be printed, so a counterexample involving one says
+//! nothing about what the function did. [`Fun`] keeps the lookup table it was
+//! built from and prints it instead: `f(0)=3, f(2)=7, otherwise 1`.
+
+use super::Gen;
+use crate::tree::Tree;
+use std::fmt;
+
+/// A finite function: a lookup table with a default for every other input.
+#[derive(Clone, PartialEq)]
+pub struct Fun<A, B> {
+    table: Vec<(A, B)>,
+    default: B,
+}
+
+impl<A, B> Fun<A, B>
+where
+    A: PartialEq,
+    B: Clone,
+{
+    /// Create a function from a lookup table and a default output.
+    ///
+    /// When an input appears more than once the first entry wins.
+    pub fn new(table: Vec<(A, B)>, default: B) -> Self {
+        let mut unique: Vec<(A, B)> = Vec::with_capacity(table.len());
+        for (input, output) in table {
+            if !unique.iter().any(|(existing, _)| *existing == input) {
+                unique.push((input, output));
+            }
+        }
+        Fun {
+            table: unique,
+            default,
+        }
+    }
+
+    /// Apply the function to an input.
+    pub fn apply(&self, input: &A) -> B {
+        self.table
+            .iter()
+            .find(|(key, _)| key == input)
+            .map_or_else(|| self.default.clone(), |(_, output)| output.clone())
+    }
+
+    /// The inputs with their own outputs, in the order they were generated.
+    pub fn table(&self) -> &[(A, B)] {
+        &self.table
+    }
+
+    /// The output for every input not in the table.
+    pub fn default_output(&self) -> &B {
+        &self.default
+    }
+}
+
+impl<A, B> fmt::Display for Fun<A, B>
+where
+    A: fmt::Debug,
+    B: fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (input, output) in &self.table {
+            write!(f, "f({input:?})={output:?}, ")?;
+        }
+        write!(f, "otherwise {:?}", self.default)
+    }
+}
+
+impl<A, B> fmt::Debug for Fun<A, B>
+where
+    A: fmt::Debug,
+    B: fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(self, f)
+    }
+}
+
+impl<A, B> Gen<Fun<A, B>>
+where
+    A: 'static + Clone + PartialEq,
+    B: 'static + Clone,
+{
+    /// Generate finite functions with outputs from `output_gen` for inputs
+    /// from `input_gen`, and a generated default for everything else.
+    ///
+    /// Shrinks by dropping the whole table, dropping single entries, and
+    /// shrinking individual outputs and the default.
+    ///
+    /// # Example
+    /// ```rust
+    /// use hedgehog_core::*;
+    ///
+    /// let gen = Gen::<Fun<i32, i32>>::fun_of(Gen::int_range(0, 10), Gen::int_range(0, 100));
+    /// let f = gen.sample();
+    /// assert_eq!(f.apply(&-1), *f.default_output());
+    /// ```
+    pub fn fun_of(input_gen: Gen<A>, output_gen: Gen<B>) -> Self {
+        Gen::new(move |size, seed| {
+            let (table_size_seed, rest_seed) = seed.split();
+            let (default_seed, entries_seed) = rest_seed.split();
+            let (table_size, _) = table_size_seed.next_bounded((size.get() + 1) as u64);
+            let table_size = (table_size as usize).min(20);
+
+            let default_tree = output_gen.generate(size, default_seed);
+
+            let mut current_seed = entries_seed;
+            let mut inputs: Vec<A> = Vec::new();
+            let mut output_trees = Vec::new();
+            for _ in 0..table_size {
+                let (input_seed, rest) = current_seed.split();
+                let (output_seed, next_seed) = rest.split();
+                current_seed = next_seed;
+
+                let input = input_gen.generate(size, input_seed).value;
+                if !inputs.contains(&input) {
+                    inputs.push(input);
+                    output_trees.push(output_gen.generate(size, output_seed));
+                }
+            }
+
+            let default = default_tree.value.clone();
+            let outputs: Vec<B> = output_trees.iter().map(|tree| tree.value.clone()).collect();
+            let build = |outputs: Vec<B>, default: B| Fun {
+                table: inputs.iter().cloned().zip(outputs).collect(),
+                default,
+            };
+
+            let mut shrinks = Vec::new();
+            if !inputs.is_empty() {
+                shrinks.push(Tree::singleton(Fun {
+                    table: Vec::new(),
+                    default: default.clone(),
+                }));
+            }
+            if inputs.len() > 1 {
+                for removed in 0..inputs.len() {
+                    let mut fewer = build(outputs.clone(), default.clone());
+                    fewer.table.remove(removed);
+                    shrinks.push(Tree::singleton(fewer));
+                }
+            }
+            for (position, output_tree) in output_trees.iter().enumerate() {
+                for shrunk_output in output_tree.shrinks() {
+                    let mut changed = outputs.clone();
+                    changed[position] = shrunk_output.clone();
+                    shrinks.push(Tree::singleton(build(changed, default.clone())));
+                }
+            }
+            for shrunk_default in default_tree.shrinks() {
+                shrinks.push(Tree::singleton(build(
+                    outputs.clone(),
+                    shrunk_default.clone(),
+                )));
+            }
+
+            Tree::with_children(build(outputs.clone(), default), shrinks)
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data::{Seed, Size};
+
+    #[test]
+    fn test_fun_display_shows_table() {
+        let f = Fun::new(vec![(0, 3), (2, 7), (0, 9)], 1);
+        assert_eq!(f.to_string(), "f(0)=3, f(2)=7, otherwise 1");
+        assert_eq!(format!("{f:?}"), "f(0)=3, f(2)=7, otherwise 1");
+        assert_eq!(f.apply(&0), 3);
+        assert_eq!(f.apply(&2), 7);
+        assert_eq!(f.apply(&5), 1);
+
+        let constant: Fun<&str, bool> = Fun::new(Vec::new(), true);
+        assert_eq!(constant.to_string(), "otherwise true");
+    }
+
+    #[test]
+    fn test_fun_of_generates_consistent_tables() {
+        let gen = Gen::<Fun<i32, i32>>::fun_of(Gen::int_range(0, 5), Gen::int_range(10, 20));
+
+        for seed in 0..20 {
+            let tree = gen.generate(Size::new(10), Seed::from_u64(seed));
+            let f = &tree.value;
+            for (input, output) in f.table() {
+                assert_eq!(f.apply(input), *output);
+                assert!((10..=20).contains(output));
+            }
+            assert_eq!(f.apply(&100), *f.default_output());
+
+            for shrink in tree.shrinks() {
+                assert!(shrink.table().len() <= f.table().len());
+                assert_ne!(shrink, f);
+            }
+            if !f.table().is_empty() {
+                assert!(tree.shrinks().iter().any(|s| s.table().is_empty()));
+            }
+        }
+    }
+
+    #[test]
+    fn test_failing_property_prints_function() {
+        use crate::property::for_all;
+
+        let prop = for_all(
+            Gen::<Fun<i32, i32>>::fun_of(Gen::int_range(0, 3), Gen::int_range(0, 10)),
+            |f: &Fun<i32, i32>| f.apply(&0) < 5,
+        );
+
+        match prop.run(&crate::Config::default()) {
+            crate::TestResult::Fail { counterexample, .. } => {
+                assert!(counterexample.contains("otherwise"))
+            }
+            other => panic!("Expected failure, got {other:?}"),
+        }
+    }
+}
@@ -0,0 +1,545 @@
+//! String generation from context-free grammars.
+//!
+//! Productions are built from a handful of combinators ([`lit`], [`chars`],
+//! [`rule`], [`seq`], [`choice`], [`repeat`]) and named in a [`Grammar`]. The
+//! generated strings keep their derivation tree, so shrinking works on the
+//! grammar's structure: a rule is replaced by a smaller use of the same rule
+//! inside it, choices fall back to their simplest alternative and repetitions
+//! lose items. Every shrink is still a sentence of the grammar.
+//!
+//! # Example
+//! ```rust
+//! use hedgehog_core::gen::grammar::*;
+//! use hedgehog_core::*;
+//!
+//! let arithmetic = Grammar::new()
+//!     .rule("expr", choice(vec![
+//!         rule("number"),
+//!         seq(vec![lit("("), rule("expr"), lit(" + "), rule("expr"), lit(")")]),
+//!     ]))
+//!     .rule("number", repeat(chars('0', '9'), 1, 3));
+//!
+//! let gen = arithmetic.gen("expr").unwrap();
+//! ```
+
+use super::Gen;
+use crate::data::{Seed, Size};
+use crate::tree::Tree;
+use std::collections::HashMap;
+
+/// A production of a grammar.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Production {
+    /// Exactly this text
+    Literal(String),
+    /// One character from the given codepoint ranges
+    Chars(Vec<(char, char)>),
+    /// The production named by a rule of the grammar
+    Rule(String),
+    /// Each production in turn
+    Seq(Vec<Production>),
+    /// One of the productions
+    Choice(Vec<Production>),
+    /// The production repeated between `min` and `max` times
+    Repeat {
+        item: Box<Production>,
+        min: usize,
+        max: usize,
+    },
+}
+
+/// A literal string.
+pub fn lit(text: &str) -> Production {
+    Production::Literal(text.to_string())
+}
+
+/// A single character in `lo..=hi`.
+pub fn chars(lo: char, hi: char) -> Production {
+    Production::Chars(vec![(lo, hi)])
+}
+
+/// A reference to a named rule.
+pub fn rule(name: &str) -> Production {
+    Production::Rule(name.to_string())
+}
+
+/// A sequence of productions.
+pub fn seq(items: Vec<Production>) -> Production {
+    Production::Seq(items)
+}
+
+/// A choice between productions. The first alternative that can be derived
+/// without recursion is preferred when shrinking.
+pub fn choice(alternatives: Vec<Production>) -> Production {
+    Production::Choice(alternatives)
+}
+
+/// A production repeated between `min` and `max` times.
+pub fn repeat(item: Production, min: usize, max: usize) -> Production {
+    Production::Repeat {
+        item: Box::new(item),
+        min,
+        max: max.max(min),
+    }
+}
+
+/// A set of named rules.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Grammar {
+    rules: HashMap<String, Production>,
+}
+
+impl Grammar {
+    /// Create a grammar with no rules.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Define or replace a rule.
+    pub fn rule(mut self, name: &str, production: Production) -> Self {
+        self.rules.insert(name.to_string(), production);
+        self
+    }
+
+    /// Generate sentences derived from the rule `start`.
+    ///
+    /// Size bounds recursion: every rule expansion halves the size, and once
+    /// it reaches zero, choices take their shallowest alternative and
+    /// repetitions their minimum count. Returns an error if `start` or any
+    /// rule it refers to is undefined, or if some rule can never finish
+    /// deriving.
+    pub fn gen(&self, start: &str) -> crate::Result<Gen<String>> {
+        let heights = self.validate(start)?;
+        let grammar = Derivations {
+            rules: self.rules.clone(),
+            heights,
+        };
+
+        let start = rule(start);
+
+        Ok(Gen::new(move |size, seed| {
+            let derivation = grammar.derive(&start, size, seed);
+            derivation_tree(&grammar, derivation)
+        }))
+    }
+
+    /// Check every rule is defined and can finish, returning the minimum
+    /// derivation height of each rule.
+    fn validate(&self, start: &str) -> crate::Result<HashMap<String, usize>> {
+        let invalid = |message: String| crate::HedgehogError::InvalidGenerator { message };
+
+        if !self.rules.contains_key(start) {
+            return Err(invalid(format!("grammar has no rule named '{start}'")));
+        }
+        for production in self.rules.values() {
+            if let Some(name) = undefined_rule(production, &self.rules) {
+                return Err(invalid(format!(
+                    "grammar refers to undefined rule '{name}'"
+                )));
+            }
+        }
+
+        // Iterate to a fixed point; rules that never get a finite height can
+        // only derive infinitely
+        let mut heights = HashMap::new();
+        loop {
+            let mut changed = false;
+            for (name, production) in &self.rules {
+                if let Some(height) = min_height(production, &heights) {
+                    if heights.get(name).is_none_or(|&known| height < known) {
+                        heights.insert(name.clone(), height);
+                        changed = true;
+                    }
+                }
+            }
+            if !changed {
+                break;
+            }
+        }
+
+        let mut unproductive: Vec<_> = self
+            .rules
+            .keys()
+            .filter(|name| !heights.contains_key(*name))
+            .collect();
+        unproductive.sort();
+        match unproductive.first() {
+            Some(name) => Err(invalid(format!("grammar rule '{name}' never terminates"))),
+            None => Ok(heights),
+        }
+    }
+}
+
+fn undefined_rule<'a>(
+    production: &'a Production,
+    rules: &HashMap<String, Production>,
+) -> Option<&'a str> {
+    match production {
+        Production::Literal(_) | Production::Chars(_) => None,
+        Production::Rule(name) => (!rules.contains_key(name)).then_some(name.as_str()),
+        Production::Seq(items) | Production::Choice(items) => {
+            items.iter().find_map(|item| undefined_rule(item, rules))
+        }
+        Production::Repeat { item, .. } => undefined_rule(item, rules),
+    }
+}
+
+/// The fewest rule expansions needed to derive a production, if it is known
+/// to be derivable with the heights found so far.
+fn min_height(production: &Production, heights: &HashMap<String, usize>) -> Option<usize> {
+    match production {
+        Production::Literal(_) => Some(0),
+        Production::Chars(ranges) => (!ranges.is_empty()).then_some(0),
+        Production::Rule(name) => heights.get(name).map(|height| height + 1),
+        Production::Seq(items) => items.iter().try_fold(0, |height, item| {
+            min_height(item, heights).map(|item_height| height.max(item_height))
+        }),
+        Production::Choice(alternatives) => alternatives
+            .iter()
+            .filter_map(|alternative| min_height(alternative, heights))
+            .min(),
+        Production::Repeat { item, min, .. } => {
+            if *min == 0 {
+                Some(0)
+            } else {
+                min_height(item, heights)
+            }
+        }
+    }
+}
+
+/// How a sentence was derived.
+#[derive(Debug, Clone, PartialEq)]
+enum Derivation {
+    Text(String),
+    Char {
+        value: char,
+        lowest: char,
+    },
+    Rule {
+        name: String,
+        inner: Box<Derivation>,
+    },
+    Seq(Vec<Derivation>),
+    Choice {
+        index: usize,
+        inner: Box<Derivation>,
+    },
+    Repeat {
+        items: Vec<Derivation>,
+        min: usize,
+    },
+}
+
+impl Derivation {
+    fn render(&self, out: &mut String) {
+        match self {
+            Derivation::Text(text) => out.push_str(text),
+            Derivation::Char { value, .. } => out.push(*value),
+            Derivation::Rule { inner, .. } | Derivation::Choice { inner, .. } => inner.render(out),
+            Derivation::Seq(items) | Derivation::Repeat { items, .. } => {
+                items.iter().for_each(|item| item.render(out))
+            }
+        }
+    }
+
+    fn to_text(&self) -> String {
+        let mut out = String::new();
+        self.render(&mut out);
+        out
+    }
+
+    /// Uses of the rule `name` nested anywhere inside this derivation.
+    fn nested_uses<'a>(&'a self, name: &str, found: &mut Vec<&'a Derivation>) {
+        let children: Vec<&Derivation> = match self {
+            Derivation::Text(_) | Derivation::Char { .. } => Vec::new(),
+            Derivation::Rule { inner, .. } | Derivation::Choice { inner, .. } => vec![inner],
+            Derivation::Seq(items) | Derivation::Repeat { items, .. } => items.iter().collect(),
+        };
+        for child in children {
+            match child {
+                Derivation::Rule {
+                    name: child_name, ..
+                } if child_name == name => found.push(child),
+                _ => {}
+            }
+            child.nested_uses(name, found);
+        }
+    }
+}
+
+/// A validated grammar, ready to derive sentences.
+struct Derivations {
+    rules: HashMap<String, Production>,
+    heights: HashMap<String, usize>,
+}
+
+impl Derivations {
+    fn derive(&self, production: &Production, size: Size, seed: Seed) -> Derivation {
+        match production {
+            Production::Literal(text) => Derivation::Text(text.clone()),
+            Production::Chars(ranges) => {
+                let value = Gen::char_ranges(ranges.clone()).generate(size, seed).value;
+                let lowest = ranges.first().map_or('\0', |&(lo, hi)| lo.min(hi));
+                Derivation::Char { value, lowest }
+            }
+            Production::Rule(name) => Derivation::Rule {
+                name: name.clone(),
+                inner: Box::new(self.derive(&self.rules[name], Size::new(size.get() / 2), seed)),
+            },
+            Production::Seq(items) => {
+                let mut current_seed = seed;
+                Derivation::Seq(
+                    items
+                        .iter()
+                        .map(|item| {
+                            let (item_seed, next_seed) = current_seed.split();
+                            current_seed = next_seed;
+                            self.derive(item, size, item_seed)
+                        })
+                        .collect(),
+                )
+            }
+            Production::Choice(alternatives) => {
+                let (choice_seed, inner_seed) = seed.split();
+                let index = if size.get() == 0 {
+                    self.simplest_alternative(alternatives)
+                } else {
+                    choice_seed.next_bounded(alternatives.len() as u64).0 as usize
+                };
+                Derivation::Choice {
+                    index,
+                    inner: Box::new(self.derive(&alternatives[index], size, inner_seed)),
+                }
+            }
+            Production::Repeat { item, min, max } => {
+                let (count_seed, items_seed) = seed.split();
+                let upper = (*max).min(min.saturating_add(size.get()));
+                let extra = count_seed.next_bounded((upper - min) as u64 + 1).0 as usize;
+
+                let mut current_seed = items_seed;
+                let items = (0..min + extra)
+                    .map(|_| {
+                        let (item_seed, next_seed) = current_seed.split();
+                        current_seed = next_seed;
+                        self.derive(item, size, item_seed)
+                    })
+                    .collect();
+                Derivation::Repeat { items, min: *min }
+            }
+        }
+    }
+
+    /// The first alternative with the smallest derivation height.
+    fn simplest_alternative(&self, alternatives: &[Production]) -> usize {
+        alternatives
+            .iter()
+            .enumerate()
+            .filter_map(|(index, alternative)| {
+                min_height(alternative, &self.heights).map(|height| (height, index))
+            })
+            .min()
+            .map_or(0, |(_, index)| index)
+    }
+
+    /// Smaller derivations of the same production, largest reductions first.
+    fn shrinks(&self, derivation: &Derivation, production: &Production) -> Vec<Derivation> {
+        let mut candidates = Vec::new();
+        match (derivation, production) {
+            (Derivation::Char { value, lowest }, _) if value != lowest => {
+                candidates.push(Derivation::Char {
+                    value: *lowest,
+                    lowest: *lowest,
+                });
+            }
+            (Derivation::Rule { name, inner }, _) => {
+                let mut nested = Vec::new();
+                inner.nested_uses(name, &mut nested);
+                candidates.extend(nested.into_iter().cloned());
+
+                for shrunk in self.shrinks(inner, &self.rules[name]) {
+                    candidates.push(Derivation::Rule {
+                        name: name.clone(),
+                        inner: Box::new(shrunk),
+                    });
+                }
+            }
+            (Derivation::Choice { index, inner }, Production::Choice(alternatives)) => {
+                let simplest = self.simplest_alternative(alternatives);
+                if *index != simplest {
+                    candidates.push(Derivation::Choice {
+                        index: simplest,
+                        inner: Box::new(self.derive(
+                            &alternatives[simplest],
+                            Size::new(0),
+                            Seed::from_u64(0),
+                        )),
+                    });
+                }
+                for shrunk in self.shrinks(inner, &alternatives[*index]) {
+                    candidates.push(Derivation::Choice {
+                        index: *index,
+                        inner: Box::new(shrunk),
+                    });
+                }
+            }
+            (Derivation::Repeat { items, min }, Production::Repeat { item, .. }) => {
+                if items.len() > *min {
+                    candidates.push(Derivation::Repeat {
+                        items: items[..*min].to_vec(),
+                        min: *min,
+                    });
+                    for removed in 0..items.len() {
+                        let mut fewer = items.clone();
+                        fewer.remove(removed);
+                        candidates.push(Derivation::Repeat {
+                            items: fewer,
+                            min: *min,
+                        });
+                    }
+                }
+                for (position, current) in items.iter().enumerate() {
+                    for shrunk in self.shrinks(current, item) {
+                        let mut changed = items.clone();
+                        changed[position] = shrunk;
+                        candidates.push(Derivation::Repeat {
+                            items: changed,
+                            min: *min,
+                        });
+                    }
+                }
+            }
+            (Derivation::Seq(items), Production::Seq(productions)) => {
+                for (position, (current, production)) in items.iter().zip(productions).enumerate() {
+                    for shrunk in self.shrinks(current, production) {
+                        let mut changed = items.clone();
+                        changed[position] = shrunk;
+                        candidates.push(Derivation::Seq(changed));
+                    }
+                }
+            }
+            _ => {}
+        }
+        candidates
+    }
+}
+
+/// Build a shrink tree of rendered sentences from a derivation.
+fn derivation_tree(grammar: &Derivations, derivation: Derivation) -> Tree<String> {
+    let production = match &derivation {
+        Derivation::Rule { name, .. } => rule(name),
+        _ => unreachable!("derivations start from a rule"),
+    };
+
+    let mut seen = vec![derivation.to_text()];
+    let mut shrinks = Vec::new();
+    for candidate in grammar.shrinks(&derivation, &production) {
+        let text = candidate.to_text();
+        if !seen.contains(&text) {
+            seen.push(text.clone());
+            shrinks.push(Tree::singleton(text));
+        }
+    }
+    Tree::with_children(seen.swap_remove(0), shrinks)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn arithmetic() -> Grammar {
+        Grammar::new()
+            .rule(
+                "expr",
+                choice(vec![
+                    rule("number"),
+                    seq(vec![
+                        lit("("),
+                        rule("expr"),
+                        lit("+"),
+                        rule("expr"),
+                        lit(")"),
+                    ]),
+                ]),
+            )
+            .rule("number", repeat(chars('0', '9'), 1, 3))
+    }
+
+    /// A recogniser for the arithmetic grammar.
+    fn parse_expr(input: &str) -> Option<&str> {
+        if let Some(rest) = input.strip_prefix('(') {
+            let rest = parse_expr(rest)?.strip_prefix('+')?;
+            return parse_expr(rest)?.strip_prefix(')');
+        }
+        let digits = input.chars().take_while(char::is_ascii_digit).count();
+        (1..=3).contains(&digits).then(|| &input[digits..])
+    }
+
+    fn is_sentence(input: &str) -> bool {
+        parse_expr(input) == Some("")
+    }
+
+    #[test]
+    fn test_generates_sentences_of_the_grammar() {
+        let gen = arithmetic().gen("expr").unwrap();
+
+        let mut nested = false;
+        for seed in 0..100 {
+            let tree = gen.generate(Size::new(30), Seed::from_u64(seed));
+            assert!(is_sentence(&tree.value), "not a sentence: {}", tree.value);
+            nested |= tree.value.starts_with('(');
+        }
+        assert!(nested);
+    }
+
+    #[test]
+    fn test_size_zero_takes_shallowest_derivation() {
+        let gen = arithmetic().gen("expr").unwrap();
+        for seed in 0..20 {
+            let value = gen.generate(Size::new(0), Seed::from_u64(seed)).value;
+            assert_eq!(value.len(), 1);
+            assert!(value.chars().all(|c| c.is_ascii_digit()));
+        }
+    }
+
+    #[test]
+    fn test_shrinks_are_smaller_sentences() {
+        let gen = arithmetic().gen("expr").unwrap();
+        for seed in 0..50 {
+            let tree = gen.generate(Size::new(30), Seed::from_u64(seed));
+            for shrink in tree.shrinks() {
+                assert!(is_sentence(shrink), "not a sentence: {shrink}");
+                assert_ne!(shrink, &tree.value);
+            }
+            if tree.value.starts_with('(') {
+                // Hoisting a nested expression gives a shorter sentence
+                assert!(tree.shrinks().iter().any(|s| s.len() < tree.value.len()));
+            }
+        }
+    }
+
+    #[test]
+    fn test_invalid_grammars_are_rejected() {
+        let error = |result: crate::Result<Gen<String>>| match result {
+            Err(crate::HedgehogError::InvalidGenerator { message }) => message,
+            other => panic!("Expected invalid generator, got ok: {}", other.is_ok()),
+        };
+
+        assert_eq!(
+            error(arithmetic().gen("statement")),
+            "grammar has no rule named 'statement'"
+        );
+        assert_eq!(
+            error(Grammar::new().rule("a", rule("b")).gen("a")),
+            "grammar refers to undefined rule 'b'"
+        );
+        assert_eq!(
+            error(
+                Grammar::new()
+                    .rule("loop", seq(vec![lit("x"), rule("loop")]))
+                    .gen("loop")
+            ),
+            "grammar rule 'loop' never terminates"
+        );
+    }
+}
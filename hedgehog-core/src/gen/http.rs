@@ -0,0 +1,342 @@
+//! HTTP request and response generators.
+//!
+//! Routers and middleware take whole requests, so testing them field by
+//! field leaves out the combinations that matter: a `POST` without a body, a
+//! `Host` with an unusual port, a header the router has never seen. These
+//! generators build complete messages from the existing domain, port and
+//! status code generators, and every part shrinks on its own: methods
+//! towards `GET`, paths towards `/`, headers away and bodies towards empty.
+
+use super::Gen;
+use crate::data::Range;
+use std::fmt;
+
+/// An HTTP/1.1 request.
+///
+/// `Display` writes the request as it would go over the wire.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HttpRequest {
+    /// The method, such as `GET` or `POST`
+    pub method: String,
+    /// The request target: a path, possibly with a query string
+    pub path: String,
+    /// Header names and values in order, starting with `Host`
+    pub headers: Vec<(String, String)>,
+    /// The body, empty for methods that don't carry one
+    pub body: String,
+}
+
+/// An HTTP/1.1 response.
+///
+/// `Display` writes the response as it would go over the wire.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HttpResponse {
+    /// The status code
+    pub status: u16,
+    /// Header names and values in order
+    pub headers: Vec<(String, String)>,
+    /// The body, empty for statuses that don't carry one
+    pub body: String,
+}
+
+impl HttpRequest {
+    /// The value of the first header called `name`, ignoring case.
+    pub fn header(&self, name: &str) -> Option<&str> {
+        find_header(&self.headers, name)
+    }
+}
+
+impl HttpResponse {
+    /// The value of the first header called `name`, ignoring case.
+    pub fn header(&self, name: &str) -> Option<&str> {
+        find_header(&self.headers, name)
+    }
+}
+
+fn find_header<'a>(headers: &'a [(String, String)], name: &str) -> Option<&'a str> {
+    headers
+        .iter()
+        .find(|(header, _)| header.eq_ignore_ascii_case(name))
+        .map(|(_, value)| value.as_str())
+}
+
+impl fmt::Display for HttpRequest {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} {} HTTP/1.1\r\n", self.method, self.path)?;
+        write_message(f, &self.headers, &self.body)
+    }
+}
+
+impl fmt::Display for HttpResponse {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "HTTP/1.1 {}\r\n", self.status)?;
+        write_message(f, &self.headers, &self.body)
+    }
+}
+
+fn write_message(
+    f: &mut fmt::Formatter<'_>,
+    headers: &[(String, String)],
+    body: &str,
+) -> fmt::Result {
+    for (name, value) in headers {
+        write!(f, "{name}: {value}\r\n")?;
+    }
+    write!(f, "\r\n{body}")
+}
+
+impl Gen<HttpRequest> {
+    /// Generate requests with a method, a path and query, a `Host` built from
+    /// [`Gen::web_domain`] and [`Gen::network_port`], common and custom
+    /// headers, and a JSON body for `POST`, `PUT` and `PATCH`.
+    ///
+    /// Requests with a body also get matching `Content-Type` and
+    /// `Content-Length` headers.
+    ///
+    /// # Example
+    /// ```rust
+    /// use hedgehog_core::*;
+    ///
+    /// let prop = for_all(Gen::<HttpRequest>::http_request(), |request| {
+    ///     request.header("host").is_some()
+    /// });
+    /// ```
+    pub fn http_request() -> Self {
+        let methods = ["GET", "POST", "PUT", "PATCH", "DELETE", "HEAD", "OPTIONS"];
+        let method = Gen::from_elements(methods.iter().map(|m| m.to_string()).collect()).unwrap();
+        let host =
+            Gen::<(String, u16)>::tuple_of(Gen::<String>::web_domain(), Gen::<u16>::network_port())
+                .map(|(domain, port)| match port {
+                    80 | 443 => domain,
+                    _ => format!("{domain}:{port}"),
+                });
+        let headers = Gen::vec_with_range(Range::linear(0, 6), request_header());
+
+        Gen::<(String, String, String, Vec<(String, String)>, String)>::tuple_of(
+            method,
+            path(),
+            host,
+            headers,
+            json_body(),
+        )
+        .map(|(method, path, host, extra, body)| {
+            let body = match method.as_str() {
+                "POST" | "PUT" | "PATCH" => body,
+                _ => String::new(),
+            };
+            let mut headers = vec![("Host".to_string(), host)];
+            headers.extend(extra);
+            if !body.is_empty() {
+                headers.push(("Content-Type".to_string(), "application/json".to_string()));
+                headers.push(("Content-Length".to_string(), body.len().to_string()));
+            }
+            HttpRequest {
+                method,
+                path,
+                headers,
+                body,
+            }
+        })
+    }
+}
+
+impl Gen<HttpResponse> {
+    /// Generate responses with a status from [`Gen::http_status_code`],
+    /// common headers, and a JSON body unless the status forbids one
+    /// (`1xx`, `204` and `304`).
+    ///
+    /// # Example
+    /// ```rust
+    /// use hedgehog_core::*;
+    ///
+    /// let prop = for_all(Gen::<HttpResponse>::http_response(), |response| {
+    ///     (100..600).contains(&response.status)
+    /// });
+    /// ```
+    pub fn http_response() -> Self {
+        let headers = Gen::vec_with_range(Range::linear(0, 4), response_header());
+        Gen::<(u16, Vec<(String, String)>, String)>::tuple_of(
+            Gen::<u16>::http_status_code(),
+            headers,
+            json_body(),
+        )
+        .map(|(status, mut headers, body)| {
+            let body = match status {
+                100..=199 | 204 | 304 => String::new(),
+                _ => body,
+            };
+            if !body.is_empty() {
+                headers.push(("Content-Type".to_string(), "application/json".to_string()));
+                headers.push(("Content-Length".to_string(), body.len().to_string()));
+            }
+            HttpResponse {
+                status,
+                headers,
+                body,
+            }
+        })
+    }
+}
+
+/// Paths of up to four segments with an optional query string.
+fn path() -> Gen<String> {
+    let segments = Gen::vec_with_range(
+        Range::linear(0, 4),
+        Gen::<String>::alphanumeric_with_range(Range::linear(1, 10)),
+    );
+    let query = Gen::option_of(Gen::vec_with_range(
+        Range::linear(1, 3),
+        Gen::<(String, String)>::tuple_of(
+            Gen::<String>::alpha_with_range(Range::linear(1, 8)),
+            Gen::<String>::alphanumeric_with_range(Range::linear(0, 10)),
+        ),
+    ));
+    Gen::<(Vec<String>, Option<Vec<(String, String)>>)>::tuple_of(segments, query).map(
+        |(segments, query)| {
+            let mut path = format!("/{}", segments.join("/"));
+            if let Some(params) = query {
+                let params: Vec<String> = params.iter().map(|(k, v)| format!("{k}={v}")).collect();
+                path.push('?');
+                path.push_str(&params.join("&"));
+            }
+            path
+        },
+    )
+}
+
+/// A header with one of the given values.
+fn fixed(name: &'static str, values: &[&str]) -> Gen<(String, String)> {
+    Gen::from_elements(values.iter().map(|v| v.to_string()).collect())
+        .unwrap()
+        .map(move |value| (name.to_string(), value))
+}
+
+fn request_header() -> Gen<(String, String)> {
+    Gen::one_of(vec![
+        fixed(
+            "Accept",
+            &["*/*", "application/json", "text/html", "text/plain;q=0.5"],
+        ),
+        fixed(
+            "Accept-Encoding",
+            &["identity", "gzip", "gzip, deflate, br"],
+        ),
+        fixed("User-Agent", &["curl/8.5.0", "Mozilla/5.0", ""]),
+        Gen::<String>::alphanumeric_with_range(Range::linear(8, 40))
+            .map(|token| ("Authorization".to_string(), format!("Bearer {token}"))),
+        Gen::<(String, String)>::tuple_of(
+            Gen::<String>::alpha_with_range(Range::linear(1, 8)),
+            Gen::<String>::alphanumeric_with_range(Range::linear(0, 16)),
+        )
+        .map(|(name, value)| ("Cookie".to_string(), format!("{name}={value}"))),
+        custom_header(),
+    ])
+    .unwrap()
+}
+
+fn response_header() -> Gen<(String, String)> {
+    Gen::one_of(vec![
+        fixed(
+            "Cache-Control",
+            &["no-store", "no-cache", "max-age=3600", "private"],
+        ),
+        fixed("Connection", &["keep-alive", "close"]),
+        Gen::<(String, String)>::tuple_of(
+            Gen::<String>::alpha_with_range(Range::linear(1, 8)),
+            Gen::<String>::alphanumeric_with_range(Range::linear(0, 16)),
+        )
+        .map(|(name, value)| ("Set-Cookie".to_string(), format!("{name}={value}; Path=/"))),
+        custom_header(),
+    ])
+    .unwrap()
+}
+
+/// `X-` headers the code under test has never heard of.
+fn custom_header() -> Gen<(String, String)> {
+    Gen::<(String, String)>::tuple_of(
+        Gen::<String>::alpha_with_range(Range::linear(1, 12)),
+        Gen::<String>::alphanumeric_with_range(Range::linear(0, 20)),
+    )
+    .map(|(name, value)| (format!("X-{name}"), value))
+}
+
+/// A flat JSON object with string values, or nothing.
+fn json_body() -> Gen<String> {
+    Gen::vec_with_range(
+        Range::linear(0, 4),
+        Gen::<(String, String)>::tuple_of(
+            Gen::<String>::alpha_with_range(Range::linear(1, 8)),
+            Gen::<String>::alphanumeric_with_range(Range::linear(0, 16)),
+        ),
+    )
+    .map(|fields| {
+        if fields.is_empty() {
+            return String::new();
+        }
+        let fields: Vec<String> = fields
+            .iter()
+            .map(|(key, value)| format!("\"{key}\":\"{value}\""))
+            .collect();
+        format!("{{{}}}", fields.join(","))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data::{Seed, Size};
+
+    #[test]
+    fn test_requests_are_consistent() {
+        let gen = Gen::<HttpRequest>::http_request();
+        let mut with_body = 0;
+        for seed in 0..100 {
+            let tree = gen.generate(Size::new(30), Seed::from_u64(seed));
+            for request in std::iter::once(&tree.value).chain(tree.shrinks()) {
+                assert!(request.path.starts_with('/'));
+                assert_eq!(request.headers[0].0, "Host");
+                match request.header("content-length") {
+                    Some(length) => assert_eq!(length, request.body.len().to_string()),
+                    None => assert!(request.body.is_empty()),
+                }
+                if !request.body.is_empty() {
+                    assert!(["POST", "PUT", "PATCH"].contains(&request.method.as_str()));
+                }
+            }
+            with_body += usize::from(!tree.value.body.is_empty());
+        }
+        assert!(with_body > 0);
+    }
+
+    #[test]
+    fn test_requests_shrink_towards_get() {
+        let gen = Gen::<HttpRequest>::http_request();
+        let tree = (0..50)
+            .map(|seed| gen.generate(Size::new(30), Seed::from_u64(seed)))
+            .find(|tree| tree.value.method != "GET")
+            .unwrap();
+        assert!(tree.shrinks().iter().any(|request| request.method == "GET"));
+    }
+
+    #[test]
+    fn test_wire_format() {
+        let response = HttpResponse {
+            status: 404,
+            headers: vec![("Connection".to_string(), "close".to_string())],
+            body: String::new(),
+        };
+        assert_eq!(
+            response.to_string(),
+            "HTTP/1.1 404\r\nConnection: close\r\n\r\n"
+        );
+
+        let gen = Gen::<HttpResponse>::http_response();
+        for seed in 0..50 {
+            let response = gen.generate(Size::new(30), Seed::from_u64(seed)).value;
+            if matches!(response.status, 100..=199 | 204 | 304) {
+                assert!(response.body.is_empty());
+            }
+            assert!(response.to_string().starts_with("HTTP/1.1 "));
+        }
+    }
+}
@@ -0,0 +1,126 @@
+//! Generators for ordered pairs and intervals.
+//!
+//! Properties over intervals need `start <= end`, and filtering two
+//! independent values for it discards half of them; shrinking each value
+//! on its own breaks the ordering again. These generators sort each pair
+//! as it's built, shrinks included, so every value keeps its ends in order.
+
+use super::{bind_tree, Gen};
+use crate::data::Range;
+
+impl<T> Gen<(T, T)>
+where
+    T: 'static + Clone + PartialOrd,
+{
+    /// Generate pairs `(lo, hi)` of values from `gen` with `lo <= hi`.
+    ///
+    /// Both values shrink as `gen`'s do, the first before the second, and
+    /// every shrink is put back in order.
+    ///
+    /// # Example
+    /// ```rust
+    /// use hedgehog_core::*;
+    ///
+    /// let gen = Gen::ordered_pair(Gen::int_range(-100, 100));
+    /// let (lo, hi) = gen.sample();
+    /// assert!(lo <= hi);
+    /// ```
+    pub fn ordered_pair(gen: Gen<T>) -> Self {
+        let description = gen
+            .description()
+            .map(|description| format!("ordered_pair({description})"));
+        let pairs = Gen::new(move |size, seed| {
+            let (first_seed, second_seed) = seed.split();
+            let first = gen.generate(size, first_seed);
+            let second = gen.generate(size, second_seed);
+            bind_tree(first, &|lo: &T| {
+                let lo = lo.clone();
+                second.clone().map(move |hi| ordered(lo.clone(), hi))
+            })
+        });
+        match description {
+            Some(description) => pairs.describe(&description),
+            None => pairs,
+        }
+    }
+}
+
+/// The pair with its smaller value first.
+fn ordered<T: PartialOrd>(a: T, b: T) -> (T, T) {
+    if b < a {
+        (b, a)
+    } else {
+        (a, b)
+    }
+}
+
+macro_rules! impl_interval {
+    ($($type:ty),*) => {
+        $(
+            impl Gen<($type, $type)> {
+                /// Generate intervals `(start, end)` within `range`, with
+                /// `start <= end`, shrinking both ends towards the range's
+                /// origin.
+                ///
+                /// # Example
+                /// ```rust
+                /// use hedgehog_core::*;
+                ///
+                /// let gen = Gen::<(i32, i32)>::interval(Range::new(0, 10));
+                /// let (start, end) = gen.sample();
+                /// assert!(start <= end && end <= 10);
+                /// ```
+                pub fn interval(range: Range<$type>) -> Self {
+                    Gen::ordered_pair(Gen::<$type>::from_range(range))
+                }
+            }
+
+            impl Gen<std::ops::Range<$type>> {
+                /// Generate half-open ranges `start..end` within `range`,
+                /// possibly empty; see [`Gen::interval`].
+                pub fn range_within(range: Range<$type>) -> Self {
+                    Gen::<($type, $type)>::interval(range).map(|(start, end)| start..end)
+                }
+            }
+        )*
+    };
+}
+
+impl_interval!(i8, i16, i32, i64, isize, u8, u16, u32, u64, usize, f64);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data::{Config, Seed, Size};
+    use crate::shrinking::minimal_counterexample;
+
+    #[test]
+    fn test_ordered_pairs_stay_ordered_when_shrunk() {
+        let gen = Gen::ordered_pair(Gen::int_range(-1000, 1000));
+        for n in 0..50 {
+            let tree = gen.generate(Size::new(50), Seed::from_u64(n));
+            let mut pending = vec![&tree];
+            while let Some(node) = pending.pop() {
+                assert!(node.value.0 <= node.value.1, "{:?}", node.value);
+                pending.extend(&node.children);
+            }
+        }
+    }
+
+    #[test]
+    fn test_intervals_shrink_towards_the_origin() {
+        let gen = Gen::<(i32, i32)>::interval(Range::new(0, 1000));
+        let shrunk =
+            minimal_counterexample(&gen, |&(start, end)| end - start < 100, &Config::default())
+                .expect("some interval should be wide");
+        let &(start, end) = shrunk.minimal();
+        assert_eq!(start, 0);
+        assert!(end >= 100);
+
+        let ranges = Gen::<std::ops::Range<u8>>::range_within(Range::new(10, 20));
+        for n in 0..20 {
+            let range = ranges.generate(Size::new(50), Seed::from_u64(n)).value;
+            assert!(10 <= range.start && range.start <= range.end && range.end <= 20);
+        }
+    }
+}
@@ -0,0 +1,130 @@
+//! Generators for fieldless enums that derive `strum::EnumIter`.
+//!
+//! The variants come from the enum itself, so adding a variant doesn't leave
+//! a hand-written `from_elements` list out of date.
+
+use super::{towards, Gen};
+use crate::tree::Tree;
+use strum::IntoEnumIterator;
+
+impl<T> Gen<T>
+where
+    T: IntoEnumIterator + Clone + 'static,
+{
+    /// Generate every variant of an enum equally often, shrinking towards
+    /// the first variant.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the enum has no variants.
+    ///
+    /// # Example
+    /// ```rust
+    /// use hedgehog_core::*;
+    /// use strum::EnumIter;
+    ///
+    /// #[derive(Debug, Clone, Copy, EnumIter)]
+    /// enum Colour {
+    ///     Red,
+    ///     Green,
+    ///     Blue,
+    /// }
+    ///
+    /// let gen = Gen::<Colour>::iterable_enum();
+    /// ```
+    pub fn iterable_enum() -> Self {
+        Self::iterable_enum_weighted(|_| 1)
+            .expect("iterable_enum needs an enum with at least one variant")
+    }
+
+    /// Generate the variants of an enum in proportion to `weight`, shrinking
+    /// towards the earliest variant with a non-zero weight.
+    ///
+    /// Variants with weight zero are never generated. Returns an error if
+    /// every variant has weight zero.
+    pub fn iterable_enum_weighted<F>(weight: F) -> crate::Result<Self>
+    where
+        F: Fn(&T) -> u64,
+    {
+        let variants: Vec<(T, u64)> = T::iter()
+            .map(|variant| {
+                let weight = weight(&variant);
+                (variant, weight)
+            })
+            .filter(|&(_, weight)| weight > 0)
+            .collect();
+
+        if variants.is_empty() {
+            return Err(crate::HedgehogError::InvalidGenerator {
+                message: "enum has no variants with a non-zero weight".to_string(),
+            });
+        }
+
+        let total: u64 = variants.iter().map(|&(_, weight)| weight).sum();
+
+        Ok(Gen::new(move |_size, seed| {
+            let (mut choice, _new_seed) = seed.next_bounded(total);
+            let mut index = 0;
+            while choice >= variants[index].1 {
+                choice -= variants[index].1;
+                index += 1;
+            }
+
+            let shrinks = towards(0, index)
+                .into_iter()
+                .map(|shrink| Tree::singleton(variants[shrink].0.clone()))
+                .collect();
+            Tree::with_children(variants[index].0.clone(), shrinks)
+        }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data::{Seed, Size};
+    use std::collections::HashSet;
+    use strum::EnumIter;
+
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, EnumIter)]
+    enum Colour {
+        Red,
+        Green,
+        Blue,
+    }
+
+    #[derive(Debug, Clone, EnumIter)]
+    enum Empty {}
+
+    #[test]
+    fn test_iterable_enum_covers_variants_and_shrinks_to_first() {
+        let gen = Gen::<Colour>::iterable_enum();
+        let mut seen = HashSet::new();
+        for seed in 0..50 {
+            let tree = gen.generate(Size::new(10), Seed::from_u64(seed));
+            seen.insert(tree.value);
+            if tree.value != Colour::Red {
+                assert_eq!(*tree.shrinks()[0], Colour::Red);
+            }
+        }
+        assert_eq!(seen, Colour::iter().collect());
+    }
+
+    #[test]
+    fn test_iterable_enum_weighted_skips_zero_weights() {
+        let gen = Gen::<Colour>::iterable_enum_weighted(|colour| match colour {
+            Colour::Red => 0,
+            Colour::Green => 1,
+            Colour::Blue => 3,
+        })
+        .unwrap();
+        for seed in 0..50 {
+            let tree = gen.generate(Size::new(10), Seed::from_u64(seed));
+            assert_ne!(tree.value, Colour::Red);
+            assert!(tree.shrinks().iter().all(|&&c| c == Colour::Green));
+        }
+
+        assert!(Gen::<Colour>::iterable_enum_weighted(|_| 0).is_err());
+        assert!(Gen::<Empty>::iterable_enum_weighted(|_| 1).is_err());
+    }
+}
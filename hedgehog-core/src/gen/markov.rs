@@ -0,0 +1,218 @@
+//! Event sequences generated by a Markov process.
+//!
+//! Session handling and analytics code sees streams of events where each
+//! event depends on the one before: a purchase follows browsing, which
+//! follows a login. A sequence of independently generated events is mostly
+//! nonsense to such code. Here each event is drawn from weighted transitions
+//! out of the previous one, and shrinking only removes events in ways that
+//! keep every remaining transition possible.
+
+use super::{sample_length, Draw, Gen};
+use crate::data::Range;
+use crate::tree::Tree;
+
+impl<T> Gen<Vec<T>>
+where
+    T: 'static + Clone,
+{
+    /// Generate event sequences that start in the first state and move
+    /// between states with the given weights.
+    ///
+    /// `transition_weights[i][j]` is the relative weight of moving from
+    /// `states[i]` to `states[j]`; a zero weight forbids the move. The length
+    /// is drawn from `len_range`, but a sequence ends early on reaching a
+    /// state with no way out.
+    ///
+    /// Shrinking tries shorter prefixes, then cuts out loops that return to
+    /// the same state, then removes single events where the events either
+    /// side of them can follow each other. Every shrink is still a walk
+    /// through the transitions, and none is shorter than the range's minimum.
+    ///
+    /// Returns an error if there are no states or the weights are not a
+    /// square matrix with a row and a column for each state.
+    ///
+    /// # Example
+    /// ```rust
+    /// use hedgehog_core::*;
+    ///
+    /// let gen = Gen::sequence_markov(
+    ///     vec!["login", "browse", "purchase", "logout"],
+    ///     vec![
+    ///         vec![0, 1, 0, 0], // login → browse
+    ///         vec![0, 6, 2, 1], // browse → browse, purchase or logout
+    ///         vec![0, 3, 0, 1], // purchase → browse or logout
+    ///         vec![0, 0, 0, 0], // logout ends the session
+    ///     ],
+    ///     Range::linear(1, 20),
+    /// )
+    /// .unwrap();
+    /// ```
+    pub fn sequence_markov(
+        states: Vec<T>,
+        transition_weights: Vec<Vec<u64>>,
+        len_range: Range<usize>,
+    ) -> crate::Result<Self> {
+        if states.is_empty() {
+            return Err(crate::HedgehogError::InvalidGenerator {
+                message: "sequence_markov needs at least one state".to_string(),
+            });
+        }
+        if transition_weights.len() != states.len()
+            || transition_weights
+                .iter()
+                .any(|row| row.len() != states.len())
+        {
+            return Err(crate::HedgehogError::InvalidGenerator {
+                message: format!(
+                    "transition weights must be a {0}x{0} matrix, one row and column per state",
+                    states.len()
+                ),
+            });
+        }
+
+        Ok(Gen::new(move |_size, seed| {
+            let (len_seed, walk_seed) = seed.split();
+            let length = sample_length(&len_range, len_seed);
+            let walk = walk(&transition_weights, length, &mut Draw(walk_seed));
+            let events = |walk: &[usize]| walk.iter().map(|&i| states[i].clone()).collect();
+
+            let shrinks = walk_shrinks(&transition_weights, &walk, len_range.min)
+                .iter()
+                .map(|shrink| Tree::singleton(events(shrink)))
+                .collect();
+            Tree::with_children(events(&walk), shrinks)
+        }))
+    }
+}
+
+/// Up to `length` state indices, starting from the first state.
+fn walk(weights: &[Vec<u64>], length: usize, draw: &mut Draw) -> Vec<usize> {
+    let mut walk = Vec::with_capacity(length);
+    let mut state = 0;
+    for _ in 0..length {
+        walk.push(state);
+        let row = &weights[state];
+        let total: u64 = row.iter().sum();
+        if total == 0 {
+            break;
+        }
+        let mut pick = draw.below(usize::try_from(total).unwrap_or(usize::MAX)) as u64;
+        state = row
+            .iter()
+            .position(|&weight| {
+                if pick < weight {
+                    true
+                } else {
+                    pick -= weight;
+                    false
+                }
+            })
+            .unwrap_or(0);
+    }
+    walk
+}
+
+/// Shorter walks that are still walks, none shorter than `min`.
+fn walk_shrinks(weights: &[Vec<u64>], walk: &[usize], min: usize) -> Vec<Vec<usize>> {
+    let mut shrinks: Vec<Vec<usize>> = Vec::new();
+    let mut push = |shrink: Vec<usize>| {
+        if shrink.len() >= min && shrink.len() < walk.len() && !shrinks.contains(&shrink) {
+            shrinks.push(shrink);
+        }
+    };
+
+    // Prefixes of a walk are walks
+    let mut len = min;
+    while len < walk.len() {
+        push(walk[..len].to_vec());
+        len = (len * 2).max(len + 1);
+    }
+
+    // A loop back to the same state can be cut out, longest first
+    let mut loops = Vec::new();
+    for i in 0..walk.len() {
+        for j in i + 1..walk.len() {
+            if walk[i] == walk[j] {
+                loops.push((i, j));
+            }
+        }
+    }
+    loops.sort_by_key(|&(i, j)| std::cmp::Reverse(j - i));
+    for (i, j) in loops {
+        push([&walk[..=i], &walk[j + 1..]].concat());
+    }
+
+    // Single events, where their neighbours can follow each other
+    for i in 1..walk.len() {
+        if i + 1 == walk.len() || weights[walk[i - 1]][walk[i + 1]] > 0 {
+            let mut shrink = walk.to_vec();
+            shrink.remove(i);
+            push(shrink);
+        }
+    }
+
+    shrinks
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data::{Seed, Size};
+
+    fn session() -> (Vec<&'static str>, Vec<Vec<u64>>) {
+        (
+            vec!["login", "browse", "purchase", "logout"],
+            vec![
+                vec![0, 1, 0, 0],
+                vec![0, 6, 2, 1],
+                vec![0, 3, 0, 1],
+                vec![0, 0, 0, 0],
+            ],
+        )
+    }
+
+    fn is_walk(states: &[&str], weights: &[Vec<u64>], events: &[&str]) -> bool {
+        let index = |event: &str| states.iter().position(|&s| s == event).unwrap();
+        events.first().is_none_or(|&first| index(first) == 0)
+            && events
+                .windows(2)
+                .all(|pair| weights[index(pair[0])][index(pair[1])] > 0)
+    }
+
+    #[test]
+    fn test_sequences_and_shrinks_follow_transitions() {
+        let (states, weights) = session();
+        let gen = Gen::sequence_markov(states.clone(), weights.clone(), Range::new(3, 30)).unwrap();
+
+        let mut purchased = false;
+        for seed in 0..100 {
+            let tree = gen.generate(Size::new(30), Seed::from_u64(seed));
+            assert!(is_walk(&states, &weights, &tree.value), "{:?}", tree.value);
+            // Only a logout ends a session before its length
+            assert!(tree.value.len() >= 3 || tree.value.last() == Some(&"logout"));
+            purchased |= tree.value.contains(&"purchase");
+
+            for shrink in tree.shrinks() {
+                assert!(is_walk(&states, &weights, shrink), "{shrink:?}");
+                assert!(shrink.len() >= 3 && shrink.len() < tree.value.len());
+            }
+        }
+        assert!(purchased);
+    }
+
+    #[test]
+    fn test_loops_are_cut_out() {
+        let (_, weights) = session();
+        // login browse purchase browse browse logout
+        let shrinks = walk_shrinks(&weights, &[0, 1, 2, 1, 1, 3], 0);
+        assert!(shrinks.contains(&vec![0, 1, 3]));
+        // Dropping the login would leave a walk that doesn't start there
+        assert!(!shrinks.iter().any(|shrink| shrink.first() == Some(&1)));
+    }
+
+    #[test]
+    fn test_rejects_mismatched_weights() {
+        assert!(Gen::sequence_markov(vec!['a', 'b'], vec![vec![1, 1]], Range::new(0, 5)).is_err());
+        assert!(Gen::<Vec<char>>::sequence_markov(vec![], vec![], Range::new(0, 5)).is_err());
+    }
+}
@@ -0,0 +1,159 @@
+//! Rectangular two-dimensional generators.
+//!
+//! Nesting `vec_of` gives ragged rows, and its shrinks remove single elements
+//! from single rows, which breaks the shape numerical code relies on. A
+//! matrix is generated as a grid, and every shrink removes a whole row or a
+//! whole column, so each candidate is still rectangular.
+
+use super::{sample_length, Gen};
+use crate::data::{Range, Seed, Size};
+use crate::tree::Tree;
+
+impl<T> Gen<Vec<Vec<T>>>
+where
+    T: 'static + Clone,
+{
+    /// Generate rectangular matrices as a vector of rows, with the number of
+    /// rows and columns drawn from the given ranges.
+    ///
+    /// Every row has the same length. Shrinking first tries the origin shape
+    /// (or the minimum), then removes single rows and columns, never going
+    /// below either minimum, then shrinks individual elements.
+    ///
+    /// # Example
+    /// ```rust
+    /// use hedgehog_core::*;
+    ///
+    /// // Greyscale images from 1x1 up to 64x64 pixels
+    /// let gen = Gen::matrix(Range::linear(1, 64), Range::linear(1, 64), Gen::<u8>::from_range(Range::new(0, 255)));
+    /// ```
+    pub fn matrix(rows_range: Range<usize>, cols_range: Range<usize>, element_gen: Gen<T>) -> Self {
+        Gen::new(move |size, seed| {
+            let (shape_seed, elements_seed) = seed.split();
+            let (rows_seed, cols_seed) = shape_seed.split();
+            let rows = sample_length(&rows_range, rows_seed);
+            let cols = sample_length(&cols_range, cols_seed);
+
+            let trees = grid_trees(&element_gen, rows, cols, size, elements_seed);
+            let matrix: Vec<Vec<T>> = trees
+                .iter()
+                .map(|row| row.iter().map(|tree| tree.value.clone()).collect())
+                .collect();
+
+            let mut shrinks = Vec::new();
+
+            // Try the origin shape first, keeping the top-left corner
+            let origin_rows = rows_range.origin.unwrap_or(rows_range.min).min(rows);
+            let origin_cols = cols_range.origin.unwrap_or(cols_range.min).min(cols);
+            if (origin_rows, origin_cols) != (rows, cols)
+                && origin_rows >= rows_range.min
+                && origin_cols >= cols_range.min
+            {
+                shrinks.push(Tree::singleton(truncate(&matrix, origin_rows, origin_cols)));
+            }
+
+            // Remove whole rows, then whole columns
+            if rows > rows_range.min {
+                for i in (0..rows).rev() {
+                    let mut fewer_rows = matrix.clone();
+                    fewer_rows.remove(i);
+                    shrinks.push(Tree::singleton(fewer_rows));
+                }
+            }
+            if cols > cols_range.min {
+                for j in (0..cols).rev() {
+                    let fewer_cols = matrix
+                        .iter()
+                        .map(|row| {
+                            let mut row = row.clone();
+                            row.remove(j);
+                            row
+                        })
+                        .collect();
+                    shrinks.push(Tree::singleton(fewer_cols));
+                }
+            }
+
+            // Shrink elements in place
+            for (i, row) in trees.iter().enumerate() {
+                for (j, tree) in row.iter().enumerate() {
+                    for shrunk in tree.shrinks() {
+                        let mut simpler = matrix.clone();
+                        simpler[i][j] = shrunk.clone();
+                        shrinks.push(Tree::singleton(simpler));
+                    }
+                }
+            }
+
+            Tree::with_children(matrix, shrinks)
+        })
+    }
+}
+
+/// Generate the element trees of a `rows` by `cols` grid.
+fn grid_trees<T>(
+    element_gen: &Gen<T>,
+    rows: usize,
+    cols: usize,
+    size: Size,
+    seed: Seed,
+) -> Vec<Vec<Tree<T>>> {
+    let mut current_seed = seed;
+    (0..rows)
+        .map(|_| {
+            (0..cols)
+                .map(|_| {
+                    let (element_seed, next_seed) = current_seed.split();
+                    current_seed = next_seed;
+                    element_gen.generate(size, element_seed)
+                })
+                .collect()
+        })
+        .collect()
+}
+
+/// The top-left `rows` by `cols` corner of a matrix.
+fn truncate<T: Clone>(matrix: &[Vec<T>], rows: usize, cols: usize) -> Vec<Vec<T>> {
+    matrix[..rows]
+        .iter()
+        .map(|row| row[..cols].to_vec())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn is_rectangular<T>(matrix: &[Vec<T>], cols: usize) -> bool {
+        matrix.iter().all(|row| row.len() == cols)
+    }
+
+    #[test]
+    fn test_matrix_shrinks_stay_rectangular_and_in_range() {
+        let gen = Gen::matrix(
+            Range::new(2, 6),
+            Range::new(3, 5).with_origin(4),
+            Gen::int_range(0, 9),
+        );
+
+        for seed in 0..50 {
+            let tree = gen.generate(Size::new(10), Seed::from_u64(seed));
+            let cols = tree.value[0].len();
+            assert!((2..=6).contains(&tree.value.len()));
+            assert!((3..=5).contains(&cols));
+            assert!(is_rectangular(&tree.value, cols));
+
+            for shrink in tree.shrinks() {
+                assert!(shrink.len() >= 2);
+                assert!(shrink[0].len() >= 3);
+                assert!(is_rectangular(shrink, shrink[0].len()));
+            }
+
+            let origin = (2, cols.min(4));
+            if (tree.value.len(), cols) != origin {
+                let first = tree.shrinks()[0];
+                assert_eq!((first.len(), first[0].len()), origin);
+            }
+        }
+    }
+}
@@ -0,0 +1,182 @@
+//! Numeric generators concentrated around a pivot.
+//!
+//! Tolerance windows, rounding and clamping logic break at values close to
+//! a threshold, which uniform ranges over the whole type rarely produce.
+//! `near(pivot, spread)` generates values at most `spread` either side of
+//! `pivot`, mostly close to it, and shrinks them back towards the pivot so
+//! a counterexample shows the smallest distance that still fails.
+
+use super::{towards, Gen};
+use crate::data::{Distribution, Seed};
+use crate::tree::Tree;
+
+macro_rules! impl_near {
+    ($($type:ty),*) => {
+        $(
+            impl Gen<$type> {
+                /// Generate values at most `spread` either side of `pivot`,
+                /// favouring those closest to it, and shrinking towards it.
+                ///
+                /// Values past the bounds of the type are clamped to them.
+                ///
+                /// # Example
+                /// ```rust
+                /// use hedgehog_core::*;
+                ///
+                #[doc = concat!("let gen = Gen::<", stringify!($type), ">::near(50, 5);")]
+                /// let value = gen.sample();
+                /// assert!((45..=55).contains(&value));
+                /// ```
+                pub fn near(pivot: $type, spread: $type) -> Self {
+                    Self::near_with(pivot, spread, Distribution::Linear)
+                }
+
+                /// Like [`near`](Self::near), drawing the distance from the
+                /// pivot from `distribution`: [`Distribution::Uniform`]
+                /// spreads values evenly across the window, and
+                /// [`Distribution::Exponential`] keeps them within a few
+                /// steps of the pivot.
+                pub fn near_with(pivot: $type, spread: $type, distribution: Distribution) -> Self {
+                    let (lo, hi) = (<$type>::MIN as i128, <$type>::MAX as i128);
+                    let spread_width = (spread as i128).abs();
+                    Gen::new(move |_size, seed| {
+                        let offset = offset(seed, spread_width, distribution);
+                        let value = (pivot as i128 + offset).clamp(lo, hi);
+                        let shrinks = towards(pivot as i128, value)
+                            .into_iter()
+                            .map(|shrink| Tree::singleton(shrink as $type))
+                            .collect();
+                        Tree::with_children(value as $type, shrinks)
+                    })
+                    .describe(&format!("near({pivot}, {spread})"))
+                }
+            }
+        )*
+    };
+}
+
+impl_near!(i8, i16, i32, i64, isize, u8, u16, u32, u64, usize);
+
+impl Gen<f64> {
+    /// Generate values at most `spread` either side of `pivot`, favouring
+    /// those closest to it, and shrinking towards it.
+    ///
+    /// # Example
+    /// ```rust
+    /// use hedgehog_core::*;
+    ///
+    /// let gen = Gen::<f64>::near(1.0, 0.001);
+    /// let value = gen.sample();
+    /// assert!((value - 1.0).abs() <= 0.001);
+    /// ```
+    pub fn near(pivot: f64, spread: f64) -> Self {
+        Self::near_with(pivot, spread, Distribution::Linear)
+    }
+
+    /// Like [`near`](Self::near), drawing the distance from the pivot as a
+    /// fraction of `spread` from `distribution`.
+    pub fn near_with(pivot: f64, spread: f64, distribution: Distribution) -> Self {
+        let spread_width = spread.abs();
+        Gen::new(move |_size, seed| {
+            let (negative, seed) = seed.next_bool();
+            let (fraction, _) = distribution.sample_f64(seed);
+            let offset = fraction * spread_width;
+            let value = if negative {
+                pivot - offset
+            } else {
+                pivot + offset
+            };
+
+            let mut shrinks = Vec::new();
+            if value != pivot {
+                shrinks.push(Tree::singleton(pivot));
+            }
+            // Halve the distance a few times, so shrinking finds roughly
+            // where along the window the property starts to fail
+            let mut distance = (value - pivot) / 2.0;
+            for _ in 0..8 {
+                let shrink = pivot + distance;
+                if shrink != pivot && shrink != value {
+                    shrinks.push(Tree::singleton(shrink));
+                }
+                distance /= 2.0;
+            }
+            Tree::with_children(value, shrinks)
+        })
+        .describe(&format!("near({pivot}, {spread})"))
+    }
+}
+
+/// A signed distance of at most `spread` from the pivot.
+fn offset(seed: Seed, spread: i128, distribution: Distribution) -> i128 {
+    let (negative, seed) = seed.next_bool();
+    let width = spread.min(u64::MAX as i128 - 1) as u64 + 1;
+    let (distance, _) = distribution.sample_u64(seed, width);
+    if negative {
+        -(distance as i128)
+    } else {
+        distance as i128
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data::Size;
+
+    #[test]
+    fn test_near_stays_in_the_window_and_shrinks_to_the_pivot() {
+        let gen = Gen::<i32>::near(1000, 10);
+        let mut close = 0;
+        for seed in 0..500 {
+            let tree = gen.generate(Size::new(50), Seed::from_u64(seed));
+            assert!((990..=1010).contains(&tree.value), "{}", tree.value);
+            if (tree.value - 1000).abs() <= 5 {
+                close += 1;
+            }
+            if tree.value != 1000 {
+                assert_eq!(*tree.shrinks()[0], 1000);
+                assert!(tree
+                    .shrinks()
+                    .iter()
+                    .all(|&&shrink| (shrink - 1000).abs() < (tree.value - 1000).abs()));
+            }
+        }
+        // Linear favours the pivot: well over half land in the inner half
+        assert!(close > 300, "{close}");
+        assert_eq!(gen.description().as_deref(), Some("near(1000, 10)"));
+    }
+
+    #[test]
+    fn test_near_clamps_to_the_type_bounds() {
+        let gen = Gen::<u8>::near_with(2, 10, Distribution::Uniform);
+        let values: Vec<u8> = (0..200)
+            .map(|seed| gen.generate(Size::new(50), Seed::from_u64(seed)).value)
+            .collect();
+        assert!(values.iter().all(|&value| value <= 12));
+        assert!(values.contains(&0));
+        assert!(values.contains(&12));
+
+        let constant = Gen::<i64>::near_with(i64::MAX, 5, Distribution::Constant);
+        assert_eq!(
+            constant.sample_with(Size::new(50), Seed::from_u64(3)),
+            i64::MAX
+        );
+    }
+
+    #[test]
+    fn test_float_near_shrinks_towards_the_pivot() {
+        let gen = Gen::<f64>::near(0.5, 0.25);
+        for seed in 0..200 {
+            let tree = gen.generate(Size::new(50), Seed::from_u64(seed));
+            assert!((tree.value - 0.5).abs() <= 0.25, "{}", tree.value);
+            if tree.value != 0.5 {
+                assert_eq!(*tree.shrinks()[0], 0.5);
+                assert!(tree
+                    .shrinks()
+                    .iter()
+                    .all(|&&shrink| (shrink - 0.5).abs() < (tree.value - 0.5).abs()));
+            }
+        }
+    }
+}
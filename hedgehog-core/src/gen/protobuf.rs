@@ -0,0 +1,523 @@
+//! Protobuf message generators from descriptors, using `prost-reflect`.
+//!
+//! Messages are generated field by field from their descriptor: scalars
+//! lean towards the edges of their type, enums only take declared values,
+//! at most one field of each oneof is set, and nested messages get smaller
+//! until optional ones are left unset. Messages shrink by clearing fields
+//! and by simplifying the values left, and every shrink is still a valid
+//! message of the same type.
+//!
+//! Dynamic messages are wrapped in [`ProtobufMessage`], whose debug output
+//! shows only the fields that are set, so counterexamples stay readable.
+
+use super::{Draw, Gen};
+use crate::tree::Tree;
+use prost_reflect::bytes::Bytes;
+use prost_reflect::{
+    DynamicMessage, FieldDescriptor, Kind, MapKey, MessageDescriptor, ReflectMessage, Value,
+};
+use std::collections::HashMap;
+use std::fmt;
+
+/// A generated message of a type known only through its descriptor.
+#[derive(Clone, PartialEq)]
+pub struct ProtobufMessage(pub DynamicMessage);
+
+impl ProtobufMessage {
+    /// The message itself.
+    pub fn into_inner(self) -> DynamicMessage {
+        self.0
+    }
+}
+
+impl fmt::Debug for ProtobufMessage {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write_message(f, &self.0)
+    }
+}
+
+fn write_message(f: &mut fmt::Formatter<'_>, message: &DynamicMessage) -> fmt::Result {
+    write!(f, "{} {{", message.descriptor().full_name())?;
+    let mut first = true;
+    for field in message.descriptor().fields() {
+        if !message.has_field(&field) {
+            continue;
+        }
+        write!(f, "{} {}: ", if first { "" } else { "," }, field.name())?;
+        write_value(f, &message.get_field(&field), &field.kind())?;
+        first = false;
+    }
+    write!(f, "{}}}", if first { "" } else { " " })
+}
+
+fn write_value(f: &mut fmt::Formatter<'_>, value: &Value, kind: &Kind) -> fmt::Result {
+    match value {
+        Value::Message(message) => write_message(f, message),
+        Value::Bytes(bytes) => write!(f, "{:?}", bytes.as_ref()),
+        Value::EnumNumber(number) => match kind {
+            Kind::Enum(descriptor) => match descriptor.get_value(*number) {
+                Some(value) => write!(f, "{}", value.name()),
+                None => write!(f, "{number}"),
+            },
+            _ => write!(f, "{number}"),
+        },
+        Value::List(values) => {
+            write!(f, "[")?;
+            for (i, value) in values.iter().enumerate() {
+                if i > 0 {
+                    write!(f, ", ")?;
+                }
+                write_value(f, value, kind)?;
+            }
+            write!(f, "]")
+        }
+        Value::Map(map) => {
+            let Kind::Message(entry) = kind else {
+                return write!(f, "{map:?}");
+            };
+            let value_kind = entry.map_entry_value_field().kind();
+            let mut entries: Vec<_> = map.iter().collect();
+            entries.sort_by(|a, b| a.0.cmp(b.0));
+            write!(f, "{{")?;
+            for (i, (key, value)) in entries.into_iter().enumerate() {
+                if i > 0 {
+                    write!(f, ", ")?;
+                }
+                match key {
+                    MapKey::Bool(b) => write!(f, "{b:?}")?,
+                    MapKey::I32(n) => write!(f, "{n:?}")?,
+                    MapKey::I64(n) => write!(f, "{n:?}")?,
+                    MapKey::U32(n) => write!(f, "{n:?}")?,
+                    MapKey::U64(n) => write!(f, "{n:?}")?,
+                    MapKey::String(s) => write!(f, "{s:?}")?,
+                }
+                write!(f, ": ")?;
+                write_value(f, value, &value_kind)?;
+            }
+            write!(f, "}}")
+        }
+        Value::Bool(b) => write!(f, "{b:?}"),
+        Value::I32(n) => write!(f, "{n:?}"),
+        Value::I64(n) => write!(f, "{n:?}"),
+        Value::U32(n) => write!(f, "{n:?}"),
+        Value::U64(n) => write!(f, "{n:?}"),
+        Value::F32(x) => write!(f, "{x:?}"),
+        Value::F64(x) => write!(f, "{x:?}"),
+        Value::String(s) => write!(f, "{s:?}"),
+    }
+}
+
+impl Gen<ProtobufMessage> {
+    /// Generate messages of the type `descriptor` describes.
+    ///
+    /// # Example
+    /// ```rust,ignore
+    /// use hedgehog_core::*;
+    /// use prost_reflect::DescriptorPool;
+    ///
+    /// let pool = DescriptorPool::decode(include_bytes!("orders.bin").as_ref()).unwrap();
+    /// let order = pool.get_message_by_name("shop.Order").unwrap();
+    /// let gen = Gen::<ProtobufMessage>::protobuf_message(order);
+    /// ```
+    pub fn protobuf_message(descriptor: MessageDescriptor) -> Self {
+        Gen::new(move |size, seed| {
+            let message = message(&descriptor, size.get(), &mut Draw(seed));
+            let shrinks = message_shrinks(&message)
+                .into_iter()
+                .map(|shrink| Tree::singleton(ProtobufMessage(shrink)))
+                .collect();
+            Tree::with_children(ProtobufMessage(message), shrinks)
+        })
+    }
+}
+
+impl<T> Gen<T>
+where
+    T: ReflectMessage + Default + 'static,
+{
+    /// Generate messages of a prost-generated type that implements
+    /// `ReflectMessage`, such as one built with `prost-reflect-build`.
+    pub fn prost_message() -> Self {
+        Gen::<ProtobufMessage>::protobuf_message(T::default().descriptor()).map(|message| {
+            message
+                .0
+                .transcode_to::<T>()
+                .expect("a generated message decodes as its own type")
+        })
+    }
+}
+
+/// Most elements in a generated repeated or map field.
+const MAX_ELEMENTS: usize = 4;
+
+fn message(descriptor: &MessageDescriptor, size: usize, draw: &mut Draw) -> DynamicMessage {
+    let mut message = DynamicMessage::new(descriptor.clone());
+    let nested_size = size / 2;
+
+    for field in descriptor.fields() {
+        let in_oneof = field
+            .containing_oneof()
+            .is_some_and(|oneof| !oneof.is_synthetic());
+        if in_oneof {
+            continue;
+        }
+
+        if field.is_list() {
+            let len = draw.below(size.min(MAX_ELEMENTS) + 1);
+            let values = (0..len)
+                .map(|_| value(&field.kind(), nested_size, draw))
+                .collect();
+            set(&mut message, &field, Value::List(values));
+        } else if field.is_map() {
+            let Kind::Message(entry) = field.kind() else {
+                continue;
+            };
+            let (key_field, value_field) =
+                (entry.map_entry_key_field(), entry.map_entry_value_field());
+            let map = (0..draw.below(size.min(MAX_ELEMENTS) + 1))
+                .filter_map(|_| {
+                    let key = map_key(value(&key_field.kind(), nested_size, draw))?;
+                    Some((key, value(&value_field.kind(), nested_size, draw)))
+                })
+                .collect::<HashMap<_, _>>();
+            set(&mut message, &field, Value::Map(map));
+        } else {
+            // Optional fields, nested messages among them, run out with size
+            let always = is_required(&field) || !field.supports_presence();
+            if always || (size > 0 && draw.chance()) {
+                set(
+                    &mut message,
+                    &field,
+                    value(&field.kind(), nested_size, draw),
+                );
+            }
+        }
+    }
+
+    for oneof in descriptor.oneofs().filter(|oneof| !oneof.is_synthetic()) {
+        let fields: Vec<_> = oneof.fields().collect();
+        let choice = draw.below(fields.len() + 1);
+        if let Some(field) = fields.get(choice) {
+            set(&mut message, field, value(&field.kind(), nested_size, draw));
+        }
+    }
+
+    message
+}
+
+/// Set a field, leaving fields without presence unset at their default so
+/// the message equals itself after a round trip through the wire format.
+fn set(message: &mut DynamicMessage, field: &FieldDescriptor, value: Value) {
+    if !field.supports_presence() && value == field.default_value() {
+        message.clear_field(field);
+    } else {
+        message.set_field(field, value);
+    }
+}
+
+fn is_required(field: &FieldDescriptor) -> bool {
+    field.cardinality() == prost_reflect::Cardinality::Required
+}
+
+fn map_key(value: Value) -> Option<MapKey> {
+    Some(match value {
+        Value::Bool(b) => MapKey::Bool(b),
+        Value::I32(n) => MapKey::I32(n),
+        Value::I64(n) => MapKey::I64(n),
+        Value::U32(n) => MapKey::U32(n),
+        Value::U64(n) => MapKey::U64(n),
+        Value::String(s) => MapKey::String(s),
+        _ => return None,
+    })
+}
+
+/// A value of the given kind, picking an edge case a third of the time.
+fn value(kind: &Kind, size: usize, draw: &mut Draw) -> Value {
+    let edge = draw.below(3) == 0;
+    let bits = draw.below(usize::MAX) as u64;
+
+    match kind {
+        Kind::Double => Value::F64(float(edge, draw, [f64::MIN, f64::MAX, f64::EPSILON])),
+        Kind::Float => Value::F32(float(
+            edge,
+            draw,
+            [f32::MIN.into(), f32::MAX.into(), f32::EPSILON.into()],
+        ) as f32),
+        Kind::Int32 | Kind::Sint32 | Kind::Sfixed32 => Value::I32(pick(
+            edge,
+            draw,
+            &[0, 1, -1, i32::MIN, i32::MAX],
+            bits as i32,
+        )),
+        Kind::Int64 | Kind::Sint64 | Kind::Sfixed64 => Value::I64(pick(
+            edge,
+            draw,
+            &[0, 1, -1, i64::MIN, i64::MAX],
+            bits as i64,
+        )),
+        Kind::Uint32 | Kind::Fixed32 => {
+            Value::U32(pick(edge, draw, &[0, 1, u32::MAX], bits as u32))
+        }
+        Kind::Uint64 | Kind::Fixed64 => Value::U64(pick(edge, draw, &[0, 1, u64::MAX], bits)),
+        Kind::Bool => Value::Bool(draw.chance()),
+        Kind::String => {
+            const CHARACTERS: &[char] =
+                &['a', 'b', 'z', 'A', '0', '9', ' ', '_', 'é', 'ß', '中', '🦔'];
+            let len = if edge { 0 } else { draw.below(9) };
+            Value::String(
+                (0..len)
+                    .map(|_| CHARACTERS[draw.below(CHARACTERS.len())])
+                    .collect(),
+            )
+        }
+        Kind::Bytes => {
+            let len = if edge { 0 } else { draw.below(9) };
+            let bytes: Vec<u8> = (0..len).map(|_| draw.below(256) as u8).collect();
+            Value::Bytes(Bytes::from(bytes))
+        }
+        Kind::Enum(descriptor) => {
+            let values: Vec<_> = descriptor.values().collect();
+            Value::EnumNumber(values[draw.below(values.len())].number())
+        }
+        Kind::Message(descriptor) => Value::Message(message(descriptor, size, draw)),
+    }
+}
+
+fn pick<N: Copy>(edge: bool, draw: &mut Draw, edges: &[N], random: N) -> N {
+    if edge {
+        edges[draw.below(edges.len())]
+    } else {
+        random
+    }
+}
+
+/// A finite float: an edge case, or a small number with a fractional part.
+fn float(edge: bool, draw: &mut Draw, extremes: [f64; 3]) -> f64 {
+    if edge {
+        let edges = [0.0, -0.0, 1.0, -1.0, extremes[0], extremes[1], extremes[2]];
+        edges[draw.below(edges.len())]
+    } else {
+        let whole = draw.below(2001) as f64 - 1000.0;
+        whole + draw.below(100) as f64 / 100.0
+    }
+}
+
+/// Messages one step simpler: each field cleared, then each field's value
+/// simplified in place.
+fn message_shrinks(message: &DynamicMessage) -> Vec<DynamicMessage> {
+    let present: Vec<(FieldDescriptor, Value)> = message
+        .descriptor()
+        .fields()
+        .filter(|field| message.has_field(field))
+        .map(|field| {
+            let value = message.get_field(&field).into_owned();
+            (field, value)
+        })
+        .collect();
+
+    let mut shrinks = Vec::new();
+    for (field, _) in &present {
+        if !is_required(field) {
+            let mut cleared = message.clone();
+            cleared.clear_field(field);
+            shrinks.push(cleared);
+        }
+    }
+    for (field, value) in &present {
+        for simpler in value_shrinks(value, field) {
+            let mut shrunk = message.clone();
+            set(&mut shrunk, field, simpler);
+            shrinks.push(shrunk);
+        }
+    }
+    shrinks
+}
+
+fn value_shrinks(value: &Value, field: &FieldDescriptor) -> Vec<Value> {
+    match value {
+        Value::I32(n) if n.abs_diff(0) > 1 => vec![Value::I32(n / 2)],
+        Value::I64(n) if n.abs_diff(0) > 1 => vec![Value::I64(n / 2)],
+        Value::U32(n) if *n > 1 => vec![Value::U32(n / 2)],
+        Value::U64(n) if *n > 1 => vec![Value::U64(n / 2)],
+        Value::F32(x) if x.fract() != 0.0 => vec![Value::F32(x.trunc())],
+        Value::F64(x) if x.fract() != 0.0 => vec![Value::F64(x.trunc())],
+        Value::String(s) if s.chars().count() > 1 => {
+            let half = s.chars().count() / 2;
+            vec![Value::String(s.chars().take(half).collect())]
+        }
+        Value::Bytes(b) if b.len() > 1 => vec![Value::Bytes(b.slice(..b.len() / 2))],
+        Value::EnumNumber(n) => match field.kind() {
+            Kind::Enum(descriptor) => {
+                let first = descriptor.values().next().map(|value| value.number());
+                first
+                    .filter(|first| first != n)
+                    .map(Value::EnumNumber)
+                    .into_iter()
+                    .collect()
+            }
+            _ => Vec::new(),
+        },
+        Value::List(values) if !values.is_empty() => {
+            vec![Value::List(values[..values.len() / 2].to_vec())]
+        }
+        Value::Message(nested) => message_shrinks(nested)
+            .into_iter()
+            .map(Value::Message)
+            .collect(),
+        _ => Vec::new(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data::{Seed, Size};
+    use prost::Message;
+    use prost_reflect::prost_types::{
+        field_descriptor_proto::{Label, Type},
+        DescriptorProto, EnumDescriptorProto, EnumValueDescriptorProto, FieldDescriptorProto,
+        FileDescriptorProto, FileDescriptorSet, MessageOptions, OneofDescriptorProto,
+    };
+    use prost_reflect::{prost, DescriptorPool};
+
+    fn field(name: &str, number: i32, ty: Type, label: Label) -> FieldDescriptorProto {
+        FieldDescriptorProto {
+            name: Some(name.to_string()),
+            number: Some(number),
+            r#type: Some(ty as i32),
+            label: Some(label as i32),
+            ..Default::default()
+        }
+    }
+
+    fn typed(mut field: FieldDescriptorProto, type_name: &str) -> FieldDescriptorProto {
+        field.type_name = Some(type_name.to_string());
+        field
+    }
+
+    fn in_oneof(mut field: FieldDescriptorProto, index: i32) -> FieldDescriptorProto {
+        field.oneof_index = Some(index);
+        field
+    }
+
+    /// `shop.Order`: scalars, an enum with gaps, a oneof, a map, a repeated
+    /// nested message and a recursive field.
+    fn order() -> MessageDescriptor {
+        use Label::{Optional, Repeated};
+        let file = FileDescriptorProto {
+            name: Some("shop.proto".to_string()),
+            package: Some("shop".to_string()),
+            syntax: Some("proto3".to_string()),
+            message_type: vec![
+                DescriptorProto {
+                    name: Some("Order".to_string()),
+                    field: vec![
+                        field("id", 1, Type::String, Optional),
+                        field("quantity", 2, Type::Int32, Optional),
+                        typed(field("items", 3, Type::Message, Repeated), ".shop.Item"),
+                        typed(field("status", 4, Type::Enum, Optional), ".shop.Status"),
+                        in_oneof(field("card", 5, Type::String, Optional), 0),
+                        in_oneof(field("account", 6, Type::Int64, Optional), 0),
+                        typed(
+                            field("tags", 7, Type::Message, Repeated),
+                            ".shop.Order.TagsEntry",
+                        ),
+                        typed(field("parent", 8, Type::Message, Optional), ".shop.Order"),
+                    ],
+                    nested_type: vec![DescriptorProto {
+                        name: Some("TagsEntry".to_string()),
+                        field: vec![
+                            field("key", 1, Type::String, Optional),
+                            field("value", 2, Type::Uint32, Optional),
+                        ],
+                        options: Some(MessageOptions {
+                            map_entry: Some(true),
+                            ..Default::default()
+                        }),
+                        ..Default::default()
+                    }],
+                    oneof_decl: vec![OneofDescriptorProto {
+                        name: Some("payment".to_string()),
+                        ..Default::default()
+                    }],
+                    ..Default::default()
+                },
+                DescriptorProto {
+                    name: Some("Item".to_string()),
+                    field: vec![
+                        field("sku", 1, Type::Bytes, Optional),
+                        field("price", 2, Type::Double, Optional),
+                    ],
+                    ..Default::default()
+                },
+            ],
+            enum_type: vec![EnumDescriptorProto {
+                name: Some("Status".to_string()),
+                value: [("PENDING", 0), ("SHIPPED", 3), ("CANCELLED", 7)]
+                    .into_iter()
+                    .map(|(name, number)| EnumValueDescriptorProto {
+                        name: Some(name.to_string()),
+                        number: Some(number),
+                        ..Default::default()
+                    })
+                    .collect(),
+                ..Default::default()
+            }],
+            ..Default::default()
+        };
+        let pool = DescriptorPool::from_file_descriptor_set(FileDescriptorSet { file: vec![file] })
+            .unwrap();
+        pool.get_message_by_name("shop.Order").unwrap()
+    }
+
+    fn check(message: &DynamicMessage) {
+        let status = message.get_field_by_name("status").unwrap();
+        assert!([0, 3, 7].contains(&status.as_enum_number().unwrap()));
+        assert!(!(message.has_field_by_name("card") && message.has_field_by_name("account")));
+        if message.has_field_by_name("parent") {
+            let parent = message.get_field_by_name("parent").unwrap();
+            check(parent.as_message().unwrap());
+        }
+
+        let decoded =
+            DynamicMessage::decode(message.descriptor(), message.encode_to_vec().as_slice())
+                .unwrap();
+        assert_eq!(&decoded, message);
+    }
+
+    #[test]
+    fn test_messages_follow_their_descriptor_and_shrink() {
+        let gen = Gen::<ProtobufMessage>::protobuf_message(order());
+        let mut nested = false;
+
+        for seed in 0..100 {
+            let tree = gen.generate(Size::new(8), Seed::from_u64(seed));
+            check(&tree.value.0);
+            nested |= tree.value.0.has_field_by_name("parent");
+
+            for shrink in tree.shrinks() {
+                check(&shrink.0);
+                assert!(shrink.0.encoded_len() <= tree.value.0.encoded_len());
+            }
+        }
+        assert!(nested);
+    }
+
+    #[test]
+    fn test_debug_output_shows_set_fields() {
+        let descriptor = order();
+        let mut message = DynamicMessage::new(descriptor.clone());
+        assert_eq!(
+            format!("{:?}", ProtobufMessage(message.clone())),
+            "shop.Order {}"
+        );
+
+        message.set_field_by_name("quantity", Value::I32(2));
+        message.set_field_by_name("status", Value::EnumNumber(3));
+        message.set_field_by_name("parent", Value::Message(DynamicMessage::new(descriptor)));
+        assert_eq!(
+            format!("{:?}", ProtobufMessage(message)),
+            "shop.Order { quantity: 2, status: SHIPPED, parent: shop.Order {} }"
+        );
+    }
+}
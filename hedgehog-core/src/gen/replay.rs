@@ -0,0 +1,197 @@
+//! Generators that replay fixed inputs instead of random ones.
+//!
+//! Regression suites and data-driven tests have their inputs already: a
+//! table of known cases, or the inputs an earlier run recorded. Turning
+//! them into generators lets them run through the same properties,
+//! classifications and reports as generated inputs.
+//!
+//! Logs hold one input per line, written with `Display` and read back with
+//! `FromStr`. Backslashes, newlines and carriage returns are escaped as
+//! `\\`, `\n` and `\r`, so any input fits on one line.
+//!
+//! # Example
+//! ```rust
+//! use hedgehog_core::*;
+//!
+//! let log = std::env::temp_dir().join(format!("inputs-{}.log", std::process::id()));
+//! # let _ = std::fs::remove_file(&log);
+//! let recorded = Gen::int_range(0, 100).recording_to(&log);
+//! for_all(recorded, |&n| n <= 100).run(&Config::default());
+//!
+//! // Later, the same inputs in the same order
+//! let replayed = Gen::<i32>::replay_log(&log).unwrap();
+//! for_all(replayed, |&n| n <= 100).run(&Config::default());
+//! # std::fs::remove_file(&log).unwrap();
+//! ```
+
+use super::Gen;
+use crate::tree::Tree;
+use std::cell::Cell;
+use std::fmt::Display;
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+
+impl<T: Clone + 'static> Gen<T> {
+    /// Generate the given values in order, starting again from the first
+    /// after the last.
+    ///
+    /// Each value is produced once per generation whatever the size or
+    /// seed, so a property with as many tests as values checks each once.
+    /// Values don't shrink.
+    ///
+    /// # Panics
+    /// Panics if there are no values.
+    ///
+    /// # Example
+    /// ```rust
+    /// use hedgehog_core::*;
+    ///
+    /// let gen = Gen::from_iter_cycle(["GET", "POST", "DELETE"]);
+    /// let seed = Seed::from_u64(0);
+    /// let methods: Vec<_> = (0..4).map(|_| gen.generate(Size::new(10), seed).value).collect();
+    /// assert_eq!(methods, ["GET", "POST", "DELETE", "GET"]);
+    /// ```
+    pub fn from_iter_cycle<I>(values: I) -> Self
+    where
+        I: IntoIterator<Item = T>,
+    {
+        let values: Vec<T> = values.into_iter().collect();
+        assert!(
+            !values.is_empty(),
+            "from_iter_cycle needs at least one value"
+        );
+        let next = Cell::new(0);
+        let count = values.len();
+        Gen::new(move |_, _| {
+            let index = next.get();
+            next.set((index + 1) % values.len());
+            Tree::singleton(values[index].clone())
+        })
+        .describe(&format!("from_iter_cycle({count} values)"))
+    }
+
+    /// Replay the inputs in a log written by
+    /// [`recording_to`](Gen::recording_to), in order and cycling like
+    /// [`from_iter_cycle`](Gen::from_iter_cycle).
+    ///
+    /// Fails if the log can't be read, is empty, or has a line that doesn't
+    /// parse, naming the line.
+    pub fn replay_log(path: impl AsRef<Path>) -> io::Result<Self>
+    where
+        T: FromStr,
+        T::Err: Display,
+    {
+        let path = path.as_ref();
+        let text = std::fs::read_to_string(path)?;
+        let inputs = text
+            .lines()
+            .enumerate()
+            .map(|(index, line)| {
+                unescape(line).parse::<T>().map_err(|err| {
+                    io::Error::new(
+                        io::ErrorKind::InvalidData,
+                        format!("{}:{}: {err}", path.display(), index + 1),
+                    )
+                })
+            })
+            .collect::<io::Result<Vec<T>>>()?;
+        if inputs.is_empty() {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("{} has no inputs", path.display()),
+            ));
+        }
+        Ok(Self::from_iter_cycle(inputs).describe(&format!("replay_log({})", path.display())))
+    }
+
+    /// Append every value this generator produces to the log at `path`,
+    /// for [`replay_log`](Gen::replay_log) to replay.
+    ///
+    /// Shrinks aren't recorded, only the values generated for each case.
+    ///
+    /// # Panics
+    /// Generating panics if the log can't be written.
+    pub fn recording_to(self, path: impl AsRef<Path>) -> Self
+    where
+        T: Display,
+    {
+        let path: PathBuf = path.as_ref().to_path_buf();
+        let description = format!("recording_to({})", self.label());
+        Gen::new(move |size, seed| {
+            let tree = self.generate(size, seed);
+            let written = std::fs::OpenOptions::new()
+                .create(true)
+                .append(true)
+                .open(&path)
+                .and_then(|mut log| writeln!(log, "{}", escape(&tree.value.to_string())));
+            if let Err(err) = written {
+                panic!("couldn't record an input to {}: {err}", path.display());
+            }
+            tree
+        })
+        .describe(&description)
+    }
+}
+
+fn escape(text: &str) -> String {
+    text.replace('\\', "\\\\")
+        .replace('\n', "\\n")
+        .replace('\r', "\\r")
+}
+
+fn unescape(line: &str) -> String {
+    let mut text = String::with_capacity(line.len());
+    let mut chars = line.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            text.push(c);
+            continue;
+        }
+        match chars.next() {
+            Some('n') => text.push('\n'),
+            Some('r') => text.push('\r'),
+            Some(other) => text.push(other),
+            None => text.push('\\'),
+        }
+    }
+    text
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data::{Seed, Size};
+
+    #[test]
+    fn test_recorded_inputs_replay_in_order() {
+        let log = std::env::temp_dir().join(format!("hedgehog-replay-{}.log", std::process::id()));
+        let _ = std::fs::remove_file(&log);
+
+        let gen = Gen::from_iter_cycle(["plain", "two\nlines", "back\\slash", ""])
+            .map(str::to_string)
+            .recording_to(&log);
+        let generated: Vec<String> = (0..4)
+            .map(|seed| gen.generate(Size::new(10), Seed::from_u64(seed)).value)
+            .collect();
+
+        let replayed = Gen::<String>::replay_log(&log).unwrap();
+        let again: Vec<String> = (0..5)
+            .map(|seed| replayed.generate(Size::new(0), Seed::from_u64(seed)).value)
+            .collect();
+        assert_eq!(again[..4], generated);
+        assert_eq!(again[4], generated[0]);
+        std::fs::remove_file(&log).unwrap();
+    }
+
+    #[test]
+    fn test_unparseable_lines_are_reported() {
+        let log = std::env::temp_dir().join(format!("hedgehog-bad-{}.log", std::process::id()));
+        std::fs::write(&log, "1\n2\nthree\n").unwrap();
+        let err = Gen::<i32>::replay_log(&log).err().unwrap();
+        assert!(err
+            .to_string()
+            .ends_with(":3: invalid digit found in string"));
+        std::fs::remove_file(&log).unwrap();
+    }
+}
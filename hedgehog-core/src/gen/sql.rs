@@ -0,0 +1,727 @@
+//! Structured SQL statement generation.
+//!
+//! Statements are generated as a small syntax tree of `SELECT`, `INSERT` and
+//! `UPDATE` statements over generated tables and columns, then rendered for a
+//! [`SqlDialect`]. Shrinking works on the tree: `WHERE` and `LIMIT` clauses
+//! are dropped, conditions lose one side of an `AND` or `OR`, column lists
+//! and rows get shorter and literals get simpler. Every shrink is still a
+//! well-formed statement in the same dialect.
+//!
+//! Text literals can be mixed with SQL injection payloads. The payloads are
+//! escaped like any other literal, so a parser or query builder under test
+//! should always see them as plain data.
+//!
+//! # Example
+//! ```rust
+//! use hedgehog_core::gen::sql::*;
+//! use hedgehog_core::*;
+//!
+//! let options = SqlOptions::new(SqlDialect::Postgres)
+//!     .with_statements(&[SqlStatementKind::Select, SqlStatementKind::Update])
+//!     .with_injection(0.2);
+//! let gen = Gen::<String>::sql_statement(options);
+//! ```
+
+use super::{Draw, Gen};
+use crate::tree::Tree;
+
+/// Reserved words, quoted when used as identifiers.
+pub(super) const SQL_KEYWORDS: &[&str] = &[
+    "SELECT", "INSERT", "UPDATE", "DELETE", "FROM", "WHERE", "JOIN", "INNER", "LEFT", "RIGHT",
+    "ON", "AS", "AND", "OR", "NOT", "NULL", "TRUE", "FALSE", "ORDER", "BY", "GROUP", "HAVING",
+    "LIMIT", "OFFSET", "UNION", "DISTINCT", "COUNT", "SUM", "AVG", "MAX", "MIN", "CREATE", "TABLE",
+    "INDEX", "PRIMARY", "KEY", "FOREIGN", "UNIQUE", "CHECK", "DEFAULT",
+];
+
+/// Classic injection payloads mixed into text literals.
+const INJECTION_PAYLOADS: &[&str] = &[
+    "' OR '1'='1",
+    "' OR 1=1 --",
+    "'; DROP TABLE users; --",
+    "\" OR \"\"=\"",
+    "admin'--",
+    "1; SELECT * FROM information_schema.tables",
+    "' UNION SELECT NULL, NULL --",
+    "\\'; --",
+    "%' AND 1=0 UNION SELECT 'a",
+    "/* comment */",
+];
+
+/// The SQL dialect statements are rendered in.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SqlDialect {
+    /// Standard SQL: double-quoted identifiers, `FETCH FIRST n ROWS ONLY`
+    Ansi,
+    /// PostgreSQL: double-quoted identifiers, `LIMIT n`
+    Postgres,
+    /// MySQL: backquoted identifiers, backslash escapes in strings, `LIMIT n`
+    MySql,
+    /// SQLite: double-quoted identifiers, booleans as `1` and `0`, `LIMIT n`
+    Sqlite,
+}
+
+/// The kinds of statement to generate.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SqlStatementKind {
+    /// `SELECT columns FROM table [WHERE ...] [LIMIT ...]`
+    Select,
+    /// `INSERT INTO table (columns) VALUES (...), ...`
+    Insert,
+    /// `UPDATE table SET column = value, ... [WHERE ...]`
+    Update,
+}
+
+/// Options for [`Gen::sql_statement`].
+#[derive(Debug, Clone)]
+pub struct SqlOptions {
+    dialect: SqlDialect,
+    kinds: Vec<SqlStatementKind>,
+    injection: f64,
+    keyword_identifiers: bool,
+}
+
+impl SqlOptions {
+    /// Generate every kind of statement in `dialect`, without injection
+    /// payloads or reserved words as identifiers.
+    pub fn new(dialect: SqlDialect) -> Self {
+        SqlOptions {
+            dialect,
+            kinds: vec![
+                SqlStatementKind::Select,
+                SqlStatementKind::Insert,
+                SqlStatementKind::Update,
+            ],
+            injection: 0.0,
+            keyword_identifiers: false,
+        }
+    }
+
+    /// Only generate these kinds of statement. An empty list keeps the
+    /// current kinds.
+    pub fn with_statements(mut self, kinds: &[SqlStatementKind]) -> Self {
+        if !kinds.is_empty() {
+            self.kinds = kinds.to_vec();
+        }
+        self
+    }
+
+    /// Replace text literals with an injection payload with the given
+    /// probability, clamped to `0.0..=1.0`.
+    pub fn with_injection(mut self, probability: f64) -> Self {
+        self.injection = probability.clamp(0.0, 1.0);
+        self
+    }
+
+    /// Sometimes use reserved words as table and column names, quoted for
+    /// the dialect.
+    pub fn with_keyword_identifiers(mut self) -> Self {
+        self.keyword_identifiers = true;
+        self
+    }
+}
+
+impl Default for SqlOptions {
+    fn default() -> Self {
+        Self::new(SqlDialect::Ansi)
+    }
+}
+
+impl Gen<String> {
+    /// Generate `SELECT`, `INSERT` and `UPDATE` statements as configured by
+    /// `options`.
+    ///
+    /// Statements grow with size: more columns, more rows and deeper `WHERE`
+    /// clauses. Shrinking drops optional clauses first, then columns, rows
+    /// and conditions, then simplifies literals.
+    ///
+    /// # Example
+    /// ```rust
+    /// use hedgehog_core::gen::sql::*;
+    /// use hedgehog_core::*;
+    ///
+    /// let gen = Gen::<String>::sql_statement(SqlOptions::new(SqlDialect::MySql));
+    /// let statement = gen.generate(Size::new(20), Seed::from_u64(1)).value;
+    /// assert!(["SELECT", "INSERT", "UPDATE"].iter().any(|k| statement.starts_with(k)));
+    /// ```
+    pub fn sql_statement(options: SqlOptions) -> Self {
+        Gen::new(move |size, seed| {
+            let mut draw = Draw(seed);
+            let kind = options.kinds[draw.below(options.kinds.len())];
+            let statement = statement(kind, size.get(), &options, &mut draw);
+            let shrinks = statement
+                .shrinks()
+                .iter()
+                .map(|shrink| Tree::singleton(shrink.render(options.dialect)))
+                .collect();
+            Tree::with_children(statement.render(options.dialect), shrinks)
+        })
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Literal {
+    Null,
+    Bool(bool),
+    Int(i64),
+    Text(String),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Condition {
+    Compare {
+        column: String,
+        op: &'static str,
+        value: Literal,
+    },
+    IsNull(String),
+    And(Box<Condition>, Box<Condition>),
+    Or(Box<Condition>, Box<Condition>),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Statement {
+    Select {
+        table: String,
+        columns: Vec<String>,
+        filter: Option<Condition>,
+        limit: Option<u32>,
+    },
+    Insert {
+        table: String,
+        columns: Vec<String>,
+        rows: Vec<Vec<Literal>>,
+    },
+    Update {
+        table: String,
+        assignments: Vec<(String, Literal)>,
+        filter: Option<Condition>,
+    },
+}
+
+const MAX_COLUMNS: usize = 5;
+const MAX_ROWS: usize = 4;
+const OPERATORS: &[&str] = &["=", "<>", "<", "<=", ">", ">="];
+
+fn statement(
+    kind: SqlStatementKind,
+    size: usize,
+    options: &SqlOptions,
+    draw: &mut Draw,
+) -> Statement {
+    let table = identifier(options, draw);
+    let columns = columns(size, options, draw);
+    match kind {
+        SqlStatementKind::Select => Statement::Select {
+            table,
+            filter: (size > 0 && draw.chance()).then(|| condition(&columns, size, options, draw)),
+            // A quarter of selects list every column with `*`
+            columns: if draw.below(4) == 0 {
+                Vec::new()
+            } else {
+                columns
+            },
+            limit: (size > 0 && draw.chance()).then(|| draw.below(size * 10 + 1) as u32),
+        },
+        SqlStatementKind::Insert => {
+            let rows = (0..1 + draw.below(size.min(MAX_ROWS)))
+                .map(|_| {
+                    columns
+                        .iter()
+                        .map(|_| literal(size, options, draw))
+                        .collect()
+                })
+                .collect();
+            Statement::Insert {
+                table,
+                columns,
+                rows,
+            }
+        }
+        SqlStatementKind::Update => Statement::Update {
+            table,
+            filter: (size > 0 && draw.chance()).then(|| condition(&columns, size, options, draw)),
+            assignments: columns
+                .into_iter()
+                .map(|column| {
+                    let value = literal(size, options, draw);
+                    (column, value)
+                })
+                .collect(),
+        },
+    }
+}
+
+/// Between one and [`MAX_COLUMNS`] distinct column names.
+fn columns(size: usize, options: &SqlOptions, draw: &mut Draw) -> Vec<String> {
+    let count = 1 + draw.below(size.min(MAX_COLUMNS));
+    let mut columns: Vec<String> = Vec::new();
+    for _ in 0..count {
+        let column = identifier(options, draw);
+        if !columns.contains(&column) {
+            columns.push(column);
+        }
+    }
+    columns
+}
+
+fn identifier(options: &SqlOptions, draw: &mut Draw) -> String {
+    if options.keyword_identifiers && draw.below(4) == 0 {
+        return SQL_KEYWORDS[draw.below(SQL_KEYWORDS.len())].to_lowercase();
+    }
+    let len = 1 + draw.below(8);
+    (0..len)
+        .map(|i| {
+            if i > 0 && draw.below(6) == 0 {
+                '_'
+            } else {
+                (b'a' + draw.below(26) as u8) as char
+            }
+        })
+        .collect()
+}
+
+fn condition(columns: &[String], size: usize, options: &SqlOptions, draw: &mut Draw) -> Condition {
+    if size > 1 && draw.below(3) == 0 {
+        let left = Box::new(condition(columns, size / 2, options, draw));
+        let right = Box::new(condition(columns, size / 2, options, draw));
+        return if draw.chance() {
+            Condition::And(left, right)
+        } else {
+            Condition::Or(left, right)
+        };
+    }
+    let column = columns[draw.below(columns.len())].clone();
+    if draw.below(6) == 0 {
+        Condition::IsNull(column)
+    } else {
+        Condition::Compare {
+            column,
+            op: OPERATORS[draw.below(OPERATORS.len())],
+            value: literal(size, options, draw),
+        }
+    }
+}
+
+fn literal(size: usize, options: &SqlOptions, draw: &mut Draw) -> Literal {
+    match draw.below(8) {
+        0 => Literal::Null,
+        1 => Literal::Bool(draw.chance()),
+        2..=4 => Literal::Int(match draw.below(8) {
+            0 => i64::MAX,
+            1 => i64::MIN,
+            _ => draw.below(size * 100 + 1) as i64 - (size * 50) as i64,
+        }),
+        _ => {
+            if draw.below(1_000_000) < (options.injection * 1_000_000.0) as usize {
+                return Literal::Text(
+                    INJECTION_PAYLOADS[draw.below(INJECTION_PAYLOADS.len())].to_string(),
+                );
+            }
+            let len = draw.below(size.min(16) + 1);
+            Literal::Text(
+                (0..len)
+                    .map(|_| match draw.below(10) {
+                        0 => ' ',
+                        1 => (b'0' + draw.below(10) as u8) as char,
+                        _ => (b'a' + draw.below(26) as u8) as char,
+                    })
+                    .collect(),
+            )
+        }
+    }
+}
+
+impl Literal {
+    fn shrinks(&self) -> Vec<Literal> {
+        match self {
+            Literal::Null | Literal::Bool(false) => Vec::new(),
+            Literal::Bool(true) => vec![Literal::Bool(false)],
+            Literal::Int(0) => Vec::new(),
+            Literal::Int(n) if n / 2 == 0 => vec![Literal::Int(0)],
+            Literal::Int(n) => vec![Literal::Int(0), Literal::Int(n / 2)],
+            Literal::Text(text) if text.is_empty() => Vec::new(),
+            Literal::Text(text) => {
+                let mut shrinks = vec![Literal::Text(String::new())];
+                let half: String = text.chars().take(text.chars().count() / 2).collect();
+                if !half.is_empty() {
+                    shrinks.push(Literal::Text(half));
+                }
+                shrinks
+            }
+        }
+    }
+
+    fn render(&self, dialect: SqlDialect) -> String {
+        match self {
+            Literal::Null => "NULL".to_string(),
+            Literal::Bool(b) => match (dialect, b) {
+                (SqlDialect::Sqlite, true) => "1".to_string(),
+                (SqlDialect::Sqlite, false) => "0".to_string(),
+                (_, true) => "TRUE".to_string(),
+                (_, false) => "FALSE".to_string(),
+            },
+            Literal::Int(n) => n.to_string(),
+            Literal::Text(text) => {
+                let mut escaped = text.replace('\'', "''");
+                if dialect == SqlDialect::MySql {
+                    escaped = escaped.replace('\\', "\\\\");
+                }
+                format!("'{escaped}'")
+            }
+        }
+    }
+}
+
+impl Condition {
+    /// Each side of a connective, then simpler comparison values.
+    fn shrinks(&self) -> Vec<Condition> {
+        match self {
+            Condition::And(left, right) | Condition::Or(left, right) => {
+                let mut shrinks = vec![(**left).clone(), (**right).clone()];
+                let rebuild = |l, r| match self {
+                    Condition::And(..) => Condition::And(Box::new(l), Box::new(r)),
+                    _ => Condition::Or(Box::new(l), Box::new(r)),
+                };
+                for l in left.shrinks() {
+                    shrinks.push(rebuild(l, (**right).clone()));
+                }
+                for r in right.shrinks() {
+                    shrinks.push(rebuild((**left).clone(), r));
+                }
+                shrinks
+            }
+            Condition::Compare { column, op, value } => value
+                .shrinks()
+                .into_iter()
+                .map(|value| Condition::Compare {
+                    column: column.clone(),
+                    op,
+                    value,
+                })
+                .collect(),
+            Condition::IsNull(_) => Vec::new(),
+        }
+    }
+
+    fn render(&self, dialect: SqlDialect) -> String {
+        match self {
+            Condition::Compare { column, op, value } => {
+                format!("{} {op} {}", quote(column, dialect), value.render(dialect))
+            }
+            Condition::IsNull(column) => format!("{} IS NULL", quote(column, dialect)),
+            Condition::And(left, right) => {
+                format!("({} AND {})", left.render(dialect), right.render(dialect))
+            }
+            Condition::Or(left, right) => {
+                format!("({} OR {})", left.render(dialect), right.render(dialect))
+            }
+        }
+    }
+}
+
+impl Statement {
+    /// Statements one step simpler: clauses dropped, lists shortened, then
+    /// conditions and literals simplified.
+    fn shrinks(&self) -> Vec<Statement> {
+        let mut shrinks = Vec::new();
+        match self {
+            Statement::Select {
+                table,
+                columns,
+                filter,
+                limit,
+            } => {
+                let select =
+                    |columns: &[String], filter: Option<Condition>, limit| Statement::Select {
+                        table: table.clone(),
+                        columns: columns.to_vec(),
+                        filter,
+                        limit,
+                    };
+                if filter.is_some() {
+                    shrinks.push(select(columns, None, *limit));
+                }
+                if limit.is_some() {
+                    shrinks.push(select(columns, filter.clone(), None));
+                }
+                if columns.len() > 1 {
+                    for i in 0..columns.len() {
+                        shrinks.push(select(&without(columns, i), filter.clone(), *limit));
+                    }
+                }
+                for condition in filter.iter().flat_map(Condition::shrinks) {
+                    shrinks.push(select(columns, Some(condition), *limit));
+                }
+                if let Some(n) = limit.filter(|&n| n > 0) {
+                    shrinks.push(select(columns, filter.clone(), Some(n / 2)));
+                }
+            }
+            Statement::Insert {
+                table,
+                columns,
+                rows,
+            } => {
+                let insert = |columns: Vec<String>, rows: Vec<Vec<Literal>>| Statement::Insert {
+                    table: table.clone(),
+                    columns,
+                    rows,
+                };
+                if rows.len() > 1 {
+                    for i in 0..rows.len() {
+                        shrinks.push(insert(columns.clone(), without(rows, i)));
+                    }
+                }
+                if columns.len() > 1 {
+                    for i in 0..columns.len() {
+                        let rows = rows.iter().map(|row| without(row, i)).collect();
+                        shrinks.push(insert(without(columns, i), rows));
+                    }
+                }
+                for (r, row) in rows.iter().enumerate() {
+                    for (c, value) in row.iter().enumerate() {
+                        for simpler in value.shrinks() {
+                            let mut rows = rows.clone();
+                            rows[r][c] = simpler;
+                            shrinks.push(insert(columns.clone(), rows));
+                        }
+                    }
+                }
+            }
+            Statement::Update {
+                table,
+                assignments,
+                filter,
+            } => {
+                let update = |assignments: Vec<(String, Literal)>, filter| Statement::Update {
+                    table: table.clone(),
+                    assignments,
+                    filter,
+                };
+                if filter.is_some() {
+                    shrinks.push(update(assignments.clone(), None));
+                }
+                if assignments.len() > 1 {
+                    for i in 0..assignments.len() {
+                        shrinks.push(update(without(assignments, i), filter.clone()));
+                    }
+                }
+                for condition in filter.iter().flat_map(Condition::shrinks) {
+                    shrinks.push(update(assignments.clone(), Some(condition)));
+                }
+                for (i, (_, value)) in assignments.iter().enumerate() {
+                    for simpler in value.shrinks() {
+                        let mut assignments = assignments.clone();
+                        assignments[i].1 = simpler;
+                        shrinks.push(update(assignments, filter.clone()));
+                    }
+                }
+            }
+        }
+        shrinks
+    }
+
+    fn render(&self, dialect: SqlDialect) -> String {
+        let list = |columns: &[String]| {
+            columns
+                .iter()
+                .map(|column| quote(column, dialect))
+                .collect::<Vec<_>>()
+                .join(", ")
+        };
+        let filter = |filter: &Option<Condition>| match filter {
+            Some(condition) => format!(" WHERE {}", condition.render(dialect)),
+            None => String::new(),
+        };
+        match self {
+            Statement::Select {
+                table,
+                columns,
+                filter: condition,
+                limit,
+            } => {
+                let columns = if columns.is_empty() {
+                    "*".to_string()
+                } else {
+                    list(columns)
+                };
+                let limit = match (limit, dialect) {
+                    (None, _) => String::new(),
+                    (Some(n), SqlDialect::Ansi) => format!(" FETCH FIRST {n} ROWS ONLY"),
+                    (Some(n), _) => format!(" LIMIT {n}"),
+                };
+                format!(
+                    "SELECT {columns} FROM {}{}{limit}",
+                    quote(table, dialect),
+                    filter(condition)
+                )
+            }
+            Statement::Insert {
+                table,
+                columns,
+                rows,
+            } => {
+                let rows = rows
+                    .iter()
+                    .map(|row| {
+                        let values: Vec<String> =
+                            row.iter().map(|value| value.render(dialect)).collect();
+                        format!("({})", values.join(", "))
+                    })
+                    .collect::<Vec<_>>()
+                    .join(", ");
+                format!(
+                    "INSERT INTO {} ({}) VALUES {rows}",
+                    quote(table, dialect),
+                    list(columns)
+                )
+            }
+            Statement::Update {
+                table,
+                assignments,
+                filter: condition,
+            } => {
+                let assignments = assignments
+                    .iter()
+                    .map(|(column, value)| {
+                        format!("{} = {}", quote(column, dialect), value.render(dialect))
+                    })
+                    .collect::<Vec<_>>()
+                    .join(", ");
+                format!(
+                    "UPDATE {} SET {assignments}{}",
+                    quote(table, dialect),
+                    filter(condition)
+                )
+            }
+        }
+    }
+}
+
+fn without<T: Clone>(items: &[T], index: usize) -> Vec<T> {
+    let mut items = items.to_vec();
+    items.remove(index);
+    items
+}
+
+/// Quote identifiers that are reserved words.
+fn quote(identifier: &str, dialect: SqlDialect) -> String {
+    if !SQL_KEYWORDS
+        .iter()
+        .any(|keyword| keyword.eq_ignore_ascii_case(identifier))
+    {
+        return identifier.to_string();
+    }
+    match dialect {
+        SqlDialect::MySql => format!("`{}`", identifier.replace('`', "``")),
+        _ => format!("\"{}\"", identifier.replace('"', "\"\"")),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data::{Seed, Size};
+
+    /// Every single-quoted literal is closed, so the text outside the
+    /// literals is the statement's own syntax.
+    fn syntax_outside_literals(statement: &str, dialect: SqlDialect) -> Option<String> {
+        let mut syntax = String::new();
+        let mut chars = statement.chars().peekable();
+        while let Some(c) = chars.next() {
+            if c != '\'' {
+                syntax.push(c);
+                continue;
+            }
+            syntax.push_str("''");
+            loop {
+                match chars.next()? {
+                    '\\' if dialect == SqlDialect::MySql => {
+                        chars.next()?;
+                    }
+                    '\'' if chars.peek() == Some(&'\'') => {
+                        chars.next();
+                    }
+                    '\'' => break,
+                    _ => {}
+                }
+            }
+        }
+        Some(syntax)
+    }
+
+    #[test]
+    fn test_payloads_stay_inside_literals() {
+        for dialect in [
+            SqlDialect::Ansi,
+            SqlDialect::Postgres,
+            SqlDialect::MySql,
+            SqlDialect::Sqlite,
+        ] {
+            let gen = Gen::<String>::sql_statement(
+                SqlOptions::new(dialect)
+                    .with_injection(1.0)
+                    .with_keyword_identifiers(),
+            );
+            for seed in 0..100 {
+                let tree = gen.generate(Size::new(30), Seed::from_u64(seed));
+                for statement in std::iter::once(&tree.value).chain(tree.shrinks()) {
+                    let syntax = syntax_outside_literals(statement, dialect)
+                        .unwrap_or_else(|| panic!("unterminated literal: {statement}"));
+                    assert!(!syntax.contains("--"), "{statement}");
+                    assert!(!syntax.contains(';'), "{statement}");
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_dialects_render_differently() {
+        let select = Statement::Select {
+            table: "order".to_string(),
+            columns: vec!["id".to_string()],
+            filter: Some(Condition::Compare {
+                column: "paid".to_string(),
+                op: "=",
+                value: Literal::Bool(true),
+            }),
+            limit: Some(3),
+        };
+        assert_eq!(
+            select.render(SqlDialect::Ansi),
+            "SELECT id FROM \"order\" WHERE paid = TRUE FETCH FIRST 3 ROWS ONLY"
+        );
+        assert_eq!(
+            select.render(SqlDialect::MySql),
+            "SELECT id FROM `order` WHERE paid = TRUE LIMIT 3"
+        );
+        assert_eq!(
+            select.render(SqlDialect::Sqlite),
+            "SELECT id FROM \"order\" WHERE paid = 1 LIMIT 3"
+        );
+        assert_eq!(
+            Literal::Text("it's \\".to_string()).render(SqlDialect::MySql),
+            "'it''s \\\\'"
+        );
+    }
+
+    #[test]
+    fn test_shrinks_drop_clauses_first() {
+        let gen = Gen::<String>::sql_statement(
+            SqlOptions::new(SqlDialect::Postgres).with_statements(&[SqlStatementKind::Select]),
+        );
+        let mut with_where = 0;
+        for seed in 0..50 {
+            let tree = gen.generate(Size::new(30), Seed::from_u64(seed));
+            assert!(tree.value.starts_with("SELECT "));
+            if tree.value.contains(" WHERE ") {
+                with_where += 1;
+                assert!(!tree.shrinks()[0].contains(" WHERE "));
+            }
+            for shrink in tree.shrinks() {
+                assert!(shrink.starts_with("SELECT "));
+                assert_ne!(shrink, &tree.value);
+            }
+        }
+        assert!(with_where > 0);
+    }
+}
@@ -0,0 +1,77 @@
+//! Generators for the string types APIs use besides `String`.
+//!
+//! Interned names, shared configuration keys and borrowed-or-owned messages
+//! are held as `Box<str>`, `Rc<str>`, `Arc<str>` or `Cow<'static, str>`.
+//! Each generator here builds a `String` and converts it, so values shrink
+//! exactly as the `String` generator's do.
+
+use super::Gen;
+use std::borrow::Cow;
+use std::rc::Rc;
+use std::sync::Arc;
+
+macro_rules! impl_string_like {
+    ($($type:ty),*) => {
+        $(
+            impl Gen<$type> {
+                /// Convert the strings `strings` generates, keeping its
+                /// shrinks and description.
+                ///
+                /// # Example
+                /// ```rust
+                /// use hedgehog_core::*;
+                /// # #[allow(unused_imports)]
+                /// use std::{borrow::Cow, rc::Rc, sync::Arc};
+                ///
+                #[doc = concat!("let gen = Gen::<", stringify!($type), ">::from_strings(Gen::<String>::hex());")]
+                /// assert!(gen.sample().chars().all(|c| c.is_ascii_hexdigit()));
+                /// ```
+                pub fn from_strings(strings: Gen<String>) -> Self {
+                    strings.map(<$type>::from)
+                }
+
+                /// Generate ASCII alphabetic strings.
+                pub fn ascii_alpha() -> Self {
+                    Self::from_strings(Gen::<String>::ascii_alpha())
+                }
+
+                /// Generate ASCII alphanumeric strings.
+                pub fn ascii_alphanumeric() -> Self {
+                    Self::from_strings(Gen::<String>::ascii_alphanumeric())
+                }
+
+                /// Generate printable ASCII strings.
+                pub fn ascii_printable() -> Self {
+                    Self::from_strings(Gen::<String>::ascii_printable())
+                }
+            }
+        )*
+    };
+}
+
+impl_string_like!(Box<str>, Rc<str>, Arc<str>, Cow<'static, str>);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data::Config;
+    use crate::shrinking::minimal_counterexample;
+
+    #[test]
+    fn test_string_like_values_shrink_as_strings() {
+        let shrunk = minimal_counterexample(
+            &Gen::<Rc<str>>::ascii_alpha(),
+            |s: &Rc<str>| s.len() < 3,
+            &Config::default(),
+        )
+        .expect("some string should be long");
+        assert_eq!(shrunk.minimal().len(), 3);
+
+        let gen = Gen::<Cow<'static, str>>::from_strings(Gen::<String>::ascii_alpha());
+        assert!(matches!(gen.sample(), Cow::Owned(_)));
+        assert_eq!(
+            Gen::<Box<str>>::ascii_printable().description().as_deref(),
+            Gen::<String>::ascii_printable().description().as_deref()
+        );
+    }
+}
@@ -0,0 +1,293 @@
+//! Time series built from trend, seasonality and noise.
+//!
+//! Metrics and alerting code reacts to the shape of a series: a steady
+//! climb, a daily cycle, a spike above the noise. Independently generated
+//! points have no shape at all. Here each point is the sum of a level, a
+//! linear trend, a sine wave and per-point noise, each drawn from its own
+//! generator, and shrinking removes points and flattens the components so a
+//! counterexample shows which part of the shape the code gets wrong.
+
+use super::{sample_length, Gen};
+use crate::data::{Range, Seed, Size};
+use crate::tree::Tree;
+use std::f64::consts::TAU;
+
+/// The parts of a series for [`Gen::time_series`].
+///
+/// Point `i` is at `start + i * interval` and has the value
+/// `level + trend * i + amplitude * sin(2π * i / period) + noise`.
+pub struct TimeSeries {
+    len_range: Range<usize>,
+    start: u64,
+    interval: u64,
+    level: Gen<f64>,
+    trend: Gen<f64>,
+    seasonality: Option<(Gen<f64>, Gen<usize>)>,
+    noise: Option<Gen<f64>>,
+}
+
+impl TimeSeries {
+    /// Series with a length from `len_range`, one point a minute from the
+    /// Unix epoch, and a level of zero with no trend, seasonality or noise.
+    pub fn new(len_range: Range<usize>) -> Self {
+        TimeSeries {
+            len_range,
+            start: 0,
+            interval: 60,
+            level: Gen::constant(0.0),
+            trend: Gen::constant(0.0),
+            seasonality: None,
+            noise: None,
+        }
+    }
+
+    /// Timestamp the first point `start`.
+    pub fn with_start(mut self, start: u64) -> Self {
+        self.start = start;
+        self
+    }
+
+    /// Space points `interval` apart.
+    pub fn with_interval(mut self, interval: u64) -> Self {
+        self.interval = interval;
+        self
+    }
+
+    /// Draw the value the series starts from.
+    pub fn with_level(mut self, level: Gen<f64>) -> Self {
+        self.level = level;
+        self
+    }
+
+    /// Draw the change in value from one point to the next.
+    pub fn with_trend(mut self, slope: Gen<f64>) -> Self {
+        self.trend = slope;
+        self
+    }
+
+    /// Add a sine wave, drawing its amplitude and its period in points.
+    /// Periods below two are treated as two.
+    pub fn with_seasonality(mut self, amplitude: Gen<f64>, period: Gen<usize>) -> Self {
+        self.seasonality = Some((amplitude, period));
+        self
+    }
+
+    /// Add noise to each point, drawn separately for every point.
+    pub fn with_noise(mut self, noise: Gen<f64>) -> Self {
+        self.noise = Some(noise);
+        self
+    }
+}
+
+impl Gen<Vec<(u64, f64)>> {
+    /// Generate `(timestamp, value)` series shaped by `series`.
+    ///
+    /// Shrinking first flattens the series: all at once to just the level,
+    /// then without noise, seasonality or trend in turn. It then tries
+    /// shorter prefixes and removes single points, leaving a gap in the
+    /// timestamps as missing data would. No shrink is shorter than the
+    /// length range's minimum.
+    ///
+    /// # Example
+    /// ```rust
+    /// use hedgehog_core::*;
+    ///
+    /// // A day of hourly CPU readings with a daily cycle
+    /// let gen = Gen::time_series(
+    ///     TimeSeries::new(Range::linear(1, 24))
+    ///         .with_interval(3600)
+    ///         .with_level(Gen::f64_range(10.0, 50.0))
+    ///         .with_trend(Gen::f64_range(-0.5, 0.5))
+    ///         .with_seasonality(Gen::f64_range(0.0, 20.0), Gen::constant(24))
+    ///         .with_noise(Gen::f64_range(-2.0, 2.0)),
+    /// );
+    /// let series = gen.sample();
+    /// assert!(series.windows(2).all(|pair| pair[0].0 + 3600 == pair[1].0));
+    /// ```
+    pub fn time_series(series: TimeSeries) -> Self {
+        Gen::new(move |size, seed| {
+            let shape = Shape::generate(&series, size, seed);
+            let points: Vec<usize> = (0..shape.noise.len()).collect();
+            let render = |shape: &Shape, points: &[usize]| {
+                points
+                    .iter()
+                    .map(|&i| (series.start + i as u64 * series.interval, shape.value(i)))
+                    .collect()
+            };
+
+            let mut shrinks = Vec::new();
+            for flat in shape.flattened() {
+                shrinks.push(Tree::singleton(render(&flat, &points)));
+            }
+            for shorter in point_shrinks(&points, series.len_range.min) {
+                shrinks.push(Tree::singleton(render(&shape, &shorter)));
+            }
+            Tree::with_children(render(&shape, &points), shrinks)
+        })
+    }
+}
+
+/// The components drawn for one series.
+#[derive(Clone)]
+struct Shape {
+    level: f64,
+    trend: f64,
+    amplitude: f64,
+    period: usize,
+    noise: Vec<f64>,
+}
+
+impl Shape {
+    fn generate(series: &TimeSeries, size: Size, seed: Seed) -> Shape {
+        let seeds = seed.split_n(6);
+        let length = sample_length(&series.len_range, seeds[0]);
+        let (amplitude, period) = match &series.seasonality {
+            Some((amplitude, period)) => (
+                amplitude.generate(size, seeds[3]).value,
+                period.generate(size, seeds[4]).value.max(2),
+            ),
+            None => (0.0, 2),
+        };
+        let noise = match &series.noise {
+            Some(noise) => seeds[5]
+                .split_n(length)
+                .into_iter()
+                .map(|seed| noise.generate(size, seed).value)
+                .collect(),
+            None => vec![0.0; length],
+        };
+        Shape {
+            level: series.level.generate(size, seeds[1]).value,
+            trend: series.trend.generate(size, seeds[2]).value,
+            amplitude,
+            period,
+            noise,
+        }
+    }
+
+    fn value(&self, i: usize) -> f64 {
+        let season = self.amplitude * (TAU * i as f64 / self.period as f64).sin();
+        self.level + self.trend * i as f64 + season + self.noise[i]
+    }
+
+    /// Copies with fewer components: just the level, then each of noise,
+    /// seasonality and trend removed on its own.
+    fn flattened(&self) -> Vec<Shape> {
+        let without_noise = Shape {
+            noise: vec![0.0; self.noise.len()],
+            ..self.clone()
+        };
+        let without_seasonality = Shape {
+            amplitude: 0.0,
+            ..self.clone()
+        };
+        let without_trend = Shape {
+            trend: 0.0,
+            ..self.clone()
+        };
+        let level_only = Shape {
+            trend: 0.0,
+            amplitude: 0.0,
+            ..without_noise.clone()
+        };
+
+        let mut flattened = Vec::new();
+        let has_noise = self.noise.iter().any(|&noise| noise != 0.0);
+        if [has_noise, self.amplitude != 0.0, self.trend != 0.0]
+            .iter()
+            .filter(|&&present| present)
+            .count()
+            > 1
+        {
+            flattened.push(level_only);
+        }
+        if has_noise {
+            flattened.push(without_noise);
+        }
+        if self.amplitude != 0.0 {
+            flattened.push(without_seasonality);
+        }
+        if self.trend != 0.0 {
+            flattened.push(without_trend);
+        }
+        flattened
+    }
+}
+
+/// Subsets of `points` with shorter prefixes first, then with one point
+/// removed, none shorter than `min`.
+fn point_shrinks(points: &[usize], min: usize) -> Vec<Vec<usize>> {
+    let mut shrinks = Vec::new();
+    let mut len = min;
+    while len < points.len() {
+        shrinks.push(points[..len].to_vec());
+        len = (len * 2).max(len + 1);
+    }
+    if points.len() > min {
+        for i in 0..points.len() {
+            let mut shrink = points.to_vec();
+            shrink.remove(i);
+            if !shrinks.contains(&shrink) {
+                shrinks.push(shrink);
+            }
+        }
+    }
+    shrinks
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn cycle() -> TimeSeries {
+        TimeSeries::new(Range::new(4, 30))
+            .with_start(1_000)
+            .with_interval(10)
+            .with_level(Gen::constant(100.0))
+            .with_trend(Gen::constant(1.0))
+            .with_seasonality(Gen::constant(5.0), Gen::constant(4))
+    }
+
+    #[test]
+    fn test_points_follow_the_components() {
+        let gen = Gen::time_series(cycle());
+        for seed in 0..50 {
+            let series = gen.sample_with(Size::new(50), Seed::from_u64(seed));
+            assert!((4..=30).contains(&series.len()));
+            for (i, &(timestamp, value)) in series.iter().enumerate() {
+                assert_eq!(timestamp, 1_000 + 10 * i as u64);
+                let season = 5.0 * (TAU * i as f64 / 4.0).sin();
+                assert!((value - (100.0 + i as f64 + season)).abs() < 1e-9);
+            }
+        }
+    }
+
+    #[test]
+    fn test_shrinks_flatten_components_then_remove_points() {
+        let gen = Gen::time_series(cycle().with_noise(Gen::f64_range(1.0, 2.0)));
+        let tree = gen.generate(Size::new(50), Seed::from_u64(7));
+        let shrinks = tree.shrinks();
+
+        // Level only first: every point at the level
+        assert_eq!(shrinks[0].len(), tree.value.len());
+        assert!(shrinks[0].iter().all(|&(_, value)| value == 100.0));
+
+        let mut removed_point = false;
+        for shrink in &shrinks {
+            assert!(shrink.len() >= 4);
+            if shrink.len() == tree.value.len() - 1 && shrink.last() == tree.value.last() {
+                removed_point = true;
+            }
+        }
+        assert!(removed_point);
+    }
+
+    #[test]
+    fn test_point_shrinks_respect_the_minimum() {
+        assert_eq!(
+            point_shrinks(&[0, 1, 2], 1),
+            vec![vec![0], vec![0, 1], vec![1, 2], vec![0, 2]]
+        );
+        assert!(point_shrinks(&[0, 1], 2).is_empty());
+    }
+}
@@ -0,0 +1,417 @@
+//! Keeping a history of property runs across CI builds.
+//!
+//! A single run says whether a property passed; a history says whether it
+//! is getting slower, failing more often, or testing different inputs than
+//! it used to. That last one is easy to miss: a refactored generator that
+//! stops producing negative numbers still passes every run, and only the
+//! `classify` percentages show it.
+//!
+//! A config built with [`Config::with_history`](crate::Config::with_history),
+//! or [`Config::from_env`](crate::Config::from_env) while `HEDGEHOG_HISTORY`
+//! names a directory, appends a [`RunRecord`] line to the property's file
+//! each time a property finishes. `cargo hedgehog report` reads the files
+//! back, shows the trends for each property and flags classifications whose
+//! share has drifted; see [`drift`].
+//!
+//! Properties run by [`TestRunner`](crate::TestRunner) or with
+//! [`run_with_context`](crate::Property::run_with_context) are recorded
+//! under their name. Others are recorded under the location they were
+//! defined at, which changes when code above them moves.
+
+use crate::error::TestResult;
+use crate::property::{PropertyMarker, TestStatistics};
+use std::collections::BTreeMap;
+use std::io::Write;
+use std::path::Path;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+/// Environment variable naming the directory run records are appended in.
+pub const HISTORY_ENV_VAR: &str = "HEDGEHOG_HISTORY";
+
+/// How many earlier runs [`drift`] compares the latest one against.
+pub const DRIFT_BASELINE_RUNS: usize = 10;
+
+/// How a recorded run ended.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RunOutcome {
+    /// Every case passed
+    Passed,
+    /// A case failed
+    Failed,
+    /// Too many cases were discarded
+    GaveUp,
+}
+
+impl RunOutcome {
+    fn name(self) -> &'static str {
+        match self {
+            RunOutcome::Passed => "pass",
+            RunOutcome::Failed => "fail",
+            RunOutcome::GaveUp => "discard",
+        }
+    }
+
+    fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "pass" => Some(RunOutcome::Passed),
+            "fail" => Some(RunOutcome::Failed),
+            "discard" => Some(RunOutcome::GaveUp),
+            _ => None,
+        }
+    }
+}
+
+/// A summary of one property run, as stored in a history file.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RunRecord {
+    /// When the run finished, in seconds since the Unix epoch
+    pub timestamp: u64,
+    /// The property's name, or the location it was defined at
+    pub property: String,
+    /// How the run ended
+    pub outcome: RunOutcome,
+    /// Cases run
+    pub tests: usize,
+    /// Cases discarded
+    pub discards: usize,
+    /// Shrinks taken to reach the reported counterexample
+    pub shrinks: usize,
+    /// Time spent generating, testing and shrinking
+    pub duration: Duration,
+    /// Cases in each classification, by name
+    pub classifications: BTreeMap<String, usize>,
+    /// Whether the property was marked as expected to fail or quarantined
+    pub marker: Option<PropertyMarker>,
+}
+
+impl RunRecord {
+    /// Summarize a finished run.
+    pub(crate) fn new(
+        property: &str,
+        result: &TestResult,
+        statistics: &TestStatistics,
+        marker: Option<&PropertyMarker>,
+    ) -> Self {
+        let (outcome, tests, shrinks) = match result {
+            TestResult::Pass { tests_run, .. }
+            | TestResult::PassWithStatistics { tests_run, .. } => {
+                (RunOutcome::Passed, *tests_run, 0)
+            }
+            TestResult::Fail {
+                tests_run,
+                shrinks_performed,
+                ..
+            } => (RunOutcome::Failed, *tests_run, *shrinks_performed),
+            TestResult::Discard { .. } => (RunOutcome::GaveUp, statistics.total_tests, 0),
+        };
+        RunRecord {
+            timestamp: SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map_or(0, |elapsed| elapsed.as_secs()),
+            property: property.to_string(),
+            outcome,
+            tests,
+            discards: statistics.discards,
+            shrinks,
+            duration: statistics.timings.total(),
+            classifications: statistics
+                .classifications
+                .iter()
+                .map(|(name, count)| (name.clone(), *count))
+                .collect(),
+            marker: marker.cloned(),
+        }
+    }
+
+    /// The share of cases in a classification, as a percentage.
+    pub fn percentage(&self, classification: &str) -> f64 {
+        let count = self.classifications.get(classification).copied();
+        match (count, self.tests) {
+            (Some(count), tests) if tests > 0 => count as f64 * 100.0 / tests as f64,
+            _ => 0.0,
+        }
+    }
+
+    /// The record as one line of a history file, without the newline.
+    ///
+    /// Fields are tab-separated `key=value` pairs, classifications written
+    /// as `name:count` pairs separated by commas. A marker is written as
+    /// `marker=quarantined` or `marker=expected:<reason>`, and left out for
+    /// unmarked properties.
+    pub fn to_line(&self) -> String {
+        let classifications: Vec<String> = self
+            .classifications
+            .iter()
+            .map(|(name, count)| format!("{}:{count}", escape(name)))
+            .collect();
+        let marker = self.marker.as_ref().map(|marker| match marker {
+            PropertyMarker::ExpectedFailure(reason) => {
+                format!("marker=expected:{}", escape(reason))
+            }
+            PropertyMarker::Quarantined => "marker=quarantined".to_string(),
+        });
+        [
+            format!("time={}", self.timestamp),
+            format!("property={}", escape(&self.property)),
+            format!("outcome={}", self.outcome.name()),
+            format!("tests={}", self.tests),
+            format!("discards={}", self.discards),
+            format!("shrinks={}", self.shrinks),
+            format!("micros={}", self.duration.as_micros()),
+            format!("classes={}", classifications.join(",")),
+        ]
+        .into_iter()
+        .chain(marker)
+        .collect::<Vec<_>>()
+        .join("\t")
+    }
+
+    /// Read a line written by [`to_line`](Self::to_line), or `None` if it
+    /// isn't one.
+    pub fn parse(line: &str) -> Option<RunRecord> {
+        let fields: BTreeMap<&str, &str> = line
+            .split('\t')
+            .filter_map(|field| field.split_once('='))
+            .collect();
+        let number = |key: &str| fields.get(key)?.parse::<u64>().ok();
+
+        let mut classifications = BTreeMap::new();
+        for class in fields.get("classes")?.split(',').filter(|c| !c.is_empty()) {
+            let (name, count) = class.rsplit_once(':')?;
+            classifications.insert(unescape(name), count.parse().ok()?);
+        }
+        let marker = match fields.get("marker") {
+            None => None,
+            Some(&"quarantined") => Some(PropertyMarker::Quarantined),
+            Some(marker) => Some(PropertyMarker::ExpectedFailure(unescape(
+                marker.strip_prefix("expected:")?,
+            ))),
+        };
+        Some(RunRecord {
+            timestamp: number("time")?,
+            property: unescape(fields.get("property")?),
+            outcome: RunOutcome::from_name(fields.get("outcome")?)?,
+            tests: number("tests")? as usize,
+            discards: number("discards")? as usize,
+            shrinks: number("shrinks")? as usize,
+            duration: Duration::from_micros(number("micros")?),
+            classifications,
+            marker,
+        })
+    }
+}
+
+/// Append a record for a finished run to the history file, reporting on
+/// stderr if it can't be written.
+pub(crate) fn append(
+    path: &Path,
+    property: &str,
+    result: &TestResult,
+    stats: &TestStatistics,
+    marker: Option<&PropertyMarker>,
+) {
+    let record = RunRecord::new(property, result, stats, marker);
+    let written = std::fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(path)
+        .and_then(|mut file| writeln!(file, "{}", record.to_line()));
+    if let Err(error) = written {
+        eprintln!("Could not append to {}: {error}", path.display());
+    }
+}
+
+/// A classification whose share of cases in a property's latest run is
+/// unlike its share in the runs before.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Drift {
+    /// The property
+    pub property: String,
+    /// The classification
+    pub classification: String,
+    /// Its mean percentage over the earlier runs
+    pub baseline: f64,
+    /// Its percentage in the latest run
+    pub latest: f64,
+}
+
+/// Classifications whose percentage in each property's latest run is at
+/// least `threshold` percentage points from their mean over the
+/// [`DRIFT_BASELINE_RUNS`] runs before it.
+///
+/// Runs that didn't record any cases are skipped, and a property needs at
+/// least one earlier run to be compared. A classification that was never
+/// hit in a run counts as 0% for it.
+///
+/// # Example
+/// ```rust
+/// use hedgehog_core::history::{drift, RunRecord};
+///
+/// let runs: Vec<RunRecord> = [
+///     "time=1\tproperty=p\toutcome=pass\ttests=100\tdiscards=0\tshrinks=0\tmicros=5\tclasses=negative:50",
+///     "time=2\tproperty=p\toutcome=pass\ttests=100\tdiscards=0\tshrinks=0\tmicros=5\tclasses=negative:2",
+/// ]
+/// .iter()
+/// .filter_map(|line| RunRecord::parse(line))
+/// .collect();
+///
+/// let drifted = drift(&runs, 20.0);
+/// assert_eq!(drifted[0].classification, "negative");
+/// assert_eq!((drifted[0].baseline, drifted[0].latest), (50.0, 2.0));
+/// ```
+pub fn drift(records: &[RunRecord], threshold: f64) -> Vec<Drift> {
+    let mut by_property: BTreeMap<&str, Vec<&RunRecord>> = BTreeMap::new();
+    for record in records.iter().filter(|record| record.tests > 0) {
+        by_property
+            .entry(&record.property)
+            .or_default()
+            .push(record);
+    }
+
+    let mut drifted = Vec::new();
+    for (property, runs) in by_property {
+        let Some((latest, earlier)) = runs.split_last() else {
+            continue;
+        };
+        let baseline_runs = &earlier[earlier.len().saturating_sub(DRIFT_BASELINE_RUNS)..];
+        if baseline_runs.is_empty() {
+            continue;
+        }
+
+        let mut names: Vec<&String> = baseline_runs
+            .iter()
+            .chain([latest])
+            .flat_map(|run| run.classifications.keys())
+            .collect();
+        names.sort();
+        names.dedup();
+        for name in names {
+            let baseline = baseline_runs
+                .iter()
+                .map(|run| run.percentage(name))
+                .sum::<f64>()
+                / baseline_runs.len() as f64;
+            let latest = latest.percentage(name);
+            if (latest - baseline).abs() >= threshold {
+                drifted.push(Drift {
+                    property: property.to_string(),
+                    classification: name.clone(),
+                    baseline,
+                    latest,
+                });
+            }
+        }
+    }
+    drifted
+}
+
+/// Escape the characters that separate fields and classifications.
+fn escape(text: &str) -> String {
+    let mut escaped = String::new();
+    for c in text.chars() {
+        match c {
+            '%' | '\t' | '\n' | '\r' | '=' | ',' | ':' => {
+                escaped.push_str(&format!("%{:02X}", c as u32))
+            }
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+fn unescape(text: &str) -> String {
+    let mut unescaped = String::new();
+    let mut rest = text;
+    while let Some(index) = rest.find('%') {
+        unescaped.push_str(&rest[..index]);
+        let code = rest
+            .get(index + 1..index + 3)
+            .and_then(|hex| u8::from_str_radix(hex, 16).ok());
+        match code {
+            Some(code) => {
+                unescaped.push(code as char);
+                rest = &rest[index + 3..];
+            }
+            None => {
+                unescaped.push('%');
+                rest = &rest[index + 1..];
+            }
+        }
+    }
+    unescaped.push_str(rest);
+    unescaped
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn run(timestamp: u64, negative: usize, zero: usize) -> RunRecord {
+        RunRecord {
+            timestamp,
+            property: "parse: round trip".to_string(),
+            outcome: RunOutcome::Passed,
+            tests: 100,
+            discards: 0,
+            shrinks: 0,
+            duration: Duration::from_millis(12),
+            classifications: BTreeMap::from([
+                ("negative".to_string(), negative),
+                ("zero, or close".to_string(), zero),
+            ]),
+            marker: None,
+        }
+    }
+
+    #[test]
+    fn test_records_round_trip_through_lines() {
+        let record = run(1_700_000_000, 40, 10);
+        let line = record.to_line();
+        assert!(!line.contains('\n'));
+        assert!(line.contains("classes=negative:40,zero%2C or close:10"));
+        assert_eq!(RunRecord::parse(&line), Some(record.clone()));
+        assert_eq!(RunRecord::parse("not a record"), None);
+
+        let expected = RunRecord {
+            marker: Some(PropertyMarker::ExpectedFailure(
+                "issue-123: parser".to_string(),
+            )),
+            ..record
+        };
+        let line = expected.to_line();
+        assert!(line.ends_with("\tmarker=expected:issue-123%3A parser"));
+        assert_eq!(RunRecord::parse(&line), Some(expected));
+    }
+
+    #[test]
+    fn test_drift_compares_the_latest_run_with_the_ones_before() {
+        let mut runs: Vec<RunRecord> = (0..12).map(|i| run(i, 40 + i as usize % 3, 10)).collect();
+        assert!(drift(&runs, 10.0).is_empty());
+
+        runs.push(run(12, 5, 12));
+        let drifted = drift(&runs, 10.0);
+        assert_eq!(drifted.len(), 1);
+        assert_eq!(drifted[0].classification, "negative");
+        assert_eq!(drifted[0].latest, 5.0);
+        assert!((drifted[0].baseline - 41.0).abs() < 1.0);
+    }
+
+    #[test]
+    fn test_finished_runs_are_appended_to_the_history() {
+        let path = std::env::temp_dir().join(format!("hedgehog-history-{}", std::process::id()));
+        let config = crate::Config::default().with_tests(20).with_history(&path);
+        let prop = crate::for_all(crate::Gen::int_range(-10, 10), |&n: &i32| n < 100)
+            .classify("negative", |&n| n < 0);
+        prop.run_with_context(&config, Some("bounded"), None);
+        prop.run_with_context(&config, Some("bounded"), None);
+        let history = std::fs::read_to_string(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        let records: Vec<RunRecord> = history.lines().filter_map(RunRecord::parse).collect();
+        assert_eq!(records.len(), 2);
+        assert_eq!(records[0].property, "bounded");
+        assert_eq!(records[0].outcome, RunOutcome::Passed);
+        assert_eq!(records[0].tests, 20);
+        assert!(records[0].classifications.contains_key("negative"));
+    }
+}
@@ -0,0 +1,217 @@
+//! Checking that random streams look uniform and independent.
+//!
+//! Generators composed by hand need a separate seed for each part. Seeds
+//! from [`Seed::split`] and [`Seed::split_n`] are independent. Reusing one
+//! seed for two parts, or making seeds some other way, may not be, and
+//! correlated streams quietly shrink the space of inputs a property
+//! explores. These chi-squared tests catch the mistake: sample the streams, bucket the values, and check the counts are
+//! what independent uniform streams would give.
+
+use crate::data::Seed;
+use std::fmt;
+
+/// The result of a chi-squared test.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ChiSquared {
+    /// The chi-squared statistic
+    pub statistic: f64,
+    /// Degrees of freedom of the test
+    pub degrees_of_freedom: usize,
+}
+
+impl ChiSquared {
+    /// The statistic above which fewer than one in a thousand samples from
+    /// uniform, independent streams would fall.
+    ///
+    /// Uses the Wilson-Hilferty approximation, which is close for the
+    /// degrees of freedom these tests have.
+    pub fn critical_value(&self) -> f64 {
+        // Upper 0.1% point of the standard normal distribution
+        const Z: f64 = 3.090_232;
+        let k = self.degrees_of_freedom.max(1) as f64;
+        let spread = 2.0 / (9.0 * k);
+        k * (1.0 - spread + Z * spread.sqrt()).powi(3)
+    }
+
+    /// Whether the samples are plausibly from uniform, independent streams.
+    pub fn is_plausible(&self) -> bool {
+        self.statistic <= self.critical_value()
+    }
+}
+
+impl fmt::Display for ChiSquared {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "χ² = {:.1} with {} degrees of freedom (critical value {:.1})",
+            self.statistic,
+            self.degrees_of_freedom,
+            self.critical_value()
+        )
+    }
+}
+
+fn bucket(value: u64, buckets: usize) -> usize {
+    ((value as u128 * buckets as u128) >> 64) as usize
+}
+
+/// Test whether `values` are spread evenly over `buckets` equal ranges.
+///
+/// Aim for at least five values per bucket on average.
+pub fn uniformity<I>(values: I, buckets: usize) -> ChiSquared
+where
+    I: IntoIterator<Item = u64>,
+{
+    let mut counts = vec![0usize; buckets];
+    let mut total = 0;
+    for value in values {
+        counts[bucket(value, buckets)] += 1;
+        total += 1;
+    }
+    let expected = total as f64 / buckets as f64;
+    let statistic = counts
+        .iter()
+        .map(|&count| (count as f64 - expected).powi(2) / expected)
+        .sum();
+    ChiSquared {
+        statistic,
+        degrees_of_freedom: buckets.saturating_sub(1),
+    }
+}
+
+/// Test whether the two values in each pair are independent of each other,
+/// bucketing each into `buckets` ranges.
+///
+/// Aim for at least five pairs per cell of the `buckets` by `buckets`
+/// table on average.
+pub fn independence<I>(pairs: I, buckets: usize) -> ChiSquared
+where
+    I: IntoIterator<Item = (u64, u64)>,
+{
+    let mut table = vec![vec![0usize; buckets]; buckets];
+    let mut rows = vec![0usize; buckets];
+    let mut columns = vec![0usize; buckets];
+    let mut total = 0;
+    for (a, b) in pairs {
+        let (row, column) = (bucket(a, buckets), bucket(b, buckets));
+        table[row][column] += 1;
+        rows[row] += 1;
+        columns[column] += 1;
+        total += 1;
+    }
+
+    let mut statistic = 0.0;
+    for (row, counts) in table.iter().enumerate() {
+        for (column, &count) in counts.iter().enumerate() {
+            let expected = (rows[row] * columns[column]) as f64 / total as f64;
+            if expected > 0.0 {
+                statistic += (count as f64 - expected).powi(2) / expected;
+            }
+        }
+    }
+    ChiSquared {
+        statistic,
+        degrees_of_freedom: buckets.saturating_sub(1).pow(2),
+    }
+}
+
+/// Test whether streams made from many seeds are independent: for each of
+/// `samples` base seeds, `make` turns it into two seeds, and the first value
+/// of each stream is compared.
+///
+/// # Example
+/// ```rust
+/// use hedgehog_core::*;
+/// use hedgehog_core::independence::streams;
+///
+/// // Seeds from split are independent
+/// assert!(streams(10_000, |seed| seed.split()).is_plausible());
+///
+/// // One seed reused for two parts is not
+/// assert!(!streams(10_000, |seed| (seed, seed)).is_plausible());
+/// ```
+pub fn streams<F>(samples: usize, make: F) -> ChiSquared
+where
+    F: Fn(Seed) -> (Seed, Seed),
+{
+    let pairs = Seed::from_u64(0).split_n(samples).into_iter().map(|seed| {
+        let (a, b) = make(seed);
+        (a.next_u64().0, b.next_u64().0)
+    });
+    independence(pairs, 16)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SAMPLES: usize = 20_000;
+
+    #[test]
+    fn test_split_halves_are_uniform_and_independent() {
+        let seeds = Seed::from_u64(1).split_n(SAMPLES);
+        let halves: Vec<(u64, u64)> = seeds
+            .iter()
+            .map(|seed| {
+                let (a, b) = seed.split();
+                (a.next_u64().0, b.next_u64().0)
+            })
+            .collect();
+
+        let left = uniformity(halves.iter().map(|&(a, _)| a), 64);
+        let right = uniformity(halves.iter().map(|&(_, b)| b), 64);
+        assert!(left.is_plausible(), "{left}");
+        assert!(right.is_plausible(), "{right}");
+
+        let pairs = independence(halves.iter().copied(), 16);
+        assert!(pairs.is_plausible(), "{pairs}");
+    }
+
+    #[test]
+    fn test_split_n_streams_are_independent() {
+        let neighbours = streams(SAMPLES, |seed| {
+            let seeds = seed.split_n(2);
+            (seeds[0], seeds[1])
+        });
+        assert!(neighbours.is_plausible(), "{neighbours}");
+
+        let apart = streams(SAMPLES, |seed| {
+            let seeds = seed.split_n(10);
+            (seeds[0], seeds[9])
+        });
+        assert!(apart.is_plausible(), "{apart}");
+
+        // Consecutive outputs within one stream, too
+        let within: Vec<(u64, u64)> = Seed::from_u64(2)
+            .split_n(SAMPLES)
+            .into_iter()
+            .map(|seed| {
+                let (first, seed) = seed.next_u64();
+                (first, seed.next_u64().0)
+            })
+            .collect();
+        let within = independence(within, 16);
+        assert!(within.is_plausible(), "{within}");
+    }
+
+    #[test]
+    fn test_detects_correlation() {
+        let reused = streams(SAMPLES, |seed| (seed, seed));
+        assert!(!reused.is_plausible(), "{reused}");
+
+        let skewed = uniformity((0..SAMPLES as u64).map(|n| n % 1000), 16);
+        assert!(!skewed.is_plausible(), "{skewed}");
+    }
+
+    #[test]
+    fn test_critical_value() {
+        // Tabulated upper 0.1% points
+        for (degrees_of_freedom, expected) in [(10, 29.59), (15, 37.70), (100, 149.45)] {
+            let test = ChiSquared {
+                statistic: 0.0,
+                degrees_of_freedom,
+            };
+            assert!((test.critical_value() - expected).abs() < 0.5);
+        }
+    }
+}
@@ -0,0 +1,281 @@
+//! Running test cases in forked child processes.
+//!
+//! A segfault, abort or stack overflow in the code under test takes the whole
+//! test binary down with it, losing the input that caused it. With process
+//! isolation each call of the test function happens in a child forked from
+//! the runner; the child sends its result back over a pipe, and a child that
+//! dies instead is reported as a failure of that input.
+//!
+//! Only the forking thread survives in the child, so test functions that
+//! rely on other threads of the test binary (a shared runtime, say) should
+//! not be isolated.
+
+use crate::error::{FailureDetails, TestResult};
+use std::fs::File;
+use std::io::{Read, Write};
+use std::os::unix::io::FromRawFd;
+
+/// How an isolated call ended, when it did not return a result.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) enum Crash {
+    /// The test function panicked with this message.
+    Panic(String),
+    /// The child was killed by a signal.
+    Signal(i32),
+    /// The child exited without reporting a result.
+    Exit(i32),
+}
+
+impl Crash {
+    /// The assertion type to report, such as `Crashed: signal 11 (SIGSEGV)`.
+    pub(crate) fn assertion_type(&self) -> String {
+        match self {
+            Crash::Panic(message) => format!("Panic: {message}"),
+            Crash::Signal(signal) => match signal_name(*signal) {
+                Some(name) => format!("Crashed: signal {signal} ({name})"),
+                None => format!("Crashed: signal {signal}"),
+            },
+            Crash::Exit(code) => format!("Crashed: exit status {code}"),
+        }
+    }
+}
+
+fn signal_name(signal: i32) -> Option<&'static str> {
+    Some(match signal {
+        libc::SIGSEGV => "SIGSEGV",
+        libc::SIGABRT => "SIGABRT",
+        libc::SIGBUS => "SIGBUS",
+        libc::SIGFPE => "SIGFPE",
+        libc::SIGILL => "SIGILL",
+        libc::SIGKILL => "SIGKILL",
+        libc::SIGTRAP => "SIGTRAP",
+        _ => return None,
+    })
+}
+
+/// Run `f` in a forked child and return its result, or how the child died.
+///
+/// A panic in `f` is caught in the child, so it is reported with its message
+/// rather than as an exit status.
+pub(crate) fn run<F>(f: F) -> Result<TestResult, Crash>
+where
+    F: FnOnce() -> TestResult,
+{
+    let mut fds = [0; 2];
+    // SAFETY: `fds` has room for the two descriptors `pipe` writes.
+    if unsafe { libc::pipe(fds.as_mut_ptr()) } != 0 {
+        panic!(
+            "could not create a pipe for an isolated test case: {}",
+            std::io::Error::last_os_error()
+        );
+    }
+    let (read_fd, write_fd) = (fds[0], fds[1]);
+
+    // SAFETY: the child only runs the test function and exits without
+    // returning into the caller.
+    let pid = unsafe { libc::fork() };
+    if pid < 0 {
+        panic!(
+            "could not fork an isolated test case: {}",
+            std::io::Error::last_os_error()
+        );
+    }
+
+    if pid == 0 {
+        // SAFETY: both descriptors came from `pipe` above and are owned here.
+        unsafe { libc::close(read_fd) };
+        let mut pipe = unsafe { File::from_raw_fd(write_fd) };
+        let bytes = match crate::panic::catch(f) {
+            Ok(result) => encode(&result),
+            Err(message) => {
+                let mut bytes = vec![PANIC];
+                push_str(&mut bytes, &message);
+                bytes
+            }
+        };
+        let written = pipe.write_all(&bytes).is_ok();
+        // SAFETY: `_exit` skips destructors and atexit handlers, which
+        // belong to the parent.
+        unsafe { libc::_exit(if written { 0 } else { 1 }) };
+    }
+
+    // SAFETY: as above; the parent keeps only the read end.
+    unsafe { libc::close(write_fd) };
+    let mut pipe = unsafe { File::from_raw_fd(read_fd) };
+    let mut bytes = Vec::new();
+    let _ = pipe.read_to_end(&mut bytes);
+
+    let mut status = 0;
+    // SAFETY: `pid` is our own child and `status` is a valid out pointer.
+    while unsafe { libc::waitpid(pid, &mut status, 0) } < 0 {
+        if std::io::Error::last_os_error().kind() != std::io::ErrorKind::Interrupted {
+            break;
+        }
+    }
+
+    if let Some(outcome) = decode(&bytes) {
+        outcome
+    } else if libc::WIFSIGNALED(status) {
+        Err(Crash::Signal(libc::WTERMSIG(status)))
+    } else {
+        Err(Crash::Exit(libc::WEXITSTATUS(status)))
+    }
+}
+
+const PASS: u8 = 0;
+const FAIL: u8 = 1;
+const DISCARD: u8 = 2;
+const PANIC: u8 = 3;
+
+/// Encode the parts of a test function's result the runner looks at.
+fn encode(result: &TestResult) -> Vec<u8> {
+    let mut bytes = Vec::new();
+    match result {
+        TestResult::Pass { .. } | TestResult::PassWithStatistics { .. } => bytes.push(PASS),
+        TestResult::Fail {
+            counterexample,
+            assertion_type,
+            ..
+        } => {
+            bytes.push(FAIL);
+            push_str(&mut bytes, counterexample);
+            match assertion_type {
+                Some(assertion) => {
+                    bytes.push(1);
+                    push_str(&mut bytes, assertion);
+                }
+                None => bytes.push(0),
+            }
+        }
+        TestResult::Discard { limit, .. } => {
+            bytes.push(DISCARD);
+            bytes.extend_from_slice(&(*limit as u64).to_le_bytes());
+        }
+    }
+    bytes
+}
+
+fn push_str(bytes: &mut Vec<u8>, s: &str) {
+    bytes.extend_from_slice(&(s.len() as u64).to_le_bytes());
+    bytes.extend_from_slice(s.as_bytes());
+}
+
+/// Decode what the child wrote, or `None` if it is incomplete.
+fn decode(bytes: &[u8]) -> Option<Result<TestResult, Crash>> {
+    let mut reader = Reader { bytes };
+    let result = match reader.byte()? {
+        PASS => TestResult::Pass {
+            tests_run: 1,
+            property_name: None,
+            module_path: None,
+        },
+        FAIL => {
+            let counterexample = reader.string()?;
+            let assertion_type = match reader.byte()? {
+                0 => None,
+                _ => Some(reader.string()?),
+            };
+            TestResult::Fail {
+                counterexample,
+                tests_run: 1,
+                shrinks_performed: 0,
+                property_name: None,
+                module_path: None,
+                assertion_type,
+                shrink_steps: Vec::new(),
+                details: FailureDetails::default(),
+            }
+        }
+        DISCARD => TestResult::Discard {
+            limit: reader.u64()? as usize,
+            property_name: None,
+            module_path: None,
+        },
+        PANIC => return Some(Err(Crash::Panic(reader.string()?))),
+        _ => return None,
+    };
+    Some(Ok(result))
+}
+
+struct Reader<'a> {
+    bytes: &'a [u8],
+}
+
+impl<'a> Reader<'a> {
+    fn take(&mut self, n: usize) -> Option<&'a [u8]> {
+        if self.bytes.len() < n {
+            return None;
+        }
+        let (taken, rest) = self.bytes.split_at(n);
+        self.bytes = rest;
+        Some(taken)
+    }
+
+    fn byte(&mut self) -> Option<u8> {
+        self.take(1).map(|bytes| bytes[0])
+    }
+
+    fn u64(&mut self) -> Option<u64> {
+        self.take(8)
+            .map(|bytes| u64::from_le_bytes(bytes.try_into().unwrap()))
+    }
+
+    fn string(&mut self) -> Option<String> {
+        let len = self.u64()? as usize;
+        self.take(len)
+            .and_then(|bytes| String::from_utf8(bytes.to_vec()).ok())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_results_cross_the_process_boundary() {
+        let pass = run(|| TestResult::Pass {
+            tests_run: 1,
+            property_name: None,
+            module_path: None,
+        });
+        assert!(matches!(pass, Ok(TestResult::Pass { .. })));
+
+        let fail = run(|| TestResult::Fail {
+            counterexample: "42".to_string(),
+            tests_run: 1,
+            shrinks_performed: 0,
+            property_name: None,
+            module_path: None,
+            assertion_type: Some("Boolean Condition".to_string()),
+            shrink_steps: Vec::new(),
+            details: FailureDetails::default(),
+        });
+        match fail {
+            Ok(TestResult::Fail {
+                counterexample,
+                assertion_type,
+                ..
+            }) => {
+                assert_eq!(counterexample, "42");
+                assert_eq!(assertion_type.as_deref(), Some("Boolean Condition"));
+            }
+            other => panic!("expected a failure, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn test_crashes_are_reported() {
+        let aborted = run(|| std::process::abort());
+        assert_eq!(aborted, Err(Crash::Signal(libc::SIGABRT)));
+        assert_eq!(
+            Crash::Signal(libc::SIGABRT).assertion_type(),
+            format!("Crashed: signal {} (SIGABRT)", libc::SIGABRT)
+        );
+
+        let exited = run(|| std::process::exit(3));
+        assert_eq!(exited, Err(Crash::Exit(3)));
+
+        let panicked = run(|| panic!("boom"));
+        assert_eq!(panicked, Err(Crash::Panic("boom".to_string())));
+    }
+}
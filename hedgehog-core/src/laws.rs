@@ -0,0 +1,743 @@
+//! Ready-made properties for common algebraic laws.
+//!
+//! Many properties are instances of the same few laws: normalising twice
+//! is normalising once, reversing twice is doing nothing, decoding undoes
+//! encoding, merging in either order gives the same result. Each function
+//! here builds the property for one law from a generator and the function
+//! under test, and a failure names the law and shows both sides of it.
+//!
+//! Comparison and hashing impls have laws of their own that sorting,
+//! ordered collections and hash maps rely on. [`ord_laws`],
+//! [`partial_ord_laws`], [`comparator_laws`] and [`hash_laws`] check them on
+//! generated triples or pairs, shrinking to a small set of values that
+//! break one.
+
+use crate::compare::{diff, disagreement};
+use crate::error::TestResult;
+use crate::gen::Gen;
+use crate::property::Property;
+use std::cmp::Ordering;
+use std::collections::hash_map::DefaultHasher;
+use std::fmt::Debug;
+use std::hash::{Hash, Hasher};
+use std::rc::Rc;
+
+/// How the three values of a triple are named in reports.
+const NAMES: [&str; 3] = ["a", "b", "c"];
+
+/// Create a property that passes when applying `f` twice gives what
+/// applying it once does: `f(f(x)) == f(x)`.
+///
+/// # Example
+/// ```rust
+/// use hedgehog_core::*;
+///
+/// let prop = idempotent(Gen::<String>::ascii_alpha(), |s: &String| s.to_lowercase());
+/// assert!(matches!(prop.run(&Config::default()), TestResult::Pass { .. }));
+/// ```
+#[track_caller]
+pub fn idempotent<T, F>(generator: Gen<T>, f: F) -> Property<T>
+where
+    T: 'static + Debug + Clone + PartialEq,
+    F: Fn(&T) -> T + 'static,
+{
+    Property::new(generator, move |x| {
+        let once = f(x);
+        let twice = f(&once);
+        law(x, "Not Idempotent", ("f(x)", &once), ("f(f(x))", &twice))
+    })
+}
+
+/// Create a property that passes when `f` undoes itself:
+/// `f(f(x)) == x`.
+///
+/// # Example
+/// ```rust
+/// use hedgehog_core::*;
+///
+/// let prop = involutive(Gen::<Vec<i32>>::vec_of(Gen::int_range(0, 9)), |xs: &Vec<i32>| {
+///     xs.iter().rev().copied().collect()
+/// });
+/// assert!(matches!(prop.run(&Config::default()), TestResult::Pass { .. }));
+/// ```
+#[track_caller]
+pub fn involutive<T, F>(generator: Gen<T>, f: F) -> Property<T>
+where
+    T: 'static + Debug + Clone + PartialEq,
+    F: Fn(&T) -> T + 'static,
+{
+    Property::new(generator, move |x| {
+        let twice = f(&f(x));
+        law(x, "Not An Involution", ("x", x), ("f(f(x))", &twice))
+    })
+}
+
+/// Create a property that passes when `decode` recovers each input from
+/// its encoding: `decode(encode(x)) == Ok(x)`.
+///
+/// A decoding error fails with the error and the encoding that caused it.
+/// Decoders that can't fail can return `Ok::<_, Infallible>`.
+///
+/// # Example
+/// ```rust
+/// use hedgehog_core::*;
+///
+/// let prop = roundtrip(
+///     Gen::int_range(-1000, 1000),
+///     |n: &i32| n.to_string(),
+///     |s: &String| s.parse::<i32>(),
+/// );
+/// assert!(matches!(prop.run(&Config::default()), TestResult::Pass { .. }));
+/// ```
+#[track_caller]
+pub fn roundtrip<T, E, D, Enc, Dec>(generator: Gen<T>, encode: Enc, decode: Dec) -> Property<T>
+where
+    T: 'static + Debug + Clone + PartialEq,
+    E: Debug,
+    D: Debug,
+    Enc: Fn(&T) -> E + 'static,
+    Dec: Fn(&E) -> Result<T, D> + 'static,
+{
+    Property::new(generator, move |x| {
+        let encoded = encode(x);
+        match decode(&encoded) {
+            Ok(decoded) if decoded == *x => pass(),
+            Ok(decoded) => disagreement(
+                x,
+                "Round Trip Changed Value",
+                &format!(
+                    "encoded: {encoded:?}\n{}",
+                    diff(("x", x), ("decode(encode(x))", &decoded))
+                ),
+            ),
+            Err(error) => disagreement(
+                x,
+                "Round Trip Decode Failed",
+                &format!("encoded: {encoded:?}\nerror: {error:?}"),
+            ),
+        }
+    })
+}
+
+/// Create a property that passes when parsing a value's printed form gives
+/// the value back: `x.to_string().parse() == Ok(x)`.
+///
+/// This is [`roundtrip`] through `Display` and `FromStr`. Chain
+/// [`Property::with_corpus_capture`] to keep the printed form of each
+/// counterexample and replay it on later runs.
+///
+/// # Example
+/// ```rust
+/// use hedgehog_core::*;
+///
+/// let prop = parse_roundtrip(Gen::int_range(-1000, 1000));
+/// assert!(matches!(prop.run(&Config::default()), TestResult::Pass { .. }));
+/// ```
+#[track_caller]
+pub fn parse_roundtrip<T>(generator: Gen<T>) -> Property<T>
+where
+    T: 'static + Debug + Clone + PartialEq + std::fmt::Display + std::str::FromStr,
+    T::Err: Debug,
+{
+    roundtrip(
+        generator,
+        |x: &T| x.to_string(),
+        |s: &String| s.parse::<T>(),
+    )
+}
+
+/// Create a property that passes when `f` ignores the order of its
+/// arguments: `f(a, b) == f(b, a)`.
+///
+/// Both arguments come from `generator`.
+///
+/// # Example
+/// ```rust
+/// use hedgehog_core::*;
+///
+/// let prop = commutative(Gen::int_range(-100, 100), |a: &i32, b: &i32| a.max(b).to_owned());
+/// assert!(matches!(prop.run(&Config::default()), TestResult::Pass { .. }));
+/// ```
+#[track_caller]
+pub fn commutative<T, O, F>(generator: Gen<T>, f: F) -> Property<(T, T)>
+where
+    T: 'static + Debug + Clone,
+    O: PartialEq + Debug,
+    F: Fn(&T, &T) -> O + 'static,
+{
+    let generator = shared(generator);
+    Property::new(
+        Gen::<(T, T)>::tuple_of(generator(), generator()),
+        move |(a, b)| {
+            law(
+                &(a, b),
+                "Not Commutative",
+                ("f(a, b)", &f(a, b)),
+                ("f(b, a)", &f(b, a)),
+            )
+        },
+    )
+}
+
+/// Create a property that passes when `f` ignores how its applications
+/// are grouped: `f(f(a, b), c) == f(a, f(b, c))`.
+///
+/// All three arguments come from `generator`.
+///
+/// # Example
+/// ```rust
+/// use hedgehog_core::*;
+///
+/// let prop = associative(Gen::<String>::ascii_alpha(), |a: &String, b: &String| {
+///     format!("{a}{b}")
+/// });
+/// assert!(matches!(prop.run(&Config::default()), TestResult::Pass { .. }));
+/// ```
+#[track_caller]
+pub fn associative<T, F>(generator: Gen<T>, f: F) -> Property<(T, T, T)>
+where
+    T: 'static + Debug + Clone + PartialEq,
+    F: Fn(&T, &T) -> T + 'static,
+{
+    Property::new(triples(generator), move |(a, b, c)| {
+        law(
+            &(a, b, c),
+            "Not Associative",
+            ("f(f(a, b), c)", &f(&f(a, b), c)),
+            ("f(a, f(b, c))", &f(a, &f(b, c))),
+        )
+    })
+}
+
+/// Create a property that passes when `compare` is a total order:
+/// reflexive, antisymmetric and transitive.
+///
+/// Sorting with a comparator that breaks these laws gives unspecified
+/// orders and may panic. Each generated triple is checked in every order,
+/// so a failure shrinks to the values that break a law.
+///
+/// # Example
+/// ```rust
+/// use hedgehog_core::*;
+///
+/// // Compares by absolute value, breaking ties by sign
+/// let prop = comparator_laws(Gen::int_range(-100, 100), |a: &i32, b: &i32| {
+///     a.abs().cmp(&b.abs()).then(a.cmp(b))
+/// });
+/// assert!(matches!(prop.run(&Config::default()), TestResult::Pass { .. }));
+/// ```
+#[track_caller]
+pub fn comparator_laws<T, F>(generator: Gen<T>, compare: F) -> Property<(T, T, T)>
+where
+    T: 'static + Debug + Clone,
+    F: Fn(&T, &T) -> Ordering + 'static,
+{
+    Property::new(triples(generator), move |triple| {
+        verdict(triple, ordering_violation(triple, "cmp", &compare))
+    })
+}
+
+/// Create a property that passes when `T`'s `Ord` impl is a total order
+/// that agrees with its `PartialEq` and `PartialOrd` impls.
+///
+/// # Example
+/// ```rust
+/// use hedgehog_core::*;
+///
+/// let prop = ord_laws(Gen::<String>::ascii_alpha());
+/// assert!(matches!(prop.run(&Config::default()), TestResult::Pass { .. }));
+/// ```
+#[track_caller]
+pub fn ord_laws<T>(generator: Gen<T>) -> Property<(T, T, T)>
+where
+    T: 'static + Debug + Clone + Ord,
+{
+    Property::new(triples(generator), move |triple| {
+        let violation = ordering_violation(triple, "cmp", &T::cmp)
+            .or_else(|| eq_violation(triple, "cmp", &T::cmp))
+            .or_else(|| {
+                pairs(triple).find_map(|((x, a), (y, b))| {
+                    let (total, partial) = (a.cmp(b), a.partial_cmp(b));
+                    (partial != Some(total)).then(|| {
+                        (
+                            "partial_cmp Disagrees With cmp".to_string(),
+                            format!("cmp({x}, {y}): {total:?}\npartial_cmp({x}, {y}): {partial:?}"),
+                        )
+                    })
+                })
+            });
+        verdict(triple, violation)
+    })
+}
+
+/// Create a property that passes when `T`'s `PartialOrd` impl is a
+/// partial order that agrees with its `PartialEq` impl.
+///
+/// Values may be incomparable, as `NaN` is, but comparisons must still be
+/// antisymmetric and transitive wherever they give an answer.
+///
+/// # Example
+/// ```rust
+/// use hedgehog_core::*;
+///
+/// let prop = partial_ord_laws(Gen::<f64>::from_range(Range::new(-10.0, 10.0)));
+/// assert!(matches!(prop.run(&Config::default()), TestResult::Pass { .. }));
+/// ```
+#[track_caller]
+pub fn partial_ord_laws<T>(generator: Gen<T>) -> Property<(T, T, T)>
+where
+    T: 'static + Debug + Clone + PartialOrd,
+{
+    Property::new(triples(generator), move |triple| {
+        let violation = ordering_violation(triple, "partial_cmp", &T::partial_cmp)
+            .or_else(|| eq_violation(triple, "partial_cmp", &T::partial_cmp));
+        verdict(triple, violation)
+    })
+}
+
+/// Create a property that passes when values equal under `T`'s `Eq` impl
+/// hash the same under its `Hash` impl.
+///
+/// Hash maps and sets lose track of keys that break this. Random pairs
+/// are rarely equal, so generators for this law should draw from a small
+/// set of values or produce the same value in different forms.
+///
+/// # Example
+/// ```rust
+/// use hedgehog_core::*;
+///
+/// let prop = hash_laws(Gen::int_range(0, 5));
+/// assert!(matches!(prop.run(&Config::default()), TestResult::Pass { .. }));
+/// ```
+#[track_caller]
+pub fn hash_laws<T>(generator: Gen<T>) -> Property<(T, T)>
+where
+    T: 'static + Debug + Clone + Hash + Eq,
+{
+    let generator = shared(generator);
+    Property::new(
+        Gen::<(T, T)>::tuple_of(generator(), generator()),
+        move |(a, b)| {
+            let clone = a.clone();
+            let checks = [(("a", a), ("a.clone()", &clone)), (("a", a), ("b", b))];
+            for ((x, left), (y, right)) in checks {
+                let (left_hash, right_hash) = (hash_of(left), hash_of(right));
+                if left == right && left_hash != right_hash {
+                    return disagreement(
+                        &(a, b),
+                        "Hash Disagrees With Eq",
+                        &format!(
+                            "{x} == {y}: true\nhash({x}): {left_hash}\nhash({y}): {right_hash}"
+                        ),
+                    );
+                }
+            }
+            pass()
+        },
+    )
+}
+
+/// The outcome of comparing two values: an [`Ordering`], or `None` for
+/// incomparable values under a partial order.
+trait Comparison: Copy + PartialEq + Debug {
+    /// Whether comparisons must be reflexive.
+    const TOTAL: bool;
+
+    fn reverse(self) -> Self;
+
+    fn is_equal(self) -> bool;
+
+    fn is_incomparable(self) -> bool;
+}
+
+impl Comparison for Ordering {
+    const TOTAL: bool = true;
+
+    fn reverse(self) -> Self {
+        Ordering::reverse(self)
+    }
+
+    fn is_equal(self) -> bool {
+        self == Ordering::Equal
+    }
+
+    fn is_incomparable(self) -> bool {
+        false
+    }
+}
+
+impl Comparison for Option<Ordering> {
+    const TOTAL: bool = false;
+
+    fn reverse(self) -> Self {
+        self.map(Ordering::reverse)
+    }
+
+    fn is_equal(self) -> bool {
+        self == Some(Ordering::Equal)
+    }
+
+    fn is_incomparable(self) -> bool {
+        self.is_none()
+    }
+}
+
+/// Each ordered pair of distinct positions in a triple, with their names.
+fn pairs<T>(
+    (a, b, c): &(T, T, T),
+) -> impl Iterator<Item = ((&'static str, &T), (&'static str, &T))> {
+    let values = [a, b, c];
+    (0..3).flat_map(move |i| {
+        (0..3)
+            .filter(move |&j| j != i)
+            .map(move |j| ((NAMES[i], values[i]), (NAMES[j], values[j])))
+    })
+}
+
+/// The first law of orderings that `compare` breaks on `triple`, as a
+/// headline and the comparisons that show it.
+fn ordering_violation<T, O: Comparison>(
+    triple: &(T, T, T),
+    name: &str,
+    compare: &dyn Fn(&T, &T) -> O,
+) -> Option<(String, String)> {
+    let (a, b, c) = triple;
+    let values = [a, b, c];
+
+    if O::TOTAL {
+        for (x, value) in NAMES.iter().zip(values) {
+            let result = compare(value, value);
+            if !result.is_equal() {
+                return Some((
+                    "Ordering Not Reflexive".to_string(),
+                    format!("{name}({x}, {x}): {result:?}"),
+                ));
+            }
+        }
+    }
+
+    for ((x, left), (y, right)) in pairs(triple) {
+        let (forward, backward) = (compare(left, right), compare(right, left));
+        if forward.reverse() != backward {
+            return Some((
+                "Ordering Not Antisymmetric".to_string(),
+                format!("{name}({x}, {y}): {forward:?}\n{name}({y}, {x}): {backward:?}"),
+            ));
+        }
+    }
+
+    // x ~ y and y ~ z give x ~ z, and x == y means x compares with z as y does
+    for ((x, left), (y, middle)) in pairs(triple) {
+        let (z, right) = NAMES
+            .iter()
+            .zip(values)
+            .find(|(z, _)| **z != x && **z != y)
+            .map(|(z, right)| (*z, right))?;
+        let (first, second, outer) = (
+            compare(left, middle),
+            compare(middle, right),
+            compare(left, right),
+        );
+        let expected = if first.is_equal() {
+            Some(second)
+        } else if first == second && !first.is_incomparable() {
+            Some(first)
+        } else {
+            None
+        };
+        if expected.is_some_and(|expected| outer != expected) {
+            return Some((
+                "Ordering Not Transitive".to_string(),
+                format!(
+                    "{name}({x}, {y}): {first:?}\n{name}({y}, {z}): {second:?}\n{name}({x}, {z}): {outer:?}"
+                ),
+            ));
+        }
+    }
+
+    None
+}
+
+/// The first pair in `triple` whose comparison says equal when `==`
+/// doesn't, or the other way round.
+fn eq_violation<T: PartialEq, O: Comparison>(
+    triple: &(T, T, T),
+    name: &str,
+    compare: &dyn Fn(&T, &T) -> O,
+) -> Option<(String, String)> {
+    pairs(triple).find_map(|((x, left), (y, right))| {
+        let (result, equal) = (compare(left, right), left == right);
+        (result.is_equal() != equal).then(|| {
+            (
+                "Ordering Disagrees With Eq".to_string(),
+                format!("{name}({x}, {y}): {result:?}\n{x} == {y}: {equal}"),
+            )
+        })
+    })
+}
+
+fn hash_of<T: Hash>(value: &T) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    value.hash(&mut hasher);
+    hasher.finish()
+}
+
+/// Pass if both sides of a law agree, otherwise fail with `headline` and a
+/// diff of the two.
+fn law<T: Debug, O: PartialEq + Debug>(
+    input: &T,
+    headline: &str,
+    left: (&str, &O),
+    right: (&str, &O),
+) -> TestResult {
+    if left.1 == right.1 {
+        pass()
+    } else {
+        disagreement(input, headline, &diff(left, right))
+    }
+}
+
+/// Pass unless there is a violation, otherwise fail with its headline and
+/// detail.
+fn verdict<T: Debug>(input: &T, violation: Option<(String, String)>) -> TestResult {
+    match violation {
+        Some((headline, detail)) => disagreement(input, &headline, &detail),
+        None => pass(),
+    }
+}
+
+fn pass() -> TestResult {
+    TestResult::Pass {
+        tests_run: 1,
+        property_name: None,
+        module_path: None,
+    }
+}
+
+/// Triples of values from `generator`.
+fn triples<T: 'static + Clone>(generator: Gen<T>) -> Gen<(T, T, T)> {
+    let generator = shared(generator);
+    Gen::<(T, T, T)>::tuple_of(generator(), generator(), generator())
+}
+
+/// Generators that each draw from `generator`, for building tuples of it.
+fn shared<T: 'static>(generator: Gen<T>) -> impl Fn() -> Gen<T> {
+    let description = generator.description();
+    let generator = Rc::new(generator);
+    move || {
+        let generator = Rc::clone(&generator);
+        let copy = Gen::new(move |size, seed| generator.generate(size, seed));
+        match &description {
+            Some(description) => copy.describe(description),
+            None => copy,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data::Config;
+    use crate::regression::corpus_entries;
+
+    fn failure<T>(prop: Property<T>) -> (String, String)
+    where
+        T: 'static + Debug + Clone,
+    {
+        match prop.run(&Config::default()) {
+            TestResult::Fail {
+                counterexample,
+                assertion_type,
+                ..
+            } => (counterexample, assertion_type.unwrap()),
+            other => panic!("Expected failure, got: {other:?}"),
+        }
+    }
+
+    #[test]
+    fn test_laws_that_hold_pass() {
+        let config = Config::default();
+        let abs = idempotent(Gen::int_range(-100, 100), |n: &i32| n.abs());
+        let negate = involutive(Gen::int_range(-100, 100), |n: &i32| -n);
+        let add = commutative(Gen::int_range(-100, 100), |a: &i32, b: &i32| a + b);
+        let min = associative(Gen::int_range(-100, 100), |a: &i32, b: &i32| *a.min(b));
+        assert!(matches!(abs.run(&config), TestResult::Pass { .. }));
+        assert!(matches!(negate.run(&config), TestResult::Pass { .. }));
+        assert!(matches!(add.run(&config), TestResult::Pass { .. }));
+        assert!(matches!(min.run(&config), TestResult::Pass { .. }));
+    }
+
+    #[test]
+    fn test_broken_laws_show_both_sides() {
+        let (counterexample, assertion) =
+            failure(idempotent(Gen::int_range(0, 100), |n: &i32| n + 1));
+        let n: i32 = counterexample.parse().unwrap();
+        assert_eq!(
+            assertion,
+            format!("Not Idempotent\n- f(x): {}\n+ f(f(x)): {}", n + 1, n + 2)
+        );
+
+        let (counterexample, assertion) =
+            failure(commutative(Gen::int_range(0, 100), |a: &i32, b: &i32| {
+                a - b
+            }));
+        let (a, b): (i32, i32) = {
+            let parts: Vec<i32> = counterexample
+                .trim_matches(|c| c == '(' || c == ')')
+                .split(", ")
+                .map(|part| part.parse().unwrap())
+                .collect();
+            (parts[0], parts[1])
+        };
+        assert_eq!(
+            assertion,
+            format!(
+                "Not Commutative\n- f(a, b): {}\n+ f(b, a): {}",
+                a - b,
+                b - a
+            )
+        );
+    }
+
+    #[test]
+    fn test_roundtrip_reports_decode_errors() {
+        let prop = roundtrip(
+            Gen::int_range(10, 99),
+            |n: &i32| format!("{n}!"),
+            |s: &String| s.parse::<i32>(),
+        );
+        let (counterexample, assertion) = failure(prop);
+        assert_eq!(
+            assertion,
+            format!(
+                "Round Trip Decode Failed\nencoded: \"{counterexample}!\"\nerror: ParseIntError {{ kind: InvalidDigit }}"
+            )
+        );
+    }
+
+    /// Prints negative readings with a sign its parser doesn't accept.
+    #[derive(Debug, Clone, PartialEq)]
+    struct Celsius(i32);
+
+    impl std::fmt::Display for Celsius {
+        fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+            write!(f, "{}C", self.0)
+        }
+    }
+
+    impl std::str::FromStr for Celsius {
+        type Err = String;
+
+        fn from_str(s: &str) -> Result<Self, Self::Err> {
+            let digits = s.strip_suffix('C').ok_or("missing unit")?;
+            if digits.starts_with('-') {
+                return Err(format!("unsigned reading {digits}"));
+            }
+            digits.parse().map(Celsius).map_err(|err| format!("{err}"))
+        }
+    }
+
+    #[test]
+    fn test_parse_roundtrip_captures_failures_in_the_corpus() {
+        let directory =
+            std::env::temp_dir().join(format!("hedgehog-laws-corpus-{}", std::process::id()));
+        let celsius = || Gen::int_range(-50, 50).map(Celsius);
+
+        let prop = parse_roundtrip(celsius()).with_corpus_capture_in(&directory);
+        let (counterexample, assertion) = failure(prop);
+        let printed = format!(
+            "{}C",
+            counterexample
+                .trim_start_matches("Celsius(")
+                .trim_end_matches(')')
+        );
+        assert!(printed.starts_with('-'));
+        assert!(assertion.starts_with(&format!("Round Trip Decode Failed\nencoded: \"{printed}\"")));
+        let entries = corpus_entries(&directory).unwrap();
+        assert_eq!(entries.len(), 1);
+        assert_eq!(std::fs::read_to_string(&entries[0]).unwrap(), printed);
+
+        // The stored string still doesn't parse, so the next run fails on it
+        let replayed = std::panic::catch_unwind(|| {
+            parse_roundtrip(celsius()).with_corpus_capture_in(&directory)
+        });
+        assert!(replayed.is_err());
+        std::fs::remove_dir_all(directory).unwrap();
+
+        let positive = Gen::int_range(0, 50).map(Celsius);
+        assert!(matches!(
+            parse_roundtrip(positive).run(&Config::default()),
+            TestResult::Pass { .. }
+        ));
+    }
+
+    #[test]
+    fn test_comparators_that_are_not_orders_fail() {
+        // Treats values within two of each other as equal
+        let (_, assertion) = failure(comparator_laws(
+            Gen::int_range(0, 20),
+            |a: &i32, b: &i32| {
+                if (a - b).abs() <= 2 {
+                    Ordering::Equal
+                } else {
+                    a.cmp(b)
+                }
+            },
+        ));
+        assert!(assertion.starts_with("Ordering Not Transitive\ncmp("));
+
+        let (_, assertion) = failure(comparator_laws(
+            Gen::int_range(0, 20),
+            |a: &i32, b: &i32| {
+                if a == b {
+                    Ordering::Equal
+                } else {
+                    Ordering::Less
+                }
+            },
+        ));
+        assert!(assertion.starts_with("Ordering Not Antisymmetric\ncmp("));
+    }
+
+    #[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Hash)]
+    struct Version {
+        major: i32,
+        build: i32,
+    }
+
+    // Ignores the build, so differing builds compare equal but aren't `==`
+    #[allow(clippy::derive_ord_xor_partial_ord)]
+    impl Ord for Version {
+        fn cmp(&self, other: &Self) -> Ordering {
+            self.major.cmp(&other.major)
+        }
+    }
+
+    #[test]
+    fn test_ord_must_agree_with_eq() {
+        let versions = Gen::<(i32, i32)>::tuple_of(Gen::int_range(0, 2), Gen::int_range(0, 2))
+            .map(|(major, build)| Version { major, build });
+        let (_, assertion) = failure(ord_laws(versions));
+        assert!(assertion.starts_with("Ordering Disagrees With Eq\ncmp("));
+    }
+
+    // Equal ignoring case, but hashed with case
+    #[allow(clippy::derived_hash_with_manual_eq)]
+    #[derive(Debug, Clone, Hash)]
+    struct Tag(String);
+
+    impl PartialEq for Tag {
+        fn eq(&self, other: &Self) -> bool {
+            self.0.eq_ignore_ascii_case(&other.0)
+        }
+    }
+
+    impl Eq for Tag {}
+
+    #[test]
+    fn test_hash_must_agree_with_eq() {
+        let tags = Gen::<bool>::bool().map(|upper| Tag(if upper { "A" } else { "a" }.to_string()));
+        let (_, assertion) = failure(hash_laws(tags));
+        assert!(assertion.starts_with("Hash Disagrees With Eq\na == b: true\nhash(a): "));
+    }
+}
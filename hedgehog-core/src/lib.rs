@@ -3,21 +3,75 @@
 //! This crate provides the fundamental building blocks for property-based testing
 //! with Hedgehog, including generators, properties, and shrinking.
 
+pub mod bench;
+pub mod cassette;
+pub mod clock;
+pub mod compare;
 pub mod data;
+pub mod default_gen;
+pub mod determinism;
 pub mod error;
+pub mod executor;
+pub mod fixture;
+pub mod fuzz;
 pub mod gen;
+pub mod history;
+pub mod independence;
+#[cfg(all(unix, feature = "isolation"))]
+mod isolation;
+pub mod laws;
+pub mod memo;
+pub mod minimize;
+pub mod observe;
+mod panic;
 pub mod parallel;
+mod pretty;
+pub mod profile;
 pub mod property;
+pub mod regression;
+pub mod rng;
+pub mod runner;
+pub mod shrink_graph;
+pub mod shrinking;
+pub mod size_of;
+pub mod small;
 pub mod state;
 pub mod targeted;
 pub mod tree;
+pub mod validation;
 
 // Re-export the main types
+pub use cassette::{Cassette, CassetteMode};
+pub use clock::{for_all_with_clock, Clock, ClockSchedule, SystemClock, TestClock};
+pub use compare::{for_all_against_model, for_all_compare};
 pub use data::*;
+pub use default_gen::DefaultGen;
+pub use determinism::DETERMINISM_ENV_VAR;
 pub use error::*;
+pub use executor::{for_all_async, TaskSchedule, VirtualRuntime};
+pub use fixture::{for_all_with_fixture, Fixture};
 pub use gen::*;
+pub use history::{RunOutcome, RunRecord, HISTORY_ENV_VAR};
+pub use laws::{
+    associative, commutative, comparator_laws, hash_laws, idempotent, involutive, ord_laws,
+    parse_roundtrip, partial_ord_laws, roundtrip,
+};
+pub use memo::Memo;
+pub use minimize::{Shrink, MINIMIZE_ENV_VAR, MINIMIZE_OUTPUT_ENV_VAR};
 pub use parallel::*;
+pub use profile::{EnvOverrides, Profile, ProfileFile};
 pub use property::*;
+pub use regression::{
+    corpus_entries, corpus_entry_name, store_corpus_entry, RegressionExport, RustLiteral,
+    CORPUS_CHECK_ENV_VAR, DEFAULT_CORPUS_DIR,
+};
+#[cfg(feature = "rand")]
+pub use rng::{rng_from_seed, CaseRng};
+pub use runner::{ConsoleReporter, Reporter, TestRunner};
+pub use shrink_graph::{ShrinkGraph, ShrinkNode, ShrinkOutcome, SHRINK_GRAPH_ENV_VAR};
+pub use shrinking::{assert_shrinks_to, minimal_counterexample, Shrunk};
+pub use size_of::SizeOf;
+pub use small::{SmallValues, SMALL_VALUES_LIMIT};
 pub use state::*;
 pub use targeted::*;
 pub use tree::*;
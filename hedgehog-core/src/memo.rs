@@ -0,0 +1,98 @@
+//! Caching expensive setup derived from generated values.
+//!
+//! Shrinking runs a property on many candidates that share parts of the
+//! original input. When a test builds something costly from its input, such
+//! as a compiled schema or an in-memory database, [`Memo`] builds it once per
+//! distinct key and hands out the cached copy afterwards.
+
+use std::cell::{Cell, RefCell};
+use std::collections::HashMap;
+use std::hash::Hash;
+use std::rc::Rc;
+
+/// A cache of values built from keys, each built at most once.
+///
+/// # Example
+/// ```rust
+/// use hedgehog_core::*;
+///
+/// // Only the schema half of each input is expensive to prepare
+/// let compiled = Memo::new(|schema: &String| schema.to_uppercase());
+/// let gen = Gen::<(String, i32)>::tuple_of(Gen::<String>::ascii_alpha(), Gen::int_range(0, 9));
+/// let prop = for_all(gen, move |(schema, _row)| compiled.get(schema).len() == schema.len());
+/// ```
+pub struct Memo<K, A> {
+    build: Box<dyn Fn(&K) -> A>,
+    cache: RefCell<HashMap<K, Rc<A>>>,
+    builds: Cell<usize>,
+    hits: Cell<usize>,
+}
+
+impl<K, A> Memo<K, A>
+where
+    K: Hash + Eq + Clone,
+{
+    /// Create an empty cache that builds values with `build`.
+    pub fn new<F>(build: F) -> Self
+    where
+        F: Fn(&K) -> A + 'static,
+    {
+        Memo {
+            build: Box::new(build),
+            cache: RefCell::new(HashMap::new()),
+            builds: Cell::new(0),
+            hits: Cell::new(0),
+        }
+    }
+
+    /// The value for `key`, building it if this is the first time it was
+    /// asked for.
+    pub fn get(&self, key: &K) -> Rc<A> {
+        if let Some(value) = self.cache.borrow().get(key) {
+            self.hits.set(self.hits.get() + 1);
+            return Rc::clone(value);
+        }
+
+        let value = Rc::new((self.build)(key));
+        self.builds.set(self.builds.get() + 1);
+        self.cache
+            .borrow_mut()
+            .insert(key.clone(), Rc::clone(&value));
+        value
+    }
+
+    /// Drop every cached value and reset the counts.
+    pub fn clear(&self) {
+        self.cache.borrow_mut().clear();
+        self.builds.set(0);
+        self.hits.set(0);
+    }
+
+    /// Number of values built since the cache was created or cleared.
+    pub fn builds(&self) -> usize {
+        self.builds.get()
+    }
+
+    /// Number of lookups answered from the cache.
+    pub fn hits(&self) -> usize {
+        self.hits.get()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_memo_builds_each_key_once() {
+        let memo = Memo::new(|n: &u32| n * 10);
+        assert_eq!(*memo.get(&1), 10);
+        assert_eq!(*memo.get(&2), 20);
+        assert_eq!(*memo.get(&1), 10);
+        assert_eq!((memo.builds(), memo.hits()), (2, 1));
+
+        memo.clear();
+        assert_eq!(*memo.get(&1), 10);
+        assert_eq!((memo.builds(), memo.hits()), (1, 0));
+    }
+}
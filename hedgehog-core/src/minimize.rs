@@ -0,0 +1,190 @@
+//! Minimizing failing inputs that came from outside a property.
+//!
+//! Inputs from production incidents or fuzzers weren't generated, so they
+//! have no shrink tree. [`Shrink`] proposes smaller values for a value
+//! directly, and [`Property::minimize`](crate::Property::minimize) uses it to
+//! shrink such an input through the property. A test that runs its property
+//! with [`Property::run_minimizable`](crate::Property::run_minimizable)
+//! minimizes the input file named by [`MINIMIZE_ENV_VAR`] instead of
+//! generating, which is how `cargo hedgehog minimize` drives it.
+
+/// Environment variable naming a file holding an input to minimize.
+pub const MINIMIZE_ENV_VAR: &str = "HEDGEHOG_MINIMIZE";
+
+/// Environment variable naming a file to write the minimized input to.
+pub const MINIMIZE_OUTPUT_ENV_VAR: &str = "HEDGEHOG_MINIMIZE_OUTPUT";
+
+/// Values that can propose smaller versions of themselves.
+pub trait Shrink: Sized {
+    /// Smaller values, the most promising first.
+    fn shrinks(&self) -> Vec<Self>;
+}
+
+macro_rules! impl_shrink_int {
+    ($($t:ty),*) => {
+        $(impl Shrink for $t {
+            #[allow(unused_comparisons)]
+            fn shrinks(&self) -> Vec<Self> {
+                let n = *self;
+                if n == 0 {
+                    return Vec::new();
+                }
+                let mut shrinks = vec![0];
+                let half = n / 2;
+                if half != 0 {
+                    shrinks.push(half);
+                }
+                let closer = if n < 0 { n + 1 } else { n - 1 };
+                if closer != 0 && closer != half {
+                    shrinks.push(closer);
+                }
+                shrinks
+            }
+        })*
+    };
+}
+
+impl_shrink_int!(i8, i16, i32, i64, i128, isize, u8, u16, u32, u64, u128, usize);
+
+impl Shrink for f64 {
+    fn shrinks(&self) -> Vec<Self> {
+        if *self == 0.0 {
+            return Vec::new();
+        }
+        let mut shrinks = vec![0.0];
+        if self.is_finite() && self.trunc() != *self {
+            shrinks.push(self.trunc());
+        }
+        shrinks
+    }
+}
+
+impl Shrink for bool {
+    fn shrinks(&self) -> Vec<Self> {
+        if *self {
+            vec![false]
+        } else {
+            Vec::new()
+        }
+    }
+}
+
+impl Shrink for char {
+    fn shrinks(&self) -> Vec<Self> {
+        if *self == 'a' {
+            Vec::new()
+        } else {
+            vec!['a']
+        }
+    }
+}
+
+impl Shrink for String {
+    fn shrinks(&self) -> Vec<Self> {
+        let chars: Vec<char> = self.chars().collect();
+        chars
+            .shrinks()
+            .into_iter()
+            .map(|chars| chars.into_iter().collect())
+            .collect()
+    }
+}
+
+impl<T: Shrink + Clone> Shrink for Vec<T> {
+    /// Removing everything, then halves, then single elements, then
+    /// shrinking single elements.
+    fn shrinks(&self) -> Vec<Self> {
+        if self.is_empty() {
+            return Vec::new();
+        }
+        let mut shrinks = vec![Vec::new()];
+        let half = self.len() / 2;
+        if half > 0 {
+            shrinks.push(self[..half].to_vec());
+            shrinks.push(self[half..].to_vec());
+        }
+        if self.len() > 1 {
+            for i in 0..self.len() {
+                let mut fewer = self.clone();
+                fewer.remove(i);
+                shrinks.push(fewer);
+            }
+        }
+        for (i, element) in self.iter().enumerate() {
+            for smaller in element.shrinks() {
+                let mut values = self.clone();
+                values[i] = smaller;
+                shrinks.push(values);
+            }
+        }
+        shrinks
+    }
+}
+
+impl<T: Shrink> Shrink for Option<T> {
+    fn shrinks(&self) -> Vec<Self> {
+        match self {
+            None => Vec::new(),
+            Some(value) => std::iter::once(None)
+                .chain(value.shrinks().into_iter().map(Some))
+                .collect(),
+        }
+    }
+}
+
+impl<T: Shrink> Shrink for Box<T> {
+    fn shrinks(&self) -> Vec<Self> {
+        self.as_ref().shrinks().into_iter().map(Box::new).collect()
+    }
+}
+
+macro_rules! impl_shrink_tuple {
+    ($($name:ident: $index:tt),+) => {
+        impl<$($name: Shrink + Clone),+> Shrink for ($($name,)+) {
+            /// Shrinking one part at a time, the first part first.
+            fn shrinks(&self) -> Vec<Self> {
+                let mut shrinks = Vec::new();
+                $(
+                    for smaller in self.$index.shrinks() {
+                        let mut tuple = self.clone();
+                        tuple.$index = smaller;
+                        shrinks.push(tuple);
+                    }
+                )+
+                shrinks
+            }
+        }
+    };
+}
+
+impl_shrink_tuple!(A: 0, B: 1);
+impl_shrink_tuple!(A: 0, B: 1, C: 2);
+impl_shrink_tuple!(A: 0, B: 1, C: 2, D: 3);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_shrinks_head_for_the_simplest_value() {
+        assert_eq!(10i32.shrinks(), [0, 5, 9]);
+        assert_eq!((-3i8).shrinks(), [0, -1, -2]);
+        assert_eq!(1u8.shrinks(), [0]);
+        assert!(0u64.shrinks().is_empty());
+        assert_eq!(
+            vec![2u8, 0].shrinks(),
+            [
+                vec![],
+                vec![2],
+                vec![0],
+                vec![0],
+                vec![2],
+                vec![0, 0],
+                vec![1, 0]
+            ]
+        );
+        assert_eq!("ab".to_string().shrinks()[..3], ["", "a", "b"]);
+        assert_eq!(Some(true).shrinks(), [None, Some(false)]);
+        assert_eq!((1u8, true).shrinks(), [(0, true), (1, false)]);
+    }
+}
@@ -0,0 +1,136 @@
+//! Recording which code paths test cases exercised.
+//!
+//! [`Property::classify`](crate::Property::classify) labels cases by their
+//! input, which only says what the input looked like. [`observe!`] labels a
+//! case from inside the test function, at the branch it took, so a property
+//! can show that its inputs reached the code it was written for. Labels are
+//! counted once per case, as classifications, and reported alongside them.
+
+use std::cell::RefCell;
+use std::collections::BTreeSet;
+
+thread_local! {
+    /// Labels observed by the case running on this thread, if one is being
+    /// observed.
+    static OBSERVATIONS: RefCell<Option<BTreeSet<String>>> = const { RefCell::new(None) };
+}
+
+/// Record that the running test case reached this point.
+///
+/// The label is counted in the run's statistics as a classification, once
+/// per case however often it's observed. Outside a property run, and while
+/// shrinking, observations are ignored.
+///
+/// # Example
+/// ```rust
+/// use hedgehog_core::*;
+///
+/// let prop = for_all(Gen::int_range(-10, 10), |&n: &i32| {
+///     if n < 0 {
+///         observe!("negative branch");
+///     } else {
+///         observe!("non-negative branch");
+///     }
+///     n.abs() >= 0
+/// });
+///
+/// match prop.run(&Config::default()) {
+///     TestResult::PassWithStatistics { statistics, .. } => {
+///         assert!(statistics.classifications.contains_key("non-negative branch"));
+///     }
+///     result => panic!("expected statistics, got {result:?}"),
+/// }
+/// ```
+#[macro_export]
+macro_rules! observe {
+    ($label:expr) => {
+        $crate::observe::record($label)
+    };
+}
+
+/// Record an observation for the running case; see [`observe!`].
+#[doc(hidden)]
+pub fn record<S: AsRef<str>>(label: S) {
+    OBSERVATIONS.with(|observations| {
+        if let Some(labels) = observations.borrow_mut().as_mut() {
+            if !labels.contains(label.as_ref()) {
+                labels.insert(label.as_ref().to_string());
+            }
+        }
+    });
+}
+
+/// Run `f`, returning the labels observed while it ran.
+///
+/// Observations made by a property run inside `f` are its own; the labels
+/// being observed before `f` are put back afterwards, even if it panics.
+pub(crate) fn capture<R>(f: impl FnOnce() -> R) -> (R, BTreeSet<String>) {
+    struct Restore(Option<BTreeSet<String>>);
+
+    impl Drop for Restore {
+        fn drop(&mut self) {
+            let previous = self.0.take();
+            OBSERVATIONS.with(|observations| *observations.borrow_mut() = previous);
+        }
+    }
+
+    let restore = Restore(
+        OBSERVATIONS.with(|observations| observations.borrow_mut().replace(BTreeSet::new())),
+    );
+    let result = f();
+    let labels = OBSERVATIONS
+        .with(|observations| observations.borrow_mut().take())
+        .unwrap_or_default();
+    drop(restore);
+    (result, labels)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data::Config;
+    use crate::error::TestResult;
+    use crate::gen::Gen;
+    use crate::property::for_all;
+
+    #[test]
+    fn test_capture_counts_each_label_once_and_nests() {
+        let (_, outer) = capture(|| {
+            record("outer");
+            let (_, inner) = capture(|| {
+                record("inner");
+                record("inner");
+            });
+            assert_eq!(inner.into_iter().collect::<Vec<_>>(), vec!["inner"]);
+            record(String::from("outer again"));
+        });
+        assert_eq!(
+            outer.into_iter().collect::<Vec<_>>(),
+            vec!["outer", "outer again"]
+        );
+
+        // Nothing is being observed outside a capture
+        record("ignored");
+        assert_eq!(capture(|| ()).1.len(), 0);
+    }
+
+    #[test]
+    fn test_observations_are_counted_per_case() {
+        let prop = for_all(Gen::int_range(0, 9), |&n: &i32| {
+            observe!("every case");
+            observe!("every case");
+            if n == 100 {
+                observe!("unreachable");
+            }
+            true
+        });
+
+        match prop.run(&Config::default().with_tests(50)) {
+            TestResult::PassWithStatistics { statistics, .. } => {
+                assert_eq!(statistics.classifications.get("every case"), Some(&50));
+                assert!(!statistics.classifications.contains_key("unreachable"));
+            }
+            result => panic!("Expected PassWithStatistics, got: {result:?}"),
+        }
+    }
+}
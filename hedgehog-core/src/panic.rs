@@ -0,0 +1,190 @@
+//! Catching panics raised by test functions.
+//!
+//! Properties that expect or forbid panics run the test function under
+//! `catch_unwind`. The default panic hook would still print every caught
+//! panic, so while a test function runs here the hook stays quiet on that
+//! thread; panics on other threads are reported as usual.
+//!
+//! The hook notes where each silenced panic was raised, and its backtrace
+//! when [`PanicBacktraces`] asks for one, so [`catch_panic`] can report them
+//! with the message.
+//!
+//! Generators can't return errors, so one that can't produce a value unwinds
+//! with a [`HedgehogError`] through [`raise`], which [`catch_error`] turns
+//! back into a result. The unwind skips the panic hook, so nothing is
+//! printed, and only goes as far as the outermost generator being run.
+
+use crate::data::PanicBacktraces;
+use crate::error::{HedgehogError, PanicReport};
+use std::any::Any;
+use std::backtrace::{Backtrace, BacktraceStatus};
+use std::cell::{Cell, RefCell};
+use std::panic::{self, AssertUnwindSafe};
+use std::sync::Once;
+
+thread_local! {
+    static SILENCED: Cell<bool> = const { Cell::new(false) };
+    static GENERATING: Cell<bool> = const { Cell::new(false) };
+    static BACKTRACES: Cell<PanicBacktraces> = const { Cell::new(PanicBacktraces::FromEnv) };
+    /// The location and backtrace of the last silenced panic.
+    static LAST_PANIC: RefCell<(Option<String>, Option<String>)> =
+        const { RefCell::new((None, None)) };
+}
+
+/// Which panics caught on this thread keep a backtrace; restores the
+/// previous choice when dropped.
+pub(crate) struct Backtraces(PanicBacktraces);
+
+impl Backtraces {
+    /// Capture backtraces as `backtraces` says until the returned guard is
+    /// dropped.
+    pub(crate) fn enter(backtraces: PanicBacktraces) -> Self {
+        Backtraces(BACKTRACES.with(|current| current.replace(backtraces)))
+    }
+}
+
+impl Drop for Backtraces {
+    fn drop(&mut self) {
+        BACKTRACES.with(|current| current.set(self.0));
+    }
+}
+
+/// The backtrace at the current panic, if this thread keeps them.
+fn capture_backtrace() -> Option<String> {
+    let backtrace = match BACKTRACES.with(Cell::get) {
+        PanicBacktraces::Never => return None,
+        PanicBacktraces::Always => Backtrace::force_capture(),
+        PanicBacktraces::FromEnv => Backtrace::capture(),
+    };
+    (backtrace.status() == BacktraceStatus::Captured).then(|| backtrace.to_string())
+}
+
+static INSTALL_HOOK: Once = Once::new();
+
+/// Wrap the current panic hook so that it skips threads inside [`catch`].
+fn install_hook() {
+    INSTALL_HOOK.call_once(|| {
+        let previous = panic::take_hook();
+        panic::set_hook(Box::new(move |info| {
+            if SILENCED.with(Cell::get) {
+                let location = info.location().map(ToString::to_string);
+                LAST_PANIC.with(|last| *last.borrow_mut() = (location, capture_backtrace()));
+                return;
+            }
+            previous(info);
+        }));
+    });
+}
+
+/// The payload [`raise`] unwinds with, so other panics aren't mistaken for
+/// generation errors.
+struct GenerationError(HedgehogError);
+
+/// Whether a generator is running under [`catch_error`] on this thread.
+pub(crate) fn generating() -> bool {
+    GENERATING.with(Cell::get)
+}
+
+/// Unwind with `error` to the enclosing [`catch_error`], for code that can't
+/// return it, such as generators.
+pub(crate) fn raise(error: HedgehogError) -> ! {
+    debug_assert!(
+        generating(),
+        "generation errors are raised under catch_error"
+    );
+    panic::resume_unwind(Box::new(GenerationError(error)))
+}
+
+/// Run `f`, returning the error it [raised](raise) if it did. Other panics
+/// carry on unwinding.
+pub(crate) fn catch_error<R>(f: impl FnOnce() -> R) -> Result<R, HedgehogError> {
+    let was_generating = GENERATING.with(|generating| generating.replace(true));
+    let result = panic::catch_unwind(AssertUnwindSafe(f));
+    GENERATING.with(|generating| generating.set(was_generating));
+    result.map_err(|payload| match payload.downcast::<GenerationError>() {
+        Ok(error) => error.0,
+        Err(payload) => panic::resume_unwind(payload),
+    })
+}
+
+/// Run `f`, returning the panic message if it panics.
+pub(crate) fn catch<R>(f: impl FnOnce() -> R) -> Result<R, String> {
+    catch_panic(f).map_err(|report| report.message)
+}
+
+/// Run `f`, returning the panic with where it was raised, and its backtrace
+/// if this thread keeps them, if it panics.
+pub(crate) fn catch_panic<R>(f: impl FnOnce() -> R) -> Result<R, PanicReport> {
+    install_hook();
+    let was_silenced = SILENCED.with(|silenced| silenced.replace(true));
+    let result = panic::catch_unwind(AssertUnwindSafe(f));
+    SILENCED.with(|silenced| silenced.set(was_silenced));
+    result.map_err(|payload| {
+        let (location, backtrace) = LAST_PANIC.with(|last| last.take());
+        PanicReport {
+            message: message(payload.as_ref()),
+            location,
+            backtrace,
+        }
+    })
+}
+
+/// The message of a panic payload, as given to `panic!`.
+pub(crate) fn message(payload: &(dyn Any + Send)) -> String {
+    if let Some(message) = payload.downcast_ref::<&str>() {
+        message.to_string()
+    } else if let Some(message) = payload.downcast_ref::<String>() {
+        message.clone()
+    } else {
+        "<non-string panic payload>".to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_catch_returns_value_or_message() {
+        assert_eq!(catch(|| 1 + 1), Ok(2));
+        assert_eq!(catch(|| panic!("plain")), Err::<(), _>("plain".to_string()));
+        assert_eq!(
+            catch(|| panic!("formatted {}", 42)),
+            Err::<(), _>("formatted 42".to_string())
+        );
+        assert_eq!(
+            catch(|| std::panic::panic_any(7)),
+            Err::<(), _>("<non-string panic payload>".to_string())
+        );
+    }
+
+    #[test]
+    fn test_catch_panic_reports_location_and_backtrace() {
+        let _backtraces = Backtraces::enter(PanicBacktraces::Always);
+        let line = line!() + 1;
+        let report = catch_panic(|| panic!("deep")).unwrap_err();
+        assert_eq!(report.message, "deep");
+        assert!(report
+            .location
+            .is_some_and(|location| location.contains(&format!("panic.rs:{line}:"))));
+        assert!(report.backtrace.is_some());
+
+        let _backtraces = Backtraces::enter(PanicBacktraces::Never);
+        let report = catch_panic(|| panic!("shallow")).unwrap_err();
+        assert_eq!(report.backtrace, None);
+    }
+
+    #[test]
+    fn test_catch_error_returns_raised_errors_only() {
+        let raised = catch_error(|| raise(HedgehogError::FilterTooRestrictive { limit: 3 }));
+        assert!(matches!(
+            raised,
+            Err(HedgehogError::FilterTooRestrictive { limit: 3 })
+        ));
+        assert!(!generating());
+        assert_eq!(catch_error(|| 1 + 1).ok(), Some(2));
+
+        let other = panic::catch_unwind(|| catch_error(|| panic!("not an error")));
+        assert!(other.is_err());
+    }
+}
@@ -5,10 +5,26 @@
 //! 2. Concurrent system testing - detect race conditions and test thread safety
 
 use crate::{data::*, error::*, gen::*};
-use std::sync::Arc;
+use std::sync::{mpsc, Arc};
 use std::thread;
 use std::time::{Duration, Instant};
 
+pub mod chaos;
+pub mod histogram;
+pub mod locks;
+pub mod memory;
+
+pub use chaos::{FaultInjector, FaultKind, InjectedFault, InjectedIoError};
+pub use histogram::LatencyHistogram;
+pub use locks::{
+    LockCycle, LockGraph, LockId, TrackedMutex, TrackedMutexGuard, TrackedReadGuard, TrackedRwLock,
+    TrackedWriteGuard,
+};
+pub use memory::{MemorySampler, MemoryUsage};
+
+/// How often resident memory is sampled while parallel and load tests run.
+const MEMORY_SAMPLE_INTERVAL: Duration = Duration::from_millis(10);
+
 /// Configuration for parallel property testing.
 #[derive(Debug, Clone)]
 pub struct ParallelConfig {
@@ -20,6 +36,8 @@ pub struct ParallelConfig {
     pub timeout: Option<Duration>,
     /// Whether to detect non-deterministic behavior
     pub detect_non_determinism: bool,
+    /// What runs the distributed work
+    pub executor: Executor,
 }
 
 impl Default for ParallelConfig {
@@ -29,10 +47,31 @@ impl Default for ParallelConfig {
             work_distribution: WorkDistribution::RoundRobin,
             timeout: Some(Duration::from_secs(10)),
             detect_non_determinism: true,
+            executor: Executor::Threads,
         }
     }
 }
 
+impl ParallelConfig {
+    /// Set the executor used to run the distributed work.
+    pub fn executor(mut self, executor: Executor) -> Self {
+        self.executor = executor;
+        self
+    }
+}
+
+/// Executors for running parallel property tests.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Executor {
+    /// Spawn a fresh OS thread for each unit of work on every run
+    #[default]
+    Threads,
+    /// Run each unit of work as a task on rayon's global thread pool,
+    /// avoiding thread start-up cost when many fast runs are made
+    #[cfg(feature = "rayon")]
+    Rayon,
+}
+
 /// Strategies for distributing work across threads.
 #[derive(Debug, Clone, PartialEq)]
 pub enum WorkDistribution {
@@ -59,6 +98,8 @@ pub struct ConcurrentTestResult {
     pub deadlock_info: Option<DeadlockInfo>,
     /// Whether timeout was detected
     pub timeout_detected: bool,
+    /// Faults injected while testing this input, if a fault injector was used
+    pub injected_faults: Vec<InjectedFault>,
 }
 
 /// Result of parallel property testing.
@@ -85,6 +126,8 @@ pub struct ParallelPerformanceMetrics {
     pub speedup_factor: f64,
     /// Thread utilization efficiency
     pub thread_efficiency: f64,
+    /// Resident memory observed during the run, where the platform supports it
+    pub memory_usage: Option<MemoryUsage>,
 }
 
 /// Issues detected during concurrent testing.
@@ -113,6 +156,8 @@ pub struct DeadlockInfo {
     pub timeout_duration: Duration,
     /// Timestamp when deadlock was detected
     pub detected_at: std::time::SystemTime,
+    /// Lock-order cycles recorded by tracked locks, if a lock graph was used
+    pub lock_cycles: Vec<LockCycle>,
 }
 
 /// Error types for thread joining operations.
@@ -393,6 +438,10 @@ where
     pub timeout: Option<Duration>,
     /// Variable name for debugging
     pub variable_name: Option<String>,
+    /// Lock graph shared with the tracked locks used by the test function
+    pub lock_graph: Option<LockGraph>,
+    /// Fault injector used to perturb the test threads
+    pub fault_injector: Option<FaultInjector>,
 }
 /// A property that can be executed in parallel.
 pub struct ParallelProperty<T, F>
@@ -407,6 +456,46 @@ where
     pub config: ParallelConfig,
     /// Variable name for debugging
     pub variable_name: Option<String>,
+    /// Classifications and collections applied on the worker threads
+    collectors: StatisticsCollectors<T>,
+}
+
+type SharedClassificationFn<T> = Arc<dyn Fn(&T) -> bool + Send + Sync>;
+type SharedCollectionFn<T> = Arc<dyn Fn(&T) -> f64 + Send + Sync>;
+
+/// Classifications and collections recorded for each input of a parallel run.
+struct StatisticsCollectors<T> {
+    classifications: Vec<(String, SharedClassificationFn<T>)>,
+    collections: Vec<(String, SharedCollectionFn<T>)>,
+}
+
+impl<T> StatisticsCollectors<T> {
+    fn new() -> Self {
+        StatisticsCollectors {
+            classifications: Vec::new(),
+            collections: Vec::new(),
+        }
+    }
+
+    fn record(&self, value: &T, statistics: &mut crate::property::TestStatistics) {
+        for (name, predicate) in &self.classifications {
+            if predicate(value) {
+                statistics.record_classification(name);
+            }
+        }
+        for (name, extractor) in &self.collections {
+            statistics.record_collection(name, extractor(value));
+        }
+    }
+}
+
+impl<T> Clone for StatisticsCollectors<T> {
+    fn clone(&self) -> Self {
+        StatisticsCollectors {
+            classifications: self.classifications.clone(),
+            collections: self.collections.clone(),
+        }
+    }
 }
 
 impl<T, F> ConcurrentProperty<T, F>
@@ -422,6 +511,8 @@ where
             thread_count,
             timeout: Some(Duration::from_secs(10)),
             variable_name: None,
+            lock_graph: None,
+            fault_injector: None,
         }
     }
 
@@ -437,6 +528,26 @@ where
         self
     }
 
+    /// Report lock-order cycles recorded in `graph` as deadlocks.
+    ///
+    /// The test function should create its locks from the same graph with
+    /// [`LockGraph::mutex`] or [`LockGraph::rwlock`].
+    pub fn with_lock_graph(mut self, graph: LockGraph) -> Self {
+        self.lock_graph = Some(graph);
+        self
+    }
+
+    /// Perturb the test threads with seeded fault injection.
+    ///
+    /// Each thread is bound to a decision stream derived from the test number
+    /// and its index, and passes an injection point named `thread_start`
+    /// before calling the test function. The test function can add its own
+    /// points through a clone of the same injector.
+    pub fn with_fault_injector(mut self, injector: FaultInjector) -> Self {
+        self.fault_injector = Some(injector);
+        self
+    }
+
     /// Run concurrent tests on generated inputs to detect non-deterministic behavior.
     pub fn run(&self, test_config: &Config) -> Vec<ConcurrentTestResult> {
         let mut results = Vec::new();
@@ -453,7 +564,7 @@ where
             let input = tree.value;
 
             // Test this input concurrently from multiple threads
-            let concurrent_result = self.test_input_concurrently(&input);
+            let concurrent_result = self.test_input_concurrently(i, &input);
             results.push(concurrent_result);
         }
 
@@ -461,12 +572,16 @@ where
     }
 
     /// Test a single input from multiple threads simultaneously to detect race conditions.
-    fn test_input_concurrently(&self, input: &T) -> ConcurrentTestResult {
-        self.test_input_concurrently_with_deadlock_detection(input)
+    fn test_input_concurrently(&self, test_index: usize, input: &T) -> ConcurrentTestResult {
+        self.test_input_concurrently_with_deadlock_detection(test_index, input)
     }
 
     /// Enhanced concurrent testing with deadlock detection.
-    fn test_input_concurrently_with_deadlock_detection(&self, input: &T) -> ConcurrentTestResult {
+    fn test_input_concurrently_with_deadlock_detection(
+        &self,
+        test_index: usize,
+        input: &T,
+    ) -> ConcurrentTestResult {
         let mut thread_handles = Vec::new();
         let timeout_duration = self.timeout.unwrap_or(Duration::from_secs(10));
         let test_start = Instant::now();
@@ -475,15 +590,24 @@ where
         for thread_id in 0..self.thread_count {
             let input_clone = input.clone();
             let test_function = Arc::clone(&self.test_function);
+            let fault_injector = self.fault_injector.clone();
 
+            // Each thread signals completion over its own channel. If the
+            // thread panics the sender is dropped without sending, which the
+            // receiver observes as a disconnect.
+            let (sender, receiver) = mpsc::channel();
             let handle = thread::spawn(move || {
+                if let Some(injector) = &fault_injector {
+                    injector.bind_thread(test_index, thread_id);
+                    injector.point("thread_start");
+                }
                 let thread_start = Instant::now();
                 let result = test_function(&input_clone);
                 let thread_duration = thread_start.elapsed();
-                (thread_id, result, thread_duration)
+                let _ = sender.send((thread_id, result, thread_duration));
             });
 
-            thread_handles.push(handle);
+            thread_handles.push((handle, receiver));
         }
 
         // Collect results from all threads with timeout detection
@@ -493,7 +617,7 @@ where
         let mut timeout_detected = false;
         let mut hanging_threads = Vec::new();
 
-        for (idx, handle) in thread_handles.into_iter().enumerate() {
+        for (idx, (handle, receiver)) in thread_handles.into_iter().enumerate() {
             // Check if we've already exceeded our timeout
             let elapsed = test_start.elapsed();
             if elapsed > timeout_duration {
@@ -511,6 +635,7 @@ where
                     module_path: None,
                     assertion_type: Some("Deadlock/Timeout".to_string()),
                     shrink_steps: Vec::new(),
+                    details: FailureDetails::default(),
                 });
                 execution_times.push(timeout_duration);
                 race_conditions_detected += 1;
@@ -519,7 +644,7 @@ where
 
             // Try to join with remaining timeout
             let remaining_timeout = timeout_duration - elapsed;
-            let join_result = self.join_with_timeout(handle, remaining_timeout);
+            let join_result = self.join_with_timeout(handle, receiver, remaining_timeout);
 
             match join_result {
                 Ok((_thread_id, result, duration)) => {
@@ -540,6 +665,7 @@ where
                         module_path: None,
                         assertion_type: Some("Deadlock/Timeout".to_string()),
                         shrink_steps: Vec::new(),
+                        details: FailureDetails::default(),
                     });
                     execution_times.push(timeout_duration);
                     race_conditions_detected += 1;
@@ -554,6 +680,7 @@ where
                         module_path: None,
                         assertion_type: Some("Thread Panic".to_string()),
                         shrink_steps: Vec::new(),
+                        details: FailureDetails::default(),
                     });
                     execution_times.push(Duration::from_secs(0));
                     race_conditions_detected += 1;
@@ -576,13 +703,22 @@ where
             race_conditions_detected += hanging_threads.len();
         }
 
-        // Generate deadlock info if timeout was detected
-        let deadlock_info = if timeout_detected {
+        // Lock-order cycles are definitive, unlike timeouts
+        let lock_cycles = self
+            .lock_graph
+            .as_ref()
+            .map(LockGraph::take_cycles)
+            .unwrap_or_default();
+        race_conditions_detected += lock_cycles.len();
+
+        // Generate deadlock info if a timeout or lock cycle was detected
+        let deadlock_info = if timeout_detected || !lock_cycles.is_empty() {
             Some(DeadlockInfo {
                 input: format!("{input:?}"),
                 threads_involved: hanging_threads,
                 timeout_duration,
                 detected_at: std::time::SystemTime::now(),
+                lock_cycles,
             })
         } else {
             None
@@ -594,36 +730,43 @@ where
             execution_times,
             deadlock_info,
             timeout_detected,
+            injected_faults: self
+                .fault_injector
+                .as_ref()
+                .map(FaultInjector::take_faults)
+                .unwrap_or_default(),
         }
     }
 
     /// Join a thread handle with timeout support.
+    ///
+    /// Blocks on the thread's completion channel until it reports a result
+    /// or the timeout elapses. Threads that time out are detached rather
+    /// than joined, so a hanging test cannot stall the runner.
     fn join_with_timeout(
         &self,
-        handle: thread::JoinHandle<(usize, TestResult, Duration)>,
+        handle: thread::JoinHandle<()>,
+        receiver: mpsc::Receiver<(usize, TestResult, Duration)>,
         timeout: Duration,
     ) -> std::result::Result<(usize, TestResult, Duration), JoinError> {
-        // Rust's JoinHandle doesn't have built-in timeout, so we simulate it
-        // In a production implementation, you'd want to use a more sophisticated approach
-        // For now, we'll use a simple busy-wait approach
-        let start = Instant::now();
-        let mut handle = Some(handle);
-
-        while start.elapsed() < timeout {
-            if let Some(h) = &handle {
-                if h.is_finished() {
-                    match handle.take().unwrap().join() {
-                        Ok(result) => return Ok(result),
-                        Err(_) => return Err(JoinError::Panic),
-                    }
-                }
+        match receiver.recv_timeout(timeout) {
+            Ok(result) => {
+                // The thread has already produced its result; joining only
+                // reaps it.
+                let _ = handle.join();
+                Ok(result)
+            }
+            Err(mpsc::RecvTimeoutError::Timeout) => {
+                drop(handle);
+                Err(JoinError::Timeout)
+            }
+            Err(mpsc::RecvTimeoutError::Disconnected) => {
+                let _ = handle.join();
+                Err(JoinError::Panic)
             }
-            thread::sleep(Duration::from_millis(10)); // Small delay to avoid busy-waiting
         }
-
-        // If we get here, we timed out
-        Err(JoinError::Timeout)
     }
+
     /// Analyze thread results to determine if they are deterministic.
     fn analyze_determinism(&self, results: &[TestResult]) -> bool {
         if results.is_empty() {
@@ -681,6 +824,7 @@ where
             test_function: Arc::new(test_function),
             config,
             variable_name: None,
+            collectors: StatisticsCollectors::new(),
         }
     }
 
@@ -690,9 +834,32 @@ where
         self
     }
 
+    /// Add a classification to categorize test inputs.
+    pub fn classify<P>(mut self, name: &str, predicate: P) -> Self
+    where
+        P: Fn(&T) -> bool + Send + Sync + 'static,
+    {
+        self.collectors
+            .classifications
+            .push((name.to_string(), Arc::new(predicate)));
+        self
+    }
+
+    /// Add a collection to gather numerical statistics from test inputs.
+    pub fn collect<E>(mut self, name: &str, extractor: E) -> Self
+    where
+        E: Fn(&T) -> f64 + Send + Sync + 'static,
+    {
+        self.collectors
+            .collections
+            .push((name.to_string(), Arc::new(extractor)));
+        self
+    }
+
     /// Run the property tests in parallel across multiple threads.
     pub fn run(&self, test_config: &Config) -> ParallelTestResult {
         let start_time = Instant::now();
+        let memory_sampler = MemorySampler::start(MEMORY_SAMPLE_INTERVAL);
 
         // Pre-generate all test inputs to avoid Send/Sync issues with Gen<T>
         let total_tests = test_config.test_limit;
@@ -712,37 +879,26 @@ where
         let threads = self.config.thread_count;
         let work_items = self.distribute_work(total_tests, threads);
 
-        let mut thread_handles = Vec::new();
-        let mut input_start = 0;
-
-        // Spawn worker threads
+        // Split the pre-generated inputs into one unit of work per thread
+        let mut work = Vec::with_capacity(work_items.len());
+        let mut remaining_inputs = test_inputs.into_iter();
         for (thread_id, test_count) in work_items.into_iter().enumerate() {
-            let thread_inputs = test_inputs[input_start..input_start + test_count].to_vec();
-            input_start += test_count;
-
-            let test_function = Arc::clone(&self.test_function);
-            let timeout = self.config.timeout;
-            let variable_name = self.variable_name.clone();
-
-            let handle = thread::spawn(move || {
-                Self::run_thread_tests_with_inputs(
-                    thread_id,
-                    thread_inputs,
-                    test_function,
-                    timeout,
-                    variable_name,
-                )
-            });
-
-            thread_handles.push(handle);
+            let thread_inputs: Vec<T> = remaining_inputs.by_ref().take(test_count).collect();
+            work.push((thread_id, thread_inputs));
         }
 
+        let joined = match self.config.executor {
+            Executor::Threads => self.run_on_threads(work),
+            #[cfg(feature = "rayon")]
+            Executor::Rayon => self.run_on_rayon(work),
+        };
+
         // Collect results from all threads
         let mut thread_results = Vec::new();
         let mut concurrency_issues = ConcurrencyIssues::default();
 
-        for handle in thread_handles {
-            match handle.join() {
+        for joined_result in joined {
+            match joined_result {
                 Ok(result) => {
                     thread_results.push(result.clone());
                     // Analyze for concurrency issues
@@ -760,8 +916,9 @@ where
 
         // Aggregate results and compute metrics
         let outcome = Self::aggregate_results(&thread_results);
-        let performance =
+        let mut performance =
             Self::calculate_performance_metrics(total_duration, &thread_results, threads);
+        performance.memory_usage = memory_sampler.stop();
 
         ParallelTestResult {
             outcome,
@@ -771,6 +928,62 @@ where
         }
     }
 
+    /// Run each unit of work on its own spawned thread.
+    fn run_on_threads(&self, work: Vec<(usize, Vec<T>)>) -> Vec<thread::Result<TestResult>> {
+        let collectors = Arc::new(self.collectors.clone());
+        let handles: Vec<_> = work
+            .into_iter()
+            .map(|(thread_id, thread_inputs)| {
+                let test_function = Arc::clone(&self.test_function);
+                let collectors = Arc::clone(&collectors);
+                let timeout = self.config.timeout;
+                let variable_name = self.variable_name.clone();
+
+                thread::spawn(move || {
+                    Self::run_thread_tests_with_inputs(
+                        thread_id,
+                        thread_inputs,
+                        test_function,
+                        &collectors,
+                        timeout,
+                        variable_name,
+                    )
+                })
+            })
+            .collect();
+
+        handles.into_iter().map(|handle| handle.join()).collect()
+    }
+
+    /// Run each unit of work as a task on rayon's global thread pool.
+    #[cfg(feature = "rayon")]
+    fn run_on_rayon(&self, work: Vec<(usize, Vec<T>)>) -> Vec<thread::Result<TestResult>> {
+        use rayon::prelude::*;
+
+        // Capture only what the tasks need; the generator is not `Sync`.
+        let test_function = &self.test_function;
+        let collectors = &self.collectors;
+        let timeout = self.config.timeout;
+        let variable_name = &self.variable_name;
+
+        work.into_par_iter()
+            .map(|(thread_id, thread_inputs)| {
+                // Rayon re-raises task panics in the caller; catch them so
+                // they are reported like a panicked thread instead.
+                std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+                    Self::run_thread_tests_with_inputs(
+                        thread_id,
+                        thread_inputs,
+                        Arc::clone(test_function),
+                        collectors,
+                        timeout,
+                        variable_name.clone(),
+                    )
+                }))
+            })
+            .collect()
+    }
+
     /// Distribute work across threads based on the configured strategy.
     fn distribute_work(&self, total_tests: usize, thread_count: usize) -> Vec<usize> {
         match self.config.work_distribution {
@@ -813,49 +1026,83 @@ where
         _thread_id: usize,
         test_inputs: Vec<T>,
         test_function: Arc<F>,
+        collectors: &StatisticsCollectors<T>,
         _timeout: Option<Duration>,
         _variable_name: Option<String>,
     ) -> TestResult {
         let mut tests_run = 0;
+        let mut statistics = crate::property::TestStatistics::new();
 
         for input in test_inputs {
-            tests_run += 1;
             match test_function(&input) {
-                TestResult::Pass { .. } => continue,
-                result @ TestResult::Fail { .. } => {
+                TestResult::Pass { .. } => {
+                    tests_run += 1;
+                    collectors.record(&input, &mut statistics);
+                }
+                TestResult::PassWithStatistics {
+                    statistics: case_statistics,
+                    ..
+                } => {
+                    tests_run += 1;
+                    collectors.record(&input, &mut statistics);
+                    statistics.merge(&case_statistics);
+                }
+                TestResult::Discard { .. } => {
+                    statistics.discards += 1;
+                }
+                TestResult::Fail {
+                    counterexample,
+                    shrinks_performed,
+                    property_name,
+                    module_path,
+                    assertion_type,
+                    shrink_steps,
+                    ..
+                } => {
                     // Return the failure result with updated test count
-                    match result {
-                        TestResult::Fail {
-                            counterexample,
-                            shrinks_performed,
-                            property_name,
-                            module_path,
-                            assertion_type,
-                            shrink_steps,
-                            ..
-                        } => {
-                            return TestResult::Fail {
-                                counterexample,
-                                tests_run,
-                                shrinks_performed,
-                                property_name,
-                                module_path,
-                                assertion_type,
-                                shrink_steps,
-                            };
-                        }
-                        _ => unreachable!(),
-                    }
+                    return TestResult::Fail {
+                        counterexample,
+                        tests_run: tests_run + 1,
+                        shrinks_performed,
+                        property_name,
+                        module_path,
+                        assertion_type,
+                        shrink_steps,
+                        details: FailureDetails::default(),
+                    };
                 }
-                other => return other,
             }
         }
 
-        // All tests passed
-        TestResult::Pass {
-            tests_run,
-            property_name: None,
-            module_path: None,
+        Self::finish_statistics(tests_run, statistics)
+    }
+
+    /// Build the passing result for a run, keeping statistics if any were gathered.
+    fn finish_statistics(
+        tests_run: usize,
+        mut statistics: crate::property::TestStatistics,
+    ) -> TestResult {
+        statistics.total_tests = tests_run;
+
+        if tests_run == 0 && statistics.discards > 0 {
+            TestResult::Discard {
+                limit: statistics.discards,
+                property_name: None,
+                module_path: None,
+            }
+        } else if statistics.is_empty() {
+            TestResult::Pass {
+                tests_run,
+                property_name: None,
+                module_path: None,
+            }
+        } else {
+            TestResult::PassWithStatistics {
+                tests_run,
+                property_name: None,
+                module_path: None,
+                statistics,
+            }
         }
     }
 
@@ -877,21 +1124,26 @@ where
             }
         }
 
-        // If all threads passed, aggregate the success
-        let total_tests: usize = thread_results
-            .iter()
-            .map(|r| match r {
-                TestResult::Pass { tests_run, .. } => *tests_run,
-                TestResult::PassWithStatistics { tests_run, .. } => *tests_run,
-                _ => 0,
-            })
-            .sum();
-
-        TestResult::Pass {
-            tests_run: total_tests,
-            property_name: None,
-            module_path: None,
+        // If all threads passed, aggregate the success and its statistics
+        let mut total_tests = 0;
+        let mut statistics = crate::property::TestStatistics::new();
+        for result in thread_results {
+            match result {
+                TestResult::Pass { tests_run, .. } => total_tests += tests_run,
+                TestResult::PassWithStatistics {
+                    tests_run,
+                    statistics: thread_statistics,
+                    ..
+                } => {
+                    total_tests += tests_run;
+                    statistics.merge(thread_statistics);
+                }
+                TestResult::Discard { limit, .. } => statistics.discards += limit,
+                TestResult::Fail { .. } => unreachable!(),
+            }
         }
+
+        Self::finish_statistics(total_tests, statistics)
     }
 
     /// Calculate performance metrics from parallel execution.
@@ -918,6 +1170,7 @@ where
             total_cpu_time: estimated_sequential_time,
             speedup_factor,
             thread_efficiency: speedup_factor / thread_count as f64,
+            memory_usage: None,
         }
     }
 }
@@ -946,15 +1199,7 @@ where
                     module_path: None,
                 }
             } else {
-                TestResult::Fail {
-                    counterexample: format!("{input:?}"),
-                    tests_run: 0,
-                    shrinks_performed: 0,
-                    property_name: None,
-                    module_path: None,
-                    assertion_type: Some("Boolean Condition".to_string()),
-                    shrink_steps: Vec::new(),
-                }
+                TestResult::failure(format!("{input:?}"), "Boolean Condition")
             }
         },
         config,
@@ -1005,6 +1250,7 @@ where
                     module_path: None,
                     assertion_type: Some("Boolean Condition".to_string()),
                     shrink_steps: Vec::new(),
+                    details: FailureDetails::default(),
                 }
             }
         },
@@ -1167,6 +1413,7 @@ where
                             module_path: None,
                             assertion_type: Some("Race Condition".to_string()),
                             shrink_steps: Vec::new(),
+                            details: FailureDetails::default(),
                         }),
                     threads_involved: (0..self.operation_count).collect(),
                 };
@@ -1215,6 +1462,7 @@ where
                     module_path: None,
                     assertion_type: Some("Thread Panic".to_string()),
                     shrink_steps: Vec::new(),
+                    details: FailureDetails::default(),
                 }),
             }
         }
@@ -1285,6 +1533,8 @@ pub struct LoadTestConfig {
     pub cool_down_duration: Duration,
     /// Whether to collect detailed timing statistics
     pub collect_stats: bool,
+    /// How operations are scheduled
+    pub arrival: ArrivalModel,
 }
 
 impl Default for LoadTestConfig {
@@ -1296,7 +1546,86 @@ impl Default for LoadTestConfig {
             ramp_up_duration: Duration::from_secs(2),
             cool_down_duration: Duration::from_secs(1),
             collect_stats: true,
+            arrival: ArrivalModel::Closed,
+        }
+    }
+}
+
+/// How load test operations are scheduled.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum ArrivalModel {
+    /// Each thread starts its next operation as soon as the previous one
+    /// finishes, limited per thread by `ops_per_second` if set
+    #[default]
+    Closed,
+    /// Operations arrive at a fixed total rate regardless of how long earlier
+    /// operations take
+    ConstantRate {
+        /// Total arrivals per second across all threads
+        ops_per_second: f64,
+    },
+    /// Operations arrive as a Poisson process with the given mean rate
+    Poisson {
+        /// Mean arrivals per second across all threads
+        ops_per_second: f64,
+    },
+}
+
+/// Shared schedule of intended start times for open-model load tests.
+///
+/// Threads take arrivals in order; an operation's response time is measured
+/// from its intended start, so time spent queued behind slow operations is
+/// counted rather than omitted.
+struct ArrivalSchedule {
+    model: ArrivalModel,
+    duration: Duration,
+    state: std::sync::Mutex<(crate::data::Seed, Duration)>,
+}
+
+impl ArrivalSchedule {
+    fn new(model: ArrivalModel, duration: Duration, seed: crate::data::Seed) -> Option<Self> {
+        match model {
+            ArrivalModel::Closed => None,
+            ArrivalModel::ConstantRate { ops_per_second }
+            | ArrivalModel::Poisson { ops_per_second } => {
+                if ops_per_second > 0.0 {
+                    Some(ArrivalSchedule {
+                        model,
+                        duration,
+                        state: std::sync::Mutex::new((seed, Duration::ZERO)),
+                    })
+                } else {
+                    None
+                }
+            }
+        }
+    }
+
+    /// Take the next intended start, as an offset from the start of the run.
+    fn next(&self) -> Option<Duration> {
+        let mut state = self
+            .state
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner);
+        let (seed, next_arrival) = &mut *state;
+        let arrival = *next_arrival;
+        if arrival >= self.duration {
+            return None;
         }
+
+        let gap = match self.model {
+            ArrivalModel::ConstantRate { ops_per_second } => 1.0 / ops_per_second,
+            ArrivalModel::Poisson { ops_per_second } => {
+                let (bits, next_seed) = seed.next_u64();
+                *seed = next_seed;
+                let uniform = (bits >> 11) as f64 / (1u64 << 53) as f64;
+                -(1.0 - uniform).ln() / ops_per_second
+            }
+            ArrivalModel::Closed => unreachable!("closed model has no schedule"),
+        };
+        *next_arrival = arrival + Duration::from_secs_f64(gap);
+
+        Some(arrival)
     }
 }
 
@@ -1320,13 +1649,13 @@ pub struct LoadTestStats {
     /// Maximum response time
     pub max_response_time: Duration,
     /// Response time distribution
-    pub response_times: Vec<Duration>,
+    pub response_times: LatencyHistogram,
     /// Thread utilization (0.0 to 1.0)
     pub thread_utilization: f64,
     /// Whether any deadlocks were detected
     pub deadlocks_detected: usize,
-    /// Memory usage statistics (if available)
-    pub memory_usage_mb: Option<f64>,
+    /// Resident memory observed during the run, where the platform supports it
+    pub memory_usage: Option<MemoryUsage>,
 }
 
 /// Result of a load test execution.
@@ -1368,6 +1697,92 @@ where
     pub test_function: Arc<F>,
     /// Load test configuration
     pub config: LoadTestConfig,
+    /// Periodic progress reporting, if enabled
+    progress: Option<ProgressReporting>,
+}
+
+/// A snapshot of a running load test.
+#[derive(Debug, Clone, PartialEq)]
+pub struct LoadTestProgress {
+    /// Time since the load test started
+    pub elapsed: Duration,
+    /// Operations completed so far
+    pub operations_completed: usize,
+    /// Operations failed so far
+    pub operations_failed: usize,
+    /// Operations per second since the previous snapshot
+    pub ops_per_second: f64,
+    /// 95th percentile response time since the previous snapshot
+    pub p95_response_time: Duration,
+    /// Percentage of all operations so far that failed
+    pub error_rate_percent: f64,
+}
+
+type ProgressFn = Box<dyn Fn(&LoadTestProgress) + Send + Sync>;
+
+struct ProgressReporting {
+    interval: Duration,
+    callback: ProgressFn,
+}
+
+/// Counters shared by the worker threads for progress snapshots.
+#[derive(Default)]
+struct ProgressRecorder {
+    completed: std::sync::atomic::AtomicUsize,
+    failed: std::sync::atomic::AtomicUsize,
+    window: std::sync::Mutex<Vec<Duration>>,
+}
+
+impl ProgressRecorder {
+    fn record(&self, response_time: Duration, failed: bool) {
+        use std::sync::atomic::Ordering;
+
+        self.completed.fetch_add(1, Ordering::Relaxed);
+        if failed {
+            self.failed.fetch_add(1, Ordering::Relaxed);
+        }
+        self.window
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner)
+            .push(response_time);
+    }
+
+    /// Take a snapshot, starting a new window for interval measures.
+    fn snapshot(&self, elapsed: Duration, window_length: Duration) -> LoadTestProgress {
+        use std::sync::atomic::Ordering;
+
+        let mut window = std::mem::take(
+            &mut *self
+                .window
+                .lock()
+                .unwrap_or_else(std::sync::PoisonError::into_inner),
+        );
+        window.sort();
+        let p95_response_time = if window.is_empty() {
+            Duration::from_secs(0)
+        } else {
+            window[((window.len() as f64 * 0.95) as usize).min(window.len() - 1)]
+        };
+
+        let operations_completed = self.completed.load(Ordering::Relaxed);
+        let operations_failed = self.failed.load(Ordering::Relaxed);
+        LoadTestProgress {
+            elapsed,
+            operations_completed,
+            operations_failed,
+            ops_per_second: if window_length.is_zero() {
+                0.0
+            } else {
+                window.len() as f64 / window_length.as_secs_f64()
+            },
+            p95_response_time,
+            error_rate_percent: if operations_completed == 0 {
+                0.0
+            } else {
+                operations_failed as f64 / operations_completed as f64 * 100.0
+            },
+        }
+    }
 }
 
 impl<T, F> LoadGenerator<T, F>
@@ -1381,12 +1796,28 @@ where
             generator,
             test_function: Arc::new(test_function),
             config,
+            progress: None,
         }
     }
 
+    /// Report a progress snapshot every `interval` while the load test runs.
+    ///
+    /// The callback runs on the thread that called `run_load_test`.
+    pub fn with_progress<P>(mut self, interval: Duration, callback: P) -> Self
+    where
+        P: Fn(&LoadTestProgress) + Send + Sync + 'static,
+    {
+        self.progress = Some(ProgressReporting {
+            interval,
+            callback: Box::new(callback),
+        });
+        self
+    }
+
     /// Execute the load test.
     pub fn run_load_test(&self) -> LoadTestResult {
         let start_time = Instant::now();
+        let memory_sampler = MemorySampler::start(MEMORY_SAMPLE_INTERVAL);
 
         // Pre-generate test inputs to avoid generator contention during load test
         let input_count = (self.config.duration.as_secs() as usize + 10) * self.config.thread_count;
@@ -1394,6 +1825,16 @@ where
 
         let mut thread_handles = Vec::new();
         let mut stats = LoadTestStats::default();
+        let schedule = ArrivalSchedule::new(
+            self.config.arrival,
+            self.config.duration,
+            crate::data::Seed::random(),
+        )
+        .map(Arc::new);
+        let recorder = self
+            .progress
+            .as_ref()
+            .map(|_| Arc::new(ProgressRecorder::default()));
 
         // Phase 1: Ramp up
         let ramp_up_start = Instant::now();
@@ -1407,16 +1848,35 @@ where
             let inputs = test_inputs.clone();
             let test_function = Arc::clone(&self.test_function);
             let config = self.config.clone();
+            let schedule = schedule.clone();
+            let recorder = recorder.clone();
             let thread_start_delay = Duration::from_millis(
                 (thread_id as u64 * self.config.ramp_up_duration.as_millis() as u64)
                     / self.config.thread_count as u64,
             );
 
-            let handle = thread::spawn(move || {
-                // Stagger thread starts during ramp-up
-                thread::sleep(thread_start_delay);
-
-                Self::worker_thread(thread_id, inputs, test_function, config)
+            let handle = thread::spawn(move || match schedule {
+                Some(schedule) => Self::open_worker_thread(
+                    thread_id,
+                    inputs,
+                    test_function,
+                    config,
+                    &schedule,
+                    start_time,
+                    recorder.as_deref(),
+                ),
+                None => {
+                    // Stagger thread starts during ramp-up
+                    thread::sleep(thread_start_delay);
+
+                    Self::worker_thread(
+                        thread_id,
+                        inputs,
+                        test_function,
+                        config,
+                        recorder.as_deref(),
+                    )
+                }
             });
 
             thread_handles.push(handle);
@@ -1426,7 +1886,19 @@ where
 
         // Phase 2: Steady state (wait for load test to complete)
         let steady_state_start = Instant::now();
-        thread::sleep(self.config.duration);
+        match (&self.progress, &recorder) {
+            (Some(progress), Some(recorder)) => {
+                let mut last_snapshot = steady_state_start;
+                while steady_state_start.elapsed() < self.config.duration {
+                    let remaining = self.config.duration - steady_state_start.elapsed();
+                    thread::sleep(progress.interval.min(remaining));
+                    let snapshot = recorder.snapshot(start_time.elapsed(), last_snapshot.elapsed());
+                    last_snapshot = Instant::now();
+                    (progress.callback)(&snapshot);
+                }
+            }
+            _ => thread::sleep(self.config.duration),
+        }
         let steady_state_time = steady_state_start.elapsed();
 
         // Phase 3: Cool down and collect results
@@ -1434,7 +1906,7 @@ where
         println!("🔽 Load test cooling down...");
 
         let mut thread_results = Vec::new();
-        let mut all_response_times = Vec::new();
+        let mut all_response_times = LatencyHistogram::new();
         let mut total_ops = 0;
         let mut failed_ops = 0;
 
@@ -1443,7 +1915,7 @@ where
                 Ok((thread_stats, response_times)) => {
                     total_ops += thread_stats.operations_completed;
                     failed_ops += thread_stats.operations_failed;
-                    all_response_times.extend(response_times);
+                    all_response_times.merge(&response_times);
 
                     thread_results.push(TestResult::Pass {
                         tests_run: thread_stats.operations_completed,
@@ -1460,6 +1932,7 @@ where
                         module_path: None,
                         assertion_type: Some("Thread Panic".to_string()),
                         shrink_steps: Vec::new(),
+                        details: FailureDetails::default(),
                     });
                 }
             }
@@ -1469,37 +1942,10 @@ where
         let total_time = start_time.elapsed();
 
         // Calculate statistics
-        all_response_times.sort();
-        let avg_response_time = if !all_response_times.is_empty() {
-            all_response_times.iter().sum::<Duration>() / all_response_times.len() as u32
-        } else {
-            Duration::from_secs(0)
-        };
-
-        let p95_response_time = if !all_response_times.is_empty() {
-            let index = (all_response_times.len() as f64 * 0.95) as usize;
-            all_response_times
-                .get(index)
-                .copied()
-                .unwrap_or(Duration::from_secs(0))
-        } else {
-            Duration::from_secs(0)
-        };
-
-        let p99_response_time = if !all_response_times.is_empty() {
-            let index = (all_response_times.len() as f64 * 0.99) as usize;
-            all_response_times
-                .get(index)
-                .copied()
-                .unwrap_or(Duration::from_secs(0))
-        } else {
-            Duration::from_secs(0)
-        };
-
-        let max_response_time = all_response_times
-            .last()
-            .copied()
-            .unwrap_or(Duration::from_secs(0));
+        let avg_response_time = all_response_times.mean();
+        let p95_response_time = all_response_times.percentile(95.0);
+        let p99_response_time = all_response_times.percentile(99.0);
+        let max_response_time = all_response_times.max();
         let avg_ops_per_second = total_ops as f64 / steady_state_time.as_secs_f64();
 
         stats.operations_completed = total_ops;
@@ -1517,7 +1963,7 @@ where
             thread_results.len() as f64 / self.config.thread_count as f64
         };
         stats.deadlocks_detected = 0; // Would need more sophisticated detection
-        stats.memory_usage_mb = None;
+        stats.memory_usage = memory_sampler.stop();
 
         LoadTestResult {
             config: self.config.clone(),
@@ -1554,17 +2000,73 @@ where
         inputs
     }
 
+    /// Worker thread for open-model load testing.
+    ///
+    /// Takes intended start times from the shared schedule, waiting for each
+    /// one that is still in the future.
+    fn open_worker_thread(
+        _thread_id: usize,
+        inputs: Vec<T>,
+        test_function: Arc<F>,
+        config: LoadTestConfig,
+        schedule: &ArrivalSchedule,
+        run_start: Instant,
+        recorder: Option<&ProgressRecorder>,
+    ) -> (LoadTestStats, LatencyHistogram) {
+        let mut operations_completed = 0;
+        let mut operations_failed = 0;
+        let mut response_times = LatencyHistogram::new();
+        let mut input_iter = inputs.iter().cycle();
+
+        while let Some(offset) = schedule.next() {
+            let Some(input) = input_iter.next() else {
+                break;
+            };
+            let intended_start = run_start + offset;
+            let now = Instant::now();
+            if intended_start > now {
+                thread::sleep(intended_start - now);
+            }
+
+            let result = test_function(input);
+            let response_time = intended_start.elapsed();
+
+            if config.collect_stats {
+                response_times.record(response_time);
+            }
+
+            operations_completed += 1;
+            let failed = matches!(result, TestResult::Fail { .. });
+            if failed {
+                operations_failed += 1;
+            }
+            if let Some(recorder) = recorder {
+                recorder.record(response_time, failed);
+            }
+        }
+
+        let thread_stats = LoadTestStats {
+            operations_completed,
+            operations_failed,
+            avg_ops_per_second: operations_completed as f64 / config.duration.as_secs_f64(),
+            ..LoadTestStats::default()
+        };
+
+        (thread_stats, response_times)
+    }
+
     /// Worker thread for load testing.
     fn worker_thread(
         _thread_id: usize,
         inputs: Vec<T>,
         test_function: Arc<F>,
         config: LoadTestConfig,
-    ) -> (LoadTestStats, Vec<Duration>) {
+        recorder: Option<&ProgressRecorder>,
+    ) -> (LoadTestStats, LatencyHistogram) {
         let start_time = Instant::now();
         let mut operations_completed = 0;
         let mut operations_failed = 0;
-        let mut response_times = Vec::new();
+        let mut response_times = LatencyHistogram::new();
         let mut input_iter = inputs.iter().cycle();
 
         // Run until duration expires
@@ -1575,7 +2077,7 @@ where
                 let response_time = op_start.elapsed();
 
                 if config.collect_stats {
-                    response_times.push(response_time);
+                    response_times.record(response_time);
                 }
 
                 match result {
@@ -1587,6 +2089,10 @@ where
                     _ => operations_completed += 1,
                 }
 
+                if let Some(recorder) = recorder {
+                    recorder.record(response_time, matches!(result, TestResult::Fail { .. }));
+                }
+
                 // Rate limiting if specified
                 if let Some(target_ops_per_sec) = config.ops_per_second {
                     let target_interval = Duration::from_secs_f64(1.0 / target_ops_per_sec as f64);
@@ -1606,10 +2112,10 @@ where
             p95_response_time: Duration::from_secs(0),
             p99_response_time: Duration::from_secs(0),
             max_response_time: Duration::from_secs(0),
-            response_times: Vec::new(),
+            response_times: LatencyHistogram::new(),
             thread_utilization: 1.0,
             deadlocks_detected: 0,
-            memory_usage_mb: None,
+            memory_usage: None,
         };
 
         (thread_stats, response_times)
@@ -1627,36 +2133,233 @@ impl Default for LoadTestStats {
             p95_response_time: Duration::from_secs(0),
             p99_response_time: Duration::from_secs(0),
             max_response_time: Duration::from_secs(0),
-            response_times: Vec::new(),
+            response_times: LatencyHistogram::new(),
             thread_utilization: 0.0,
             deadlocks_detected: 0,
-            memory_usage_mb: None,
+            memory_usage: None,
         }
     }
 }
 
-/// Create a load generator for stress testing.
-pub fn load_generator<T, F>(generator: Gen<T>, test_function: F) -> LoadGenerator<T, F>
-where
-    T: 'static + std::fmt::Debug + Clone + Send + Sync,
-    F: Fn(&T) -> TestResult + Send + Sync + 'static,
-{
-    LoadGenerator::new(generator, test_function, LoadTestConfig::default())
+/// Service level objectives a load test must meet to pass.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct LoadTestSlo {
+    /// Maximum acceptable average response time
+    pub max_avg_response_time: Option<Duration>,
+    /// Maximum acceptable 95th percentile response time
+    pub max_p95_response_time: Option<Duration>,
+    /// Maximum acceptable 99th percentile response time
+    pub max_p99_response_time: Option<Duration>,
+    /// Maximum acceptable error rate, as a percentage of operations
+    pub max_error_rate_percent: Option<f64>,
+    /// Minimum acceptable average operations per second
+    pub min_ops_per_second: Option<f64>,
 }
-// Add num_cpus dependency detection placeholder
-mod num_cpus {
-    pub fn get() -> usize {
-        // Fallback to 4 threads if we can't detect CPU count
-        std::thread::available_parallelism()
-            .map(|n| n.get())
-            .unwrap_or(4)
+
+impl LoadTestSlo {
+    /// Create an empty set of objectives that every load test meets.
+    pub fn new() -> Self {
+        Self::default()
     }
-}
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-    use crate::gen::Gen;
+    /// Require the average response time to be under `limit`.
+    pub fn require_avg_response_under(mut self, limit: Duration) -> Self {
+        self.max_avg_response_time = Some(limit);
+        self
+    }
+
+    /// Require the 95th percentile response time to be under `limit`.
+    pub fn require_p95_under(mut self, limit: Duration) -> Self {
+        self.max_p95_response_time = Some(limit);
+        self
+    }
+
+    /// Require the 99th percentile response time to be under `limit`.
+    pub fn require_p99_under(mut self, limit: Duration) -> Self {
+        self.max_p99_response_time = Some(limit);
+        self
+    }
+
+    /// Require the error rate to be below `percent` of operations.
+    pub fn require_error_rate_below(mut self, percent: f64) -> Self {
+        self.max_error_rate_percent = Some(percent);
+        self
+    }
+
+    /// Require the average throughput to be above `ops_per_second`.
+    pub fn require_throughput_above(mut self, ops_per_second: f64) -> Self {
+        self.min_ops_per_second = Some(ops_per_second);
+        self
+    }
+}
+
+/// A service level objective that a load test did not meet.
+#[derive(Debug, Clone, PartialEq)]
+pub enum SloViolation {
+    /// A response time measure was at or above its limit
+    ResponseTime {
+        /// Which measure was violated, e.g. `p99`
+        measure: &'static str,
+        /// The required upper bound
+        limit: Duration,
+        /// The measured value
+        actual: Duration,
+    },
+    /// Too many operations failed
+    ErrorRate {
+        /// The required upper bound, as a percentage
+        limit_percent: f64,
+        /// The measured error rate, as a percentage
+        actual_percent: f64,
+    },
+    /// Too few operations completed per second
+    Throughput {
+        /// The required lower bound
+        minimum: f64,
+        /// The measured average operations per second
+        actual: f64,
+    },
+}
+
+impl std::fmt::Display for SloViolation {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SloViolation::ResponseTime {
+                measure,
+                limit,
+                actual,
+            } => write!(
+                f,
+                "{measure} response time {actual:?} is not under {limit:?}"
+            ),
+            SloViolation::ErrorRate {
+                limit_percent,
+                actual_percent,
+            } => write!(
+                f,
+                "error rate {actual_percent:.2}% is not below {limit_percent:.2}%"
+            ),
+            SloViolation::Throughput { minimum, actual } => write!(
+                f,
+                "throughput {actual:.1} ops/s is not above {minimum:.1} ops/s"
+            ),
+        }
+    }
+}
+
+impl LoadTestResult {
+    /// Percentage of operations that failed.
+    pub fn error_rate_percent(&self) -> f64 {
+        if self.stats.operations_completed == 0 {
+            0.0
+        } else {
+            self.stats.operations_failed as f64 / self.stats.operations_completed as f64 * 100.0
+        }
+    }
+
+    /// Check the result against `slo`, returning every objective not met.
+    pub fn check_slo(&self, slo: &LoadTestSlo) -> Vec<SloViolation> {
+        let mut violations = Vec::new();
+
+        let response_times = [
+            (
+                "average",
+                slo.max_avg_response_time,
+                self.stats.avg_response_time,
+            ),
+            (
+                "p95",
+                slo.max_p95_response_time,
+                self.stats.p95_response_time,
+            ),
+            (
+                "p99",
+                slo.max_p99_response_time,
+                self.stats.p99_response_time,
+            ),
+        ];
+        for (measure, limit, actual) in response_times {
+            if let Some(limit) = limit {
+                if actual >= limit {
+                    violations.push(SloViolation::ResponseTime {
+                        measure,
+                        limit,
+                        actual,
+                    });
+                }
+            }
+        }
+
+        if let Some(limit_percent) = slo.max_error_rate_percent {
+            let actual_percent = self.error_rate_percent();
+            if actual_percent >= limit_percent {
+                violations.push(SloViolation::ErrorRate {
+                    limit_percent,
+                    actual_percent,
+                });
+            }
+        }
+
+        if let Some(minimum) = slo.min_ops_per_second {
+            let actual = self.stats.avg_ops_per_second;
+            if actual <= minimum {
+                violations.push(SloViolation::Throughput { minimum, actual });
+            }
+        }
+
+        violations
+    }
+
+    /// Turn the load test into a pass/fail result against `slo`.
+    pub fn assert_slo(&self, slo: &LoadTestSlo) -> TestResult {
+        let violations = self.check_slo(slo);
+        if violations.is_empty() {
+            TestResult::Pass {
+                tests_run: self.stats.operations_completed,
+                property_name: Some("load_test".to_string()),
+                module_path: None,
+            }
+        } else {
+            TestResult::Fail {
+                counterexample: violations
+                    .iter()
+                    .map(|violation| violation.to_string())
+                    .collect::<Vec<_>>()
+                    .join("; "),
+                tests_run: self.stats.operations_completed,
+                shrinks_performed: 0,
+                property_name: Some("load_test".to_string()),
+                module_path: None,
+                assertion_type: Some("SLO Violation".to_string()),
+                shrink_steps: Vec::new(),
+                details: FailureDetails::default(),
+            }
+        }
+    }
+}
+
+/// Create a load generator for stress testing.
+pub fn load_generator<T, F>(generator: Gen<T>, test_function: F) -> LoadGenerator<T, F>
+where
+    T: 'static + std::fmt::Debug + Clone + Send + Sync,
+    F: Fn(&T) -> TestResult + Send + Sync + 'static,
+{
+    LoadGenerator::new(generator, test_function, LoadTestConfig::default())
+}
+// Add num_cpus dependency detection placeholder
+mod num_cpus {
+    pub fn get() -> usize {
+        // Fallback to 4 threads if we can't detect CPU count
+        std::thread::available_parallelism()
+            .map(|n| n.get())
+            .unwrap_or(4)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::gen::Gen;
 
     #[test]
     fn test_work_distribution_round_robin() {
@@ -1845,6 +2548,63 @@ mod tests {
         assert_eq!(work, vec![4, 3, 3]);
     }
 
+    #[test]
+    fn test_executor_defaults_to_threads() {
+        assert_eq!(ParallelConfig::default().executor, Executor::Threads);
+        assert_eq!(
+            ParallelConfig::default()
+                .executor(Executor::Threads)
+                .executor,
+            Executor::Threads
+        );
+    }
+
+    #[cfg(feature = "rayon")]
+    #[test]
+    fn test_rayon_executor_runs_all_tests() {
+        let config = ParallelConfig {
+            thread_count: 4,
+            ..ParallelConfig::default()
+        }
+        .executor(Executor::Rayon);
+
+        let prop = ParallelProperty::new(
+            Gen::int_range(0, 100),
+            |_| TestResult::Pass {
+                tests_run: 1,
+                property_name: None,
+                module_path: None,
+            },
+            config,
+        );
+
+        let result = prop.run(&Config::default().with_tests(1000));
+
+        match result.outcome {
+            TestResult::Pass { tests_run, .. } => assert_eq!(tests_run, 1000),
+            other => panic!("Expected pass, got {other:?}"),
+        }
+        assert_eq!(result.thread_results.len(), 4);
+    }
+
+    #[cfg(feature = "rayon")]
+    #[test]
+    fn test_rayon_executor_reports_panics() {
+        let prop = ParallelProperty::new(
+            Gen::constant(1),
+            |_: &i32| -> TestResult { panic!("boom") },
+            ParallelConfig {
+                thread_count: 2,
+                ..ParallelConfig::default()
+            }
+            .executor(Executor::Rayon),
+        );
+
+        let result = prop.run(&Config::default().with_tests(4));
+
+        assert_eq!(result.concurrency_issues.thread_failures.len(), 2);
+    }
+
     #[test]
     fn test_performance_metrics_calculation() {
         let thread_results = vec![
@@ -1894,6 +2654,7 @@ mod tests {
             module_path: None,
             assertion_type: None,
             shrink_steps: Vec::new(),
+            details: FailureDetails::default(),
         };
 
         ParallelProperty::<bool, fn(&bool) -> TestResult>::analyze_thread_result(
@@ -1953,6 +2714,7 @@ mod tests {
                 module_path: None,
                 assertion_type: None,
                 shrink_steps: Vec::new(),
+                details: FailureDetails::default(),
             },
             TestResult::Pass {
                 tests_run: 30,
@@ -1972,6 +2734,72 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_statistics_aggregation_across_threads() {
+        let prop = ParallelProperty::new(
+            Gen::int_range(-50, 50),
+            |&n: &i32| {
+                if n == 0 {
+                    TestResult::Discard {
+                        limit: 1,
+                        property_name: None,
+                        module_path: None,
+                    }
+                } else {
+                    TestResult::Pass {
+                        tests_run: 1,
+                        property_name: None,
+                        module_path: None,
+                    }
+                }
+            },
+            ParallelConfig {
+                thread_count: 4,
+                ..ParallelConfig::default()
+            },
+        )
+        .classify("negative", |&n| n < 0)
+        .classify("positive", |&n| n > 0)
+        .collect("value", |&n| n as f64);
+
+        let result = prop.run(&Config::default().with_tests(200));
+
+        match result.outcome {
+            TestResult::PassWithStatistics {
+                tests_run,
+                statistics,
+                ..
+            } => {
+                assert_eq!(tests_run + statistics.discards, 200);
+                assert_eq!(statistics.total_tests, tests_run);
+                let negative = statistics.classifications.get("negative").unwrap_or(&0);
+                let positive = statistics.classifications.get("positive").unwrap_or(&0);
+                assert_eq!(negative + positive, tests_run);
+                assert_eq!(statistics.collections["value"].len(), tests_run);
+            }
+            other => panic!("Expected PassWithStatistics, got: {other:?}"),
+        }
+    }
+
+    #[test]
+    fn test_all_discarded_threads_aggregate_to_discard() {
+        let discard = || TestResult::Discard {
+            limit: 5,
+            property_name: None,
+            module_path: None,
+        };
+
+        let aggregated = ParallelProperty::<bool, fn(&bool) -> TestResult>::aggregate_results(&[
+            discard(),
+            discard(),
+        ]);
+
+        match aggregated {
+            TestResult::Discard { limit, .. } => assert_eq!(limit, 10),
+            other => panic!("Expected discard, got: {other:?}"),
+        }
+    }
+
     #[test]
     fn test_concurrent_property_basic() {
         let prop = for_all_concurrent(
@@ -2026,6 +2854,7 @@ mod tests {
                             module_path: None,
                             assertion_type: Some("Flip Flop".to_string()),
                             shrink_steps: Vec::new(),
+                            details: FailureDetails::default(),
                         }
                     }
                 }
@@ -2090,6 +2919,7 @@ mod tests {
             module_path: None,
             assertion_type: None,
             shrink_steps: Vec::new(),
+            details: FailureDetails::default(),
         };
 
         assert_eq!(
@@ -2200,6 +3030,178 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_hanging_threads_are_detached_at_timeout() {
+        // Threads that never finish within the timeout must not hold up the
+        // runner beyond the configured deadline.
+        let prop = ConcurrentProperty::new(
+            Gen::constant(1),
+            |_: &i32| {
+                thread::sleep(Duration::from_secs(2));
+                TestResult::Pass {
+                    tests_run: 1,
+                    property_name: None,
+                    module_path: None,
+                }
+            },
+            2,
+        )
+        .with_timeout(Duration::from_millis(50));
+
+        let start = Instant::now();
+        let results = prop.run(&Config::default().with_tests(1));
+        let elapsed = start.elapsed();
+
+        assert!(results[0].timeout_detected);
+        assert_eq!(
+            results[0].deadlock_info.as_ref().unwrap().threads_involved,
+            vec![0, 1]
+        );
+        assert!(
+            elapsed < Duration::from_secs(1),
+            "Runner should return at the timeout, took {elapsed:?}"
+        );
+    }
+
+    #[test]
+    fn test_lock_graph_reports_inverted_lock_order() {
+        use std::sync::atomic::{AtomicUsize, Ordering};
+
+        let graph = LockGraph::new();
+        let gate = Arc::new(graph.mutex("gate", ()));
+        let accounts = Arc::new(graph.mutex("accounts", 0));
+        let ledger = Arc::new(graph.mutex("ledger", 0));
+        let calls = Arc::new(AtomicUsize::new(0));
+
+        let prop = ConcurrentProperty::new(
+            Gen::constant(()),
+            move |_: &()| {
+                // The gate serialises the threads, so the inversion below is
+                // latent: it never actually deadlocks here.
+                let _gate = gate.lock();
+                if calls.fetch_add(1, Ordering::SeqCst).is_multiple_of(2) {
+                    let _a = accounts.lock();
+                    let _l = ledger.lock();
+                } else {
+                    let _l = ledger.lock();
+                    let _a = accounts.lock();
+                }
+                TestResult::Pass {
+                    tests_run: 1,
+                    property_name: None,
+                    module_path: None,
+                }
+            },
+            2,
+        )
+        .with_lock_graph(graph.clone());
+
+        let results = prop.run(&Config::default().with_tests(1));
+        let result = &results[0];
+
+        assert!(!result.timeout_detected);
+        let info = result.deadlock_info.as_ref().expect("lock cycle reported");
+        assert_eq!(info.lock_cycles.len(), 1);
+        assert_eq!(
+            info.lock_cycles[0].locks,
+            vec!["accounts", "ledger", "accounts"]
+        );
+        assert!(result.race_conditions_detected > 0);
+        assert!(!graph.has_cycles(), "cycles are taken into the result");
+    }
+
+    #[test]
+    fn test_fault_injector_faults_are_reported() {
+        let injector = FaultInjector::from_seed(99)
+            .with_delays(1.0, Duration::from_millis(2))
+            .with_io_failures(1.0);
+
+        let prop = ConcurrentProperty::new(
+            Gen::constant(3),
+            {
+                let injector = injector.clone();
+                move |n: &i32| match injector.io("save") {
+                    Ok(()) => TestResult::Pass {
+                        tests_run: 1,
+                        property_name: None,
+                        module_path: None,
+                    },
+                    Err(error) => TestResult::Fail {
+                        counterexample: format!("{n}: {error}"),
+                        tests_run: 1,
+                        shrinks_performed: 0,
+                        property_name: None,
+                        module_path: None,
+                        assertion_type: None,
+                        shrink_steps: Vec::new(),
+                        details: FailureDetails::default(),
+                    },
+                }
+            },
+            3,
+        )
+        .with_fault_injector(injector.clone());
+
+        let results = prop.run(&Config::default().with_tests(2));
+
+        for result in &results {
+            assert!(result.deterministic);
+            assert!(result
+                .results
+                .iter()
+                .all(|r| matches!(r, TestResult::Fail { .. })));
+
+            let mut io_threads: Vec<_> = result
+                .injected_faults
+                .iter()
+                .filter(|fault| fault.kind == FaultKind::IoFailure)
+                .map(|fault| fault.thread_index.unwrap())
+                .collect();
+            io_threads.sort();
+            assert_eq!(io_threads, vec![0, 1, 2]);
+
+            let delays = result
+                .injected_faults
+                .iter()
+                .filter(|fault| fault.point == "thread_start")
+                .count();
+            assert_eq!(delays, 3);
+        }
+        assert!(injector.faults().is_empty(), "faults are taken per input");
+    }
+
+    #[test]
+    fn test_panicking_thread_is_reported() {
+        let prop = ConcurrentProperty::new(
+            Gen::constant(7),
+            |n: &i32| {
+                if *n == 7 {
+                    panic!("boom");
+                }
+                TestResult::Pass {
+                    tests_run: 1,
+                    property_name: None,
+                    module_path: None,
+                }
+            },
+            2,
+        );
+
+        let results = prop.run(&Config::default().with_tests(1));
+        let result = &results[0];
+
+        assert!(!result.timeout_detected);
+        assert_eq!(result.results.len(), 2);
+        for thread_result in &result.results {
+            match thread_result {
+                TestResult::Fail { assertion_type, .. } => {
+                    assert_eq!(assertion_type.as_deref(), Some("Thread Panic"));
+                }
+                other => panic!("Expected panic failure, got {other:?}"),
+            }
+        }
+    }
+
     #[test]
     fn test_concurrent_scenario_builder() {
         // Test building a simple scenario
@@ -2220,6 +3222,7 @@ mod tests {
                         module_path: None,
                         assertion_type: None,
                         shrink_steps: Vec::new(),
+                        details: FailureDetails::default(),
                     }
                 }
             })
@@ -2274,6 +3277,7 @@ mod tests {
                         module_path: None,
                         assertion_type: None,
                         shrink_steps: Vec::new(),
+                        details: FailureDetails::default(),
                     }
                 }
             })
@@ -2357,6 +3361,7 @@ mod tests {
                     module_path: None,
                     assertion_type: None,
                     shrink_steps: Vec::new(),
+                    details: FailureDetails::default(),
                 }
             }
         });
@@ -2384,6 +3389,7 @@ mod tests {
                     module_path: None,
                     assertion_type: None,
                     shrink_steps: Vec::new(),
+                    details: FailureDetails::default(),
                 }
             }
         })
@@ -2415,6 +3421,7 @@ mod tests {
                     module_path: None,
                     assertion_type: None,
                     shrink_steps: Vec::new(),
+                    details: FailureDetails::default(),
                 }
             }
         })
@@ -2463,6 +3470,7 @@ mod tests {
                         module_path: None,
                         assertion_type: Some("Non-deterministic".to_string()),
                         shrink_steps: Vec::new(),
+                        details: FailureDetails::default(),
                     }
                 }
             }
@@ -2512,6 +3520,7 @@ mod tests {
             ramp_up_duration: Duration::from_millis(10),
             cool_down_duration: Duration::from_millis(10),
             collect_stats: true,
+            arrival: ArrivalModel::Closed,
         };
 
         let generator = LoadGenerator::new(
@@ -2532,6 +3541,7 @@ mod tests {
                         module_path: None,
                         assertion_type: None,
                         shrink_steps: Vec::new(),
+                        details: FailureDetails::default(),
                     }
                 }
             },
@@ -2552,6 +3562,7 @@ mod tests {
             ramp_up_duration: Duration::from_millis(5),
             cool_down_duration: Duration::from_millis(5),
             collect_stats: true,
+            arrival: ArrivalModel::Closed,
         };
 
         let generator = LoadGenerator::new(
@@ -2607,6 +3618,37 @@ mod tests {
         }
     }
 
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn test_memory_usage_is_sampled() {
+        let parallel = for_all_parallel(Gen::int_range(1, 100), |&n| n > 0, 2)
+            .run(&Config::default().with_tests(50));
+        let memory = parallel.performance.memory_usage.unwrap();
+        assert!(memory.samples >= 2);
+        assert!(memory.peak_mb >= memory.avg_mb && memory.avg_mb > 0.0);
+
+        let config = LoadTestConfig {
+            thread_count: 1,
+            duration: Duration::from_millis(30),
+            ramp_up_duration: Duration::from_millis(1),
+            cool_down_duration: Duration::from_millis(1),
+            ..LoadTestConfig::default()
+        };
+        let load = LoadGenerator::new(
+            Gen::constant(1),
+            |_| TestResult::Pass {
+                tests_run: 1,
+                property_name: None,
+                module_path: None,
+            },
+            config,
+        )
+        .run_load_test();
+        let memory = load.stats.memory_usage.unwrap();
+        assert!(memory.samples >= 2);
+        assert!(memory.peak_mb >= memory.avg_mb && memory.avg_mb > 0.0);
+    }
+
     #[test]
     fn test_load_generator_with_failures() {
         let config = LoadTestConfig {
@@ -2616,6 +3658,7 @@ mod tests {
             ramp_up_duration: Duration::from_millis(2),
             cool_down_duration: Duration::from_millis(2),
             collect_stats: true,
+            arrival: ArrivalModel::Closed,
         };
 
         let generator = LoadGenerator::new(
@@ -2631,6 +3674,7 @@ mod tests {
                         module_path: None,
                         assertion_type: Some("Even Number".to_string()),
                         shrink_steps: Vec::new(),
+                        details: FailureDetails::default(),
                     }
                 } else {
                     TestResult::Pass {
@@ -2665,6 +3709,7 @@ mod tests {
             ramp_up_duration: Duration::from_millis(2),
             cool_down_duration: Duration::from_millis(2),
             collect_stats: true,
+            arrival: ArrivalModel::Closed,
         };
 
         let generator = LoadGenerator::new(
@@ -2714,4 +3759,241 @@ mod tests {
             "Counter should match operations completed"
         );
     }
+
+    fn load_test_result_with(stats: LoadTestStats) -> LoadTestResult {
+        LoadTestResult {
+            config: LoadTestConfig::default(),
+            stats,
+            thread_results: Vec::new(),
+            phase_timings: LoadTestPhases {
+                ramp_up_time: Duration::from_secs(0),
+                steady_state_time: Duration::from_secs(1),
+                cool_down_time: Duration::from_secs(0),
+                total_time: Duration::from_secs(1),
+            },
+            success_rate: 0.0,
+        }
+    }
+
+    #[test]
+    fn test_load_test_slo_checks() {
+        let result = load_test_result_with(LoadTestStats {
+            operations_completed: 1000,
+            operations_failed: 20,
+            avg_ops_per_second: 1000.0,
+            avg_response_time: Duration::from_millis(2),
+            p95_response_time: Duration::from_millis(8),
+            p99_response_time: Duration::from_millis(30),
+            ..LoadTestStats::default()
+        });
+
+        let met = LoadTestSlo::new()
+            .require_p95_under(Duration::from_millis(10))
+            .require_error_rate_below(5.0)
+            .require_throughput_above(500.0);
+        assert!(result.check_slo(&met).is_empty());
+        assert!(matches!(
+            result.assert_slo(&met),
+            TestResult::Pass {
+                tests_run: 1000,
+                ..
+            }
+        ));
+
+        let missed = LoadTestSlo::new()
+            .require_avg_response_under(Duration::from_millis(5))
+            .require_p99_under(Duration::from_millis(25))
+            .require_error_rate_below(1.0)
+            .require_throughput_above(2000.0);
+        let violations = result.check_slo(&missed);
+        assert_eq!(
+            violations,
+            vec![
+                SloViolation::ResponseTime {
+                    measure: "p99",
+                    limit: Duration::from_millis(25),
+                    actual: Duration::from_millis(30),
+                },
+                SloViolation::ErrorRate {
+                    limit_percent: 1.0,
+                    actual_percent: 2.0,
+                },
+                SloViolation::Throughput {
+                    minimum: 2000.0,
+                    actual: 1000.0,
+                },
+            ]
+        );
+
+        match result.assert_slo(&missed) {
+            TestResult::Fail {
+                counterexample,
+                assertion_type,
+                ..
+            } => {
+                assert_eq!(assertion_type.as_deref(), Some("SLO Violation"));
+                assert_eq!(
+                    counterexample,
+                    "p99 response time 30ms is not under 25ms; \
+                     error rate 2.00% is not below 1.00%; \
+                     throughput 1000.0 ops/s is not above 2000.0 ops/s"
+                );
+            }
+            other => panic!("Expected SLO failure, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn test_load_test_error_rate_with_no_operations() {
+        let result = load_test_result_with(LoadTestStats::default());
+
+        assert_eq!(result.error_rate_percent(), 0.0);
+        assert!(result
+            .check_slo(&LoadTestSlo::new().require_error_rate_below(1.0))
+            .is_empty());
+    }
+
+    #[test]
+    fn test_constant_rate_schedule() {
+        let schedule = ArrivalSchedule::new(
+            ArrivalModel::ConstantRate {
+                ops_per_second: 100.0,
+            },
+            Duration::from_millis(100),
+            crate::data::Seed::from_u64(1),
+        )
+        .unwrap();
+
+        let arrivals: Vec<_> = std::iter::from_fn(|| schedule.next()).collect();
+        assert_eq!(arrivals.len(), 10);
+        assert_eq!(arrivals[0], Duration::ZERO);
+        let gap = arrivals[5] - arrivals[4];
+        assert!(gap >= Duration::from_micros(9_990) && gap <= Duration::from_micros(10_010));
+    }
+
+    #[test]
+    fn test_poisson_schedule_mean_rate() {
+        let schedule = ArrivalSchedule::new(
+            ArrivalModel::Poisson {
+                ops_per_second: 1000.0,
+            },
+            Duration::from_secs(10),
+            crate::data::Seed::from_u64(7),
+        )
+        .unwrap();
+
+        let arrivals: Vec<_> = std::iter::from_fn(|| schedule.next()).collect();
+        assert!(
+            (9_000..11_000).contains(&arrivals.len()),
+            "Expected about 10000 arrivals, got {}",
+            arrivals.len()
+        );
+        assert!(arrivals.windows(2).all(|w| w[0] <= w[1]));
+    }
+
+    #[test]
+    fn test_closed_model_has_no_schedule() {
+        assert!(ArrivalSchedule::new(
+            ArrivalModel::Closed,
+            Duration::from_secs(1),
+            crate::data::Seed::from_u64(1)
+        )
+        .is_none());
+        assert_eq!(LoadTestConfig::default().arrival, ArrivalModel::Closed);
+    }
+
+    #[test]
+    fn test_open_model_counts_queueing_delay() {
+        let config = LoadTestConfig {
+            thread_count: 1,
+            duration: Duration::from_millis(100),
+            ops_per_second: None,
+            ramp_up_duration: Duration::from_millis(0),
+            cool_down_duration: Duration::from_millis(0),
+            collect_stats: true,
+            arrival: ArrivalModel::ConstantRate {
+                ops_per_second: 100.0,
+            },
+        };
+
+        // Each operation takes twice the arrival interval, so a closed-loop
+        // measurement would report ~20ms while arrivals queue up behind it.
+        let generator = LoadGenerator::new(
+            Gen::constant(()),
+            |_| {
+                thread::sleep(Duration::from_millis(20));
+                TestResult::Pass {
+                    tests_run: 1,
+                    property_name: None,
+                    module_path: None,
+                }
+            },
+            config,
+        );
+
+        let result = generator.run_load_test();
+
+        assert_eq!(result.stats.operations_completed, 10);
+        assert!(
+            result.stats.max_response_time >= Duration::from_millis(100),
+            "Queueing delay should be included, max was {:?}",
+            result.stats.max_response_time
+        );
+    }
+
+    #[test]
+    fn test_load_generator_reports_progress() {
+        use std::sync::Mutex;
+
+        let snapshots = Arc::new(Mutex::new(Vec::new()));
+        let config = LoadTestConfig {
+            thread_count: 2,
+            duration: Duration::from_millis(100),
+            ramp_up_duration: Duration::from_millis(0),
+            cool_down_duration: Duration::from_millis(0),
+            ..LoadTestConfig::default()
+        };
+
+        let generator = LoadGenerator::new(
+            Gen::int_range(1, 4),
+            |&n| {
+                thread::sleep(Duration::from_micros(200));
+                if n == 4 {
+                    TestResult::Fail {
+                        counterexample: format!("{n}"),
+                        tests_run: 1,
+                        shrinks_performed: 0,
+                        property_name: None,
+                        module_path: None,
+                        assertion_type: None,
+                        shrink_steps: Vec::new(),
+                        details: FailureDetails::default(),
+                    }
+                } else {
+                    TestResult::Pass {
+                        tests_run: 1,
+                        property_name: None,
+                        module_path: None,
+                    }
+                }
+            },
+            config,
+        )
+        .with_progress(Duration::from_millis(20), {
+            let snapshots = Arc::clone(&snapshots);
+            move |progress| snapshots.lock().unwrap().push(progress.clone())
+        });
+
+        let result = generator.run_load_test();
+        let snapshots = snapshots.lock().unwrap();
+
+        assert!(snapshots.len() >= 4, "got {} snapshots", snapshots.len());
+        assert!(snapshots.windows(2).all(|w| w[0].elapsed <= w[1].elapsed
+            && w[0].operations_completed <= w[1].operations_completed));
+        let last = snapshots.last().unwrap();
+        assert!(last.operations_completed > 0);
+        assert!(last.operations_completed <= result.stats.operations_completed);
+        assert!(last.ops_per_second > 0.0);
+        assert!(last.error_rate_percent > 0.0 && last.error_rate_percent < 100.0);
+    }
 }
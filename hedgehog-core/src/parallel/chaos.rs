@@ -0,0 +1,325 @@
+//! Seeded fault injection for concurrent tests.
+//!
+//! A [`FaultInjector`] perturbs the threads of a concurrent test to make
+//! races more likely to surface: it delays threads at injection points,
+//! reports spurious wakeups to wait loops, and fails simulated IO. Every
+//! decision is drawn from a stream derived from the injector's seed, the test
+//! number and the thread's index, so the same faults are injected at the same
+//! points when a run is repeated with the same seed.
+
+use crate::data::Seed;
+use std::collections::HashMap;
+use std::fmt;
+use std::sync::{Arc, Mutex, PoisonError};
+use std::thread::{self, ThreadId};
+use std::time::Duration;
+
+/// The kind of fault that was injected.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum FaultKind {
+    /// The thread was paused for the given duration
+    Delay(Duration),
+    /// A wait loop was told it had been woken without its condition holding
+    SpuriousWakeup,
+    /// A simulated IO operation failed
+    IoFailure,
+}
+
+/// A fault injected at a named point.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InjectedFault {
+    /// Name of the injection point
+    pub point: String,
+    /// Index of the thread within the concurrent test
+    pub thread_index: Option<usize>,
+    /// The fault that was injected
+    pub kind: FaultKind,
+}
+
+impl fmt::Display for InjectedFault {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.thread_index {
+            Some(index) => write!(f, "thread {index} at {}: ", self.point)?,
+            None => write!(f, "at {}: ", self.point)?,
+        }
+        match &self.kind {
+            FaultKind::Delay(duration) => write!(f, "delayed {duration:?}"),
+            FaultKind::SpuriousWakeup => write!(f, "spurious wakeup"),
+            FaultKind::IoFailure => write!(f, "IO failure"),
+        }
+    }
+}
+
+/// Error returned from an IO injection point that was made to fail.
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+#[error("injected IO failure at {point}")]
+pub struct InjectedIoError {
+    /// Name of the injection point
+    pub point: String,
+}
+
+impl From<InjectedIoError> for std::io::Error {
+    fn from(error: InjectedIoError) -> Self {
+        std::io::Error::other(error)
+    }
+}
+
+#[derive(Default)]
+struct InjectorState {
+    streams: HashMap<ThreadId, (Option<usize>, Seed)>,
+    unbound_streams: u64,
+    log: Vec<InjectedFault>,
+}
+
+/// Seeded, reproducible fault injection for concurrent tests.
+///
+/// Cloning a `FaultInjector` shares its state, so the clone captured by a test
+/// function records into the same fault log the runner reports.
+#[derive(Clone)]
+pub struct FaultInjector {
+    seed: Seed,
+    delay_probability: f64,
+    max_delay: Duration,
+    spurious_wakeup_probability: f64,
+    io_failure_probability: f64,
+    state: Arc<Mutex<InjectorState>>,
+}
+
+impl FaultInjector {
+    /// Create an injector that injects nothing until faults are enabled.
+    pub fn new(seed: Seed) -> Self {
+        FaultInjector {
+            seed,
+            delay_probability: 0.0,
+            max_delay: Duration::ZERO,
+            spurious_wakeup_probability: 0.0,
+            io_failure_probability: 0.0,
+            state: Arc::new(Mutex::new(InjectorState::default())),
+        }
+    }
+
+    /// Create an injector from a single seed value.
+    pub fn from_seed(seed: u64) -> Self {
+        Self::new(Seed::from_u64(seed))
+    }
+
+    /// Delay threads at injection points with the given probability, for up
+    /// to `max_delay`.
+    pub fn with_delays(mut self, probability: f64, max_delay: Duration) -> Self {
+        self.delay_probability = probability.clamp(0.0, 1.0);
+        self.max_delay = max_delay;
+        self
+    }
+
+    /// Report spurious wakeups with the given probability.
+    pub fn with_spurious_wakeups(mut self, probability: f64) -> Self {
+        self.spurious_wakeup_probability = probability.clamp(0.0, 1.0);
+        self
+    }
+
+    /// Fail IO injection points with the given probability.
+    pub fn with_io_failures(mut self, probability: f64) -> Self {
+        self.io_failure_probability = probability.clamp(0.0, 1.0);
+        self
+    }
+
+    /// The seed all fault decisions are derived from.
+    pub fn seed(&self) -> Seed {
+        self.seed
+    }
+
+    /// Bind the current thread to a reproducible decision stream.
+    ///
+    /// The concurrent runner binds each of its threads using the test number
+    /// and the thread's index. Threads that are never bound get a stream in
+    /// the order they first reach an injection point.
+    pub fn bind_thread(&self, test_index: usize, thread_index: usize) {
+        let mut seed = self.seed;
+        for _ in 0..test_index {
+            seed = seed.split().1;
+        }
+        let mut stream = seed.split().0;
+        for _ in 0..thread_index {
+            stream = stream.split().1;
+        }
+        self.lock_state().streams.insert(
+            thread::current().id(),
+            (Some(thread_index), stream.split().0),
+        );
+    }
+
+    /// An injection point that may delay the current thread.
+    pub fn point(&self, name: &str) {
+        if self.delay_probability <= 0.0 || self.max_delay.is_zero() {
+            return;
+        }
+        let (thread_index, roll, amount) = self.draw(2);
+        if roll < self.delay_probability {
+            let delay = self.max_delay.mul_f64(amount);
+            self.record(name, thread_index, FaultKind::Delay(delay));
+            thread::sleep(delay);
+        }
+    }
+
+    /// An injection point inside a wait loop.
+    ///
+    /// Returns `true` when the caller should behave as if it had been woken
+    /// without its condition holding, e.g. by re-checking the condition.
+    pub fn spurious_wakeup(&self, name: &str) -> bool {
+        if self.spurious_wakeup_probability <= 0.0 {
+            return false;
+        }
+        let (thread_index, roll, _) = self.draw(1);
+        let injected = roll < self.spurious_wakeup_probability;
+        if injected {
+            self.record(name, thread_index, FaultKind::SpuriousWakeup);
+        }
+        injected
+    }
+
+    /// An injection point in front of an IO operation.
+    pub fn io(&self, name: &str) -> std::result::Result<(), InjectedIoError> {
+        if self.io_failure_probability <= 0.0 {
+            return Ok(());
+        }
+        let (thread_index, roll, _) = self.draw(1);
+        if roll < self.io_failure_probability {
+            self.record(name, thread_index, FaultKind::IoFailure);
+            Err(InjectedIoError {
+                point: name.to_string(),
+            })
+        } else {
+            Ok(())
+        }
+    }
+
+    /// Faults injected so far.
+    pub fn faults(&self) -> Vec<InjectedFault> {
+        self.lock_state().log.clone()
+    }
+
+    /// Remove and return the faults injected so far.
+    pub fn take_faults(&self) -> Vec<InjectedFault> {
+        std::mem::take(&mut self.lock_state().log)
+    }
+
+    fn lock_state(&self) -> std::sync::MutexGuard<'_, InjectorState> {
+        self.state.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    /// Draw up to two unit values from the current thread's stream.
+    fn draw(&self, count: usize) -> (Option<usize>, f64, f64) {
+        let mut state = self.lock_state();
+        let thread_id = thread::current().id();
+        if !state.streams.contains_key(&thread_id) {
+            state.unbound_streams += 1;
+            let mut stream = self.seed.split().1;
+            for _ in 0..state.unbound_streams {
+                stream = stream.split().1;
+            }
+            state.streams.insert(thread_id, (None, stream));
+        }
+
+        let (thread_index, seed) = state.streams.get_mut(&thread_id).unwrap();
+        let mut values = [0.0; 2];
+        for value in values.iter_mut().take(count) {
+            let (bits, next) = seed.next_u64();
+            *seed = next;
+            *value = (bits >> 11) as f64 / (1u64 << 53) as f64;
+        }
+        (*thread_index, values[0], values[1])
+    }
+
+    fn record(&self, point: &str, thread_index: Option<usize>, kind: FaultKind) {
+        self.lock_state().log.push(InjectedFault {
+            point: point.to_string(),
+            thread_index,
+            kind,
+        });
+    }
+}
+
+impl fmt::Debug for FaultInjector {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("FaultInjector")
+            .field("seed", &self.seed)
+            .field("delay_probability", &self.delay_probability)
+            .field("max_delay", &self.max_delay)
+            .field(
+                "spurious_wakeup_probability",
+                &self.spurious_wakeup_probability,
+            )
+            .field("io_failure_probability", &self.io_failure_probability)
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn io_pattern(injector: &FaultInjector, test_index: usize, thread_index: usize) -> Vec<bool> {
+        let injector = injector.clone();
+        thread::spawn(move || {
+            injector.bind_thread(test_index, thread_index);
+            (0..32).map(|_| injector.io("write").is_err()).collect()
+        })
+        .join()
+        .unwrap()
+    }
+
+    #[test]
+    fn test_disabled_injector_injects_nothing() {
+        let injector = FaultInjector::from_seed(1);
+
+        injector.point("start");
+        assert!(!injector.spurious_wakeup("wait"));
+        assert!(injector.io("write").is_ok());
+        assert!(injector.faults().is_empty());
+    }
+
+    #[test]
+    fn test_same_seed_and_slot_reproduces_faults() {
+        let first = FaultInjector::from_seed(42).with_io_failures(0.5);
+        let second = FaultInjector::from_seed(42).with_io_failures(0.5);
+
+        assert_eq!(io_pattern(&first, 3, 1), io_pattern(&second, 3, 1));
+        assert_ne!(io_pattern(&first, 3, 1), io_pattern(&first, 3, 2));
+        assert_ne!(io_pattern(&first, 3, 1), io_pattern(&first, 4, 1));
+    }
+
+    #[test]
+    fn test_faults_are_logged_with_thread_index() {
+        let injector = FaultInjector::from_seed(7)
+            .with_spurious_wakeups(1.0)
+            .with_delays(1.0, Duration::from_millis(1));
+
+        let logged = injector.clone();
+        thread::spawn(move || {
+            logged.bind_thread(0, 5);
+            assert!(logged.spurious_wakeup("queue_wait"));
+            logged.point("before_push");
+        })
+        .join()
+        .unwrap();
+
+        let faults = injector.take_faults();
+        assert_eq!(faults.len(), 2);
+        assert_eq!(faults[0].kind, FaultKind::SpuriousWakeup);
+        assert_eq!(faults[0].thread_index, Some(5));
+        assert_eq!(
+            faults[0].to_string(),
+            "thread 5 at queue_wait: spurious wakeup"
+        );
+        assert!(matches!(faults[1].kind, FaultKind::Delay(d) if d <= Duration::from_millis(1)));
+        assert!(injector.faults().is_empty());
+    }
+
+    #[test]
+    fn test_io_error_converts_to_std_io_error() {
+        let injector = FaultInjector::from_seed(0).with_io_failures(1.0);
+
+        let error: std::io::Error = injector.io("flush").unwrap_err().into();
+        assert_eq!(error.to_string(), "injected IO failure at flush");
+    }
+}
@@ -0,0 +1,230 @@
+//! Fixed-memory latency histogram for load testing.
+//!
+//! Response times are counted in log-linear buckets in the style of HDR
+//! histograms: values below 256ns are exact, and every power of two above that
+//! is split into 128 equal sub-buckets, keeping the relative error of any
+//! reported value under 1% no matter how many samples are recorded.
+
+use std::time::Duration;
+
+/// Sub-buckets per power of two, as a power of two.
+const PRECISION_BITS: u32 = 7;
+const SUB_BUCKETS: u64 = 1 << PRECISION_BITS;
+
+/// A latency histogram with bounded relative error and fixed memory.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct LatencyHistogram {
+    counts: Vec<u64>,
+    total: u64,
+    sum_nanos: u128,
+    min_nanos: u64,
+    max_nanos: u64,
+}
+
+impl LatencyHistogram {
+    /// Create an empty histogram.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Record a single response time.
+    pub fn record(&mut self, value: Duration) {
+        let nanos = u64::try_from(value.as_nanos()).unwrap_or(u64::MAX);
+        let index = bucket_index(nanos);
+        if index >= self.counts.len() {
+            self.counts.resize(index + 1, 0);
+        }
+        self.counts[index] += 1;
+
+        if self.total == 0 || nanos < self.min_nanos {
+            self.min_nanos = nanos;
+        }
+        self.max_nanos = self.max_nanos.max(nanos);
+        self.total += 1;
+        self.sum_nanos += nanos as u128;
+    }
+
+    /// Add every sample recorded in `other`.
+    pub fn merge(&mut self, other: &LatencyHistogram) {
+        if other.total == 0 {
+            return;
+        }
+        if other.counts.len() > self.counts.len() {
+            self.counts.resize(other.counts.len(), 0);
+        }
+        for (count, other_count) in self.counts.iter_mut().zip(&other.counts) {
+            *count += other_count;
+        }
+
+        if self.total == 0 || other.min_nanos < self.min_nanos {
+            self.min_nanos = other.min_nanos;
+        }
+        self.max_nanos = self.max_nanos.max(other.max_nanos);
+        self.total += other.total;
+        self.sum_nanos += other.sum_nanos;
+    }
+
+    /// Number of recorded samples.
+    pub fn len(&self) -> u64 {
+        self.total
+    }
+
+    /// Whether no samples have been recorded.
+    pub fn is_empty(&self) -> bool {
+        self.total == 0
+    }
+
+    /// Smallest recorded value, exactly.
+    pub fn min(&self) -> Duration {
+        Duration::from_nanos(self.min_nanos)
+    }
+
+    /// Largest recorded value, exactly.
+    pub fn max(&self) -> Duration {
+        Duration::from_nanos(self.max_nanos)
+    }
+
+    /// Mean of the recorded values, exactly.
+    pub fn mean(&self) -> Duration {
+        if self.total == 0 {
+            Duration::from_secs(0)
+        } else {
+            Duration::from_nanos((self.sum_nanos / self.total as u128) as u64)
+        }
+    }
+
+    /// Value at the given percentile, from 0.0 to 100.0.
+    ///
+    /// Reports the highest value equivalent to the bucket the percentile falls
+    /// in, clamped to the recorded range.
+    pub fn percentile(&self, percentile: f64) -> Duration {
+        if self.total == 0 {
+            return Duration::from_secs(0);
+        }
+
+        let fraction = percentile.clamp(0.0, 100.0) / 100.0;
+        let rank = ((fraction * self.total as f64).ceil() as u64).clamp(1, self.total);
+
+        let mut seen = 0;
+        for (index, &count) in self.counts.iter().enumerate() {
+            seen += count;
+            if seen >= rank {
+                let value = bucket_upper_bound(index).clamp(self.min_nanos, self.max_nanos);
+                return Duration::from_nanos(value);
+            }
+        }
+
+        self.max()
+    }
+
+    /// Non-empty buckets as `(highest equivalent value, count)` pairs, in
+    /// ascending order.
+    pub fn buckets(&self) -> impl Iterator<Item = (Duration, u64)> + '_ {
+        self.counts
+            .iter()
+            .enumerate()
+            .filter(|(_, &count)| count > 0)
+            .map(|(index, &count)| (Duration::from_nanos(bucket_upper_bound(index)), count))
+    }
+}
+
+fn bucket_index(value: u64) -> usize {
+    if value < SUB_BUCKETS * 2 {
+        return value as usize;
+    }
+    let msb = 63 - value.leading_zeros();
+    let shift = msb - PRECISION_BITS;
+    let mantissa = value >> shift;
+    ((shift as u64 + 1) * SUB_BUCKETS + (mantissa - SUB_BUCKETS)) as usize
+}
+
+fn bucket_upper_bound(index: usize) -> u64 {
+    let index = index as u64;
+    if index < SUB_BUCKETS * 2 {
+        return index;
+    }
+    let shift = index / SUB_BUCKETS - 1;
+    let mantissa = index % SUB_BUCKETS + SUB_BUCKETS;
+    let upper = ((mantissa as u128 + 1) << shift) - 1;
+    upper.min(u64::MAX as u128) as u64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_bucket_bounds_contain_their_values() {
+        for value in (0..100_000u64).chain([u64::MAX / 3, u64::MAX]) {
+            let index = bucket_index(value);
+            let upper = bucket_upper_bound(index);
+            assert!(value <= upper, "{value} above bucket bound {upper}");
+            if index > 0 {
+                assert!(bucket_upper_bound(index - 1) < value);
+            }
+        }
+    }
+
+    #[test]
+    fn test_empty_histogram() {
+        let histogram = LatencyHistogram::new();
+
+        assert!(histogram.is_empty());
+        assert_eq!(histogram.percentile(99.0), Duration::from_secs(0));
+        assert_eq!(histogram.mean(), Duration::from_secs(0));
+        assert_eq!(histogram.buckets().count(), 0);
+    }
+
+    #[test]
+    fn test_percentiles_within_one_percent() {
+        let mut histogram = LatencyHistogram::new();
+        for micros in 1..=10_000u64 {
+            histogram.record(Duration::from_micros(micros));
+        }
+
+        assert_eq!(histogram.len(), 10_000);
+        assert_eq!(histogram.min(), Duration::from_micros(1));
+        assert_eq!(histogram.max(), Duration::from_micros(10_000));
+        assert_eq!(histogram.mean(), Duration::from_nanos(5_000_500));
+
+        for (percentile, expected_micros) in [(50.0, 5_000.0), (95.0, 9_500.0), (99.0, 9_900.0)] {
+            let actual = histogram.percentile(percentile).as_nanos() as f64 / 1000.0;
+            let error = (actual - expected_micros).abs() / expected_micros;
+            assert!(error < 0.01, "p{percentile} was {actual}us");
+        }
+        assert_eq!(histogram.percentile(100.0), histogram.max());
+    }
+
+    #[test]
+    fn test_memory_is_bounded() {
+        let mut histogram = LatencyHistogram::new();
+        for i in 0..1_000_000u64 {
+            histogram.record(Duration::from_nanos(i * 7919 % 50_000_000));
+        }
+
+        assert!(histogram.counts.len() < 4096);
+        assert_eq!(
+            histogram.buckets().map(|(_, count)| count).sum::<u64>(),
+            1_000_000
+        );
+    }
+
+    #[test]
+    fn test_merge_matches_single_histogram() {
+        let mut combined = LatencyHistogram::new();
+        let mut left = LatencyHistogram::new();
+        let mut right = LatencyHistogram::new();
+        for millis in 1..=200u64 {
+            let value = Duration::from_millis(millis);
+            combined.record(value);
+            if millis % 3 == 0 {
+                left.record(value);
+            } else {
+                right.record(value);
+            }
+        }
+
+        left.merge(&right);
+        assert_eq!(left, combined);
+    }
+}
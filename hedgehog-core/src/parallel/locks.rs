@@ -0,0 +1,482 @@
+//! Instrumented locks for definitive deadlock detection.
+//!
+//! Timeouts can only suggest that a concurrent test deadlocked. The wrappers
+//! in this module record the order in which every thread acquires locks in a
+//! shared [`LockGraph`]. Whenever a thread acquires a lock while holding
+//! another, an edge is added from the held lock to the new one; an edge that
+//! closes a cycle is a lock-order inversion, which is reported as a
+//! [`LockCycle`] naming the exact locks involved. Inversions are detected even
+//! on runs where the threads happen not to interleave into an actual deadlock.
+
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::fmt;
+use std::ops::{Deref, DerefMut};
+use std::sync::{Arc, Mutex, MutexGuard, PoisonError, RwLock, RwLockReadGuard, RwLockWriteGuard};
+use std::thread::{self, ThreadId};
+
+/// Identifier of a lock registered with a [`LockGraph`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct LockId(pub usize);
+
+/// A cycle in the lock acquisition order.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LockCycle {
+    /// Names of the locks in the cycle, in acquisition order. The first lock
+    /// is repeated at the end to close the cycle.
+    pub locks: Vec<String>,
+    /// Name of the thread whose acquisition closed the cycle
+    pub thread: String,
+}
+
+impl fmt::Display for LockCycle {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} (closed by {})", self.locks.join(" -> "), self.thread)
+    }
+}
+
+#[derive(Default)]
+struct GraphState {
+    names: Vec<String>,
+    edges: HashMap<LockId, HashSet<LockId>>,
+    held: HashMap<ThreadId, Vec<LockId>>,
+    cycles: Vec<LockCycle>,
+    reported: HashSet<Vec<LockId>>,
+}
+
+impl GraphState {
+    /// Find a path of acquisition-order edges from `from` to `to`.
+    fn path(&self, from: LockId, to: LockId) -> Option<Vec<LockId>> {
+        let mut previous: HashMap<LockId, LockId> = HashMap::new();
+        let mut queue = VecDeque::from([from]);
+        let mut visited = HashSet::from([from]);
+
+        while let Some(current) = queue.pop_front() {
+            if current == to {
+                let mut path = vec![to];
+                let mut node = to;
+                while let Some(&prev) = previous.get(&node) {
+                    path.push(prev);
+                    node = prev;
+                }
+                path.reverse();
+                return Some(path);
+            }
+            if let Some(next) = self.edges.get(&current) {
+                let mut next: Vec<_> = next.iter().copied().collect();
+                next.sort();
+                for lock in next {
+                    if visited.insert(lock) {
+                        previous.insert(lock, current);
+                        queue.push_back(lock);
+                    }
+                }
+            }
+        }
+
+        None
+    }
+
+    fn record_cycle(&mut self, mut cycle: Vec<LockId>) {
+        // Rotate to the smallest id so the same cycle is reported once no
+        // matter which edge closed it.
+        let start = (0..cycle.len())
+            .min_by_key(|&i| cycle[i])
+            .unwrap_or_default();
+        cycle.rotate_left(start);
+        if !self.reported.insert(cycle.clone()) {
+            return;
+        }
+
+        let mut locks: Vec<String> = cycle.iter().map(|id| self.names[id.0].clone()).collect();
+        locks.push(self.names[cycle[0].0].clone());
+        let current = thread::current();
+        let thread = current
+            .name()
+            .map(str::to_string)
+            .unwrap_or_else(|| format!("{:?}", current.id()));
+        self.cycles.push(LockCycle { locks, thread });
+    }
+}
+
+/// Shared record of lock acquisition order across threads.
+///
+/// Cloning a `LockGraph` shares the underlying record, so locks created from
+/// any clone are tracked together.
+#[derive(Clone, Default)]
+pub struct LockGraph {
+    state: Arc<Mutex<GraphState>>,
+}
+
+impl LockGraph {
+    /// Create an empty lock graph.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Create a tracked mutex registered with this graph.
+    pub fn mutex<T>(&self, name: &str, value: T) -> TrackedMutex<T> {
+        TrackedMutex {
+            id: self.register(name),
+            graph: self.clone(),
+            inner: Mutex::new(value),
+        }
+    }
+
+    /// Create a tracked read-write lock registered with this graph.
+    pub fn rwlock<T>(&self, name: &str, value: T) -> TrackedRwLock<T> {
+        TrackedRwLock {
+            id: self.register(name),
+            graph: self.clone(),
+            inner: RwLock::new(value),
+        }
+    }
+
+    /// Lock-order cycles detected so far.
+    pub fn cycles(&self) -> Vec<LockCycle> {
+        self.lock_state().cycles.clone()
+    }
+
+    /// Remove and return the lock-order cycles detected so far.
+    ///
+    /// The acquisition order itself is kept, so an inversion against an
+    /// order seen in an earlier test is still detected.
+    pub fn take_cycles(&self) -> Vec<LockCycle> {
+        std::mem::take(&mut self.lock_state().cycles)
+    }
+
+    /// Whether any lock-order cycle has been detected.
+    pub fn has_cycles(&self) -> bool {
+        !self.lock_state().cycles.is_empty()
+    }
+
+    /// Name of a registered lock.
+    pub fn lock_name(&self, id: LockId) -> Option<String> {
+        self.lock_state().names.get(id.0).cloned()
+    }
+
+    fn register(&self, name: &str) -> LockId {
+        let mut state = self.lock_state();
+        state.names.push(name.to_string());
+        LockId(state.names.len() - 1)
+    }
+
+    fn lock_state(&self) -> MutexGuard<'_, GraphState> {
+        self.state.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    /// Record that the current thread is about to block on `lock`.
+    fn before_acquire(&self, lock: LockId) {
+        let mut state = self.lock_state();
+        let held = state
+            .held
+            .get(&thread::current().id())
+            .cloned()
+            .unwrap_or_default();
+
+        for held_lock in held {
+            if held_lock == lock {
+                continue;
+            }
+            let is_new = state.edges.entry(held_lock).or_default().insert(lock);
+            if is_new {
+                if let Some(path) = state.path(lock, held_lock) {
+                    state.record_cycle(path);
+                }
+            }
+        }
+    }
+
+    /// Record that the current thread now holds `lock`.
+    fn acquired(&self, lock: LockId) {
+        self.lock_state()
+            .held
+            .entry(thread::current().id())
+            .or_default()
+            .push(lock);
+    }
+
+    /// Record that the current thread released `lock`.
+    fn released(&self, lock: LockId) {
+        let mut state = self.lock_state();
+        let thread_id = thread::current().id();
+        if let Some(held) = state.held.get_mut(&thread_id) {
+            if let Some(position) = held.iter().rposition(|&id| id == lock) {
+                held.remove(position);
+            }
+            if held.is_empty() {
+                state.held.remove(&thread_id);
+            }
+        }
+    }
+}
+
+impl fmt::Debug for LockGraph {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let state = self.lock_state();
+        f.debug_struct("LockGraph")
+            .field("locks", &state.names)
+            .field("cycles", &state.cycles)
+            .finish()
+    }
+}
+
+/// A mutex that records its acquisition order in a [`LockGraph`].
+///
+/// Poisoning is ignored: a panicking test thread is already reported as a
+/// failure by the concurrent runner.
+pub struct TrackedMutex<T> {
+    id: LockId,
+    graph: LockGraph,
+    inner: Mutex<T>,
+}
+
+impl<T> TrackedMutex<T> {
+    /// Identifier of this lock in its graph.
+    pub fn id(&self) -> LockId {
+        self.id
+    }
+
+    /// Acquire the mutex, blocking until it is available.
+    pub fn lock(&self) -> TrackedMutexGuard<'_, T> {
+        self.graph.before_acquire(self.id);
+        let guard = self.inner.lock().unwrap_or_else(PoisonError::into_inner);
+        self.graph.acquired(self.id);
+        TrackedMutexGuard { lock: self, guard }
+    }
+
+    /// Attempt to acquire the mutex without blocking.
+    ///
+    /// A non-blocking attempt cannot deadlock, so it adds no ordering edges.
+    pub fn try_lock(&self) -> Option<TrackedMutexGuard<'_, T>> {
+        let guard = match self.inner.try_lock() {
+            Ok(guard) => guard,
+            Err(std::sync::TryLockError::Poisoned(poisoned)) => poisoned.into_inner(),
+            Err(std::sync::TryLockError::WouldBlock) => return None,
+        };
+        self.graph.acquired(self.id);
+        Some(TrackedMutexGuard { lock: self, guard })
+    }
+}
+
+/// Guard returned by [`TrackedMutex::lock`].
+pub struct TrackedMutexGuard<'a, T> {
+    lock: &'a TrackedMutex<T>,
+    guard: MutexGuard<'a, T>,
+}
+
+impl<T> Deref for TrackedMutexGuard<'_, T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        &self.guard
+    }
+}
+
+impl<T> DerefMut for TrackedMutexGuard<'_, T> {
+    fn deref_mut(&mut self) -> &mut T {
+        &mut self.guard
+    }
+}
+
+impl<T> Drop for TrackedMutexGuard<'_, T> {
+    fn drop(&mut self) {
+        self.lock.graph.released(self.lock.id);
+    }
+}
+
+/// A read-write lock that records its acquisition order in a [`LockGraph`].
+pub struct TrackedRwLock<T> {
+    id: LockId,
+    graph: LockGraph,
+    inner: RwLock<T>,
+}
+
+impl<T> TrackedRwLock<T> {
+    /// Identifier of this lock in its graph.
+    pub fn id(&self) -> LockId {
+        self.id
+    }
+
+    /// Acquire shared read access, blocking until it is available.
+    pub fn read(&self) -> TrackedReadGuard<'_, T> {
+        self.graph.before_acquire(self.id);
+        let guard = self.inner.read().unwrap_or_else(PoisonError::into_inner);
+        self.graph.acquired(self.id);
+        TrackedReadGuard { lock: self, guard }
+    }
+
+    /// Acquire exclusive write access, blocking until it is available.
+    pub fn write(&self) -> TrackedWriteGuard<'_, T> {
+        self.graph.before_acquire(self.id);
+        let guard = self.inner.write().unwrap_or_else(PoisonError::into_inner);
+        self.graph.acquired(self.id);
+        TrackedWriteGuard { lock: self, guard }
+    }
+}
+
+/// Guard returned by [`TrackedRwLock::read`].
+pub struct TrackedReadGuard<'a, T> {
+    lock: &'a TrackedRwLock<T>,
+    guard: RwLockReadGuard<'a, T>,
+}
+
+impl<T> Deref for TrackedReadGuard<'_, T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        &self.guard
+    }
+}
+
+impl<T> Drop for TrackedReadGuard<'_, T> {
+    fn drop(&mut self) {
+        self.lock.graph.released(self.lock.id);
+    }
+}
+
+/// Guard returned by [`TrackedRwLock::write`].
+pub struct TrackedWriteGuard<'a, T> {
+    lock: &'a TrackedRwLock<T>,
+    guard: RwLockWriteGuard<'a, T>,
+}
+
+impl<T> Deref for TrackedWriteGuard<'_, T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        &self.guard
+    }
+}
+
+impl<T> DerefMut for TrackedWriteGuard<'_, T> {
+    fn deref_mut(&mut self) -> &mut T {
+        &mut self.guard
+    }
+}
+
+impl<T> Drop for TrackedWriteGuard<'_, T> {
+    fn drop(&mut self) {
+        self.lock.graph.released(self.lock.id);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_consistent_order_has_no_cycle() {
+        let graph = LockGraph::new();
+        let a = graph.mutex("a", 0);
+        let b = graph.mutex("b", 0);
+
+        for _ in 0..3 {
+            let _ga = a.lock();
+            let _gb = b.lock();
+        }
+
+        assert!(!graph.has_cycles());
+    }
+
+    #[test]
+    fn test_inverted_order_reports_cycle() {
+        let graph = LockGraph::new();
+        let a = graph.mutex("a", 0);
+        let b = graph.mutex("b", 0);
+
+        {
+            let _ga = a.lock();
+            let _gb = b.lock();
+        }
+        {
+            let _gb = b.lock();
+            let _ga = a.lock();
+        }
+
+        let cycles = graph.take_cycles();
+        assert_eq!(cycles.len(), 1);
+        assert_eq!(cycles[0].locks, vec!["a", "b", "a"]);
+        assert!(!graph.has_cycles());
+    }
+
+    #[test]
+    fn test_cycle_across_threads_and_rwlocks() {
+        let graph = LockGraph::new();
+        let a = Arc::new(graph.rwlock("config", 0));
+        let b = Arc::new(graph.mutex("cache", 0));
+        let c = Arc::new(graph.mutex("log", 0));
+
+        // config -> cache, cache -> log, log -> config: each thread runs to
+        // completion before the next starts, so there is never an actual
+        // deadlock.
+        {
+            let (a, b) = (Arc::clone(&a), Arc::clone(&b));
+            thread::spawn(move || {
+                let _config = a.read();
+                let _cache = b.lock();
+            })
+            .join()
+            .unwrap();
+        }
+        {
+            let (b, c) = (Arc::clone(&b), Arc::clone(&c));
+            thread::spawn(move || {
+                let _cache = b.lock();
+                let _log = c.lock();
+            })
+            .join()
+            .unwrap();
+        }
+        {
+            let (a, c) = (Arc::clone(&a), Arc::clone(&c));
+            thread::spawn(move || {
+                let _log = c.lock();
+                let _config = a.write();
+            })
+            .join()
+            .unwrap();
+        }
+
+        let cycles = graph.cycles();
+        assert_eq!(cycles.len(), 1);
+        assert_eq!(cycles[0].locks, vec!["config", "cache", "log", "config"]);
+        assert!(cycles[0]
+            .to_string()
+            .starts_with("config -> cache -> log -> config"));
+    }
+
+    #[test]
+    fn test_try_lock_adds_no_edges() {
+        let graph = LockGraph::new();
+        let a = graph.mutex("a", 0);
+        let b = graph.mutex("b", 0);
+
+        {
+            let _ga = a.lock();
+            let _gb = b.try_lock().unwrap();
+        }
+        {
+            let _gb = b.lock();
+            let _ga = a.try_lock().unwrap();
+        }
+
+        assert!(!graph.has_cycles());
+    }
+
+    #[test]
+    fn test_reentrant_reads_and_guard_mutation() {
+        let graph = LockGraph::new();
+        let lock = graph.rwlock("shared", vec![1]);
+
+        {
+            let _r1 = lock.read();
+            let r2 = lock.read();
+            assert_eq!(*r2, vec![1]);
+        }
+        lock.write().push(2);
+
+        assert_eq!(*lock.read(), vec![1, 2]);
+        assert!(!graph.has_cycles());
+        assert_eq!(graph.lock_name(lock.id()), Some("shared".to_string()));
+    }
+}
@@ -0,0 +1,121 @@
+//! Resident memory sampling for load and parallel tests.
+//!
+//! A [`MemorySampler`] polls the process's resident set size on a background
+//! thread while a test runs. Sampling is supported on Linux, where the value
+//! is read from `/proc/self/status`; on other platforms no samples are taken
+//! and the reported usage is `None`.
+
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, Instant};
+
+/// Memory usage observed while a test ran.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct MemoryUsage {
+    /// Highest resident set size seen, in megabytes
+    pub peak_mb: f64,
+    /// Average resident set size across samples, in megabytes
+    pub avg_mb: f64,
+    /// Number of samples taken
+    pub samples: usize,
+}
+
+/// Current resident set size of this process in bytes, if the platform
+/// supports reading it.
+pub fn resident_memory_bytes() -> Option<u64> {
+    #[cfg(target_os = "linux")]
+    {
+        let status = std::fs::read_to_string("/proc/self/status").ok()?;
+        let line = status.lines().find(|line| line.starts_with("VmRSS:"))?;
+        let kilobytes: u64 = line.split_whitespace().nth(1)?.parse().ok()?;
+        Some(kilobytes * 1024)
+    }
+    #[cfg(not(target_os = "linux"))]
+    {
+        None
+    }
+}
+
+/// Samples resident memory on a background thread until stopped.
+pub struct MemorySampler {
+    stop: Arc<AtomicBool>,
+    handle: JoinHandle<Vec<u64>>,
+}
+
+impl MemorySampler {
+    /// Start sampling every `interval`.
+    ///
+    /// A sample is always taken when sampling starts and when it stops, so
+    /// runs shorter than the interval are still measured.
+    pub fn start(interval: Duration) -> Self {
+        let stop = Arc::new(AtomicBool::new(false));
+        let stopped = Arc::clone(&stop);
+        let handle = thread::spawn(move || {
+            let mut samples = Vec::new();
+            let mut next_sample = Instant::now();
+            while !stopped.load(Ordering::Relaxed) {
+                if Instant::now() >= next_sample {
+                    samples.extend(resident_memory_bytes());
+                    next_sample += interval;
+                }
+                thread::sleep(interval.min(Duration::from_millis(5)));
+            }
+            samples.extend(resident_memory_bytes());
+            samples
+        });
+
+        MemorySampler { stop, handle }
+    }
+
+    /// Stop sampling and summarise the samples taken.
+    ///
+    /// Returns `None` when the platform does not support sampling.
+    pub fn stop(self) -> Option<MemoryUsage> {
+        self.stop.store(true, Ordering::Relaxed);
+        let samples = self.handle.join().ok()?;
+        summarise(&samples)
+    }
+}
+
+fn summarise(samples: &[u64]) -> Option<MemoryUsage> {
+    const BYTES_PER_MB: f64 = 1024.0 * 1024.0;
+
+    let peak = *samples.iter().max()?;
+    let total: u128 = samples.iter().map(|&bytes| bytes as u128).sum();
+    Some(MemoryUsage {
+        peak_mb: peak as f64 / BYTES_PER_MB,
+        avg_mb: total as f64 / samples.len() as f64 / BYTES_PER_MB,
+        samples: samples.len(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_summarise_samples() {
+        let mb = 1024 * 1024;
+        let usage = summarise(&[10 * mb, 30 * mb, 20 * mb]).unwrap();
+
+        assert_eq!(usage.peak_mb, 30.0);
+        assert_eq!(usage.avg_mb, 20.0);
+        assert_eq!(usage.samples, 3);
+        assert_eq!(summarise(&[]), None);
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn test_sampler_observes_resident_memory() {
+        assert!(resident_memory_bytes().unwrap() > 0);
+
+        let sampler = MemorySampler::start(Duration::from_millis(1));
+        thread::sleep(Duration::from_millis(20));
+        let usage = sampler.stop().unwrap();
+
+        assert!(usage.samples >= 2);
+        assert!(usage.peak_mb >= usage.avg_mb);
+        assert!(usage.avg_mb > 0.0);
+    }
+}
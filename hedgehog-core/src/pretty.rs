@@ -0,0 +1,153 @@
+//! Readable counterexamples for large values.
+//!
+//! Small counterexamples read best on one line, exactly as `{:?}` prints
+//! them. Large structs and vectors don't: a hundred-element vector of
+//! structs is a wall of text. Those are printed with `{:#?}` instead, one
+//! field or element per line, and unless full output is asked for, long
+//! containers and long lines are cut short with a note of what was left out.
+
+use std::fmt::Debug;
+
+/// Values whose compact form is at most this long are printed on one line.
+const COMPACT_WIDTH: usize = 80;
+
+/// Elements or fields shown per container before the rest are counted.
+const MAX_ITEMS: usize = 10;
+
+/// Characters shown per line before the rest are counted.
+const MAX_LINE: usize = 160;
+
+/// Show `value` as a counterexample: compact if short, otherwise multi-line,
+/// abbreviated unless `full` is set.
+pub(crate) fn counterexample<T: Debug + ?Sized>(value: &T, full: bool) -> String {
+    let compact = format!("{value:?}");
+    if compact.chars().count() <= COMPACT_WIDTH {
+        return compact;
+    }
+    let pretty = format!("{value:#?}");
+    if full {
+        pretty
+    } else {
+        abbreviate(&pretty)
+    }
+}
+
+/// An open bracket in the `{:#?}` output.
+struct Frame {
+    indent: usize,
+    items: usize,
+    hiding: bool,
+}
+
+/// Cut `{:#?}` output down to the first few items of each container and the
+/// start of each line.
+///
+/// Pretty debug output puts each item of a container on lines indented four
+/// spaces more than the line that opened it, and the closing bracket back at
+/// the opening line's indentation, which is all this relies on.
+fn abbreviate(pretty: &str) -> String {
+    let mut out = Vec::new();
+    let mut frames: Vec<Frame> = Vec::new();
+
+    for line in pretty.lines() {
+        let trimmed = line.trim_start();
+        let indent = line.len() - trimmed.len();
+
+        while frames.last().is_some_and(|frame| frame.indent > indent) {
+            frames.pop();
+        }
+
+        let closes = trimmed.starts_with([']', '}', ')'])
+            && frames.last().is_some_and(|frame| frame.indent == indent);
+        if closes {
+            let frame = frames.pop().expect("checked above");
+            let visible = !frames.iter().any(|frame| frame.hiding);
+            if visible && frame.items > MAX_ITEMS {
+                out.push(format!(
+                    "{}... and {} more ({} in total)",
+                    " ".repeat(indent + 4),
+                    frame.items - MAX_ITEMS,
+                    frame.items
+                ));
+            }
+            if visible {
+                out.push(line.to_string());
+            }
+            continue;
+        }
+
+        if let Some(frame) = frames.last_mut() {
+            if indent == frame.indent + 4 {
+                frame.items += 1;
+                frame.hiding = frame.items > MAX_ITEMS;
+            }
+        }
+        if !frames.iter().any(|frame| frame.hiding) {
+            out.push(truncate(line));
+        }
+        if trimmed.ends_with(['[', '{', '(']) {
+            frames.push(Frame {
+                indent,
+                items: 0,
+                hiding: false,
+            });
+        }
+    }
+
+    out.join("\n")
+}
+
+fn truncate(line: &str) -> String {
+    let length = line.chars().count();
+    if length <= MAX_LINE {
+        return line.to_string();
+    }
+    let kept: String = line.chars().take(MAX_LINE).collect();
+    format!("{kept}... ({} more characters)", length - MAX_LINE)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Debug)]
+    #[allow(dead_code)]
+    struct Order {
+        id: u32,
+        items: Vec<u32>,
+        note: String,
+    }
+
+    #[test]
+    fn test_small_values_stay_compact() {
+        assert_eq!(counterexample(&vec![1, 2, 3], false), "[1, 2, 3]");
+        assert_eq!(counterexample("abc", false), "\"abc\"");
+    }
+
+    #[test]
+    fn test_large_values_are_abbreviated() {
+        let order = Order {
+            id: 7,
+            items: (0..100).collect(),
+            note: "x".repeat(500),
+        };
+        let shown = counterexample(&order, false);
+        assert!(shown.starts_with("Order {\n    id: 7,\n    items: [\n        0,\n"));
+        assert!(shown.contains("        9,\n        ... and 90 more (100 in total)\n    ],"));
+        assert!(!shown.contains("        10,"));
+        assert!(shown.contains("... (353 more characters)"));
+        assert!(shown.ends_with('}'));
+
+        let full = counterexample(&order, true);
+        assert_eq!(full, format!("{order:#?}"));
+    }
+
+    #[test]
+    fn test_hidden_items_hide_their_contents() {
+        let nested: Vec<Vec<u32>> = (0..12).map(|n| vec![n; 12]).collect();
+        let shown = counterexample(&nested, false);
+        // Ten inner vectors are shown, each cut to ten elements
+        assert_eq!(shown.matches("... and 2 more (12 in total)").count(), 11);
+        assert!(!shown.contains("        10,\n"));
+    }
+}
@@ -0,0 +1,496 @@
+//! Named configuration profiles.
+//!
+//! A profile adjusts the default [`Config`] for a kind of run: `dev` for quick
+//! local runs, `ci` and `nightly` for progressively more thorough ones. The
+//! active profile is named by the `HEDGEHOG_PROFILE` environment variable, or
+//! by the top-level `profile` key of a `hedgehog.toml` in the current
+//! directory or one of its parents. That file can also define profiles of its
+//! own, or override the built-in ones:
+//!
+//! ```toml
+//! profile = "dev"
+//!
+//! [profile.ci]
+//! tests = 2000
+//! timeout_ms = 60000
+//! ```
+//!
+//! On top of the profile, `HEDGEHOG_TEST_MULTIPLIER` scales the number of
+//! tests, `HEDGEHOG_MAX_SHRINKS` sets the shrink budget and
+//! `HEDGEHOG_SHRINK_GRAPH` names a directory for shrink graphs; see
+//! [`crate::shrink_graph`]. `HEDGEHOG_HISTORY` names a directory to record
+//! runs in; see [`crate::history`]. Each property writes a file of its own
+//! there, unless the variable is a path with a `{property}` in it to name
+//! the files differently. `HEDGEHOG_VERIFY_DETERMINISM` runs each case
+//! twice; see [`crate::determinism`].
+//!
+//! [`Config::default`] applies only the test multiplier and shrink budget;
+//! [`Config::from_env`] reads all of these.
+
+use crate::data::{Config, PROPERTY_PLACEHOLDER};
+use crate::determinism::DETERMINISM_ENV_VAR;
+use crate::error::{HedgehogError, Result};
+use crate::history::HISTORY_ENV_VAR;
+use crate::shrink_graph::SHRINK_GRAPH_ENV_VAR;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+/// Environment variable naming the active profile.
+pub const PROFILE_ENV_VAR: &str = "HEDGEHOG_PROFILE";
+
+/// Environment variable scaling the number of tests (and discards allowed).
+pub const TEST_MULTIPLIER_ENV_VAR: &str = "HEDGEHOG_TEST_MULTIPLIER";
+
+/// Environment variable setting the shrink budget.
+pub const MAX_SHRINKS_ENV_VAR: &str = "HEDGEHOG_MAX_SHRINKS";
+
+/// Name of the file profiles are read from.
+pub const PROFILE_FILE_NAME: &str = "hedgehog.toml";
+
+/// Settings a profile overrides; anything left `None` keeps its default.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Profile {
+    pub tests: Option<usize>,
+    pub shrinks: Option<usize>,
+    pub size: Option<usize>,
+    pub discards: Option<usize>,
+    pub timeout: Option<Duration>,
+}
+
+impl Profile {
+    /// One of the built-in profiles: `dev`, `ci` or `nightly`.
+    pub fn builtin(name: &str) -> Option<Profile> {
+        let (tests, shrinks, discards) = match name {
+            "dev" => (100, 1_000, 100),
+            "ci" => (1_000, 10_000, 1_000),
+            "nightly" => (10_000, 100_000, 10_000),
+            _ => return None,
+        };
+        Some(Profile {
+            tests: Some(tests),
+            shrinks: Some(shrinks),
+            discards: Some(discards),
+            ..Profile::default()
+        })
+    }
+
+    /// Apply this profile's settings to a config.
+    pub fn apply(&self, mut config: Config) -> Config {
+        config.test_limit = self.tests.unwrap_or(config.test_limit);
+        config.shrink_limit = self.shrinks.unwrap_or(config.shrink_limit);
+        config.size_limit = self.size.unwrap_or(config.size_limit);
+        config.discard_limit = self.discards.unwrap_or(config.discard_limit);
+        config.timeout = self.timeout.or(config.timeout);
+        config
+    }
+
+    /// This profile with any settings from `other` taking precedence.
+    fn overridden_by(&self, other: &Profile) -> Profile {
+        Profile {
+            tests: other.tests.or(self.tests),
+            shrinks: other.shrinks.or(self.shrinks),
+            size: other.size.or(self.size),
+            discards: other.discards.or(self.discards),
+            timeout: other.timeout.or(self.timeout),
+        }
+    }
+}
+
+/// The contents of a `hedgehog.toml`.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ProfileFile {
+    /// Profile used when `HEDGEHOG_PROFILE` is not set
+    pub default_profile: Option<String>,
+    /// Profiles defined in `[profile.<name>]` sections
+    pub profiles: HashMap<String, Profile>,
+}
+
+impl ProfileFile {
+    /// Parse the contents of a `hedgehog.toml`.
+    ///
+    /// Only the subset of TOML profiles need is understood: a top-level
+    /// `profile` string, and `[profile.<name>]` sections of integer settings
+    /// (`tests`, `shrinks`, `size`, `discards`, `timeout_ms`).
+    pub fn parse(text: &str) -> Result<ProfileFile> {
+        let mut file = ProfileFile::default();
+        let mut section: Option<String> = None;
+
+        for (index, raw_line) in text.lines().enumerate() {
+            let invalid = |message: String| HedgehogError::InvalidConfig {
+                message: format!("{PROFILE_FILE_NAME} line {}: {message}", index + 1),
+            };
+            let line = raw_line.split('#').next().unwrap_or("").trim();
+            if line.is_empty() {
+                continue;
+            }
+
+            if let Some(header) = line.strip_prefix('[').and_then(|l| l.strip_suffix(']')) {
+                let name = header
+                    .trim()
+                    .strip_prefix("profile.")
+                    .ok_or_else(|| invalid(format!("unknown section [{header}]")))?;
+                file.profiles.entry(name.to_string()).or_default();
+                section = Some(name.to_string());
+                continue;
+            }
+
+            let (key, value) = line
+                .split_once('=')
+                .map(|(key, value)| (key.trim(), value.trim()))
+                .ok_or_else(|| invalid(format!("expected `key = value`, got `{line}`")))?;
+
+            let Some(name) = &section else {
+                if key != "profile" {
+                    return Err(invalid(format!("unknown setting `{key}`")));
+                }
+                let profile = value
+                    .strip_prefix('"')
+                    .and_then(|v| v.strip_suffix('"'))
+                    .ok_or_else(|| invalid("profile must be a quoted string".to_string()))?;
+                file.default_profile = Some(profile.to_string());
+                continue;
+            };
+
+            let number: usize = value
+                .replace('_', "")
+                .parse()
+                .map_err(|_| invalid(format!("`{key}` must be a whole number")))?;
+            let profile = file.profiles.entry(name.clone()).or_default();
+            match key {
+                "tests" => profile.tests = Some(number),
+                "shrinks" => profile.shrinks = Some(number),
+                "size" => profile.size = Some(number),
+                "discards" => profile.discards = Some(number),
+                "timeout_ms" => profile.timeout = Some(Duration::from_millis(number as u64)),
+                _ => return Err(invalid(format!("unknown profile setting `{key}`"))),
+            }
+        }
+
+        Ok(file)
+    }
+
+    /// Find and parse the nearest `hedgehog.toml`, starting from the current
+    /// directory.
+    pub fn load() -> Result<Option<ProfileFile>> {
+        let Some(path) = std::env::current_dir()
+            .ok()
+            .and_then(|dir| find_profile_file(&dir))
+        else {
+            return Ok(None);
+        };
+
+        let text =
+            std::fs::read_to_string(&path).map_err(|error| HedgehogError::InvalidConfig {
+                message: format!("could not read {}: {error}", path.display()),
+            })?;
+        ProfileFile::parse(&text).map(Some)
+    }
+
+    /// The profile with the given name: a section of this file, a built-in
+    /// profile, or a built-in profile overridden by a section of this file.
+    pub fn profile(&self, name: &str) -> Option<Profile> {
+        match (Profile::builtin(name), self.profiles.get(name)) {
+            (Some(builtin), Some(overrides)) => Some(builtin.overridden_by(overrides)),
+            (builtin, overrides) => builtin.or_else(|| overrides.cloned()),
+        }
+    }
+}
+
+fn find_profile_file(start: &Path) -> Option<PathBuf> {
+    start
+        .ancestors()
+        .map(|dir| dir.join(PROFILE_FILE_NAME))
+        .find(|path| path.is_file())
+}
+
+/// Resolve the active profile from the environment variable's value and the
+/// profile file, if any.
+fn resolve(requested: Option<&str>, file: Option<&ProfileFile>) -> Result<Option<Profile>> {
+    let name = match requested.or(file.and_then(|f| f.default_profile.as_deref())) {
+        Some(name) => name,
+        None => return Ok(None),
+    };
+
+    let profile = match file {
+        Some(file) => file.profile(name),
+        None => Profile::builtin(name),
+    };
+    profile
+        .map(Some)
+        .ok_or_else(|| HedgehogError::InvalidConfig {
+            message: format!("unknown profile '{name}'"),
+        })
+}
+
+/// The profile selected by the environment, if any.
+pub fn active_profile() -> Result<Option<Profile>> {
+    let requested = std::env::var(PROFILE_ENV_VAR).ok();
+    let file = ProfileFile::load()?;
+    resolve(requested.as_deref(), file.as_ref())
+}
+
+/// Overrides applied after the profile, read from environment variables.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct EnvOverrides {
+    /// Factor applied to the number of tests and discards allowed
+    pub test_multiplier: Option<f64>,
+    /// Shrink budget replacing the profile's
+    pub max_shrinks: Option<usize>,
+}
+
+impl EnvOverrides {
+    /// Read `HEDGEHOG_TEST_MULTIPLIER` and `HEDGEHOG_MAX_SHRINKS`.
+    pub fn from_env() -> Result<EnvOverrides> {
+        EnvOverrides::parse(
+            std::env::var(TEST_MULTIPLIER_ENV_VAR).ok().as_deref(),
+            std::env::var(MAX_SHRINKS_ENV_VAR).ok().as_deref(),
+        )
+    }
+
+    fn parse(multiplier: Option<&str>, max_shrinks: Option<&str>) -> Result<EnvOverrides> {
+        let test_multiplier = multiplier
+            .map(|value| match value.trim().parse::<f64>() {
+                Ok(factor) if factor.is_finite() && factor > 0.0 => Ok(factor),
+                _ => Err(HedgehogError::InvalidConfig {
+                    message: format!(
+                        "{TEST_MULTIPLIER_ENV_VAR} must be a positive number, got '{value}'"
+                    ),
+                }),
+            })
+            .transpose()?;
+        let max_shrinks = max_shrinks
+            .map(|value| {
+                value
+                    .trim()
+                    .parse::<usize>()
+                    .map_err(|_| HedgehogError::InvalidConfig {
+                        message: format!(
+                            "{MAX_SHRINKS_ENV_VAR} must be a whole number, got '{value}'"
+                        ),
+                    })
+            })
+            .transpose()?;
+
+        Ok(EnvOverrides {
+            test_multiplier,
+            max_shrinks,
+        })
+    }
+
+    /// Apply these overrides to a config.
+    ///
+    /// Scaled counts are rounded up, so a run never drops to zero tests.
+    pub fn apply(&self, mut config: Config) -> Config {
+        if let Some(factor) = self.test_multiplier {
+            let scale = |count: usize| (count as f64 * factor).ceil() as usize;
+            config.test_limit = scale(config.test_limit);
+            config.discard_limit = scale(config.discard_limit);
+        }
+        config.shrink_limit = self.max_shrinks.unwrap_or(config.shrink_limit);
+        config
+    }
+}
+
+/// The output path an environment variable gives each property: the
+/// variable's value if it has a `{property}` in it, or a file named after
+/// the property in the directory it names.
+fn output_template(variable: &str, extension: &str) -> Option<PathBuf> {
+    let value = PathBuf::from(std::env::var_os(variable)?);
+    if value.to_string_lossy().contains(PROPERTY_PLACEHOLDER) {
+        Some(value)
+    } else {
+        Some(value.join(format!("{PROPERTY_PLACEHOLDER}.{extension}")))
+    }
+}
+
+impl Config {
+    /// The built-in defaults with the active profile and environment
+    /// overrides applied, for runs whose thoroughness is chosen by the
+    /// environment rather than the code.
+    ///
+    /// Fails if the environment is misconfigured, such as with an unknown
+    /// profile or a malformed `hedgehog.toml`, so a typo in CI fails the run
+    /// rather than silently testing less.
+    ///
+    /// # Example
+    /// ```rust
+    /// use hedgehog_core::*;
+    ///
+    /// let config = Config::from_env().expect("hedgehog settings are valid");
+    /// let prop = for_all(Gen::int_range(0, 10), |&n: &i32| n <= 10);
+    /// assert!(matches!(prop.run(&config), TestResult::Pass { .. }));
+    /// ```
+    pub fn from_env() -> Result<Config> {
+        let profile = active_profile()?;
+        let overrides = EnvOverrides::from_env()?;
+        let mut config = overrides.apply(profile.unwrap_or_default().apply(Config::builtin()));
+        config.shrink_graph = output_template(SHRINK_GRAPH_ENV_VAR, "dot");
+        config.history = output_template(HISTORY_ENV_VAR, "txt");
+        config.verify_determinism = std::env::var_os(DETERMINISM_ENV_VAR).is_some();
+        Ok(config)
+    }
+
+    /// The default config with the named profile applied, taking any
+    /// `hedgehog.toml` into account.
+    pub fn from_profile(name: &str) -> Result<Config> {
+        let file = ProfileFile::load()?;
+        let profile = resolve(Some(name), file.as_ref())?.unwrap_or_default();
+        Ok(profile.apply(Config::builtin()))
+    }
+
+    /// Create a new config with a profile's settings applied.
+    pub fn with_profile(self, profile: &Profile) -> Self {
+        profile.apply(self)
+    }
+
+    /// A config for the edit-and-test loop: 25 cases, with the run and its
+    /// shrinking each stopped after a second, so properties answer while the
+    /// change is fresh. Rare failures are left to CI.
+    ///
+    /// Like [`Config::builtin`], presets ignore the active profile.
+    pub fn quick() -> Self {
+        Config::builtin()
+            .with_tests(25)
+            .with_shrinks(200)
+            .with_timeout(Duration::from_secs(1))
+            .with_shrink_deadline(Duration::from_secs(1))
+    }
+
+    /// A config for CI: the `ci` profile's 1,000 cases, at most a minute per
+    /// property so one slow property can't hold up the pipeline, and at most
+    /// 30 seconds shrinking. Counterexamples are printed in full, since the
+    /// log is the only record of them.
+    pub fn ci() -> Self {
+        Config::builtin()
+            .with_profile(&builtin("ci"))
+            .with_timeout(Duration::from_secs(60))
+            .with_shrink_deadline(Duration::from_secs(30))
+            .with_full_counterexamples()
+    }
+
+    /// A config for scheduled runs hunting rare failures: the `nightly`
+    /// profile's 10,000 cases and 100,000 shrinks with no time limits, and
+    /// a failure followed by the smallest size it fails at.
+    pub fn thorough() -> Self {
+        Config::builtin()
+            .with_profile(&builtin("nightly"))
+            .with_size_diagnostics()
+    }
+}
+
+/// A built-in profile the presets are based on.
+fn builtin(name: &str) -> Profile {
+    Profile::builtin(name).unwrap_or_else(|| unreachable!("{name} is a built-in profile"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const FILE: &str = r#"
+# Used when HEDGEHOG_PROFILE is unset
+profile = "quick"
+
+[profile.quick]
+tests = 20
+timeout_ms = 1_500
+
+[profile.ci]
+shrinks = 50  # override only the shrink budget
+"#;
+
+    #[test]
+    fn test_parse_profile_file() {
+        let file = ProfileFile::parse(FILE).unwrap();
+        assert_eq!(file.default_profile.as_deref(), Some("quick"));
+        assert_eq!(
+            file.profiles["quick"],
+            Profile {
+                tests: Some(20),
+                timeout: Some(Duration::from_millis(1500)),
+                ..Profile::default()
+            }
+        );
+
+        // Sections override built-in profiles setting by setting
+        let ci = file.profile("ci").unwrap();
+        assert_eq!(ci.shrinks, Some(50));
+        assert_eq!(ci.tests, Profile::builtin("ci").unwrap().tests);
+    }
+
+    #[test]
+    fn test_parse_errors_name_the_line() {
+        let error = ProfileFile::parse("[profile.ci]\ntests = many\n").unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "Invalid configuration: hedgehog.toml line 2: `tests` must be a whole number"
+        );
+        assert!(ProfileFile::parse("[tool]\n").is_err());
+        assert!(ProfileFile::parse("[profile.ci]\ncolour = 1\n").is_err());
+        assert!(ProfileFile::parse("profile = ci\n").is_err());
+    }
+
+    #[test]
+    fn test_resolve_prefers_environment_over_file() {
+        let file = ProfileFile::parse(FILE).unwrap();
+
+        let quick = resolve(None, Some(&file)).unwrap().unwrap();
+        assert_eq!(quick.tests, Some(20));
+
+        let nightly = resolve(Some("nightly"), Some(&file)).unwrap().unwrap();
+        assert_eq!(nightly, Profile::builtin("nightly").unwrap());
+
+        assert_eq!(resolve(None, None).unwrap(), None);
+        assert!(resolve(Some("weekly"), None).is_err());
+    }
+
+    #[test]
+    fn test_profile_applies_to_config() {
+        let config = Config::builtin().with_profile(&Profile {
+            tests: Some(5),
+            timeout: Some(Duration::from_secs(1)),
+            ..Profile::default()
+        });
+        assert_eq!(config.test_limit, 5);
+        assert_eq!(config.shrink_limit, Config::builtin().shrink_limit);
+        assert_eq!(config.timeout, Some(Duration::from_secs(1)));
+    }
+
+    #[test]
+    fn test_presets_scale_with_the_builtin_profiles() {
+        let quick = Config::quick();
+        assert!(quick.test_limit < Config::builtin().test_limit);
+        assert_eq!(quick.timeout, Some(Duration::from_secs(1)));
+
+        let ci = Config::ci();
+        assert_eq!(ci.test_limit, 1_000);
+        assert_eq!(ci.shrink_deadline, Some(Duration::from_secs(30)));
+        assert!(ci.full_counterexamples);
+
+        let thorough = Config::thorough();
+        assert_eq!(thorough.test_limit, 10_000);
+        assert_eq!(thorough.timeout, None);
+        assert!(thorough.size_diagnostics);
+    }
+
+    #[test]
+    fn test_env_overrides() {
+        let overrides = EnvOverrides::parse(Some("10"), Some("50")).unwrap();
+        let config = overrides.apply(Config::builtin());
+        assert_eq!(config.test_limit, 1000);
+        assert_eq!(config.discard_limit, 1000);
+        assert_eq!(config.shrink_limit, 50);
+
+        let fewer = EnvOverrides::parse(Some("0.001"), None).unwrap();
+        assert_eq!(fewer.apply(Config::builtin()).test_limit, 1);
+
+        assert_eq!(
+            EnvOverrides::parse(None, None).unwrap(),
+            EnvOverrides::default()
+        );
+        assert!(EnvOverrides::parse(Some("0"), None).is_err());
+        assert!(EnvOverrides::parse(Some("lots"), None).is_err());
+        assert!(EnvOverrides::parse(None, Some("-1")).is_err());
+    }
+}
@@ -1,8 +1,23 @@
 //! Property definitions for property-based testing.
 
+use crate::compare::diff;
+use crate::determinism;
 use crate::error::ShrinkStep;
+use crate::history;
+use crate::memo::Memo;
+use crate::minimize::{Shrink, MINIMIZE_ENV_VAR, MINIMIZE_OUTPUT_ENV_VAR};
+use crate::observe;
+use crate::regression::{
+    corpus_entries, store_corpus_entry, RegressionExport, RustLiteral, CORPUS_CHECK_ENV_VAR,
+    DEFAULT_CORPUS_DIR,
+};
+use crate::shrink_graph::{self, ShrinkOutcome};
 use crate::{data::*, error::*, gen::*, tree::*};
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
+use std::hash::{Hash, Hasher};
+use std::panic::Location;
+use std::rc::Rc;
+use std::time::{Duration, Instant};
 
 /// Strategy for integrating explicit examples with property-based testing.
 #[derive(Debug, Clone, PartialEq)]
@@ -17,12 +32,71 @@ pub enum ExampleStrategy {
     ExamplesUpTo(usize),
 }
 
+/// A known problem with a property, which lets it fail without failing
+/// the suite; see [`Property::expect_failure`] and [`Property::quarantined`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PropertyMarker {
+    /// The property is known to fail, for the reason given, such as an
+    /// issue number.
+    ExpectedFailure(String),
+    /// The property is unreliable and is kept out of the suite's verdict
+    /// until it's fixed.
+    Quarantined,
+}
+
 /// Statistics gathered during property testing.
 #[derive(Debug, Clone, PartialEq)]
 pub struct TestStatistics {
     pub classifications: HashMap<String, usize>,
     pub collections: HashMap<String, Vec<f64>>,
     pub total_tests: usize,
+    pub discards: usize,
+    pub retried_cases: Vec<RetriedCase>,
+    pub timings: PhaseTimings,
+    /// Generated cases equal to one generated earlier in the same run, when
+    /// the property tracks duplicates
+    pub duplicates: usize,
+    /// The largest size an input was generated at
+    pub max_size: usize,
+    /// The description of the generator inputs came from, if it has one;
+    /// see [`Gen::named`]
+    pub generator: Option<String>,
+}
+
+/// Outcomes of a case that failed and was re-run under a retry policy.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RetriedCase {
+    /// The input that was retried
+    pub input: String,
+    /// Number of attempts that passed
+    pub passes: usize,
+    /// Number of attempts that failed, including the first
+    pub failures: usize,
+}
+
+/// Time a property run spent in each phase.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct PhaseTimings {
+    /// Generating inputs, including their shrink trees
+    pub generation: Duration,
+    /// Running the test function on generated inputs
+    pub execution: Duration,
+    /// Running the test function on shrinks of a failing input
+    pub shrinking: Duration,
+}
+
+impl PhaseTimings {
+    /// Time spent across all phases.
+    pub fn total(&self) -> Duration {
+        self.generation + self.execution + self.shrinking
+    }
+
+    /// Add timings measured separately, such as on another thread.
+    pub fn merge(&mut self, other: &PhaseTimings) {
+        self.generation += other.generation;
+        self.execution += other.execution;
+        self.shrinking += other.shrinking;
+    }
 }
 
 impl Default for TestStatistics {
@@ -37,6 +111,12 @@ impl TestStatistics {
             classifications: HashMap::new(),
             collections: HashMap::new(),
             total_tests: 0,
+            discards: 0,
+            retried_cases: Vec::new(),
+            timings: PhaseTimings::default(),
+            duplicates: 0,
+            max_size: 0,
+            generator: None,
         }
     }
 
@@ -50,20 +130,276 @@ impl TestStatistics {
             .or_default()
             .push(value);
     }
+
+    /// Merge statistics gathered separately, such as on another thread.
+    pub fn merge(&mut self, other: &TestStatistics) {
+        for (name, count) in &other.classifications {
+            *self.classifications.entry(name.clone()).or_insert(0) += count;
+        }
+        for (name, values) in &other.collections {
+            self.collections
+                .entry(name.clone())
+                .or_default()
+                .extend_from_slice(values);
+        }
+        self.total_tests += other.total_tests;
+        self.discards += other.discards;
+        self.retried_cases
+            .extend(other.retried_cases.iter().cloned());
+        self.timings.merge(&other.timings);
+        self.duplicates += other.duplicates;
+        self.max_size = self.max_size.max(other.max_size);
+        if self.generator.is_none() {
+            self.generator = other.generator.clone();
+        }
+    }
+
+    /// Whether more than half of the cases were duplicates, which usually
+    /// means the test count is high for how many distinct values the
+    /// generator can produce.
+    pub fn mostly_duplicates(&self) -> bool {
+        self.total_tests > 0 && self.duplicates * 2 > self.total_tests
+    }
+
+    /// Whether any classifications, collections, discards or retries were
+    /// recorded. Timings and duplicates are not considered.
+    pub fn is_empty(&self) -> bool {
+        self.classifications.is_empty()
+            && self.collections.is_empty()
+            && self.discards == 0
+            && self.retried_cases.is_empty()
+    }
+}
+
+/// Hash a generated input to count duplicates.
+fn input_hash<K: Hash + ?Sized>(key: &K) -> u64 {
+    let mut hasher = std::collections::hash_map::DefaultHasher::new();
+    key.hash(&mut hasher);
+    hasher.finish()
+}
+
+/// Hash a value through its debug representation, which every input has,
+/// without building the string.
+pub(crate) fn debug_hash<T: std::fmt::Debug>(value: &T) -> u64 {
+    struct HashWriter(std::collections::hash_map::DefaultHasher);
+
+    impl std::fmt::Write for HashWriter {
+        fn write_str(&mut self, s: &str) -> std::fmt::Result {
+            self.0.write(s.as_bytes());
+            Ok(())
+        }
+    }
+
+    let mut writer = HashWriter(Default::default());
+    let _ = std::fmt::write(&mut writer, format_args!("{value:?}"));
+    writer.0.finish()
+}
+
+/// The first line of a failure's assertion type, with each run of digits
+/// replaced by `N`.
+fn failure_signature(assertion_type: Option<&str>) -> String {
+    let headline = assertion_type.and_then(|assertion| assertion.lines().next());
+    let mut signature = String::new();
+    for c in headline.unwrap_or("Failure").chars() {
+        if !c.is_ascii_digit() {
+            signature.push(c);
+        } else if !signature.ends_with('N') {
+            signature.push('N');
+        }
+    }
+    signature
+}
+
+/// How a shrink candidate's test result is shown in a shrink graph.
+fn shrink_outcome(result: &TestResult) -> ShrinkOutcome {
+    match result {
+        TestResult::Fail { .. } => ShrinkOutcome::Failed,
+        TestResult::Discard { .. } => ShrinkOutcome::Discarded,
+        TestResult::Pass { .. } | TestResult::PassWithStatistics { .. } => ShrinkOutcome::Passed,
+    }
+}
+
+/// Read an input stored as text, without its trailing newline.
+///
+/// Panics naming the file if it can't be read or parsed, since the test
+/// can't go on without it.
+fn read_input<T>(path: &std::path::Path) -> T
+where
+    T: std::str::FromStr,
+    T::Err: std::fmt::Display,
+{
+    let text = std::fs::read_to_string(path)
+        .unwrap_or_else(|err| panic!("couldn't read {}: {err}", path.display()));
+    text.trim_end_matches(['\n', '\r'])
+        .parse()
+        .unwrap_or_else(|err| panic!("couldn't parse {}: {err}", path.display()))
 }
 
 type ClassificationFn<T> = Box<dyn Fn(&T) -> bool>;
 type CollectionFn<T> = Box<dyn Fn(&T) -> f64>;
+type LiteralFn<T> = Box<dyn Fn(&T) -> String>;
+type ByteEstimateFn<T> = Box<dyn Fn(&T) -> usize>;
+type HashFn<T> = Box<dyn Fn(&T) -> u64>;
+type TestFn<T> = Box<dyn Fn(&T) -> TestResult>;
+type ComponentsFn<T> = for<'a> fn(&'a T) -> Vec<&'a dyn std::fmt::Debug>;
+
+/// Inputs made of parts that can be reported separately; see
+/// [`Property::with_variable_names`].
+pub trait Components {
+    /// How many parts there are.
+    const COUNT: usize;
+
+    /// The parts, in order.
+    fn components(&self) -> Vec<&dyn std::fmt::Debug>;
+}
+
+macro_rules! impl_components {
+    ($count:expr; $($name:ident: $index:tt),+) => {
+        impl<$($name: std::fmt::Debug),+> Components for ($($name,)+) {
+            const COUNT: usize = $count;
+
+            fn components(&self) -> Vec<&dyn std::fmt::Debug> {
+                vec![$(&self.$index),+]
+            }
+        }
+    };
+}
+
+impl_components!(2; A: 0, B: 1);
+impl_components!(3; A: 0, B: 1, C: 2);
+impl_components!(4; A: 0, B: 1, C: 2, D: 3);
+impl_components!(5; A: 0, B: 1, C: 2, D: 3, E: 4);
+impl_components!(6; A: 0, B: 1, C: 2, D: 3, E: 4, F: 5);
+
+/// Cases run at each size when a config asks for size diagnostics.
+const SIZE_DIAGNOSIS_TESTS: usize = 10;
+
+/// The assertion type and caught panic of a failure, carried through
+/// shrinking to be reported with the minimal counterexample.
+type FailureDetail = (Option<String>, Option<PanicReport>);
+
+/// What shrinking found: the smaller failing value, if any, the steps it
+/// took, and the shrink budget if shrinking stopped at it with candidates
+/// left untried.
+type Shrunk<'a, T> = (
+    Option<(&'a T, FailureDetail)>,
+    Vec<ShrinkStep>,
+    Option<usize>,
+);
+
+/// Where a property starts failing, from [`Property::diagnose_sizes`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SizeDiagnosis {
+    /// Each size tried, with the number of cases run at it
+    pub sizes: Vec<(usize, usize)>,
+    /// The smallest size at which a case failed
+    pub smallest_failing_size: Option<usize>,
+}
+
+impl std::fmt::Display for SizeDiagnosis {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let cases: usize = self.sizes.iter().map(|(_, cases)| cases).sum();
+        match (self.smallest_failing_size, self.sizes.last()) {
+            (Some(size), Some((_, at_size))) => write!(
+                f,
+                "Fails from size {size}: {} cases passed at smaller sizes, then case {at_size} at size {size} failed.",
+                cases - at_size
+            ),
+            (_, Some((largest, _))) => write!(
+                f,
+                "No failure at sizes 0 to {largest} in {cases} cases."
+            ),
+            (_, None) => write!(f, "No sizes were tried."),
+        }
+    }
+}
+
+/// Failures of one run grouped by cause; see [`Property::run_clustered`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct FailureClusters {
+    /// The number of cases run
+    pub tests_run: usize,
+    /// One cluster per distinct failure, the most frequent first
+    pub clusters: Vec<FailureCluster>,
+}
+
+/// Failing cases that share a failure signature.
+#[derive(Debug, Clone, PartialEq)]
+pub struct FailureCluster {
+    /// The assertion type with every number replaced by `N`, so failures
+    /// differing only in the values they mention fall together
+    pub signature: String,
+    /// The assertion type of the first failure in the cluster
+    pub assertion_type: Option<String>,
+    /// How many cases failed this way
+    pub failures: usize,
+    /// The first failure, shrunk only through inputs failing the same way
+    pub counterexample: String,
+    pub shrinks_performed: usize,
+}
+
+impl FailureClusters {
+    /// The total number of failing cases.
+    pub fn failures(&self) -> usize {
+        self.clusters.iter().map(|cluster| cluster.failures).sum()
+    }
+}
+
+impl std::fmt::Display for FailureClusters {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if self.clusters.is_empty() {
+            return write!(f, "No failures in {} cases.", self.tests_run);
+        }
+        write!(
+            f,
+            "{} of {} cases failed in {} distinct ways:",
+            self.failures(),
+            self.tests_run,
+            self.clusters.len()
+        )?;
+        for (index, cluster) in self.clusters.iter().enumerate() {
+            let cause = cluster.assertion_type.as_deref().unwrap_or("Failure");
+            write!(
+                f,
+                "\n  {}. {cause} ({} cases)\n     Minimal counterexample: {}",
+                index + 1,
+                cluster.failures,
+                cluster.counterexample.replace('\n', "\n     ")
+            )?;
+        }
+        Ok(())
+    }
+}
+
+/// A run of a property in progress, for runners that advance it one case
+/// at a time.
+pub(crate) struct RunState {
+    seed: Seed,
+    test_num: usize,
+    examples_tested: usize,
+    started: Instant,
+    /// Hashes of the generated inputs, to count duplicates
+    seen: HashSet<u64>,
+}
 
 /// A property that can be tested with generated inputs.
 pub struct Property<T> {
     generator: Gen<T>,
-    test_function: Box<dyn Fn(&T) -> TestResult>,
+    test_function: TestFn<T>,
     variable_name: Option<String>,
+    variable_names: Option<(Vec<String>, ComponentsFn<T>)>,
     classifications: Vec<(String, ClassificationFn<T>)>,
     collections: Vec<(String, CollectionFn<T>)>,
     examples: Vec<T>,
     example_strategy: ExampleStrategy,
+    location: &'static Location<'static>,
+    module_path: Option<String>,
+    regression: Option<(RegressionExport, LiteralFn<T>)>,
+    corpus_capture: Option<(std::path::PathBuf, LiteralFn<T>)>,
+    byte_estimate: Option<ByteEstimateFn<T>>,
+    duplicate_hash: Option<HashFn<T>>,
+    marker: Option<PropertyMarker>,
+    on_run_start: Vec<Box<dyn Fn()>>,
 }
 
 impl<T> Property<T>
@@ -71,6 +407,7 @@ where
     T: 'static + std::fmt::Debug + Clone,
 {
     /// Create a new property from a generator and test function.
+    #[track_caller]
     pub fn new<F>(generator: Gen<T>, test_function: F) -> Self
     where
         F: Fn(&T) -> TestResult + 'static,
@@ -79,14 +416,24 @@ where
             generator,
             test_function: Box::new(test_function),
             variable_name: None,
+            variable_names: None,
             classifications: Vec::new(),
             collections: Vec::new(),
             examples: Vec::new(),
             example_strategy: ExampleStrategy::ExamplesFirst,
+            location: Location::caller(),
+            module_path: None,
+            regression: None,
+            corpus_capture: None,
+            byte_estimate: None,
+            duplicate_hash: None,
+            marker: None,
+            on_run_start: Vec::new(),
         }
     }
 
     /// Create a property that checks a boolean condition.
+    #[track_caller]
     pub fn for_all<F>(generator: Gen<T>, condition: F) -> Self
     where
         F: Fn(&T) -> bool + 'static,
@@ -99,20 +446,13 @@ where
                     module_path: None,
                 }
             } else {
-                TestResult::Fail {
-                    counterexample: format!("{input:?}"),
-                    tests_run: 0,
-                    shrinks_performed: 0,
-                    property_name: None,
-                    module_path: None,
-                    assertion_type: Some("Boolean Condition".to_string()),
-                    shrink_steps: Vec::new(),
-                }
+                TestResult::failure(format!("{input:?}"), "Boolean Condition")
             }
         })
     }
 
     /// Create a property that checks a boolean condition with a named variable.
+    #[track_caller]
     pub fn for_all_named<F>(generator: Gen<T>, variable_name: &str, condition: F) -> Self
     where
         F: Fn(&T) -> bool + 'static,
@@ -125,21 +465,276 @@ where
                     module_path: None,
                 }
             } else {
+                TestResult::failure(format!("{input:?}"), "Boolean Condition")
+            }
+        });
+        property.variable_name = Some(variable_name.to_string());
+        property
+    }
+
+    /// Name each part of a tuple input, so failures report every part on
+    /// its own line, and shrinking progress shows each part as it changes.
+    ///
+    /// # Panics
+    /// Panics if the number of names differs from the number of parts.
+    ///
+    /// # Example
+    /// ```rust
+    /// use hedgehog_core::*;
+    ///
+    /// let gen = Gen::<(i32, i32)>::tuple_of(Gen::int_range(0, 100), Gen::int_range(0, 100));
+    /// let prop = for_all(gen, |&(x, y)| x + y < 150).with_variable_names(["x", "y"]);
+    /// // Minimal counterexample:
+    /// //   x = 50
+    /// //   y = 100
+    /// ```
+    pub fn with_variable_names<const N: usize>(mut self, names: [&str; N]) -> Self
+    where
+        T: Components,
+    {
+        assert_eq!(
+            N,
+            T::COUNT,
+            "with_variable_names needs a name for each of the input's {} parts",
+            T::COUNT
+        );
+        let names = names.iter().map(|name| name.to_string()).collect();
+        self.variable_names = Some((names, T::components));
+        self
+    }
+
+    /// Create a property that passes when the test function panics, with
+    /// any message.
+    ///
+    /// # Example
+    /// ```rust
+    /// use hedgehog_core::*;
+    ///
+    /// let prop = for_all_panics(Gen::<Vec<i32>>::vec_of(Gen::int_range(0, 9)), |xs| {
+    ///     let _ = xs[xs.len()];
+    /// });
+    /// ```
+    #[track_caller]
+    pub fn for_all_panics<F>(generator: Gen<T>, f: F) -> Self
+    where
+        F: Fn(&T) + 'static,
+    {
+        Property::for_all_panics_with(generator, "", f)
+    }
+
+    /// Create a property that passes when the test function panics with a
+    /// message containing `expected`.
+    ///
+    /// Returning normally fails, and so does panicking with any other
+    /// message.
+    #[track_caller]
+    pub fn for_all_panics_with<F>(generator: Gen<T>, expected: &str, f: F) -> Self
+    where
+        F: Fn(&T) + 'static,
+    {
+        let expected = expected.to_string();
+        Property::new(generator, move |input| {
+            let (assertion_type, panic) = match crate::panic::catch_panic(|| f(input)) {
+                Err(report) if report.message.contains(&expected) => {
+                    return TestResult::Pass {
+                        tests_run: 1,
+                        property_name: None,
+                        module_path: None,
+                    }
+                }
+                Err(report) => (
+                    format!(
+                        "Expected Panic Containing {expected:?}, Got {:?}",
+                        report.message
+                    ),
+                    Some(report),
+                ),
+                Ok(()) => ("Expected Panic".to_string(), None),
+            };
+            TestResult::Fail {
+                counterexample: format!("{input:?}"),
+                tests_run: 0,
+                shrinks_performed: 0,
+                property_name: None,
+                module_path: None,
+                assertion_type: Some(assertion_type),
+                shrink_steps: Vec::new(),
+                details: FailureDetails {
+                    panic,
+                    ..Default::default()
+                },
+            }
+        })
+    }
+
+    /// Report panics in the test function as failures rather than letting
+    /// them unwind through the runner.
+    ///
+    /// The failure shrinks like any other and records the panic message as
+    /// its assertion type. Without this, the first panicking input ends the
+    /// test with no shrinking.
+    pub fn no_panic(mut self) -> Self {
+        let test_function = self.test_function;
+        self.test_function = Box::new(move |input| {
+            crate::panic::catch_panic(|| test_function(input)).unwrap_or_else(|report| {
                 TestResult::Fail {
                     counterexample: format!("{input:?}"),
                     tests_run: 0,
                     shrinks_performed: 0,
                     property_name: None,
                     module_path: None,
-                    assertion_type: Some("Boolean Condition".to_string()),
+                    assertion_type: Some(format!("Panic: {}", report.message)),
                     shrink_steps: Vec::new(),
+                    details: FailureDetails {
+                        panic: Some(report),
+                        ..Default::default()
+                    },
                 }
-            }
+            })
         });
-        property.variable_name = Some(variable_name.to_string());
+        self
+    }
+
+    /// Create a property whose condition also gets an expensive value built
+    /// from the input, such as a compiled schema or a database.
+    ///
+    /// Each distinct input is set up once per run and the result reused,
+    /// so re-checking the same value while shrinking costs nothing. To
+    /// share setup between inputs that differ, key a [`Memo`] on the part
+    /// that matters instead.
+    #[track_caller]
+    pub fn for_all_memoized<A, S, F>(generator: Gen<T>, setup: S, condition: F) -> Self
+    where
+        T: Hash + Eq,
+        A: 'static,
+        S: Fn(&T) -> A + 'static,
+        F: Fn(&T, &A) -> bool + 'static,
+    {
+        let memo = Rc::new(Memo::new(setup));
+        let cleared = Rc::clone(&memo);
+        let mut property =
+            Property::for_all(generator, move |input| condition(input, &memo.get(input)));
+        property
+            .on_run_start
+            .push(Box::new(move || cleared.clear()));
         property
     }
 
+    /// Combine with another property over the same input type, so both
+    /// are checked on each generated value.
+    ///
+    /// The combined property generates with this property's generator and
+    /// ignores `other`'s. Classifications, collections and examples from
+    /// both are kept. When both fail, this property's failure is reported.
+    ///
+    /// # Example
+    /// ```rust
+    /// use hedgehog_core::*;
+    ///
+    /// fn sorted(xs: &Vec<i32>) -> Vec<i32> {
+    ///     let mut xs = xs.clone();
+    ///     xs.sort();
+    ///     xs
+    /// }
+    ///
+    /// let gen = || Gen::<Vec<i32>>::vec_of(Gen::int_range(-10, 10));
+    /// let prop = for_all(gen(), |xs| sorted(xs).len() == xs.len())
+    ///     .and(for_all(gen(), |xs| sorted(xs).windows(2).all(|w| w[0] <= w[1])));
+    /// assert!(matches!(prop.run(&Config::default()), TestResult::Pass { .. }));
+    /// ```
+    pub fn and(self, other: Property<T>) -> Self {
+        self.combine(other, |first, second, input| match first(input) {
+            TestResult::Pass { .. } | TestResult::PassWithStatistics { .. } => second(input),
+            failed => failed,
+        })
+    }
+
+    /// Combine with another property so that each generated value must
+    /// satisfy at least one of them.
+    ///
+    /// Generation, statistics and examples work as for [`and`](Self::and).
+    /// When both fail, the failure names both assertion types.
+    pub fn or(self, other: Property<T>) -> Self {
+        self.combine(other, |first, second, input| {
+            let (first_assertion, first_panic) = match first(input) {
+                TestResult::Fail {
+                    assertion_type,
+                    details,
+                    ..
+                } => (assertion_type, details.panic),
+                passed => return passed,
+            };
+            match second(input) {
+                TestResult::Fail {
+                    counterexample,
+                    tests_run,
+                    shrinks_performed,
+                    property_name,
+                    module_path,
+                    assertion_type,
+                    shrink_steps,
+                    details,
+                } => {
+                    let describe =
+                        |assertion: Option<String>| assertion.unwrap_or_else(|| "Failure".into());
+                    TestResult::Fail {
+                        counterexample,
+                        tests_run,
+                        shrinks_performed,
+                        property_name,
+                        module_path,
+                        assertion_type: Some(format!(
+                            "Neither Held: {} / {}",
+                            describe(first_assertion),
+                            describe(assertion_type)
+                        )),
+                        shrink_steps,
+                        details: FailureDetails {
+                            panic: details.panic.or(first_panic),
+                            ..details
+                        },
+                    }
+                }
+                other => other,
+            }
+        })
+    }
+
+    /// Combine with another property that only has to hold for values
+    /// satisfying this one.
+    ///
+    /// Values this property rejects pass without checking `consequent`;
+    /// classify them if you need to know how many there were. Generation,
+    /// statistics and examples work as for [`and`](Self::and).
+    pub fn implies(self, consequent: Property<T>) -> Self {
+        self.combine(consequent, |first, second, input| match first(input) {
+            TestResult::Pass { .. } | TestResult::PassWithStatistics { .. } => second(input),
+            TestResult::Fail { .. } => TestResult::Pass {
+                tests_run: 1,
+                property_name: None,
+                module_path: None,
+            },
+            discarded => discarded,
+        })
+    }
+
+    fn combine<F>(mut self, other: Property<T>, combine: F) -> Self
+    where
+        F: Fn(&TestFn<T>, &TestFn<T>, &T) -> TestResult + 'static,
+    {
+        let first = self.test_function;
+        let second = other.test_function;
+        self.test_function = Box::new(move |input| combine(&first, &second, input));
+        self.variable_name = self.variable_name.or(other.variable_name);
+        self.variable_names = self.variable_names.or(other.variable_names);
+        self.classifications.extend(other.classifications);
+        self.collections.extend(other.collections);
+        self.examples.extend(other.examples);
+        self.marker = self.marker.or(other.marker);
+        self.on_run_start.extend(other.on_run_start);
+        self
+    }
+
     /// Add a classification to categorize test inputs.
     pub fn classify<F>(mut self, name: &str, predicate: F) -> Self
     where
@@ -202,6 +797,211 @@ where
         self
     }
 
+    /// Check the simplest values of the input type before generating any.
+    ///
+    /// Up to `budget` values from [`SmallValues`](crate::small::SmallValues)
+    /// that `in_domain` accepts run first, simplest first, such as the empty
+    /// list, zero and each variant of a fieldless enum. `in_domain` should
+    /// accept what the generator can produce, so that a property over
+    /// `Gen::int_range(1, 10)` never sees zero. They count towards the test
+    /// limit like other examples, and the rest of the run generates as usual.
+    pub fn with_small_cases<F>(mut self, budget: usize, in_domain: F) -> Self
+    where
+        T: crate::small::SmallValues + Clone,
+        F: Fn(&T) -> bool,
+    {
+        self.examples.extend(
+            T::small_values()
+                .into_iter()
+                .filter(|value| in_domain(value))
+                .take(budget),
+        );
+        self.example_strategy = ExampleStrategy::ExamplesFirst;
+        self
+    }
+
+    /// Replay the property's corpus before generating.
+    ///
+    /// Each file under `tests/corpus/<name>` ([`DEFAULT_CORPUS_DIR`]) holds
+    /// one input, parsed with `FromStr`, and runs first like an example.
+    /// Keep inputs that once failed there so they stay fixed; `cargo
+    /// hedgehog corpus` lists, adds and cleans up entries.
+    pub fn with_corpus(self, name: &str) -> Self
+    where
+        T: std::str::FromStr + Clone,
+        T::Err: std::fmt::Display,
+    {
+        self.with_corpus_from(std::path::Path::new(DEFAULT_CORPUS_DIR).join(name))
+    }
+
+    /// Replay the inputs stored in `directory` before generating.
+    ///
+    /// When [`CORPUS_CHECK_ENV_VAR`] is set, each entry is also run once
+    /// here and reported on stdout as still failing or passing.
+    pub fn with_corpus_from(mut self, directory: impl AsRef<std::path::Path>) -> Self
+    where
+        T: std::str::FromStr + Clone,
+        T::Err: std::fmt::Display,
+    {
+        let directory = directory.as_ref();
+        let entries = corpus_entries(directory)
+            .unwrap_or_else(|err| panic!("couldn't read {}: {err}", directory.display()));
+        let check = std::env::var_os(CORPUS_CHECK_ENV_VAR).is_some();
+        for path in entries {
+            let input: T = read_input(&path);
+            if check {
+                let verdict = match self.run_test(&input, &Config::default()).0 {
+                    TestResult::Fail {
+                        assertion_type: Some(assertion),
+                        ..
+                    } => format!("still fails ({assertion})"),
+                    TestResult::Fail { .. } => "still fails".to_string(),
+                    _ => "passes".to_string(),
+                };
+                println!("hedgehog-corpus: {}: {verdict}", path.display());
+            }
+            self.examples.push(input);
+        }
+        self.example_strategy = ExampleStrategy::ExamplesFirst;
+        self
+    }
+
+    /// Replay the property's corpus before generating, and store the shrunk
+    /// counterexample there when the property fails.
+    ///
+    /// The counterexample is written with `Display`, so it's read back with
+    /// `FromStr` on the next run and keeps failing until it's fixed. An input
+    /// whose printed form no longer parses fails the next run with the
+    /// file's path and the parse error.
+    pub fn with_corpus_capture(self, name: &str) -> Self
+    where
+        T: std::fmt::Display + std::str::FromStr,
+        T::Err: std::fmt::Display,
+    {
+        self.with_corpus_capture_in(std::path::Path::new(DEFAULT_CORPUS_DIR).join(name))
+    }
+
+    /// Replay the inputs stored in `directory` before generating, and store
+    /// the shrunk counterexample there when the property fails.
+    pub fn with_corpus_capture_in(self, directory: impl AsRef<std::path::Path>) -> Self
+    where
+        T: std::fmt::Display + std::str::FromStr,
+        T::Err: std::fmt::Display,
+    {
+        let directory = directory.as_ref().to_path_buf();
+        let mut property = self.with_corpus_from(&directory);
+        property.corpus_capture = Some((directory, Box::new(|value: &T| value.to_string())));
+        property
+    }
+
+    /// Estimate the bytes each generated value takes with
+    /// [`SizeOf`](crate::size_of::SizeOf), so a config's
+    /// [`max_value_bytes`](crate::Config::with_max_value_bytes) budget can
+    /// stop runs whose inputs grow too large.
+    pub fn with_byte_estimate(self) -> Self
+    where
+        T: crate::size_of::SizeOf,
+    {
+        self.with_byte_estimate_by(|value: &T| value.estimated_bytes())
+    }
+
+    /// Estimate the bytes each generated value takes with `estimate`, for
+    /// types without a [`SizeOf`](crate::size_of::SizeOf) impl.
+    pub fn with_byte_estimate_by<F>(mut self, estimate: F) -> Self
+    where
+        F: Fn(&T) -> usize + 'static,
+    {
+        self.byte_estimate = Some(Box::new(estimate));
+        self
+    }
+
+    /// Count generated inputs equal to one generated earlier in the run,
+    /// warning when more than half of a passing run's cases were duplicates.
+    ///
+    /// Each input is hashed as it's generated, so tracking is off unless
+    /// asked for.
+    pub fn with_duplicate_tracking(mut self) -> Self
+    where
+        T: Hash,
+    {
+        self.duplicate_hash = Some(Box::new(|value: &T| input_hash(value)));
+        self
+    }
+
+    /// Count duplicate inputs by the key `key` gives each, for types without
+    /// a [`Hash`](std::hash::Hash) impl or to count inputs alike in a
+    /// coarser sense as duplicates.
+    pub fn with_duplicate_tracking_by<K, F>(mut self, key: F) -> Self
+    where
+        K: Hash,
+        F: Fn(&T) -> K + 'static,
+    {
+        self.duplicate_hash = Some(Box::new(move |value: &T| input_hash(&key(value))));
+        self
+    }
+
+    /// Mark the property as known to fail, for `reason`, such as the issue
+    /// tracking the bug.
+    ///
+    /// The property still runs. A failure is reported on stderr and the run
+    /// passes, so the suite doesn't fail; a pass is reported as unexpected,
+    /// since the bug may have been fixed. Run histories record the marker,
+    /// and `cargo hedgehog report` lists expected failures that passed.
+    ///
+    /// # Example
+    /// ```rust
+    /// use hedgehog_core::*;
+    ///
+    /// let prop = for_all(Gen::int_range(0, 100), |&n: &i32| n < 50).expect_failure("issue-123");
+    /// assert!(matches!(prop.run(&Config::default()), TestResult::Pass { .. }));
+    /// ```
+    pub fn expect_failure(mut self, reason: &str) -> Self {
+        self.marker = Some(PropertyMarker::ExpectedFailure(reason.to_string()));
+        self
+    }
+
+    /// Quarantine an unreliable property: it runs and reports failures on
+    /// stderr, but the run passes, so the suite doesn't fail.
+    ///
+    /// Run histories record the marker, and `cargo hedgehog report` counts
+    /// quarantined properties and lists those that passed.
+    pub fn quarantined(mut self) -> Self {
+        self.marker = Some(PropertyMarker::Quarantined);
+        self
+    }
+
+    /// The property's known-problem marker, if it has one.
+    pub fn marker(&self) -> Option<&PropertyMarker> {
+        self.marker.as_ref()
+    }
+
+    /// Record the module the property belongs to, for failure reports.
+    ///
+    /// The file and line the property was created on are recorded
+    /// automatically; pass `module_path!()` to name the module too.
+    pub fn in_module(mut self, module_path: &str) -> Self {
+        self.module_path = Some(module_path.to_string());
+        self
+    }
+
+    /// Export the shrunk counterexample as a regression test when the
+    /// property fails.
+    ///
+    /// The input is written out as Rust source, so the test keeps covering
+    /// the case even if the generators change. See [`RegressionExport`].
+    pub fn export_regressions(mut self, export: RegressionExport) -> Self
+    where
+        T: RustLiteral,
+    {
+        self.regression = Some((export, Box::new(|value: &T| value.rust_literal())));
+        self
+    }
+
+    /// Where this property was created.
+    pub fn location(&self) -> &'static Location<'static> {
+        self.location
+    }
+
     /// Run this property with the given configuration.
     pub fn run(&self, config: &Config) -> TestResult {
         self.run_with_context(config, None, None)
@@ -214,69 +1014,704 @@ where
         property_name: Option<&str>,
         module_path: Option<&str>,
     ) -> TestResult {
-        let mut seed = Seed::random();
+        self.run_gathering(
+            config,
+            property_name,
+            module_path,
+            &mut TestStatistics::new(),
+        )
+    }
+
+    /// Run this property, also returning the time spent generating inputs,
+    /// running the test function, and shrinking.
+    ///
+    /// Unlike the timings in `PassWithStatistics`, these are available
+    /// whatever the outcome, including the time spent shrinking a failure.
+    pub fn run_with_timings(&self, config: &Config) -> (TestResult, PhaseTimings) {
         let mut statistics = TestStatistics::new();
-        let mut examples_tested = 0;
-
-        for test_num in 0..config.test_limit {
-            let size = Size::new((test_num * config.size_limit) / config.test_limit);
-            let (test_seed, next_seed) = seed.split();
-            seed = next_seed;
-
-            // Determine whether to use an example or generate a value
-            let tree = match self.should_use_example(test_num, examples_tested) {
-                Some(example_index) => {
-                    examples_tested += 1;
-                    Tree::singleton(self.examples[example_index].clone())
-                }
-                None => self.generator.generate(size, test_seed),
-            };
+        let result = self.run_gathering(config, None, None, &mut statistics);
+        (result, statistics.timings)
+    }
 
-            // Collect statistics from the generated value
-            self.collect_statistics(&tree.value, &mut statistics);
+    /// Shrink a failing input that came from outside the property, such
+    /// as a production incident or a fuzzer, to a minimal failing one.
+    ///
+    /// Shrinking moves to the first of the input's [`Shrink`] candidates
+    /// that still fails, until none does or `config.shrink_limit` steps have
+    /// been taken. Returns a pass if the input doesn't fail at all.
+    ///
+    /// # Example
+    /// ```rust
+    /// use hedgehog_core::*;
+    ///
+    /// let prop = for_all(Gen::vec_of(Gen::int_range(0, 100)), |xs: &Vec<i32>| {
+    ///     !xs.contains(&42)
+    /// });
+    /// let result = prop.minimize(vec![7, 3, 42, 99, 1], &Config::default());
+    /// assert!(matches!(result, TestResult::Fail { counterexample, .. } if counterexample == "[42]"));
+    /// ```
+    pub fn minimize(&self, input: T, config: &Config) -> TestResult
+    where
+        T: Shrink,
+    {
+        self.minimize_value(input, config).1
+    }
 
-            match self.check_tree(&tree, config) {
-                TestResult::Pass { .. } => continue,
-                TestResult::Fail {
-                    counterexample,
-                    shrinks_performed,
-                    shrink_steps,
-                    assertion_type,
+    /// Run the property, unless the `HEDGEHOG_MINIMIZE` environment
+    /// variable names a file holding an input, in which case minimize that
+    /// input instead.
+    ///
+    /// The input is read with `FromStr`. If `HEDGEHOG_MINIMIZE_OUTPUT` is
+    /// set too, the minimal input is written there with `Display`. This is
+    /// what `cargo hedgehog minimize` runs.
+    ///
+    /// # Panics
+    /// Panics if the input file can't be read or parsed, or the output
+    /// can't be written.
+    pub fn run_minimizable(&self, config: &Config) -> TestResult
+    where
+        T: Shrink + std::str::FromStr + std::fmt::Display,
+        T::Err: std::fmt::Display,
+    {
+        let Some(path) = std::env::var_os(MINIMIZE_ENV_VAR) else {
+            return self.run(config);
+        };
+        let path = std::path::PathBuf::from(path);
+        let input: T = read_input(&path);
+
+        let (minimal, result) = self.minimize_value(input, config);
+        if !matches!(result, TestResult::Fail { .. }) {
+            eprintln!(
+                "hedgehog: the input in {} passes; nothing to minimize",
+                path.display()
+            );
+        } else if let Some(output) = std::env::var_os(MINIMIZE_OUTPUT_ENV_VAR) {
+            let output = std::path::PathBuf::from(output);
+            std::fs::write(&output, format!("{minimal}\n"))
+                .unwrap_or_else(|err| panic!("couldn't write {}: {err}", output.display()));
+        }
+        result
+    }
+
+    /// Check the one test case picked by fuzzer input `data`, shrinking it
+    /// if it fails.
+    ///
+    /// This is what [`hedgehog_fuzz_target!`](crate::hedgehog_fuzz_target)
+    /// runs for each input. Examples and corpus entries are not run.
+    ///
+    /// # Example
+    /// ```rust
+    /// use hedgehog_core::*;
+    ///
+    /// let prop = for_all(Gen::int_range(0, 100), |&n: &i32| n < 1000);
+    /// let result = prop.run_fuzz_input(b"\x05fuzz", &Config::default());
+    /// assert!(matches!(result, TestResult::Pass { tests_run: 1, .. }));
+    /// ```
+    pub fn run_fuzz_input(&self, data: &[u8], config: &Config) -> TestResult {
+        let (size, seed) = crate::fuzz::case_from_bytes(data, config.size_limit);
+        let _case_seed = crate::rng::CaseSeed::enter(seed);
+        let tree = self.generator.generate(size, seed);
+        let location = self.location.to_string();
+        let result = self.check_tree(&tree, config, &location, &mut TestStatistics::new());
+        match result {
+            TestResult::Fail {
+                counterexample,
+                tests_run,
+                shrinks_performed,
+                assertion_type,
+                shrink_steps,
+                details,
+                ..
+            } => TestResult::Fail {
+                counterexample,
+                tests_run,
+                shrinks_performed,
+                property_name: None,
+                module_path: self.module_path.clone(),
+                assertion_type,
+                shrink_steps,
+                details: FailureDetails {
+                    location: Some(self.location.to_string()),
+                    size: Some(size.get()),
+                    generator: self.generator.description(),
+                    ..details
+                },
+            },
+            other => other,
+        }
+    }
+
+    /// Minimize an input, returning the smallest failing value reached and
+    /// the outcome.
+    fn minimize_value(&self, input: T, config: &Config) -> (T, TestResult)
+    where
+        T: Shrink,
+    {
+        let TestResult::Fail {
+            mut assertion_type,
+            details: FailureDetails { mut panic, .. },
+            ..
+        } = self.run_test(&input, config).0
+        else {
+            return (
+                input,
+                TestResult::Pass {
+                    tests_run: 1,
+                    property_name: None,
+                    module_path: self.module_path.clone(),
+                },
+            );
+        };
+
+        let mut shrink_steps = self.shrink_steps_for(&input, None, 0, config);
+        let mut current = input;
+        let mut shrinks_performed = 0;
+        'shrinking: while shrinks_performed < config.shrink_limit {
+            for candidate in current.shrinks() {
+                if let TestResult::Fail {
+                    assertion_type: failure,
+                    details:
+                        FailureDetails {
+                            panic: failure_panic,
+                            ..
+                        },
                     ..
-                } => {
-                    return TestResult::Fail {
-                        counterexample,
-                        tests_run: test_num + 1,
+                } = self.run_test(&candidate, config).0
+                {
+                    shrinks_performed += 1;
+                    shrink_steps.extend(self.shrink_steps_for(
+                        &candidate,
+                        Some(&current),
                         shrinks_performed,
-                        property_name: property_name.map(|s| s.to_string()),
-                        module_path: module_path.map(|s| s.to_string()),
-                        assertion_type,
-                        shrink_steps,
+                        config,
+                    ));
+                    assertion_type = failure;
+                    panic = failure_panic;
+                    current = candidate;
+                    continue 'shrinking;
+                }
+            }
+            break;
+        }
+
+        let result = TestResult::Fail {
+            counterexample: self.show(&current, config),
+            tests_run: 1,
+            shrinks_performed,
+            property_name: None,
+            module_path: self.module_path.clone(),
+            assertion_type,
+            shrink_steps,
+            details: FailureDetails {
+                location: Some(self.location.to_string()),
+                panic,
+                ..Default::default()
+            },
+        };
+        (current, result)
+    }
+
+    fn run_gathering(
+        &self,
+        config: &Config,
+        property_name: Option<&str>,
+        module_path: Option<&str>,
+        statistics: &mut TestStatistics,
+    ) -> TestResult {
+        let mut run = self.begin_run();
+        let mut result = None;
+        while result.is_none() && self.has_next_case(&run, config) {
+            result = self.run_case(&mut run, config, property_name, module_path, statistics);
+        }
+        let result = result.unwrap_or_else(|| {
+            self.finish_run(&run, config, property_name, module_path, statistics)
+        });
+
+        let location = self.location.to_string();
+        let name = property_name.unwrap_or(&location);
+        if let Some(path) = &config.history {
+            let path = property_path(path, name);
+            history::append(&path, name, &result, statistics, self.marker.as_ref());
+        }
+        self.settle(name, result)
+    }
+
+    /// The result of a finished run as the suite sees it: a failure of a
+    /// property marked as failing is reported on stderr and passes.
+    pub(crate) fn settle(&self, name: &str, result: TestResult) -> TestResult {
+        let Some(marker) = &self.marker else {
+            return result;
+        };
+        let (tests_run, property_name, module_path) = match &result {
+            TestResult::Fail {
+                tests_run,
+                property_name,
+                module_path,
+                ..
+            } => (*tests_run, property_name.clone(), module_path.clone()),
+            TestResult::Discard {
+                property_name,
+                module_path,
+                ..
+            } if *marker == PropertyMarker::Quarantined => {
+                (0, property_name.clone(), module_path.clone())
+            }
+            _ => {
+                if let PropertyMarker::ExpectedFailure(reason) = marker {
+                    eprintln!(
+                        "warning: {name} passed but is expected to fail ({reason}); \
+                         remove expect_failure if it's fixed"
+                    );
+                }
+                return result;
+            }
+        };
+        match marker {
+            PropertyMarker::ExpectedFailure(reason) => {
+                eprintln!("{name} failed as expected ({reason}):\n{result}")
+            }
+            PropertyMarker::Quarantined => {
+                eprintln!("{name} is quarantined; not failing the suite:\n{result}")
+            }
+        }
+        TestResult::Pass {
+            tests_run,
+            property_name,
+            module_path,
+        }
+    }
+
+    /// Start a run, one case at a time.
+    pub(crate) fn begin_run(&self) -> RunState {
+        for hook in &self.on_run_start {
+            hook();
+        }
+        take_generation_discards();
+        RunState {
+            seed: Seed::random(),
+            test_num: 0,
+            examples_tested: 0,
+            started: Instant::now(),
+            seen: HashSet::new(),
+        }
+    }
+
+    /// Whether the run has cases left within the config's limits.
+    pub(crate) fn has_next_case(&self, run: &RunState, config: &Config) -> bool {
+        run.test_num < config.test_limit
+            && config
+                .timeout
+                .is_none_or(|timeout| run.started.elapsed() < timeout)
+    }
+
+    /// Generate and check the next case, returning the outcome of the run if
+    /// this case ended it.
+    pub(crate) fn run_case(
+        &self,
+        run: &mut RunState,
+        config: &Config,
+        property_name: Option<&str>,
+        module_path: Option<&str>,
+        statistics: &mut TestStatistics,
+    ) -> Option<TestResult> {
+        let module_path = module_path.or(self.module_path.as_deref());
+        let test_num = run.test_num;
+        run.test_num += 1;
+
+        let size = Size::new((test_num * config.size_limit) / config.test_limit);
+        let (test_seed, next_seed) = run.seed.split();
+        run.seed = next_seed;
+        let _case_seed = crate::rng::CaseSeed::enter(test_seed);
+
+        // Determine whether to use an example or generate a value
+        let examples_before = run.examples_tested;
+        let generation_start = Instant::now();
+        let tree = match self.should_use_example(test_num, run.examples_tested) {
+            Some(example_index) => {
+                run.examples_tested += 1;
+                Tree::singleton(self.examples[example_index].clone())
+            }
+            None => {
+                let generated = self
+                    .generator
+                    .try_generate(size, test_seed)
+                    .and_then(|tree| match config.timeout {
+                        Some(limit) if generation_start.elapsed() > limit => {
+                            Err(HedgehogError::GenerationTimeout {
+                                size: size.get(),
+                                elapsed: generation_start.elapsed(),
+                                limit,
+                            })
+                        }
+                        _ => Ok(tree),
+                    });
+                let tree = match generated {
+                    Ok(tree) => tree,
+                    Err(error) => {
+                        let failure = self.generation_failure(
+                            error,
+                            test_num,
+                            size,
+                            property_name,
+                            module_path,
+                        );
+                        return Some(failure);
+                    }
+                };
+                statistics.max_size = statistics.max_size.max(size.get());
+                if let Some(hash) = &self.duplicate_hash {
+                    if !run.seen.insert(hash(&tree.value)) {
+                        statistics.duplicates += 1;
                     }
                 }
-                other => return other,
+                tree
             }
+        };
+        statistics.timings.generation += generation_start.elapsed();
+
+        statistics.discards += take_generation_discards();
+        if statistics.discards > config.discard_limit {
+            return Some(TestResult::Discard {
+                limit: config.discard_limit,
+                property_name: property_name.map(|s| s.to_string()),
+                module_path: module_path.map(|s| s.to_string()),
+            });
         }
 
-        statistics.total_tests = config.test_limit;
+        if let (Some(budget), Some(estimate)) = (config.max_value_bytes, &self.byte_estimate) {
+            let bytes = estimate(&tree.value);
+            if bytes > budget {
+                // Too large to shrink, or to print
+                return Some(TestResult::Fail {
+                    counterexample: format!("<value of about {bytes} bytes>"),
+                    tests_run: test_num + 1,
+                    shrinks_performed: 0,
+                    property_name: property_name.map(|s| s.to_string()),
+                    module_path: module_path.map(|s| s.to_string()),
+                    assertion_type: Some(format!(
+                        "Value Over Byte Budget\nabout {bytes} bytes generated at size {}, \
+                         over the budget of {budget} bytes",
+                        size.get()
+                    )),
+                    shrink_steps: Vec::new(),
+                    details: FailureDetails {
+                        location: Some(self.location.to_string()),
+                        size: Some(size.get()),
+                        generator: self.generator.description(),
+                        origin: tree.origin().map(str::to_string),
+                        ..Default::default()
+                    },
+                });
+            }
+        }
+
+        if config.verify_determinism {
+            let generated = run.examples_tested == examples_before;
+            if let Some(assertion) =
+                self.nondeterminism(&tree.value, generated, size, test_seed, config)
+            {
+                return Some(TestResult::Fail {
+                    counterexample: self.show(&tree.value, config),
+                    tests_run: test_num + 1,
+                    shrinks_performed: 0,
+                    property_name: property_name.map(|s| s.to_string()),
+                    module_path: module_path.map(|s| s.to_string()),
+                    assertion_type: Some(assertion),
+                    shrink_steps: Vec::new(),
+                    details: FailureDetails {
+                        location: Some(self.location.to_string()),
+                        size: Some(size.get()),
+                        generator: self.generator.description(),
+                        origin: tree.origin().map(str::to_string),
+                        ..Default::default()
+                    },
+                });
+            }
+        }
 
-        // Return PassWithStatistics only if we have classifications or collections
-        if !self.classifications.is_empty() || !self.collections.is_empty() {
+        // Collect statistics from the generated value
+        self.collect_statistics(&tree.value, statistics);
+
+        let location = self.location.to_string();
+        let name = property_name.unwrap_or(&location);
+        match self.check_tree(&tree, config, name, statistics) {
+            TestResult::Pass { .. } => None,
+            TestResult::Fail {
+                counterexample,
+                shrinks_performed,
+                shrink_steps,
+                assertion_type,
+                details,
+                ..
+            } => {
+                if config.size_diagnostics {
+                    eprintln!("{}", self.diagnose_sizes(config, SIZE_DIAGNOSIS_TESTS));
+                }
+                Some(TestResult::Fail {
+                    counterexample,
+                    tests_run: test_num + 1,
+                    shrinks_performed,
+                    property_name: property_name.map(|s| s.to_string()),
+                    module_path: module_path.map(|s| s.to_string()),
+                    assertion_type,
+                    shrink_steps,
+                    details: FailureDetails {
+                        location: Some(self.location.to_string()),
+                        size: Some(size.get()),
+                        generator: self.generator.description(),
+                        ..details
+                    },
+                })
+            }
+            other => Some(other),
+        }
+    }
+
+    /// The outcome of a run whose generator couldn't produce a case.
+    fn generation_failure(
+        &self,
+        error: HedgehogError,
+        test_num: usize,
+        size: Size,
+        property_name: Option<&str>,
+        module_path: Option<&str>,
+    ) -> TestResult {
+        let headline = match error {
+            HedgehogError::GenerationTimeout { .. } => "Generation Timed Out",
+            HedgehogError::FilterTooRestrictive { .. } => "Filter Too Restrictive",
+            _ => "Generation Failed",
+        };
+        let hint = error
+            .hint()
+            .map_or(String::new(), |hint| format!("\nhint: {hint}"));
+        TestResult::Fail {
+            counterexample: "<no value generated>".to_string(),
+            tests_run: test_num + 1,
+            shrinks_performed: 0,
+            property_name: property_name.map(|s| s.to_string()),
+            module_path: module_path.map(|s| s.to_string()),
+            assertion_type: Some(format!("{headline}\n{error}{hint}")),
+            shrink_steps: Vec::new(),
+            details: FailureDetails {
+                location: Some(self.location.to_string()),
+                size: Some(size.get()),
+                generator: self.generator.description(),
+                ..Default::default()
+            },
+        }
+    }
+
+    /// How a case came out differently when run again, if it did: the
+    /// input generated again from the same size and seed, or the verdict of
+    /// a second call of the test. Examples aren't generated, so only their
+    /// verdicts are compared.
+    fn nondeterminism(
+        &self,
+        value: &T,
+        generated: bool,
+        size: Size,
+        seed: Seed,
+        config: &Config,
+    ) -> Option<String> {
+        if generated {
+            let again = self.generator.generate(size, seed).value;
+            // The first generation's discards are already counted
+            take_generation_discards();
+            if format!("{value:?}") != format!("{again:?}") {
+                return Some(format!(
+                    "Nondeterministic Generator\n{}",
+                    diff(("first generation", value), ("second generation", &again))
+                ));
+            }
+        }
+        let first = determinism::verdict(&self.call(value, config));
+        let second = determinism::verdict(&self.call(value, config));
+        (first != second).then(|| {
+            format!("Nondeterministic Test\n- first call: {first}\n+ second call: {second}")
+        })
+    }
+
+    /// The outcome of a run that passed every case it ran.
+    pub(crate) fn finish_run(
+        &self,
+        run: &RunState,
+        config: &Config,
+        property_name: Option<&str>,
+        module_path: Option<&str>,
+        statistics: &mut TestStatistics,
+    ) -> TestResult {
+        let module_path = module_path.or(self.module_path.as_deref());
+        let tests_run = run.test_num;
+        statistics.total_tests = tests_run;
+        statistics.generator = self.generator.description();
+
+        if statistics.mostly_duplicates() {
+            eprintln!(
+                "warning: {} of {tests_run} cases of {} were duplicates; fewer tests or a wider range would cover the same ground",
+                statistics.duplicates,
+                property_name.unwrap_or("the property"),
+            );
+        }
+
+        // Return PassWithStatistics only if we have classifications, collections,
+        // observations, retries or discards, or timings were asked for
+        if !self.classifications.is_empty()
+            || !statistics.classifications.is_empty()
+            || !self.collections.is_empty()
+            || !statistics.retried_cases.is_empty()
+            || statistics.discards > 0
+            || config.report_timings
+        {
             TestResult::PassWithStatistics {
-                tests_run: config.test_limit,
+                tests_run,
                 property_name: property_name.map(|s| s.to_string()),
                 module_path: module_path.map(|s| s.to_string()),
-                statistics,
+                statistics: statistics.clone(),
             }
         } else {
             TestResult::Pass {
-                tests_run: config.test_limit,
+                tests_run,
                 property_name: property_name.map(|s| s.to_string()),
                 module_path: module_path.map(|s| s.to_string()),
             }
         }
     }
 
+    /// Re-run the property at fixed sizes, from 0 upwards, to find the
+    /// smallest size at which it fails.
+    ///
+    /// Up to `tests_per_size` cases run at each size up to the config's size
+    /// limit, stopping at the first failure. Failures are not shrunk. A
+    /// property that only fails at large sizes points at a bug that needs
+    /// big inputs; one that fails from size 0 does not.
+    ///
+    /// # Example
+    /// ```rust
+    /// use hedgehog_core::*;
+    ///
+    /// let prop = for_all(Gen::vec_of(Gen::int_range(0, 9)), |xs: &Vec<i32>| xs.len() < 20);
+    /// let diagnosis = prop.diagnose_sizes(&Config::default(), 20);
+    /// assert!(diagnosis.smallest_failing_size.is_some_and(|size| size >= 20));
+    /// ```
+    pub fn diagnose_sizes(&self, config: &Config, tests_per_size: usize) -> SizeDiagnosis {
+        let mut seed = Seed::random();
+        let mut sizes = Vec::new();
+        for size in 0..=config.size_limit {
+            let mut tests_run = 0;
+            for _ in 0..tests_per_size {
+                let (test_seed, next_seed) = seed.split();
+                seed = next_seed;
+                let tree = self.generator.generate(Size::new(size), test_seed);
+                take_generation_discards();
+                tests_run += 1;
+                if let TestResult::Fail { .. } = self.run_test(&tree.value, config).0 {
+                    sizes.push((size, tests_run));
+                    return SizeDiagnosis {
+                        sizes,
+                        smallest_failing_size: Some(size),
+                    };
+                }
+            }
+            sizes.push((size, tests_run));
+        }
+        SizeDiagnosis {
+            sizes,
+            smallest_failing_size: None,
+        }
+    }
+
+    /// Run every case, grouping failures by cause instead of stopping at the
+    /// first one.
+    ///
+    /// Failures are grouped by their assertion type with numbers ignored,
+    /// so a panic message mentioning the failing index lands in one cluster
+    /// whatever the index. The first failure in each cluster is shrunk,
+    /// accepting only smaller inputs that fail the same way, so each cluster
+    /// keeps its own minimal counterexample. Panics still unwind through the
+    /// run unless the property uses [`no_panic`](Property::no_panic).
+    ///
+    /// # Example
+    /// ```rust
+    /// use hedgehog_core::*;
+    ///
+    /// let prop = for_all(Gen::int_range(0, 100), |&n| {
+    ///     assert!(n % 7 != 3, "remainder 3");
+    ///     assert!(n < 90, "too big");
+    ///     true
+    /// })
+    /// .no_panic();
+    /// let report = prop.run_clustered(&Config::default());
+    /// assert!(report.clusters.len() <= 2);
+    /// ```
+    pub fn run_clustered(&self, config: &Config) -> FailureClusters {
+        let mut run = self.begin_run();
+        let mut clusters: Vec<FailureCluster> = Vec::new();
+        while self.has_next_case(&run, config) {
+            let size = Size::new((run.test_num * config.size_limit) / config.test_limit);
+            run.test_num += 1;
+            let (test_seed, next_seed) = run.seed.split();
+            run.seed = next_seed;
+            let tree = self.generator.generate(size, test_seed);
+            take_generation_discards();
+
+            let TestResult::Fail { assertion_type, .. } = self.run_test(&tree.value, config).0
+            else {
+                continue;
+            };
+            let signature = failure_signature(assertion_type.as_deref());
+            if let Some(cluster) = clusters
+                .iter_mut()
+                .find(|cluster| cluster.signature == signature)
+            {
+                cluster.failures += 1;
+                continue;
+            }
+            let (minimal, shrinks_performed) = self.shrink_matching(&tree, config, &signature);
+            clusters.push(FailureCluster {
+                counterexample: self.show(minimal, config),
+                signature,
+                assertion_type,
+                failures: 1,
+                shrinks_performed,
+            });
+        }
+        clusters.sort_by_key(|cluster| std::cmp::Reverse(cluster.failures));
+        FailureClusters {
+            tests_run: run.test_num,
+            clusters,
+        }
+    }
+
+    /// Shrink a failure through the first child at each level that fails
+    /// with the same signature, returning the smallest input reached and
+    /// the number of steps taken.
+    fn shrink_matching<'a>(
+        &self,
+        tree: &'a Tree<T>,
+        config: &Config,
+        signature: &str,
+    ) -> (&'a T, usize) {
+        let mut current = tree;
+        let mut shrinks = 0;
+        while shrinks < config.shrink_limit {
+            let same_failure = current.children.iter().find(|child| {
+                matches!(
+                    self.run_test(&child.value, config).0,
+                    TestResult::Fail { assertion_type, .. }
+                        if failure_signature(assertion_type.as_deref()) == signature
+                )
+            });
+            match same_failure {
+                Some(child) => {
+                    current = child;
+                    shrinks += 1;
+                }
+                None => break,
+            }
+        }
+        (&current.value, shrinks)
+    }
+
     /// Collect statistics from a test input.
     fn collect_statistics(&self, value: &T, statistics: &mut TestStatistics) {
         // Apply all classifications
@@ -334,9 +1769,82 @@ where
         }
     }
 
-    /// Check a single tree, attempting to shrink on failure.
-    fn check_tree(&self, tree: &Tree<T>, config: &Config) -> TestResult {
-        match (self.test_function)(&tree.value) {
+    /// Run the test function on a value, re-running a failure as many times
+    /// as the config allows and letting its retry policy decide the outcome.
+    fn run_test(&self, value: &T, config: &Config) -> (TestResult, Option<RetriedCase>) {
+        let result = self.call(value, config);
+        if config.retries == 0 || !matches!(result, TestResult::Fail { .. }) {
+            return (result, None);
+        }
+
+        let mut passes = 0;
+        let mut failures = 1;
+        let mut passing_result = None;
+        for _ in 0..config.retries {
+            match self.call(value, config) {
+                TestResult::Fail { .. } => failures += 1,
+                TestResult::Discard { .. } => {}
+                other => {
+                    passes += 1;
+                    passing_result.get_or_insert(other);
+                }
+            }
+        }
+
+        let retried = RetriedCase {
+            input: format!("{value:?}"),
+            passes,
+            failures,
+        };
+        match passing_result {
+            Some(passing) if config.retry_policy.accepts(passes, failures) => {
+                (passing, Some(retried))
+            }
+            _ => (result, Some(retried)),
+        }
+    }
+
+    /// Call the test function on a value, in a child process if the config
+    /// asks for isolation.
+    fn call(&self, value: &T, config: &Config) -> TestResult {
+        crate::rng::restart();
+        let _backtraces = crate::panic::Backtraces::enter(config.panic_backtraces);
+        #[cfg(all(unix, feature = "isolation"))]
+        if config.process_isolation {
+            return crate::isolation::run(|| (self.test_function)(value)).unwrap_or_else(|crash| {
+                TestResult::Fail {
+                    counterexample: format!("{value:?}"),
+                    tests_run: 1,
+                    shrinks_performed: 0,
+                    property_name: None,
+                    module_path: None,
+                    assertion_type: Some(crash.assertion_type()),
+                    shrink_steps: Vec::new(),
+                    details: FailureDetails::default(),
+                }
+            });
+        }
+        (self.test_function)(value)
+    }
+
+    /// Check a single tree, attempting to shrink on failure. `name` is the
+    /// property's name, for its shrink graph.
+    fn check_tree(
+        &self,
+        tree: &Tree<T>,
+        config: &Config,
+        name: &str,
+        statistics: &mut TestStatistics,
+    ) -> TestResult {
+        let execution_start = Instant::now();
+        let ((result, retried), observed) = observe::capture(|| self.run_test(&tree.value, config));
+        statistics.timings.execution += execution_start.elapsed();
+        statistics.retried_cases.extend(retried);
+        for label in &observed {
+            statistics.record_classification(label);
+        }
+
+        match result {
             TestResult::Pass { .. } => TestResult::Pass {
                 tests_run: 1,
                 property_name: None,
@@ -347,56 +1855,107 @@ where
                 tests_run,
                 shrinks_performed,
                 assertion_type,
+                details: FailureDetails { panic, .. },
                 ..
             } => {
                 // Try to shrink the failing case
-                let (shrunk_counterexample, shrink_steps) = self.shrink_failure(tree, config);
+                let shrinking_start = Instant::now();
+                let (shrunk, shrink_steps, shrink_budget) = self.shrink_failure(tree, config, name);
+                statistics.timings.shrinking += shrinking_start.elapsed();
+                let minimal = shrunk.as_ref().map_or(&tree.value, |(value, _)| value);
+
+                if let Some((export, literal)) = &self.regression {
+                    export.export(&literal(minimal), Some(&self.location.to_string()));
+                }
+                if let Some((directory, printed)) = &self.corpus_capture {
+                    match store_corpus_entry(directory, &printed(minimal)) {
+                        Ok(path) => eprintln!("Stored counterexample as {}", path.display()),
+                        Err(error) => {
+                            eprintln!("Could not store in {}: {error}", directory.display())
+                        }
+                    }
+                }
+
+                // Test functions show their input compactly; show it again
+                // laid out for reading when it's large. A shrunk input
+                // reports its own assertion, which may mention the input.
+                let (counterexample, assertion_type, panic) = match shrunk {
+                    Some((value, (shrunk_assertion, shrunk_panic))) => (
+                        self.show(value, config),
+                        shrunk_assertion.or(assertion_type),
+                        shrunk_panic,
+                    ),
+                    None if counterexample == format!("{:?}", tree.value) => {
+                        (self.show(&tree.value, config), assertion_type, panic)
+                    }
+                    None => (counterexample, assertion_type, panic),
+                };
 
                 TestResult::Fail {
-                    counterexample: shrunk_counterexample.unwrap_or(counterexample),
+                    counterexample,
                     tests_run,
                     shrinks_performed: shrinks_performed
-                        .saturating_add(shrink_steps.len().saturating_sub(1)),
+                        .saturating_add(shrink_steps.last().map_or(0, |step| step.step)),
                     property_name: None,
                     module_path: None,
                     assertion_type,
                     shrink_steps,
+                    details: FailureDetails {
+                        origin: tree.origin().map(str::to_string),
+                        panic,
+                        shrink_budget,
+                        ..Default::default()
+                    },
                 }
             }
             other => other,
         }
     }
 
-    /// Attempt to find a smaller failing case through shrinking.
-    fn shrink_failure(&self, tree: &Tree<T>, config: &Config) -> (Option<String>, Vec<ShrinkStep>) {
+    /// Attempt to find a smaller failing case through shrinking, returning
+    /// it with the assertion type and panic it failed with.
+    fn shrink_failure<'a>(&self, tree: &'a Tree<T>, config: &Config, name: &str) -> Shrunk<'a, T> {
+        if let Some(deadline) = config.shrink_deadline {
+            return self.shrink_failure_within(tree, config, name, deadline);
+        }
+
         let mut shrink_steps = Vec::new();
         let mut current_failure = &tree.value;
+        let mut current_detail = (None, None);
         let mut shrink_count = 0;
 
         // Add the original failing value as step 0
-        shrink_steps.push(ShrinkStep {
-            counterexample: format!("{current_failure:?}"),
-            step: 0,
-            variable_name: self.variable_name.clone(),
-        });
+        shrink_steps.extend(self.shrink_steps_for(current_failure, None, 0, config));
 
         // Simple breadth-first shrinking
-        for shrink_value in tree.shrinks() {
+        let candidates = shrink_graph::candidates(tree, config.shrink_candidate_limit);
+        let mut outcomes = vec![ShrinkOutcome::Untried; candidates.len()];
+        let mut minimal = 0;
+        for (i, &(_, shrink_value)) in candidates.iter().enumerate() {
             if shrink_count >= config.shrink_limit {
                 break;
             }
 
-            match (self.test_function)(shrink_value) {
-                TestResult::Fail { .. } => {
-                    current_failure = shrink_value;
+            let result = self.run_test(shrink_value, config).0;
+            outcomes[i] = shrink_outcome(&result);
+            match result {
+                TestResult::Fail {
+                    assertion_type,
+                    details: FailureDetails { panic, .. },
+                    ..
+                } => {
                     shrink_count += 1;
+                    minimal = i + 1;
 
                     // Record this shrinking step
-                    shrink_steps.push(ShrinkStep {
-                        counterexample: format!("{shrink_value:?}"),
-                        step: shrink_count,
-                        variable_name: self.variable_name.clone(),
-                    });
+                    shrink_steps.extend(self.shrink_steps_for(
+                        shrink_value,
+                        Some(current_failure),
+                        shrink_count,
+                        config,
+                    ));
+                    current_failure = shrink_value;
+                    current_detail = (assertion_type, panic);
                 }
                 TestResult::Pass { .. } => continue,
                 TestResult::PassWithStatistics { .. } => continue,
@@ -404,15 +1963,174 @@ where
             }
         }
 
+        if let Some(path) = &config.shrink_graph {
+            shrink_graph::graph(&tree.value, &candidates, &outcomes, minimal)
+                .write(&property_path(path, name));
+        }
+
+        let budget = (shrink_count >= config.shrink_limit && minimal < candidates.len())
+            .then_some(config.shrink_limit);
+
         if shrink_count > 0 {
-            (Some(format!("{current_failure:?}")), shrink_steps)
+            (
+                Some((current_failure, current_detail)),
+                shrink_steps,
+                budget,
+            )
         } else {
-            (None, shrink_steps)
+            (None, shrink_steps, budget)
+        }
+    }
+
+    /// Shrink under a time budget, trying the structurally smallest
+    /// candidates first.
+    ///
+    /// The length of a candidate's debug representation stands in for its
+    /// structural size: shorter lists and smaller magnitudes print shorter.
+    /// Each node's shrinks are tried smallest first, and the first that
+    /// still fails becomes the node whose shrinks are tried next, so each
+    /// step takes the biggest cheap jump and a short budget still lands on a
+    /// small counterexample rather than on the first of many fine-grained
+    /// ones. Shrinking goes on until no shrink fails, the deadline passes or
+    /// `shrink_limit` candidates have been tried.
+    fn shrink_failure_within<'a>(
+        &self,
+        tree: &'a Tree<T>,
+        config: &Config,
+        name: &str,
+        deadline: Duration,
+    ) -> Shrunk<'a, T> {
+        let started = Instant::now();
+        let per_node = config.shrink_candidate_limit.unwrap_or(usize::MAX);
+        let mut shrink_steps = self.shrink_steps_for(&tree.value, None, 0, config);
+        let mut seen = HashSet::from([debug_hash(&tree.value)]);
+        let mut candidates = Vec::new();
+        let mut outcomes = Vec::new();
+        let mut current = (0, tree);
+        let mut shrunk = None;
+        let mut shrink_count = 0;
+        let mut budget = None;
+
+        'descent: loop {
+            let mut children: Vec<&Tree<T>> = current
+                .1
+                .children
+                .iter()
+                .filter(|child| seen.insert(debug_hash(&child.value)))
+                .take(per_node)
+                .collect();
+            children.sort_by_cached_key(|child| format!("{:?}", child.value).len());
+
+            let mut next = None;
+            for child in children {
+                if started.elapsed() >= deadline {
+                    break 'descent;
+                }
+                if candidates.len() >= config.shrink_limit {
+                    budget = Some(config.shrink_limit);
+                    break 'descent;
+                }
+                candidates.push((current.0, &child.value));
+                let result = self.run_test(&child.value, config).0;
+                outcomes.push(shrink_outcome(&result));
+                if let TestResult::Fail {
+                    assertion_type,
+                    details: FailureDetails { panic, .. },
+                    ..
+                } = result
+                {
+                    let previous = shrunk.as_ref().map_or(&tree.value, |(_, value, _)| *value);
+                    shrink_count += 1;
+                    shrink_steps.extend(self.shrink_steps_for(
+                        &child.value,
+                        Some(previous),
+                        shrink_count,
+                        config,
+                    ));
+                    shrunk = Some((candidates.len(), &child.value, (assertion_type, panic)));
+                    next = Some((candidates.len(), child));
+                    break;
+                }
+            }
+            match next {
+                Some(node) => current = node,
+                None => break,
+            }
+        }
+
+        if let Some(path) = &config.shrink_graph {
+            outcomes.resize(candidates.len(), ShrinkOutcome::Untried);
+            let minimal = shrunk.as_ref().map_or(0, |(id, _, _)| *id);
+            shrink_graph::graph(&tree.value, &candidates, &outcomes, minimal)
+                .write(&property_path(path, name));
+        }
+
+        (
+            shrunk.map(|(_, value, detail)| (value, detail)),
+            shrink_steps,
+            budget,
+        )
+    }
+
+    /// Show a counterexample, one named part per line if the property has
+    /// variable names.
+    fn show(&self, value: &T, config: &Config) -> String {
+        let full = config.full_counterexamples;
+        match &self.variable_names {
+            Some((names, components)) => names
+                .iter()
+                .zip(components(value))
+                .map(|(name, part)| {
+                    format!("{name} = {}", crate::pretty::counterexample(part, full))
+                })
+                .collect::<Vec<_>>()
+                .join("\n"),
+            None => crate::pretty::counterexample(value, full),
         }
     }
+
+    /// The shrink steps recording `value` as step `step`: the whole value,
+    /// or with variable names, each part that differs from `previous`.
+    fn shrink_steps_for(
+        &self,
+        value: &T,
+        previous: Option<&T>,
+        step: usize,
+        config: &Config,
+    ) -> Vec<ShrinkStep> {
+        let full = config.full_counterexamples;
+        let Some((names, components)) = &self.variable_names else {
+            return vec![ShrinkStep {
+                counterexample: crate::pretty::counterexample(value, full),
+                step,
+                variable_name: self.variable_name.clone(),
+            }];
+        };
+
+        let before: Vec<String> = previous
+            .map(|previous| {
+                components(previous)
+                    .iter()
+                    .map(|part| format!("{part:?}"))
+                    .collect()
+            })
+            .unwrap_or_default();
+        names
+            .iter()
+            .zip(components(value))
+            .enumerate()
+            .filter(|(index, (_, part))| before.get(*index) != Some(&format!("{part:?}")))
+            .map(|(_, (name, part))| ShrinkStep {
+                counterexample: crate::pretty::counterexample(part, full),
+                step,
+                variable_name: Some(name.clone()),
+            })
+            .collect()
+    }
 }
 
 /// Create a property for a generator and test function.
+#[track_caller]
 pub fn property<T, F>(generator: Gen<T>, test_function: F) -> Property<T>
 where
     T: 'static + std::fmt::Debug + Clone,
@@ -422,6 +2140,7 @@ where
 }
 
 /// Create a property that checks a boolean condition.
+#[track_caller]
 pub fn for_all<T, F>(generator: Gen<T>, condition: F) -> Property<T>
 where
     T: 'static + std::fmt::Debug + Clone,
@@ -431,6 +2150,7 @@ where
 }
 
 /// Create a property that checks a boolean condition with a named variable.
+#[track_caller]
 pub fn for_all_named<T, F>(generator: Gen<T>, variable_name: &str, condition: F) -> Property<T>
 where
     T: 'static + std::fmt::Debug + Clone,
@@ -439,6 +2159,60 @@ where
     Property::for_all_named(generator, variable_name, condition)
 }
 
+/// Create a property that checks a boolean condition, generating inputs
+/// with the [`DefaultGen`](crate::default_gen::DefaultGen) for their type.
+///
+/// # Example
+/// ```rust
+/// use hedgehog_core::*;
+///
+/// let prop = for_all_default(|(xs, n): &(Vec<i32>, usize)| {
+///     xs.iter().take(*n).count() <= *n
+/// });
+/// ```
+#[track_caller]
+pub fn for_all_default<T, F>(condition: F) -> Property<T>
+where
+    T: crate::default_gen::DefaultGen + 'static + std::fmt::Debug + Clone,
+    F: Fn(&T) -> bool + 'static,
+{
+    Property::for_all(T::default_gen(), condition)
+}
+
+/// Create a property whose condition also gets a value built from the
+/// input, built once per distinct input and run.
+#[track_caller]
+pub fn for_all_memoized<T, A, S, F>(generator: Gen<T>, setup: S, condition: F) -> Property<T>
+where
+    T: 'static + std::fmt::Debug + Clone + Hash + Eq,
+    A: 'static,
+    S: Fn(&T) -> A + 'static,
+    F: Fn(&T, &A) -> bool + 'static,
+{
+    Property::for_all_memoized(generator, setup, condition)
+}
+
+/// Create a property that passes when the test function panics.
+#[track_caller]
+pub fn for_all_panics<T, F>(generator: Gen<T>, f: F) -> Property<T>
+where
+    T: 'static + std::fmt::Debug + Clone,
+    F: Fn(&T) + 'static,
+{
+    Property::for_all_panics(generator, f)
+}
+
+/// Create a property that passes when the test function panics with a
+/// message containing `expected`.
+#[track_caller]
+pub fn for_all_panics_with<T, F>(generator: Gen<T>, expected: &str, f: F) -> Property<T>
+where
+    T: 'static + std::fmt::Debug + Clone,
+    F: Fn(&T) + 'static,
+{
+    Property::for_all_panics_with(generator, expected, f)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -469,6 +2243,23 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_for_all_default() {
+        let prop = for_all_default(|(n, s): &(u8, String)| (*n as usize) < 200 || s.len() < 5);
+        match prop.run(&Config::default()) {
+            TestResult::Fail { counterexample, .. } => {
+                assert!(counterexample.starts_with('('), "{counterexample}");
+            }
+            other => panic!("Expected failure, got: {other:?}"),
+        }
+
+        let prop = for_all_default(|xs: &Vec<Option<bool>>| xs.len() < 1000);
+        assert!(matches!(
+            prop.run(&Config::default()),
+            TestResult::Pass { .. }
+        ));
+    }
+
     #[test]
     fn test_boolean_generator_reliability() {
         // Test that boolean generator with SplitMix64 produces both true and false
@@ -534,6 +2325,7 @@ mod tests {
                     variable_name: None,
                 },
             ],
+            details: FailureDetails::default(),
         };
 
         // Capture the failure output for regression testing
@@ -573,6 +2365,7 @@ mod tests {
                     variable_name: Some("n".to_string()),
                 },
             ],
+            details: FailureDetails::default(),
         };
 
         let formatted_output = format!("{expected_result}");
@@ -598,79 +2391,496 @@ mod tests {
                 let output = format!("{result}");
                 archetype::snap("enhanced_success_reporting", output);
             }
-            other => panic!("Expected success, got: {other:?}"),
+            other => panic!("Expected success, got: {other:?}"),
+        }
+    }
+
+    #[test]
+    fn test_property_classification() {
+        // Test that property classification works correctly
+        let gen = Gen::int_range(-10, 10);
+        let prop = for_all(gen, |&x| (-10..=10).contains(&x)) // Always passes
+            .classify("negative", |&x| x < 0)
+            .classify("zero", |&x| x == 0)
+            .classify("positive", |&x| x > 0)
+            .collect("value", |&x| x as f64);
+
+        let config = Config::default().with_tests(50);
+        let result = prop.run(&config);
+
+        match result {
+            TestResult::PassWithStatistics { statistics, .. } => {
+                // Should have some classifications (but zero might not always appear with only 50 tests)
+                assert!(!statistics.classifications.is_empty());
+
+                // Should at least have negative or positive (much more likely than zero)
+                assert!(
+                    statistics.classifications.contains_key("negative")
+                        || statistics.classifications.contains_key("positive")
+                );
+
+                // Should have collected values
+                assert!(statistics.collections.contains_key("value"));
+                let values = &statistics.collections["value"];
+                assert_eq!(values.len(), 50);
+
+                // Values should be in range
+                for &value in values {
+                    assert!((-10.0..=10.0).contains(&value));
+                }
+
+                assert_eq!(statistics.total_tests, 50);
+            }
+            other => panic!("Expected PassWithStatistics, got: {other:?}"),
+        }
+    }
+
+    #[test]
+    fn test_classification_with_nan_values() {
+        // Test that NaN and infinite values are handled gracefully
+        let gen = Gen::int_range(1, 5);
+        let prop = for_all(gen, |&x| x > 0)
+            .collect("problematic", |&x| match x {
+                1 => f64::NAN,
+                2 => f64::INFINITY,
+                3 => f64::NEG_INFINITY,
+                _ => x as f64,
+            })
+            .collect("normal", |&x| x as f64);
+
+        let config = Config::default().with_tests(20);
+        let result = prop.run(&config);
+
+        match &result {
+            TestResult::PassWithStatistics { statistics, .. } => {
+                // Should have both collections
+                assert!(statistics.collections.contains_key("problematic"));
+                assert!(statistics.collections.contains_key("normal"));
+
+                // The output should format without panicking
+                let output = format!("{result}");
+                assert!(output.contains("normal"));
+                // Problematic collection might not appear if all values are NaN/infinite
+            }
+            other => panic!("Expected PassWithStatistics, got: {other:?}"),
+        }
+    }
+
+    #[test]
+    fn test_statistics_merge() {
+        let mut left = TestStatistics::new();
+        left.record_classification("small");
+        left.record_collection("value", 1.0);
+        left.total_tests = 1;
+
+        let mut right = TestStatistics::new();
+        right.record_classification("small");
+        right.record_classification("large");
+        right.record_collection("value", 2.0);
+        right.total_tests = 2;
+        right.discards = 3;
+
+        left.merge(&right);
+
+        assert_eq!(left.classifications["small"], 2);
+        assert_eq!(left.classifications["large"], 1);
+        assert_eq!(left.collections["value"], vec![1.0, 2.0]);
+        assert_eq!(left.total_tests, 3);
+        assert_eq!(left.discards, 3);
+        assert!(!left.is_empty());
+        assert!(TestStatistics::new().is_empty());
+    }
+
+    #[test]
+    fn test_try_map_discards_are_counted() {
+        let gen = Gen::int_range(0, 100).try_map(|n| if n % 2 == 0 { Ok(n) } else { Err(n) });
+        let prop = for_all(gen, |&n| n % 2 == 0);
+
+        match prop.run(&Config::default().with_tests(50)) {
+            TestResult::PassWithStatistics { statistics, .. } => {
+                assert!(statistics.discards > 0);
+            }
+            other => panic!("Expected pass with discards, got: {other:?}"),
+        }
+
+        let mostly_rejected =
+            Gen::int_range(0, 99).try_map(|n| if n % 4 == 0 { Ok(n) } else { Err(n) });
+        let config = Config {
+            discard_limit: 10,
+            ..Config::default()
+        };
+        assert!(matches!(
+            for_all(mostly_rejected, |_| true).run(&config),
+            TestResult::Discard { limit: 10, .. }
+        ));
+    }
+
+    #[test]
+    fn test_duplicates_are_counted_and_reported() {
+        let prop = for_all(Gen::int_range(0, 3), |_| true).with_duplicate_tracking();
+        match prop.run(&Config::default().with_tests(100).with_timings()) {
+            TestResult::PassWithStatistics { statistics, .. } => {
+                assert!(statistics.duplicates >= 96);
+                assert!(statistics.mostly_duplicates());
+            }
+            other => panic!("Expected pass with duplicates, got: {other:?}"),
+        }
+
+        let prop = for_all(Gen::<u64>::from_range(Range::new(0, u64::MAX)), |_| true)
+            .with_duplicate_tracking();
+        match prop.run(&Config::default().with_tests(100).with_timings()) {
+            TestResult::PassWithStatistics { statistics, .. } => {
+                assert!(!statistics.mostly_duplicates());
+            }
+            other => panic!("Expected pass with statistics, got: {other:?}"),
+        }
+
+        // Untracked properties don't hash their inputs
+        let prop = for_all(Gen::int_range(0, 3), |_| true);
+        match prop.run(&Config::default().with_tests(100).with_timings()) {
+            TestResult::PassWithStatistics { statistics, .. } => {
+                assert_eq!(statistics.duplicates, 0);
+            }
+            other => panic!("Expected pass with statistics, got: {other:?}"),
+        }
+    }
+
+    #[test]
+    fn test_failures_record_their_size() {
+        let prop = for_all(Gen::vec_of(Gen::int_range(0, 9)), |xs: &Vec<i32>| {
+            xs.len() < 10
+        });
+        match prop.run(&Config::default()) {
+            TestResult::Fail { details, .. } => {
+                assert!(details.size.is_some_and(|size| size >= 10))
+            }
+            other => panic!("Expected failure, got: {other:?}"),
+        }
+
+        let diagnosis = prop.diagnose_sizes(&Config::default(), 50);
+        let smallest = diagnosis.smallest_failing_size.unwrap();
+        assert!(smallest >= 10);
+        assert_eq!(diagnosis.sizes.len(), smallest + 1);
+        assert!(diagnosis
+            .to_string()
+            .starts_with(&format!("Fails from size {smallest}")));
+
+        let passing = for_all(Gen::int_range(0, 9), |_| true);
+        let diagnosis = passing.diagnose_sizes(&Config::default().with_size_limit(5), 2);
+        assert_eq!(diagnosis.smallest_failing_size, None);
+        assert_eq!(
+            diagnosis.to_string(),
+            "No failure at sizes 0 to 5 in 12 cases."
+        );
+    }
+
+    #[test]
+    fn test_shrink_candidates_are_deduplicated_and_capped() {
+        use std::cell::Cell;
+
+        let shrinks = [3, 0, 0, 3, 1, 2, 0].map(Tree::singleton).to_vec();
+        let gen = Gen::new(move |_, _| Tree::with_children(3, shrinks.clone()));
+        let calls = Rc::new(Cell::new(0));
+        let counted = calls.clone();
+        let prop = for_all(gen, move |_| {
+            counted.set(counted.get() + 1);
+            false
+        });
+
+        // The original case, then 0, 1 and 2 once each
+        match prop.run(&Config::default().with_tests(1)) {
+            TestResult::Fail { shrink_steps, .. } => assert_eq!(shrink_steps.len(), 4),
+            other => panic!("Expected failure, got: {other:?}"),
+        }
+        assert_eq!(calls.get(), 4);
+
+        calls.set(0);
+        match prop.run(&Config::default().with_tests(1).with_shrink_candidates(1)) {
+            TestResult::Fail { counterexample, .. } => assert_eq!(counterexample, "0"),
+            other => panic!("Expected failure, got: {other:?}"),
+        }
+        assert_eq!(calls.get(), 2);
+    }
+
+    #[test]
+    fn test_shrink_candidate_cap_speeds_up_large_inputs() {
+        use crate::data::Range;
+        use std::cell::Cell;
+
+        let calls = Rc::new(Cell::new(0));
+        let counted = calls.clone();
+        let gen = Gen::<Vec<i32>>::vec_with_range(Range::constant(500), Gen::int_range(0, 100));
+        let prop = for_all(gen, move |xs: &Vec<i32>| {
+            counted.set(counted.get() + 1);
+            xs.iter().all(|&x| x < 50)
+        });
+
+        let mut shrunk_lengths = Vec::new();
+        let mut call_counts = Vec::new();
+        for config in [
+            Config::default().with_tests(1),
+            Config::default().with_tests(1).with_shrink_candidates(10),
+        ] {
+            calls.set(0);
+            match prop.run(&config) {
+                TestResult::Fail { counterexample, .. } => {
+                    shrunk_lengths.push(counterexample.matches(',').count() + 1)
+                }
+                other => panic!("Expected failure, got: {other:?}"),
+            }
+            call_counts.push(calls.get());
+        }
+
+        // Removals come first, so the cap keeps the big reductions
+        assert!(call_counts[1] * 10 < call_counts[0], "{call_counts:?}");
+        assert!(shrunk_lengths[1] <= 250, "{shrunk_lengths:?}");
+    }
+
+    #[test]
+    fn test_marked_properties_do_not_fail_the_suite() {
+        let failing = || for_all(Gen::int_range(0, 100), |&n: &i32| n < 10);
+        let config = Config::default();
+
+        let expected = failing().expect_failure("issue-123");
+        assert_eq!(
+            expected.marker(),
+            Some(&PropertyMarker::ExpectedFailure("issue-123".to_string()))
+        );
+        assert!(matches!(expected.run(&config), TestResult::Pass { .. }));
+        assert!(matches!(
+            failing().quarantined().run(&config),
+            TestResult::Pass { .. }
+        ));
+        assert!(matches!(failing().run(&config), TestResult::Fail { .. }));
+
+        // A marked property that passes still passes
+        let fixed = for_all(Gen::int_range(0, 100), |&n: &i32| n <= 100).expect_failure("issue-9");
+        assert!(matches!(
+            fixed.run(&config),
+            TestResult::Pass { tests_run: 100, .. }
+        ));
+
+        // Combined properties keep the marker
+        let combined = failing().quarantined().and(failing());
+        assert_eq!(combined.marker(), Some(&PropertyMarker::Quarantined));
+    }
+
+    #[test]
+    fn test_generator_descriptions_are_reported() {
+        let users = Gen::vec_of(Gen::int_range(0, 100)).named("user");
+        let prop = for_all(users, |xs: &Vec<i32>| xs.len() < 3);
+        let result = prop.run(&Config::default());
+        let TestResult::Fail { details, .. } = &result else {
+            panic!("Expected failure, got: {result:?}");
+        };
+        assert_eq!(
+            details.generator.as_deref(),
+            Some("user → vec_of(int_range(0, 100))")
+        );
+        assert!(result
+            .to_string()
+            .contains("    input generated by: user → vec_of(int_range(0, 100))\n"));
+
+        let evens = Gen::int_range(0, 10).map(|n| n * 2).filter(|n| n % 4 == 0);
+        match for_all(evens, |_| true).run(&Config::default().with_timings()) {
+            TestResult::PassWithStatistics { statistics, .. } => {
+                assert_eq!(statistics.generator.as_deref(), Some("int_range(0, 10)"));
+            }
+            other => panic!("Expected statistics, got: {other:?}"),
+        }
+    }
+
+    #[test]
+    fn test_choice_branches_are_reported() {
+        let numbers = || {
+            Gen::frequency(vec![
+                WeightedChoice::new(9, Gen::int_range(0, 10)),
+                WeightedChoice::new(1, Gen::int_range(1000, 2000).named("large")),
+            ])
+            .unwrap()
+        };
+        let result = for_all(numbers(), |&n: &i32| n < 1000).run(&Config::default());
+        let TestResult::Fail {
+            counterexample,
+            details: FailureDetails { origin, .. },
+            ..
+        } = &result
+        else {
+            panic!("Expected failure, got: {result:?}");
+        };
+        assert!(counterexample.parse::<i32>().unwrap() >= 1000);
+        assert_eq!(origin.as_deref(), Some("frequency[1]: large"));
+        assert!(result
+            .to_string()
+            .contains("    chosen branch: frequency[1]: large\n"));
+
+        // Nested choices name every branch taken, outermost first
+        let nested = Gen::one_of(vec![Gen::constant(-1), numbers().map(|n| n + 1)]).unwrap();
+        match for_all(nested, |&n: &i32| n < 1000).run(&Config::default()) {
+            TestResult::Fail { details, .. } => {
+                assert_eq!(
+                    details.origin.as_deref(),
+                    Some("one_of[1] → frequency[1]: large")
+                )
+            }
+            other => panic!("Expected failure, got: {other:?}"),
+        }
+    }
+
+    #[test]
+    fn test_large_counterexamples_are_abbreviated() {
+        let large: Vec<u32> = (0..50).collect();
+        let prop = for_all(Gen::constant(large.clone()), |xs: &Vec<u32>| xs.is_empty());
+
+        match prop.run(&Config::default()) {
+            TestResult::Fail { counterexample, .. } => {
+                assert!(counterexample.starts_with("[\n    0,\n    1,\n"));
+                assert!(counterexample.contains("    ... and 40 more (50 in total)\n]"));
+            }
+            other => panic!("Expected failure, got: {other:?}"),
+        }
+        match prop.run(&Config::default().with_full_counterexamples()) {
+            TestResult::Fail { counterexample, .. } => {
+                assert_eq!(counterexample, format!("{large:#?}"));
+            }
+            other => panic!("Expected failure, got: {other:?}"),
+        }
+
+        let small = for_all(Gen::constant(vec![1, 2, 3]), |xs: &Vec<i32>| xs.is_empty());
+        match small.run(&Config::default()) {
+            TestResult::Fail { counterexample, .. } => assert_eq!(counterexample, "[1, 2, 3]"),
+            other => panic!("Expected failure, got: {other:?}"),
+        }
+    }
+
+    #[test]
+    fn test_failures_are_clustered_by_cause() {
+        let prop = for_all(Gen::int_range(0, 1000), |&n| {
+            assert!(n % 10 != 3, "ends in 3: {n}");
+            assert!(n < 500, "too big: {n}");
+            true
+        })
+        .no_panic();
+        let report = prop.run_clustered(&Config::default().with_tests(300));
+
+        let signatures: HashSet<&str> = report
+            .clusters
+            .iter()
+            .map(|cluster| cluster.signature.as_str())
+            .collect();
+        assert_eq!(
+            signatures,
+            HashSet::from(["Panic: ends in N: N", "Panic: too big: N"])
+        );
+        assert_eq!(report.tests_run, 300);
+        assert!(report.failures() > report.clusters.len());
+        assert!(report.clusters[0].failures >= report.clusters[1].failures);
+
+        let too_big = report
+            .clusters
+            .iter()
+            .find(|cluster| cluster.signature == "Panic: too big: N")
+            .unwrap();
+        let minimal: i32 = too_big.counterexample.parse().unwrap();
+        assert!(minimal >= 500 && minimal % 10 != 3);
+        assert!(report.to_string().contains("in 2 distinct ways"));
+    }
+
+    #[test]
+    fn test_external_inputs_are_minimized() {
+        let prop = for_all(Gen::<String>::ascii_alpha(), |s: &String| !s.contains("xy"));
+        match prop.minimize("abcxyzzy".to_string(), &Config::default()) {
+            TestResult::Fail {
+                counterexample,
+                shrinks_performed,
+                shrink_steps,
+                ..
+            } => {
+                assert_eq!(counterexample, "\"xy\"");
+                assert!(shrinks_performed > 0);
+                assert_eq!(shrink_steps[0].counterexample, "\"abcxyzzy\"");
+            }
+            other => panic!("Expected failure, got: {other:?}"),
         }
+        assert!(matches!(
+            prop.minimize("abc".to_string(), &Config::default()),
+            TestResult::Pass { .. }
+        ));
     }
 
     #[test]
-    fn test_property_classification() {
-        // Test that property classification works correctly
-        let gen = Gen::int_range(-10, 10);
-        let prop = for_all(gen, |&x| (-10..=10).contains(&x)) // Always passes
-            .classify("negative", |&x| x < 0)
-            .classify("zero", |&x| x == 0)
-            .classify("positive", |&x| x > 0)
-            .collect("value", |&x| x as f64);
-
-        let config = Config::default().with_tests(50);
-        let result = prop.run(&config);
-
-        match result {
-            TestResult::PassWithStatistics { statistics, .. } => {
-                // Should have some classifications (but zero might not always appear with only 50 tests)
-                assert!(!statistics.classifications.is_empty());
+    fn test_corpus_entries_run_first() {
+        let directory =
+            std::env::temp_dir().join(format!("hedgehog-property-corpus-{}", std::process::id()));
+        std::fs::create_dir_all(&directory).unwrap();
+        std::fs::write(directory.join("0a1b"), "1000\n").unwrap();
+
+        let prop = for_all(Gen::int_range(0, 10), |&n: &i32| n < 100).with_corpus_from(&directory);
+        std::fs::remove_dir_all(&directory).unwrap();
+        match prop.run(&Config::default()) {
+            TestResult::Fail { counterexample, .. } => assert_eq!(counterexample, "1000"),
+            other => panic!("Expected the corpus entry to fail, got: {other:?}"),
+        }
+    }
 
-                // Should at least have negative or positive (much more likely than zero)
-                assert!(
-                    statistics.classifications.contains_key("negative")
-                        || statistics.classifications.contains_key("positive")
-                );
+    #[test]
+    fn test_retries_absorb_transient_failures() {
+        use std::cell::Cell;
 
-                // Should have collected values
-                assert!(statistics.collections.contains_key("value"));
-                let values = &statistics.collections["value"];
-                assert_eq!(values.len(), 50);
+        let calls = Cell::new(0);
+        let prop = for_all(Gen::constant(7), move |_| {
+            calls.set(calls.get() + 1);
+            calls.get() % 3 != 1
+        });
 
-                // Values should be in range
-                for &value in values {
-                    assert!((-10.0..=10.0).contains(&value));
+        let config = Config::default()
+            .with_tests(5)
+            .with_retries(2, RetryPolicy::AnyPass);
+        match prop.run(&config) {
+            TestResult::PassWithStatistics { statistics, .. } => {
+                assert_eq!(statistics.retried_cases.len(), 5);
+                for case in &statistics.retried_cases {
+                    assert_eq!(case.input, "7");
+                    assert_eq!((case.passes, case.failures), (2, 1));
                 }
-
-                assert_eq!(statistics.total_tests, 50);
             }
-            other => panic!("Expected PassWithStatistics, got: {other:?}"),
+            other => panic!("Expected retried cases to pass, got: {other:?}"),
         }
     }
 
     #[test]
-    fn test_classification_with_nan_values() {
-        // Test that NaN and infinite values are handled gracefully
-        let gen = Gen::int_range(1, 5);
-        let prop = for_all(gen, |&x| x > 0)
-            .collect("problematic", |&x| match x {
-                1 => f64::NAN,
-                2 => f64::INFINITY,
-                3 => f64::NEG_INFINITY,
-                _ => x as f64,
+    fn test_retry_policy_decides_outcome() {
+        use std::cell::Cell;
+
+        // Fails twice, then passes once, for every case
+        let flaky = || {
+            let calls = Cell::new(0);
+            for_all(Gen::constant(1), move |_| {
+                calls.set(calls.get() + 1);
+                calls.get() % 3 == 0
             })
-            .collect("normal", |&x| x as f64);
-
-        let config = Config::default().with_tests(20);
-        let result = prop.run(&config);
-
-        match &result {
-            TestResult::PassWithStatistics { statistics, .. } => {
-                // Should have both collections
-                assert!(statistics.collections.contains_key("problematic"));
-                assert!(statistics.collections.contains_key("normal"));
+        };
 
-                // The output should format without panicking
-                let output = format!("{result}");
-                assert!(output.contains("normal"));
-                // Problematic collection might not appear if all values are NaN/infinite
-            }
-            other => panic!("Expected PassWithStatistics, got: {other:?}"),
-        }
+        let majority = Config::default()
+            .with_tests(3)
+            .with_retries(2, RetryPolicy::Majority);
+        assert!(matches!(flaky().run(&majority), TestResult::Fail { .. }));
+
+        let any = Config::default()
+            .with_tests(3)
+            .with_retries(2, RetryPolicy::AnyPass);
+        assert!(matches!(
+            flaky().run(&any),
+            TestResult::PassWithStatistics { .. }
+        ));
+
+        let without_retries = Config::default().with_tests(3);
+        assert!(matches!(
+            flaky().run(&without_retries),
+            TestResult::Fail { .. }
+        ));
+        assert!(RetryPolicy::AtLeast(2).accepts(2, 5));
+        assert!(!RetryPolicy::AtLeast(2).accepts(1, 0));
     }
 
     #[test]
@@ -689,6 +2899,12 @@ mod tests {
                 map
             },
             total_tests: 30,
+            discards: 0,
+            retried_cases: Vec::new(),
+            timings: PhaseTimings::default(),
+            duplicates: 0,
+            max_size: 0,
+            generator: None,
         };
 
         let result = TestResult::PassWithStatistics {
@@ -806,6 +3022,16 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_small_cases_stay_in_the_domain() {
+        let prop = for_all(Gen::int_range(1, 10), |&x| x > 0)
+            .with_small_cases(5, |x| (1..=10).contains(x));
+        assert_eq!(prop.examples, [1, 2, 3]);
+
+        let config = Config::default().with_tests(5);
+        assert!(matches!(prop.run(&config), TestResult::Pass { .. }));
+    }
+
     #[test]
     fn test_empty_examples() {
         // Test that empty examples work normally
@@ -823,6 +3049,45 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_tuple_variable_names() {
+        let gen =
+            Gen::<(i32, String)>::tuple_of(Gen::int_range(0, 100), Gen::<String>::ascii_alpha());
+        let prop = for_all(gen, |(n, _)| *n < 10).with_variable_names(["n", "name"]);
+
+        let result = prop.run(&Config::default());
+        let TestResult::Fail {
+            counterexample,
+            shrink_steps,
+            ..
+        } = &result
+        else {
+            panic!("Expected failure, got: {result:?}");
+        };
+        assert!(counterexample.starts_with("n = "));
+        assert!(counterexample.contains("\nname = \""));
+
+        // Step 0 reports both parts, later steps only what changed
+        assert_eq!(shrink_steps[0].variable_name.as_deref(), Some("n"));
+        assert_eq!(shrink_steps[1].variable_name.as_deref(), Some("name"));
+        assert!(shrink_steps.iter().all(|step| step.variable_name.is_some()));
+        let steps = shrink_steps.last().unwrap().step;
+        assert!(shrink_steps[2..]
+            .iter()
+            .all(|step| step.step > 0 && step.step <= steps));
+
+        assert!(result
+            .to_string()
+            .contains("    Minimal counterexample:\n      n = "));
+    }
+
+    #[test]
+    #[should_panic(expected = "a name for each of the input's 2 parts")]
+    fn test_variable_names_must_match_parts() {
+        let gen = Gen::<(i32, i32)>::tuple_of(Gen::int_range(0, 9), Gen::int_range(0, 9));
+        let _ = for_all(gen, |_| true).with_variable_names(["x", "y", "z"]);
+    }
+
     #[test]
     fn test_examples_with_variable_names() {
         // Test that examples work with named variables
@@ -847,4 +3112,447 @@ mod tests {
             result => panic!("Expected failure, got: {result:?}"),
         }
     }
+
+    #[test]
+    fn test_timings_reported_when_requested() {
+        let prop = for_all(Gen::<Vec<i32>>::vec_of(Gen::int_range(0, 100)), |xs| {
+            xs.len() < 1000
+        });
+
+        assert!(matches!(
+            prop.run(&Config::default()),
+            TestResult::Pass { .. }
+        ));
+
+        let result = prop.run(&Config::default().with_timings());
+        match &result {
+            TestResult::PassWithStatistics { statistics, .. } => {
+                assert!(statistics.is_empty());
+                assert!(statistics.timings.generation > Duration::ZERO);
+                assert_eq!(statistics.timings.shrinking, Duration::ZERO);
+                assert!(format!("{result}").contains("Time spent:"));
+            }
+            other => panic!("Expected PassWithStatistics, got: {other:?}"),
+        }
+    }
+
+    #[test]
+    fn test_run_with_timings_includes_shrinking() {
+        let prop = for_all(Gen::int_range(0, 1000), |&n| n < 500);
+
+        let (result, timings) = prop.run_with_timings(&Config::default().with_tests(1000));
+        assert!(matches!(result, TestResult::Fail { .. }));
+        assert!(timings.shrinking > Duration::ZERO);
+        assert_eq!(
+            timings.total(),
+            timings.generation + timings.execution + timings.shrinking
+        );
+    }
+
+    #[test]
+    fn test_timeout_stops_starting_new_cases() {
+        let prop = for_all(Gen::int_range(0, 10), |_| {
+            std::thread::sleep(Duration::from_millis(5));
+            true
+        });
+
+        let config = Config::default()
+            .with_tests(1000)
+            .with_timeout(Duration::from_millis(50));
+        match prop.run(&config) {
+            TestResult::Pass { tests_run, .. } => assert!(tests_run > 0 && tests_run < 1000),
+            other => panic!("Expected pass, got: {other:?}"),
+        }
+    }
+
+    #[test]
+    fn test_shrink_deadline_tries_smallest_candidates_first() {
+        let prop = for_all(Gen::<Vec<i32>>::vec_of(Gen::int_range(0, 1000)), |xs| {
+            xs.iter().sum::<i32>() < 2000
+        });
+
+        let config = Config::default()
+            .with_tests(500)
+            .with_shrink_deadline(Duration::from_secs(5));
+        match prop.run(&config) {
+            TestResult::Fail {
+                counterexample,
+                shrink_steps,
+                ..
+            } => {
+                let original = &shrink_steps[0].counterexample;
+                assert!(counterexample.len() <= original.len());
+            }
+            other => panic!("Expected failure, got: {other:?}"),
+        }
+
+        // A budget allowing several steps shrinks all the way down. Built a
+        // digit at a time, each shrink of a digit has shrinks of its own
+        let digit = || Gen::int_range(0, 9);
+        let number = Gen::int_range(3, 9).bind(move |hundreds| {
+            digit().bind(move |tens| digit().map(move |units| hundreds * 100 + tens * 10 + units))
+        });
+        let prop = for_all(number, |&x| x < 300);
+        let config = Config::default().with_shrink_deadline(Duration::from_secs(5));
+        match prop.run(&config) {
+            TestResult::Fail {
+                counterexample,
+                shrink_steps,
+                ..
+            } => {
+                assert_eq!(counterexample, "300");
+                // One step per accepted shrink, the last reaching 300
+                let last = shrink_steps.last().map(|step| step.counterexample.as_str());
+                assert_eq!(last, Some("300"));
+            }
+            other => panic!("Expected failure, got: {other:?}"),
+        }
+
+        // With no time at all, the original failure is reported
+        let config = Config::default()
+            .with_tests(500)
+            .with_shrink_deadline(Duration::ZERO);
+        match prop.run(&config) {
+            TestResult::Fail { shrink_steps, .. } => assert_eq!(shrink_steps.len(), 1),
+            other => panic!("Expected failure, got: {other:?}"),
+        }
+    }
+
+    #[test]
+    fn test_for_all_panics_checks_message() {
+        let gen = || Gen::int_range(0, 100);
+        let config = Config::default();
+
+        let prop = for_all_panics(gen(), |&n| panic!("bad input {n}"));
+        assert!(matches!(prop.run(&config), TestResult::Pass { .. }));
+
+        let prop = for_all_panics_with(gen(), "bad input", |&n| panic!("bad input {n}"));
+        assert!(matches!(prop.run(&config), TestResult::Pass { .. }));
+
+        let prop = for_all_panics_with(gen(), "overflow", |&n| panic!("bad input {n}"));
+        match prop.run(&config) {
+            TestResult::Fail { assertion_type, .. } => {
+                assert!(assertion_type.unwrap().contains("\"bad input"));
+            }
+            other => panic!("Expected failure, got: {other:?}"),
+        }
+
+        let prop = for_all_panics(gen(), |&n| assert!(n < 50, "too big"));
+        match prop.run(&config) {
+            TestResult::Fail {
+                counterexample,
+                assertion_type,
+                ..
+            } => {
+                assert!(counterexample.parse::<i32>().unwrap() < 50);
+                assert_eq!(assertion_type.as_deref(), Some("Expected Panic"));
+            }
+            other => panic!("Expected failure, got: {other:?}"),
+        }
+    }
+
+    #[test]
+    fn test_no_panic_reports_and_shrinks_panics() {
+        let prop = for_all(Gen::int_range(0, 1000), |&n| {
+            assert!(n < 10, "{n} is too big");
+            true
+        })
+        .no_panic();
+
+        match prop.run(&Config::default()) {
+            TestResult::Fail {
+                counterexample,
+                assertion_type,
+                shrink_steps,
+                ..
+            } => {
+                assert!(counterexample.parse::<i32>().unwrap() >= 10);
+                assert!(shrink_steps.len() > 1);
+                assert!(assertion_type.unwrap().ends_with("is too big"));
+            }
+            other => panic!("Expected failure, got: {other:?}"),
+        }
+    }
+
+    #[test]
+    fn test_caught_panics_are_reported_with_backtraces() {
+        let prop = || {
+            for_all(Gen::int_range(0, 1000), |&n| {
+                assert!(n < 10, "{n} is too big");
+                true
+            })
+            .no_panic()
+        };
+        let config = Config::default().with_panic_backtraces(PanicBacktraces::Always);
+        let TestResult::Fail {
+            counterexample,
+            details: FailureDetails {
+                panic: Some(panic), ..
+            },
+            ..
+        } = prop().run(&config)
+        else {
+            panic!("Expected a failure with its panic");
+        };
+        // The panic is the minimal counterexample's
+        assert_eq!(panic.message, format!("{counterexample} is too big"));
+        assert!(panic.location.unwrap().contains("property.rs"));
+        assert!(panic.backtrace.is_some());
+
+        let config = Config::default().with_panic_backtraces(PanicBacktraces::Never);
+        match prop().run(&config) {
+            TestResult::Fail {
+                details:
+                    FailureDetails {
+                        panic: Some(panic), ..
+                    },
+                ..
+            } => assert_eq!(panic.backtrace, None),
+            other => panic!("Expected a failure with its panic, got: {other:?}"),
+        }
+
+        // Only the frames outside the runtime are shown
+        let backtrace = "   0: std::panicking::begin_panic
+             at /rustc/library/std/src/panicking.rs:10:5
+   1: app::parse
+             at ./src/parse.rs:4:9
+   2: <app::Parser as core::ops::Fn<(&str,)>>::call
+             at ./src/parse.rs:20:1
+   3: hedgehog_core::property::Property<T>::call
+             at ./hedgehog-core/src/property.rs:1:1
+   4: __libc_start_main";
+        let result = TestResult::Fail {
+            counterexample: "\"(\"".to_string(),
+            tests_run: 3,
+            shrinks_performed: 0,
+            property_name: None,
+            module_path: None,
+            assertion_type: Some("Panic: unbalanced".to_string()),
+            shrink_steps: Vec::new(),
+            details: FailureDetails {
+                panic: Some(PanicReport {
+                    message: "unbalanced".to_string(),
+                    location: Some("src/parse.rs:4:9".to_string()),
+                    backtrace: Some(backtrace.to_string()),
+                }),
+                ..Default::default()
+            },
+        };
+        let shown = result.to_string();
+        assert!(shown.contains("    panicked at src/parse.rs:4:9\n"));
+        assert!(shown.ends_with(
+            "    ▸ Backtrace (2 frames, 3 runtime frames hidden)
+      1: app::parse
+            at ./src/parse.rs:4:9
+      2: <app::Parser as core::ops::Fn<(&str,)>>::call
+            at ./src/parse.rs:20:1"
+        ));
+    }
+
+    #[cfg(all(unix, feature = "isolation"))]
+    #[test]
+    fn test_process_isolation_reports_crashes() {
+        let prop = for_all(Gen::int_range(0, 1000), |&n| {
+            if n >= 10 {
+                std::process::abort();
+            }
+            true
+        });
+        let config = Config::default().with_tests(50).with_process_isolation();
+
+        match prop.run(&config) {
+            TestResult::Fail {
+                counterexample,
+                assertion_type,
+                ..
+            } => {
+                assert!(counterexample.parse::<i32>().unwrap() >= 10);
+                assert!(assertion_type.unwrap().contains("SIGABRT"));
+            }
+            other => panic!("Expected failure, got: {other:?}"),
+        }
+    }
+
+    #[test]
+    fn test_failures_report_where_the_property_was_created() {
+        let line = line!() + 1;
+        let prop = for_all(Gen::int_range(0, 100), |&n| n < 0).in_module(module_path!());
+        assert_eq!(prop.location().line(), line);
+
+        match prop.run(&Config::default()) {
+            ref result @ TestResult::Fail {
+                details: FailureDetails { ref location, .. },
+                ref module_path,
+                ..
+            } => {
+                let expected = format!("{}:{line}:20", file!());
+                assert_eq!(location.as_deref(), Some(expected.as_str()));
+                assert_eq!(
+                    module_path.as_deref(),
+                    Some("hedgehog_core::property::tests")
+                );
+                assert!(format!("{result}").contains(&format!("    at {expected}\n")));
+            }
+            other => panic!("Expected failure, got: {other:?}"),
+        }
+    }
+
+    #[test]
+    fn test_failures_export_the_shrunk_input_as_a_test() {
+        let directory =
+            std::env::temp_dir().join(format!("hedgehog-export-{}", std::process::id()));
+        let export = RegressionExport::new("big_number", "is_small").to_directory(&directory);
+        let prop = for_all(Gen::int_range(0, 1000), |&n| n < 10).export_regressions(export.clone());
+
+        match prop.run(&Config::default()) {
+            TestResult::Fail { counterexample, .. } => {
+                let test = std::fs::read_to_string(export.path().unwrap()).unwrap();
+                assert!(test.contains(&format!("let input = {counterexample};")));
+                assert!(test.contains("assert!(is_small(&input));"));
+            }
+            other => panic!("Expected failure, got: {other:?}"),
+        }
+        std::fs::remove_dir_all(directory).unwrap();
+    }
+
+    #[test]
+    fn test_and_or_implies() {
+        let gen = || Gen::int_range(0, 100);
+        let config = Config::default();
+        let small = || for_all(gen(), |&n| n < 50).classify("small", |&n| n < 50);
+        let even = || for_all(gen(), |&n| n % 2 == 0).classify("even", |&n| n % 2 == 0);
+        let in_range = || for_all(gen(), |&n| (0..=100).contains(&n));
+
+        match in_range().and(small()).run(&config) {
+            TestResult::Fail { counterexample, .. } => {
+                assert!(counterexample.parse::<i32>().unwrap() >= 50)
+            }
+            other => panic!("Expected failure, got: {other:?}"),
+        }
+
+        match small().or(even()).run(&config) {
+            TestResult::Fail {
+                counterexample,
+                assertion_type,
+                ..
+            } => {
+                let n: i32 = counterexample.parse().unwrap();
+                assert!(n >= 50 && n % 2 == 1);
+                assert_eq!(
+                    assertion_type.as_deref(),
+                    Some("Neither Held: Boolean Condition / Boolean Condition")
+                );
+            }
+            other => panic!("Expected failure, got: {other:?}"),
+        }
+
+        // Classifications from both sides are gathered from the same values
+        let implication = small().implies(for_all(gen(), |&n| n < 50)).and(even());
+        let half = for_all(gen(), |&n| n % 2 == 0).implies(in_range().and(even()));
+        assert!(matches!(
+            half.run(&config),
+            TestResult::PassWithStatistics { .. }
+        ));
+        match implication.run(&config) {
+            TestResult::Fail { counterexample, .. } => {
+                assert!(counterexample.parse::<i32>().unwrap() % 2 == 1)
+            }
+            other => panic!("Expected failure, got: {other:?}"),
+        }
+
+        match small().implies(in_range()).or(even()).run(&config) {
+            TestResult::PassWithStatistics { statistics, .. } => {
+                let total = statistics.total_tests;
+                assert!(statistics.classifications["small"] < total);
+                assert!(statistics.classifications["even"] < total);
+            }
+            other => panic!("Expected pass with statistics, got: {other:?}"),
+        }
+    }
+
+    #[test]
+    fn test_for_all_memoized_sets_up_each_value_once_per_run() {
+        let setups = Rc::new(std::cell::Cell::new(0));
+        let counter = Rc::clone(&setups);
+        let prop = for_all_memoized(
+            Gen::int_range(0, 3),
+            move |&n| {
+                counter.set(counter.get() + 1);
+                vec![n; 1000]
+            },
+            |&n, table| table.len() == 1000 && table[0] == n,
+        );
+
+        let config = Config::default().with_tests(100);
+        assert!(matches!(prop.run(&config), TestResult::Pass { .. }));
+        assert!(setups.get() <= 4);
+
+        // A second run starts with an empty cache
+        let after_first_run = setups.get();
+        prop.run(&config);
+        assert!(setups.get() > after_first_run);
+    }
+
+    #[test]
+    fn test_generation_errors_fail_the_run_with_a_hint() {
+        let never = for_all(Gen::int_range(0, 10).filter(|&n| n > 10), |_| true);
+        let TestResult::Fail {
+            counterexample,
+            assertion_type: Some(assertion),
+            ..
+        } = never.run(&Config::default())
+        else {
+            panic!("Expected the filter to fail the run");
+        };
+        assert_eq!(counterexample, "<no value generated>");
+        assert!(assertion.starts_with("Filter Too Restrictive\n"));
+        assert!(assertion.contains("\nhint: "));
+
+        let slow = Gen::new(|_, _| {
+            std::thread::sleep(Duration::from_millis(20));
+            Tree::singleton(0)
+        });
+        let result = for_all(slow, |_: &i32| true)
+            .run(&Config::default().with_timeout(Duration::from_millis(5)));
+        assert!(matches!(
+            result,
+            TestResult::Fail { assertion_type: Some(ref assertion), .. }
+                if assertion.starts_with("Generation Timed Out\n")
+        ));
+
+        let seed = Seed::from_u64(7);
+        assert_eq!(seed.to_string().parse::<Seed>().unwrap(), seed);
+        assert!(matches!(
+            "Seed(1, x)".parse::<Seed>(),
+            Err(HedgehogError::SeedDecodeError { .. })
+        ));
+
+        // Plain generation panics with the error instead
+        let message = crate::panic::catch(|| {
+            Gen::int_range(0, 10)
+                .filter(|&n| n > 10)
+                .generate(Size::new(10), Seed::from_u64(1))
+        })
+        .unwrap_err();
+        assert_eq!(message, "Filter rejected 100 generated values in a row");
+    }
+
+    #[test]
+    fn test_shrink_budget_is_reported_in_the_result() {
+        let prop = for_all(Gen::<Vec<i32>>::vec_of(Gen::int_range(0, 100)), |xs| {
+            xs.len() < 5
+        });
+        let result = prop.run(&Config::default().with_shrinks(1));
+        assert!(matches!(
+            result.shrink_error(),
+            Some(HedgehogError::ShrinkBudgetExceeded { limit: 1, .. })
+        ));
+        assert!(result
+            .to_string()
+            .contains("⚠ Shrinking stopped after 1 steps"));
+
+        let result = prop.run(&Config::default());
+        assert!(matches!(result, TestResult::Fail { .. }));
+        assert!(result.shrink_error().is_none());
+    }
 }
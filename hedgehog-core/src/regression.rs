@@ -0,0 +1,370 @@
+//! Exporting counterexamples as regression tests.
+//!
+//! A failing seed only reproduces a failure while the generators stay the
+//! same. Exporting the shrunk input as a unit test, with the value written
+//! out as Rust source, keeps the case covered however the generators change.
+//!
+//! ```rust
+//! use hedgehog_core::*;
+//!
+//! fn sorted_is_idempotent(xs: &Vec<i32>) -> bool {
+//!     let mut once = xs.clone();
+//!     once.sort();
+//!     let mut twice = once.clone();
+//!     twice.sort();
+//!     once == twice
+//! }
+//!
+//! let prop = for_all(Gen::<Vec<i32>>::vec_of(Gen::int_range(0, 9)), sorted_is_idempotent)
+//!     .export_regressions(RegressionExport::new("sort_regression", "sorted_is_idempotent"));
+//! ```
+//!
+//! Inputs that can't be written as Rust, or that came from outside the
+//! property, can be kept in a corpus instead: a directory per property
+//! under [`DEFAULT_CORPUS_DIR`] with one input per file, parsed with
+//! `FromStr` and replayed by [`Property::with_corpus`](crate::Property::with_corpus).
+//! [`Property::with_corpus_capture`](crate::Property::with_corpus_capture)
+//! also stores new counterexamples there, written with `Display`.
+
+use std::path::{Path, PathBuf};
+
+/// Directory regression tests are written to by
+/// [`RegressionExport::to_default_directory`].
+pub const DEFAULT_REGRESSION_DIR: &str = "tests/regressions";
+
+/// Directory holding each property's corpus of stored inputs, one
+/// subdirectory per property.
+pub const DEFAULT_CORPUS_DIR: &str = "tests/corpus";
+
+/// Environment variable that makes properties with a corpus report whether
+/// each entry still fails, as `cargo hedgehog corpus check` does.
+pub const CORPUS_CHECK_ENV_VAR: &str = "HEDGEHOG_CORPUS_CHECK";
+
+/// Values that can be written out as a Rust expression that rebuilds them.
+pub trait RustLiteral {
+    /// A Rust expression evaluating to this value.
+    fn rust_literal(&self) -> String;
+}
+
+macro_rules! impl_rust_literal_via_debug {
+    ($($t:ty),*) => {
+        $(
+            impl RustLiteral for $t {
+                fn rust_literal(&self) -> String {
+                    format!("{self:?}")
+                }
+            }
+        )*
+    };
+}
+
+impl_rust_literal_via_debug!(
+    i8,
+    i16,
+    i32,
+    i64,
+    i128,
+    isize,
+    u8,
+    u16,
+    u32,
+    u64,
+    u128,
+    usize,
+    bool,
+    char,
+    &str,
+    ()
+);
+
+macro_rules! impl_rust_literal_float {
+    ($($t:ident),*) => {
+        $(
+            impl RustLiteral for $t {
+                fn rust_literal(&self) -> String {
+                    if self.is_nan() {
+                        concat!(stringify!($t), "::NAN").to_string()
+                    } else if self.is_infinite() && *self > 0.0 {
+                        concat!(stringify!($t), "::INFINITY").to_string()
+                    } else if self.is_infinite() {
+                        concat!(stringify!($t), "::NEG_INFINITY").to_string()
+                    } else {
+                        format!(concat!("{:?}_", stringify!($t)), self)
+                    }
+                }
+            }
+        )*
+    };
+}
+
+impl_rust_literal_float!(f32, f64);
+
+impl RustLiteral for String {
+    fn rust_literal(&self) -> String {
+        format!("String::from({self:?})")
+    }
+}
+
+impl<T: RustLiteral> RustLiteral for Vec<T> {
+    fn rust_literal(&self) -> String {
+        format!("vec![{}]", join(self))
+    }
+}
+
+impl<T: RustLiteral, const N: usize> RustLiteral for [T; N] {
+    fn rust_literal(&self) -> String {
+        format!("[{}]", join(self))
+    }
+}
+
+impl<T: RustLiteral> RustLiteral for Box<T> {
+    fn rust_literal(&self) -> String {
+        format!("Box::new({})", self.as_ref().rust_literal())
+    }
+}
+
+impl<T: RustLiteral> RustLiteral for Option<T> {
+    fn rust_literal(&self) -> String {
+        match self {
+            Some(value) => format!("Some({})", value.rust_literal()),
+            None => "None".to_string(),
+        }
+    }
+}
+
+impl<T: RustLiteral, E: RustLiteral> RustLiteral for Result<T, E> {
+    fn rust_literal(&self) -> String {
+        match self {
+            Ok(value) => format!("Ok({})", value.rust_literal()),
+            Err(error) => format!("Err({})", error.rust_literal()),
+        }
+    }
+}
+
+macro_rules! impl_rust_literal_tuple {
+    ($($name:ident),+) => {
+        impl<$($name: RustLiteral),+> RustLiteral for ($($name,)+) {
+            #[allow(non_snake_case)]
+            fn rust_literal(&self) -> String {
+                let ($($name,)+) = self;
+                let parts = [$($name.rust_literal()),+];
+                if parts.len() == 1 {
+                    format!("({},)", parts[0])
+                } else {
+                    format!("({})", parts.join(", "))
+                }
+            }
+        }
+    };
+}
+
+impl_rust_literal_tuple!(A);
+impl_rust_literal_tuple!(A, B);
+impl_rust_literal_tuple!(A, B, C);
+impl_rust_literal_tuple!(A, B, C, D);
+
+fn join<T: RustLiteral>(values: &[T]) -> String {
+    values
+        .iter()
+        .map(RustLiteral::rust_literal)
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+/// How to export a property's shrunk counterexample as a regression test.
+///
+/// The test binds the input and asserts that `check(&input)` holds, so
+/// `check` should name a function taking a reference to the input and
+/// returning `bool`, usually the one given to `for_all`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RegressionExport {
+    name: String,
+    check: String,
+    directory: Option<PathBuf>,
+}
+
+impl RegressionExport {
+    /// Export a test called `name` that calls `check` on the input.
+    ///
+    /// Without a directory the test is printed to stderr when the property
+    /// fails, ready to paste into a test module.
+    pub fn new(name: &str, check: &str) -> Self {
+        RegressionExport {
+            name: name.to_string(),
+            check: check.to_string(),
+            directory: None,
+        }
+    }
+
+    /// Also write the test to `<directory>/<name>.rs`, replacing any
+    /// earlier export with the same name.
+    ///
+    /// Cargo only builds files directly under `tests/` as test targets, so
+    /// files in a subdirectory need to be included from one of those.
+    pub fn to_directory(mut self, directory: impl AsRef<Path>) -> Self {
+        self.directory = Some(directory.as_ref().to_path_buf());
+        self
+    }
+
+    /// Also write the test under [`DEFAULT_REGRESSION_DIR`], relative to
+    /// the directory the tests run in (the package root under `cargo test`).
+    pub fn to_default_directory(self) -> Self {
+        self.to_directory(DEFAULT_REGRESSION_DIR)
+    }
+
+    /// The name of the exported test.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// The file the test is written to, if any.
+    pub fn path(&self) -> Option<PathBuf> {
+        self.directory
+            .as_ref()
+            .map(|directory| directory.join(format!("{}.rs", self.name)))
+    }
+
+    /// Render the regression test for an input, given as a Rust expression.
+    pub fn render(&self, input: &str, location: Option<&str>) -> String {
+        let origin = match location {
+            Some(location) => format!(" at {location}"),
+            None => String::new(),
+        };
+        format!(
+            "// Counterexample exported by hedgehog from the property{origin}.\n\
+             #[test]\n\
+             fn {name}() {{\n    \
+                 let input = {input};\n    \
+                 assert!({check}(&input));\n\
+             }}\n",
+            name = self.name,
+            check = self.check,
+        )
+    }
+
+    /// Print the test for an input, and write it out if a directory was
+    /// given.
+    pub(crate) fn export(&self, input: &str, location: Option<&str>) {
+        let test = self.render(input, location);
+        eprintln!("Regression test for this failure:\n\n{test}");
+
+        if let Some(path) = self.path() {
+            let written = path
+                .parent()
+                .map_or(Ok(()), std::fs::create_dir_all)
+                .and_then(|()| std::fs::write(&path, &test));
+            match written {
+                Ok(()) => eprintln!("Written to {}", path.display()),
+                Err(error) => eprintln!("Could not write {}: {error}", path.display()),
+            }
+        }
+    }
+}
+
+/// The entries of a corpus directory, sorted by file name.
+///
+/// A directory that doesn't exist is an empty corpus; hidden files are
+/// skipped.
+pub fn corpus_entries(directory: &Path) -> std::io::Result<Vec<PathBuf>> {
+    let entries = match std::fs::read_dir(directory) {
+        Ok(entries) => entries,
+        Err(error) if error.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(error) => return Err(error),
+    };
+    let mut paths = Vec::new();
+    for entry in entries {
+        let entry = entry?;
+        let hidden = entry.file_name().to_string_lossy().starts_with('.');
+        if entry.file_type()?.is_file() && !hidden {
+            paths.push(entry.path());
+        }
+    }
+    paths.sort();
+    Ok(paths)
+}
+
+/// The file name a corpus entry with `contents` is stored under: a hash of
+/// the contents, so storing the same input twice keeps one file.
+pub fn corpus_entry_name(contents: &[u8]) -> String {
+    // FNV-1a, which unlike the std hasher is stable across Rust releases
+    let hash = contents
+        .iter()
+        .fold(0xcbf2_9ce4_8422_2325u64, |hash, &byte| {
+            (hash ^ u64::from(byte)).wrapping_mul(0x0100_0000_01b3)
+        });
+    format!("{hash:016x}")
+}
+
+/// Store `contents` as an entry of the corpus in `directory`, creating the
+/// directory if needed, and return the entry's path.
+pub fn store_corpus_entry(directory: &Path, contents: &str) -> std::io::Result<PathBuf> {
+    std::fs::create_dir_all(directory)?;
+    let path = directory.join(corpus_entry_name(contents.as_bytes()));
+    if !path.exists() {
+        std::fs::write(&path, contents)?;
+    }
+    Ok(path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rust_literals() {
+        assert_eq!(vec![1, -2].rust_literal(), "vec![1, -2]");
+        assert_eq!(
+            String::from("a\"b\n").rust_literal(),
+            r#"String::from("a\"b\n")"#
+        );
+        assert_eq!(
+            (Some('x'), Ok::<u8, String>(3)).rust_literal(),
+            "(Some('x'), Ok(3))"
+        );
+        assert_eq!((1u8,).rust_literal(), "(1,)");
+        assert_eq!(1.5f64.rust_literal(), "1.5_f64");
+        assert_eq!(f32::NEG_INFINITY.rust_literal(), "f32::NEG_INFINITY");
+        assert_eq!([[true]; 2].rust_literal(), "[[true], [true]]");
+    }
+
+    #[test]
+    fn test_render_and_write() {
+        let directory =
+            std::env::temp_dir().join(format!("hedgehog-regression-{}", std::process::id()));
+        let export =
+            RegressionExport::new("sum_regression", "sum_is_small").to_directory(&directory);
+
+        let test = export.render("vec![7, 9]", Some("src/lib.rs:3:5"));
+        assert_eq!(
+            test,
+            "// Counterexample exported by hedgehog from the property at src/lib.rs:3:5.\n\
+             #[test]\n\
+             fn sum_regression() {\n    \
+                 let input = vec![7, 9];\n    \
+                 assert!(sum_is_small(&input));\n\
+             }\n"
+        );
+
+        export.export("vec![7, 9]", Some("src/lib.rs:3:5"));
+        let path = export.path().unwrap();
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), test);
+        std::fs::remove_dir_all(directory).unwrap();
+    }
+
+    #[test]
+    fn test_corpus_entries_are_sorted_files() {
+        let directory =
+            std::env::temp_dir().join(format!("hedgehog-corpus-entries-{}", std::process::id()));
+        assert!(corpus_entries(&directory).unwrap().is_empty());
+
+        std::fs::create_dir_all(directory.join("nested")).unwrap();
+        for name in ["b", "a", ".gitkeep"] {
+            std::fs::write(directory.join(name), "1").unwrap();
+        }
+        assert_eq!(
+            corpus_entries(&directory).unwrap(),
+            [directory.join("a"), directory.join("b")]
+        );
+        std::fs::remove_dir_all(directory).unwrap();
+    }
+}
@@ -0,0 +1,164 @@
+//! Randomness for test code, drawn from the case seed.
+//!
+//! Test functions sometimes need randomness of their own: shuffling work
+//! between threads, picking a fault to inject, or calling code that takes an
+//! RNG. Drawing it from `rand::thread_rng()` means a failing case behaves
+//! differently each time it's run, so it may stop failing while it's shrunk
+//! and won't replay from a fuzzer input. With the `rand` feature,
+//! [`rng_from_seed`] hands out a [`rand::RngCore`] seeded from the running
+//! case's seed instead. Each test call, shrinks, retries and determinism
+//! checks included, draws the same RNGs in the same order, so a case sees
+//! the same randomness every time it runs.
+
+use crate::data::Seed;
+use std::cell::Cell;
+
+thread_local! {
+    /// The seed of the case running on this thread, and the seed the next
+    /// RNG will be drawn from.
+    static CASE_SEED: Cell<Option<(Seed, Seed)>> = const { Cell::new(None) };
+}
+
+/// The case seed for RNGs drawn while a case runs; restores the previous one
+/// when dropped.
+pub(crate) struct CaseSeed(Option<(Seed, Seed)>);
+
+impl CaseSeed {
+    /// Draw RNGs from `seed` until the returned guard is dropped.
+    pub(crate) fn enter(seed: Seed) -> Self {
+        CaseSeed(CASE_SEED.with(|case| case.replace(Some((seed, seed)))))
+    }
+}
+
+impl Drop for CaseSeed {
+    fn drop(&mut self) {
+        CASE_SEED.with(|case| case.set(self.0));
+    }
+}
+
+/// Start drawing RNGs from the case seed again, before each call to the
+/// test function.
+pub(crate) fn restart() {
+    CASE_SEED.with(|case| case.set(case.get().map(|(seed, _)| (seed, seed))));
+}
+
+/// A seed for the next RNG: split from the case seed during a case, or
+/// random outside one.
+#[cfg_attr(not(feature = "rand"), allow(dead_code))]
+fn next_seed() -> Seed {
+    let drawn = CASE_SEED.with(|case| {
+        let (seed, next) = case.get()?;
+        let (drawn, rest) = next.split();
+        case.set(Some((seed, rest)));
+        Some(drawn)
+    });
+    drawn.unwrap_or_else(|| {
+        eprintln!(
+            "warning: rng_from_seed() was called outside a property run; \
+             its values are random and won't replay"
+        );
+        Seed::random()
+    })
+}
+
+/// An RNG seeded from the running case's seed; see [`rng_from_seed`].
+#[cfg(feature = "rand")]
+#[derive(Debug, Clone)]
+pub struct CaseRng {
+    seed: Seed,
+}
+
+/// An RNG for test code whose values replay with the case.
+///
+/// Successive calls within a test function give independent RNGs, and the
+/// same ones each time the case runs. Outside a property run the RNG is
+/// seeded randomly, with a warning on stderr.
+///
+/// # Example
+/// ```rust
+/// use hedgehog_core::*;
+/// use rand::seq::SliceRandom;
+///
+/// let prop = for_all(Gen::vec_of(Gen::int_range(0, 100)), |xs: &Vec<i32>| {
+///     let mut shuffled = xs.clone();
+///     shuffled.shuffle(&mut rng_from_seed());
+///     shuffled.iter().sum::<i32>() == xs.iter().sum::<i32>()
+/// });
+/// assert!(matches!(prop.run(&Config::default()), TestResult::Pass { .. }));
+/// ```
+#[cfg(feature = "rand")]
+pub fn rng_from_seed() -> CaseRng {
+    CaseRng { seed: next_seed() }
+}
+
+#[cfg(feature = "rand")]
+impl rand::RngCore for CaseRng {
+    fn next_u32(&mut self) -> u32 {
+        (self.next_u64() >> 32) as u32
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        let (value, seed) = self.seed.next_u64();
+        self.seed = seed;
+        value
+    }
+
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        for chunk in dest.chunks_mut(8) {
+            let bytes = self.next_u64().to_le_bytes();
+            chunk.copy_from_slice(&bytes[..chunk.len()]);
+        }
+    }
+
+    fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), rand::Error> {
+        self.fill_bytes(dest);
+        Ok(())
+    }
+}
+
+#[cfg(all(test, feature = "rand"))]
+mod tests {
+    use super::*;
+    use crate::data::Config;
+    use crate::error::TestResult;
+    use crate::gen::Gen;
+    use crate::property::for_all;
+    use rand::{Rng, RngCore};
+    use std::cell::RefCell;
+    use std::rc::Rc;
+
+    #[test]
+    fn test_rngs_replay_with_the_case() {
+        let _case = CaseSeed::enter(Seed::from_u64(7));
+        let first: Vec<u64> = (0..2).map(|_| rng_from_seed().gen()).collect();
+        restart();
+        let again: Vec<u64> = (0..2).map(|_| rng_from_seed().gen()).collect();
+        assert_eq!(first, again);
+        assert_ne!(first[0], first[1]);
+
+        let mut bytes = [0; 11];
+        rng_from_seed().fill_bytes(&mut bytes);
+        assert!(bytes.iter().any(|&byte| byte != 0));
+    }
+
+    #[test]
+    fn test_failing_cases_see_the_same_randomness_when_shrunk() {
+        let draws = Rc::new(RefCell::new(Vec::new()));
+        let recorded = draws.clone();
+        let prop = for_all(Gen::int_range(0, 1000), move |&n: &i32| {
+            let roll: u32 = rng_from_seed().gen_range(0..1000);
+            recorded.borrow_mut().push((n, roll));
+            n < 500
+        });
+
+        let TestResult::Fail { .. } = prop.run(&Config::default()) else {
+            panic!("expected a failure");
+        };
+        // Every call on the failing case, shrinks included, rolled the same
+        let draws = draws.take();
+        let failing = draws.iter().position(|&(n, _)| n >= 500).unwrap();
+        let (_, roll) = draws[failing];
+        assert!(draws.len() > failing + 1);
+        assert!(draws[failing..].iter().all(|&(_, r)| r == roll));
+    }
+}
@@ -0,0 +1,335 @@
+//! Running several properties together.
+//!
+//! Running properties one after another means the last one says nothing
+//! until every one before it has used its whole test budget. A
+//! [`TestRunner`] interleaves them instead: each property runs one case in
+//! turn, so a quick smoke run gets a signal from every property, and a
+//! failure is reported as soon as it is found and shrunk.
+
+use crate::data::{property_path, Config};
+use crate::error::TestResult;
+use crate::history;
+use crate::property::{Property, PropertyMarker, RunState, TestStatistics};
+use std::time::Instant;
+
+/// Receives results from a [`TestRunner`] as properties finish.
+pub trait Reporter {
+    /// A property has finished, either by running all its cases or by
+    /// failing.
+    fn finished(&mut self, name: &str, result: &TestResult);
+
+    /// Every property has finished.
+    fn summary(&mut self, _results: &[(String, TestResult)]) {}
+}
+
+/// Prints each result as it arrives, and failures again at the end.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ConsoleReporter;
+
+impl Reporter for ConsoleReporter {
+    fn finished(&mut self, _name: &str, result: &TestResult) {
+        println!("{result}");
+    }
+
+    fn summary(&mut self, results: &[(String, TestResult)]) {
+        let failed: Vec<&str> = results
+            .iter()
+            .filter(|(_, result)| !is_pass(result))
+            .map(|(name, _)| name.as_str())
+            .collect();
+        if failed.is_empty() {
+            println!("{} properties passed.", results.len());
+        } else {
+            println!(
+                "{} of {} properties failed: {}",
+                failed.len(),
+                results.len(),
+                failed.join(", ")
+            );
+        }
+    }
+}
+
+fn is_pass(result: &TestResult) -> bool {
+    matches!(
+        result,
+        TestResult::Pass { .. } | TestResult::PassWithStatistics { .. }
+    )
+}
+
+/// A property of any input type, run one case at a time.
+trait Interleaved {
+    fn name(&self) -> &str;
+    fn begin(&mut self);
+    fn has_next(&self, config: &Config) -> bool;
+    fn step(&mut self, config: &Config) -> Option<TestResult>;
+    fn finish(&mut self, config: &Config) -> TestResult;
+    fn statistics(&self) -> &TestStatistics;
+    fn marker(&self) -> Option<&PropertyMarker>;
+    fn settle(&self, result: TestResult) -> TestResult;
+}
+
+struct Entry<T> {
+    name: String,
+    property: Property<T>,
+    run: Option<RunState>,
+    statistics: TestStatistics,
+}
+
+impl<T> Interleaved for Entry<T>
+where
+    T: 'static + std::fmt::Debug + Clone,
+{
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn begin(&mut self) {
+        self.statistics = TestStatistics::new();
+        self.run = Some(self.property.begin_run());
+    }
+
+    fn has_next(&self, config: &Config) -> bool {
+        self.run
+            .as_ref()
+            .is_some_and(|run| self.property.has_next_case(run, config))
+    }
+
+    fn step(&mut self, config: &Config) -> Option<TestResult> {
+        let run = self.run.as_mut()?;
+        self.property
+            .run_case(run, config, Some(&self.name), None, &mut self.statistics)
+    }
+
+    fn finish(&mut self, config: &Config) -> TestResult {
+        let run = self.run.take().expect("finish called before begin");
+        self.property
+            .finish_run(&run, config, Some(&self.name), None, &mut self.statistics)
+    }
+
+    fn statistics(&self) -> &TestStatistics {
+        &self.statistics
+    }
+
+    fn marker(&self) -> Option<&PropertyMarker> {
+        self.property.marker()
+    }
+
+    fn settle(&self, result: TestResult) -> TestResult {
+        self.property.settle(&self.name, result)
+    }
+}
+
+/// Runs a set of properties together, interleaving their cases.
+///
+/// Every property runs with the runner's config, taking one case in turn
+/// until it has run `test_limit` cases or failed. A config timeout is shared:
+/// once it has passed, no property starts another case. An overall budget
+/// of cases can be set with [`TestRunner::with_budget`].
+///
+/// # Example
+/// ```rust
+/// use hedgehog_core::*;
+///
+/// let results = TestRunner::new(Config::default().with_tests(50))
+///     .add("reverse twice", for_all(Gen::vec_of(Gen::int_range(0, 9)), |xs: &Vec<i32>| {
+///         let mut ys = xs.clone();
+///         ys.reverse();
+///         ys.reverse();
+///         &ys == xs
+///     }))
+///     .add("abs is positive", for_all(Gen::int_range(-100, 100), |&n| n.abs() >= 0))
+///     .run();
+///
+/// assert!(results.iter().all(|(_, result)| matches!(result, TestResult::Pass { .. })));
+/// ```
+pub struct TestRunner {
+    config: Config,
+    reporter: Box<dyn Reporter>,
+    properties: Vec<Box<dyn Interleaved>>,
+    budget: Option<usize>,
+}
+
+impl TestRunner {
+    /// A runner with no properties that reports to the console.
+    pub fn new(config: Config) -> Self {
+        TestRunner {
+            config,
+            reporter: Box::new(ConsoleReporter),
+            properties: Vec::new(),
+            budget: None,
+        }
+    }
+
+    /// Send results to `reporter` instead of the console.
+    pub fn with_reporter<R: Reporter + 'static>(mut self, reporter: R) -> Self {
+        self.reporter = Box::new(reporter);
+        self
+    }
+
+    /// Run at most `cases` cases across all properties. Properties still
+    /// running when the budget runs out pass with the cases they ran.
+    pub fn with_budget(mut self, cases: usize) -> Self {
+        self.budget = Some(cases);
+        self
+    }
+
+    /// Add a property, reported under `name`.
+    pub fn add<T>(mut self, name: &str, property: Property<T>) -> Self
+    where
+        T: 'static + std::fmt::Debug + Clone,
+    {
+        self.properties.push(Box::new(Entry {
+            name: name.to_string(),
+            property,
+            run: None,
+            statistics: TestStatistics::new(),
+        }));
+        self
+    }
+
+    /// Run every property, returning each one's name and result in the
+    /// order they were added.
+    pub fn run(mut self) -> Vec<(String, TestResult)> {
+        let started = Instant::now();
+        let mut results: Vec<Option<TestResult>> = self.properties.iter().map(|_| None).collect();
+        for property in &mut self.properties {
+            property.begin();
+        }
+
+        let mut cases = 0;
+        loop {
+            let mut progressed = false;
+            for (property, result) in self.properties.iter_mut().zip(&mut results) {
+                if result.is_some() {
+                    continue;
+                }
+                let out_of_budget = self.budget.is_some_and(|budget| cases >= budget)
+                    || self
+                        .config
+                        .timeout
+                        .is_some_and(|timeout| started.elapsed() >= timeout);
+                let finished = if out_of_budget || !property.has_next(&self.config) {
+                    Some(property.finish(&self.config))
+                } else {
+                    cases += 1;
+                    progressed = true;
+                    property.step(&self.config)
+                };
+                if let Some(finished) = finished {
+                    if let Some(path) = &self.config.history {
+                        history::append(
+                            &property_path(path, property.name()),
+                            property.name(),
+                            &finished,
+                            property.statistics(),
+                            property.marker(),
+                        );
+                    }
+                    let finished = property.settle(finished);
+                    self.reporter.finished(property.name(), &finished);
+                    *result = Some(finished);
+                }
+            }
+            if !progressed {
+                break;
+            }
+        }
+
+        let results: Vec<(String, TestResult)> = self
+            .properties
+            .iter()
+            .zip(results)
+            .map(|(property, result)| {
+                (
+                    property.name().to_string(),
+                    result.expect("every property finishes"),
+                )
+            })
+            .collect();
+        self.reporter.summary(&results);
+        results
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::gen::Gen;
+    use crate::property::for_all;
+    use std::cell::RefCell;
+    use std::rc::Rc;
+
+    #[derive(Clone, Default)]
+    struct Recorder {
+        finished: Rc<RefCell<Vec<String>>>,
+    }
+
+    impl Reporter for Recorder {
+        fn finished(&mut self, name: &str, _result: &TestResult) {
+            self.finished.borrow_mut().push(name.to_string());
+        }
+    }
+
+    #[test]
+    fn test_cases_are_interleaved() {
+        let calls = Rc::new(RefCell::new(Vec::new()));
+        let (a, b) = (calls.clone(), calls.clone());
+        let recorder = Recorder::default();
+
+        let results = TestRunner::new(Config::default().with_tests(3))
+            .with_reporter(recorder.clone())
+            .add(
+                "a",
+                for_all(Gen::bool(), move |_| {
+                    a.borrow_mut().push('a');
+                    true
+                }),
+            )
+            .add(
+                "b",
+                for_all(Gen::bool(), move |_| {
+                    b.borrow_mut().push('b');
+                    true
+                }),
+            )
+            .run();
+
+        assert_eq!(calls.borrow().iter().collect::<String>(), "ababab");
+        assert_eq!(*recorder.finished.borrow(), ["a", "b"]);
+        assert!(results.iter().all(|(_, result)| is_pass(result)));
+    }
+
+    #[test]
+    fn test_failures_are_reported_first() {
+        let recorder = Recorder::default();
+        let results = TestRunner::new(Config::default().with_tests(100))
+            .with_reporter(recorder.clone())
+            .add("slow", for_all(Gen::int_range(0, 10), |&n| n <= 10))
+            .add("broken", for_all(Gen::int_range(0, 10), |&n| n < 0))
+            .run();
+
+        assert_eq!(*recorder.finished.borrow(), ["broken", "slow"]);
+        match &results[1] {
+            (name, TestResult::Fail { property_name, .. }) => {
+                assert_eq!(name, "broken");
+                assert_eq!(property_name.as_deref(), Some("broken"));
+            }
+            other => panic!("expected a failure, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn test_budget_is_shared() {
+        let results = TestRunner::new(Config::default().with_tests(100))
+            .with_reporter(Recorder::default())
+            .with_budget(10)
+            .add("a", for_all(Gen::bool(), |_| true))
+            .add("b", for_all(Gen::bool(), |_| true))
+            .run();
+
+        for (_, result) in results {
+            assert!(matches!(result, TestResult::Pass { tests_run: 5, .. }));
+        }
+    }
+}
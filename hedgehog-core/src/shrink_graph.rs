@@ -0,0 +1,311 @@
+//! Exporting the shrink tree explored for a failure.
+//!
+//! When a counterexample isn't as small as expected, the question is why
+//! the shrinker stopped there: the generator never offered a smaller value,
+//! the smaller values it offered passed, or the shrink budget ran out. A
+//! [`ShrinkGraph`] records every candidate in the failing value's shrink
+//! tree with what happened when it was tried, and renders it as Graphviz
+//! DOT or JSON.
+//!
+//! A config built with [`Config::with_shrink_graph`](crate::Config::with_shrink_graph),
+//! or [`Config::from_env`](crate::Config::from_env) while
+//! `HEDGEHOG_SHRINK_GRAPH` names a directory, writes the graph of each
+//! failure to a file named after the property: as JSON if the file name
+//! ends in `.json`, otherwise as DOT.
+
+use crate::property::debug_hash;
+use crate::tree::Tree;
+use std::collections::{HashMap, VecDeque};
+use std::fmt::Debug;
+use std::path::Path;
+
+/// Environment variable naming the directory shrink graphs are written to.
+pub const SHRINK_GRAPH_ENV_VAR: &str = "HEDGEHOG_SHRINK_GRAPH";
+
+/// What happened to a value in the shrink tree.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ShrinkOutcome {
+    /// The failing value shrinking started from
+    Original,
+    /// Tried, and the property still failed
+    Failed,
+    /// Tried, and the property passed
+    Passed,
+    /// Tried, and the test case was discarded
+    Discarded,
+    /// Never tried, because the shrink budget or deadline ran out
+    Untried,
+}
+
+impl ShrinkOutcome {
+    fn name(self) -> &'static str {
+        match self {
+            ShrinkOutcome::Original => "original",
+            ShrinkOutcome::Failed => "failed",
+            ShrinkOutcome::Passed => "passed",
+            ShrinkOutcome::Discarded => "discarded",
+            ShrinkOutcome::Untried => "untried",
+        }
+    }
+}
+
+/// A value in the shrink tree.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ShrinkNode {
+    /// The node this value is a shrink of; `None` for the original
+    pub parent: Option<usize>,
+    /// The value, as printed with `Debug`
+    pub value: String,
+    /// What happened when the value was tried
+    pub outcome: ShrinkOutcome,
+}
+
+/// The shrink tree of a failing value, with the outcome of each candidate.
+///
+/// Node 0 is the original failing value and every other node names its
+/// parent. Values that appear more than once in the tree are only tried,
+/// and only shown, the first time.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ShrinkGraph {
+    /// Every distinct value in the tree, in the order shrinking visits them
+    pub nodes: Vec<ShrinkNode>,
+    /// The node shrinking stopped at
+    pub minimal: usize,
+}
+
+impl ShrinkGraph {
+    /// Render as a Graphviz digraph, for `dot -Tsvg`.
+    ///
+    /// Failing values are filled, untried ones dashed, and the minimal
+    /// value has a double border.
+    pub fn to_dot(&self) -> String {
+        let mut dot = String::from("digraph shrinks {\n    node [shape=box];\n");
+        for (id, node) in self.nodes.iter().enumerate() {
+            let mut attributes = vec![format!(
+                "label=\"{}\\n{}\"",
+                escape(&node.value),
+                node.outcome.name()
+            )];
+            match node.outcome {
+                ShrinkOutcome::Original | ShrinkOutcome::Failed => {
+                    attributes.push("style=filled".to_string())
+                }
+                ShrinkOutcome::Untried => attributes.push("style=dashed".to_string()),
+                ShrinkOutcome::Discarded => attributes.push("style=dotted".to_string()),
+                ShrinkOutcome::Passed => {}
+            }
+            if id == self.minimal {
+                attributes.push("peripheries=2".to_string());
+            }
+            dot.push_str(&format!("    n{id} [{}];\n", attributes.join(", ")));
+        }
+        for (id, node) in self.nodes.iter().enumerate() {
+            if let Some(parent) = node.parent {
+                dot.push_str(&format!("    n{parent} -> n{id};\n"));
+            }
+        }
+        dot.push_str("}\n");
+        dot
+    }
+
+    /// Render as JSON: `{"minimal": id, "nodes": [...]}`, each node with its
+    /// `id`, `parent`, `value` and `outcome`.
+    pub fn to_json(&self) -> String {
+        let nodes: Vec<String> = self
+            .nodes
+            .iter()
+            .enumerate()
+            .map(|(id, node)| {
+                let parent = node
+                    .parent
+                    .map_or("null".to_string(), |parent| parent.to_string());
+                format!(
+                    "    {{\"id\": {id}, \"parent\": {parent}, \"value\": \"{}\", \"outcome\": \"{}\"}}",
+                    escape(&node.value),
+                    node.outcome.name()
+                )
+            })
+            .collect();
+        format!(
+            "{{\n  \"minimal\": {},\n  \"nodes\": [\n{}\n  ]\n}}\n",
+            self.minimal,
+            nodes.join(",\n")
+        )
+    }
+
+    /// Write the graph to `path`, as JSON if it ends in `.json` and DOT
+    /// otherwise, reporting where it went on stderr.
+    pub(crate) fn write(&self, path: &Path) {
+        let rendered = match path.extension() {
+            Some(extension) if extension == "json" => self.to_json(),
+            _ => self.to_dot(),
+        };
+        match std::fs::write(path, rendered) {
+            Ok(()) => eprintln!("Shrink graph written to {}", path.display()),
+            Err(error) => eprintln!("Could not write {}: {error}", path.display()),
+        }
+    }
+}
+
+/// The distinct values in a shrink tree below its root, breadth first,
+/// each with the index of its parent: 0 for the root, `i + 1` for the
+/// `i`th candidate.
+///
+/// Generators that combine several shrinking strategies often propose the
+/// same candidate more than once, such as the empty string from both
+/// removing every character and taking a prefix. Testing it again can't
+/// fail differently, so only its first appearance is a candidate, though
+/// the shrinks of later appearances are still visited.
+///
+/// With a `per_node` cap only the first that many new candidates among
+/// each node's shrinks are followed. Generators list shrinks most
+/// aggressive first, so the cap cuts the long tail of small changes at
+/// every level rather than cutting the walk off after its first few nodes.
+pub(crate) fn candidates<T: Debug>(tree: &Tree<T>, per_node: Option<usize>) -> Vec<(usize, &T)> {
+    let per_node = per_node.unwrap_or(usize::MAX);
+    let mut ids = HashMap::from([(debug_hash(&tree.value), 0)]);
+    let mut queue = VecDeque::from([(0, tree)]);
+    let mut candidates = Vec::new();
+    while let Some((id, node)) = queue.pop_front() {
+        let mut taken = 0;
+        for child in &node.children {
+            if taken == per_node {
+                break;
+            }
+            let next_id = candidates.len() + 1;
+            let child_id = *ids.entry(debug_hash(&child.value)).or_insert(next_id);
+            if child_id == next_id {
+                candidates.push((id, &child.value));
+                taken += 1;
+            }
+            queue.push_back((child_id, child));
+        }
+    }
+    candidates
+}
+
+/// The graph of a shrink: the root, then each candidate with its outcome.
+pub(crate) fn graph<T: Debug>(
+    root: &T,
+    candidates: &[(usize, &T)],
+    outcomes: &[ShrinkOutcome],
+    minimal: usize,
+) -> ShrinkGraph {
+    let root = ShrinkNode {
+        parent: None,
+        value: format!("{root:?}"),
+        outcome: ShrinkOutcome::Original,
+    };
+    let nodes = candidates
+        .iter()
+        .zip(outcomes)
+        .map(|((parent, value), outcome)| ShrinkNode {
+            parent: Some(*parent),
+            value: format!("{value:?}"),
+            outcome: *outcome,
+        });
+    ShrinkGraph {
+        nodes: std::iter::once(root).chain(nodes).collect(),
+        minimal,
+    }
+}
+
+/// Escape a string for a double-quoted DOT or JSON string.
+fn escape(text: &str) -> String {
+    let mut escaped = String::new();
+    for c in text.chars() {
+        match c {
+            '"' => escaped.push_str("\\\""),
+            '\\' => escaped.push_str("\\\\"),
+            '\n' => escaped.push_str("\\n"),
+            c if c.is_control() => escaped.push_str(&format!("\\u{:04x}", c as u32)),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample() -> ShrinkGraph {
+        let tree = Tree::with_children(
+            4,
+            vec![
+                Tree::singleton(0),
+                Tree::with_children(2, vec![Tree::singleton(0), Tree::singleton(1)]),
+                Tree::singleton(3),
+            ],
+        );
+        let candidates = candidates(&tree, None);
+        let outcomes = [
+            ShrinkOutcome::Passed,
+            ShrinkOutcome::Failed,
+            ShrinkOutcome::Failed,
+            ShrinkOutcome::Untried,
+        ];
+        graph(&tree.value, &candidates, &outcomes, 3)
+    }
+
+    #[test]
+    fn test_candidates_skip_repeated_values() {
+        let graph = sample();
+        let values: Vec<_> = graph.nodes.iter().map(|node| node.value.as_str()).collect();
+        assert_eq!(values, ["4", "0", "2", "3", "1"]);
+        let parents: Vec<_> = graph.nodes.iter().map(|node| node.parent).collect();
+        assert_eq!(parents, [None, Some(0), Some(0), Some(0), Some(2)]);
+    }
+
+    #[test]
+    fn test_candidates_cap_each_nodes_shrinks() {
+        let tree = Tree::with_children(
+            9,
+            vec![
+                Tree::with_children(4, vec![Tree::singleton(0), Tree::singleton(2)]),
+                Tree::with_children(8, vec![Tree::singleton(6), Tree::singleton(7)]),
+            ],
+        );
+        let values: Vec<_> = candidates(&tree, Some(1))
+            .into_iter()
+            .map(|(_, value)| *value)
+            .collect();
+        assert_eq!(values, [4, 0]);
+    }
+
+    #[test]
+    fn test_renders_dot_and_json() {
+        let graph = sample();
+        let dot = graph.to_dot();
+        assert!(dot.starts_with("digraph shrinks {\n"));
+        assert!(dot.contains("    n3 [label=\"3\\nfailed\", style=filled, peripheries=2];\n"));
+        assert!(dot.contains("    n4 [label=\"1\\nuntried\", style=dashed];\n"));
+        assert!(dot.contains("    n2 -> n4;\n"));
+
+        let json = graph.to_json();
+        assert!(json.starts_with("{\n  \"minimal\": 3,\n"));
+        assert!(
+            json.contains("{\"id\": 1, \"parent\": 0, \"value\": \"0\", \"outcome\": \"passed\"}")
+        );
+        assert_eq!(escape("\"a\"\n"), "\\\"a\\\"\\n");
+    }
+
+    #[test]
+    fn test_failing_runs_write_their_shrink_graph() {
+        let path =
+            std::env::temp_dir().join(format!("hedgehog-shrink-graph-{}.json", std::process::id()));
+        let config = crate::Config::default().with_shrink_graph(&path);
+        let prop = crate::for_all(crate::Gen::int_range(0, 100), |&n: &i32| n < 10);
+        let result = prop.run(&config);
+        let json = std::fs::read_to_string(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        let crate::TestResult::Fail { counterexample, .. } = result else {
+            panic!("Expected failure, got: {result:?}");
+        };
+        assert!(json.contains("\"outcome\": \"original\""));
+        assert!(json.contains(&format!(
+            "\"value\": \"{counterexample}\", \"outcome\": \"failed\""
+        )));
+    }
+}
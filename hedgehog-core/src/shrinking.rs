@@ -0,0 +1,165 @@
+//! Testing how well a generator shrinks.
+//!
+//! A generator's shrink tree decides what counterexamples users see. Whether
+//! it leads to the canonical minimum, say `10` for a property that fails on
+//! numbers of at least ten, can only be checked by walking the tree, which
+//! generator authors would otherwise do by hand. [`assert_shrinks_to`]
+//! finds a failing value, follows the tree down to a minimal one and checks
+//! it is the expected value.
+
+use crate::data::{Config, Seed, Size};
+use crate::gen::Gen;
+use crate::tree::Tree;
+use std::fmt::Debug;
+
+/// A failing value and the shrinks that led to a minimal one.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Shrunk<T> {
+    /// The failing value first generated
+    pub original: T,
+    /// Each smaller failing value shrinking moved to, in order
+    pub steps: Vec<T>,
+}
+
+impl<T> Shrunk<T> {
+    /// The value shrinking stopped at: the last step, or the original if no
+    /// shrink failed.
+    pub fn minimal(&self) -> &T {
+        self.steps.last().unwrap_or(&self.original)
+    }
+}
+
+/// Find a value for which `holds` is false and shrink it as far as the
+/// generator's shrink trees allow.
+///
+/// Values are generated as in a property run, with sizes ramping up over
+/// `config.test_limit` cases, but from a fixed seed so the result is the
+/// same every time. Shrinking moves to the first shrink that still fails,
+/// descending the tree until no shrink fails or `config.shrink_limit` steps
+/// have been taken.
+///
+/// Returns `None` if no generated value fails.
+pub fn minimal_counterexample<T, F>(gen: &Gen<T>, holds: F, config: &Config) -> Option<Shrunk<T>>
+where
+    T: Clone,
+    F: Fn(&T) -> bool,
+{
+    let mut seed = Seed::from_u64(0);
+    let tests = config.test_limit.max(1);
+    let failing = (0..tests).find_map(|test| {
+        let size = Size::new(test * config.size_limit / tests);
+        let (case_seed, next) = seed.split();
+        seed = next;
+        let tree = gen.generate(size, case_seed);
+        (!holds(&tree.value)).then_some(tree)
+    })?;
+
+    let mut steps = Vec::new();
+    let mut current: &Tree<T> = &failing;
+    while steps.len() < config.shrink_limit {
+        match current.children.iter().find(|child| !holds(&child.value)) {
+            Some(child) => {
+                steps.push(child.value.clone());
+                current = child;
+            }
+            None => break,
+        }
+    }
+
+    Some(Shrunk {
+        original: failing.value.clone(),
+        steps,
+    })
+}
+
+/// Assert that shrinking a failure of `holds` reaches `expected`.
+///
+/// Uses [`minimal_counterexample`] with the default config.
+///
+/// # Panics
+/// Panics if no generated value fails, or if shrinking stops anywhere but
+/// `expected`, showing the path it took.
+///
+/// # Example
+/// ```rust
+/// use hedgehog_core::*;
+///
+/// assert_shrinks_to(&Gen::bool(), |&b| !b, true);
+/// ```
+#[track_caller]
+pub fn assert_shrinks_to<T, F>(gen: &Gen<T>, holds: F, expected: T)
+where
+    T: Clone + Debug + PartialEq,
+    F: Fn(&T) -> bool,
+{
+    let Some(shrunk) = minimal_counterexample(gen, holds, &Config::default()) else {
+        panic!("expected a failure shrinking to {expected:?}, but no generated value failed");
+    };
+    if *shrunk.minimal() != expected {
+        let path: Vec<String> = std::iter::once(&shrunk.original)
+            .chain(&shrunk.steps)
+            .map(|value| format!("{value:?}"))
+            .collect();
+        panic!(
+            "expected shrinking to reach {expected:?}, but it stopped at {:?}\n  path: {}",
+            shrunk.minimal(),
+            path.join(" → ")
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Numbers whose trees go all the way down: each shrink halves the
+    /// distance to zero or steps one closer.
+    fn deep_natural(max: u32) -> Gen<u32> {
+        fn tree(n: u32) -> Tree<u32> {
+            let mut shrinks = Vec::new();
+            if n > 0 {
+                shrinks.push(n / 2);
+                shrinks.push(n - 1);
+            }
+            shrinks.dedup();
+            Tree::with_children(n, shrinks.into_iter().map(tree).collect())
+        }
+        Gen::new(move |size, seed| {
+            let bound = u64::from(max).min(size.get() as u64) + 1;
+            tree(seed.next_bounded(bound).0 as u32)
+        })
+    }
+
+    #[test]
+    fn test_reaches_the_canonical_minimum() {
+        assert_shrinks_to(&deep_natural(40), |&n| n < 10, 10);
+
+        let shrunk =
+            minimal_counterexample(&deep_natural(40), |&n| n < 10, &Config::default()).unwrap();
+        assert!(shrunk.original >= 10);
+        assert!(shrunk.steps.iter().all(|&n| n >= 10));
+        assert_eq!(*shrunk.minimal(), 10);
+    }
+
+    #[test]
+    fn test_no_failure() {
+        let config = Config::default().with_tests(20);
+        assert_eq!(
+            minimal_counterexample(&deep_natural(40), |_| true, &config),
+            None
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "expected shrinking to reach 11, but it stopped at 10\n  path:")]
+    fn test_reports_where_shrinking_stopped() {
+        assert_shrinks_to(&deep_natural(40), |&n| n < 10, 11);
+    }
+
+    #[test]
+    fn test_shrink_limit_bounds_the_descent() {
+        let config = Config::default().with_shrinks(1);
+        let shrunk = minimal_counterexample(&deep_natural(40), |&n| n < 10, &config).unwrap();
+        assert!(shrunk.steps.len() <= 1);
+    }
+}
@@ -0,0 +1,196 @@
+//! Estimating how much memory a generated value takes.
+//!
+//! Sizes bound each generator, but not their compositions: a vector of
+//! vectors of strings grows with the cube of the size, and one generator
+//! nested too deep can build inputs of gigabytes before anything fails.
+//! [`Config::with_max_value_bytes`](crate::Config::with_max_value_bytes)
+//! sets a budget for each generated value, estimated with [`SizeOf`] once a
+//! property opts in with [`Property::with_byte_estimate`](crate::Property::with_byte_estimate).
+//! Sizes grow over a run, so the first value over the budget stops it with
+//! the size it was generated at, long before the largest sizes run.
+//!
+//! # Example
+//! ```rust
+//! use hedgehog_core::*;
+//!
+//! let words = Gen::<Vec<String>>::vec_of(Gen::<String>::ascii_alpha());
+//! let prop = for_all(words, |_| true).with_byte_estimate();
+//! let result = prop.run(&Config::default().with_max_value_bytes(1024 * 1024));
+//! assert!(matches!(result, TestResult::Pass { .. }));
+//! ```
+
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet, VecDeque};
+use std::mem::size_of;
+
+/// Values whose memory use can be estimated.
+pub trait SizeOf {
+    /// Roughly how many bytes the value takes, counting its own size and
+    /// everything it owns on the heap. Spare capacity isn't counted.
+    fn estimated_bytes(&self) -> usize;
+}
+
+macro_rules! impl_size_of_inline {
+    ($($t:ty),*) => {
+        $(impl SizeOf for $t {
+            fn estimated_bytes(&self) -> usize {
+                size_of::<$t>()
+            }
+        })*
+    };
+}
+
+impl_size_of_inline!(i8, i16, i32, i64, i128, isize);
+impl_size_of_inline!(u8, u16, u32, u64, u128, usize);
+impl_size_of_inline!(f32, f64, bool, char, ());
+
+impl SizeOf for String {
+    fn estimated_bytes(&self) -> usize {
+        size_of::<String>() + self.len()
+    }
+}
+
+impl SizeOf for Box<str> {
+    fn estimated_bytes(&self) -> usize {
+        size_of::<Box<str>>() + self.len()
+    }
+}
+
+impl<T: SizeOf> SizeOf for Box<T> {
+    fn estimated_bytes(&self) -> usize {
+        size_of::<Box<T>>() + (**self).estimated_bytes()
+    }
+}
+
+impl<T: SizeOf> SizeOf for Option<T> {
+    fn estimated_bytes(&self) -> usize {
+        let inline = size_of::<Option<T>>();
+        match self {
+            Some(value) => inline + value.estimated_bytes() - size_of::<T>(),
+            None => inline,
+        }
+    }
+}
+
+impl<T: SizeOf, E: SizeOf> SizeOf for Result<T, E> {
+    fn estimated_bytes(&self) -> usize {
+        let inline = size_of::<Result<T, E>>();
+        match self {
+            Ok(value) => inline + value.estimated_bytes() - size_of::<T>(),
+            Err(error) => inline + error.estimated_bytes() - size_of::<E>(),
+        }
+    }
+}
+
+impl<T: SizeOf, const N: usize> SizeOf for [T; N] {
+    fn estimated_bytes(&self) -> usize {
+        self.iter().map(SizeOf::estimated_bytes).sum()
+    }
+}
+
+/// Collections take their own size plus each element's.
+macro_rules! impl_size_of_collection {
+    ($($collection:ident<$($param:ident),*>),*) => {
+        $(impl<$($param: SizeOf),*> SizeOf for $collection<$($param),*> {
+            fn estimated_bytes(&self) -> usize {
+                size_of::<Self>()
+                    + self.iter().map(SizeOf::estimated_bytes).sum::<usize>()
+            }
+        })*
+    };
+}
+
+impl_size_of_collection!(Vec<T>, VecDeque<T>, BTreeSet<T>, HashSet<T>);
+
+macro_rules! impl_size_of_map {
+    ($($map:ident),*) => {
+        $(impl<K: SizeOf, V: SizeOf> SizeOf for $map<K, V> {
+            fn estimated_bytes(&self) -> usize {
+                size_of::<Self>()
+                    + self
+                        .iter()
+                        .map(|(key, value)| key.estimated_bytes() + value.estimated_bytes())
+                        .sum::<usize>()
+            }
+        })*
+    };
+}
+
+impl_size_of_map!(BTreeMap, HashMap);
+
+macro_rules! impl_size_of_tuple {
+    ($(($($name:ident),+)),*) => {
+        $(impl<$($name: SizeOf),+> SizeOf for ($($name,)+) {
+            #[allow(non_snake_case)]
+            fn estimated_bytes(&self) -> usize {
+                let ($($name,)+) = self;
+                // Each field's estimate counts its own inline size; padding
+                // is the difference between those and the tuple's.
+                let fields = 0 $(+ size_of::<$name>())+;
+                size_of::<Self>() - fields $(+ $name.estimated_bytes())+
+            }
+        })*
+    };
+}
+
+impl_size_of_tuple!((A), (A, B), (A, B, C), (A, B, C, D), (A, B, C, D, E));
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data::Config;
+    use crate::error::{FailureDetails, TestResult};
+    use crate::gen::Gen;
+    use crate::property::for_all;
+
+    #[test]
+    fn test_estimates_count_owned_heap_data() {
+        assert_eq!(7u32.estimated_bytes(), 4);
+        assert_eq!(
+            String::from("abc").estimated_bytes(),
+            size_of::<String>() + 3
+        );
+        assert_eq!(
+            vec![1u64, 2, 3].estimated_bytes(),
+            size_of::<Vec<u64>>() + 24
+        );
+        assert_eq!(
+            None::<String>.estimated_bytes(),
+            size_of::<Option<String>>()
+        );
+        assert_eq!(
+            Some(String::from("ab")).estimated_bytes(),
+            size_of::<Option<String>>() + 2
+        );
+        assert_eq!(
+            (1u8, String::from("ab")).estimated_bytes(),
+            size_of::<(u8, String)>() + 2
+        );
+    }
+
+    #[test]
+    fn test_values_over_the_budget_stop_the_run() {
+        let rows = Gen::<Vec<Vec<u64>>>::vec_of(Gen::<Vec<u64>>::vec_of(Gen::constant(0)));
+        let prop = for_all(rows, |_| true).with_byte_estimate();
+
+        let result = prop.run(&Config::default().with_max_value_bytes(512));
+        let TestResult::Fail {
+            counterexample,
+            assertion_type: Some(assertion),
+            shrinks_performed,
+            details: FailureDetails {
+                size: Some(size), ..
+            },
+            ..
+        } = result
+        else {
+            panic!("Expected the budget to stop the run, got: {result:?}");
+        };
+        assert!(counterexample.starts_with("<value of "));
+        assert!(assertion.starts_with("Value Over Byte Budget\n"));
+        assert!(assertion.contains(&format!("at size {size}")));
+        assert_eq!(shrinks_performed, 0);
+
+        let generous = Config::default().with_max_value_bytes(usize::MAX);
+        assert!(matches!(prop.run(&generous), TestResult::Pass { .. }));
+    }
+}
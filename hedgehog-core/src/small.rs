@@ -0,0 +1,242 @@
+//! The simplest values of a type, for checking exhaustively.
+//!
+//! Random generation usually finds the empty list, zero and the boundary
+//! between one element and two, but not always. [`SmallValues`] lists every
+//! value of a type below a tiny bound, simplest first: integers in
+//! `[-3, 3]`, collections of up to three elements and every variant of a
+//! fieldless enum. [`Property::with_small_cases`](crate::Property::with_small_cases)
+//! checks those within the generator's domain before generating anything,
+//! so those cases are always covered.
+//!
+//! # Example
+//! ```rust
+//! use hedgehog_core::*;
+//!
+//! let prop = for_all(Gen::vec_of(Gen::int_range(1, 100)), |xs: &Vec<i32>| {
+//!     xs.iter().rev().rev().eq(xs.iter())
+//! })
+//! .with_small_cases(50, |xs| xs.iter().all(|x| (1..=100).contains(x)));
+//! ```
+
+/// Most values listed for any one type, so that nested collections stay
+/// cheap to list.
+pub const SMALL_VALUES_LIMIT: usize = 1000;
+
+/// Longest collection listed.
+const MAX_LENGTH: usize = 3;
+
+/// Types whose simplest values can be listed.
+pub trait SmallValues: Sized {
+    /// The values, simplest first, at most [`SMALL_VALUES_LIMIT`] of them.
+    fn small_values() -> Vec<Self>;
+}
+
+macro_rules! impl_small_signed {
+    ($($t:ty),*) => {
+        $(impl SmallValues for $t {
+            fn small_values() -> Vec<Self> {
+                vec![0, -1, 1, -2, 2, -3, 3]
+            }
+        })*
+    };
+}
+
+macro_rules! impl_small_unsigned {
+    ($($t:ty),*) => {
+        $(impl SmallValues for $t {
+            fn small_values() -> Vec<Self> {
+                vec![0, 1, 2, 3]
+            }
+        })*
+    };
+}
+
+impl_small_signed!(i8, i16, i32, i64, i128, isize);
+impl_small_unsigned!(u8, u16, u32, u64, u128, usize);
+
+impl SmallValues for f64 {
+    fn small_values() -> Vec<Self> {
+        vec![0.0, -1.0, 1.0, -0.5, 0.5]
+    }
+}
+
+impl SmallValues for f32 {
+    fn small_values() -> Vec<Self> {
+        vec![0.0, -1.0, 1.0, -0.5, 0.5]
+    }
+}
+
+impl SmallValues for bool {
+    fn small_values() -> Vec<Self> {
+        vec![false, true]
+    }
+}
+
+impl SmallValues for () {
+    fn small_values() -> Vec<Self> {
+        vec![()]
+    }
+}
+
+impl SmallValues for char {
+    fn small_values() -> Vec<Self> {
+        vec!['a', 'b', ' ', '0']
+    }
+}
+
+impl SmallValues for String {
+    fn small_values() -> Vec<Self> {
+        Vec::<char>::small_values()
+            .into_iter()
+            .map(|chars| chars.into_iter().collect())
+            .collect()
+    }
+}
+
+impl<T: SmallValues + Clone> SmallValues for Vec<T> {
+    fn small_values() -> Vec<Self> {
+        let elements = T::small_values();
+        let mut values = Vec::new();
+        for length in 0..=MAX_LENGTH {
+            if elements.is_empty() && length > 0 {
+                break;
+            }
+            let limit = SMALL_VALUES_LIMIT - values.len();
+            values.extend(
+                by_simplicity(&vec![elements.len(); length], limit)
+                    .into_iter()
+                    .map(|indices| indices.iter().map(|&i| elements[i].clone()).collect()),
+            );
+        }
+        values
+    }
+}
+
+impl<T: SmallValues> SmallValues for Option<T> {
+    fn small_values() -> Vec<Self> {
+        std::iter::once(None)
+            .chain(T::small_values().into_iter().map(Some))
+            .take(SMALL_VALUES_LIMIT)
+            .collect()
+    }
+}
+
+impl<T: SmallValues, E: SmallValues> SmallValues for Result<T, E> {
+    fn small_values() -> Vec<Self> {
+        let mut oks = T::small_values().into_iter().map(Ok);
+        let mut errs = E::small_values().into_iter().map(Err);
+        let mut values = Vec::new();
+        while values.len() < SMALL_VALUES_LIMIT {
+            match (oks.next(), errs.next()) {
+                (None, None) => break,
+                (ok, err) => values.extend(ok.into_iter().chain(err)),
+            }
+        }
+        values.truncate(SMALL_VALUES_LIMIT);
+        values
+    }
+}
+
+impl<T: SmallValues> SmallValues for Box<T> {
+    fn small_values() -> Vec<Self> {
+        T::small_values().into_iter().map(Box::new).collect()
+    }
+}
+
+macro_rules! impl_small_tuple {
+    ($($name:ident: $index:tt),+) => {
+        impl<$($name: SmallValues + Clone),+> SmallValues for ($($name,)+) {
+            fn small_values() -> Vec<Self> {
+                let parts = ($($name::small_values(),)+);
+                by_simplicity(&[$(parts.$index.len()),+], SMALL_VALUES_LIMIT)
+                    .into_iter()
+                    .map(|indices| ($(parts.$index[indices[$index]].clone(),)+))
+                    .collect()
+            }
+        }
+    };
+}
+
+impl_small_tuple!(A: 0, B: 1);
+impl_small_tuple!(A: 0, B: 1, C: 2);
+impl_small_tuple!(A: 0, B: 1, C: 2, D: 3);
+impl_small_tuple!(A: 0, B: 1, C: 2, D: 3, E: 4);
+
+/// Every choice of one index below each of `counts`, in order of the sum
+/// of the indices, so combinations of simpler parts come first. Stops after
+/// `limit` choices.
+fn by_simplicity(counts: &[usize], limit: usize) -> Vec<Vec<usize>> {
+    if counts.contains(&0) {
+        return Vec::new();
+    }
+    let largest: usize = counts.iter().map(|count| count - 1).sum();
+    let mut choices = Vec::new();
+    for total in 0..=largest {
+        summing_to(counts, total, &mut Vec::new(), &mut choices, limit);
+        if choices.len() >= limit {
+            break;
+        }
+    }
+    choices
+}
+
+/// Push every choice of indices below `counts` summing to `total`, after
+/// the indices already in `prefix`.
+fn summing_to(
+    counts: &[usize],
+    total: usize,
+    prefix: &mut Vec<usize>,
+    choices: &mut Vec<Vec<usize>>,
+    limit: usize,
+) {
+    let Some((&count, rest)) = counts.split_first() else {
+        if total == 0 && choices.len() < limit {
+            choices.push(prefix.clone());
+        }
+        return;
+    };
+    let most_later: usize = rest.iter().map(|count| count - 1).sum();
+    for index in total.saturating_sub(most_later)..count.min(total + 1) {
+        prefix.push(index);
+        summing_to(rest, total - index, prefix, choices, limit);
+        prefix.pop();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_lists_are_complete_and_simplest_first() {
+        let lists = Vec::<bool>::small_values();
+        assert_eq!(lists.len(), 1 + 2 + 4 + 8);
+        assert_eq!(
+            lists[..4],
+            [vec![], vec![false], vec![true], vec![false, false]]
+        );
+
+        let pairs = <(u8, bool)>::small_values();
+        assert_eq!(pairs.len(), 8);
+        assert_eq!(pairs[0], (0, false));
+        assert_eq!(pairs.last(), Some(&(3, true)));
+        let sums: Vec<usize> = pairs
+            .iter()
+            .map(|&(n, b)| n as usize + b as usize)
+            .collect();
+        assert!(sums.windows(2).all(|w| w[0] <= w[1]));
+
+        assert_eq!(
+            Result::<bool, ()>::small_values(),
+            [Ok(false), Err(()), Ok(true)]
+        );
+    }
+
+    #[test]
+    fn test_nested_collections_are_capped() {
+        let nested = Vec::<Vec<i32>>::small_values();
+        assert_eq!(nested.len(), SMALL_VALUES_LIMIT);
+        assert_eq!(nested[0], Vec::<Vec<i32>>::new());
+        assert_eq!(nested[1], vec![vec![]]);
+    }
+}
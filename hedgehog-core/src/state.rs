@@ -8,7 +8,7 @@ use std::any::{Any, TypeId};
 use std::collections::HashMap;
 use std::fmt::{Debug, Display};
 use std::marker::PhantomData;
-use std::sync::Arc;
+use std::sync::{Arc, Mutex};
 
 /// A unique identifier for symbolic variables during generation phase.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
@@ -257,6 +257,18 @@ impl<Input, Output, State, M> Command<Input, Output, State, M> {
     pub fn can_execute(&self, state: &State) -> bool {
         (self.input_gen)(state).is_some()
     }
+
+    /// Record this command's effects on `cassette`, or replay them from it
+    /// without running the command, depending on the cassette's mode.
+    pub fn recorded(mut self, cassette: &crate::cassette::Cassette) -> Self
+    where
+        Input: Debug + 'static,
+        M: Clone + Send + Sync + 'static,
+    {
+        let execute = self.execute.clone();
+        self.execute = Arc::new(cassette.wrap(&self.name, move |input| execute(input)));
+        self
+    }
 }
 
 /// An instantiated action ready for execution.
@@ -358,9 +370,133 @@ pub trait ActionTrait<State, M>: Send {
     ) -> Result<CaptureCheckResult<State>, String>;
 }
 
+/// How often one command was generated and executed.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct CommandCount {
+    pub generated: usize,
+    pub executed: usize,
+}
+
+/// Counts of which commands were generated and executed, shared across
+/// every case of a property.
+///
+/// A command whose precondition rarely holds, or whose input generator
+/// rarely succeeds, may never run at all, and the property passes without
+/// ever testing it. Create one `CommandCoverage` outside the property, track
+/// it in each case's [`ActionGenerator`], and print it afterwards.
+///
+/// # Example
+///
+/// ```
+/// use hedgehog_core::*;
+/// use hedgehog_core::state::*;
+///
+/// #[derive(Clone, Debug)]
+/// struct Counter { value: i32 }
+///
+/// let coverage = CommandCoverage::new();
+/// let tracked = coverage.clone();
+/// let prop = for_all(Gen::bool(), move |_| {
+///     let mut gen = ActionGenerator::new();
+///     gen.add_command(Command::<i32, i32, Counter, i32>::new(
+///         "inc".to_string(),
+///         |_| Some(Gen::constant(1)),
+///         |amount| amount,
+///     ));
+///     gen.track_coverage(&tracked);
+///     execute_sequential(Counter { value: 0 }, gen.generate_sequential(Counter { value: 0 }, 5))
+///         .is_ok()
+/// });
+/// prop.run(&Config::default().with_tests(10));
+///
+/// assert_eq!(coverage.count("inc").executed, 50);
+/// print!("{coverage}");
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct CommandCoverage {
+    counts: Arc<Mutex<HashMap<String, CommandCount>>>,
+}
+
+impl CommandCoverage {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Counts for the command called `name`.
+    pub fn count(&self, name: &str) -> CommandCount {
+        self.lock().get(name).copied().unwrap_or_default()
+    }
+
+    /// Every command seen, with its counts, sorted by name.
+    pub fn counts(&self) -> Vec<(String, CommandCount)> {
+        let mut counts: Vec<_> = self
+            .lock()
+            .iter()
+            .map(|(name, count)| (name.clone(), *count))
+            .collect();
+        counts.sort_by(|a, b| a.0.cmp(&b.0));
+        counts
+    }
+
+    /// Commands known to a tracked generator that never executed.
+    pub fn never_executed(&self) -> Vec<String> {
+        self.counts()
+            .into_iter()
+            .filter(|(_, count)| count.executed == 0)
+            .map(|(name, _)| name)
+            .collect()
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, HashMap<String, CommandCount>> {
+        self.counts
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    fn register(&self, name: &str) {
+        self.lock().entry(name.to_string()).or_default();
+    }
+
+    fn generated(&self, name: &str) {
+        self.lock().entry(name.to_string()).or_default().generated += 1;
+    }
+
+    fn executed(&self, name: &str) {
+        self.lock().entry(name.to_string()).or_default().executed += 1;
+    }
+}
+
+impl Display for CommandCoverage {
+    /// Each command's share of the executed commands, in the style of
+    /// classification labels.
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let counts = self.counts();
+        let executed: usize = counts.iter().map(|(_, count)| count.executed).sum();
+        writeln!(f, "  Command coverage:")?;
+        for (name, count) in &counts {
+            let percentage = if executed == 0 {
+                0.0
+            } else {
+                count.executed as f64 / executed as f64 * 100.0
+            };
+            writeln!(
+                f,
+                "    {percentage:>3.0}% {name} ({} generated, {} executed)",
+                count.generated, count.executed
+            )?;
+        }
+        let never = self.never_executed();
+        if !never.is_empty() {
+            writeln!(f, "  ⚠ Never executed: {}", never.join(", "))?;
+        }
+        Ok(())
+    }
+}
+
 /// Generator for creating sequences of actions.
 pub struct ActionGenerator<State> {
     commands: Vec<Box<dyn CommandTrait<State>>>,
+    coverage: Option<CommandCoverage>,
 }
 
 impl<State> Default for ActionGenerator<State> {
@@ -373,9 +509,20 @@ impl<State> ActionGenerator<State> {
     pub fn new() -> Self {
         Self {
             commands: Vec::new(),
+            coverage: None,
         }
     }
 
+    /// Count the commands this generator generates, and that its actions
+    /// execute, in `coverage`. Every command added is listed, so ones that
+    /// never fire show up with zero counts.
+    pub fn track_coverage(&mut self, coverage: &CommandCoverage) {
+        for command in &self.commands {
+            coverage.register(command.name());
+        }
+        self.coverage = Some(coverage.clone());
+    }
+
     pub fn add_command<Input, Output, M>(&mut self, command: Command<Input, Output, State, M>)
     where
         Input: 'static + Clone + Debug + Display + Send + Sync,
@@ -383,6 +530,9 @@ impl<State> ActionGenerator<State> {
         State: 'static + Clone + Send,
         M: 'static + Clone + Send + Sync + Into<Output>,
     {
+        if let Some(coverage) = &self.coverage {
+            coverage.register(&command.name);
+        }
         self.commands.push(Box::new(TypedCommand {
             command,
             _phantom: PhantomData::<M>,
@@ -416,7 +566,9 @@ impl<State> ActionGenerator<State> {
             let (command_index, _) = command_seed.next_bounded(available_commands.len() as u64);
             let selected_command = available_commands[command_index as usize];
 
-            if let Some(action) = selected_command.generate_action_dyn(&mut ctx) {
+            if let Some(action) =
+                selected_command.generate_action_dyn(&mut ctx, self.coverage.as_ref())
+            {
                 actions.push(action);
 
                 // CRITICAL: Update the generation state so next commands see the change
@@ -544,7 +696,7 @@ impl<State> ActionGenerator<State> {
         let (command_index, _) = command_seed.next_bounded(available_commands.len() as u64);
         let selected_command = available_commands[command_index as usize];
 
-        if let Some(action) = selected_command.generate_action_dyn(ctx) {
+        if let Some(action) = selected_command.generate_action_dyn(ctx, self.coverage.as_ref()) {
             selected_command.update_generation_state(ctx);
             Some(action)
         } else {
@@ -555,10 +707,12 @@ impl<State> ActionGenerator<State> {
 
 /// Trait for type-erased commands.
 trait CommandTrait<State> {
+    fn name(&self) -> &str;
     fn can_execute_dyn(&self, state: &State) -> bool;
     fn generate_action_dyn(
         &self,
         ctx: &mut GenerationContext<State>,
+        coverage: Option<&CommandCoverage>,
     ) -> Option<Box<dyn ActionTrait<State, ()>>>;
     fn update_generation_state(&self, ctx: &mut GenerationContext<State>);
 }
@@ -576,6 +730,10 @@ where
     State: 'static + Clone + Send,
     M: 'static + Clone + Send + Sync + Into<Output>,
 {
+    fn name(&self) -> &str {
+        &self.command.name
+    }
+
     fn can_execute_dyn(&self, state: &State) -> bool {
         self.command.can_execute(state)
     }
@@ -583,6 +741,7 @@ where
     fn generate_action_dyn(
         &self,
         ctx: &mut GenerationContext<State>,
+        coverage: Option<&CommandCoverage>,
     ) -> Option<Box<dyn ActionTrait<State, ()>>> {
         let input_gen = (self.command.input_gen)(ctx.state())?;
 
@@ -606,6 +765,10 @@ where
         let execute_fn = self.command.execute.clone();
         let callbacks = create_callback_handlers(&self.command.callbacks);
 
+        if let Some(coverage) = coverage {
+            coverage.generated(&self.command.name);
+        }
+
         Some(Box::new(FunctionalAction {
             input: input.clone(),
             output,
@@ -613,6 +776,7 @@ where
             update_fn: callbacks.0,
             ensure_fn: callbacks.1,
             name: self.command.name.clone(),
+            coverage: coverage.cloned(),
             _phantom: PhantomData::<(Output, State, M)>,
         }))
     }
@@ -676,6 +840,7 @@ struct FunctionalAction<Input, Output, State, M> {
     update_fn: Option<UpdateFn<State, Input, Output>>,
     ensure_fn: Option<EnsureFn<State, Input, Output>>,
     name: String,
+    coverage: Option<CommandCoverage>,
     _phantom: PhantomData<(Output, State, M)>,
 }
 
@@ -692,6 +857,9 @@ where
 
         // Execute the actual command function
         let output_value = (self.execute_fn)(concrete_input.clone());
+        if let Some(coverage) = &self.coverage {
+            coverage.executed(&self.name);
+        }
 
         // Store the result in the environment (convert M to Output)
         let converted_output: Output = output_value.into();
@@ -736,6 +904,9 @@ where
 
         // Execute the actual command function
         let output_value = (self.execute_fn)(concrete_input.clone());
+        if let Some(coverage) = &self.coverage {
+            coverage.executed(&self.name);
+        }
 
         // Store the result in the environment (convert M to Output)
         let converted_output: Output = output_value.clone().into();
@@ -2169,6 +2340,104 @@ mod tests {
         println!("✓ State consistency maintained between generation and execution phases");
     }
 
+    #[test]
+    fn test_command_coverage() {
+        #[derive(Debug, Clone)]
+        struct Counter {
+            value: i32,
+        }
+
+        let coverage = CommandCoverage::new();
+        for _ in 0..3 {
+            let mut generator = ActionGenerator::new();
+            generator.track_coverage(&coverage);
+            generator.add_command(
+                Command::<i32, i32, Counter, i32>::new(
+                    "inc".to_string(),
+                    |_| Some(Gen::constant(1)),
+                    |amount| amount,
+                )
+                .with_update(|state: &mut Counter, amount: &i32, _| state.value += amount),
+            );
+            // Only possible once the counter passes 100, which it never does
+            generator.add_command(Command::<i32, i32, Counter, i32>::new(
+                "reset".to_string(),
+                |state: &Counter| (state.value > 100).then(|| Gen::constant(0)),
+                |_| 0,
+            ));
+
+            let sequential = generator.generate_sequential(Counter { value: 0 }, 4);
+            execute_sequential(Counter { value: 0 }, sequential).unwrap();
+        }
+
+        assert_eq!(
+            coverage.count("inc"),
+            CommandCount {
+                generated: 12,
+                executed: 12
+            }
+        );
+        assert_eq!(coverage.count("reset"), CommandCount::default());
+        assert_eq!(coverage.never_executed(), ["reset"]);
+        assert_eq!(
+            coverage.to_string(),
+            "  Command coverage:\n\
+             \x20   100% inc (12 generated, 12 executed)\n\
+             \x20     0% reset (0 generated, 0 executed)\n\
+             \x20 ⚠ Never executed: reset\n"
+        );
+    }
+
+    #[test]
+    fn test_recorded_commands_replay_without_the_system() {
+        #[derive(Debug, Clone)]
+        struct Model {
+            total: u32,
+        }
+
+        fn commands(
+            cassette: &crate::cassette::Cassette,
+            deposit: impl Fn(u32) -> u32 + Send + Sync + 'static,
+        ) -> ActionGenerator<Model> {
+            let mut generator = ActionGenerator::new();
+            generator.add_command(
+                Command::<u32, u32, Model, u32>::new(
+                    "deposit".to_string(),
+                    |_| Some(Gen::u32_range(1, 5)),
+                    deposit,
+                )
+                .recorded(cassette)
+                .with_update(|model: &mut Model, amount: &u32, _| model.total += amount)
+                .with_ensure(|_, after, _, balance: &u32| {
+                    if *balance == after.total {
+                        Ok(())
+                    } else {
+                        Err(format!("balance {balance}, expected {}", after.total))
+                    }
+                }),
+            );
+            generator
+        }
+
+        // Record against the real system
+        let bank = Arc::new(Mutex::new(0));
+        let cassette = crate::cassette::Cassette::new();
+        let sequential = commands(&cassette, move |amount| {
+            let mut balance = bank.lock().unwrap();
+            *balance += amount;
+            *balance
+        })
+        .generate_sequential(Model { total: 0 }, 6);
+        execute_sequential(Model { total: 0 }, sequential).unwrap();
+        assert_eq!(cassette.len(), 6);
+
+        // Replay the same run with no system at all
+        let replaying = cassette.replay();
+        let sequential = commands(&replaying, |_| unreachable!("the system is gone"))
+            .generate_sequential(Model { total: 0 }, 6);
+        execute_sequential(Model { total: 0 }, sequential).unwrap();
+    }
+
     #[test]
     fn test_parallel_execution_simple() {
         // Simple test for parallel state machine execution with linearizability checking
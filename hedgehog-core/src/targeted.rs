@@ -366,8 +366,148 @@ where
     )
 }
 
+/// Search for inputs that maximize `utility`, checking `property` on each.
+///
+/// The simple front-end to [`SimulatedAnnealing`], after PropEr's
+/// `?FORALL_TARGETED`: neighbors are drawn from the generator itself with a
+/// [`GenNeighborhood`], and a failing input counts as the best possible, so
+/// the search reports the first failure it finds.
+///
+/// ```rust
+/// use hedgehog_core::*;
+///
+/// let search = for_all_maximizing(
+///     Gen::<i32>::from_range(Range::new(0, 1000)),
+///     |&n| n as f64,
+///     |&n| n < 900,
+/// );
+/// let (result, stats) = search.search(&Config::default());
+/// assert!(stats.evaluations > 0);
+/// # let _ = result;
+/// ```
+pub fn for_all_maximizing<T, U, P>(
+    generator: Gen<T>,
+    utility: U,
+    property: P,
+) -> SimulatedAnnealing<T>
+where
+    T: 'static + std::fmt::Debug + Clone,
+    U: Fn(&T) -> f64 + 'static,
+    P: Fn(&T) -> bool + 'static,
+{
+    for_all_optimizing(generator, utility, property, SearchObjective::Maximize)
+}
+
+/// Search for inputs that minimize `utility`, checking `property` on each.
+///
+/// See [`for_all_maximizing`].
+pub fn for_all_minimizing<T, U, P>(
+    generator: Gen<T>,
+    utility: U,
+    property: P,
+) -> SimulatedAnnealing<T>
+where
+    T: 'static + std::fmt::Debug + Clone,
+    U: Fn(&T) -> f64 + 'static,
+    P: Fn(&T) -> bool + 'static,
+{
+    for_all_optimizing(generator, utility, property, SearchObjective::Minimize)
+}
+
+fn for_all_optimizing<T, U, P>(
+    generator: Gen<T>,
+    utility: U,
+    property: P,
+    objective: SearchObjective,
+) -> SimulatedAnnealing<T>
+where
+    T: 'static + std::fmt::Debug + Clone,
+    U: Fn(&T) -> f64 + 'static,
+    P: Fn(&T) -> bool + 'static,
+{
+    let generator = std::rc::Rc::new(generator);
+    let shared = generator.clone();
+    let neighborhood =
+        GenNeighborhood::new(Gen::new(move |size, seed| shared.generate(size, seed)));
+    let generator = Gen::new(move |size, seed| generator.generate(size, seed));
+
+    // Failures outrank every other input, and ties aren't better, so the
+    // first failure found is the one kept.
+    let best = match objective {
+        SearchObjective::Maximize => f64::INFINITY,
+        SearchObjective::Minimize => f64::NEG_INFINITY,
+    };
+    let utility_function = move |input: &T, result: &TargetedResult| {
+        if result.is_fail() {
+            best
+        } else {
+            utility(input)
+        }
+    };
+    let test_function = move |input: &T| {
+        if property(input) {
+            TargetedResult::Pass {
+                tests_run: 1,
+                property_name: None,
+                module_path: None,
+                utility: 0.0,
+            }
+        } else {
+            TargetedResult::Fail {
+                counterexample: format!("{input:?}"),
+                tests_run: 1,
+                shrinks_performed: 0,
+                property_name: None,
+                module_path: None,
+                assertion_type: None,
+                shrink_steps: Vec::new(),
+                utility: best,
+            }
+        }
+    };
+
+    SimulatedAnnealing::new(
+        generator,
+        utility_function,
+        test_function,
+        neighborhood,
+        TargetedConfig {
+            objective,
+            ..TargetedConfig::default()
+        },
+    )
+}
+
 // ===== Built-in Neighborhood Functions =====
 
+/// Neighborhood function that draws each neighbor afresh from a generator.
+///
+/// Works for any type a generator exists for, at the cost of locality: the
+/// search climbs by keeping the best draws rather than by small moves. Hotter
+/// searches draw at larger sizes.
+pub struct GenNeighborhood<T> {
+    generator: Gen<T>,
+}
+
+impl<T> GenNeighborhood<T> {
+    /// Create a neighborhood that draws from the given generator.
+    pub fn new(generator: Gen<T>) -> Self {
+        GenNeighborhood { generator }
+    }
+}
+
+impl<T> NeighborhoodFunction<T> for GenNeighborhood<T> {
+    fn neighbor(&self, _input: &T, temperature: f64, rng: &mut dyn RngCore) -> Option<T> {
+        let max_size = temperature.clamp(1.0, 100.0) as usize;
+        let size = Size::new(rng.gen_range(0..=max_size));
+        Some(
+            self.generator
+                .generate(size, Seed::from_u64(rng.gen()))
+                .value,
+        )
+    }
+}
+
 /// Neighborhood function for integers that adds/subtracts small random values.
 #[derive(Debug, Clone)]
 pub struct IntegerNeighborhood {
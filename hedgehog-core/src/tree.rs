@@ -1,8 +1,12 @@
 //! Rose tree implementation for shrinking test values.
 
 use std::collections::VecDeque;
+use std::sync::Arc;
 
 pub mod render;
+pub mod traversal;
+
+pub use traversal::{Bfs, Dfs, Visit};
 
 /// A rose tree containing a value and its shrink possibilities.
 ///
@@ -12,6 +16,7 @@ pub mod render;
 pub struct Tree<T> {
     pub value: T,
     pub children: Vec<Tree<T>>,
+    origin: Option<Arc<str>>,
 }
 
 impl<T> Tree<T> {
@@ -20,12 +25,45 @@ impl<T> Tree<T> {
         Tree {
             value,
             children: Vec::new(),
+            origin: None,
         }
     }
 
     /// Create a new tree with the given value and children.
     pub fn with_children(value: T, children: Vec<Tree<T>>) -> Self {
-        Tree { value, children }
+        Tree {
+            value,
+            children,
+            origin: None,
+        }
+    }
+
+    /// Which branch of a choice generator such as
+    /// [`Gen::frequency`](crate::Gen::frequency) produced the tree, if one
+    /// did, as in `one_of[1]`. Nested choices are joined outermost first.
+    ///
+    /// The branch is recorded on the root only; a tree's shrinks all come
+    /// from the same branch.
+    pub fn origin(&self) -> Option<&str> {
+        self.origin.as_deref()
+    }
+
+    /// Record that the tree came from the choice `branch`, outside any
+    /// choices already recorded.
+    pub fn with_origin(mut self, branch: Arc<str>) -> Self {
+        self.origin = Some(match self.origin.take() {
+            Some(inner) => format!("{branch} → {inner}").into(),
+            None => branch,
+        });
+        self
+    }
+
+    /// Keep the tree's own origin, or take `outer`'s if it has none.
+    pub(crate) fn or_origin<U>(mut self, outer: &Tree<U>) -> Self {
+        if self.origin.is_none() {
+            self.origin.clone_from(&outer.origin);
+        }
+        self
     }
 
     /// Map a function over the tree values.
@@ -40,6 +78,7 @@ impl<T> Tree<T> {
                 .into_iter()
                 .map(|child| child.map(f.clone()))
                 .collect(),
+            origin: self.origin,
         }
     }
 
@@ -51,6 +90,7 @@ impl<T> Tree<T> {
         let Tree {
             value: new_value,
             children: new_children,
+            origin: new_origin,
         } = f(self.value);
 
         let mapped_children: Vec<Tree<U>> = self
@@ -66,6 +106,7 @@ impl<T> Tree<T> {
                 result.extend(mapped_children);
                 result
             },
+            origin: new_origin.or(self.origin),
         }
     }
 
@@ -129,6 +170,28 @@ impl<T> Tree<T> {
         Some(Tree {
             value: self.value,
             children: filtered_children,
+            origin: self.origin,
+        })
+    }
+
+    /// Map a partial function over the tree, dropping every subtree whose
+    /// value it does not map.
+    pub fn filter_map<U, F>(self, f: F) -> Option<Tree<U>>
+    where
+        F: Fn(T) -> Option<U> + Clone,
+    {
+        let value = f(self.value)?;
+
+        let mapped_children: Vec<Tree<U>> = self
+            .children
+            .into_iter()
+            .filter_map(|child| child.filter_map(f.clone()))
+            .collect();
+
+        Some(Tree {
+            value,
+            children: mapped_children,
+            origin: self.origin,
         })
     }
 
@@ -201,6 +264,36 @@ mod tests {
         assert_eq!(mapped.children[1].value, 0);
     }
 
+    #[test]
+    fn test_tree_origin_is_shared_by_the_root() {
+        let branch: Arc<str> = Arc::from("one_of[1]");
+        let tree = Tree::with_children(10, vec![Tree::singleton(5)])
+            .with_origin(Arc::from("frequency[0]"))
+            .with_origin(branch.clone())
+            .map(|x| x * 2);
+        assert_eq!(tree.origin(), Some("one_of[1] → frequency[0]"));
+        assert_eq!(tree.children[0].origin(), None);
+
+        let tree = Tree::singleton(1).with_origin(branch.clone());
+        assert!(Arc::ptr_eq(tree.origin.as_ref().unwrap(), &branch));
+    }
+
+    #[test]
+    fn test_tree_filter_map() {
+        let tree = Tree::with_children(
+            10,
+            vec![
+                Tree::with_children(5, vec![Tree::singleton(2)]),
+                Tree::singleton(0),
+            ],
+        );
+        let halve = |x: i32| if x % 2 == 0 { Some(x / 2) } else { None };
+
+        let mapped = tree.clone().filter_map(halve).unwrap();
+        assert_eq!(mapped, Tree::with_children(5, vec![Tree::singleton(0)]));
+        assert!(Tree::singleton(3).filter_map(halve).is_none());
+    }
+
     #[test]
     fn test_shrinks() {
         let tree = Tree::with_children(
@@ -0,0 +1,213 @@
+//! Traversals over the shrinks of a tree.
+//!
+//! These expose a tree's structure to custom generators and to tests about
+//! shrinking behaviour, without reaching into `children` directly.
+
+use super::Tree;
+use std::collections::VecDeque;
+
+/// Breadth-first iterator over the shrinks of a tree, with their depth.
+///
+/// Direct shrinks have depth 1. Created by [`Tree::bfs`].
+pub struct Bfs<'a, T> {
+    queue: VecDeque<(usize, &'a Tree<T>)>,
+}
+
+impl<'a, T> Iterator for Bfs<'a, T> {
+    type Item = (usize, &'a Tree<T>);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let (depth, tree) = self.queue.pop_front()?;
+        self.queue
+            .extend(tree.children.iter().map(|child| (depth + 1, child)));
+        Some((depth, tree))
+    }
+}
+
+/// Depth-first (pre-order) iterator over the shrinks of a tree, with their
+/// depth.
+///
+/// Direct shrinks have depth 1. Created by [`Tree::dfs`].
+pub struct Dfs<'a, T> {
+    stack: Vec<(usize, &'a Tree<T>)>,
+}
+
+impl<'a, T> Iterator for Dfs<'a, T> {
+    type Item = (usize, &'a Tree<T>);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let (depth, tree) = self.stack.pop()?;
+        self.stack
+            .extend(tree.children.iter().rev().map(|child| (depth + 1, child)));
+        Some((depth, tree))
+    }
+}
+
+/// What a visitor passed to [`Tree::visit`] wants to do next.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Visit {
+    /// Carry on into this node's shrinks
+    Continue,
+    /// Skip this node's shrinks but carry on with its siblings
+    SkipChildren,
+    /// End the traversal
+    Stop,
+}
+
+impl<T> Tree<T> {
+    /// Iterate breadth-first over the shrinks of this tree, not including
+    /// the tree itself, in the order the runner tries them.
+    pub fn bfs(&self) -> Bfs<'_, T> {
+        Bfs {
+            queue: self.children.iter().map(|child| (1, child)).collect(),
+        }
+    }
+
+    /// Iterate depth-first over the shrinks of this tree, not including the
+    /// tree itself.
+    pub fn dfs(&self) -> Dfs<'_, T> {
+        Dfs {
+            stack: self.children.iter().rev().map(|child| (1, child)).collect(),
+        }
+    }
+
+    /// Visit this tree and its shrinks depth-first, letting the visitor
+    /// prune subtrees or stop early. The root has depth 0.
+    ///
+    /// Returns `false` if the visitor stopped the traversal.
+    pub fn visit<F>(&self, mut visitor: F) -> bool
+    where
+        F: FnMut(&T, usize) -> Visit,
+    {
+        self.visit_from(0, &mut visitor)
+    }
+
+    fn visit_from<F>(&self, depth: usize, visitor: &mut F) -> bool
+    where
+        F: FnMut(&T, usize) -> Visit,
+    {
+        match visitor(&self.value, depth) {
+            Visit::Stop => false,
+            Visit::SkipChildren => true,
+            Visit::Continue => self
+                .children
+                .iter()
+                .all(|child| child.visit_from(depth + 1, visitor)),
+        }
+    }
+
+    /// Number of shrinks at each depth, starting with the direct shrinks.
+    pub fn shrinks_per_depth(&self) -> Vec<usize> {
+        let mut counts = Vec::new();
+        for (depth, _) in self.bfs() {
+            if counts.len() < depth {
+                counts.resize(depth, 0);
+            }
+            counts[depth - 1] += 1;
+        }
+        counts
+    }
+
+    /// Map a function over the tree, and add the shrinks `extra_shrinks`
+    /// suggests for each mapped value after that node's existing shrinks.
+    ///
+    /// The added shrinks have no shrinks of their own. This is the usual way
+    /// to give a mapped generator shrinks the original couldn't express, such
+    /// as trying an empty collection first.
+    pub fn map_with_shrinks<U, F, S>(self, f: F, extra_shrinks: S) -> Tree<U>
+    where
+        F: Fn(T) -> U + Clone,
+        S: Fn(&U) -> Vec<U> + Clone,
+    {
+        let value = f(self.value);
+        let mut children: Vec<Tree<U>> = self
+            .children
+            .into_iter()
+            .map(|child| child.map_with_shrinks(f.clone(), extra_shrinks.clone()))
+            .collect();
+        children.extend(extra_shrinks(&value).into_iter().map(Tree::singleton));
+        Tree::with_children(value, children)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn example() -> Tree<i32> {
+        Tree::with_children(
+            10,
+            vec![
+                Tree::with_children(5, vec![Tree::singleton(2), Tree::singleton(3)]),
+                Tree::with_children(0, vec![Tree::singleton(1)]),
+            ],
+        )
+    }
+
+    #[test]
+    fn test_bfs_and_dfs_orders() {
+        let tree = example();
+
+        let bfs: Vec<_> = tree.bfs().map(|(depth, t)| (depth, t.value)).collect();
+        assert_eq!(bfs, vec![(1, 5), (1, 0), (2, 2), (2, 3), (2, 1)]);
+        assert_eq!(
+            tree.bfs().map(|(_, t)| &t.value).collect::<Vec<_>>(),
+            tree.shrinks()
+        );
+
+        let dfs: Vec<_> = tree.dfs().map(|(depth, t)| (depth, t.value)).collect();
+        assert_eq!(dfs, vec![(1, 5), (2, 2), (2, 3), (1, 0), (2, 1)]);
+
+        assert_eq!(tree.shrinks_per_depth(), vec![2, 3]);
+        assert_eq!(Tree::singleton(1).shrinks_per_depth(), Vec::<usize>::new());
+    }
+
+    #[test]
+    fn test_visit_prunes_and_stops() {
+        let tree = example();
+
+        let mut seen = Vec::new();
+        let finished = tree.visit(|&value, _depth| {
+            seen.push(value);
+            if value == 5 {
+                Visit::SkipChildren
+            } else {
+                Visit::Continue
+            }
+        });
+        assert!(finished);
+        assert_eq!(seen, vec![10, 5, 0, 1]);
+
+        let mut seen = Vec::new();
+        let finished = tree.visit(|&value, depth| {
+            seen.push((value, depth));
+            if value == 2 {
+                Visit::Stop
+            } else {
+                Visit::Continue
+            }
+        });
+        assert!(!finished);
+        assert_eq!(seen, vec![(10, 0), (5, 1), (2, 2)]);
+    }
+
+    #[test]
+    fn test_map_with_shrinks() {
+        let tree = Tree::with_children(3, vec![Tree::singleton(1)]);
+        let mapped = tree.map_with_shrinks(
+            |n: i32| vec![n; n as usize],
+            |xs: &Vec<i32>| if xs.is_empty() { vec![] } else { vec![vec![]] },
+        );
+
+        assert_eq!(
+            mapped,
+            Tree::with_children(
+                vec![3, 3, 3],
+                vec![
+                    Tree::with_children(vec![1], vec![Tree::singleton(vec![])]),
+                    Tree::singleton(vec![]),
+                ]
+            )
+        );
+    }
+}
@@ -0,0 +1,360 @@
+//! Generators that obey, or break, validation rules.
+//!
+//! Types validated with the `validator` or `garde` crates describe their
+//! rules in attributes: `email`, `length(min = 1, max = 64)`,
+//! `range(min = 0, max = 150)`. The `Generate` derive reads those attributes
+//! and builds its generators from the rules here, so generated values pass
+//! validation. Each rule can also produce generators that break it, which
+//! the derive's `generate_invalid` uses to test that validation rejects
+//! what it should.
+
+use crate::data::Range;
+use crate::gen::Gen;
+use std::fmt::Debug;
+
+/// How far past the minimum lengths go when no maximum is given, and how
+/// far past the maximum lengths that break it go.
+const LENGTH_SPREAD: usize = 32;
+
+/// The domain of emails generated under length rules.
+const EMAIL_DOMAIN: &str = "@example.com";
+
+/// Length bounds, inclusive.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct Length {
+    /// Shortest allowed length
+    pub min: Option<usize>,
+    /// Longest allowed length
+    pub max: Option<usize>,
+}
+
+impl Length {
+    fn bounds(&self) -> (usize, usize) {
+        let min = self.min.unwrap_or(0);
+        (min, self.max.unwrap_or(min + LENGTH_SPREAD))
+    }
+
+    /// Ranges of lengths that break exactly one bound.
+    fn violations(&self) -> Vec<(usize, usize)> {
+        let mut ranges = Vec::new();
+        if let Some(min) = self.min.filter(|&min| min > 0) {
+            ranges.push((0, min - 1));
+        }
+        if let Some(max) = self.max {
+            ranges.push((max + 1, max + LENGTH_SPREAD));
+        }
+        ranges
+    }
+}
+
+/// Rules for a string field.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct TextRules {
+    /// The string must be an email address
+    pub email: bool,
+    /// Bounds on the length in characters
+    pub length: Length,
+}
+
+impl TextRules {
+    /// Strings that satisfy every rule.
+    ///
+    /// # Panics
+    /// Panics if no email address fits the length bounds.
+    pub fn valid(&self) -> Gen<String> {
+        match (self.email, self.length == Length::default()) {
+            (true, true) => Gen::<String>::email_address(),
+            (true, false) => {
+                let (min, max) = self.length.bounds();
+                email_with_length(min, max).unwrap_or_else(|| {
+                    panic!("no email address is between {min} and {max} characters long")
+                })
+            }
+            (false, _) => {
+                let (min, max) = self.length.bounds();
+                Gen::<String>::printable_with_range(Range::linear(min, max))
+            }
+        }
+    }
+
+    /// Generators of strings that each break one rule and satisfy the rest.
+    ///
+    /// Rules that can't be broken alone, such as a short minimum length on
+    /// an email address, produce no generator.
+    pub fn violations(&self) -> Vec<Gen<String>> {
+        let mut gens: Vec<Gen<String>> = self
+            .length
+            .violations()
+            .into_iter()
+            .filter_map(|(min, max)| {
+                if self.email {
+                    email_with_length(min, max)
+                } else {
+                    Some(Gen::<String>::printable_with_range(Range::linear(min, max)))
+                }
+            })
+            .collect();
+
+        if self.email {
+            // Letters alone are never an email address
+            let (min, max) = self.length.bounds();
+            if max > 0 {
+                gens.push(Gen::<String>::alpha_with_range(Range::linear(
+                    min.max(1),
+                    max,
+                )));
+            }
+        }
+        gens
+    }
+}
+
+/// Emails of `min..=max` characters, if any are long enough to exist.
+fn email_with_length(min: usize, max: usize) -> Option<Gen<String>> {
+    let shortest = EMAIL_DOMAIN.len() + 1;
+    if max < shortest {
+        return None;
+    }
+    let local = Range::linear(
+        min.max(shortest) - EMAIL_DOMAIN.len(),
+        max - EMAIL_DOMAIN.len(),
+    );
+    Some(
+        Gen::<String>::alpha_with_range(local)
+            .map(|local| format!("{}{EMAIL_DOMAIN}", local.to_lowercase())),
+    )
+}
+
+/// Rules for a collection field.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct CollectionRules {
+    /// Bounds on the number of elements
+    pub length: Length,
+}
+
+impl CollectionRules {
+    /// Vectors of `element` that satisfy the rules.
+    pub fn valid<T: Clone + 'static>(&self, element: Gen<T>) -> Gen<Vec<T>> {
+        let (min, max) = self.length.bounds();
+        Gen::vec_with_range(Range::linear(min, max), element)
+    }
+
+    /// Generators of vectors that each break one rule, with elements from
+    /// `element`.
+    pub fn violations<T, F>(&self, element: F) -> Vec<Gen<Vec<T>>>
+    where
+        T: Clone + 'static,
+        F: Fn() -> Gen<T>,
+    {
+        self.length
+            .violations()
+            .into_iter()
+            .map(|(min, max)| Gen::vec_with_range(Range::linear(min, max), element()))
+            .collect()
+    }
+}
+
+/// Numbers that range rules apply to.
+pub trait Bounded: Copy + PartialOrd + Debug + 'static {
+    /// The smallest value of the type
+    const MIN: Self;
+    /// The largest value of the type
+    const MAX: Self;
+
+    /// Values in `min..=max`.
+    fn between(min: Self, max: Self) -> Gen<Self>;
+
+    /// The largest value below this one, if there is one.
+    fn below(self) -> Option<Self>;
+
+    /// The smallest value above this one, if there is one.
+    fn above(self) -> Option<Self>;
+}
+
+macro_rules! impl_bounded_int {
+    ($($type:ty => $method:ident),*) => {
+        $(
+            impl Bounded for $type {
+                const MIN: Self = <$type>::MIN;
+                const MAX: Self = <$type>::MAX;
+
+                fn between(min: Self, max: Self) -> Gen<Self> {
+                    Gen::<$type>::$method(min, max)
+                }
+
+                fn below(self) -> Option<Self> {
+                    self.checked_sub(1)
+                }
+
+                fn above(self) -> Option<Self> {
+                    self.checked_add(1)
+                }
+            }
+        )*
+    };
+}
+
+impl_bounded_int!(
+    i8 => i8_range,
+    i16 => i16_range,
+    i32 => int_range,
+    i64 => i64_range,
+    isize => isize_range,
+    u8 => u8_range,
+    u16 => u16_range,
+    u32 => u32_range,
+    u64 => u64_range,
+    usize => usize_range
+);
+
+macro_rules! impl_bounded_float {
+    ($($type:ty),*) => {
+        $(
+            impl Bounded for $type {
+                const MIN: Self = <$type>::MIN;
+                const MAX: Self = <$type>::MAX;
+
+                fn between(min: Self, max: Self) -> Gen<Self> {
+                    // Interpolating rather than adding a scaled span keeps
+                    // wide ranges like MIN..=MAX finite
+                    Gen::<f64>::unit().map(move |t| {
+                        let t = t as $type;
+                        (min * (1.0 - t) + max * t).clamp(min, max)
+                    })
+                }
+
+                fn below(self) -> Option<Self> {
+                    (self > <$type>::MIN).then(|| self.next_down())
+                }
+
+                fn above(self) -> Option<Self> {
+                    (self < <$type>::MAX).then(|| self.next_up())
+                }
+            }
+        )*
+    };
+}
+
+impl_bounded_float!(f32, f64);
+
+/// Rules for a numeric field.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct NumberRules<T> {
+    /// Smallest allowed value
+    pub min: Option<T>,
+    /// Largest allowed value
+    pub max: Option<T>,
+}
+
+impl<T: Bounded> NumberRules<T> {
+    /// Numbers that satisfy the rules.
+    pub fn valid(&self) -> Gen<T> {
+        T::between(self.min.unwrap_or(T::MIN), self.max.unwrap_or(T::MAX))
+    }
+
+    /// Generators of numbers that each break one bound.
+    pub fn violations(&self) -> Vec<Gen<T>> {
+        let below = self
+            .min
+            .and_then(T::below)
+            .map(|below| T::between(T::MIN, below));
+        let above = self
+            .max
+            .and_then(T::above)
+            .map(|above| T::between(above, T::MAX));
+        below.into_iter().chain(above).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data::{Seed, Size};
+
+    fn samples<T>(gen: &Gen<T>) -> Vec<T> {
+        (0..200)
+            .map(|n| gen.generate(Size::new(30), Seed::from_u64(n)).value)
+            .collect()
+    }
+
+    fn is_email(s: &str) -> bool {
+        matches!(s.split_once('@'), Some((local, domain)) if !local.is_empty() && domain.contains('.'))
+    }
+
+    #[test]
+    fn test_text_rules() {
+        let rules = TextRules {
+            email: true,
+            length: Length {
+                min: Some(16),
+                max: Some(20),
+            },
+        };
+        for email in samples(&rules.valid()) {
+            assert!(
+                is_email(&email) && (16..=20).contains(&email.len()),
+                "{email}"
+            );
+        }
+
+        // One generator for each bound and one for the format
+        let violations = rules.violations();
+        assert_eq!(violations.len(), 3);
+        for (index, gen) in violations.iter().enumerate() {
+            for value in samples(gen) {
+                let broken = [value.len() < 16, value.len() > 20, !is_email(&value)];
+                assert_eq!(broken.iter().filter(|&&b| b).count(), 1, "{value}");
+                assert!(broken[index], "{value}");
+            }
+        }
+
+        // Too short for any email to break the minimum alone
+        let short = TextRules {
+            email: true,
+            length: Length {
+                min: Some(3),
+                max: None,
+            },
+        };
+        assert_eq!(short.violations().len(), 1);
+    }
+
+    #[test]
+    fn test_collection_rules() {
+        let rules = CollectionRules {
+            length: Length {
+                min: Some(2),
+                max: Some(4),
+            },
+        };
+        assert!(samples(&rules.valid(Gen::bool()))
+            .iter()
+            .all(|xs| (2..=4).contains(&xs.len())));
+
+        let violations = rules.violations(Gen::bool);
+        assert!(samples(&violations[0]).iter().all(|xs| xs.len() < 2));
+        assert!(samples(&violations[1]).iter().all(|xs| xs.len() > 4));
+    }
+
+    #[test]
+    fn test_number_rules() {
+        let rules = NumberRules {
+            min: Some(18u8),
+            max: Some(u8::MAX),
+        };
+        assert!(samples(&rules.valid()).iter().all(|&n| n >= 18));
+        // Nothing is above u8::MAX
+        let violations = rules.violations();
+        assert_eq!(violations.len(), 1);
+        assert!(samples(&violations[0]).iter().all(|&n| n < 18));
+
+        let floats = NumberRules {
+            min: Some(0.0),
+            max: None,
+        };
+        assert!(samples(&floats.valid())
+            .iter()
+            .all(|x: &f64| x.is_finite() && *x >= 0.0));
+        assert!(samples(&floats.violations()[0]).iter().all(|&x| x < 0.0));
+    }
+}
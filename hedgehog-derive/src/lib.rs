@@ -6,6 +6,7 @@
 use proc_macro::TokenStream;
 use proc_macro2::TokenStream as TokenStream2;
 use quote::quote;
+use syn::spanned::Spanned;
 use syn::{parse_macro_input, Data, DeriveInput, Fields, Type};
 
 /// Derive macro for automatically generating `Gen<T>` implementations.
@@ -29,7 +30,47 @@ use syn::{parse_macro_input, Data, DeriveInput, Fields, Type};
 /// // Now you can use User::generate() automatically
 /// let user_gen = User::generate();
 /// ```
-#[proc_macro_derive(Generate)]
+///
+/// The type also gets a `DefaultGen` implementation, so it can be used in
+/// `for_all_default` and as a field of other derived types. Fields of
+/// other types with no built-in mapping use their `DefaultGen`, and a
+/// `#[generate(...)]` attribute overrides the generator for one field:
+///
+/// ```rust,ignore
+/// #[derive(Generate, Debug, Clone)]
+/// struct Order {
+///     #[generate(Gen::u32_range(1, 10))]
+///     quantity: u32,
+///     items: Vec<String>,
+/// }
+/// ```
+///
+/// Fields with `#[validate(...)]` or `#[garde(...)]` attributes get
+/// generators obeying their `email`, `length` and `range` rules, and
+/// structs with such fields get `generate_invalid()`, whose values break
+/// exactly one rule.
+///
+/// On an enum whose variants have no fields, `#[generate(small_values)]`
+/// also implements `SmallValues`, listing every variant, for
+/// `with_small_cases`.
+///
+/// On the type itself, `#[generate(roundtrip = "json")]` also emits a test
+/// checking that generated values survive a round trip through
+/// `serde_json`. The type must implement `Serialize`, `Deserialize`,
+/// `PartialEq` and `Debug`, have no generic parameters, and be declared
+/// in a crate depending on `serde_json`:
+///
+/// ```rust,ignore
+/// #[derive(Generate, Serialize, Deserialize, Debug, Clone, PartialEq)]
+/// #[generate(roundtrip = "json")]
+/// struct Settings {
+///     name: String,
+///     retries: u8,
+/// }
+///
+/// // Emits #[test] fn settings_roundtrips_through_json()
+/// ```
+#[proc_macro_derive(Generate, attributes(generate, validate, garde))]
 pub fn derive_generate(input: TokenStream) -> TokenStream {
     let input = parse_macro_input!(input as DeriveInput);
 
@@ -45,9 +86,9 @@ fn generate_impl(input: &DeriveInput) -> Result<TokenStream2, syn::Error> {
     let generics = &input.generics;
     let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
 
-    let generator_impl = match &input.data {
+    let (generator_impl, invalid_impl) = match &input.data {
         Data::Struct(data) => generate_struct_impl(data)?,
-        Data::Enum(data) => generate_enum_impl(data)?,
+        Data::Enum(data) => (generate_enum_impl(data)?, None),
         Data::Union(_) => {
             return Err(syn::Error::new_spanned(
                 input,
@@ -56,202 +97,289 @@ fn generate_impl(input: &DeriveInput) -> Result<TokenStream2, syn::Error> {
         }
     };
 
+    let options = TypeOptions::parse(input)?;
+    let small_values_impl = match options.small_values {
+        Some(span) => Some(small_values_impl(input, span)?),
+        None => None,
+    };
+    let roundtrip_test = match options.roundtrip {
+        Some(format) => Some(roundtrip_test(input, &format)?),
+        None => None,
+    };
+
+    let invalid_fn = invalid_impl.map(|invalid_impl| {
+        quote! {
+            /// Generate values that break exactly one validation rule.
+            pub fn generate_invalid() -> hedgehog::Gen<Self> {
+                #invalid_impl
+            }
+        }
+    });
+
     let expanded = quote! {
         impl #impl_generics #name #ty_generics #where_clause {
             /// Generate a generator for this type.
             pub fn generate() -> hedgehog::Gen<Self> {
                 #generator_impl
             }
+
+            #invalid_fn
         }
+
+        impl #impl_generics hedgehog::DefaultGen for #name #ty_generics #where_clause {
+            fn default_gen() -> hedgehog::Gen<Self> {
+                Self::generate()
+            }
+        }
+
+        #small_values_impl
+
+        #roundtrip_test
     };
 
     Ok(expanded)
 }
 
-/// Generate implementation for structs.
-fn generate_struct_impl(data: &syn::DataStruct) -> Result<TokenStream2, syn::Error> {
-    match &data.fields {
-        Fields::Named(fields) => {
-            let field_data: Vec<_> = fields
-                .named
-                .iter()
-                .enumerate()
-                .map(|(i, field)| {
-                    let field_name = field.ident.as_ref().unwrap();
-                    let field_var =
-                        syn::Ident::new(&format!("field_{i}"), proc_macro2::Span::call_site());
-                    let generator = generate_field_generator(&field.ty)?;
-                    Ok((field_name.clone(), field_var, generator))
-                })
-                .collect::<Result<Vec<_>, syn::Error>>()?;
+/// Options from `#[generate(...)]` on the type itself.
+struct TypeOptions {
+    /// Format of the round-trip test to emit, from `roundtrip = "json"`
+    roundtrip: Option<syn::LitStr>,
+    /// Where `small_values` was asked for
+    small_values: Option<proc_macro2::Span>,
+}
 
-            let field_bindings = field_data.iter().map(|(_, var, gen)| {
-                quote! {
-                    let (field_seed, next_seed) = current_seed.split();
-                    current_seed = next_seed;
-                    let #var = (#gen).generate(size, field_seed).outcome().clone();
+impl TypeOptions {
+    fn parse(input: &DeriveInput) -> Result<Self, syn::Error> {
+        let mut options = TypeOptions {
+            roundtrip: None,
+            small_values: None,
+        };
+        for attr in input
+            .attrs
+            .iter()
+            .filter(|attr| attr.path().is_ident("generate"))
+        {
+            attr.parse_nested_meta(|meta| {
+                if meta.path.is_ident("small_values") {
+                    options.small_values = Some(meta.path.span());
+                    return Ok(());
                 }
-            });
-
-            let field_assignments = field_data.iter().map(|(name, var, _)| {
-                quote! {
-                    #name: #var
+                if !meta.path.is_ident("roundtrip") {
+                    return Err(meta.error("expected `roundtrip = \"json\"` or `small_values`"));
+                }
+                let value: syn::LitStr = meta.value()?.parse()?;
+                if value.value() != "json" {
+                    return Err(syn::Error::new_spanned(
+                        &value,
+                        "unsupported round-trip format; expected \"json\"",
+                    ));
                 }
-            });
+                options.roundtrip = Some(value);
+                Ok(())
+            })?;
+        }
+        Ok(options)
+    }
+}
 
-            Ok(quote! {
-                hedgehog::Gen::new(|size, seed| {
-                    use hedgehog::{Tree, Seed};
+/// `SmallValues` for a fieldless enum, listing every variant.
+fn small_values_impl(
+    input: &DeriveInput,
+    span: proc_macro2::Span,
+) -> Result<TokenStream2, syn::Error> {
+    let variants = match &input.data {
+        Data::Enum(data)
+            if data
+                .variants
+                .iter()
+                .all(|variant| matches!(variant.fields, Fields::Unit)) =>
+        {
+            data.variants.iter().map(|variant| &variant.ident)
+        }
+        _ => {
+            return Err(syn::Error::new(
+                span,
+                "`small_values` needs an enum whose variants have no fields",
+            ))
+        }
+    };
+    let name = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+    Ok(quote! {
+        impl #impl_generics hedgehog::SmallValues for #name #ty_generics #where_clause {
+            fn small_values() -> Vec<Self> {
+                vec![#(Self::#variants),*]
+            }
+        }
+    })
+}
 
-                    let mut current_seed = seed;
-                    #(#field_bindings)*
+/// The round-trip test asked for by a `#[generate(roundtrip = "...")]`
+/// attribute on the type, if it has one.
+fn roundtrip_test(input: &DeriveInput, format: &syn::LitStr) -> Result<TokenStream2, syn::Error> {
+    if !input.generics.params.is_empty() {
+        return Err(syn::Error::new_spanned(
+            &input.generics,
+            "round-trip tests need a type without generic parameters",
+        ));
+    }
 
-                    let value = Self {
-                        #(#field_assignments),*
-                    };
+    let name = &input.ident;
+    let test_name = syn::Ident::new(
+        &format!(
+            "{}_roundtrips_through_{}",
+            snake_case(&name.to_string()),
+            format.value()
+        ),
+        name.span(),
+    );
+    let test_name_str = test_name.to_string();
 
-                    Tree::singleton(value)
-                })
-            })
+    Ok(quote! {
+        #[cfg(test)]
+        #[test]
+        fn #test_name() {
+            let prop = hedgehog::roundtrip(
+                #name::generate(),
+                |value: &#name| {
+                    ::serde_json::to_string(value).expect("serializing to JSON failed")
+                },
+                |json: &String| ::serde_json::from_str::<#name>(json),
+            );
+            let result = prop.run_with_context(
+                &hedgehog::Config::from_env().unwrap_or_else(|error| panic!("{error}")),
+                Some(#test_name_str),
+                Some(module_path!()),
+            );
+            if let hedgehog::TestResult::Fail { .. } | hedgehog::TestResult::Discard { .. } = result {
+                panic!("{result}");
+            }
         }
-        Fields::Unnamed(fields) => {
-            let field_data: Vec<_> = fields
-                .unnamed
-                .iter()
-                .enumerate()
-                .map(|(i, field)| {
-                    let field_var =
-                        syn::Ident::new(&format!("field_{i}"), proc_macro2::Span::call_site());
-                    let generator = generate_field_generator(&field.ty)?;
-                    Ok((field_var, generator))
-                })
-                .collect::<Result<Vec<_>, syn::Error>>()?;
+    })
+}
 
-            let field_bindings = field_data.iter().map(|(var, gen)| {
-                quote! {
-                    let (field_seed, next_seed) = current_seed.split();
-                    current_seed = next_seed;
-                    let #var = (#gen).generate(size, field_seed).outcome().clone();
-                }
-            });
+/// `name` in snake case: `HttpRequest` becomes `http_request`.
+fn snake_case(name: &str) -> String {
+    let mut snake = String::new();
+    let chars: Vec<char> = name.chars().collect();
+    for (i, &c) in chars.iter().enumerate() {
+        if c.is_uppercase() {
+            let after_lower = i > 0 && chars[i - 1].is_lowercase();
+            let before_lower = i > 0 && chars.get(i + 1).is_some_and(|next| next.is_lowercase());
+            if after_lower || (before_lower && chars[i - 1].is_uppercase()) {
+                snake.push('_');
+            }
+            snake.extend(c.to_lowercase());
+        } else {
+            snake.push(c);
+        }
+    }
+    snake
+}
 
-            let field_vars = field_data.iter().map(|(var, _)| var);
+/// Generate implementation for structs, and for structs with validation
+/// rules, the implementation of `generate_invalid`.
+fn generate_struct_impl(
+    data: &syn::DataStruct,
+) -> Result<(TokenStream2, Option<TokenStream2>), syn::Error> {
+    let generators = data
+        .fields
+        .iter()
+        .map(field_generator)
+        .collect::<Result<Vec<_>, syn::Error>>()?;
 
-            Ok(quote! {
-                hedgehog::Gen::new(|size, seed| {
-                    use hedgehog::{Tree, Seed};
+    let valid: Vec<_> = generators.iter().map(|gen| gen.valid.clone()).collect();
+    let generator = construct(&data.fields, quote! { Self }, &valid);
 
-                    let mut current_seed = seed;
-                    #(#field_bindings)*
+    // One generator for each way of breaking one rule of one field, with
+    // every other field valid
+    let violations: Vec<_> = generators
+        .iter()
+        .enumerate()
+        .filter_map(|(i, gen)| {
+            let field_violations = gen.violations.as_ref()?;
+            let mut gens = valid.clone();
+            gens[i] = quote! { violation };
+            let generator = construct(&data.fields, quote! { Self }, &gens);
+            Some(quote! {
+                for violation in #field_violations {
+                    generators.push(#generator);
+                }
+            })
+        })
+        .collect();
+
+    let invalid = (!violations.is_empty()).then(|| {
+        quote! {
+            let mut generators: Vec<hedgehog::Gen<Self>> = Vec::new();
+            #(#violations)*
+            hedgehog::Gen::one_of(generators)
+                .expect("no validation rule of this type can be broken on its own")
+        }
+    });
 
-                    let value = Self(#(#field_vars),*);
+    Ok((generator, invalid))
+}
 
-                    Tree::singleton(value)
-                })
-            })
+/// A generator that builds a value with `constructor` from fields
+/// generated by `generators`, one for each field in order.
+fn construct(
+    fields: &Fields,
+    constructor: TokenStream2,
+    generators: &[TokenStream2],
+) -> TokenStream2 {
+    let vars: Vec<_> = (0..generators.len())
+        .map(|i| syn::Ident::new(&format!("field_{i}"), proc_macro2::Span::call_site()))
+        .collect();
+
+    let field_bindings = vars.iter().zip(generators).map(|(var, gen)| {
+        quote! {
+            let (field_seed, next_seed) = current_seed.split();
+            current_seed = next_seed;
+            let #var = (#gen).generate(size, field_seed).outcome().clone();
         }
-        Fields::Unit => Ok(quote! {
-            hedgehog::Gen::constant(Self)
-        }),
+    });
+
+    let value = match fields {
+        Fields::Named(fields) => {
+            let names = fields.named.iter().map(|field| &field.ident);
+            quote! { #constructor { #(#names: #vars),* } }
+        }
+        Fields::Unnamed(_) => quote! { #constructor(#(#vars),*) },
+        Fields::Unit => return quote! { hedgehog::Gen::constant(#constructor) },
+    };
+
+    quote! {
+        hedgehog::Gen::new(move |size, seed| {
+            use hedgehog::{Tree, Seed};
+
+            let mut current_seed = seed;
+            #(#field_bindings)*
+
+            let value = #value;
+
+            Tree::singleton(value)
+        })
     }
 }
 
 /// Generate implementation for enums.
 fn generate_enum_impl(data: &syn::DataEnum) -> Result<TokenStream2, syn::Error> {
-    let variants: Vec<_> = data
+    let variants = data
         .variants
         .iter()
         .map(|variant| {
             let variant_name = &variant.ident;
-
-            match &variant.fields {
-                Fields::Named(fields) => {
-                    let field_data: Vec<_> = fields
-                        .named
-                        .iter()
-                        .enumerate()
-                        .map(|(i, field)| {
-                            let field_name = field.ident.as_ref().unwrap();
-                            let field_var = syn::Ident::new(
-                                &format!("field_{i}"),
-                                proc_macro2::Span::call_site(),
-                            );
-                            let generator = generate_field_generator(&field.ty)?;
-                            Ok((field_name.clone(), field_var, generator))
-                        })
-                        .collect::<Result<Vec<_>, syn::Error>>()?;
-
-                    let field_bindings = field_data.iter().map(|(_, var, gen)| {
-                        quote! {
-                            let (field_seed, next_seed) = current_seed.split();
-                            current_seed = next_seed;
-                            let #var = (#gen).generate(size, field_seed).outcome().clone();
-                        }
-                    });
-
-                    let field_assignments = field_data.iter().map(|(name, var, _)| {
-                        quote! {
-                            #name: #var
-                        }
-                    });
-
-                    Ok(quote! {
-                        hedgehog::Gen::new(|size, seed| {
-                            use hedgehog::{Tree, Seed};
-
-                            let mut current_seed = seed;
-                            #(#field_bindings)*
-
-                            let value = Self::#variant_name {
-                                #(#field_assignments),*
-                            };
-
-                            Tree::singleton(value)
-                        })
-                    })
-                }
-                Fields::Unnamed(fields) => {
-                    let field_data: Vec<_> = fields
-                        .unnamed
-                        .iter()
-                        .enumerate()
-                        .map(|(i, field)| {
-                            let field_var = syn::Ident::new(
-                                &format!("field_{i}"),
-                                proc_macro2::Span::call_site(),
-                            );
-                            let generator = generate_field_generator(&field.ty)?;
-                            Ok((field_var, generator))
-                        })
-                        .collect::<Result<Vec<_>, syn::Error>>()?;
-
-                    let field_bindings = field_data.iter().map(|(var, gen)| {
-                        quote! {
-                            let (field_seed, next_seed) = current_seed.split();
-                            current_seed = next_seed;
-                            let #var = (#gen).generate(size, field_seed).outcome().clone();
-                        }
-                    });
-
-                    let field_vars = field_data.iter().map(|(var, _)| var);
-
-                    Ok(quote! {
-                        hedgehog::Gen::new(|size, seed| {
-                            use hedgehog::{Tree, Seed};
-
-                            let mut current_seed = seed;
-                            #(#field_bindings)*
-
-                            let value = Self::#variant_name(#(#field_vars),*);
-
-                            Tree::singleton(value)
-                        })
-                    })
-                }
-                Fields::Unit => Ok(quote! {
-                    hedgehog::Gen::constant(Self::#variant_name)
-                }),
-            }
+            let generators = variant
+                .fields
+                .iter()
+                .map(|field| Ok(field_generator(field)?.valid))
+                .collect::<Result<Vec<_>, syn::Error>>()?;
+            Ok(construct(
+                &variant.fields,
+                quote! { Self::#variant_name },
+                &generators,
+            ))
         })
         .collect::<Result<Vec<_>, syn::Error>>()?;
 
@@ -259,9 +387,248 @@ fn generate_enum_impl(data: &syn::DataEnum) -> Result<TokenStream2, syn::Error>
         hedgehog::Gen::one_of(vec![
             #(#variants),*
         ])
+        .expect("an enum deriving Generate needs at least one variant")
     })
 }
 
+/// Generators for a field: one for valid values, and for fields with
+/// validation rules, an expression for a `Vec` of generators that each
+/// break one rule.
+struct FieldGenerator {
+    valid: TokenStream2,
+    violations: Option<TokenStream2>,
+}
+
+/// The generators for a field: the expression in its `#[generate(...)]`
+/// attribute if it has one, then ones obeying its `#[validate(...)]` or
+/// `#[garde(...)]` rules, otherwise one based on its type.
+fn field_generator(field: &syn::Field) -> Result<FieldGenerator, syn::Error> {
+    if let Some(attr) = field
+        .attrs
+        .iter()
+        .find(|attr| attr.path().is_ident("generate"))
+    {
+        let generator: syn::Expr = attr.parse_args()?;
+        return Ok(FieldGenerator {
+            valid: quote! { #generator },
+            violations: None,
+        });
+    }
+
+    let rules = Rules::parse(&field.attrs)?;
+    if rules.is_empty() {
+        return Ok(FieldGenerator {
+            valid: generate_field_generator(&field.ty)?,
+            violations: None,
+        });
+    }
+    rules.generators(&field.ty)
+}
+
+/// Validation rules read from `#[validate(...)]` and `#[garde(...)]`.
+#[derive(Default)]
+struct Rules {
+    email: bool,
+    length: Option<(Option<syn::Expr>, Option<syn::Expr>)>,
+    range: Option<(Option<syn::Expr>, Option<syn::Expr>)>,
+}
+
+impl Rules {
+    fn parse(attrs: &[syn::Attribute]) -> Result<Self, syn::Error> {
+        let mut rules = Rules::default();
+        for attr in attrs {
+            if !(attr.path().is_ident("validate") || attr.path().is_ident("garde")) {
+                continue;
+            }
+            attr.parse_nested_meta(|meta| {
+                if meta.path.is_ident("email") {
+                    rules.email = true;
+                } else if meta.path.is_ident("length") {
+                    rules.length = Some(Self::bounds(&meta, &["equal"])?);
+                } else if meta.path.is_ident("range") {
+                    rules.range = Some(Self::bounds(&meta, &[])?);
+                } else if ["dive", "nested", "skip"]
+                    .iter()
+                    .any(|rule| meta.path.is_ident(rule))
+                {
+                    // Rules about other types or none at all
+                } else {
+                    return Err(meta.error(
+                        "Generate supports the email, length and range validation rules; \
+                         use #[generate(...)] to give this field a generator",
+                    ));
+                }
+                Ok(())
+            })?;
+        }
+        Ok(rules)
+    }
+
+    /// The `min` and `max` of a rule, and `equal` if it is in `exact`.
+    fn bounds(
+        meta: &syn::meta::ParseNestedMeta,
+        exact: &[&str],
+    ) -> Result<(Option<syn::Expr>, Option<syn::Expr>), syn::Error> {
+        let (mut min, mut max) = (None, None);
+        meta.parse_nested_meta(|bound| {
+            if bound.path.is_ident("min") {
+                min = Some(bound.value()?.parse()?);
+            } else if bound.path.is_ident("max") {
+                max = Some(bound.value()?.parse()?);
+            } else if exact.iter().any(|key| bound.path.is_ident(key)) {
+                let value: syn::Expr = bound.value()?.parse()?;
+                min = Some(value.clone());
+                max = Some(value);
+            } else if bound.path.is_ident("chars") || bound.path.is_ident("bytes") {
+                // Generated strings are ASCII, so both count the same
+            } else {
+                return Err(bound.error("expected min, max or equal"));
+            }
+            Ok(())
+        })?;
+        Ok((min, max))
+    }
+
+    fn is_empty(&self) -> bool {
+        !self.email && self.length.is_none() && self.range.is_none()
+    }
+
+    fn generators(&self, field_type: &Type) -> Result<FieldGenerator, syn::Error> {
+        let (name, argument) = match field_type {
+            Type::Path(type_path) => {
+                let segment = type_path.path.segments.last().ok_or_else(|| {
+                    syn::Error::new_spanned(
+                        field_type,
+                        "Unable to generate generator for this type",
+                    )
+                })?;
+                (segment.ident.to_string(), first_type_argument(segment))
+            }
+            _ => (String::new(), None),
+        };
+
+        let unsupported = |rule: &str| {
+            Err(syn::Error::new_spanned(
+                field_type,
+                format!("the {rule} rule is not supported on fields of this type"),
+            ))
+        };
+        let bound = |bound: &Option<syn::Expr>, ty: TokenStream2| match bound {
+            Some(bound) => quote! { Some((#bound) as #ty) },
+            None => quote! { None },
+        };
+        let length = {
+            let (min, max) = self.length.clone().unwrap_or_default();
+            let (min, max) = (bound(&min, quote! { usize }), bound(&max, quote! { usize }));
+            quote! { hedgehog::validation::Length { min: #min, max: #max } }
+        };
+
+        let (valid, violations) = match (name.as_str(), argument) {
+            ("String", _) => {
+                if self.range.is_some() {
+                    return unsupported("range");
+                }
+                let email = self.email;
+                let rules = quote! {
+                    hedgehog::validation::TextRules { email: #email, length: #length }
+                };
+                (
+                    quote! { (#rules).valid() },
+                    quote! { (#rules).violations() },
+                )
+            }
+            (wrapper, Some(argument)) if is_string_like(wrapper, Some(&argument)) => {
+                if self.range.is_some() {
+                    return unsupported("range");
+                }
+                // Checked as a String, then converted
+                let email = self.email;
+                let rules = quote! {
+                    hedgehog::validation::TextRules { email: #email, length: #length }
+                };
+                (
+                    quote! { hedgehog::Gen::<#field_type>::from_strings((#rules).valid()) },
+                    quote! {
+                        (#rules)
+                            .violations()
+                            .into_iter()
+                            .map(hedgehog::Gen::<#field_type>::from_strings)
+                            .collect::<Vec<_>>()
+                    },
+                )
+            }
+            ("Vec", Some(element)) => {
+                if self.email {
+                    return unsupported("email");
+                }
+                if self.range.is_some() {
+                    return unsupported("range");
+                }
+                let element = generate_field_generator(&element)?;
+                let rules = quote! { hedgehog::validation::CollectionRules { length: #length } };
+                (
+                    quote! { (#rules).valid(#element) },
+                    quote! { (#rules).violations(|| #element) },
+                )
+            }
+            (
+                "i8" | "i16" | "i32" | "i64" | "isize" | "u8" | "u16" | "u32" | "u64" | "usize"
+                | "f32" | "f64",
+                _,
+            ) => {
+                if self.email {
+                    return unsupported("email");
+                }
+                if self.length.is_some() {
+                    return unsupported("length");
+                }
+                let (min, max) = self.range.clone().unwrap_or_default();
+                let (min, max) = (
+                    bound(&min, quote! { #field_type }),
+                    bound(&max, quote! { #field_type }),
+                );
+                let rules = quote! {
+                    hedgehog::validation::NumberRules::<#field_type> { min: #min, max: #max }
+                };
+                (
+                    quote! { (#rules).valid() },
+                    quote! { (#rules).violations() },
+                )
+            }
+            _ => {
+                return Err(syn::Error::new_spanned(
+                    field_type,
+                    "validation rules are supported on string, Vec and number fields",
+                ))
+            }
+        };
+
+        Ok(FieldGenerator {
+            valid,
+            violations: Some(violations),
+        })
+    }
+}
+
+/// The first type argument of a path segment, such as `T` in `Vec<T>` or
+/// `str` in `Cow<'static, str>`.
+fn first_type_argument(segment: &syn::PathSegment) -> Option<Type> {
+    match &segment.arguments {
+        syn::PathArguments::AngleBracketed(args) => args.args.iter().find_map(|arg| match arg {
+            syn::GenericArgument::Type(ty) => Some(ty.clone()),
+            _ => None,
+        }),
+        _ => None,
+    }
+}
+
+/// Whether a type is one of the `str` wrappers with string generators:
+/// `Box<str>`, `Rc<str>`, `Arc<str>` or `Cow<'static, str>`.
+fn is_string_like(wrapper: &str, argument: Option<&Type>) -> bool {
+    matches!(wrapper, "Box" | "Rc" | "Arc" | "Cow")
+        && matches!(argument, Some(Type::Path(path)) if path.path.is_ident("str"))
+}
+
 /// Generate a field generator based on the type.
 fn generate_field_generator(field_type: &Type) -> Result<TokenStream2, syn::Error> {
     match field_type {
@@ -301,6 +668,13 @@ fn generate_field_generator(field_type: &Type) -> Result<TokenStream2, syn::Erro
                     "f32" => Ok(
                         quote! { hedgehog::Gen::<f64>::from_range(hedgehog::Range::new(0.0, 100.0)).map(|x| x as f32) },
                     ),
+                    wrapper if is_string_like(wrapper, first_type_argument(segment).as_ref()) => {
+                        Ok(quote! { hedgehog::Gen::<#field_type>::ascii_alpha() })
+                    }
+                    _ if !segment.arguments.is_empty() => {
+                        // Generic types such as Vec<T> and Option<T>
+                        Ok(quote! { <#field_type as hedgehog::DefaultGen>::default_gen() })
+                    }
                     _ => {
                         // For custom types, assume they have a generate() method
                         Ok(quote! { #field_type::generate() })
@@ -314,8 +688,8 @@ fn generate_field_generator(field_type: &Type) -> Result<TokenStream2, syn::Erro
             }
         }
         _ => {
-            // For other types, try to call generate() on them
-            Ok(quote! { #field_type::generate() })
+            // Tuples and other types use their DefaultGen
+            Ok(quote! { <#field_type as hedgehog::DefaultGen>::default_gen() })
         }
     }
 }
@@ -2,6 +2,7 @@
 
 use hedgehog::*;
 use hedgehog_derive::Generate;
+use serde::{Deserialize, Serialize};
 
 #[derive(Generate, Debug, Clone, PartialEq)]
 struct User {
@@ -17,6 +18,32 @@ struct Point(i32, i32);
 struct Unit;
 
 #[derive(Generate, Debug, Clone, PartialEq)]
+struct Order {
+    #[generate(Gen::u32_range(1, 10))]
+    quantity: u32,
+    items: Vec<String>,
+    discount: Option<(u8, bool)>,
+    user: User,
+}
+
+// Emits settings_roundtrips_through_json
+#[derive(Generate, Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[generate(roundtrip = "json")]
+struct Settings {
+    name: String,
+    retries: u8,
+    tags: Vec<String>,
+    mode: Mode,
+}
+
+#[derive(Generate, Serialize, Deserialize, Debug, Clone, PartialEq)]
+enum Mode {
+    Fast,
+    Careful { limit: u32 },
+}
+
+#[derive(Generate, Debug, Clone, PartialEq)]
+#[generate(small_values)]
 enum Status {
     Active,
     Inactive,
@@ -82,6 +109,35 @@ fn test_derive_simple_enum() {
     assert!(matches!(status, Status::Active | Status::Inactive));
 }
 
+#[test]
+fn test_derive_fieldless_enum_small_values() {
+    assert_eq!(Status::small_values(), [Status::Active, Status::Inactive]);
+
+    let seen = std::rc::Rc::new(std::cell::RefCell::new(Vec::new()));
+    let record = seen.clone();
+    let prop = for_all(
+        Gen::<(Status, bool)>::tuple_of(Status::generate(), Gen::bool()),
+        move |pair| {
+            record.borrow_mut().push(pair.clone());
+            true
+        },
+    )
+    .with_small_cases(4, |_| true);
+    assert!(matches!(
+        prop.run(&Config::default()),
+        TestResult::Pass { .. }
+    ));
+    assert_eq!(
+        seen.borrow()[..4],
+        [
+            (Status::Active, false),
+            (Status::Active, true),
+            (Status::Inactive, false),
+            (Status::Inactive, true),
+        ]
+    );
+}
+
 #[test]
 fn test_derive_complex_enum() {
     let gen = Color::generate();
@@ -140,3 +196,117 @@ fn test_derive_shrinking() {
         result => panic!("Unexpected result: {:?}", result),
     }
 }
+
+#[test]
+fn test_derive_default_gen_and_overrides() {
+    let prop = for_all_default(|order: &Order| {
+        (1..=10).contains(&order.quantity) && order.user.age <= 100
+    });
+
+    match prop.run(&Config::default().with_tests(50)) {
+        TestResult::Pass { .. } => {}
+        result => panic!("Property failed: {:?}", result),
+    }
+}
+
+#[derive(Generate, Debug, Clone, PartialEq)]
+struct SignUp {
+    #[validate(email, length(max = 40))]
+    email: String,
+    #[garde(length(chars, min = 3, max = 12))]
+    username: String,
+    #[validate(range(min = 18, max = 130))]
+    age: u8,
+    #[validate(length(min = 1, max = 3))]
+    tags: Vec<bool>,
+    active: bool,
+}
+
+impl SignUp {
+    /// Which rules the value breaks, in field order.
+    fn broken_rules(&self) -> Vec<&'static str> {
+        let mut broken = Vec::new();
+        if !matches!(self.email.split_once('@'), Some((local, domain)) if !local.is_empty() && domain.contains('.'))
+        {
+            broken.push("email");
+        }
+        if self.email.len() > 40 {
+            broken.push("email length");
+        }
+        if !(3..=12).contains(&self.username.chars().count()) {
+            broken.push("username length");
+        }
+        if !(18..=130).contains(&self.age) {
+            broken.push("age range");
+        }
+        if !(1..=3).contains(&self.tags.len()) {
+            broken.push("tags length");
+        }
+        broken
+    }
+}
+
+#[test]
+fn test_derive_obeys_validation_rules() {
+    let prop = for_all(SignUp::generate(), |sign_up: &SignUp| {
+        sign_up.broken_rules().is_empty()
+    });
+    match prop.run(&Config::default().with_tests(200)) {
+        TestResult::Pass { .. } => {}
+        result => panic!("Property failed: {:?}", result),
+    }
+}
+
+#[test]
+fn test_derive_invalid_breaks_exactly_one_rule() {
+    let prop = for_all(SignUp::generate_invalid(), |sign_up: &SignUp| {
+        sign_up.broken_rules().len() == 1
+    });
+    match prop.run(&Config::default().with_tests(200)) {
+        TestResult::Pass { .. } => {}
+        result => panic!("Property failed: {:?}", result),
+    }
+
+    // Every rule gets broken
+    let mut broken: Vec<_> = (0..500)
+        .flat_map(|n| {
+            SignUp::generate_invalid()
+                .generate(Size::new(30), Seed::from_u64(n))
+                .value
+                .broken_rules()
+        })
+        .collect();
+    broken.sort();
+    broken.dedup();
+    assert_eq!(broken.len(), 5, "{broken:?}");
+}
+
+#[derive(Generate, Debug, Clone, PartialEq)]
+struct Label {
+    key: std::rc::Rc<str>,
+    name: Box<str>,
+    owner: std::sync::Arc<str>,
+    #[validate(length(min = 2, max = 8))]
+    message: std::borrow::Cow<'static, str>,
+}
+
+#[test]
+fn test_derive_string_like_fields() {
+    let prop = for_all(Label::generate(), |label: &Label| {
+        label.key.chars().all(|c| c.is_ascii_alphabetic())
+            && label.name.chars().all(|c| c.is_ascii_alphabetic())
+            && (2..=8).contains(&label.message.len())
+    });
+    match prop.run(&Config::default().with_tests(100)) {
+        TestResult::Pass { .. } => {}
+        result => panic!("Property failed: {:?}", result),
+    }
+
+    let prop = for_all(Label::generate_invalid(), |label: &Label| {
+        !(2..=8).contains(&label.message.len())
+    });
+    match prop.run(&Config::default().with_tests(100)) {
+        TestResult::Pass { .. } => {}
+        result => panic!("Property failed: {:?}", result),
+    }
+}
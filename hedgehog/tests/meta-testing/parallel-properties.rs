@@ -40,6 +40,7 @@ pub fn test_parallel_work_distribution() {
                         module_path: None,
                         assertion_type: Some("Range Check".to_string()),
                         shrink_steps: Vec::new(),
+                        details: FailureDetails::default(),
                     }
                 }
             },
@@ -147,6 +148,7 @@ pub fn test_concurrent_non_determinism_detection() {
                             module_path: None,
                             assertion_type: Some("Counter Parity".to_string()),
                             shrink_steps: Vec::new(),
+                            details: FailureDetails::default(),
                         }
                     }
                 }
@@ -293,6 +295,7 @@ pub fn test_load_testing_sustained_load() {
             ramp_up_duration: Duration::from_millis(10),
             cool_down_duration: Duration::from_millis(10),
             collect_stats: true,
+            arrival: ArrivalModel::Closed,
         };
 
         let load_generator = LoadGenerator::new(
@@ -316,6 +319,7 @@ pub fn test_load_testing_sustained_load() {
                         module_path: None,
                         assertion_type: Some("Range Check".to_string()),
                         shrink_steps: Vec::new(),
+                        details: FailureDetails::default(),
                     }
                 }
             },
@@ -364,6 +368,7 @@ pub fn test_concurrent_scenario_execution() {
                         module_path: None,
                         assertion_type: Some("Positive Check".to_string()),
                         shrink_steps: Vec::new(),
+                        details: FailureDetails::default(),
                     }
                 }
             })
@@ -5,8 +5,8 @@
 
 use crate::arbitrary_seed;
 use hedgehog::targeted::{
-    for_all_targeted_with_config, IntegerNeighborhood, SearchObjective, TargetedConfig,
-    TargetedResult,
+    for_all_maximizing, for_all_minimizing, for_all_targeted_with_config, IntegerNeighborhood,
+    SearchObjective, TargetedConfig, TargetedResult,
 };
 use hedgehog::*;
 use std::time::Duration;
@@ -239,6 +239,35 @@ pub fn test_search_time_limits() {
     }
 }
 
+/// Property: The simple front-ends report failures, or else the best input
+pub fn test_maximizing_front_end() {
+    let prop = for_all_named(arbitrary_seed(), "seed", |&_seed: &Seed| {
+        let generator = || Gen::<i32>::from_range(Range::new(0, 1000));
+
+        // A failure is kept over any passing input, whatever its utility
+        let (failing, _) =
+            for_all_minimizing(generator(), |&n| n as f64, |&n| n < 500).search(&Config::default());
+        let found = match failing {
+            TargetedResult::Fail { counterexample, .. } => {
+                counterexample.parse::<i32>().is_ok_and(|n| n >= 500)
+            }
+            _ => false,
+        };
+
+        // Without failures the search climbs towards the largest input
+        let (passing, stats) =
+            for_all_maximizing(generator(), |&n| n as f64, |_| true).search(&Config::default());
+
+        found && passing.is_pass() && stats.best_utility >= 900.0
+    });
+
+    let fast_config = Config::default().with_tests(5).with_shrinks(1);
+    match prop.run(&fast_config) {
+        TestResult::Pass { .. } => println!("✓ Maximizing front-end property passed"),
+        result => panic!("Maximizing front-end property failed: {result:?}"),
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -249,5 +278,6 @@ mod tests {
         test_temperature_scheduling();
         test_search_objectives();
         test_search_time_limits();
+        test_maximizing_front_end();
     }
 }